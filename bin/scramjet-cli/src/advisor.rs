@@ -0,0 +1,320 @@
+//! Compute-budget advisor: once a transaction is confirmed, compare the
+//! compute unit limit it requested with what it actually consumed and
+//! suggest a tighter one. The priority fee is paid on the limit, not on
+//! usage, so a transfer sent with a 200k limit that uses 450 CU pays for
+//! ~440 times the compute it needs.
+//!
+//! `spam --template` keeps each template's suggestion in
+//! `COMPUTE_PROFILE_FILE`, keyed by the template's contents (editing it
+//! starts over), and later runs of the template send with it whenever it is
+//! below the configured limit.
+
+use log::{debug, warn};
+use scramjet_common::funding::transaction_fee;
+use serde::{Deserialize, Serialize};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+#[allow(deprecated)]
+use solana_sdk::compute_budget;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::output::{Event, Output};
+
+/// Margin added to the consumed units for runs that take another path
+const HEADROOM_PERCENT: u64 = 20;
+
+/// Suggestions are rounded up to a multiple of this
+const LIMIT_STEP: u32 = 1_000;
+
+/// Runtime limit per transaction
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Limit each instruction gets when the transaction sets none
+const DEFAULT_INSTRUCTION_LIMIT: u32 = 200_000;
+
+/// `ComputeBudgetInstruction` discriminants (borsh: tag byte, then the
+/// little-endian value)
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// What a transaction asked for against what it used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Advice {
+    pub requested: u32,
+    pub consumed: u64,
+    /// Consumed units plus headroom, rounded up
+    pub suggested: u32,
+    /// Compute unit price the transaction paid (microlamports)
+    pub price: u64,
+}
+
+impl Advice {
+    pub fn new(requested: u32, consumed: u64, price: u64) -> Self {
+        Self {
+            requested,
+            consumed,
+            suggested: suggested_limit(consumed),
+            price,
+        }
+    }
+
+    /// Whether the requested limit is above the suggestion
+    pub fn is_loose(&self) -> bool {
+        self.requested > self.suggested
+    }
+
+    /// One-line summary for text output
+    pub fn message(&self) -> String {
+        if !self.is_loose() {
+            return format!(
+                "Used {} of the {} CU requested (limit fits)",
+                self.consumed, self.requested
+            );
+        }
+        let mut message = format!(
+            "Requested {} CU but used {}: a limit of {} would do",
+            self.requested, self.consumed, self.suggested
+        );
+        if self.price > 0 {
+            let fee = |limit| transaction_fee(0, limit, self.price);
+            message.push_str(&format!(
+                " (priority fee {} -> {} lamports at {} microlamports/CU)",
+                fee(self.requested),
+                fee(self.suggested),
+                self.price
+            ));
+        }
+        message
+    }
+}
+
+/// `consumed` plus `HEADROOM_PERCENT`, rounded up to `LIMIT_STEP`
+fn suggested_limit(consumed: u64) -> u32 {
+    let padded = consumed
+        .saturating_mul(100 + HEADROOM_PERCENT)
+        .div_ceil(100);
+    let rounded = padded.div_ceil(u64::from(LIMIT_STEP)).max(1) * u64::from(LIMIT_STEP);
+    u32::try_from(rounded)
+        .unwrap_or(u32::MAX)
+        .min(MAX_COMPUTE_UNIT_LIMIT)
+}
+
+/// Compute unit limit and price `message` sets. Without a limit instruction
+/// the runtime's default applies (per instruction, capped); this ignores
+/// the smaller default builtin programs get, so it may overstate a request.
+pub fn compute_budget(message: &VersionedMessage) -> (u32, u64) {
+    let keys = message.static_account_keys();
+    let (mut limit, mut price, mut others) = (None, 0, 0u32);
+    for instruction in message.instructions() {
+        let program = keys.get(usize::from(instruction.program_id_index));
+        if program != Some(&compute_budget::id()) {
+            others += 1;
+            continue;
+        }
+        match instruction.data.split_first() {
+            Some((&SET_COMPUTE_UNIT_LIMIT, value)) => {
+                limit = value.try_into().ok().map(u32::from_le_bytes);
+            }
+            Some((&SET_COMPUTE_UNIT_PRICE, value)) => {
+                price = value.try_into().map(u64::from_le_bytes).unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+    let limit = limit
+        .unwrap_or_else(|| others.saturating_mul(DEFAULT_INSTRUCTION_LIMIT))
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    (limit, price)
+}
+
+/// Fetch a confirmed transaction and compare its compute budget with what it
+/// consumed. None when it failed on chain or the node does not report
+/// consumed units.
+pub async fn advise(rpc: &RpcClient, signature: &Signature) -> anyhow::Result<Option<Advice>> {
+    let confirmed = rpc
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(CommitmentConfig::confirmed()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    let Some(meta) = confirmed.transaction.meta.filter(|meta| meta.err.is_none()) else {
+        return Ok(None);
+    };
+    let Some(consumed) = Option::<u64>::from(meta.compute_units_consumed) else {
+        return Ok(None);
+    };
+    let Some(tx) = confirmed.transaction.transaction.decode() else {
+        anyhow::bail!("Transaction {} did not decode", signature);
+    };
+    let (requested, price) = compute_budget(&tx.message);
+    Ok(Some(Advice::new(requested, consumed, price)))
+}
+
+/// Advise on a landed `signature` and report it. Lookup failures are only
+/// warned about: the advice is a bonus, never a reason to fail the command.
+pub async fn report(rpc: &RpcClient, signature: &Signature, output: Output) -> Option<Advice> {
+    let advice = match advise(rpc, signature).await {
+        Ok(Some(advice)) => advice,
+        Ok(None) => return None,
+        Err(e) => {
+            warn!("Compute: Could not fetch {}: {}", signature, e);
+            return None;
+        }
+    };
+    if output.is_json() {
+        output.emit(Event::ComputeAdvice {
+            signature: signature.to_string(),
+            requested: advice.requested,
+            consumed: advice.consumed,
+            suggested: advice.suggested,
+        });
+    } else {
+        println!("Compute:  {}", advice.message());
+    }
+    Some(advice)
+}
+
+/// A template's learned limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Profile {
+    limit: u32,
+    /// Most units a confirmed run of the template consumed
+    consumed: u64,
+    updated_ms: u64,
+}
+
+/// Learned limits per template, in `COMPUTE_PROFILE_FILE`
+#[derive(Debug)]
+pub struct ComputeProfiles {
+    path: PathBuf,
+    profiles: BTreeMap<String, Profile>,
+}
+
+impl ComputeProfiles {
+    /// Read the profiles (an unreadable file starts empty)
+    pub fn load(path: PathBuf) -> Self {
+        let profiles = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Compute: Ignoring {}: {}", path.display(), e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self { path, profiles }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Limit learned for template `key`
+    pub fn limit(&self, key: &str) -> Option<u32> {
+        self.profiles.get(key).map(|profile| profile.limit)
+    }
+
+    /// Fold `advice` into `key`'s profile: the limit covers the most any
+    /// confirmed run consumed
+    pub fn record(&mut self, key: &str, advice: &Advice) {
+        let consumed = self.profiles.get(key).map_or(advice.consumed, |profile| {
+            profile.consumed.max(advice.consumed)
+        });
+        let updated_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or(0);
+        self.profiles.insert(
+            key.to_string(),
+            Profile {
+                limit: suggested_limit(consumed),
+                consumed,
+                updated_ms,
+            },
+        );
+    }
+
+    /// Drop `key`'s profile (its limit turned out too tight)
+    pub fn forget(&mut self, key: &str) {
+        self.profiles.remove(key);
+    }
+
+    /// Write the profiles (via a temp file, so readers never see a partial one)
+    pub fn save(&self) -> anyhow::Result<()> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&self.profiles)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        debug!("Compute: Saved template limits to {}", self.path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_common::tx_builder::TxBuilder;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::pubkey::Pubkey;
+
+    #[test]
+    fn test_advice_and_profiles() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let tuned = TxBuilder::new(payer)
+            .compute_unit_limit(200_000)
+            .compute_unit_price(100_000)
+            .transfer(&recipient, 1)
+            .legacy_message(Hash::default());
+        assert_eq!(
+            compute_budget(&VersionedMessage::Legacy(tuned)),
+            (200_000, 100_000)
+        );
+        // No limit instruction: the runtime default per instruction
+        let plain = TxBuilder::new(payer)
+            .transfer(&recipient, 1)
+            .legacy_message(Hash::default());
+        assert_eq!(
+            compute_budget(&VersionedMessage::Legacy(plain)),
+            (200_000, 0)
+        );
+
+        let advice = Advice::new(200_000, 450, 100_000);
+        assert_eq!(advice.suggested, 1_000);
+        assert!(advice.is_loose());
+        assert_eq!(
+            advice.message(),
+            "Requested 200000 CU but used 450: a limit of 1000 would do \
+             (priority fee 20000 -> 100 lamports at 100000 microlamports/CU)"
+        );
+        assert_eq!(Advice::new(40_000, 30_000, 0).suggested, 36_000);
+        assert!(!Advice::new(36_000, 30_000, 0).is_loose());
+        assert_eq!(suggested_limit(2_000_000), MAX_COMPUTE_UNIT_LIMIT);
+
+        let dir = std::env::temp_dir().join(format!("scramjet-compute-{}", std::process::id()));
+        let path = dir.join("compute.json");
+        let mut profiles = ComputeProfiles::load(path.clone());
+        assert_eq!(profiles.limit("template"), None);
+        profiles.record("template", &Advice::new(200_000, 30_000, 0));
+        // A cheaper run does not shrink the limit below the costliest one
+        profiles.record("template", &Advice::new(36_000, 12_000, 0));
+        assert_eq!(profiles.limit("template"), Some(36_000));
+        profiles.save().unwrap();
+
+        let mut reloaded = ComputeProfiles::load(path);
+        assert_eq!(reloaded.limit("template"), Some(36_000));
+        reloaded.forget("template");
+        assert_eq!(reloaded.limit("template"), None);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+//! `scramjet airdrop`: request SOL from the RPC node's faucet (devnet,
+//! testnet, local validators) and wait until the airdrop is confirmed, so a
+//! fresh keypair can `fire` / `spam` right away.
+
+use anyhow::Context;
+use log::info;
+use scramjet_common::{Cluster, Config};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::time::{Duration, Instant};
+
+use crate::output::{Event, Output};
+
+/// How often the airdrop's signature status is polled
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `--amount`: SOL, e.g. `1` or `0.5`, as lamports
+pub fn parse_sol(s: &str) -> Result<u64, String> {
+    let sol: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid amount '{}' (SOL, e.g. 1 or 0.5)", s))?;
+    let lamports = (sol * LAMPORTS_PER_SOL as f64).round();
+    if !(lamports >= 1.0 && lamports < u64::MAX as f64) {
+        return Err(format!("Amount must be at least 1 lamport, got '{}'", s));
+    }
+    Ok(lamports as u64)
+}
+
+pub async fn run(
+    config: &Config,
+    recipient: Pubkey,
+    lamports: u64,
+    timeout: Duration,
+    output: Output,
+) -> anyhow::Result<()> {
+    let rpc_url = &config.rpc_url;
+    let rpc = RpcClient::new_with_commitment(rpc_url.clone(), CommitmentConfig::confirmed());
+    let genesis = rpc
+        .get_genesis_hash()
+        .await
+        .with_context(|| format!("Failed to reach {}", rpc_url))?
+        .to_string();
+    if let Some(cluster) = config.cluster {
+        cluster.verify_genesis(rpc_url, &genesis)?;
+    }
+    anyhow::ensure!(
+        Cluster::from_genesis_hash(&genesis) != Some(Cluster::Mainnet),
+        "{} is mainnet, which has no faucet; airdrops work on devnet, testnet and local validators",
+        rpc_url
+    );
+
+    info!(
+        "Requesting {} SOL for {}...",
+        lamports as f64 / LAMPORTS_PER_SOL as f64,
+        recipient
+    );
+    let signature = rpc
+        .request_airdrop(&recipient, lamports)
+        .await
+        .context("Airdrop refused (faucets are rate limited; try a smaller amount or wait)")?;
+    info!(
+        "Airdrop requested: {}. Waiting for confirmation...",
+        signature
+    );
+
+    let started = Instant::now();
+    loop {
+        let status = rpc.get_signature_statuses(&[signature]).await?.value[0].clone();
+        match status {
+            Some(status) if status.err.is_some() => {
+                anyhow::bail!("Airdrop {} failed: {:?}", signature, status.err)
+            }
+            Some(status) if status.satisfies_commitment(CommitmentConfig::confirmed()) => break,
+            _ => {}
+        }
+        anyhow::ensure!(
+            started.elapsed() < timeout,
+            "Airdrop {} not confirmed after {:?}",
+            signature,
+            timeout
+        );
+        tokio::time::sleep(CONFIRM_POLL_INTERVAL).await;
+    }
+
+    let balance = rpc.get_balance(&recipient).await?;
+    if output.is_json() {
+        output.emit(Event::Airdrop {
+            recipient: recipient.to_string(),
+            signature: signature.to_string(),
+            lamports,
+            balance,
+        });
+    } else {
+        println!(
+            "Airdropped {} SOL to {} ({:.1}s). Balance: {} SOL",
+            lamports as f64 / LAMPORTS_PER_SOL as f64,
+            recipient,
+            started.elapsed().as_secs_f64(),
+            balance as f64 / LAMPORTS_PER_SOL as f64
+        );
+        println!("Sig: {}", signature);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sol() {
+        assert_eq!(parse_sol("1"), Ok(LAMPORTS_PER_SOL));
+        assert_eq!(parse_sol(" 0.5 "), Ok(LAMPORTS_PER_SOL / 2));
+        assert_eq!(parse_sol("0.000000001"), Ok(1));
+        for bad in ["0", "-1", "abc", "NaN", "inf", "1e30"] {
+            assert!(parse_sol(bad).is_err(), "{}", bad);
+        }
+    }
+}
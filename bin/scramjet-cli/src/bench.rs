@@ -0,0 +1,264 @@
+//! `scramjet bench`: paced sends to the current leader, reporting handshake
+//! time, per-stream write latency and end-to-end landing latency. With
+//! `QUIC_DATAGRAMS` the write latency covers the datagram path instead.
+//!
+//! Every transaction gets a unique signature (the compute unit price is
+//! bumped by one microlamport per send), so each one can be confirmed.
+//! Landing latency is measured when a signature first shows up in RPC
+//! status polling, so its resolution is the poll interval (one slot).
+
+use log::{info, warn};
+use scramjet_net::{
+    cartographer::{Cartographer, StakedTarget},
+    engine::QuicEngine,
+    histogram::Histogram,
+    priority::Priority,
+};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+use crate::output::{Event, Output};
+use crate::scoreboard::{InFlight, Tracker};
+use crate::{encode, journal_send, Signing};
+
+pub struct BenchArgs {
+    pub duration: Duration,
+    /// Target sends per second
+    pub rate: u32,
+    pub recipient: Pubkey,
+    pub priority_fee: u64,
+    /// Scale the fee by the leader's stake (`StakedTarget::priority_fee`)
+    pub stake_fee: bool,
+    pub compute_unit_limit: u32,
+}
+
+/// Parse `500ms`, `30s`, `2m` (bare numbers are seconds)
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (value, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("Invalid duration '{}' (expected e.g. 30s)", s))?;
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "s" => Ok(Duration::from_secs(value)),
+        "m" => value
+            .checked_mul(60)
+            .map(Duration::from_secs)
+            .ok_or_else(|| format!("Duration '{}' is too long", s)),
+        _ => Err(format!("Invalid duration unit '{}' (use ms, s or m)", unit)),
+    }
+}
+
+pub async fn run(
+    cartographer: Arc<Cartographer>,
+    engine: &QuicEngine,
+    signing: &Signing<'_>,
+    args: BenchArgs,
+    shutdown: &CancellationToken,
+    output: Output,
+) -> anyhow::Result<()> {
+    if signing.nonce.is_some() {
+        anyhow::bail!("bench signs a transaction per send; run it without --nonce-account");
+    }
+
+    let tracker = Tracker::spawn(cartographer.clone(), engine.journal().cloned(), "bench");
+
+    let mut handshake = Histogram::new();
+    let mut write = Histogram::new();
+    let mut seen_targets = HashSet::new();
+    let (mut sent, mut failed, mut no_leader) = (0u64, 0u64, 0u64);
+
+    info!(
+        "Bench: {} tx/s for {:?} (fee {} + n microlamports{})",
+        args.rate,
+        args.duration,
+        args.priority_fee,
+        if args.stake_fee {
+            ", scaled by leader stake"
+        } else {
+            ""
+        }
+    );
+    let fee = |staked: &StakedTarget| {
+        if args.stake_fee {
+            staked.priority_fee(args.priority_fee)
+        } else {
+            args.priority_fee
+        }
+    };
+    let mut ticker = tokio::time::interval(Duration::from_secs(1) / args.rate);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let started = Instant::now();
+    let mut n: u64 = 0;
+    while started.elapsed() < args.duration {
+        // Ctrl-C ends the send phase early; landings are still reported
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = ticker.tick() => {}
+        }
+        n += 1;
+
+        let slot = cartographer.get_known_slot();
+        let Some(staked) = cartographer.get_target_with_stake(slot).await else {
+            no_leader += 1;
+            continue;
+        };
+        let target = staked.addr;
+        let connection = match engine.get_connection_handle(target).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!("Bench: Connect to {} failed: {}", target, e);
+                engine.record_send_result(target, Some(&e));
+                failed += 1;
+                continue;
+            }
+        };
+        if seen_targets.insert(target) {
+            if let Some(elapsed) = engine.handshake_time(&target) {
+                handshake.record_duration(elapsed);
+            }
+        }
+
+        let tx = signing
+            .builder()
+            .compute_unit_limit(args.compute_unit_limit)
+            .compute_unit_price(fee(&staked) + n)
+            .transfer(&args.recipient, 1);
+        let tx = signing.sign(tx).await?;
+        let bytes = encode(&tx)?;
+
+        let write_started = Instant::now();
+        let result = engine
+            .write_transaction(target, &connection, &bytes, Priority::Normal)
+            .await;
+        let error = result.as_ref().err();
+        engine.record_send_result(target, error);
+        journal_send(
+            engine,
+            "bench",
+            &tx.signatures[0],
+            &bytes,
+            slot,
+            target,
+            error,
+        );
+        match result {
+            Ok(()) => {
+                write.record_duration(write_started.elapsed());
+                sent += 1;
+                tracker.track(InFlight {
+                    signature: tx.signatures[0],
+                    sent_at: write_started,
+                    slot,
+                    leader: target,
+                });
+            }
+            Err(e) => {
+                warn!("Bench: Send {} failed: {}", n, e);
+                failed += 1;
+            }
+        }
+    }
+    let elapsed = started.elapsed();
+
+    let mut landings = tracker.finish().await?;
+    let landed = landings.included();
+    let achieved_rate = sent as f64 / elapsed.as_secs_f64();
+    let datagrams = engine
+        .datagrams_enabled()
+        .then(|| engine.datagram_counts().0);
+
+    if output.is_json() {
+        output.emit(Event::Bench {
+            duration_ms: elapsed.as_millis() as u64,
+            target_rate: args.rate,
+            achieved_rate,
+            sent,
+            failed,
+            no_leader,
+            landed,
+            expired: landings.expired,
+            unconfirmed: landings.unconfirmed,
+            handshake_us: handshake.percentiles(),
+            write_us: write.percentiles(),
+            datagrams,
+            landing_ms: landings.latency_ms.percentiles(),
+            landing_slots: landings.slots.percentiles(),
+        });
+        return Ok(());
+    }
+
+    println!(
+        "Sent {} in {:.1}s ({:.0} tx/s of {} target) | failed {} | no leader {}",
+        sent,
+        elapsed.as_secs_f64(),
+        achieved_rate,
+        args.rate,
+        failed,
+        no_leader
+    );
+    println!(
+        "Landed {} ({:.1}%) | expired {} | unconfirmed {}",
+        landed,
+        if sent > 0 {
+            landed as f64 * 100.0 / sent as f64
+        } else {
+            0.0
+        },
+        landings.expired,
+        landings.unconfirmed
+    );
+    if let Some(datagrams) = datagrams {
+        println!(
+            "Datagrams {} | streams {} (QUIC_DATAGRAMS)",
+            datagrams,
+            sent - datagrams
+        );
+    }
+    print_histogram("Handshake", "us", &mut handshake);
+    print_histogram("Stream write", "us", &mut write);
+    print_histogram("Landing", "ms", &mut landings.latency_ms);
+    print_histogram("Landing", "slots", &mut landings.slots);
+    Ok(())
+}
+
+fn print_histogram(label: &str, unit: &str, histogram: &mut Histogram) {
+    let Some(p) = histogram.percentiles() else {
+        println!("\n{} ({}): no samples", label, unit);
+        return;
+    };
+    println!(
+        "\n{} ({}): n={} min={} p50={} p95={} p99={} max={}",
+        label, unit, p.count, p.min, p.p50, p.p95, p.p99, p.max
+    );
+    let buckets = histogram.buckets();
+    let peak = buckets.iter().map(|b| b.2).max().unwrap_or(1).max(1);
+    for (lower, upper, count) in buckets {
+        let bar = "#".repeat((count * 40).div_ceil(peak));
+        println!("  {:>8} - {:<8} {:>7} {}", lower, upper, count, bar);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+        assert!(parse_duration("fast").is_err());
+        assert!(parse_duration("10h").is_err());
+        assert!(parse_duration("999999999999999999m").is_err());
+    }
+}
@@ -0,0 +1,370 @@
+//! `scramjet spam --carpet`: instead of locking onto one leader, walk the
+//! leader windows of the next `--slots` slots and send `--count`
+//! transactions to each leader as its window begins.
+//!
+//! Every window's target is resolved (Shield applied) and handshaken before
+//! the window starts, then the slot clock decides when to send. Windows the
+//! clock has already passed are skipped rather than sent late. All windows
+//! share one signature (re-signed before the blockhash expires), so at most
+//! one of the sends lands, whichever leader includes it.
+
+use log::{info, warn};
+use scramjet_net::{
+    cartographer::Cartographer,
+    engine::QuicEngine,
+    priority::Priority,
+    rate_limit::RateLimiter,
+    schedule::LeaderWindow,
+    slo::{FailureStage, LandingFeed, SendOutcome, SloMonitor, TxOutcomes},
+};
+use solana_sdk::pubkey::Pubkey;
+use std::net::SocketAddr;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use crate::advisor;
+use crate::output::{Event, Output};
+use crate::scoreboard::{InFlight, Tracker};
+use crate::{encode, journal_send, report_dry_run, report_outcomes, Signing, SPAM_RESIGN_AFTER};
+
+/// Slots past a window's start before a silent slot clock counts as stalled
+const CLOCK_STALL_SLOTS: u64 = 8;
+
+pub struct CarpetArgs {
+    pub recipient: Pubkey,
+    pub priority_fee: u64,
+    pub compute_unit_limit: u32,
+    /// Transactions sent to each leader
+    pub per_leader: u64,
+    /// Slots of the schedule to walk, from the current one
+    pub slots: u64,
+    pub priority: Priority,
+    pub dry_run: bool,
+}
+
+/// Leader windows overlapping slots `first..first + slots`, in order (slots
+/// off the known schedule are stepped over)
+pub async fn plan(cartographer: &Cartographer, first: u64, slots: u64) -> Vec<LeaderWindow> {
+    let end = first.saturating_add(slots);
+    let mut windows = Vec::new();
+    let mut slot = first;
+    while slot < end {
+        match cartographer.get_leader_window(slot).await {
+            Some(window) => {
+                slot = window.end_slot + 1;
+                windows.push(window);
+            }
+            None => slot += 1,
+        }
+    }
+    windows
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn run(
+    cartographer: &Cartographer,
+    engine: &QuicEngine,
+    signing: &Signing<'_>,
+    args: CarpetArgs,
+    limiter: Option<&RateLimiter>,
+    tracker: Option<Tracker>,
+    slo: Option<&SloMonitor>,
+    landing: Option<&LandingFeed>,
+    shutdown: &CancellationToken,
+    output: Output,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(args.slots > 0, "--slots must be greater than 0");
+    let first_slot = cartographer.get_known_slot();
+    let windows = plan(cartographer, first_slot, args.slots).await;
+    if windows.is_empty() {
+        output.emit(Event::Error {
+            command: "spam",
+            index: None,
+            slot: Some(first_slot),
+            leader: None,
+            signature: None,
+            error: "No leader schedule for the carpet's slots".into(),
+        });
+        anyhow::bail!(
+            "No leader schedule for slots {}..{}",
+            first_slot,
+            first_slot.saturating_add(args.slots)
+        );
+    }
+    info!(
+        "Carpet: {} leader window(s) over slots {}..{}, {} tx each",
+        windows.len(),
+        first_slot,
+        first_slot.saturating_add(args.slots),
+        args.per_leader
+    );
+
+    let build = || {
+        signing
+            .builder()
+            .compute_unit_limit(args.compute_unit_limit)
+            .compute_unit_price(args.priority_fee)
+            .transfer(&args.recipient, 1)
+    };
+    let mut tx = signing.sign(build()).await?;
+    let mut tx_bytes = encode(&tx)?;
+    let mut signed_at = Instant::now();
+    let record = |addr: SocketAddr, outcome: SendOutcome| {
+        if let Some(monitor) = slo {
+            monitor.record_to(addr, outcome);
+        }
+    };
+    let error_event =
+        |index: Option<u64>, slot: u64, leader: Option<SocketAddr>, error: String| Event::Error {
+            command: "spam",
+            index,
+            slot: Some(slot),
+            leader,
+            signature: None,
+            error,
+        };
+
+    let mut slots = cartographer.subscribe_slots();
+    let (mut sent, mut failed, mut skipped) = (0u64, 0u64, 0u64);
+    let mut tracked = None;
+    let mut outcomes = TxOutcomes::new();
+    let mut interrupted = false;
+    let started = Instant::now();
+    for window in &windows {
+        let now = cartographer.get_known_slot();
+        if now > window.end_slot {
+            warn!(
+                "Carpet: Slot {} is past {}'s window (slots {}-{}). Skipping.",
+                now, window.leader, window.start_slot, window.end_slot
+            );
+            skipped += 1;
+            continue;
+        }
+
+        // Resolve and handshake ahead of the window
+        let Some(target) = cartographer.get_target(window.start_slot.max(now)).await else {
+            warn!(
+                "Carpet: No usable target for {} (slots {}-{}). Skipping.",
+                window.leader, window.start_slot, window.end_slot
+            );
+            if let Some(monitor) = slo {
+                monitor.record(SendOutcome::Failed(FailureStage::NoLeader));
+            }
+            output.emit(error_event(
+                None,
+                window.start_slot,
+                None,
+                "No leader found".into(),
+            ));
+            skipped += 1;
+            continue;
+        };
+        let pool = match engine.get_connection_pool(target).await {
+            Ok(pool) => pool,
+            Err(e) => {
+                warn!("Carpet: Connect to {} failed: {}. Skipping.", target, e);
+                engine.record_send_result(target, Some(&e));
+                record(target, SendOutcome::Failed(FailureStage::Connect));
+                output.emit(error_event(
+                    None,
+                    window.start_slot,
+                    Some(target),
+                    e.to_string(),
+                ));
+                skipped += 1;
+                continue;
+            }
+        };
+
+        // Hold until the slot clock reaches the window
+        let ahead = window.start_slot.saturating_sub(now) + CLOCK_STALL_SLOTS;
+        let stall = cartographer.slot_duration() * u32::try_from(ahead).unwrap_or(u32::MAX);
+        let reached =
+            tokio::time::timeout(stall, slots.wait_for(|slot| *slot >= window.start_slot));
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                interrupted = true;
+                break;
+            }
+            reached = reached => {
+                match reached {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(_)) => anyhow::bail!("Slot clock closed"),
+                    Err(_) => anyhow::bail!(
+                        "Slot clock stalled at {} waiting for slot {}",
+                        cartographer.get_known_slot(),
+                        window.start_slot
+                    ),
+                }
+            }
+        }
+        info!(
+            "Carpet: {} ({}) for slots {}-{}",
+            window.leader, target, window.start_slot, window.end_slot
+        );
+
+        if signing.nonce.is_none() && signed_at.elapsed() >= SPAM_RESIGN_AFTER {
+            tx = signing.sign(build()).await?;
+            tx_bytes = encode(&tx)?;
+            signed_at = Instant::now();
+            info!("Re-signed with fresh blockhash");
+        }
+        for i in 0..args.per_leader {
+            if shutdown.is_cancelled() {
+                interrupted = true;
+                break;
+            }
+            // The rest would reach a leader no longer producing
+            let slot = cartographer.get_known_slot();
+            if slot > window.end_slot {
+                warn!(
+                    "Carpet: {}'s window ended after {} of {} tx",
+                    window.leader, i, args.per_leader
+                );
+                break;
+            }
+            if let Some(limiter) = limiter {
+                limiter.acquire_with(args.priority).await;
+            }
+            if args.dry_run {
+                report_dry_run("spam", Some(i), slot, target, &tx, &tx_bytes, output);
+                sent += 1;
+                continue;
+            }
+
+            let connection = &pool[i as usize % pool.len()];
+            let write_started = Instant::now();
+            let result = engine
+                .write_stream(target, connection, &mut [tx_bytes.clone()], args.priority)
+                .await
+                .map_err(|e| e.with_slot(slot));
+            engine.record_send_result(target, result.as_ref().err());
+            let stage = result.as_ref().map_err(FailureStage::from_send_error);
+            outcomes.record(tx.signatures[0], target, slot, stage.copied());
+            journal_send(
+                engine,
+                "spam",
+                &tx.signatures[0],
+                &tx_bytes,
+                slot,
+                target,
+                result.as_ref().err(),
+            );
+            match result {
+                Ok(()) => {
+                    sent += 1;
+                    output.emit(Event::Sent {
+                        command: "spam",
+                        index: Some(i),
+                        slot,
+                        leader: target,
+                        signature: tx.signatures[0].to_string(),
+                        latency_us: crate::output::micros(write_started.elapsed()),
+                    });
+                    if let (Some(tracker), false) = (&tracker, tracked == Some(tx.signatures[0])) {
+                        tracked = Some(tx.signatures[0]);
+                        tracker.track(InFlight {
+                            signature: tx.signatures[0],
+                            sent_at: write_started,
+                            slot,
+                            leader: target,
+                        });
+                    }
+                }
+                Err(e) => {
+                    warn!("{} (tx {})", e, i);
+                    failed += 1;
+                    output.emit(error_event(Some(i), slot, Some(target), e.to_string()));
+                }
+            }
+        }
+        if interrupted {
+            break;
+        }
+    }
+
+    let elapsed = started.elapsed();
+    if !output.is_json() {
+        println!(
+            "{}. Sent: {}, Failed: {} across {} leader window(s) ({} skipped) in {:.1}s",
+            if interrupted {
+                "Interrupted"
+            } else if args.dry_run {
+                "Dry Run Complete"
+            } else {
+                "Carpet Complete"
+            },
+            sent,
+            failed,
+            windows.len(),
+            skipped,
+            elapsed.as_secs_f64()
+        );
+    }
+    output.emit(Event::Summary {
+        command: "spam",
+        sent,
+        failed,
+        elapsed_ms: elapsed.as_millis() as u64,
+    });
+    if args.dry_run {
+        return Ok(());
+    }
+
+    if let Some(monitor) = slo {
+        report_outcomes(outcomes, cartographer, landing, monitor, output).await;
+    }
+    if let Some(tracker) = tracker {
+        let mut landings = tracker.finish().await?;
+        if output.is_json() {
+            output.emit(Event::Scoreboard {
+                command: "spam",
+                sent: landings.sent(),
+                landed: landings.landed,
+                failed: landings.failed,
+                never_seen: landings.never_seen(),
+                time_to_land_ms: landings.latency_ms.percentiles(),
+                leaders: crate::scoreboard::leader_scores(&landings),
+            });
+        } else {
+            crate::scoreboard::print(&mut landings);
+        }
+        if let Some(ref signature) = landings.sample {
+            advisor::report(&cartographer.rpc_client(), signature, output).await;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_net::blocklist::blocklist_handle;
+    use std::collections::{HashMap, HashSet};
+
+    #[tokio::test]
+    async fn test_plan_walks_windows() {
+        let cartographer =
+            Cartographer::new("http://mock-rpc".into(), blocklist_handle(HashSet::new()));
+        let leaders: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        // Four-slot windows, with slots 8-11 missing from the schedule
+        let schedule: HashMap<u64, Pubkey> = (0..20)
+            .filter(|slot| !(8..12).contains(slot))
+            .map(|slot| (slot, leaders[(slot / 4) as usize % 4]))
+            .collect();
+        cartographer.install_schedule(schedule).await;
+
+        // Mid-window start: the current window is included, the gap skipped
+        let windows = plan(&cartographer, 2, 12).await;
+        let spans: Vec<(Pubkey, u64, u64)> = windows
+            .iter()
+            .map(|window| (window.leader, window.start_slot, window.end_slot))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![(leaders[0], 0, 3), (leaders[1], 4, 7), (leaders[3], 12, 15)]
+        );
+        assert_eq!(plan(&cartographer, 4, 1).await.len(), 1);
+        assert!(plan(&cartographer, 40, 8).await.is_empty());
+    }
+}
@@ -0,0 +1,210 @@
+//! `scramjet confirm`: follow a signature through processed, confirmed and
+//! finalized, printing the slot it landed in, its error (if it failed on
+//! chain) and how long each level took, so a `fire` can be verified without
+//! leaving the CLI.
+//!
+//! Times are measured from the first poll, at the poll interval's
+//! resolution; a signature that had already landed shows every level it had
+//! reached at +0.0s. Ledger history is searched too, so older signatures are
+//! found.
+//!
+//! A confirmed success also gets the compute-budget advisor's verdict: the
+//! units it consumed against the limit it requested.
+
+use log::warn;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::advisor;
+use crate::output::{Event, Output};
+
+/// How often the signature's status is polled (about a slot)
+const POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// Commitment levels a landed signature passes through, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Level {
+    const ALL: [Level; 3] = [Level::Processed, Level::Confirmed, Level::Finalized];
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Processed => "processed",
+            Level::Confirmed => "confirmed",
+            Level::Finalized => "finalized",
+        })
+    }
+}
+
+/// What polling has seen of a signature so far
+#[derive(Debug, Default)]
+pub struct Progress {
+    /// Slot of the block it landed in (the latest seen, should a fork move it)
+    pub slot: Option<u64>,
+    /// On-chain error, if the transaction failed
+    pub error: Option<String>,
+    /// Time to reach each level, indexed by `Level as usize`
+    reached: [Option<Duration>; 3],
+}
+
+impl Progress {
+    /// Record a poll that found the signature at `level`; returns the levels
+    /// reached for the first time (lower ones included)
+    pub fn observe(
+        &mut self,
+        slot: u64,
+        level: Level,
+        error: Option<String>,
+        elapsed: Duration,
+    ) -> Vec<Level> {
+        self.slot = Some(slot);
+        self.error = error;
+        Level::ALL
+            .into_iter()
+            .filter(|&reached| reached <= level)
+            .filter(|&reached| {
+                let first = self.reached[reached as usize].is_none();
+                if first {
+                    self.reached[reached as usize] = Some(elapsed);
+                }
+                first
+            })
+            .collect()
+    }
+
+    /// Highest level reached so far
+    pub fn level(&self) -> Option<Level> {
+        Level::ALL
+            .into_iter()
+            .rev()
+            .find(|&level| self.reached[level as usize].is_some())
+    }
+
+    pub fn time_to(&self, level: Level) -> Option<Duration> {
+        self.reached[level as usize]
+    }
+}
+
+pub async fn run(
+    rpc_url: &str,
+    signature: &str,
+    timeout: Duration,
+    output: Output,
+) -> anyhow::Result<()> {
+    let signature = Signature::from_str(signature)
+        .map_err(|e| anyhow::anyhow!("Invalid signature '{}': {}", signature, e))?;
+    let rpc = RpcClient::new(rpc_url.to_string());
+
+    let started = Instant::now();
+    let mut progress = Progress::default();
+    loop {
+        match rpc.get_signature_statuses_with_history(&[signature]).await {
+            Ok(response) => {
+                if let Some(Some(status)) = response.value.first() {
+                    let level = if status.satisfies_commitment(CommitmentConfig::finalized()) {
+                        Level::Finalized
+                    } else if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                        Level::Confirmed
+                    } else {
+                        Level::Processed
+                    };
+                    let error = status.err.as_ref().map(ToString::to_string);
+                    for reached in progress.observe(status.slot, level, error, started.elapsed()) {
+                        if !output.is_json() {
+                            println!(
+                                "{:<10} slot {} (+{:.1}s)",
+                                reached,
+                                status.slot,
+                                started.elapsed().as_secs_f64()
+                            );
+                        }
+                    }
+                }
+            }
+            Err(e) => warn!("Signature status lookup failed: {}", e),
+        }
+        if progress.level() == Some(Level::Finalized) || started.elapsed() >= timeout {
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    let millis = |level| {
+        progress
+            .time_to(level)
+            .map(|elapsed: Duration| elapsed.as_millis() as u64)
+    };
+    output.emit(Event::Confirm {
+        signature: signature.to_string(),
+        slot: progress.slot,
+        status: progress.level(),
+        error: progress.error.clone(),
+        processed_ms: millis(Level::Processed),
+        confirmed_ms: millis(Level::Confirmed),
+        finalized_ms: millis(Level::Finalized),
+    });
+    if !output.is_json() {
+        if let Some(ref error) = progress.error {
+            println!("Error:     {}", error);
+        }
+    }
+    if progress.level() >= Some(Level::Confirmed) && progress.error.is_none() {
+        advisor::report(&rpc, &signature, output).await;
+    }
+
+    match (progress.level(), progress.error) {
+        (None, _) => anyhow::bail!("Signature {} not found after {:?}", signature, timeout),
+        (Some(_), Some(error)) => anyhow::bail!("Transaction {} failed: {}", signature, error),
+        (Some(Level::Processed), None) => anyhow::bail!(
+            "Signature {} only processed after {:?} (not confirmed)",
+            signature,
+            timeout
+        ),
+        (Some(_), None) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_levels() {
+        let mut progress = Progress::default();
+        assert_eq!(progress.level(), None);
+        let ms = Duration::from_millis;
+
+        assert_eq!(
+            progress.observe(100, Level::Processed, None, ms(400)),
+            vec![Level::Processed]
+        );
+        assert!(progress
+            .observe(100, Level::Processed, None, ms(800))
+            .is_empty());
+        assert_eq!(progress.time_to(Level::Processed), Some(ms(400)));
+
+        // Finalized straight after processed: confirmed is implied
+        assert_eq!(
+            progress.observe(101, Level::Finalized, Some("custom error".into()), ms(1200)),
+            vec![Level::Confirmed, Level::Finalized]
+        );
+        assert_eq!(progress.level(), Some(Level::Finalized));
+        assert_eq!(progress.time_to(Level::Confirmed), Some(ms(1200)));
+        assert_eq!(progress.slot, Some(101));
+        assert_eq!(progress.error.as_deref(), Some("custom error"));
+        assert_eq!(serde_json::to_value(Level::Confirmed).unwrap(), "confirmed");
+    }
+}
@@ -0,0 +1,521 @@
+//! `scramjet doctor`: check what landing depends on (RPC, Geyser, keypair
+//! and balance, UDP buffers, the Shield, the clock) and print a pass / warn
+//! / fail line for each. It runs before bootstrap, so it still reports when
+//! startup itself fails: a first stop for "my transactions don't land".
+
+use scramjet_common::funding;
+use scramjet_common::keystore;
+use scramjet_common::{Config, RemoteSigner, TxSigner};
+use scramjet_net::blocklist::{self, BlocklistManager};
+use scramjet_net::geyser;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Signer};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::output::{Event, Output};
+
+/// Per network check
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// RPC round trip past which sends see stale slots and blockhashes
+const RPC_LATENCY_WARN: Duration = Duration::from_millis(300);
+
+/// Geyser slots behind RPC past which slot updates arrive late
+const GEYSER_LAG_WARN: u64 = 4;
+
+/// A payer covering fewer transactions than this at the default fee
+const LOW_BALANCE_TRANSACTIONS: u64 = 100;
+
+/// Clock skew against the chain (block times are whole seconds and trail
+/// real time by about a slot, so small values are noise)
+const CLOCK_SKEW_WARN_SECS: i64 = 5;
+const CLOCK_SKEW_FAIL_SECS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        }
+    }
+}
+
+/// One line of the report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: Status,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Where the identity comes from (as resolved for other commands)
+pub struct DoctorArgs<'a> {
+    pub keypair: Option<&'a Path>,
+    pub keypair_encrypted: Option<&'a Path>,
+}
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+/// Health, then the processed slot and how long it took to get
+async fn check_rpc(rpc: &RpcClient, url: &str) -> (Check, Option<u64>) {
+    if let Err(e) = rpc.get_health().await {
+        return (
+            Check::new("rpc", Status::Fail, format!("{}: {}", url, e)),
+            None,
+        );
+    }
+    let started = Instant::now();
+    match rpc
+        .get_slot_with_commitment(CommitmentConfig::processed())
+        .await
+    {
+        Ok(slot) => (rpc_latency(url, slot, started.elapsed()), Some(slot)),
+        Err(e) => (
+            Check::new("rpc", Status::Fail, format!("{}: {}", url, e)),
+            None,
+        ),
+    }
+}
+
+fn rpc_latency(url: &str, slot: u64, latency: Duration) -> Check {
+    let detail = format!(
+        "{} healthy, slot {} in {} ms",
+        url,
+        slot,
+        latency.as_millis()
+    );
+    if latency > RPC_LATENCY_WARN {
+        return Check::new(
+            "rpc",
+            Status::Warn,
+            format!(
+                "{} (slow: over {} ms)",
+                detail,
+                RPC_LATENCY_WARN.as_millis()
+            ),
+        );
+    }
+    Check::new("rpc", Status::Pass, detail)
+}
+
+/// Every Geyser endpoint's slot against RPC's, read right after it
+async fn check_geyser(config: &Config, rpc: &RpcClient) -> Vec<Check> {
+    let Some(ref primary) = config.geyser_url else {
+        return vec![Check::new(
+            "geyser",
+            Status::Warn,
+            "GEYSER_URL not set: slot updates come from slower sources",
+        )];
+    };
+    let mut checks = Vec::new();
+    for url in std::iter::once(primary).chain(&config.geyser_redundant_urls) {
+        // Never print the auth token carried in the path
+        let shown = geyser::parse_geyser_endpoint(url).map_or_else(|_| url.clone(), |e| e.url);
+        let started = Instant::now();
+        let probed = match tokio::time::timeout(PROBE_TIMEOUT, geyser::probe_slot(url)).await {
+            Ok(probed) => probed.map_err(|e| e.to_string()),
+            Err(_) => Err(format!("no answer in {:?}", PROBE_TIMEOUT)),
+        };
+        let elapsed = started.elapsed();
+        let check = match probed {
+            Ok(slot) => {
+                let rpc_slot = rpc
+                    .get_slot_with_commitment(CommitmentConfig::processed())
+                    .await
+                    .ok();
+                geyser_lag(&shown, slot, rpc_slot, elapsed)
+            }
+            Err(e) => Check::new("geyser", Status::Fail, format!("{}: {}", shown, e)),
+        };
+        checks.push(check);
+    }
+    checks
+}
+
+fn geyser_lag(url: &str, slot: u64, rpc_slot: Option<u64>, elapsed: Duration) -> Check {
+    let Some(rpc_slot) = rpc_slot else {
+        return Check::new(
+            "geyser",
+            Status::Pass,
+            format!("{} at slot {} in {} ms", url, slot, elapsed.as_millis()),
+        );
+    };
+    let lag = rpc_slot.saturating_sub(slot);
+    let detail = format!(
+        "{} at slot {} in {} ms, {} slot(s) behind RPC",
+        url,
+        slot,
+        elapsed.as_millis(),
+        lag
+    );
+    if lag > GEYSER_LAG_WARN {
+        return Check::new(
+            "geyser",
+            Status::Warn,
+            format!("{} (stream lagging)", detail),
+        );
+    }
+    Check::new("geyser", Status::Pass, detail)
+}
+
+/// The keypair (or remote signer) loads; returns the payer when known
+async fn check_keypair(config: &Config, args: &DoctorArgs<'_>) -> (Vec<Check>, Option<Pubkey>) {
+    let mut checks = Vec::new();
+    let mut payer = None;
+    match (args.keypair, args.keypair_encrypted) {
+        (Some(path), _) => match read_keypair_file(path) {
+            Ok(keypair) => {
+                let detail = format!("{} ({})", keypair.pubkey(), path.display());
+                checks.push(Check::new("keypair", Status::Pass, detail));
+                payer = Some(keypair.pubkey());
+            }
+            Err(e) => checks.push(Check::new(
+                "keypair",
+                Status::Fail,
+                format!("cannot load {}: {}", path.display(), e),
+            )),
+        },
+        (None, Some(path)) => {
+            let parsed = std::fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| {
+                    keystore::is_encrypted(&bytes)
+                        .then_some(())
+                        .ok_or_else(|| "not a passphrase-encrypted age file".to_string())
+                });
+            checks.push(match parsed {
+                Ok(_) => Check::new(
+                    "keypair",
+                    Status::Pass,
+                    format!("{} is encrypted (not unlocked)", path.display()),
+                ),
+                Err(e) => Check::new(
+                    "keypair",
+                    Status::Fail,
+                    format!("cannot read {}: {}", path.display(), e),
+                ),
+            });
+        }
+        (None, None) => checks.push(Check::new(
+            "keypair",
+            Status::Warn,
+            "none: QUIC handshakes use an ephemeral (unstaked) identity",
+        )),
+    }
+    if let Some(ref url) = config.remote_signer_url {
+        let connected = RemoteSigner::connect(
+            url,
+            config.remote_signer_token.clone(),
+            config.remote_signer_timeout(),
+        )
+        .await;
+        match connected {
+            Ok(signer) => {
+                let detail = format!("{} signs for {}", url, signer.pubkey());
+                checks.push(Check::new("signer", Status::Pass, detail));
+                payer = Some(signer.pubkey());
+            }
+            Err(e) => {
+                checks.push(Check::new("signer", Status::Fail, e.to_string()));
+                payer = None;
+            }
+        }
+    }
+    (checks, payer)
+}
+
+fn balance_check(payer: &Pubkey, lamports: u64, fee: u64) -> Check {
+    let transactions = lamports / fee.max(1);
+    let detail = format!(
+        "{}: {} SOL, covers ~{} transactions at {} lamports each",
+        payer,
+        sol(lamports),
+        transactions,
+        fee
+    );
+    match transactions {
+        0 => Check::new("balance", Status::Fail, detail),
+        n if n < LOW_BALANCE_TRANSACTIONS => Check::new("balance", Status::Warn, detail),
+        _ => Check::new("balance", Status::Pass, detail),
+    }
+}
+
+/// Kernel caps on UDP socket buffers against QUIC_*_BUFFER_BYTES (Linux)
+fn check_udp_buffers(config: &Config) -> Vec<Check> {
+    if !cfg!(target_os = "linux") {
+        return vec![Check::new(
+            "udp_buffers",
+            Status::Warn,
+            "not checked on this OS",
+        )];
+    }
+    let read = |name: &str| -> Option<usize> {
+        std::fs::read_to_string(format!("/proc/sys/net/core/{}", name))
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    };
+    [
+        (
+            "rmem_max",
+            "QUIC_RECV_BUFFER_BYTES",
+            config.quic_recv_buffer_bytes,
+        ),
+        (
+            "wmem_max",
+            "QUIC_SEND_BUFFER_BYTES",
+            config.quic_send_buffer_bytes,
+        ),
+    ]
+    .into_iter()
+    .map(|(limit, key, requested)| buffer_check(limit, read(limit), key, requested))
+    .collect()
+}
+
+fn buffer_check(limit: &str, max: Option<usize>, key: &str, requested: usize) -> Check {
+    let Some(max) = max else {
+        return Check::new(
+            "udp_buffers",
+            Status::Warn,
+            format!("net.core.{} unreadable", limit),
+        );
+    };
+    if requested > max {
+        return Check::new(
+            "udp_buffers",
+            Status::Fail,
+            format!(
+                "net.core.{} is {}, below {}={} (the kernel caps it: raise it with sysctl)",
+                limit, max, key, requested
+            ),
+        );
+    }
+    let detail = match requested {
+        0 => format!("net.core.{} is {} ({} unset: OS default)", limit, max, key),
+        _ => format!("net.core.{} is {}, fits {}={}", limit, max, key, requested),
+    };
+    Check::new("udp_buffers", Status::Pass, detail)
+}
+
+/// Blocklist file readable, plus allowlist mode (which can target nothing)
+async fn check_shield() -> Vec<Check> {
+    let manager = BlocklistManager::from_env();
+    let path = manager.local_path();
+    let mut checks = Vec::new();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    checks.push(match blocklist::read_blocklist_file(path).await {
+        Ok(entries) => {
+            let active = entries
+                .iter()
+                .filter(|entry| !entry.is_expired(now))
+                .count();
+            let mut detail = format!("{} validator(s) blocked by {}", active, path.display());
+            if let Some(url) = manager.remote_url() {
+                detail.push_str(&format!(", synced from {}", url));
+            }
+            Check::new("shield", Status::Pass, detail)
+        }
+        Err(e) => Check::new(
+            "shield",
+            Status::Fail,
+            format!("{} unreadable: {}", path.display(), e),
+        ),
+    });
+    match manager.load_allowlist().await {
+        Some(0) => checks.push(Check::new(
+            "shield",
+            Status::Fail,
+            "allowlist mode (SCRAMJET_ALLOWLIST_FILE) with no validators: nothing is targeted",
+        )),
+        Some(allowed) => checks.push(Check::new(
+            "shield",
+            Status::Pass,
+            format!("allowlist mode: {} validator(s) targeted", allowed),
+        )),
+        None => {}
+    }
+    checks
+}
+
+/// Local time against the block time of the latest confirmed slot
+async fn check_clock(rpc: &RpcClient) -> Check {
+    let block_time = match rpc
+        .get_slot_with_commitment(CommitmentConfig::confirmed())
+        .await
+    {
+        Ok(slot) => rpc.get_block_time(slot).await,
+        Err(e) => Err(e),
+    };
+    match block_time {
+        Ok(block_time) => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs() as i64)
+                .unwrap_or(0);
+            clock_skew(now - block_time)
+        }
+        Err(e) => Check::new(
+            "clock",
+            Status::Warn,
+            format!("no block time to compare with: {}", e),
+        ),
+    }
+}
+
+fn clock_skew(skew_secs: i64) -> Check {
+    let direction = if skew_secs < 0 { "behind" } else { "ahead of" };
+    let detail = format!("{}s {} the chain", skew_secs.abs(), direction);
+    let status = match skew_secs.abs() {
+        skew if skew > CLOCK_SKEW_FAIL_SECS => Status::Fail,
+        skew if skew > CLOCK_SKEW_WARN_SECS => Status::Warn,
+        _ => Status::Pass,
+    };
+    Check::new("clock", status, detail)
+}
+
+pub async fn run(config: &Config, args: DoctorArgs<'_>, output: Output) -> anyhow::Result<()> {
+    let rpc = RpcClient::new_with_timeout(config.rpc_url.clone(), PROBE_TIMEOUT);
+    let mut checks = Vec::new();
+
+    let (rpc_check, rpc_slot) = check_rpc(&rpc, &config.rpc_url).await;
+    checks.push(rpc_check);
+    checks.extend(check_geyser(config, &rpc).await);
+    let (keypair_checks, payer) = check_keypair(config, &args).await;
+    checks.extend(keypair_checks);
+    let unreachable = |name| Check::new(name, Status::Warn, "not checked: RPC unreachable");
+    match (payer, rpc_slot) {
+        (Some(payer), Some(_)) => {
+            let fee = funding::transaction_fee(
+                1,
+                config.default_compute_unit_limit,
+                config.default_priority_fee,
+            );
+            checks.push(match rpc.get_balance(&payer).await {
+                Ok(lamports) => balance_check(&payer, lamports, fee),
+                Err(e) => Check::new("balance", Status::Fail, format!("{}: {}", payer, e)),
+            });
+        }
+        (Some(_), None) => checks.push(unreachable("balance")),
+        (None, _) => {}
+    }
+    checks.extend(check_udp_buffers(config));
+    checks.extend(check_shield().await);
+    checks.push(match rpc_slot {
+        Some(_) => check_clock(&rpc).await,
+        None => unreachable("clock"),
+    });
+
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+    let failed = count(Status::Fail);
+    if output.is_json() {
+        for check in &checks {
+            output.emit(Event::Doctor {
+                check: check.name,
+                status: check.status.as_str(),
+                detail: check.detail.clone(),
+            });
+        }
+    } else {
+        for check in &checks {
+            println!(
+                "[{}] {:<12} {}",
+                check.status.as_str().to_uppercase(),
+                check.name,
+                check.detail
+            );
+        }
+        println!(
+            "{} passed, {} warning(s), {} failed",
+            count(Status::Pass),
+            count(Status::Warn),
+            failed
+        );
+    }
+    if failed > 0 {
+        anyhow::bail!("{} check(s) failed", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_thresholds() {
+        let url = "http://rpc";
+        assert_eq!(
+            rpc_latency(url, 7, Duration::from_millis(40)).status,
+            Status::Pass
+        );
+        assert_eq!(
+            rpc_latency(url, 7, Duration::from_millis(900)).status,
+            Status::Warn
+        );
+
+        let ms = Duration::from_millis(20);
+        let synced = geyser_lag("https://geyser", 100, Some(102), ms);
+        assert_eq!(synced.status, Status::Pass);
+        assert_eq!(
+            synced.detail,
+            "https://geyser at slot 100 in 20 ms, 2 slot(s) behind RPC"
+        );
+        assert_eq!(
+            geyser_lag("https://geyser", 100, Some(110), ms).status,
+            Status::Warn
+        );
+
+        let payer = Pubkey::new_unique();
+        assert_eq!(balance_check(&payer, 4_000, 5_000).status, Status::Fail);
+        assert_eq!(balance_check(&payer, 50_000, 5_000).status, Status::Warn);
+        assert_eq!(
+            balance_check(&payer, LAMPORTS_PER_SOL, 5_000).status,
+            Status::Pass
+        );
+
+        let key = "QUIC_RECV_BUFFER_BYTES";
+        assert_eq!(
+            buffer_check("rmem_max", Some(212_992), key, 0).status,
+            Status::Pass
+        );
+        let capped = buffer_check("rmem_max", Some(212_992), key, 8 << 20);
+        assert_eq!(capped.status, Status::Fail);
+        assert!(capped
+            .detail
+            .starts_with("net.core.rmem_max is 212992, below"));
+        assert_eq!(buffer_check("rmem_max", None, key, 0).status, Status::Warn);
+
+        assert_eq!(clock_skew(1).status, Status::Pass);
+        assert_eq!(clock_skew(-12).detail, "12s behind the chain");
+        assert_eq!(clock_skew(-12).status, Status::Warn);
+        assert_eq!(clock_skew(90).status, Status::Fail);
+    }
+}
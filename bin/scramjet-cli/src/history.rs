@@ -0,0 +1,324 @@
+//! `scramjet history`: query the send journal (`JOURNAL_FILE`) for what was
+//! sent, when, to which leader, and what became of it. Given a signature, it
+//! prints that transaction's trail with a one-line verdict, which settles
+//! "was it actually transmitted?" after the fact.
+
+use scramjet_net::journal::{self, JournalEntry, JournalResult};
+use solana_sdk::signature::Signature;
+use std::collections::HashSet;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::output::{Event, Output};
+
+pub struct HistoryArgs {
+    pub signature: Option<String>,
+    /// Only entries at or after this Unix time (ms), from [`parse_since`]
+    pub since: Option<u64>,
+    /// Only entries from this command (`fire`, `spam`, `bench`, `bundle`,
+    /// `send` for the library)
+    pub source: Option<String>,
+    /// Most recent entries shown
+    pub limit: usize,
+}
+
+/// Timestamps with at least this many seconds are read as Unix times rather
+/// than ages (September 2001)
+const UNIX_SECONDS_FLOOR: u64 = 1_000_000_000;
+
+/// `--since`: an age (`90s`, `30m`, `2h`, `1d`), a Unix timestamp in seconds,
+/// or a UTC date with optional time (`2026-10-17T09:30`). Returns the cutoff
+/// as Unix ms.
+pub fn parse_since(s: &str) -> Result<u64, String> {
+    parse_since_at(s, now_ms())
+}
+
+fn parse_since_at(s: &str, now_ms: u64) -> Result<u64, String> {
+    let s = s.trim();
+    if s.len() >= 10 && s.as_bytes()[4] == b'-' {
+        return parse_utc(s).ok_or_else(|| {
+            format!(
+                "Invalid time '{}' (expected YYYY-MM-DD[THH:MM[:SS]], UTC)",
+                s
+            )
+        });
+    }
+    match s.parse::<u64>() {
+        Ok(seconds) if seconds >= UNIX_SECONDS_FLOOR => return Ok(seconds * 1_000),
+        _ => {}
+    }
+    let scaled = |value: &str, unit: u64| {
+        value
+            .parse::<u64>()
+            .ok()
+            .map(|value| Duration::from_secs(value * unit))
+    };
+    let age = match (s.strip_suffix('h'), s.strip_suffix('d')) {
+        (Some(hours), _) => scaled(hours, 3_600),
+        (_, Some(days)) => scaled(days, 86_400),
+        _ => None,
+    };
+    let age = match age {
+        Some(age) => age,
+        None => crate::bench::parse_duration(s)?,
+    };
+    Ok(now_ms.saturating_sub(age.as_millis() as u64))
+}
+
+/// Unix ms of `YYYY-MM-DD[(T| )HH:MM[:SS]][Z]`, read as UTC
+fn parse_utc(s: &str) -> Option<u64> {
+    let s = s.strip_suffix('Z').unwrap_or(s);
+    let (date, time) = match s.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (s, None),
+    };
+    let mut date = date.split('-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    if date.next().is_some() || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let mut seconds = 0;
+    if let Some(time) = time {
+        let mut parts = time.split(':').map(|part| part.parse::<u64>().ok());
+        let (hour, minute) = (parts.next()??, parts.next()??);
+        let second = parts.next().map_or(Some(0), |second| second)?;
+        if parts.next().is_some() || hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        seconds = hour * 3_600 + minute * 60 + second;
+    }
+    // Civil date to days since 1970-01-01 (the inverse of `utc`)
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = u64::try_from(era * 146_097 + doe - 719_468).ok()?;
+    Some((days * 86_400 + seconds) * 1_000)
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// The last `limit` entries matching `args`, oldest first
+pub fn select<'a>(entries: &'a [JournalEntry], args: &HistoryArgs) -> Vec<&'a JournalEntry> {
+    let mut selected: Vec<&JournalEntry> = entries
+        .iter()
+        .filter(|entry| args.since.is_none_or(|since| entry.at_ms >= since))
+        .filter(|entry| {
+            args.signature
+                .as_ref()
+                .is_none_or(|sig| entry.signature == *sig)
+        })
+        .filter(|entry| args.source.as_ref().is_none_or(|src| entry.source == *src))
+        .collect();
+    let skip = selected.len().saturating_sub(args.limit);
+    selected.drain(..skip);
+    selected
+}
+
+/// One signature's trail in a sentence: attempts, leaders reached, and the
+/// last outcome confirmation recorded
+pub fn verdict(trail: &[&JournalEntry]) -> String {
+    let count = |result| trail.iter().filter(|entry| entry.result == result).count();
+    let sent = count(JournalResult::Sent);
+    let leaders: HashSet<_> = trail
+        .iter()
+        .filter(|entry| entry.result == JournalResult::Sent)
+        .filter_map(|entry| entry.target)
+        .collect();
+    let mut verdict = format!(
+        "Written {} time(s) to {} leader(s), {} failed attempt(s)",
+        sent,
+        leaders.len(),
+        count(JournalResult::SendFailed)
+    );
+    let outcome = trail.iter().rev().find(|entry| {
+        !matches!(
+            entry.result,
+            JournalResult::Sent | JournalResult::SendFailed
+        )
+    });
+    verdict.push_str(&match outcome {
+        Some(entry) => match (entry.result, entry.slot) {
+            (JournalResult::Landed, Some(slot)) => format!("; landed in slot {}", slot),
+            (JournalResult::Failed, Some(slot)) => format!(
+                "; landed in slot {} with error: {}",
+                slot,
+                entry.error.as_deref().unwrap_or("unknown")
+            ),
+            (JournalResult::Replaced, _) => format!(
+                "; replayed as {}",
+                entry.replaced_by.as_deref().unwrap_or("unknown")
+            ),
+            (result, _) => format!("; {}", result.as_str()),
+        },
+        None if sent == 0 => "; never written to a leader".into(),
+        None => "; no landing recorded (check with `scramjet confirm`)".into(),
+    });
+    verdict
+}
+
+/// `YYYY-MM-DD HH:MM:SS.mmm` (UTC) of a Unix time in milliseconds
+fn utc(ms: u64) -> String {
+    let (days, ms_of_day) = (ms / 86_400_000, ms % 86_400_000);
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1_000 % 60,
+        ms_of_day % 1_000
+    )
+}
+
+fn entry_line(entry: &JournalEntry) -> String {
+    let mut line = format!(
+        "{}  {:<6} {:<11} {:>12}  {:<21}  {}",
+        utc(entry.at_ms),
+        entry.source,
+        entry.result.as_str(),
+        entry
+            .slot
+            .map_or_else(|| "-".into(), |slot| format!("slot {}", slot)),
+        entry
+            .target
+            .map_or_else(|| "-".into(), |target| target.to_string()),
+        entry.signature
+    );
+    if let Some(ref error) = entry.error {
+        line.push_str(&format!("  ({})", error));
+    }
+    if let Some(ref replacement) = entry.replaced_by {
+        line.push_str(&format!("  -> {}", replacement));
+    }
+    line
+}
+
+pub fn run(path: &Path, args: HistoryArgs, output: Output) -> anyhow::Result<()> {
+    if let Some(ref signature) = args.signature {
+        Signature::from_str(signature)
+            .map_err(|e| anyhow::anyhow!("Invalid signature '{}': {}", signature, e))?;
+    }
+    let entries = journal::read_journal(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read journal {}: {}", path.display(), e))?;
+    let selected = select(&entries, &args);
+
+    if output.is_json() {
+        for entry in selected {
+            output.emit(Event::Journal {
+                entry: entry.clone(),
+            });
+        }
+        return Ok(());
+    }
+    if selected.is_empty() {
+        println!("No matching entries in {}", path.display());
+        return Ok(());
+    }
+    for entry in &selected {
+        println!("{}", entry_line(entry));
+    }
+    if args.signature.is_some() {
+        println!("{}", verdict(&selected));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    fn entry(at_ms: u64, source: &str, result: JournalResult, slot: u64) -> JournalEntry {
+        let target: SocketAddr = format!("10.0.0.{}:8009", slot % 2 + 1).parse().unwrap();
+        JournalEntry {
+            at_ms,
+            signature: if source == "fire" { "fired" } else { "spammed" }.into(),
+            slot: Some(slot),
+            target: Some(target),
+            source: source.into(),
+            result,
+            error: None,
+            tx: None,
+            replaced_by: None,
+        }
+    }
+
+    #[test]
+    fn test_select_and_verdict() {
+        let entries = vec![
+            entry(1_000, "spam", JournalResult::Sent, 10),
+            entry(2_000, "fire", JournalResult::SendFailed, 20),
+            entry(3_000, "fire", JournalResult::Sent, 20),
+            entry(4_000, "fire", JournalResult::Sent, 21),
+            entry(9_000, "fire", JournalResult::Landed, 23),
+        ];
+        let args = |signature: Option<&str>, since, limit| HistoryArgs {
+            signature: signature.map(Into::into),
+            since,
+            source: None,
+            limit,
+        };
+
+        let fired = select(&entries, &args(Some("fired"), None, 50));
+        assert_eq!(fired.len(), 4);
+        assert_eq!(
+            verdict(&fired),
+            "Written 2 time(s) to 2 leader(s), 1 failed attempt(s); landed in slot 23"
+        );
+        // Newest entries win the limit; --since cuts by age
+        let last = select(&entries, &args(None, None, 2));
+        assert_eq!(last[0].at_ms, 4_000);
+        let since = parse_since_at("7s", 10_000).unwrap();
+        let recent = select(&entries, &args(None, Some(since), 50));
+        assert_eq!(recent.len(), 3);
+
+        let spammed = select(&entries, &args(Some("spammed"), None, 50));
+        assert!(
+            verdict(&spammed).ends_with("; no landing recorded (check with `scramjet confirm`)")
+        );
+
+        assert_eq!(utc(0), "1970-01-01 00:00:00.000");
+        assert_eq!(utc(1_700_000_000_123), "2023-11-14 22:13:20.123");
+        assert_eq!(utc(951_782_400_000), "2000-02-29 00:00:00.000");
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = 1_700_000_000_000;
+        assert_eq!(parse_since_at("30m", now), Ok(now - 1_800_000));
+        assert_eq!(parse_since_at("2h", now), Ok(now - 7_200_000));
+        assert_eq!(parse_since_at("1d", now), Ok(now - 86_400_000));
+        assert_eq!(parse_since_at("1699999000", now), Ok(1_699_999_000_000));
+        assert_eq!(
+            parse_since_at("2023-11-14T22:13:20Z", now),
+            Ok(1_700_000_000_000)
+        );
+        assert_eq!(
+            parse_since_at("2023-11-14 22:13", now),
+            Ok(1_699_999_980_000)
+        );
+        assert_eq!(parse_since_at("2000-02-29", now), Ok(951_782_400_000));
+        assert!(parse_since_at("2023-13-01", now).is_err());
+        assert!(parse_since_at("2023-11-14T25:00", now).is_err());
+        assert!(parse_since_at("soon", now).is_err());
+    }
+}
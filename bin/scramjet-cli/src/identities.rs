@@ -0,0 +1,127 @@
+//! `spam --keypair-dir`: a pool of identities, so traffic is spread across
+//! them (per-identity rate limits, stake-weighted QoS experiments).
+//!
+//! Every `*.json` keypair in the directory joins the pool, in file name order.
+//! Each one pays the fees of its sends and handshakes with the leader as its
+//! own QUIC client identity (a separate engine per identity), so validators
+//! see and rate the traffic per identity. Round-robin gives each send the next
+//! identity; per-leader pins each leader to one identity for the whole run.
+
+use clap::ValueEnum;
+use scramjet_common::{Config, LocalSigner};
+use scramjet_net::engine::QuicEngine;
+use solana_sdk::signature::read_keypair_file;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::path::Path;
+
+/// How sends are assigned to pool identities
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Rotation {
+    /// Next identity on every send
+    #[default]
+    RoundRobin,
+    /// One identity per leader (stable for the run)
+    PerLeader,
+}
+
+/// One pool entry: signs (and pays for) its sends and handshakes with it
+pub struct Identity {
+    pub signer: LocalSigner,
+    pub engine: QuicEngine,
+}
+
+pub struct IdentityPool {
+    identities: Vec<Identity>,
+    rotation: Rotation,
+}
+
+impl IdentityPool {
+    /// Load every `*.json` keypair in `dir`, with a QUIC engine for each
+    pub fn load_dir(dir: &Path, rotation: Rotation, config: &Config) -> anyhow::Result<Self> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir)
+            .map_err(|e| anyhow::anyhow!("Cannot read keypair dir {:?}: {}", dir, e))?
+        {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        let identities = paths
+            .iter()
+            .map(|path| {
+                let keypair = read_keypair_file(path).map_err(|e| {
+                    anyhow::anyhow!("Failed to load keypair from {:?}: {}", path, e)
+                })?;
+                let engine = QuicEngine::new(&keypair, config)
+                    .map_err(|e| anyhow::anyhow!("Failed to init engine for {:?}: {}", path, e))?;
+                Ok(Identity {
+                    signer: LocalSigner::new(keypair),
+                    engine,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        anyhow::ensure!(!identities.is_empty(), "No *.json keypairs in {:?}", dir);
+        Ok(Self {
+            identities,
+            rotation,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.identities.len()
+    }
+
+    /// Identity for send `round` to `leader`
+    pub fn pick(&self, round: u64, leader: SocketAddr) -> &Identity {
+        let index = match self.rotation {
+            Rotation::RoundRobin => round as usize % self.identities.len(),
+            Rotation::PerLeader => {
+                let mut hasher = DefaultHasher::new();
+                leader.hash(&mut hasher);
+                hasher.finish() as usize % self.identities.len()
+            }
+        };
+        &self.identities[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_common::TxSigner;
+    use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+
+    #[tokio::test]
+    async fn test_pool_rotation() {
+        let dir = std::env::temp_dir().join(format!("scramjet-ids-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let keypairs: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+        for (i, keypair) in keypairs.iter().enumerate() {
+            write_keypair_file(keypair, dir.join(format!("id-{}.json", i))).unwrap();
+        }
+        std::fs::write(dir.join("README.txt"), "not a keypair").unwrap();
+
+        let config = Config::from_env().unwrap();
+        let leader: SocketAddr = "10.0.0.1:8009".parse().unwrap();
+        let pool = IdentityPool::load_dir(&dir, Rotation::RoundRobin, &config).unwrap();
+        assert_eq!(pool.len(), 3);
+        let picked: Vec<_> = (0..4)
+            .map(|i| pool.pick(i, leader).signer.pubkey())
+            .collect();
+        assert_eq!(picked[0], keypairs[0].pubkey());
+        assert_eq!(picked[2], keypairs[2].pubkey());
+        assert_eq!(picked[3], keypairs[0].pubkey());
+
+        // Per-leader: the same leader always gets the same identity
+        let pool = IdentityPool::load_dir(&dir, Rotation::PerLeader, &config).unwrap();
+        let first = pool.pick(0, leader).signer.pubkey();
+        assert!((1..10).all(|i| pool.pick(i, leader).signer.pubkey() == first));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(IdentityPool::load_dir(&dir, Rotation::RoundRobin, &config).is_err());
+    }
+}
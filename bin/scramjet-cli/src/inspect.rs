@@ -0,0 +1,138 @@
+//! `scramjet balance` and `scramjet account`: read an account (the payer by
+//! default) through the cartographer's RPC client, so the payer can be
+//! sanity-checked mid-workflow without switching to solana-cli.
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::output::{Event, Output};
+
+fn sol(lamports: u64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+/// Print `pubkey`'s balance (confirmed) and how many transactions it covers
+/// at `fee` lamports each
+pub async fn balance(
+    rpc: &RpcClient,
+    pubkey: Pubkey,
+    fee: u64,
+    output: Output,
+) -> anyhow::Result<()> {
+    let lamports = rpc
+        .get_balance_with_commitment(&pubkey, CommitmentConfig::confirmed())
+        .await?
+        .value;
+    let transactions = lamports / fee.max(1);
+    if output.is_json() {
+        output.emit(Event::Balance {
+            pubkey: pubkey.to_string(),
+            lamports,
+            fee,
+            transactions,
+        });
+    } else {
+        println!("{}: {} SOL ({} lamports)", pubkey, sol(lamports), lamports);
+        println!(
+            "Covers ~{} transactions at the default fee ({} lamports each)",
+            transactions, fee
+        );
+    }
+    Ok(())
+}
+
+/// Print `pubkey`'s account (confirmed): balance, owner, data size and
+/// whether it holds its rent-exempt minimum
+pub async fn account(rpc: &RpcClient, pubkey: Pubkey, output: Output) -> anyhow::Result<()> {
+    let Some(account) = rpc
+        .get_account_with_commitment(&pubkey, CommitmentConfig::confirmed())
+        .await?
+        .value
+    else {
+        anyhow::bail!("Account {} not found (no lamports, never funded)", pubkey);
+    };
+    let rent_minimum = rpc
+        .get_minimum_balance_for_rent_exemption(account.data.len())
+        .await?;
+    if output.is_json() {
+        output.emit(Event::Account {
+            pubkey: pubkey.to_string(),
+            lamports: account.lamports,
+            owner: account.owner.to_string(),
+            executable: account.executable,
+            data_len: account.data.len(),
+            rent_epoch: account.rent_epoch,
+            rent_exempt_minimum: rent_minimum,
+        });
+    } else {
+        for line in account_lines(&pubkey, &account, rent_minimum) {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+fn account_lines(pubkey: &Pubkey, account: &Account, rent_minimum: u64) -> Vec<String> {
+    vec![
+        format!("Account:     {}", pubkey),
+        format!(
+            "Balance:     {} SOL ({} lamports)",
+            sol(account.lamports),
+            account.lamports
+        ),
+        format!(
+            "Owner:       {}{}",
+            account.owner,
+            if account.executable {
+                " (executable)"
+            } else {
+                ""
+            }
+        ),
+        format!("Data:        {} bytes", account.data.len()),
+        format!(
+            "Rent:        {} (minimum {} lamports)",
+            if account.lamports >= rent_minimum {
+                "exempt"
+            } else {
+                "NOT exempt"
+            },
+            rent_minimum
+        ),
+        format!("Rent epoch:  {}", account.rent_epoch),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_lines() {
+        let pubkey = Pubkey::new_unique();
+        let account = Account {
+            lamports: LAMPORTS_PER_SOL / 2,
+            data: vec![0; 80],
+            owner: solana_sdk::system_program::id(),
+            executable: false,
+            rent_epoch: u64::MAX,
+        };
+        let lines = account_lines(&pubkey, &account, 1_447_680);
+        assert_eq!(lines[1], "Balance:     0.5 SOL (500000000 lamports)");
+        assert_eq!(
+            lines[2],
+            format!("Owner:       {}", solana_sdk::system_program::id())
+        );
+        assert_eq!(lines[3], "Data:        80 bytes");
+        assert_eq!(lines[4], "Rent:        exempt (minimum 1447680 lamports)");
+
+        let poor = Account {
+            lamports: 1,
+            ..account
+        };
+        assert!(account_lines(&pubkey, &poor, 1_447_680)[4].contains("NOT exempt"));
+    }
+}
@@ -0,0 +1,179 @@
+//! `scramjet keygen`: write a new keypair file, optionally ground until its
+//! pubkey starts with a vanity prefix, so test identities for `spam
+//! --keypair-dir` can be created without solana-keygen.
+//!
+//! Grinding is brute force on every thread: each extra prefix character
+//! multiplies the expected attempts by 58.
+
+use log::{info, warn};
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::output::{Event, Output};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Longest base58 pubkey: a longer prefix can never match
+const MAX_PREFIX_CHARS: usize = 44;
+
+/// Prefix length beyond which grinding is warned to take a long time
+const LONG_GRIND_CHARS: usize = 5;
+
+/// Attempts a thread makes between checks for a find elsewhere
+const GRIND_BATCH: u64 = 1024;
+
+/// How often a running grind logs its progress
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct KeygenArgs {
+    pub prefix: Option<String>,
+    /// Default: `<PUBKEY>.json` in the current directory
+    pub outfile: Option<PathBuf>,
+    pub threads: usize,
+    pub force: bool,
+}
+
+/// `--prefix`: base58 characters only (no 0, O, I or l), at most as long as
+/// a pubkey
+pub fn parse_prefix(s: &str) -> Result<String, String> {
+    match s.chars().find(|c| !BASE58_ALPHABET.contains(*c)) {
+        Some(c) => Err(format!(
+            "'{}' is not base58 (pubkeys never contain 0, O, I or l)",
+            c
+        )),
+        None if s.is_empty() => Err("Prefix must not be empty".into()),
+        None if s.len() > MAX_PREFIX_CHARS => Err(format!(
+            "Prefix is {} characters; pubkeys have at most {}",
+            s.len(),
+            MAX_PREFIX_CHARS
+        )),
+        None => Ok(s.to_string()),
+    }
+}
+
+/// Generate keypairs on `threads` threads until one's pubkey starts with
+/// `prefix`; returns it with the attempts made
+pub fn grind(prefix: &str, threads: usize) -> (Keypair, u64) {
+    let found = AtomicBool::new(false);
+    let attempts = AtomicU64::new(0);
+    let started = Instant::now();
+    let keypair = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.max(1))
+            .map(|worker| {
+                let (found, attempts) = (&found, &attempts);
+                scope.spawn(move || {
+                    let mut logged = Instant::now();
+                    while !found.load(Ordering::Relaxed) {
+                        for tried in 1..=GRIND_BATCH {
+                            let keypair = Keypair::new();
+                            if keypair.pubkey().to_string().starts_with(prefix) {
+                                attempts.fetch_add(tried, Ordering::Relaxed);
+                                found.store(true, Ordering::Relaxed);
+                                return Some(keypair);
+                            }
+                        }
+                        let total =
+                            attempts.fetch_add(GRIND_BATCH, Ordering::Relaxed) + GRIND_BATCH;
+                        if worker == 0 && logged.elapsed() >= PROGRESS_INTERVAL {
+                            logged = Instant::now();
+                            info!(
+                                "Grinding: {} attempts ({:.0}/s)",
+                                total,
+                                total as f64 / started.elapsed().as_secs_f64()
+                            );
+                        }
+                    }
+                    None
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .filter_map(|worker| worker.join().expect("grind thread panicked"))
+            .next()
+            .expect("a grind thread found the prefix")
+    });
+    (keypair, attempts.load(Ordering::Relaxed))
+}
+
+pub fn run(args: KeygenArgs, output: Output) -> anyhow::Result<()> {
+    let check_free = |path: &Path| {
+        anyhow::ensure!(
+            args.force || !path.exists(),
+            "{:?} already exists (pass --force to overwrite)",
+            path
+        );
+        Ok(())
+    };
+    // Before grinding, which can take hours
+    if let Some(ref path) = args.outfile {
+        check_free(path)?;
+    }
+    let started = Instant::now();
+    let (keypair, attempts) = match args.prefix {
+        Some(ref prefix) => {
+            if prefix.len() > LONG_GRIND_CHARS {
+                warn!(
+                    "A {}-character prefix takes ~{:.0e} attempts on average; this may run for a long time",
+                    prefix.len(),
+                    58f64.powi(prefix.len() as i32)
+                );
+            }
+            info!(
+                "Grinding for a pubkey starting with '{}' on {} thread(s)...",
+                prefix, args.threads
+            );
+            grind(prefix, args.threads)
+        }
+        None => (Keypair::new(), 1),
+    };
+    let pubkey = keypair.pubkey();
+    let path = match args.outfile {
+        Some(ref path) => path.clone(),
+        None => {
+            let path = PathBuf::from(format!("{}.json", pubkey));
+            check_free(&path)?;
+            path
+        }
+    };
+    write_keypair_file(&keypair, &path)
+        .map_err(|e| anyhow::anyhow!("Failed to write keypair to {:?}: {}", path, e))?;
+
+    if output.is_json() {
+        output.emit(Event::Keygen {
+            pubkey: pubkey.to_string(),
+            path: path.display().to_string(),
+            attempts,
+        });
+    } else {
+        println!("Wrote {} to {:?}", pubkey, path);
+        if args.prefix.is_some() {
+            println!(
+                "Ground in {} attempts ({:.1}s)",
+                attempts,
+                started.elapsed().as_secs_f64()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grind_prefix() {
+        assert_eq!(parse_prefix("abc"), Ok("abc".to_string()));
+        let long = "z".repeat(MAX_PREFIX_CHARS + 1);
+        for bad in ["", "0x", "lol", "Ix", &long] {
+            assert!(parse_prefix(bad).is_err(), "{}", bad);
+        }
+
+        let (keypair, attempts) = grind("z", 2);
+        assert!(keypair.pubkey().to_string().starts_with('z'));
+        assert!(attempts >= 1);
+    }
+}
@@ -0,0 +1,220 @@
+//! `scramjet leaders`: the upcoming leader schedule as the Cartographer sees
+//! it (slot, leader identity, validator name, TPU QUIC address, software
+//! version, datacenter), as a table, JSON or CSV.
+//!
+//! Names come from `--names` (a validators.app-style registry file or URL)
+//! or, by default, from on-chain Validator Info. A failed name lookup only
+//! leaves the name column empty. Version and datacenter are what the
+//! Cartographer learned from gossip and `GEOIP_DB_FILES`.
+
+use clap::ValueEnum;
+use log::warn;
+use scramjet_net::cartographer::{Cartographer, ScheduledSlot};
+use scramjet_net::validator_info::{self, ValidatorInfo, ValidatorNames};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LeadersFormat {
+    /// Aligned table
+    Text,
+    /// JSON array
+    Json,
+    /// CSV with a header row
+    Csv,
+}
+
+/// One exported slot
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct LeaderRow {
+    slot: u64,
+    leader: String,
+    name: Option<String>,
+    tpu: Option<SocketAddr>,
+    /// Shield would skip this leader
+    blocked: bool,
+    version: Option<String>,
+    datacenter: Option<String>,
+}
+
+impl LeaderRow {
+    fn new(
+        scheduled: ScheduledSlot,
+        names: &ValidatorNames,
+        validators: &HashMap<Pubkey, ValidatorInfo>,
+    ) -> Self {
+        let info = validators.get(&scheduled.leader);
+        Self {
+            slot: scheduled.slot,
+            leader: scheduled.leader.to_string(),
+            name: names.get(&scheduled.leader).cloned(),
+            tpu: scheduled.tpu,
+            blocked: scheduled.blocked,
+            version: info.and_then(|info| info.version.clone()),
+            datacenter: info.and_then(|info| info.location.datacenter()),
+        }
+    }
+}
+
+pub async fn run(
+    cartographer: &Cartographer,
+    slots: u64,
+    names_source: Option<&str>,
+    format: LeadersFormat,
+) -> anyhow::Result<()> {
+    let topology = cartographer.topology();
+    let known: ValidatorNames = topology
+        .validators
+        .iter()
+        .filter_map(|(pubkey, info)| Some((*pubkey, info.name.clone()?)))
+        .collect();
+    let names = match names_source {
+        Some(source) => validator_info::load_registry(source).await,
+        // Started from the topology cache, without names yet
+        None if known.is_empty() => {
+            validator_info::fetch_onchain_names(&cartographer.rpc_pool()).await
+        }
+        None => Ok(known),
+    }
+    .unwrap_or_else(|e| {
+        warn!("Leaders: No validator names ({})", e);
+        ValidatorNames::new()
+    });
+
+    let current_slot = cartographer.get_known_slot();
+    let rows: Vec<LeaderRow> = cartographer
+        .upcoming_schedule(current_slot, slots)
+        .await
+        .into_iter()
+        .map(|scheduled| LeaderRow::new(scheduled, &names, &topology.validators))
+        .collect();
+
+    match format {
+        LeadersFormat::Json => println!("{}", serde_json::to_string_pretty(&rows)?),
+        LeadersFormat::Csv => print!("{}", to_csv(&rows)),
+        LeadersFormat::Text => {
+            println!("Current slot: {}", current_slot);
+            println!(
+                "{:<12} {:<44} {:<24} {:<22} {:<10} Datacenter",
+                "Slot", "Leader", "Name", "TPU", "Version"
+            );
+            for row in &rows {
+                let mut name: String = row
+                    .name
+                    .as_deref()
+                    .unwrap_or("-")
+                    .chars()
+                    .take(24)
+                    .collect();
+                if row.blocked {
+                    name = format!("{} (blocked)", name);
+                }
+                let tpu = row.tpu.map_or("-".to_string(), |addr| addr.to_string());
+                println!(
+                    "{:<12} {:<44} {:<24} {:<22} {:<10} {}",
+                    row.slot,
+                    row.leader,
+                    name,
+                    tpu,
+                    row.version.as_deref().unwrap_or("-"),
+                    row.datacenter.as_deref().unwrap_or("-")
+                );
+            }
+            if rows.is_empty() {
+                println!("(no schedule for the next {} slots)", slots);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn to_csv(rows: &[LeaderRow]) -> String {
+    let mut csv = String::from("slot,leader,name,tpu,blocked,version,datacenter\n");
+    for row in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            row.slot,
+            row.leader,
+            csv_field(row.name.as_deref().unwrap_or_default()),
+            row.tpu.map(|addr| addr.to_string()).unwrap_or_default(),
+            row.blocked,
+            csv_field(row.version.as_deref().unwrap_or_default()),
+            csv_field(row.datacenter.as_deref().unwrap_or_default())
+        ));
+    }
+    csv
+}
+
+/// Quote a field that contains a delimiter, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_net::validator_info::Location;
+
+    #[test]
+    fn test_leader_rows_to_csv() {
+        let (named, unnamed) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let names = ValidatorNames::from([(named, "Alpha, \"Prime\"".to_string())]);
+        let validators = HashMap::from([(
+            named,
+            ValidatorInfo {
+                version: Some("2.1.5".into()),
+                location: Location {
+                    asn: Some(24940),
+                    as_org: Some("Hetzner Online GmbH".into()),
+                    country: Some("DE".into()),
+                    ..Location::default()
+                },
+                ..ValidatorInfo::default()
+            },
+        )]);
+        let rows = vec![
+            LeaderRow::new(
+                ScheduledSlot {
+                    slot: 100,
+                    leader: named,
+                    tpu: Some("1.2.3.4:8009".parse().unwrap()),
+                    blocked: false,
+                },
+                &names,
+                &validators,
+            ),
+            LeaderRow::new(
+                ScheduledSlot {
+                    slot: 101,
+                    leader: unnamed,
+                    tpu: None,
+                    blocked: true,
+                },
+                &names,
+                &validators,
+            ),
+        ];
+        let csv = to_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "slot,leader,name,tpu,blocked,version,datacenter");
+        assert_eq!(
+            lines[1],
+            format!(
+                "100,{},\"Alpha, \"\"Prime\"\"\",1.2.3.4:8009,false,2.1.5,\"AS24940 Hetzner Online GmbH, DE\"",
+                named
+            )
+        );
+        assert_eq!(lines[2], format!("101,{},,,true,,", unnamed));
+
+        let json = serde_json::to_value(&rows).unwrap();
+        assert_eq!(json[0]["name"], "Alpha, \"Prime\"");
+        assert!(json[1]["tpu"].is_null());
+        assert_eq!(json[0]["version"], "2.1.5");
+    }
+}
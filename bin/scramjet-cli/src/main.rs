@@ -1,29 +1,129 @@
+mod advisor;
+mod airdrop;
+mod bench;
+mod carpet;
+mod confirm;
+mod doctor;
+mod history;
+mod identities;
+mod inspect;
+mod keygen;
+mod leaders;
+mod output;
+mod passphrase;
+mod replay;
+mod scoreboard;
+mod serve;
+mod shield;
+mod template;
+
 use anyhow::Context;
+use base64::Engine as _;
+use bytes::Bytes;
 use clap::{Parser, Subcommand};
 use dotenv::dotenv;
 use log::{debug, error, info, warn};
-use scramjet_common::Config;
+use scramjet::{Scramjet, SharedConfig};
+use scramjet_common::config::DEFAULT_CONFIG_FILE;
+use scramjet_common::funding;
+use scramjet_common::keystore;
+use scramjet_common::packet;
+use scramjet_common::signer::sign_transaction;
+use scramjet_common::{Cluster, Config, NonceManager, ScramjetError, TxBuilder, TxSigner};
 use scramjet_net::{
-    blocklist::BlocklistManager,
-    cartographer::Cartographer,
+    blockhash::BlockhashCache,
+    cartographer::{Cartographer, ProductionStart, StakedTarget},
+    control,
     engine::QuicEngine,
-    geyser::spawn_geyser_monitor,
+    jito::{self, JitoClient},
+    journal::{self, Journal},
+    priority::Priority,
+    rate_limit::RateLimiter,
+    raw::{self, RawEncoding},
+    slo::{self, FailureStage, LandingFeed, SendOutcome, SloMonitor, TxOutcomes},
+    stats::{PathStats, StatsReport},
 };
-#[allow(deprecated)]
 use solana_sdk::{
-    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    message::Message,
     pubkey::Pubkey,
-    signature::{read_keypair_file, Keypair, Signer},
-    system_instruction,
+    signature::{read_keypair_file, Keypair, Signature, Signer},
     transaction::Transaction,
 };
-use std::path::PathBuf;
+use std::io::Read;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+use advisor::ComputeProfiles;
+use identities::{IdentityPool, Rotation};
+use leaders::LeadersFormat;
+use output::{Event, Output, OutputFormat};
+use scoreboard::{InFlight, Tracker};
+use shield::ShieldCommand;
+use template::TxTemplate;
+
+/// Cached blockhash age beyond which signing fetches one directly
+const MAX_BLOCKHASH_AGE: Duration = Duration::from_secs(5);
+
+/// Re-sign long spam runs before the blockhash (~60s validity) expires
+const SPAM_RESIGN_AFTER: Duration = Duration::from_secs(45);
+
+/// Lamports `fire` transfers to the recipient
+const FIRE_LAMPORTS: u64 = 1;
+
+/// How long `fire --on-leader-start` waits for a leader to start producing
+const LEADER_START_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How `spam --unique` makes each round a distinct transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Unique {
+    /// Transfer 1 + round lamports
+    Lamports,
+    /// Add a memo with a per-run id and the round
+    Memo,
+}
+
+/// How `spam` spaces its sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Pace {
+    /// Back-to-back (only --rate slows them down)
+    Burst,
+    /// Spread the rounds left over the rest of the current leader window
+    /// (~400ms x its remaining slots), re-planned before every send
+    Window,
+}
 
 #[derive(Parser)]
 #[command(name = "scramjet")]
 struct Cli {
+    /// TOML config file (default: ./scramjet.toml when present)
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    /// Config file profile to apply (default: the file's `profile` key)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Output format: `text`, or `json` for one JSON object per line on stdout
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Mainnet preset: public RPC endpoint and priority fee, and refuse to
+    /// run against an RPC node of another cluster
+    #[arg(long, group = "cluster")]
+    mainnet: bool,
+
+    /// Devnet preset (see --mainnet)
+    #[arg(long, group = "cluster")]
+    devnet: bool,
+
+    /// Testnet preset (see --mainnet)
+    #[arg(long, group = "cluster")]
+    testnet: bool,
+
     // Optional Override via Command Line
     #[arg(short, long)]
     rpc: Option<String>,
@@ -31,9 +131,34 @@ struct Cli {
     #[arg(long)]
     geyser: Option<String>,
 
+    /// Websocket endpoint for the slotSubscribe clock (used without Geyser)
+    #[arg(long)]
+    ws: Option<String>,
+
     #[arg(short, long)]
     keypair: Option<PathBuf>,
 
+    /// Passphrase-encrypted keypair (see `encrypt-keypair`); the passphrase
+    /// comes from KEYPAIR_PASSPHRASE or a prompt
+    #[arg(long, conflicts_with = "keypair")]
+    keypair_encrypted: Option<PathBuf>,
+
+    /// Secondary identity for validators that reject the primary (default: ephemeral)
+    #[arg(long)]
+    fallback_keypair: Option<PathBuf>,
+
+    /// Serve (or query, for `status`) the control socket at this path
+    #[arg(long)]
+    control_socket: Option<PathBuf>,
+
+    /// Durable nonce account for fire/spam (transactions survive blockhash rotation)
+    #[arg(long)]
+    nonce_account: Option<String>,
+
+    /// Keypair authorized to advance the nonce account (default: the signer)
+    #[arg(long, requires = "nonce_account")]
+    nonce_authority: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -46,6 +171,33 @@ enum Commands {
         recipient: Option<String>,
         #[arg(long)]
         priority_fee: Option<u64>,
+        /// Attach a memo (Memo program v2) after the transfer
+        #[arg(long)]
+        memo: Option<String>,
+        /// Fire this many ms into the leader's window (waits for the next
+        /// window when that point has already passed)
+        #[arg(long)]
+        slot_offset_ms: Option<u64>,
+        /// Fire the moment the next leader is seen producing its first slot
+        /// (Geyser entries or interslot updates: GEYSER_ENTRIES,
+        /// GEYSER_INTERSLOT_UPDATES)
+        #[arg(long, conflicts_with = "slot_offset_ms")]
+        on_leader_start: bool,
+        /// Also send to the next leader window's leader, which can hold or
+        /// forward the transaction if the current leader misses it
+        #[arg(long)]
+        double_tap: bool,
+        /// Priority class: high, normal or bulk (see QUIC_STREAM_BUDGET)
+        #[arg(long, default_value_t = Priority::Normal)]
+        priority: Priority,
+        /// Check the payer covers fees and the transfer (rent exemption
+        /// included) before sending (default: CHECK_FUNDS)
+        #[arg(long)]
+        check_funds: bool,
+        /// Resolve the leader, handshake and sign, then print what would be
+        /// sent and to whom instead of sending it
+        #[arg(long)]
+        dry_run: bool,
     },
     Spam {
         #[arg(short, long, default_value = "10")]
@@ -54,6 +206,253 @@ enum Commands {
         recipient: Option<String>,
         #[arg(long)]
         priority_fee: Option<u64>,
+        /// Switch to the new leader when the slot's leader rotates mid-run
+        #[arg(long)]
+        follow_leaders: bool,
+        /// Transaction template (JSON, or YAML/TOML by extension) instead of
+        /// self-transfers; every round is built and signed afresh
+        #[arg(long)]
+        template: Option<PathBuf>,
+        /// Send rate limit, e.g. 200/s, 50/100ms, 1000/m (default: SEND_RATE_LIMIT)
+        #[arg(long)]
+        rate: Option<String>,
+        /// Sends allowed back-to-back under --rate (default: SEND_RATE_BURST)
+        #[arg(long, requires = "rate")]
+        burst: Option<u32>,
+        /// Spread sends across every *.json keypair in this directory (each
+        /// pays its own fees and handshakes as its own QUIC identity)
+        #[arg(long, conflicts_with = "template")]
+        keypair_dir: Option<PathBuf>,
+        /// How sends are assigned to --keypair-dir identities
+        #[arg(long, value_enum, default_value_t = Rotation::RoundRobin, requires = "keypair_dir")]
+        rotation: Rotation,
+        /// Vary and re-sign every round so each send is a distinct, landable
+        /// transaction (by default all rounds share one signature and the
+        /// leader dedupes them)
+        #[arg(long, value_enum, conflicts_with = "template")]
+        unique: Option<Unique>,
+        /// Tag every round with a memo "<TEXT>-<round>" (each round is
+        /// re-signed), to find the run's transactions in explorers
+        #[arg(long)]
+        memo: Option<String>,
+        /// Skip the end-of-run landing scoreboard (and its wait of up to 20s
+        /// for signatures to land)
+        #[arg(long)]
+        no_scoreboard: bool,
+        /// burst (default) | window: spread sends over the leader window's
+        /// remaining slot time instead of bursting them
+        #[arg(long, value_enum, default_value_t = Pace::Burst)]
+        pace: Pace,
+        /// Priority class: high, normal or bulk (bulk leaves room under
+        /// --rate and QUIC_STREAM_BUDGET for higher classes)
+        #[arg(long, default_value_t = Priority::Normal)]
+        priority: Priority,
+        /// Resolve the leader, handshake and sign, then print what would be
+        /// sent and to whom instead of sending it
+        #[arg(long)]
+        dry_run: bool,
+        /// Walk the leader schedule of the next --slots slots, sending
+        /// --count transactions to each leader as its window begins
+        #[arg(
+            long,
+            requires = "slots",
+            conflicts_with_all = ["follow_leaders", "template", "keypair_dir", "unique", "memo", "pace"]
+        )]
+        carpet: bool,
+        /// Slots of the schedule --carpet covers, from the current one
+        #[arg(long, requires = "carpet")]
+        slots: Option<u64>,
+    },
+    /// Print live status of a running scramjet process (via its control socket)
+    Status,
+    /// Encrypt a keypair file for --keypair-encrypted
+    EncryptKeypair {
+        /// Plaintext keypair (id.json format)
+        input: PathBuf,
+        /// Where to write the encrypted keypair
+        output: PathBuf,
+    },
+    /// Write a new keypair file (default: <PUBKEY>.json), optionally ground
+    /// until its pubkey starts with --prefix
+    Keygen {
+        /// Vanity prefix for the pubkey (base58; each character is ~58x the work)
+        #[arg(long, value_parser = keygen::parse_prefix)]
+        prefix: Option<String>,
+        /// Where to write the keypair (id.json format)
+        #[arg(long)]
+        outfile: Option<PathBuf>,
+        /// Grinding threads (default: all cores)
+        #[arg(long, requires = "prefix")]
+        threads: Option<usize>,
+        /// Overwrite --outfile if it exists
+        #[arg(long)]
+        force: bool,
+    },
+    /// Send an admin command to a running instance's control socket
+    /// (reload-config, reload-blocklist, flush-connections, set-rate <spec|off> [burst])
+    Admin {
+        #[arg(required = true, trailing_var_arg = true)]
+        command: Vec<String>,
+    },
+    /// Add, remove or list blocklist entries (a running instance reloads via its control socket)
+    Shield {
+        #[command(subcommand)]
+        action: ShieldCommand,
+    },
+    /// Rank leaders by delivery quality (live via the control socket, or from a stats file)
+    Stats {
+        /// Read a report written to STATS_FILE instead of asking the running process
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Leaders to show
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Print the upcoming leader schedule: slot, leader, validator name, TPU address
+    Leaders {
+        /// Slots to show after the current one
+        #[arg(long, default_value_t = 100)]
+        slots: u64,
+        /// text, json or csv (default: json with `--output json`, else text)
+        #[arg(long, value_enum)]
+        format: Option<LeadersFormat>,
+        /// Validator names from a validators.app-style JSON registry (file or
+        /// URL) instead of on-chain Validator Info
+        #[arg(long)]
+        names: Option<String>,
+    },
+    /// Send pre-signed transactions (one base64/base58 per line) to the current leader
+    SendRaw {
+        /// File or directory of encoded transactions (default: stdin)
+        input: Option<PathBuf>,
+        #[arg(long, default_value = "auto")]
+        encoding: RawEncoding,
+        /// Priority class: high, normal or bulk (see QUIC_STREAM_BUDGET)
+        #[arg(long, default_value_t = Priority::Normal)]
+        priority: Priority,
+    },
+    /// Accept JSON-RPC `sendTransaction` calls over HTTP and send them to the leader
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8899")]
+        listen: SocketAddr,
+        /// Also accept `scramjet.Ingest` gRPC transaction streams on this address
+        #[arg(long)]
+        grpc: Option<SocketAddr>,
+        /// Also accept length-prefixed transactions on this Unix socket
+        #[arg(long)]
+        uds: Option<PathBuf>,
+        /// Priority class of JSON-RPC calls (unless their config names one)
+        /// and Unix socket frames; gRPC messages carry their own
+        #[arg(long, default_value_t = Priority::Normal)]
+        priority: Priority,
+    },
+    /// Paced sends measuring handshake, stream write and landing latency
+    Bench {
+        /// How long to send for (e.g. 30s, 2m)
+        #[arg(long, default_value = "30s", value_parser = bench::parse_duration)]
+        duration: Duration,
+        /// Target sends per second (1 to 100000)
+        #[arg(long, default_value = "100", value_parser = clap::value_parser!(u32).range(1..=100_000))]
+        rate: u32,
+        #[arg(short, long)]
+        recipient: Option<String>,
+        #[arg(long)]
+        priority_fee: Option<u64>,
+        /// Scale the priority fee up (to 2x) for leaders with below-average stake
+        #[arg(long)]
+        stake_fee: bool,
+    },
+    /// Send a tipped Jito bundle (direct TPU QUIC when the leader is not Jito-enabled)
+    Bundle {
+        /// Transactions in the bundle (1-5; the last one carries the tip)
+        #[arg(short, long, default_value = "1")]
+        count: usize,
+        #[arg(short, long)]
+        recipient: Option<String>,
+        #[arg(long)]
+        priority_fee: Option<u64>,
+        /// Tip in lamports (default: JITO_TIP_LAMPORTS)
+        #[arg(long)]
+        tip: Option<u64>,
+        /// Block engine gRPC endpoint (default: JITO_BLOCK_ENGINE_URL)
+        #[arg(long)]
+        block_engine: Option<String>,
+        /// Priority class of the direct QUIC fallback: high, normal or bulk
+        #[arg(long, default_value_t = Priority::Normal)]
+        priority: Priority,
+    },
+    /// Request an airdrop from the RPC node's faucet (devnet, testnet, local
+    /// validators) and wait until it is confirmed
+    Airdrop {
+        /// SOL to request
+        #[arg(long, default_value = "1", value_parser = airdrop::parse_sol)]
+        amount: u64,
+        /// Recipient (default: the keypair)
+        #[arg(long)]
+        to: Option<String>,
+        /// How long to wait for confirmation (e.g. 60s, 2m)
+        #[arg(long, default_value = "60s", value_parser = bench::parse_duration)]
+        timeout: Duration,
+    },
+    /// Print an account's balance (default: the payer) and how many
+    /// transactions it covers at the default fee
+    Balance {
+        pubkey: Option<String>,
+    },
+    /// Print an account: balance, owner, data size, rent exemption
+    Account {
+        pubkey: String,
+    },
+    /// Follow a signature to finalized: slot, on-chain error and time to
+    /// each commitment level
+    Confirm {
+        signature: String,
+        /// How long to keep polling (e.g. 30s, 2m)
+        #[arg(long, default_value = "30s", value_parser = bench::parse_duration)]
+        timeout: Duration,
+    },
+    /// Query the send journal: what was sent, when, to which leader, and
+    /// whether it landed
+    History {
+        /// Show only this signature's trail, with a verdict
+        signature: Option<String>,
+        /// Only entries since TIME: an age (30m, 2h), a Unix timestamp, or a
+        /// UTC date and time (2026-10-17T09:30)
+        #[arg(long, value_parser = history::parse_since)]
+        since: Option<u64>,
+        /// Only entries from this command (fire, spam, bench, bundle; send
+        /// for library sends)
+        #[arg(long)]
+        source: Option<String>,
+        /// Most recent entries to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        /// Read this journal instead of JOURNAL_FILE
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+    /// Check RPC, Geyser, keypair and balance, UDP buffers, the Shield and
+    /// the clock, and print a pass / warn / fail report
+    Doctor,
+    /// Resend journaled transactions that never landed (crash recovery):
+    /// as-is while their blockhash is valid, re-signed against a fresh one
+    /// after
+    Replay {
+        /// Journal to replay (default: JOURNAL_FILE)
+        #[arg(long)]
+        from: Option<PathBuf>,
+        /// Only transactions journaled since TIME: an age (30m, 2h), a Unix
+        /// timestamp, or a UTC date and time (2026-10-17T09:30)
+        #[arg(long, value_parser = history::parse_since)]
+        since: Option<u64>,
+        /// Priority class: high, normal or bulk (see QUIC_STREAM_BUDGET)
+        #[arg(long, default_value_t = Priority::Normal)]
+        priority: Priority,
+        /// Check each transaction and print what would be done without
+        /// sending anything
+        #[arg(long)]
+        dry_run: bool,
     },
 }
 
@@ -64,283 +463,1886 @@ async fn main() -> anyhow::Result<()> {
     env_logger::init();
 
     let cli = Cli::parse();
+    let output = Output::new(cli.output);
 
     // STEP 2: Load and validate config (fail-fast on invalid values)
-    let mut config = Config::from_env().context("Invalid configuration")?;
+    let config_file = cli.config.clone().or_else(|| {
+        let default = PathBuf::from(DEFAULT_CONFIG_FILE);
+        default.exists().then_some(default)
+    });
+    let mut config = match &config_file {
+        Some(path) => Config::from_file_with_profile(path, cli.profile.as_deref())
+            .context("Invalid configuration")?,
+        None if cli.profile.is_some() => {
+            anyhow::bail!("--profile requires a config file ({})", DEFAULT_CONFIG_FILE)
+        }
+        None => Config::from_env().context("Invalid configuration")?,
+    };
 
-    // STEP 3: Apply CLI overrides (CLI > env > default)
+    // STEP 3: Apply CLI overrides (CLI > env > file > default); --rpc still
+    // beats a preset's endpoint, and is then checked against its cluster
+    let preset = cli.cluster();
+    if let Some(cluster) = preset {
+        config.apply_cluster(cluster);
+    }
     if let Some(rpc) = cli.rpc {
         config.rpc_url = rpc;
     }
     if let Some(geyser) = cli.geyser {
         config.geyser_url = Some(geyser);
     }
+    if let Some(ws) = cli.ws {
+        config.ws_url = Some(ws);
+    }
+    if let Some(ref path) = cli.control_socket {
+        config.control_socket = Some(path.display().to_string());
+    }
+
+    // Commands that talk to a running process (or only touch local files,
+    // or only query RPC) skip identity + RPC bootstrap
+    let control_socket = PathBuf::from(
+        config
+            .control_socket
+            .clone()
+            .unwrap_or_else(|| control::DEFAULT_CONTROL_SOCKET.to_string()),
+    );
+    match cli.command {
+        Commands::Status => return print_status(&control_socket, output).await,
+        Commands::Stats { ref file, limit } => {
+            return print_stats(file.as_deref(), &control_socket, limit, output).await;
+        }
+        Commands::EncryptKeypair {
+            ref input,
+            output: ref path,
+        } => return encrypt_keypair(input, path),
+        Commands::Keygen {
+            ref prefix,
+            ref outfile,
+            threads,
+            force,
+        } => {
+            let args = keygen::KeygenArgs {
+                prefix: prefix.clone(),
+                outfile: outfile.clone(),
+                threads: threads.unwrap_or_else(|| {
+                    std::thread::available_parallelism().map_or(1, |threads| threads.get())
+                }),
+                force,
+            };
+            return keygen::run(args, output);
+        }
+        Commands::Shield { action } => return shield::run(action, &control_socket, output).await,
+        Commands::Admin { ref command } => {
+            let response = control::send_command(&control_socket, &command.join(" "))
+                .await
+                .with_context(|| format!("Admin command failed ({:?})", control_socket))?;
+            println!("{}", response);
+            return Ok(());
+        }
+        Commands::Confirm {
+            ref signature,
+            timeout,
+        } => return confirm::run(&config.rpc_url, signature, timeout, output).await,
+        Commands::History {
+            ref signature,
+            since,
+            ref source,
+            limit,
+            ref file,
+        } => {
+            let Some(path) = file.clone().or_else(|| config.journal_path()) else {
+                anyhow::bail!("The send journal is off (JOURNAL_FILE=off)");
+            };
+            let args = history::HistoryArgs {
+                signature: signature.clone(),
+                since,
+                source: source.clone(),
+                limit,
+            };
+            return history::run(&path, args, output);
+        }
+        _ => {}
+    }
 
+    // With a remote signer the keypair is only the QUIC identity (optional)
     let keypair_path = match cli.keypair {
-        Some(p) => p,
+        Some(p) => Some(p),
+        None if config.remote_signer_url.is_some() || cli.keypair_encrypted.is_some() => None,
         None => {
             let base = dirs::home_dir()
                 .or_else(|| std::env::current_dir().ok())
                 .ok_or_else(|| anyhow::anyhow!("Cannot determine home or current directory"))?;
-            base.join(".config/solana/id.json")
+            Some(base.join(".config/solana/id.json"))
         }
     };
-    let identity = read_keypair_file(&keypair_path)
-        .map_err(|e| anyhow::anyhow!("Failed to load keypair from {:?}: {}. Use --keypair to specify path.", keypair_path, e))?;
-    info!("Identity: {}", identity.pubkey());
-
-    // STEP 4: Initialize Shield (blocklist protection)
-    info!("Initializing Shield (blocklist protection)...");
-    let shield_manager = Arc::new(BlocklistManager::from_env());
-    
-    // Load local blocklist synchronously (fast boot with protection)
-    let loaded_count = shield_manager.load_local().await;
-    if loaded_count > 0 {
-        info!("Shield: Active with {} blocked validators", loaded_count);
-    } else {
-        warn!("Shield: No local blocklist found. Will fetch from remote.");
-    }
-    
-    // Spawn background updater (hourly refresh from remote)
-    let _shield_updater = shield_manager.clone().spawn_updater();
-
-    // STEP 5: Initialize Cartographer (cluster map + leader schedule)
-    info!("Initializing Cartographer with RPC: {}", config.rpc_url);
-    let cartographer = Arc::new(Cartographer::new(
-        config.rpc_url.clone(),
-        shield_manager.get_handle(),
-    ));
-    cartographer.refresh_topology().await?; // Fetch validator pubkey -> QUIC socket map
-    cartographer.update_schedule().await?; // Fetch leader schedule for current epoch
-
-    // STEP 6: Initialize Clock (Geyser hybrid vs RPC polling mode)
-    if let Some(ref url) = config.geyser_url {
-        info!("MODE: HYBRID (RPC Map + Geyser Clock)");
-        info!("   Geyser Endpoint: {}", url);
-        // Use Yellowstone Geyser for real-time slot updates (lowest latency)
-        let startup_rx = spawn_geyser_monitor(
-            url.clone(),
-            cartographer.clone(),
-            config.geyser_reconnect_delay(),
-            config.geyser_max_reconnect_delay(),
-        );
-
-        // Wait up to 10 seconds for initial connection, then continue regardless
-        match tokio::time::timeout(Duration::from_secs(10), startup_rx).await {
-            Ok(Ok(Ok(()))) => {
-                info!("Geyser: Initial connection established.");
-            }
-            Ok(Ok(Err(e))) => {
-                warn!("Geyser: Initial connection failed: {}. Continuing with background retries.", e);
-            }
-            Ok(Err(_)) => {
-                warn!("Geyser: Startup signal lost. Continuing with background retries.");
-            }
-            Err(_) => {
-                warn!("Geyser: Connection timed out after 10s. Continuing with background retries.");
-            }
+    if let Commands::Doctor = cli.command {
+        let args = doctor::DoctorArgs {
+            keypair: keypair_path.as_deref(),
+            keypair_encrypted: cli.keypair_encrypted.as_deref(),
+        };
+        return doctor::run(&config, args, output).await;
+    }
+    let identity = match (keypair_path, cli.keypair_encrypted) {
+        (Some(ref keypair_path), _) => read_keypair_file(keypair_path).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to load keypair from {:?}: {}. Use --keypair to specify path.",
+                keypair_path,
+                e
+            )
+        })?,
+        (None, Some(ref path)) => {
+            let passphrase = passphrase::read(&format!("Passphrase for {:?}: ", path), false)?;
+            keystore::read_encrypted_keypair_file(path, &passphrase).map_err(|e| {
+                anyhow::anyhow!("Failed to load encrypted keypair from {:?}: {}", path, e)
+            })?
         }
-    } else {
-        info!("MODE: LEGACY (RPC Polling)");
-        info!("   (Geyser URL not found in .env or args. Using fallback.)");
-        // Fall back to RPC polling for slot updates
-        let cart_clone = cartographer.clone();
-        let poll_interval = config.rpc_poll_interval();
-        tokio::spawn(async move {
-            loop {
-                if let Err(e) = cart_clone.fetch_rpc_slot().await {
-                    debug!("RPC slot fetch failed: {}", e);
-                }
-                tokio::time::sleep(poll_interval).await;
+        (None, None) => {
+            warn!("Remote signer without --keypair: QUIC handshakes use an ephemeral (unstaked) identity");
+            Keypair::new()
+        }
+    };
+    info!("Identity: {}", identity.pubkey());
+    if let Commands::Airdrop {
+        amount,
+        ref to,
+        timeout,
+    } = cli.command
+    {
+        let recipient = match to {
+            Some(to) => parse_recipient(Some(to.clone()), &identity.pubkey())?,
+            None if config.remote_signer_url.is_some() => {
+                anyhow::bail!("With a remote signer, pass the payer to fund with --to")
             }
-        });
+            None => identity.pubkey(),
+        };
+        return airdrop::run(&config, recipient, amount, timeout, output).await;
     }
+    let fallback_identity = match cli.fallback_keypair {
+        Some(ref path) => Some(read_keypair_file(path).map_err(|e| {
+            anyhow::anyhow!("Failed to load fallback keypair from {:?}: {}", path, e)
+        })?),
+        None => None,
+    };
+    let nonce_authority = match cli.nonce_authority {
+        Some(ref path) => Some(read_keypair_file(path).map_err(|e| {
+            anyhow::anyhow!("Failed to load nonce authority from {:?}: {}", path, e)
+        })?),
+        None => None,
+    };
 
-    // STEP 7: Initialize QUIC Engine with client certificate
-    info!("Initializing Engine...");
-    let engine = Arc::new(QuicEngine::new(&identity, &config)?);
+    // STEP 4: Start the sender (Shield, Cartographer, clock, Engine, SLO
+    // monitor, Scout, restart watchdog, control socket)
+    // `reload-config` and the file watcher re-read the same source (CLI
+    // overrides are not tunables)
+    let profile = cli.profile.clone();
+    let mut builder = Scramjet::builder().config(config).identity(identity);
+    if let Some(ref path) = config_file {
+        builder = builder.watch_config_file(path);
+    }
+    builder = builder.config_loader(move || {
+        let mut fresh = match config_file {
+            Some(ref path) => Config::from_file_with_profile(path, profile.as_deref())?,
+            None => Config::from_env()?,
+        };
+        // The preset's priority fee is a tunable; keep it over the file's
+        if let Some(cluster) = preset {
+            fresh.apply_cluster(cluster);
+        }
+        Ok(fresh)
+    });
+    if let Some(fallback) = fallback_identity {
+        builder = builder.fallback_identity(fallback);
+    }
+    let scramjet = builder.build().await?;
+    let config = scramjet.config();
+    let signer = scramjet.signer().as_ref();
+    let payer = signer.pubkey();
+    let cartographer = scramjet.cartographer().clone();
+    let engine = scramjet.engine().clone();
+    let slo_monitor = scramjet.slo_monitor().cloned();
 
-    // STEP 8: Start Scout (pre-warm connections to upcoming leaders)
-    let cart_clone = cartographer.clone();
-    let engine_clone = engine.clone();
-    let scout_interval = config.scout_interval();
-    let lookahead = config.scout_lookahead_slots;
+    // Ctrl-C stops send loops and background tasks; the sender then drains
+    // in-flight streams before closing. A second Ctrl-C exits immediately.
+    let shutdown = scramjet.shutdown_token();
+    let interrupt = shutdown.clone();
     tokio::spawn(async move {
-        loop {
-            let current_slot = cart_clone.get_known_slot();
-            if current_slot > 0 {
-                // Get unique upcoming leader IPs to pre-warm
-                let upcoming = cart_clone
-                    .get_upcoming_leaders(current_slot, lookahead)
-                    .await;
-                for target in upcoming {
-                    debug!("Scout: Warming up connection to {}", target);
-                    // Pre-warm connections (best-effort, failures logged but not fatal)
-                    if let Err(e) = engine_clone.get_connection_handle(target).await {
-                        debug!("Scout: Failed to warm connection to {}: {}", target, e);
-                    }
-                }
+        if tokio::signal::ctrl_c().await.is_ok() {
+            warn!("Interrupted. Finishing in-flight sends (Ctrl-C again to exit now)...");
+            interrupt.cancel();
+            if tokio::signal::ctrl_c().await.is_ok() {
+                std::process::exit(130);
             }
-            tokio::time::sleep(scout_interval).await;
         }
     });
 
+    // STEP 5: Durable nonce (optional): validate the account before sending
+    let nonce = match cli.nonce_account {
+        Some(ref account) => {
+            let account: Pubkey = account.parse().map_err(|_| {
+                anyhow::anyhow!("Invalid nonce account: '{}'. Expected base58.", account)
+            })?;
+            let authority = nonce_authority
+                .as_ref()
+                .map_or(payer, |authority| authority.pubkey());
+            let manager = NonceManager::new(cartographer.rpc_client(), account, authority);
+            let current = manager.fetch().await?;
+            info!("Nonce: Using durable nonce {} from {}", current, account);
+            Some(manager)
+        }
+        None => None,
+    };
+    let signing = Signing {
+        signer,
+        blockhashes: scramjet.blockhash_cache(),
+        nonce: nonce.as_ref().map(|manager| {
+            let authority = nonce_authority.as_ref();
+            let extra = authority.filter(|authority| authority.pubkey() != payer);
+            (manager, extra)
+        }),
+    };
+
     match cli.command {
-        Commands::Monitor => monitor_loop(cartographer, config.monitor_interval()).await,
+        Commands::Monitor => {
+            let live = scramjet.config_handle().clone();
+            let engine = scramjet.engine().clone();
+            monitor_loop(cartographer, engine, live, &shutdown, output).await
+        }
+        Commands::Balance { pubkey } => {
+            let pubkey = match pubkey {
+                Some(ref pubkey) => parse_pubkey(pubkey)?,
+                None => payer,
+            };
+            let fee = funding::transaction_fee(
+                1,
+                config.default_compute_unit_limit,
+                config.default_priority_fee,
+            );
+            inspect::balance(&cartographer.rpc_client(), pubkey, fee, output).await?;
+        }
+        Commands::Account { pubkey } => {
+            let pubkey = parse_pubkey(&pubkey)?;
+            inspect::account(&cartographer.rpc_client(), pubkey, output).await?;
+        }
+        Commands::Leaders {
+            slots,
+            format,
+            ref names,
+        } => {
+            let format = format.unwrap_or(if output.is_json() {
+                LeadersFormat::Json
+            } else {
+                LeadersFormat::Text
+            });
+            leaders::run(&cartographer, slots, names.as_deref(), format).await?;
+        }
         Commands::Fire {
             recipient,
             priority_fee,
+            memo,
+            slot_offset_ms,
+            on_leader_start,
+            double_tap,
+            priority,
+            check_funds,
+            dry_run,
         } => {
-            let to = parse_recipient(recipient, &identity)?;
+            let to = parse_recipient(recipient, &payer)?;
             let fee = priority_fee.unwrap_or(config.default_priority_fee);
-            fire_transaction(&cartographer, &engine, &identity, to, fee, &config).await?;
+            let slo = slo_monitor.as_deref();
+            fire_transaction(
+                &cartographer,
+                &engine,
+                &signing,
+                to,
+                fee,
+                memo.as_deref(),
+                slot_offset_ms.map(Duration::from_millis),
+                on_leader_start,
+                double_tap,
+                priority,
+                check_funds || config.check_funds,
+                dry_run,
+                &config,
+                slo,
+                scramjet.landing_feed().map(Arc::as_ref),
+                output,
+            )
+            .await?;
         }
         Commands::Spam {
             count,
             recipient,
             priority_fee,
+            follow_leaders,
+            template,
+            rate,
+            burst,
+            keypair_dir,
+            rotation,
+            unique,
+            memo,
+            no_scoreboard,
+            pace,
+            priority,
+            dry_run,
+            carpet,
+            slots,
         } => {
-            let to = parse_recipient(recipient, &identity)?;
-            let fee = priority_fee.unwrap_or(config.default_priority_fee);
-            spam_transactions(&cartographer, &engine, &identity, to, count, fee, &config).await?;
+            let to = parse_recipient(recipient, &payer)?;
+            let slo = slo_monitor.as_deref();
+            let template = match template {
+                Some(ref path) => Some(TxTemplate::load(path, &payer, &to)?),
+                None => None,
+            };
+            let mut profiles = template
+                .as_ref()
+                .and(config.compute_profile_path())
+                .map(ComputeProfiles::load);
+            let limiter = match rate {
+                Some(ref spec) => Some(Arc::new(RateLimiter::from_spec(
+                    spec,
+                    burst.unwrap_or(config.send_rate_burst),
+                )?)),
+                None => scramjet.rate_limiter(),
+            };
+            let identities = match keypair_dir {
+                Some(ref dir) => {
+                    // The nonce authority signs for the payer's transactions only
+                    anyhow::ensure!(
+                        signing.nonce.is_none(),
+                        "--keypair-dir cannot be combined with --nonce-account"
+                    );
+                    let pool = IdentityPool::load_dir(dir, rotation, &config)?;
+                    info!("Rotating across {} identities ({:?})", pool.len(), rotation);
+                    Some(pool)
+                }
+                None => None,
+            };
+            // Every round would advance the same nonce: only the first could land
+            anyhow::ensure!(
+                (unique.is_none() && memo.is_none() && template.is_none())
+                    || signing.nonce.is_none(),
+                "--unique, --memo and --template cannot be combined with --nonce-account"
+            );
+            let tracker = (!no_scoreboard && !dry_run)
+                .then(|| Tracker::spawn(cartographer.clone(), engine.journal().cloned(), "spam"));
+            if let (true, Some(slots)) = (carpet, slots) {
+                let args = carpet::CarpetArgs {
+                    recipient: to,
+                    priority_fee: priority_fee.unwrap_or(config.default_priority_fee),
+                    compute_unit_limit: config.default_compute_unit_limit,
+                    per_leader: count,
+                    slots,
+                    priority,
+                    dry_run,
+                };
+                carpet::run(
+                    &cartographer,
+                    &engine,
+                    &signing,
+                    args,
+                    limiter.as_deref(),
+                    tracker,
+                    slo,
+                    scramjet.landing_feed().map(Arc::as_ref),
+                    &shutdown,
+                    output,
+                )
+                .await?;
+            } else {
+                spam_transactions(
+                    &cartographer,
+                    &engine,
+                    &signing,
+                    to,
+                    template.as_ref(),
+                    profiles.as_mut(),
+                    unique,
+                    memo.as_deref(),
+                    count,
+                    priority_fee,
+                    follow_leaders,
+                    pace,
+                    priority,
+                    dry_run,
+                    limiter.as_deref(),
+                    identities.as_ref(),
+                    tracker,
+                    scramjet.config_handle(),
+                    slo,
+                    scramjet.landing_feed().map(Arc::as_ref),
+                    &shutdown,
+                    output,
+                )
+                .await?;
+            }
+        }
+        Commands::SendRaw {
+            input,
+            encoding,
+            priority,
+        } => {
+            let lines = read_raw_input(input.as_deref())?;
+            send_raw_transactions(&scramjet, &lines, encoding, priority, output).await?;
+        }
+        Commands::Replay {
+            from,
+            since,
+            priority,
+            dry_run,
+        } => {
+            let Some(path) = from.or_else(|| config.journal_path()) else {
+                anyhow::bail!("The send journal is off (JOURNAL_FILE=off); pass --from");
+            };
+            let entries = journal::read_journal(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read journal {}: {}", path.display(), e))?;
+            // Replayed sends and their outcomes go to the journal replayed
+            let journal = Journal::open(&path, config.journal_max_bytes())
+                .map_err(|e| anyhow::anyhow!("Failed to open journal {}: {}", path.display(), e))?;
+            let args = replay::ReplayArgs {
+                since,
+                priority,
+                dry_run,
+            };
+            replay::run(
+                cartographer.clone(),
+                &engine,
+                &signing,
+                Arc::new(journal),
+                &entries,
+                args,
+                output,
+            )
+            .await?;
+        }
+        Commands::Serve {
+            listen,
+            grpc,
+            uds,
+            priority,
+        } => {
+            let args = serve::ServeArgs {
+                listen,
+                grpc,
+                uds,
+                priority,
+                drain: config.shutdown_drain_timeout(),
+            };
+            serve::run(&scramjet, args, &shutdown, output).await?;
+        }
+        Commands::Bench {
+            duration,
+            rate,
+            recipient,
+            priority_fee,
+            stake_fee,
+        } => {
+            let args = bench::BenchArgs {
+                duration,
+                rate,
+                recipient: parse_recipient(recipient, &payer)?,
+                priority_fee: priority_fee.unwrap_or(config.default_priority_fee),
+                stake_fee,
+                compute_unit_limit: config.default_compute_unit_limit,
+            };
+            bench::run(
+                cartographer.clone(),
+                &engine,
+                &signing,
+                args,
+                &shutdown,
+                output,
+            )
+            .await?;
+        }
+        Commands::Bundle {
+            count,
+            recipient,
+            priority_fee,
+            tip,
+            block_engine,
+            priority,
+        } => {
+            let url = block_engine
+                .or_else(|| config.jito_block_engine_url.clone())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("bundle requires --block-engine or JITO_BLOCK_ENGINE_URL")
+                })?;
+            let request = BundleRequest {
+                recipient: parse_recipient(recipient, &payer)?,
+                count,
+                priority_fee: priority_fee.unwrap_or(config.default_priority_fee),
+                tip: tip.unwrap_or(config.jito_tip_lamports),
+                priority,
+            };
+            send_bundle(
+                &cartographer,
+                &engine,
+                &signing,
+                &url,
+                request,
+                &config,
+                output,
+            )
+            .await?;
+        }
+        Commands::Status
+        | Commands::Stats { .. }
+        | Commands::EncryptKeypair { .. }
+        | Commands::Keygen { .. }
+        | Commands::Shield { .. }
+        | Commands::Admin { .. }
+        | Commands::Airdrop { .. }
+        | Commands::Doctor
+        | Commands::Confirm { .. }
+        | Commands::History { .. } => {
+            unreachable!("handled before bootstrap")
+        }
+    }
+
+    // One-shot commands exit before the evaluator ticks again
+    if let Some(monitor) = slo_monitor {
+        for report in monitor.check() {
+            warn!("{}", report);
         }
     }
 
+    scramjet.shutdown().await;
     Ok(())
 }
 
-/// Parse recipient pubkey from CLI arg, defaulting to identity pubkey.
-fn parse_recipient(recipient: Option<String>, identity: &Keypair) -> anyhow::Result<Pubkey> {
+impl Cli {
+    /// Cluster preset flag, if one was given
+    fn cluster(&self) -> Option<Cluster> {
+        [
+            (self.mainnet, Cluster::Mainnet),
+            (self.devnet, Cluster::Devnet),
+            (self.testnet, Cluster::Testnet),
+        ]
+        .into_iter()
+        .find_map(|(set, cluster)| set.then_some(cluster))
+    }
+}
+
+/// Parse recipient pubkey from CLI arg, defaulting to the payer (self-transfer).
+fn parse_recipient(recipient: Option<String>, payer: &Pubkey) -> anyhow::Result<Pubkey> {
     match recipient {
         Some(s) => s
             .parse()
             .map_err(|_| anyhow::anyhow!("Invalid recipient pubkey: '{}'. Expected base58.", s)),
-        None => Ok(identity.pubkey()),
+        None => Ok(*payer),
     }
 }
 
-async fn monitor_loop(cartographer: Arc<Cartographer>, interval: std::time::Duration) {
+fn parse_pubkey(s: &str) -> anyhow::Result<Pubkey> {
+    s.parse()
+        .map_err(|_| anyhow::anyhow!("Invalid pubkey: '{}'. Expected base58.", s))
+}
+
+/// Query a running process over its control socket and print a concise status
+async fn print_status(path: &std::path::Path, output: Output) -> anyhow::Result<()> {
+    let status = control::request_status(path)
+        .await
+        .with_context(|| format!("No running scramjet at {:?}", path))?;
+    if output.is_json() {
+        println!("{}", serde_json::to_string(&status)?);
+        return Ok(());
+    }
+
+    let na = || "n/a".to_string();
+    println!("Clock:        {}", status.clock);
+    println!(
+        "Slot:         {} (slot time {}ms)",
+        status.slot, status.slot_time_ms
+    );
+    println!("Stale slots:  {} rejected", status.slot_regressions);
+    if status.clock == "geyser" {
+        println!(
+            "Geyser:       last slot {} ago, {} stalled stream(s) reconnected",
+            status
+                .geyser_last_slot_ms
+                .map_or_else(na, |ms| format!("{}ms", ms)),
+            status.geyser_stalls
+        );
+    }
+    println!(
+        "Leader:       {} @ {}",
+        status.leader.unwrap_or_else(na),
+        status.leader_addr.map(|a| a.to_string()).unwrap_or_else(na)
+    );
+    println!("Warm conns:   {}", status.warm_connections);
+    println!(
+        "Rate limit:   {}",
+        status
+            .send_rate
+            .map_or("unlimited".to_string(), |rate| format!("{:.0}/s", rate))
+    );
+    println!(
+        "Queue depth:  {}",
+        status
+            .queue_depth
+            .map(|depth| format!("{} ({} dropped or rejected)", depth, status.queue_overflows))
+            .unwrap_or_else(na)
+    );
+    println!("Expired:      {}", status.expired_before_send);
+    println!("Duplicates:   {}", status.duplicates_refused);
+    println!(
+        "Landing (5m): {}",
+        status
+            .landing_rate_5m
+            .map(|r| format!("{:.1}%", r * 100.0))
+            .unwrap_or_else(na)
+    );
+    for rpc in &status.rpc {
+        println!(
+            "RPC:          {} | {} | errors {:.0}% ({}/{}){}",
+            rpc.url,
+            rpc.latency_ms
+                .map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(na),
+            rpc.error_rate * 100.0,
+            rpc.errors,
+            rpc.requests,
+            if rpc.demoted { " | DEMOTED" } else { "" }
+        );
+    }
+    println!("Uptime:       {}s", status.uptime_secs);
+    Ok(())
+}
+
+/// Print leaders ranked by delivery quality
+async fn print_stats(
+    file: Option<&Path>,
+    control_socket: &Path,
+    limit: usize,
+    output: Output,
+) -> anyhow::Result<()> {
+    let report = match file {
+        Some(path) => StatsReport::load(path)
+            .await
+            .with_context(|| format!("Failed to read stats from {:?}", path))?,
+        None => control::request_stats(control_socket)
+            .await
+            .with_context(|| format!("No running scramjet at {:?}", control_socket))?,
+    };
+    if output.is_json() {
+        println!("{}", serde_json::to_string(&report)?);
+        return Ok(());
+    }
+
+    let na = || "n/a".to_string();
+    let percent = |rate: Option<f64>| rate.map(|r| format!("{:.1}%", r * 100.0));
+    println!(
+        "{} leader(s) over {}s (top {} by delivery quality)",
+        report.leaders.len(),
+        report.uptime_secs,
+        limit.min(report.leaders.len())
+    );
+    println!(
+        "{:>4}  {:<44}  {:<24}  {:<22}  {:>8}  {:>7}  {:>7}  {:>7}  {:>9}  {:>8}  {:>8}  {:>7}  {:>6}  {:>8}  {:>8}  {:>7}  Version / Datacenter",
+        "#",
+        "Identity",
+        "Name",
+        "TPU",
+        "Sent",
+        "Stream",
+        "Timeout",
+        "Connect",
+        "Handshake",
+        "RTT",
+        "Delivery",
+        "Landed",
+        "Loss",
+        "Path RTT",
+        "Cwnd",
+        "Dgrams",
+    );
+    for (rank, leader) in report.leaders.iter().take(limit).enumerate() {
+        let name: String = leader
+            .name
+            .as_deref()
+            .unwrap_or("-")
+            .chars()
+            .take(24)
+            .collect();
+        let host: Vec<&str> = leader
+            .version
+            .iter()
+            .chain(&leader.datacenter)
+            .map(String::as_str)
+            .collect();
+        let path = leader.path.as_ref();
+        println!(
+            "{:>4}  {:<44}  {:<24}  {:<22}  {:>8}  {:>7}  {:>7}  {:>7}  {:>9}  {:>8}  {:>8}  {:>7}  {:>6}  {:>8}  {:>8}  {:>7}  {}",
+            rank + 1,
+            leader.identity.clone().unwrap_or_else(|| "unknown".into()),
+            name,
+            leader.addr.to_string(),
+            leader.sent,
+            leader.stream_errors,
+            leader.timeouts,
+            leader.connect_errors,
+            leader
+                .avg_handshake_ms
+                .map(|ms| format!("{:.1}ms", ms))
+                .unwrap_or_else(na),
+            leader
+                .rtt_ms
+                .map(|ms| format!("{:.1}ms", ms))
+                .unwrap_or_else(na),
+            percent(leader.delivery_rate()).unwrap_or_else(na),
+            percent(leader.landing_rate()).unwrap_or_else(na),
+            percent(path.and_then(|path| path.loss_rate())).unwrap_or_else(na),
+            path.map(|path| format!("{:.1}ms", path.rtt_ms))
+                .unwrap_or_else(na),
+            path.map(|path| format!("{}KB", path.cwnd / 1024))
+                .unwrap_or_else(na),
+            path.map(|path| path.datagrams_sent.to_string())
+                .unwrap_or_else(na),
+            host.join(", "),
+        );
+    }
+    Ok(())
+}
+
+/// Write `input` encrypted under a new passphrase to `path`
+fn encrypt_keypair(input: &Path, path: &Path) -> anyhow::Result<()> {
+    anyhow::ensure!(!path.exists(), "{:?} already exists", path);
+    let keypair = read_keypair_file(input)
+        .map_err(|e| anyhow::anyhow!("Failed to load keypair from {:?}: {}", input, e))?;
+    let passphrase = passphrase::read("New passphrase: ", true)?;
+    keystore::write_encrypted_keypair_file(&keypair, &passphrase, path)?;
+    println!("Encrypted {} to {:?}", keypair.pubkey(), path);
+    Ok(())
+}
+
+async fn monitor_loop(
+    cartographer: Arc<Cartographer>,
+    engine: Arc<QuicEngine>,
+    config: SharedConfig,
+    shutdown: &CancellationToken,
+    output: Output,
+) {
     info!("Starting Monitor Mode...");
     loop {
         let slot = cartographer.get_known_slot();
-        if slot > 0 {
-            if let Some(target) = cartographer.get_target(slot).await {
-                println!("Slot: {} | Leader IP: {}", slot, target);
+        let leader = cartographer.get_leader(slot).await;
+        let validator = leader.and_then(|l| cartographer.topology().validator(&l).cloned());
+        let rtt = leader.and_then(|l| cartographer.rtt(&l));
+        let leader_addr = cartographer.get_target(slot).await;
+        let path = leader_addr.and_then(|addr| engine.stats().path(&addr));
+        if slot > 0 && output.is_json() {
+            output.emit(Event::Slot {
+                slot,
+                leader: leader.map(|l| l.to_string()),
+                leader_addr,
+                validator,
+                rtt_ms: rtt.map(|rtt| rtt.as_secs_f64() * 1_000.0),
+                path,
+                slot_time_ms: cartographer.slot_duration().as_millis() as u64,
+            });
+        } else if slot > 0 {
+            let slot_ms = cartographer.slot_duration().as_millis();
+            if let Some(target) = leader_addr {
+                let who = validator
+                    .map(|v| format!(" | Validator: {}", v))
+                    .unwrap_or_default();
+                let rtt = rtt
+                    .map(|rtt| format!(" | RTT: {:.1}ms", rtt.as_secs_f64() * 1_000.0))
+                    .unwrap_or_default();
+                let path = path.map(|path| format!(" | Path: {}", path_summary(&path)));
+                println!(
+                    "Slot: {} | Leader IP: {}{}{}{} | Slot Time: {}ms",
+                    slot,
+                    target,
+                    who,
+                    rtt,
+                    path.unwrap_or_default(),
+                    slot_ms
+                );
             } else {
-                println!("Slot: {} | Leader IP: UNKNOWN", slot);
+                println!(
+                    "Slot: {} | Leader IP: UNKNOWN | Slot Time: {}ms",
+                    slot, slot_ms
+                );
+            }
+        }
+        let interval = config.load().monitor_interval();
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
+
+/// QUIC path of a leader's connections, e.g. `RTT 31.2ms, cwnd 14KB, 0.4% lost`
+fn path_summary(path: &PathStats) -> String {
+    let loss = path
+        .loss_rate()
+        .map(|rate| format!(", {:.1}% lost", rate * 100.0))
+        .unwrap_or_default();
+    format!(
+        "RTT {:.1}ms, cwnd {}KB{}",
+        path.rtt_ms,
+        path.cwnd / 1024,
+        loss
+    )
+}
+
+/// Signers and blockhash source for transactions built by fire/spam
+#[derive(Clone, Copy)]
+struct Signing<'a> {
+    /// Fee payer (local keypair or remote signer)
+    signer: &'a dyn TxSigner,
+    /// Background-refreshed recent blockhash
+    blockhashes: &'a BlockhashCache,
+    /// Durable nonce instead of a recent blockhash, with its authority
+    /// keypair when that is not the payer
+    nonce: Option<(&'a NonceManager, Option<&'a Keypair>)>,
+}
+
+impl Signing<'_> {
+    /// Start a transaction paid by the signer
+    fn builder(&self) -> TxBuilder {
+        TxBuilder::new(self.signer.pubkey())
+    }
+
+    /// Sign against a fresh blockhash, or the current durable nonce (with the
+    /// advance instruction prepended)
+    async fn sign(&self, tx: TxBuilder) -> anyhow::Result<Transaction> {
+        let (tx, blockhash, authority) = match self.nonce {
+            Some((manager, authority)) => {
+                let nonce = manager.fetch().await?;
+                let tx = tx.nonce_advance(manager.advance_instruction());
+                (tx, nonce, authority)
             }
+            None => (tx, self.latest_blockhash().await?, None),
+        };
+        let mut tx = Transaction::new_unsigned(tx.legacy_message(blockhash));
+        if let Some(authority) = authority {
+            tx.try_partial_sign(&[authority], blockhash)?;
         }
-        tokio::time::sleep(interval).await;
+        sign_transaction(self.signer, &mut tx).await?;
+        Ok(tx)
+    }
+
+    /// Sign an already-built message again, against a fresh blockhash
+    /// (`scramjet replay`)
+    async fn resign(&self, mut message: Message) -> anyhow::Result<Transaction> {
+        message.recent_blockhash = self.latest_blockhash().await?;
+        let mut tx = Transaction::new_unsigned(message);
+        sign_transaction(self.signer, &mut tx).await?;
+        Ok(tx)
     }
+
+    /// Cached unless the refresher has stalled
+    async fn latest_blockhash(&self) -> anyhow::Result<Hash> {
+        match self.blockhashes.fresh(MAX_BLOCKHASH_AGE) {
+            Some(blockhash) => Ok(blockhash),
+            None => Ok(self.blockhashes.refresh().await?),
+        }
+    }
+}
+
+/// Wire bytes of a signed transaction, refused when over the packet limit
+/// (validators drop those silently)
+fn encode(tx: &Transaction) -> anyhow::Result<Bytes> {
+    let bytes = Bytes::from(bincode::serialize(tx)?);
+    packet::check_packet_size(&bytes)?;
+    Ok(bytes)
+}
+
+/// Print (or emit) a transaction a dry run would have sent to `leader`
+fn report_dry_run(
+    command: &'static str,
+    index: Option<u64>,
+    slot: u64,
+    leader: SocketAddr,
+    tx: &Transaction,
+    tx_bytes: &[u8],
+    output: Output,
+) {
+    let signature = tx.signatures.first().map(ToString::to_string);
+    let payer = tx.message.account_keys.first().map(ToString::to_string);
+    let (signature, payer) = (signature.unwrap_or_default(), payer.unwrap_or_default());
+    let transaction = base64::engine::general_purpose::STANDARD.encode(tx_bytes);
+    if !output.is_json() {
+        let round = index.map(|i| format!(" (tx {})", i)).unwrap_or_default();
+        println!(
+            "[dry run] Would send {} bytes to {} for slot {}{}",
+            tx_bytes.len(),
+            leader,
+            slot,
+            round
+        );
+        println!("  Signature: {}", signature);
+        println!("  Payer:     {}", payer);
+        println!("  Tx:        {}", transaction);
+    }
+    output.emit(Event::DryRun {
+        command,
+        index,
+        slot,
+        leader,
+        signature,
+        payer,
+        size: tx_bytes.len(),
+        transaction,
+    });
 }
 
+/// Next slot seen starting a leader window (None within LEADER_START_TIMEOUT)
+async fn next_leader_start(cartographer: &Cartographer) -> Option<ProductionStart> {
+    let mut starts = cartographer.subscribe_production();
+    starts.mark_unchanged();
+    let next = async {
+        loop {
+            starts.changed().await.ok()?;
+            match *starts.borrow_and_update() {
+                Some(start) if start.window_start => return Some(start),
+                _ => {}
+            }
+        }
+    };
+    tokio::time::timeout(LEADER_START_TIMEOUT, next)
+        .await
+        .ok()
+        .flatten()
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn fire_transaction(
     cartographer: &Cartographer,
     engine: &QuicEngine,
-    identity: &Keypair,
+    signing: &Signing<'_>,
     recipient: Pubkey,
     priority_fee: u64,
+    memo: Option<&str>,
+    slot_offset: Option<Duration>,
+    on_leader_start: bool,
+    double_tap: bool,
+    priority: Priority,
+    check_funds: bool,
+    dry_run: bool,
     config: &Config,
+    slo: Option<&SloMonitor>,
+    landing: Option<&LandingFeed>,
+    output: Output,
 ) -> anyhow::Result<()> {
-    // Get fresh blockhash for transaction
-    let rpc = cartographer.rpc_client();
-    let latest_blockhash = rpc.get_latest_blockhash().await?;
-
-    // Build transaction: compute budget + priority fee + transfer
-    let instructions = vec![
-        ComputeBudgetInstruction::set_compute_unit_limit(config.default_compute_unit_limit),
-        ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
-        system_instruction::transfer(&identity.pubkey(), &recipient, 1),
-    ];
-
-    let tx = Transaction::new_signed_with_payer(
-        &instructions,
-        Some(&identity.pubkey()),
-        &[identity],
-        latest_blockhash,
-    );
-    let tx_bytes = bincode::serialize(&tx)?;
+    // Build transaction: compute budget + priority fee + transfer (+ memo)
+    let mut builder = signing
+        .builder()
+        .compute_unit_limit(config.default_compute_unit_limit)
+        .compute_unit_price(priority_fee)
+        .transfer(&recipient, FIRE_LAMPORTS);
+    if let Some(memo) = memo {
+        builder = builder.memo(memo);
+    }
 
-    // Resolve current leader and send via QUIC
-    let slot = cartographer.get_known_slot();
-    if let Some(addr) = cartographer.get_target(slot).await {
-        info!("Target: {}. Firing (Fee: {})...", addr, priority_fee);
-        engine.send_transaction(addr, tx_bytes).await?;
-        let sig = tx
-            .signatures
-            .first()
-            .ok_or_else(|| anyhow::anyhow!("Transaction has no signatures"))?;
-        info!("Sent! Sig: {}", sig);
-    } else {
+    // Fresh blockhash (or durable nonce) for transaction
+    let tx = signing.sign(builder).await?;
+
+    // Fail here rather than land a transaction that fails on chain
+    if check_funds {
+        let fee = funding::transaction_fee(
+            tx.signatures.len(),
+            config.default_compute_unit_limit,
+            priority_fee,
+        );
+        let payer = signing.signer.pubkey();
+        funding::verify_funding(
+            &cartographer.rpc_client(),
+            &payer,
+            &recipient,
+            fee,
+            FIRE_LAMPORTS,
+        )
+        .await?;
+        debug!("Funding: {} covers fee {} + transfer", payer, fee);
+    }
+    let tx_bytes = encode(&tx)?;
+    let sig = tx
+        .signatures
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Transaction has no signatures"))?;
+
+    // Slot-phase firing: hold the signed transaction for the chosen point in
+    // a leader window, then target the slot that point falls in
+    let slot = match slot_offset {
+        Some(offset) => match cartographer.firing_point(offset).await {
+            Some(point) => {
+                info!(
+                    "Waiting {:?} to fire {:?} into {}'s window (slot {})",
+                    point.at.saturating_duration_since(Instant::now()),
+                    offset,
+                    point.leader,
+                    point.slot
+                );
+                tokio::time::sleep_until(point.at.into()).await;
+                point.slot
+            }
+            None => {
+                warn!("Slot phase unknown (no slot update or schedule yet). Firing now.");
+                cartographer.get_known_slot()
+            }
+        },
+        None if on_leader_start => {
+            info!("Waiting for the next leader to start producing...");
+            match next_leader_start(cartographer).await {
+                Some(start) => {
+                    info!(
+                        "Leader {} started slot {}. Firing.",
+                        start.leader.map_or_else(|| "?".into(), |pk| pk.to_string()),
+                        start.slot
+                    );
+                    start.slot
+                }
+                None => {
+                    warn!(
+                        "No leader start seen in {:?} (needs GEYSER_ENTRIES or GEYSER_INTERSLOT_UPDATES). Firing now.",
+                        LEADER_START_TIMEOUT
+                    );
+                    cartographer.get_known_slot()
+                }
+            }
+        }
+        None => cartographer.get_known_slot(),
+    };
+
+    // Resolve the slot's leader (plus the next window's when double tapping)
+    let Some(StakedTarget {
+        addr,
+        leader,
+        stake_share,
+        ..
+    }) = cartographer.get_target_with_stake(slot).await
+    else {
         error!("No leader found for slot {}", slot);
+        output.emit(Event::Error {
+            command: "fire",
+            index: None,
+            slot: Some(slot),
+            leader: None,
+            signature: Some(sig.to_string()),
+            error: format!("No leader found for slot {}", slot),
+        });
+        if let Some(monitor) = slo {
+            monitor.record(SendOutcome::Failed(FailureStage::NoLeader));
+        }
+        return Ok(());
+    };
+    info!(
+        "Leader for slot {}: {} ({:.2}% of stake)",
+        slot,
+        leader,
+        stake_share * 100.0
+    );
+    let mut targets = vec![addr];
+    if double_tap {
+        match cartographer.get_next_window_target(slot).await {
+            Some(next) if next != addr => targets.push(next),
+            Some(_) => debug!("Double tap: {} also leads the next window", addr),
+            None => warn!("Double tap: No leader for the window after slot {}", slot),
+        }
+    }
+
+    // Dry run: handshake with every target, but keep the transaction
+    if dry_run {
+        for &target in &targets {
+            if let Err(e) = engine.get_connection_handle(target).await {
+                output.emit(Event::Error {
+                    command: "fire",
+                    index: None,
+                    slot: Some(slot),
+                    leader: Some(target),
+                    signature: Some(sig.to_string()),
+                    error: e.to_string(),
+                });
+                return Err(e.into());
+            }
+            report_dry_run("fire", None, slot, target, &tx, &tx_bytes, output);
+        }
+        return Ok(());
+    }
+
+    // Send to every target at once via QUIC
+    info!("Target: {:?}. Firing (Fee: {})...", targets, priority_fee);
+    let sends = targets.iter().map(|&target| {
+        let tx_bytes = tx_bytes.clone();
+        async move {
+            let started = Instant::now();
+            let result = engine
+                .send_transaction_with(target, tx_bytes, priority)
+                .await;
+            (target, result, started.elapsed())
+        }
+    });
+    let mut delivered = None;
+    let mut errors = Vec::new();
+    for (target, result, elapsed) in futures::future::join_all(sends).await {
+        journal_send(
+            engine,
+            "fire",
+            sig,
+            &tx_bytes,
+            slot,
+            target,
+            result.as_ref().err(),
+        );
+        match result {
+            Ok(()) => {
+                info!("Sent to {}! Sig: {}", target, sig);
+                output.emit(Event::Sent {
+                    command: "fire",
+                    index: None,
+                    slot,
+                    leader: target,
+                    signature: sig.to_string(),
+                    latency_us: output::micros(elapsed),
+                });
+                delivered.get_or_insert(target);
+            }
+            Err(e) => {
+                if let Some(monitor) = slo {
+                    let stage = FailureStage::from_send_error(&e);
+                    monitor.record_to(target, SendOutcome::Failed(stage));
+                }
+                output.emit(Event::Error {
+                    command: "fire",
+                    index: None,
+                    slot: Some(slot),
+                    leader: Some(target),
+                    signature: Some(sig.to_string()),
+                    error: e.to_string(),
+                });
+                errors.push(e);
+            }
+        }
+    }
+    // One delivery is enough; fail only when every target failed
+    let Some(addr) = delivered else {
+        return Err(errors.swap_remove(0).into());
+    };
+
+    if let Some(monitor) = slo {
+        // Landing is credited to the first leader that took the transaction
+        let outcome = slo::await_landing(cartographer, landing, sig, slot).await;
+        info!("Outcome: {:?}", outcome);
+        output.emit(Event::outcome(sig.to_string(), addr, outcome));
+        monitor.record_to(addr, outcome);
     }
     Ok(())
 }
 
+/// Send one spam round over a pool identity's own engine (and connections)
+async fn send_as(
+    engine: &QuicEngine,
+    target: SocketAddr,
+    round: u64,
+    priority: Priority,
+    tx_bytes: &Bytes,
+) -> Result<(), ScramjetError> {
+    let pool = engine.get_connection_pool(target).await?;
+    let connection = &pool[round as usize % pool.len()];
+    engine
+        .write_stream(target, connection, &mut [tx_bytes.clone()], priority)
+        .await
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn spam_transactions(
     cartographer: &Cartographer,
     engine: &QuicEngine,
-    identity: &Keypair,
+    signing: &Signing<'_>,
     recipient: Pubkey,
+    template: Option<&TxTemplate>,
+    profiles: Option<&mut ComputeProfiles>,
+    unique: Option<Unique>,
+    memo: Option<&str>,
     count: u64,
-    priority_fee: u64,
-    config: &Config,
+    priority_fee: Option<u64>,
+    follow_leaders: bool,
+    pace: Pace,
+    priority: Priority,
+    dry_run: bool,
+    limiter: Option<&RateLimiter>,
+    identities: Option<&IdentityPool>,
+    tracker: Option<Tracker>,
+    config: &SharedConfig,
+    slo: Option<&SloMonitor>,
+    landing: Option<&LandingFeed>,
+    shutdown: &CancellationToken,
+    output: Output,
 ) -> anyhow::Result<()> {
-    // Build transaction once (reused for all sends)
-    let rpc = cartographer.rpc_client();
-    let latest_blockhash = rpc.get_latest_blockhash().await?;
-
-    // Build transaction: compute budget + priority fee + transfer
-    let instructions = vec![
-        ComputeBudgetInstruction::set_compute_unit_limit(config.default_compute_unit_limit),
-        ComputeBudgetInstruction::set_compute_unit_price(priority_fee),
-        system_instruction::transfer(&identity.pubkey(), &recipient, 1),
-    ];
-
-    let tx = Transaction::new_signed_with_payer(
-        &instructions,
-        Some(&identity.pubkey()),
-        &[identity],
-        latest_blockhash,
-    );
-    let tx_bytes = bincode::serialize(&tx)?;
+    // Build transaction: compute budget + priority fee + transfer (or the
+    // template's instructions, instantiated per round). With --unique the
+    // transfer amount or a memo differs every round; --memo tags every round
+    // with its sequence number.
+    let memo_tag = match (memo, unique) {
+        (Some(memo), _) => Some(memo.to_string()),
+        (None, Some(Unique::Memo)) => Some(format!("scramjet {:08x}", rand::random::<u32>())),
+        _ => None,
+    };
+    let configured_limit = template
+        .and_then(TxTemplate::compute_unit_limit)
+        .unwrap_or(config.load().default_compute_unit_limit);
+    // A limit learned from earlier runs of the template only ever tightens
+    let advised_limit = template
+        .zip(profiles.as_deref())
+        .and_then(|(template, profiles)| profiles.limit(template.fingerprint()))
+        .filter(|&limit| limit < configured_limit);
+    if let Some(limit) = advised_limit {
+        info!(
+            "Compute: Using the {} CU limit learned for this template (configured: {})",
+            limit, configured_limit
+        );
+    }
+    let compute_unit_limit = advised_limit.unwrap_or(configured_limit);
+    // Without --priority-fee, follow the default across config reloads
+    let current_fee = || priority_fee.unwrap_or_else(|| config.load().default_priority_fee);
+    let mut fee = current_fee();
+    let build = |round: u64, payer: Pubkey, fee: u64| -> anyhow::Result<TxBuilder> {
+        let mut tx = TxBuilder::new(payer)
+            .compute_unit_limit(compute_unit_limit)
+            .compute_unit_price(fee);
+        match template {
+            Some(template) => {
+                tx = tx.instructions(template.instructions(round, rand::random())?);
+            }
+            None => {
+                let lamports = match unique {
+                    Some(Unique::Lamports) => 1 + round,
+                    _ => 1,
+                };
+                tx = tx.transfer(&recipient, lamports);
+            }
+        }
+        if let Some(ref tag) = memo_tag {
+            tx = tx.memo(&format!("{}-{}", tag, round));
+        }
+        Ok(tx)
+    };
+
+    // Without a template, --unique or --memo, build the transaction once
+    // (reused for all sends; a durable nonce keeps it valid however long the
+    // run takes, otherwise it is re-signed before the blockhash expires)
+    let mut tx = signing
+        .sign(build(0, signing.signer.pubkey(), fee)?)
+        .await?;
+    let mut tx_bytes = encode(&tx)?;
+    let mut signed_at = Instant::now();
+    let spam_started = Instant::now();
+    let error_event =
+        |index: Option<u64>, slot: u64, leader: Option<SocketAddr>, error: String| Event::Error {
+            command: "spam",
+            index,
+            slot: Some(slot),
+            leader,
+            signature: None,
+            error,
+        };
 
     // Lock onto current leader and get connection handle
     let slot = cartographer.get_known_slot();
-    let target = cartographer
-        .get_target(slot)
-        .await
-        .ok_or(anyhow::anyhow!("No leader found"))?;
+    let Some(mut target) = cartographer.get_target(slot).await else {
+        if let Some(monitor) = slo {
+            monitor.record(SendOutcome::Failed(FailureStage::NoLeader));
+        }
+        output.emit(error_event(None, slot, None, "No leader found".into()));
+        return Err(anyhow::anyhow!("No leader found"));
+    };
+    let record = |addr: SocketAddr, outcome: SendOutcome| {
+        if let Some(monitor) = slo {
+            monitor.record_to(addr, outcome);
+        }
+    };
 
+    // With --keypair-dir each identity handshakes on its own engine, on its
+    // first send to a leader
+    let connect = |target: SocketAddr| async move {
+        match identities {
+            Some(_) => Ok(Vec::new()),
+            None => engine.get_connection_pool(target).await,
+        }
+    };
     info!("Target Locked: {}", target);
-    let connection = engine.get_connection_handle(target).await?; // Handshake once
-    info!("Pipe Open. Firing {} rounds.", count);
+    let mut pool = match connect(target).await {
+        Ok(pool) => pool, // Handshake once (per pooled connection)
+        Err(e) => {
+            engine.record_send_result(target, Some(&e));
+            record(target, SendOutcome::Failed(FailureStage::Connect));
+            output.emit(error_event(None, slot, Some(target), e.to_string()));
+            return Err(e.into());
+        }
+    };
+    match identities {
+        Some(identities) => info!(
+            "Pipe Open ({} identities). Firing {} rounds.",
+            identities.len(),
+            count
+        ),
+        None => info!(
+            "Pipe Open ({} connection(s)). Firing {} rounds.",
+            pool.len(),
+            count
+        ),
+    }
+    if let Some(limiter) = limiter {
+        info!(
+            "Rate limited to {:.0} tx/s (burst {})",
+            limiter.rate(),
+            limiter.burst()
+        );
+    }
 
     // Sequential fire: send transactions one at a time to prevent UDP packet fragmentation
     // Each transaction completes as an atomic packet before the next starts
     let mut success_count: u64 = 0;
     let mut fail_count: u64 = 0;
+    let mut last_slot = slot;
+    // Last slot of the window being sent to (None = re-resolve on every slot)
+    let mut window_end = None;
+    let mut interrupted = false;
+    // Rounds sharing a signature are tracked once (on their first send)
+    let mut tracked = None;
+    let mut outcomes = TxOutcomes::new();
     for i in 0..count {
-        match connection.open_uni().await {
-            Ok(mut stream) => {
-                if let Err(e) = stream.write_all(&tx_bytes).await {
-                    warn!("Stream write failed (tx {}): {}", i, e);
-                    fail_count += 1;
-                    continue;
+        // Stop between sends: the previous stream is already finished
+        if shutdown.is_cancelled() {
+            interrupted = true;
+            break;
+        }
+        // Window pacing: this round's share of the window time left (none
+        // once the estimate has run out, until the next window starts)
+        if pace == Pace::Window && i > 0 {
+            if let Some(remaining) = cartographer.remaining_in_window().await {
+                let rounds_left = u32::try_from(count - i).unwrap_or(u32::MAX);
+                tokio::select! {
+                    _ = shutdown.cancelled() => {
+                        interrupted = true;
+                        break;
+                    }
+                    _ = tokio::time::sleep(remaining / rounds_left) => {}
+                }
+            }
+        }
+        // Follow leader rotation: re-resolve only when the slot advances
+        // past the window of the leader being sent to
+        let current_slot = cartographer.get_known_slot();
+        if follow_leaders
+            && current_slot != last_slot
+            && window_end.is_none_or(|end| current_slot > end)
+        {
+            last_slot = current_slot;
+            window_end = None;
+            if let Some(next) = cartographer.get_target(current_slot).await {
+                if next != target {
+                    match connect(next).await {
+                        Ok(next_pool) => {
+                            info!(
+                                "Leader rotated at slot {}: {} -> {}",
+                                current_slot, target, next
+                            );
+                            target = next;
+                            pool = next_pool;
+                        }
+                        Err(e) => {
+                            warn!("Retarget to {} failed: {}. Staying on {}", next, e, target);
+                            engine.record_send_result(next, Some(&e));
+                            record(next, SendOutcome::Failed(FailureStage::Connect));
+                        }
+                    }
+                }
+                if next == target {
+                    window_end = cartographer
+                        .get_leader_window(current_slot)
+                        .await
+                        .map(|window| window.end_slot);
+                }
+            }
+        }
+
+        // Sign with this round's identity (re-signed whenever it changes) and
+        // send over its own QUIC identity's connections
+        let identity = identities.map(|pool| pool.pick(i, target));
+        let round_signing = match identity {
+            Some(identity) => Signing {
+                signer: &identity.signer,
+                ..*signing
+            },
+            None => *signing,
+        };
+        let payer = round_signing.signer.pubkey();
+        let payer_changed = tx.message.account_keys.first() != Some(&payer);
+        let per_round = template.is_some() || memo_tag.is_some() || unique.is_some();
+        let reloaded_fee = current_fee();
+        if (per_round && i > 0) || payer_changed {
+            fee = reloaded_fee;
+            tx = round_signing.sign(build(i, payer, fee)?).await?;
+            tx_bytes = encode(&tx)?;
+            signed_at = Instant::now();
+        } else if reloaded_fee != fee {
+            fee = reloaded_fee;
+            tx = round_signing.sign(build(i, payer, fee)?).await?;
+            tx_bytes = encode(&tx)?;
+            signed_at = Instant::now();
+            info!("Re-signed at the reloaded priority fee {} (tx {})", fee, i);
+        } else if signing.nonce.is_none() && signed_at.elapsed() >= SPAM_RESIGN_AFTER {
+            tx = round_signing.sign(build(i, payer, fee)?).await?;
+            tx_bytes = encode(&tx)?;
+            signed_at = Instant::now();
+            info!("Re-signed with fresh blockhash (tx {})", i);
+        }
+
+        if let Some(limiter) = limiter {
+            limiter.acquire_with(priority).await;
+        }
+        if dry_run {
+            report_dry_run(
+                "spam",
+                Some(i),
+                current_slot,
+                target,
+                &tx,
+                &tx_bytes,
+                output,
+            );
+            success_count += 1;
+            continue;
+        }
+
+        // Round-robin streams across the pool (each connection has its own
+        // stream grant). Every stream shares the one buffer: no copy per send
+        let started = Instant::now();
+        let sent = match identity {
+            Some(identity) => send_as(&identity.engine, target, i, priority, &tx_bytes).await,
+            None => {
+                let connection = &pool[i as usize % pool.len()];
+                engine
+                    .write_stream(target, connection, &mut [tx_bytes.clone()], priority)
+                    .await
+            }
+        }
+        .map_err(|e| e.with_slot(current_slot));
+        engine.record_send_result(target, sent.as_ref().err());
+        journal_send(
+            engine,
+            "spam",
+            &tx.signatures[0],
+            &tx_bytes,
+            current_slot,
+            target,
+            sent.as_ref().err(),
+        );
+        let stage = sent.as_ref().map_err(FailureStage::from_send_error);
+        outcomes.record(tx.signatures[0], target, current_slot, stage.copied());
+        match sent {
+            Ok(()) => {
+                success_count += 1;
+                output.emit(Event::Sent {
+                    command: "spam",
+                    index: Some(i),
+                    slot: current_slot,
+                    leader: target,
+                    signature: tx.signatures[0].to_string(),
+                    latency_us: output::micros(started.elapsed()),
+                });
+                if let (Some(tracker), false) = (&tracker, tracked == Some(tx.signatures[0])) {
+                    tracked = Some(tx.signatures[0]);
+                    tracker.track(InFlight {
+                        signature: tx.signatures[0],
+                        sent_at: started,
+                        slot: current_slot,
+                        leader: target,
+                    });
                 }
-                if let Err(e) = stream.finish() {
-                    warn!("Stream finish failed (tx {}): {}", i, e);
-                    fail_count += 1;
-                    continue;
+            }
+            Err(e) => {
+                warn!("{} (tx {})", e, i);
+                fail_count += 1;
+                let error = e.to_string();
+                output.emit(error_event(Some(i), current_slot, Some(target), error));
+            }
+        }
+    }
+    let elapsed = spam_started.elapsed();
+    if !output.is_json() {
+        println!(
+            "{}. Sent: {}, Failed: {} of {} in {:.1}s",
+            if interrupted {
+                "Interrupted"
+            } else if dry_run {
+                "Dry Run Complete"
+            } else {
+                "Firing Complete"
+            },
+            success_count,
+            fail_count,
+            count,
+            elapsed.as_secs_f64()
+        );
+    }
+    output.emit(Event::Summary {
+        command: "spam",
+        sent: success_count,
+        failed: fail_count,
+        elapsed_ms: elapsed.as_millis() as u64,
+    });
+    if let Some(limits) = engine.peer_limits(&target) {
+        match limits.max_concurrent_uni_streams {
+            Some(max) => info!("QoS: Leader grants {} concurrent streams", max),
+            None => debug!(
+                "QoS: Stream grant not saturated ({} opened)",
+                limits.streams_opened
+            ),
+        }
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+    // One outcome per transaction (rounds sharing a signature land at most once)
+    if let Some(monitor) = slo {
+        report_outcomes(outcomes, cartographer, landing, monitor, output).await;
+    }
+    if let Some(tracker) = tracker {
+        let mut landings = tracker.finish().await?;
+        if output.is_json() {
+            output.emit(Event::Scoreboard {
+                command: "spam",
+                sent: landings.sent(),
+                landed: landings.landed,
+                failed: landings.failed,
+                never_seen: landings.never_seen(),
+                time_to_land_ms: landings.latency_ms.percentiles(),
+                leaders: scoreboard::leader_scores(&landings),
+            });
+        } else {
+            scoreboard::print(&mut landings);
+        }
+        let rpc = cartographer.rpc_client();
+        let advice = match landings.sample {
+            Some(ref signature) => advisor::report(&rpc, signature, output).await,
+            None => None,
+        };
+        if let (Some(template), Some(profiles)) = (template, profiles) {
+            let key = template.fingerprint();
+            match advice {
+                Some(ref advice) => profiles.record(key, advice),
+                // Nothing landed cleanly but some failed: maybe out of compute
+                None if advised_limit.is_some() && landings.landed == 0 && landings.failed > 0 => {
+                    warn!(
+                        "Compute: Every included transaction failed at the learned {} CU limit. Forgetting it.",
+                        compute_unit_limit
+                    );
+                    profiles.forget(key);
+                }
+                None => return Ok(()),
+            }
+            if let Err(e) = profiles.save() {
+                warn!(
+                    "Compute: Could not save {}: {}",
+                    profiles.path().display(),
+                    e
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// What `bundle` builds: `count` transfers, the last one tipped
+struct BundleRequest {
+    recipient: Pubkey,
+    count: usize,
+    priority_fee: u64,
+    tip: u64,
+    /// Class of the direct sends when the leader does not run Jito
+    priority: Priority,
+}
+
+/// Send a Jito bundle if the current leader runs Jito, otherwise send the
+/// same transfers (untipped) directly to the leader over QUIC
+async fn send_bundle(
+    cartographer: &Cartographer,
+    engine: &QuicEngine,
+    signing: &Signing<'_>,
+    block_engine: &str,
+    request: BundleRequest,
+    config: &Config,
+    output: Output,
+) -> anyhow::Result<()> {
+    if request.count == 0 || request.count > jito::MAX_BUNDLE_TRANSACTIONS {
+        anyhow::bail!(
+            "--count must be between 1 and {}",
+            jito::MAX_BUNDLE_TRANSACTIONS
+        );
+    }
+    if request.tip < jito::MIN_TIP_LAMPORTS {
+        anyhow::bail!("--tip must be at least {} lamports", jito::MIN_TIP_LAMPORTS);
+    }
+    if signing.nonce.is_some() && request.count > 1 {
+        // The first transaction advances the nonce, invalidating the rest
+        anyhow::bail!("A durable nonce bundle can only hold one transaction");
+    }
+
+    let slot = cartographer.get_known_slot();
+    let (Some(target), Some(leader)) = (
+        cartographer.get_target(slot).await,
+        cartographer.get_leader(slot).await,
+    ) else {
+        anyhow::bail!("No leader found for slot {}", slot);
+    };
+
+    let mut client = JitoClient::connect(block_engine).await?;
+    let jito_leader = match client.connected_leaders().await {
+        Ok(leaders) => leaders.contains(&leader),
+        Err(e) => {
+            warn!(
+                "Jito: Connected leaders unavailable ({}). Sending directly.",
+                e
+            );
+            false
+        }
+    };
+    let tip_account = if jito_leader {
+        let accounts = client.tip_accounts().await?;
+        // Spread tips across accounts (each is a write lock)
+        let account = accounts.get(slot as usize % accounts.len().max(1)).copied();
+        Some(account.ok_or_else(|| anyhow::anyhow!("Block engine returned no tip accounts"))?)
+    } else {
+        None
+    };
+
+    // Distinct amounts keep signatures unique within the bundle
+    let payer = signing.signer.pubkey();
+    let mut txs = Vec::with_capacity(request.count);
+    for i in 0..request.count {
+        let mut tx = signing
+            .builder()
+            .compute_unit_limit(config.default_compute_unit_limit)
+            .compute_unit_price(request.priority_fee)
+            .transfer(&request.recipient, i as u64 + 1);
+        if let (Some(account), true) = (tip_account, i + 1 == request.count) {
+            tx = tx.instruction(jito::tip_instruction(&payer, &account, request.tip));
+        }
+        txs.push(signing.sign(tx).await?);
+    }
+    let signatures: Vec<String> = txs.iter().map(|tx| tx.signatures[0].to_string()).collect();
+    let wire = txs
+        .iter()
+        .map(|tx| encode(tx).map(Vec::from))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if tip_account.is_some() {
+        info!(
+            "Leader {} runs Jito. Sending bundle of {} (tip {} lamports)...",
+            leader, request.count, request.tip
+        );
+        let uuid = client.send_bundle(jito::build_bundle(&wire)?).await?;
+        info!("Bundle accepted: {}", uuid);
+        for signature in &signatures {
+            info!("  Sig: {}", signature);
+        }
+        output.emit(Event::Bundle {
+            uuid,
+            slot,
+            leader: leader.to_string(),
+            signatures,
+            tip_lamports: request.tip,
+        });
+        return Ok(());
+    }
+
+    info!(
+        "Leader {} is not Jito-enabled. Sending {} transaction(s) directly to {}...",
+        leader, request.count, target
+    );
+    for (i, (bytes, signature)) in wire.into_iter().zip(signatures).enumerate() {
+        let started = Instant::now();
+        let bytes = Bytes::from(bytes);
+        let sent = engine
+            .send_transaction_with(target, bytes.clone(), request.priority)
+            .await;
+        let sig = &txs[i].signatures[0];
+        let error = sent.as_ref().err();
+        journal_send(engine, "bundle", sig, &bytes, slot, target, error);
+        if let Err(e) = sent {
+            output.emit(Event::Error {
+                command: "bundle",
+                index: Some(i as u64),
+                slot: Some(slot),
+                leader: Some(target),
+                signature: Some(signature),
+                error: e.to_string(),
+            });
+            return Err(e.into());
+        }
+        info!("Sent! Sig: {}", signature);
+        output.emit(Event::Sent {
+            command: "bundle",
+            index: Some(i as u64),
+            slot,
+            leader: target,
+            signature,
+            latency_us: output::micros(started.elapsed()),
+        });
+    }
+    Ok(())
+}
+
+/// Journal a send attempt (no-op when `JOURNAL_FILE` is off)
+pub(crate) fn journal_send(
+    engine: &QuicEngine,
+    command: &str,
+    signature: &Signature,
+    tx: &[u8],
+    slot: u64,
+    target: SocketAddr,
+    error: Option<&ScramjetError>,
+) {
+    if let Some(journal) = engine.journal() {
+        journal.record_send(command, signature, tx, slot, target, error);
+    }
+}
+
+/// Await and record a run's per-transaction SLO outcomes, emitting each
+async fn report_outcomes(
+    outcomes: TxOutcomes,
+    cartographer: &Cartographer,
+    landing: Option<&LandingFeed>,
+    monitor: &SloMonitor,
+    output: Output,
+) {
+    if outcomes.is_empty() {
+        return;
+    }
+    let outcomes = outcomes.finish(cartographer, landing, monitor).await;
+    let landed = outcomes
+        .iter()
+        .filter(|(_, _, outcome)| matches!(outcome, SendOutcome::Landed { .. }))
+        .count();
+    info!(
+        "Outcome: {} of {} transaction(s) landed",
+        landed,
+        outcomes.len()
+    );
+    for (signature, target, outcome) in outcomes {
+        debug!("Outcome {}: {:?}", signature, outcome);
+        output.emit(Event::outcome(signature.to_string(), target, outcome));
+    }
+}
+
+/// Encoded transaction lines from stdin (`None` / `-`), a file, or every file
+/// in a directory (sorted by name). Blank lines and `#` comments are skipped.
+fn read_raw_input(input: Option<&Path>) -> anyhow::Result<Vec<String>> {
+    let mut text = String::new();
+    match input {
+        None => {
+            std::io::stdin().read_to_string(&mut text)?;
+        }
+        Some(path) if path == Path::new("-") => {
+            std::io::stdin().read_to_string(&mut text)?;
+        }
+        Some(path) if path.is_dir() => {
+            let mut files = std::fs::read_dir(path)?
+                .map(|entry| entry.map(|e| e.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            files.retain(|p| p.is_file());
+            files.sort();
+            for file in files {
+                text.push_str(
+                    &std::fs::read_to_string(&file)
+                        .with_context(|| format!("Failed to read {:?}", file))?,
+                );
+                text.push('\n');
+            }
+        }
+        Some(path) => {
+            text = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {:?}", path))?;
+        }
+    }
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+/// Route each pre-signed transaction to the leader of the slot it is sent in.
+/// Prints one signature per sent transaction on stdout.
+async fn send_raw_transactions(
+    scramjet: &Scramjet,
+    lines: &[String],
+    encoding: RawEncoding,
+    priority: Priority,
+    output: Output,
+) -> anyhow::Result<()> {
+    info!("Sending {} raw transaction(s)...", lines.len());
+    let started = Instant::now();
+    let mut success_count: u64 = 0;
+    let mut fail_count: u64 = 0;
+    for (i, line) in lines.iter().enumerate() {
+        let error_event = |signature: Option<String>, error: String| Event::Error {
+            command: "send-raw",
+            index: Some(i as u64),
+            slot: None,
+            leader: None,
+            signature,
+            error,
+        };
+        let tx = match raw::parse_raw_transaction(line, encoding) {
+            Ok(tx) => tx.with_priority(priority),
+            Err(e) => {
+                warn!("Skipping tx {}: {}", i, e);
+                fail_count += 1;
+                output.emit(error_event(None, e.to_string()));
+                continue;
+            }
+        };
+        let signature = tx.signature;
+        let sent_at = Instant::now();
+        match scramjet.send_raw(tx).await {
+            Ok(receipt) => {
+                debug!(
+                    "Sent {} to {} (slot {})",
+                    signature, receipt.leader, receipt.slot
+                );
+                if output.is_json() {
+                    output.emit(Event::Sent {
+                        command: "send-raw",
+                        index: Some(i as u64),
+                        slot: receipt.slot,
+                        leader: receipt.leader,
+                        signature: signature.to_string(),
+                        latency_us: output::micros(sent_at.elapsed()),
+                    });
+                } else {
+                    println!("{}", signature);
                 }
                 success_count += 1;
             }
             Err(e) => {
-                warn!("Failed to open stream (tx {}): {}", i, e);
+                warn!("Send failed (tx {}): {}", signature, e);
                 fail_count += 1;
+                output.emit(error_event(Some(signature.to_string()), e.to_string()));
             }
         }
     }
-    info!("Firing Complete. Sent: {}, Failed: {}", success_count, fail_count);
+    info!(
+        "Send-raw complete. Sent: {}, Failed: {}",
+        success_count, fail_count
+    );
+    output.emit(Event::Summary {
+        command: "send-raw",
+        sent: success_count,
+        failed: fail_count,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    });
+    if fail_count > 0 {
+        anyhow::bail!("{} of {} transaction(s) failed", fail_count, lines.len());
+    }
     Ok(())
 }
@@ -0,0 +1,309 @@
+//! Machine-readable output (`--output json`): one JSON object per line on
+//! stdout, tagged by `event`. Logs still go to stderr, so stdout stays
+//! parseable.
+
+use clap::ValueEnum;
+use scramjet_net::histogram::Percentiles;
+use scramjet_net::journal::JournalEntry;
+use scramjet_net::slo::SendOutcome;
+use scramjet_net::stats::PathStats;
+use scramjet_net::validator_info::ValidatorInfo;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::confirm::Level;
+use crate::scoreboard::LeaderScore;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text and logs
+    #[default]
+    Text,
+    /// JSON lines on stdout
+    Json,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// Monitor tick
+    Slot {
+        slot: u64,
+        leader: Option<String>,
+        leader_addr: Option<SocketAddr>,
+        /// Leader's name, version and location, when known
+        #[serde(skip_serializing_if = "Option::is_none")]
+        validator: Option<ValidatorInfo>,
+        /// Smoothed RTT to the leader, once probed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rtt_ms: Option<f64>,
+        /// QUIC path of the connections to the leader, once sampled
+        #[serde(skip_serializing_if = "Option::is_none")]
+        path: Option<PathStats>,
+        slot_time_ms: u64,
+    },
+    /// Transaction written to a leader
+    Sent {
+        command: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        index: Option<u64>,
+        slot: u64,
+        leader: SocketAddr,
+        signature: String,
+        latency_us: u64,
+    },
+    /// Transaction a `--dry-run` built and would have written to a leader
+    DryRun {
+        command: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        index: Option<u64>,
+        slot: u64,
+        leader: SocketAddr,
+        signature: String,
+        payer: String,
+        size: usize,
+        /// Base64 wire bytes
+        transaction: String,
+    },
+    /// Transaction (or the whole command) failed before reaching a leader
+    Error {
+        command: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        index: Option<u64>,
+        slot: Option<u64>,
+        leader: Option<SocketAddr>,
+        signature: Option<String>,
+        error: String,
+    },
+    /// Landing outcome (only tracked when `LANDING_SLOS` is configured)
+    Outcome {
+        signature: String,
+        leader: SocketAddr,
+        landed_slots: Option<u64>,
+        failure: Option<String>,
+    },
+    /// Bundle accepted by the Jito block engine
+    Bundle {
+        uuid: String,
+        slot: u64,
+        leader: String,
+        signatures: Vec<String>,
+        tip_lamports: u64,
+    },
+    /// `bench` results (histogram summaries; None without samples)
+    Bench {
+        duration_ms: u64,
+        target_rate: u32,
+        achieved_rate: f64,
+        sent: u64,
+        failed: u64,
+        no_leader: u64,
+        landed: u64,
+        expired: u64,
+        unconfirmed: u64,
+        handshake_us: Option<Percentiles>,
+        write_us: Option<Percentiles>,
+        /// Sends that went as QUIC datagrams (only with `QUIC_DATAGRAMS`)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        datagrams: Option<u64>,
+        landing_ms: Option<Percentiles>,
+        landing_slots: Option<Percentiles>,
+    },
+    /// Landing scoreboard at the end of `spam` (distinct signatures; None
+    /// without landings)
+    Scoreboard {
+        command: &'static str,
+        sent: u64,
+        landed: u64,
+        failed: u64,
+        never_seen: u64,
+        time_to_land_ms: Option<Percentiles>,
+        leaders: Vec<LeaderScore>,
+    },
+    /// Airdrop confirmed (balance in lamports, read afterwards)
+    Airdrop {
+        recipient: String,
+        signature: String,
+        lamports: u64,
+        balance: u64,
+    },
+    /// `keygen`: keypair file written (attempts > 1 when ground for a prefix)
+    Keygen {
+        pubkey: String,
+        path: String,
+        attempts: u64,
+    },
+    /// `balance`: lamports and the transactions they cover at `fee` each
+    Balance {
+        pubkey: String,
+        lamports: u64,
+        fee: u64,
+        transactions: u64,
+    },
+    /// `account`: the account as RPC returned it (confirmed)
+    Account {
+        pubkey: String,
+        lamports: u64,
+        owner: String,
+        executable: bool,
+        data_len: usize,
+        rent_epoch: u64,
+        rent_exempt_minimum: u64,
+    },
+    /// Final status `confirm` saw for a signature (times from its first poll)
+    Confirm {
+        signature: String,
+        slot: Option<u64>,
+        /// Highest commitment level reached (None: not found)
+        status: Option<Level>,
+        /// On-chain error, if the transaction failed
+        error: Option<String>,
+        processed_ms: Option<u64>,
+        confirmed_ms: Option<u64>,
+        finalized_ms: Option<u64>,
+    },
+    /// Compute units a landed transaction requested against what it used
+    ComputeAdvice {
+        signature: String,
+        requested: u32,
+        consumed: u64,
+        /// Consumed units plus headroom, rounded up
+        suggested: u32,
+    },
+    /// Send journal entry (`history`)
+    Journal {
+        #[serde(flatten)]
+        entry: JournalEntry,
+    },
+    /// What `replay` does with a journaled signature that never landed
+    Replay {
+        signature: String,
+        /// `resend`, `resign` or `skip`
+        action: &'static str,
+        /// Signature of the re-signed transaction
+        #[serde(skip_serializing_if = "Option::is_none")]
+        replacement: Option<String>,
+        /// Why it was skipped
+        #[serde(skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+    },
+    /// One `doctor` check
+    Doctor {
+        check: &'static str,
+        /// `pass`, `warn` or `fail`
+        status: &'static str,
+        detail: String,
+    },
+    /// End of a multi-transaction command
+    Summary {
+        command: &'static str,
+        sent: u64,
+        failed: u64,
+        elapsed_ms: u64,
+    },
+}
+
+impl Event {
+    pub fn outcome(signature: String, leader: SocketAddr, outcome: SendOutcome) -> Self {
+        let (landed_slots, failure) = match outcome {
+            SendOutcome::Landed { slots } => (Some(slots), None),
+            SendOutcome::Failed(stage) => (None, Some(stage.to_string())),
+        };
+        Event::Outcome {
+            signature,
+            leader,
+            landed_slots,
+            failure,
+        }
+    }
+}
+
+/// Where command results go: text (caller prints/logs) or JSON lines
+#[derive(Debug, Clone, Copy)]
+pub struct Output {
+    format: OutputFormat,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format }
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::Json
+    }
+
+    /// Print `event` as a JSON line (no-op in text mode)
+    pub fn emit(&self, event: Event) {
+        if !self.is_json() {
+            return;
+        }
+        match serde_json::to_string(&event) {
+            Ok(line) => println!("{}", line),
+            Err(e) => log::error!("Failed to serialize output event: {}", e),
+        }
+    }
+}
+
+pub fn micros(elapsed: Duration) -> u64 {
+    elapsed.as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_net::slo::FailureStage;
+
+    #[test]
+    fn test_event_json_shape() {
+        let leader: SocketAddr = "1.2.3.4:8009".parse().unwrap();
+        let sent = serde_json::to_value(Event::Sent {
+            command: "spam",
+            index: Some(3),
+            slot: 42,
+            leader,
+            signature: "sig".into(),
+            latency_us: 120,
+        })
+        .unwrap();
+        assert_eq!(sent["event"], "sent");
+        assert_eq!(sent["index"], 3);
+        assert_eq!(sent["leader"], "1.2.3.4:8009");
+
+        let fire = serde_json::to_value(Event::Sent {
+            command: "fire",
+            index: None,
+            slot: 42,
+            leader,
+            signature: "sig".into(),
+            latency_us: 120,
+        })
+        .unwrap();
+        assert!(fire.get("index").is_none());
+
+        let dry_run = serde_json::to_value(Event::DryRun {
+            command: "fire",
+            index: None,
+            slot: 42,
+            leader,
+            signature: "sig".into(),
+            payer: "payer".into(),
+            size: 215,
+            transaction: "AQ==".into(),
+        })
+        .unwrap();
+        assert_eq!(dry_run["event"], "dry_run");
+        assert_eq!(dry_run["size"], 215);
+
+        let outcome = serde_json::to_value(Event::outcome(
+            "sig".into(),
+            leader,
+            SendOutcome::Failed(FailureStage::Expired),
+        ))
+        .unwrap();
+        assert_eq!(outcome["event"], "outcome");
+        assert_eq!(outcome["failure"], "expired");
+        assert!(outcome["landed_slots"].is_null());
+    }
+}
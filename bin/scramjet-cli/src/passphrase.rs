@@ -0,0 +1,32 @@
+//! Passphrase input for encrypted keypairs: `KEYPAIR_PASSPHRASE` when set,
+//! otherwise a prompt on the terminal with echo turned off.
+
+use scramjet_common::keystore::PASSPHRASE_ENV;
+use std::io::IsTerminal;
+
+/// Passphrase from the environment or the terminal (never empty); `confirm`
+/// asks twice (for encrypting)
+pub fn read(prompt: &str, confirm: bool) -> anyhow::Result<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        anyhow::ensure!(
+            !passphrase.is_empty(),
+            "{} is set but empty",
+            PASSPHRASE_ENV
+        );
+        return Ok(passphrase);
+    }
+    anyhow::ensure!(
+        std::io::stdin().is_terminal(),
+        "No terminal to prompt for the keypair passphrase; set {}",
+        PASSPHRASE_ENV
+    );
+    let passphrase = rpassword::prompt_password(prompt)?;
+    anyhow::ensure!(!passphrase.is_empty(), "Passphrase must not be empty");
+    if confirm {
+        anyhow::ensure!(
+            rpassword::prompt_password("Confirm passphrase: ")? == passphrase,
+            "Passphrases do not match"
+        );
+    }
+    Ok(passphrase)
+}
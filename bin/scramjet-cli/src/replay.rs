@@ -0,0 +1,419 @@
+//! `scramjet replay`: resubmit what the send journal shows never landed, e.g.
+//! after the daemon crashed mid-run. Every candidate's status is checked on
+//! chain first, and nothing already landed is sent again.
+//!
+//! A transaction whose blockhash is still valid (or that advances a durable
+//! nonce) is resent as-is: one signature can only land once. One whose
+//! blockhash expired can no longer land, so when the identity is its only
+//! signer (fire, spam and template transactions) it is re-signed against a
+//! fresh blockhash. The original is journaled as `replaced` before the new
+//! one is sent, so a later replay never re-signs it twice.
+
+use base64::Engine;
+use bytes::Bytes;
+use log::{info, warn};
+use scramjet_net::cartographer::Cartographer;
+use scramjet_net::engine::QuicEngine;
+use scramjet_net::journal::{Journal, JournalEntry, JournalResult};
+use scramjet_net::priority::Priority;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::output::{Event, Output};
+use crate::scoreboard::{InFlight, Tracker, STATUS_BATCH};
+use crate::{encode, Signing};
+
+pub struct ReplayArgs {
+    /// Only signatures journaled at or after this Unix time (ms)
+    pub since: Option<u64>,
+    pub priority: Priority,
+    /// Decide and report, but send nothing
+    pub dry_run: bool,
+}
+
+/// A journaled transaction with no landing recorded
+#[derive(Debug)]
+pub struct Candidate {
+    pub signature: Signature,
+    pub tx: VersionedTransaction,
+    /// Its last journal entry
+    pub last: JournalResult,
+}
+
+/// What replay does with a candidate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Plan {
+    /// Send the journaled transaction again
+    Resend,
+    /// Re-sign it against a fresh blockhash and send that
+    Resign,
+    Skip(String),
+}
+
+impl Plan {
+    fn action(&self) -> &'static str {
+        match self {
+            Plan::Resend => "resend",
+            Plan::Resign => "resign",
+            Plan::Skip(_) => "skip",
+        }
+    }
+}
+
+/// Signatures last journaled at or after `since` with nothing recorded about
+/// them landing, failing on chain or being replaced, in first-sent order.
+/// Also returns how many of those had no (decodable) transaction journaled.
+pub fn candidates(entries: &[JournalEntry], since: Option<u64>) -> (Vec<Candidate>, usize) {
+    struct Trail<'a> {
+        signature: &'a str,
+        tx: Option<&'a str>,
+        latest_ms: u64,
+        last: JournalResult,
+        settled: bool,
+    }
+    let mut trails: Vec<Trail> = Vec::new();
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        let i = *index.entry(&entry.signature).or_insert_with(|| {
+            trails.push(Trail {
+                signature: &entry.signature,
+                tx: None,
+                latest_ms: 0,
+                last: entry.result,
+                settled: false,
+            });
+            trails.len() - 1
+        });
+        let trail = &mut trails[i];
+        trail.tx = trail.tx.or(entry.tx.as_deref());
+        trail.latest_ms = trail.latest_ms.max(entry.at_ms);
+        trail.last = entry.result;
+        trail.settled |= matches!(
+            entry.result,
+            JournalResult::Landed | JournalResult::Failed | JournalResult::Replaced
+        );
+    }
+
+    let mut missing = 0;
+    let mut candidates = Vec::new();
+    for trail in trails {
+        if trail.settled || since.is_some_and(|since| trail.latest_ms < since) {
+            continue;
+        }
+        let decoded = trail.tx.and_then(|tx| {
+            let bytes = base64::engine::general_purpose::STANDARD.decode(tx).ok()?;
+            bincode::deserialize::<VersionedTransaction>(&bytes).ok()
+        });
+        match (Signature::from_str(trail.signature), decoded) {
+            (Ok(signature), Some(tx)) => candidates.push(Candidate {
+                signature,
+                tx,
+                last: trail.last,
+            }),
+            _ => missing += 1,
+        }
+    }
+    (candidates, missing)
+}
+
+/// Decide for `tx`, given the slot the chain has it in (if any) and whether
+/// its blockhash can still land
+pub fn plan(
+    tx: &VersionedTransaction,
+    landed: Option<u64>,
+    blockhash_valid: bool,
+    signer: &Pubkey,
+) -> Plan {
+    if let Some(slot) = landed {
+        return Plan::Skip(format!("already in slot {}", slot));
+    }
+    // A nonce that has since advanced just makes the resend fail on chain
+    if blockhash_valid || tx.uses_durable_nonce() {
+        return Plan::Resend;
+    }
+    match tx.message {
+        VersionedMessage::Legacy(ref message)
+            if message.header.num_required_signatures == 1
+                && message.account_keys.first() == Some(signer) =>
+        {
+            Plan::Resign
+        }
+        _ => Plan::Skip(format!(
+            "blockhash expired, and only legacy transactions signed by {} alone can be re-signed",
+            signer
+        )),
+    }
+}
+
+pub async fn run(
+    cartographer: Arc<Cartographer>,
+    engine: &QuicEngine,
+    signing: &Signing<'_>,
+    journal: Arc<Journal>,
+    entries: &[JournalEntry],
+    args: ReplayArgs,
+    output: Output,
+) -> anyhow::Result<()> {
+    let (candidates, missing) = candidates(entries, args.since);
+    if missing > 0 {
+        warn!(
+            "Replay: {} unlanded signature(s) have no transaction in the journal \
+             (journaled before it stored them, or rotated away)",
+            missing
+        );
+    }
+    if candidates.is_empty() {
+        info!("Replay: Nothing to replay in {}", journal.path().display());
+        output.emit(Event::Summary {
+            command: "replay",
+            sent: 0,
+            failed: 0,
+            elapsed_ms: 0,
+        });
+        return Ok(());
+    }
+    info!("Replay: {} transaction(s) never landed", candidates.len());
+
+    // Blockhashes before statuses: a signature the chain does not have,
+    // whose blockhash had already expired, can never land
+    let rpc = cartographer.rpc_client();
+    let mut valid: HashMap<Hash, bool> = HashMap::new();
+    for candidate in &candidates {
+        let blockhash = *candidate.tx.message.recent_blockhash();
+        if let Entry::Vacant(unchecked) = valid.entry(blockhash) {
+            let is_valid = rpc
+                .is_blockhash_valid(&blockhash, CommitmentConfig::processed())
+                .await?;
+            unchecked.insert(is_valid);
+        }
+    }
+    let mut landed = Vec::with_capacity(candidates.len());
+    for batch in candidates.chunks(STATUS_BATCH) {
+        let signatures: Vec<Signature> = batch.iter().map(|c| c.signature).collect();
+        let statuses = rpc.get_signature_statuses_with_history(&signatures).await?;
+        landed.extend(statuses.value.into_iter().map(|s| s.map(|s| s.slot)));
+    }
+
+    let tracker = (!args.dry_run)
+        .then(|| Tracker::spawn(cartographer.clone(), Some(journal.clone()), "replay"));
+    let started = Instant::now();
+    let (mut sent, mut failed) = (0u64, 0u64);
+    let signer = signing.signer.pubkey();
+    for (i, (candidate, landed)) in candidates.iter().zip(landed).enumerate() {
+        let blockhash = candidate.tx.message.recent_blockhash();
+        let plan = plan(&candidate.tx, landed, valid[blockhash], &signer);
+        let (signature, bytes) = match plan {
+            Plan::Resend => {
+                let bytes = Bytes::from(bincode::serialize(&candidate.tx)?);
+                (candidate.signature, bytes)
+            }
+            Plan::Resign if !args.dry_run => {
+                let Some(legacy) = candidate.tx.clone().into_legacy_transaction() else {
+                    unreachable!("only legacy transactions are re-signed");
+                };
+                let tx = signing.resign(legacy.message).await?;
+                let bytes = encode(&tx)?;
+                (tx.signatures[0], bytes)
+            }
+            Plan::Resign | Plan::Skip(_) => (candidate.signature, Bytes::new()),
+        };
+        let replacement = (signature != candidate.signature).then(|| signature.to_string());
+        let reason = match plan {
+            Plan::Skip(ref reason) => Some(reason.clone()),
+            _ => None,
+        };
+        if output.is_json() {
+            output.emit(Event::Replay {
+                signature: candidate.signature.to_string(),
+                action: plan.action(),
+                replacement: replacement.clone(),
+                reason: reason.clone(),
+            });
+        } else {
+            let detail = match (&replacement, &reason) {
+                (Some(replacement), _) => format!(" as {}", replacement),
+                (_, Some(reason)) => format!(": {}", reason),
+                _ => String::new(),
+            };
+            println!(
+                "{} ({}): {}{}",
+                candidate.signature,
+                candidate.last.as_str(),
+                plan.action(),
+                detail
+            );
+        }
+        if args.dry_run || matches!(plan, Plan::Skip(_)) {
+            continue;
+        }
+
+        let slot = cartographer.get_known_slot();
+        let Some(target) = cartographer.get_target(slot).await else {
+            failed += 1;
+            output.emit(Event::Error {
+                command: "replay",
+                index: Some(i as u64),
+                slot: Some(slot),
+                leader: None,
+                signature: Some(signature.to_string()),
+                error: format!("No leader found for slot {}", slot),
+            });
+            continue;
+        };
+        if plan == Plan::Resign {
+            journal.record_replaced("replay", &candidate.signature, &signature);
+        }
+        let sent_at = Instant::now();
+        let result = engine
+            .send_transaction_with(target, bytes.clone(), args.priority)
+            .await
+            .map_err(|e| e.with_slot(slot));
+        let error = result.as_ref().err();
+        journal.record_send("replay", &signature, &bytes, slot, target, error);
+        match result {
+            Ok(()) => {
+                sent += 1;
+                output.emit(Event::Sent {
+                    command: "replay",
+                    index: Some(i as u64),
+                    slot,
+                    leader: target,
+                    signature: signature.to_string(),
+                    latency_us: crate::output::micros(sent_at.elapsed()),
+                });
+                if let Some(ref tracker) = tracker {
+                    tracker.track(InFlight {
+                        signature,
+                        sent_at,
+                        slot,
+                        leader: target,
+                    });
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                warn!("Replay: Send of {} failed: {}", signature, e);
+                output.emit(Event::Error {
+                    command: "replay",
+                    index: Some(i as u64),
+                    slot: Some(slot),
+                    leader: Some(target),
+                    signature: Some(signature.to_string()),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+    output.emit(Event::Summary {
+        command: "replay",
+        sent,
+        failed,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    });
+
+    if let Some(tracker) = tracker {
+        let mut landings = tracker.finish().await?;
+        if output.is_json() {
+            output.emit(Event::Scoreboard {
+                command: "replay",
+                sent: landings.sent(),
+                landed: landings.landed,
+                failed: landings.failed,
+                never_seen: landings.never_seen(),
+                time_to_land_ms: landings.latency_ms.percentiles(),
+                leaders: crate::scoreboard::leader_scores(&landings),
+            });
+        } else {
+            crate::scoreboard::print(&mut landings);
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} replayed transaction(s) failed to send", failed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_common::tx_builder::TxBuilder;
+    use solana_sdk::signature::{Keypair, Signer};
+    use solana_sdk::transaction::Transaction;
+
+    fn entry(tx: &Transaction, result: JournalResult, at_ms: u64) -> JournalEntry {
+        let bytes = bincode::serialize(tx).unwrap();
+        JournalEntry {
+            at_ms,
+            signature: tx.signatures[0].to_string(),
+            slot: Some(100),
+            target: None,
+            source: "spam".into(),
+            result,
+            error: None,
+            tx: (result == JournalResult::Sent)
+                .then(|| base64::engine::general_purpose::STANDARD.encode(bytes)),
+            replaced_by: None,
+        }
+    }
+
+    #[test]
+    fn test_candidates_and_plan() {
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let transfer = |lamports| {
+            let message = TxBuilder::new(payer.pubkey())
+                .transfer(&recipient, lamports)
+                .legacy_message(Hash::new_unique());
+            Transaction::new(&[&payer], message, Hash::new_unique())
+        };
+        let (landed, expired, replaced, unknown, old) = (
+            transfer(1),
+            transfer(2),
+            transfer(3),
+            transfer(4),
+            transfer(5),
+        );
+        let mut entries = vec![
+            entry(&old, JournalResult::Sent, 500),
+            entry(&landed, JournalResult::Sent, 1_000),
+            entry(&expired, JournalResult::Sent, 1_100),
+            entry(&replaced, JournalResult::Sent, 1_200),
+            entry(&unknown, JournalResult::Sent, 1_300),
+            entry(&landed, JournalResult::Landed, 2_000),
+            entry(&expired, JournalResult::Expired, 2_100),
+            entry(&replaced, JournalResult::Replaced, 2_200),
+        ];
+        // Journaled before transactions were stored
+        entries[4].tx = None;
+
+        let (recent, missing) = candidates(&entries, Some(1_000));
+        assert_eq!(missing, 1);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].signature, expired.signatures[0]);
+        assert_eq!(recent[0].last, JournalResult::Expired);
+        assert_eq!(candidates(&entries, None).0.len(), 2);
+
+        let tx = &recent[0].tx;
+        let me = payer.pubkey();
+        assert_eq!(plan(tx, None, true, &me), Plan::Resend);
+        assert_eq!(plan(tx, None, false, &me), Plan::Resign);
+        assert_eq!(
+            plan(tx, Some(7), false, &me),
+            Plan::Skip("already in slot 7".into())
+        );
+        // Someone else's transaction cannot be re-signed
+        assert!(matches!(
+            plan(tx, None, false, &Pubkey::new_unique()),
+            Plan::Skip(_)
+        ));
+    }
+}
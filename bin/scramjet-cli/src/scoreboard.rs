@@ -0,0 +1,309 @@
+//! Landing scoreboard: follows sent signatures through RPC status polling
+//! until each one lands, fails on chain, or is never seen (its blockhash
+//! expired, or the drain timeout passed). Shared by `bench` and `spam`.
+//!
+//! Landing time is measured when a signature first shows up in polling, so
+//! its resolution is the poll interval (one slot). Each signature's outcome
+//! is also appended to the send journal, when it is on.
+
+use log::{info, warn};
+use scramjet_net::{
+    cartographer::Cartographer,
+    histogram::Histogram,
+    journal::{Journal, JournalResult},
+    slo::BLOCKHASH_EXPIRY_SLOTS,
+};
+use serde::Serialize;
+use solana_sdk::signature::Signature;
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// `getSignatureStatuses` accepts at most this many signatures per call
+pub(crate) const STATUS_BATCH: usize = 256;
+
+/// How long to keep polling for landings after the send phase ends
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Sent transaction awaiting confirmation
+#[derive(Debug, Clone, Copy)]
+pub struct InFlight {
+    pub signature: Signature,
+    pub sent_at: Instant,
+    pub slot: u64,
+    pub leader: SocketAddr,
+}
+
+/// Per-leader counts (`never_seen` covers expired and unconfirmed)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LeaderTally {
+    pub sent: u64,
+    pub landed: u64,
+    pub failed: u64,
+    pub never_seen: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Landings {
+    /// Time to land (successful or failed on chain)
+    pub latency_ms: Histogram,
+    pub slots: Histogram,
+    pub landed: u64,
+    /// Landed with a transaction error
+    pub failed: u64,
+    /// Blockhash expired without the signature showing up
+    pub expired: u64,
+    /// Still pending when the drain timeout passed
+    pub unconfirmed: u64,
+    pub leaders: BTreeMap<SocketAddr, LeaderTally>,
+    /// First signature seen landing without an error
+    pub sample: Option<Signature>,
+}
+
+impl Landings {
+    /// Signatures included in a block, failed or not
+    pub fn included(&self) -> u64 {
+        self.landed + self.failed
+    }
+
+    pub fn never_seen(&self) -> u64 {
+        self.expired + self.unconfirmed
+    }
+
+    pub fn sent(&self) -> u64 {
+        self.leaders.values().map(|tally| tally.sent).sum()
+    }
+
+    fn tally(&mut self, leader: SocketAddr) -> &mut LeaderTally {
+        self.leaders.entry(leader).or_default()
+    }
+}
+
+/// Background confirmation of sent signatures
+pub struct Tracker {
+    sender: mpsc::UnboundedSender<InFlight>,
+    task: JoinHandle<Landings>,
+}
+
+impl Tracker {
+    /// Follow signatures sent by `command`, journaling outcomes to `journal`
+    pub fn spawn(
+        cartographer: Arc<Cartographer>,
+        journal: Option<Arc<Journal>>,
+        command: &'static str,
+    ) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let journal = journal.map(|journal| (journal, command));
+        let task = tokio::spawn(confirm_landings(cartographer, journal, receiver));
+        Self { sender, task }
+    }
+
+    pub fn track(&self, in_flight: InFlight) {
+        let _ = self.sender.send(in_flight);
+    }
+
+    /// Stop accepting signatures and wait for the outstanding ones
+    pub async fn finish(self) -> anyhow::Result<Landings> {
+        drop(self.sender);
+        info!("Send phase done. Waiting for landings...");
+        Ok(self.task.await?)
+    }
+}
+
+/// Poll signature statuses until every sent transaction landed, expired, or
+/// the drain timeout passed after the sender finished
+async fn confirm_landings(
+    cartographer: Arc<Cartographer>,
+    journal: Option<(Arc<Journal>, &'static str)>,
+    mut sent: mpsc::UnboundedReceiver<InFlight>,
+) -> Landings {
+    let outcome = |in_flight: &InFlight, slot, result, error| {
+        if let Some((ref journal, command)) = journal {
+            journal.record_outcome(
+                command,
+                &in_flight.signature,
+                slot,
+                in_flight.leader,
+                result,
+                error,
+            );
+        }
+    };
+    let rpc = cartographer.rpc_client();
+    let mut landings = Landings::default();
+    let mut pending: Vec<InFlight> = Vec::new();
+    let mut open = true;
+    let mut drain_deadline = None;
+
+    loop {
+        while let Ok(in_flight) = sent.try_recv() {
+            landings.tally(in_flight.leader).sent += 1;
+            pending.push(in_flight);
+        }
+        if open && sent.is_closed() && sent.is_empty() {
+            open = false;
+            drain_deadline = Some(Instant::now() + DRAIN_TIMEOUT);
+        }
+        if !open && pending.is_empty() {
+            break;
+        }
+        if drain_deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            landings.unconfirmed += pending.len() as u64;
+            for in_flight in &pending {
+                landings.tally(in_flight.leader).never_seen += 1;
+                outcome(in_flight, None, JournalResult::Unconfirmed, None);
+            }
+            break;
+        }
+
+        let mut still_pending = Vec::with_capacity(pending.len());
+        for batch in pending.chunks(STATUS_BATCH) {
+            let signatures: Vec<Signature> = batch.iter().map(|p| p.signature).collect();
+            let statuses = match rpc.get_signature_statuses(&signatures).await {
+                Ok(response) => response.value,
+                Err(e) => {
+                    warn!("Signature status lookup failed: {}", e);
+                    vec![None; batch.len()]
+                }
+            };
+            let now = Instant::now();
+            let current_slot = cartographer.get_known_slot();
+            for (in_flight, status) in batch.iter().zip(statuses) {
+                match status {
+                    Some(status) => {
+                        let elapsed = now.duration_since(in_flight.sent_at);
+                        landings.latency_ms.record(elapsed.as_millis() as u64);
+                        landings
+                            .slots
+                            .record(status.slot.saturating_sub(in_flight.slot));
+                        let failed = status.err.is_some();
+                        let (result, error) = match status.err {
+                            Some(ref err) => (JournalResult::Failed, Some(err.to_string())),
+                            None => (JournalResult::Landed, None),
+                        };
+                        outcome(in_flight, Some(status.slot), result, error);
+                        let tally = landings.tally(in_flight.leader);
+                        if failed {
+                            tally.failed += 1;
+                            landings.failed += 1;
+                        } else {
+                            tally.landed += 1;
+                            landings.landed += 1;
+                            landings.sample.get_or_insert(in_flight.signature);
+                        }
+                    }
+                    None if current_slot
+                        > in_flight.slot.saturating_add(BLOCKHASH_EXPIRY_SLOTS) =>
+                    {
+                        landings.tally(in_flight.leader).never_seen += 1;
+                        landings.expired += 1;
+                        outcome(in_flight, None, JournalResult::Expired, None);
+                    }
+                    None => still_pending.push(*in_flight),
+                }
+            }
+        }
+        pending = still_pending;
+        tokio::time::sleep(cartographer.slot_duration()).await;
+    }
+    landings
+}
+
+/// Leader row of the end-of-run scoreboard
+#[derive(Debug, Clone, Serialize)]
+pub struct LeaderScore {
+    pub leader: SocketAddr,
+    #[serde(flatten)]
+    pub tally: LeaderTally,
+}
+
+/// Leaders by signatures sent, most first
+pub fn leader_scores(landings: &Landings) -> Vec<LeaderScore> {
+    let mut scores: Vec<LeaderScore> = landings
+        .leaders
+        .iter()
+        .map(|(&leader, &tally)| LeaderScore { leader, tally })
+        .collect();
+    scores.sort_by_key(|score| std::cmp::Reverse(score.tally.sent));
+    scores
+}
+
+/// Print the scoreboard (text mode)
+pub fn print(landings: &mut Landings) {
+    let sent = landings.sent();
+    let pct = |n: u64| {
+        if sent > 0 {
+            n as f64 * 100.0 / sent as f64
+        } else {
+            0.0
+        }
+    };
+    println!(
+        "Scoreboard: sent {} | landed {} ({:.1}%) | failed on chain {} | never seen {}",
+        sent,
+        landings.landed,
+        pct(landings.landed),
+        landings.failed,
+        landings.never_seen()
+    );
+    match landings.latency_ms.percentiles() {
+        Some(p) => println!(
+            "Time to land: p50 {}ms (p95 {}ms, max {}ms)",
+            p.p50, p.p95, p.max
+        ),
+        None => println!("Time to land: no landings"),
+    }
+    let scores = leader_scores(landings);
+    if !scores.is_empty() {
+        println!(
+            "  {:<22} {:>7} {:>7} {:>7} {:>10}",
+            "LEADER", "SENT", "LANDED", "FAILED", "NEVER SEEN"
+        );
+        for score in scores {
+            println!(
+                "  {:<22} {:>7} {:>7} {:>7} {:>10}",
+                score.leader.to_string(),
+                score.tally.sent,
+                score.tally.landed,
+                score.tally.failed,
+                score.tally.never_seen
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leader_scores_by_sent() {
+        let (a, b): (SocketAddr, SocketAddr) = (
+            "1.1.1.1:8009".parse().unwrap(),
+            "2.2.2.2:8009".parse().unwrap(),
+        );
+        let mut landings = Landings::default();
+        *landings.tally(a) = LeaderTally {
+            sent: 2,
+            landed: 1,
+            failed: 0,
+            never_seen: 1,
+        };
+        *landings.tally(b) = LeaderTally {
+            sent: 5,
+            landed: 3,
+            failed: 1,
+            never_seen: 1,
+        };
+        let scores = leader_scores(&landings);
+        assert_eq!(scores[0].leader, b);
+        assert_eq!(landings.sent(), 7);
+
+        let row = serde_json::to_value(&scores[0]).unwrap();
+        assert_eq!(row["leader"], "2.2.2.2:8009");
+        assert_eq!(row["never_seen"], 1);
+    }
+}
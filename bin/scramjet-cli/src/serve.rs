@@ -0,0 +1,575 @@
+//! `serve` subcommand: a minimal JSON-RPC endpoint compatible with Solana's
+//! `sendTransaction`, so wallets and bots can point their RPC URL at
+//! scramjet and get direct-to-leader delivery.
+//!
+//! Only `sendTransaction` is implemented (single calls and batches).
+//! Notifications (calls without an `id`) are sent but get no response.
+//! Transactions are never simulated: `skipPreflight` and the other config
+//! fields are accepted and ignored. The result is the fee payer signature
+//! once the transaction is written to the leader, not once it lands.
+//!
+//! With `--grpc`, the `scramjet.Ingest` stream service
+//! (`scramjet_net::server`) runs alongside and feeds the same sender; with
+//! `--uds`, so does the length-prefixed Unix socket (`scramjet_net::uds`).
+//! Every source goes through the sender's `SendQueue`
+//! (`scramjet_net::queue`) in a priority class (`scramjet_net::priority`):
+//! gRPC messages pick theirs with `priority`, JSON-RPC calls with a
+//! `"priority": "high" | "normal" | "bulk"` config field; otherwise (and on
+//! the Unix socket) `--priority` applies.
+//!
+//! With `RELAY_PEERS` set, every transaction is also forwarded to those peer
+//! instances (`scramjet_net::relay`), which send it from their own region.
+//! Transactions a peer relayed here are sent but not forwarded again, and
+//! neither are those this instance refuses (expired, or duplicates within
+//! `DEDUPE_WINDOW_MS`).
+
+use crate::output::{self, Event, Output};
+use anyhow::Context;
+use futures::stream::{FuturesUnordered, StreamExt};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{header, Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use log::{debug, info, warn};
+use scramjet::Scramjet;
+use scramjet_common::ScramjetError;
+use scramjet_net::dedupe::Dedupe;
+use scramjet_net::priority::Priority;
+use scramjet_net::raw::{self, RawEncoding, RawTransaction};
+use scramjet_net::relay::Relay;
+use scramjet_net::server::{self as ingest, Submission};
+use scramjet_net::uds;
+use serde_json::{json, Value};
+use solana_sdk::signature::Signature;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+/// Largest accepted request body (a batch of ~500 base64 transactions)
+const MAX_REQUEST_BYTES: usize = 1024 * 1024;
+
+/// Queued submissions being sent at once
+const MAX_INGEST_IN_FLIGHT: usize = 256;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Server-defined: the transaction could not be written to a leader
+const SEND_FAILED: i64 = -32000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    fn invalid_request() -> Self {
+        Self::new(INVALID_REQUEST, "Invalid request")
+    }
+}
+
+pub struct ServeArgs {
+    /// JSON-RPC (HTTP) listen address
+    pub listen: SocketAddr,
+    /// gRPC ingestion listen address
+    pub grpc: Option<SocketAddr>,
+    /// Unix socket ingestion path
+    pub uds: Option<PathBuf>,
+    /// Class of JSON-RPC calls that name none, and of Unix socket frames
+    pub priority: Priority,
+    /// How long in-flight requests get once `stop` is cancelled
+    pub drain: Duration,
+}
+
+/// Accept connections until `stop` is cancelled, then let in-flight
+/// requests finish (up to `args.drain`)
+pub async fn run(
+    scramjet: &Scramjet,
+    args: ServeArgs,
+    stop: &CancellationToken,
+    output: Output,
+) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(args.listen)
+        .await
+        .with_context(|| format!("Failed to bind {}", args.listen))?;
+    info!(
+        "Serve: sendTransaction JSON-RPC on http://{}",
+        listener.local_addr()?
+    );
+    let queue = scramjet.send_queue().clone();
+    info!(
+        "Serve: Send queue holds {} ({:?} when full)",
+        queue.capacity(),
+        queue.policy()
+    );
+    let mut ingest_tasks = Vec::new();
+    if let Some(addr) = args.grpc {
+        let task = ingest::spawn_ingest_server(addr, queue.clone(), stop.clone())
+            .await
+            .with_context(|| format!("Failed to start gRPC ingestion on {}", addr))?;
+        ingest_tasks.push(task);
+    }
+    if let Some(path) = args.uds {
+        let task = uds::spawn_uds_server(path.clone(), queue.clone(), args.priority, stop.clone())
+            .with_context(|| format!("Failed to bind Unix socket {:?}", path))?;
+        ingest_tasks.push(task);
+    }
+    let relay = match &scramjet.config().relay_peers {
+        peers if peers.is_empty() => None,
+        peers => Some(Relay::spawn(peers)?),
+    };
+
+    let server = Server {
+        scramjet,
+        relay: relay.as_ref(),
+        priority: args.priority,
+        output,
+        sent: AtomicU64::new(0),
+        failed: AtomicU64::new(0),
+    };
+    let started = Instant::now();
+    // Connections are driven on this task, so handlers can borrow the sender
+    let mut connections = FuturesUnordered::new();
+    let mut ingesting = FuturesUnordered::new();
+    loop {
+        tokio::select! {
+            _ = stop.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, peer)) => {
+                    debug!("Serve: Connection from {}", peer);
+                    let service = service_fn(|request| server.handle(request));
+                    connections.push(
+                        http1::Builder::new().serve_connection(TokioIo::new(stream), service),
+                    );
+                }
+                Err(e) => warn!("Serve: Accept failed: {}", e),
+            },
+            Some(result) = connections.next() => {
+                if let Err(e) = result {
+                    debug!("Serve: Connection closed with error: {}", e);
+                }
+            }
+            Some(submission) = queue.pop(), if ingesting.len() < MAX_INGEST_IN_FLIGHT => {
+                ingesting.push(server.ingest(submission));
+            }
+            Some(()) = ingesting.next() => {}
+        }
+    }
+
+    // Answer requests already being handled and send what is queued, then
+    // close. New submissions are refused; open streams are answered with
+    // what was sent so far.
+    for connection in Pin::new(&mut connections).iter_pin_mut() {
+        connection.graceful_shutdown();
+    }
+    queue.close();
+    let in_flight = async {
+        loop {
+            tokio::select! {
+                Some(result) = connections.next() => {
+                    if let Err(e) = result {
+                        debug!("Serve: Connection closed with error: {}", e);
+                    }
+                }
+                Some(submission) = queue.pop(), if ingesting.len() < MAX_INGEST_IN_FLIGHT => {
+                    ingesting.push(server.ingest(submission));
+                }
+                Some(()) = ingesting.next() => {}
+                else => break,
+            }
+        }
+    };
+    if tokio::time::timeout(args.drain, in_flight).await.is_err() {
+        warn!(
+            "Serve: Closing with {} queued and requests still in flight",
+            queue.len()
+        );
+    }
+    for task in ingest_tasks {
+        if tokio::time::timeout(args.drain, task).await.is_err() {
+            warn!("Serve: Ingestion clients still connected, closing");
+        }
+    }
+
+    let sent = server.sent.load(Ordering::Relaxed);
+    let failed = server.failed.load(Ordering::Relaxed);
+    info!("Serve stopped. Sent: {}, Failed: {}", sent, failed);
+    // Handlers borrow the relay through `server`
+    drop(connections);
+    drop(ingesting);
+    if let Some(relay) = relay {
+        for counts in relay.close(args.drain).await {
+            info!(
+                "Serve: Relayed to {}: {} (dropped {})",
+                counts.peer, counts.forwarded, counts.dropped
+            );
+        }
+    }
+    output.emit(Event::Summary {
+        command: "serve",
+        sent,
+        failed,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    });
+    Ok(())
+}
+
+struct Server<'a> {
+    scramjet: &'a Scramjet,
+    /// Peers every transaction is also forwarded to
+    relay: Option<&'a Relay>,
+    /// Class of calls that name none
+    priority: Priority,
+    output: Output,
+    sent: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl Server<'_> {
+    async fn handle(
+        &self,
+        request: Request<Incoming>,
+    ) -> Result<Response<Full<Bytes>>, Infallible> {
+        if request.method() != Method::POST {
+            return Ok(status_response(StatusCode::METHOD_NOT_ALLOWED));
+        }
+        let body = match Limited::new(request.into_body(), MAX_REQUEST_BYTES)
+            .collect()
+            .await
+        {
+            Ok(body) => body.to_bytes(),
+            Err(e) if e.downcast_ref::<LengthLimitError>().is_some() => {
+                return Ok(status_response(StatusCode::PAYLOAD_TOO_LARGE));
+            }
+            Err(_) => return Ok(status_response(StatusCode::BAD_REQUEST)),
+        };
+
+        let Some(reply) = self.handle_body(&body).await else {
+            // Only notifications: nothing to answer
+            return Ok(status_response(StatusCode::NO_CONTENT));
+        };
+        let mut response = Response::new(Full::new(Bytes::from(reply.to_string())));
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static("application/json"),
+        );
+        Ok(response)
+    }
+
+    /// Answer a single call or a batch (calls in a batch are sent
+    /// concurrently). None when there is nothing to answer: notifications only.
+    async fn handle_body(&self, body: &[u8]) -> Option<Value> {
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(calls)) if !calls.is_empty() => {
+                let responses = futures::future::join_all(calls.iter().map(|c| self.call(c)));
+                let responses: Vec<Value> = responses.await.into_iter().flatten().collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            Ok(Value::Array(_)) => {
+                Some(rpc_response(Value::Null, Err(RpcError::invalid_request())))
+            }
+            Ok(call) => self.call(&call).await,
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, format!("Parse error: {}", e));
+                Some(rpc_response(Value::Null, Err(error)))
+            }
+        }
+    }
+
+    /// Send one call's transaction. None for a notification, which is sent
+    /// but not answered (even on failure).
+    async fn call(&self, call: &Value) -> Option<Value> {
+        let (id, tx) = parse_call(call, self.priority);
+        let result = match tx {
+            Ok(tx) => {
+                let signature = tx.signature.to_string();
+                let (submission, outcome) = Submission::new(tx);
+                // A refused submission is answered with the reason
+                let _ = self.scramjet.send_queue().push(submission).await;
+                ingest::outcome(outcome)
+                    .await
+                    .map(|()| signature)
+                    .map_err(|e| RpcError::new(SEND_FAILED, e))
+            }
+            Err(e) => Err(e),
+        };
+        id.map(|id| rpc_response(id, result))
+    }
+
+    async fn ingest(&self, submission: Submission) {
+        let result = self.send(submission.transaction.clone()).await;
+        submission.respond(result.map(|_| ()));
+    }
+
+    /// Checks that come before relaying, so peers only get what this
+    /// instance sends too: the deadline, then the duplicate claim
+    fn admit(&self, tx: &RawTransaction) -> Result<(), ScramjetError> {
+        if let Some(deadline) = tx.deadline {
+            self.scramjet.check_expiry(deadline)?;
+        }
+        claim_and_relay(self.scramjet.dedupe().map(AsRef::as_ref), self.relay, tx)
+    }
+
+    async fn send(&self, tx: RawTransaction) -> Result<Signature, ScramjetError> {
+        let signature = tx.signature;
+        let sent_at = Instant::now();
+        let sent = match self.admit(&tx) {
+            Ok(()) => self.scramjet.send_raw_claimed(tx).await,
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(receipt) => {
+                debug!(
+                    "Serve: Sent {} to {} (slot {})",
+                    signature, receipt.leader, receipt.slot
+                );
+                self.sent.fetch_add(1, Ordering::Relaxed);
+                self.output.emit(Event::Sent {
+                    command: "serve",
+                    index: None,
+                    slot: receipt.slot,
+                    leader: receipt.leader,
+                    signature: signature.to_string(),
+                    latency_us: output::micros(sent_at.elapsed()),
+                });
+                Ok(signature)
+            }
+            Err(e) => {
+                warn!("Serve: Send failed (tx {}): {}", signature, e);
+                self.failed.fetch_add(1, Ordering::Relaxed);
+                self.output.emit(Event::Error {
+                    command: "serve",
+                    index: None,
+                    slot: None,
+                    leader: None,
+                    signature: Some(signature.to_string()),
+                    error: e.to_string(),
+                });
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Claim `tx`'s signature (when deduplicating) and forward it to the relay
+/// peers. A duplicate is refused before any peer sees it.
+fn claim_and_relay(
+    dedupe: Option<&Dedupe>,
+    relay: Option<&Relay>,
+    tx: &RawTransaction,
+) -> Result<(), ScramjetError> {
+    if let Some(dedupe) = dedupe {
+        dedupe.claim(tx.signature)?;
+    }
+    if let Some(relay) = relay {
+        relay.forward(tx);
+    }
+    Ok(())
+}
+
+/// Validate one JSON-RPC call, returning its id (None for a notification)
+/// and decoded transaction (`priority` unless the call names a class)
+fn parse_call(
+    call: &Value,
+    priority: Priority,
+) -> (Option<Value>, Result<RawTransaction, RpcError>) {
+    let id = call.get("id").cloned();
+    let version = call.get("jsonrpc").and_then(Value::as_str);
+    let (Some("2.0"), Some(method)) = (version, call.get("method").and_then(Value::as_str)) else {
+        // Not a valid request, so not a notification either: always answered
+        let id = id.unwrap_or(Value::Null);
+        return (Some(id), Err(RpcError::invalid_request()));
+    };
+    let tx = match method {
+        "sendTransaction" => parse_send_transaction(call.get("params"), priority),
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Method not found: {}", method),
+        )),
+    };
+    (id, tx)
+}
+
+/// `params`: `[transaction, {"encoding": "base58" | "base64", "priority": ..., ...}?]`
+fn parse_send_transaction(
+    params: Option<&Value>,
+    priority: Priority,
+) -> Result<RawTransaction, RpcError> {
+    let invalid = |message: String| RpcError::new(INVALID_PARAMS, message);
+    let tx = params
+        .and_then(|params| params.get(0))
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("Expected [transaction, config?]".into()))?;
+    let config = params.and_then(|params| params.get(1));
+    // RPC nodes default to base58 too
+    let encoding = config
+        .and_then(|config| config.get("encoding"))
+        .and_then(Value::as_str);
+    let encoding = match encoding {
+        None | Some("base58") => RawEncoding::Base58,
+        Some("base64") => RawEncoding::Base64,
+        Some(other) => return Err(invalid(format!("Unsupported encoding '{}'", other))),
+    };
+    let priority = match config
+        .and_then(|config| config.get("priority"))
+        .and_then(Value::as_str)
+    {
+        Some(class) => class
+            .parse()
+            .map_err(|e: ScramjetError| invalid(e.to_string()))?,
+        None => priority,
+    };
+    raw::parse_raw_transaction(tx, encoding)
+        .map(|tx| tx.with_priority(priority))
+        .map_err(|e| invalid(e.to_string()))
+}
+
+fn rpc_response(id: Value, result: Result<String, RpcError>) -> Value {
+    match result {
+        Ok(signature) => json!({ "jsonrpc": "2.0", "result": signature, "id": id }),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "error": { "code": e.code, "message": e.message },
+            "id": id,
+        }),
+    }
+}
+
+fn status_response(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine as _;
+    #[allow(deprecated)]
+    use solana_sdk::system_instruction;
+    use solana_sdk::{
+        hash::Hash,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    };
+
+    fn send_call(params: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": 7, "method": "sendTransaction", "params": params })
+    }
+
+    #[test]
+    fn test_parse_send_transaction_call() {
+        let payer = Keypair::new();
+        let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+        let bytes = bincode::serialize(&tx).unwrap();
+        let base58 = bs58::encode(&bytes).into_string();
+        let base64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+
+        // Default encoding is base58, as on RPC nodes
+        let (id, parsed) = parse_call(&send_call(json!([base58])), Priority::Bulk);
+        assert_eq!(id, Some(json!(7)));
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.signature, tx.signatures[0]);
+        assert_eq!(parsed.priority, Priority::Bulk);
+
+        // The call's own class wins over the default
+        let config = json!({ "encoding": "base64", "skipPreflight": true, "priority": "high" });
+        let parsed = parse_call(&send_call(json!([base64, config])), Priority::Bulk).1;
+        let parsed = parsed.unwrap();
+        assert_eq!(
+            (parsed.bytes, parsed.priority),
+            (bytes.into(), Priority::High)
+        );
+
+        let code = |call: Value| parse_call(&call, Priority::Normal).1.unwrap_err().code;
+        assert_eq!(code(send_call(json!([base64]))), INVALID_PARAMS);
+        let config = json!({ "encoding": "json" });
+        assert_eq!(code(send_call(json!([base58, config]))), INVALID_PARAMS);
+        let config = json!({ "priority": "urgent" });
+        assert_eq!(code(send_call(json!([base58, config]))), INVALID_PARAMS);
+        assert_eq!(code(send_call(Value::Null)), INVALID_PARAMS);
+
+        let mut call = send_call(json!([base58]));
+        call["method"] = json!("getBalance");
+        assert_eq!(code(call.clone()), METHOD_NOT_FOUND);
+        call.as_object_mut().unwrap().remove("jsonrpc");
+        assert_eq!(code(call), INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_is_not_relayed() {
+        let payer = Keypair::new();
+        let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+        let base58 = bs58::encode(bincode::serialize(&tx).unwrap()).into_string();
+        let tx = raw::parse_raw_transaction(&base58, RawEncoding::Base58).unwrap();
+        let dedupe = Dedupe::new(Duration::from_secs(60), 16);
+        // Unreachable peer: forwarding only fills its buffer
+        let relay = Relay::spawn(&["http://127.0.0.1:1".to_string()]).unwrap();
+        let forwarded = || relay.counts()[0].forwarded;
+
+        claim_and_relay(Some(&dedupe), Some(&relay), &tx).unwrap();
+        assert_eq!(forwarded(), 1);
+
+        // Resubmitted within the window: refused here, and not relayed again
+        let err = claim_and_relay(Some(&dedupe), Some(&relay), &tx).unwrap_err();
+        assert!(matches!(err, ScramjetError::DuplicateTransaction(_)));
+        assert_eq!(forwarded(), 1);
+    }
+
+    #[test]
+    fn test_notification_has_no_id() {
+        let mut call = send_call(json!(["not-a-transaction"]));
+        call.as_object_mut().unwrap().remove("id");
+        let (id, parsed) = parse_call(&call, Priority::Normal);
+        assert_eq!(id, None);
+        assert_eq!(parsed.unwrap_err().code, INVALID_PARAMS);
+
+        // An invalid request is answered even without an id
+        call.as_object_mut().unwrap().remove("jsonrpc");
+        let (id, parsed) = parse_call(&call, Priority::Normal);
+        assert_eq!(id, Some(Value::Null));
+        assert_eq!(parsed.unwrap_err().code, INVALID_REQUEST);
+    }
+
+    #[test]
+    fn test_rpc_response_shape() {
+        assert_eq!(
+            rpc_response(json!(1), Ok("sig".into())),
+            json!({ "jsonrpc": "2.0", "result": "sig", "id": 1 })
+        );
+        let error = RpcError::new(SEND_FAILED, "No leader");
+        assert_eq!(
+            rpc_response(Value::Null, Err(error)),
+            json!({ "jsonrpc": "2.0", "error": { "code": -32000, "message": "No leader" }, "id": null })
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! `scramjet shield`: edit the local blocklist file (`SCRAMJET_BLOCKLIST_FILE`)
+//! without hand-editing it. Edits are atomic (temp file + rename) and a
+//! running instance is told to reload over its control socket. With a remote
+//! list (`SCRAMJET_BLOCKLIST_URL`) the file is a cache of it, overwritten on
+//! every refresh, so edits are refused.
+
+use anyhow::Context;
+use clap::Subcommand;
+use scramjet_net::blocklist::{self, BlocklistEntry, BlocklistManager};
+use scramjet_net::control;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::output::Output;
+
+#[derive(Subcommand)]
+pub enum ShieldCommand {
+    /// Block a validator (replaces its existing entry)
+    Add {
+        /// Validator identity
+        pubkey: Pubkey,
+        /// Why it is blocked (logged when its slots are skipped; no commas)
+        #[arg(long)]
+        reason: Option<String>,
+        /// Lift the block after this long (e.g. 90m, 12h, 7d) or at a Unix
+        /// timestamp (bare number)
+        #[arg(long)]
+        expires: Option<String>,
+    },
+    /// Unblock a validator
+    Remove {
+        /// Validator identity
+        pubkey: Pubkey,
+    },
+    /// Print the blocklist file's entries
+    List,
+}
+
+/// One listed entry (`--output json`)
+#[derive(Serialize)]
+struct EntryRow {
+    pubkey: String,
+    reason: Option<String>,
+    expires_at: Option<u64>,
+    expired: bool,
+}
+
+pub async fn run(
+    command: ShieldCommand,
+    control_socket: &Path,
+    output: Output,
+) -> anyhow::Result<()> {
+    let manager = BlocklistManager::from_env();
+    let path = manager.local_path();
+    if let (Some(url), ShieldCommand::Add { .. } | ShieldCommand::Remove { .. }) =
+        (manager.remote_url(), &command)
+    {
+        anyhow::bail!(
+            "{:?} mirrors the remote blocklist {} (SCRAMJET_BLOCKLIST_URL) and is overwritten on \
+             every refresh; edit the remote list instead",
+            path,
+            url
+        );
+    }
+    match command {
+        ShieldCommand::Add {
+            pubkey,
+            reason,
+            expires,
+        } => {
+            let reason = reason
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty());
+            if reason.as_deref().is_some_and(|r| r.contains(',')) {
+                anyhow::bail!("--reason may not contain commas");
+            }
+            let expires_at = expires
+                .as_deref()
+                .map(|spec| parse_expiry(spec, unix_now()))
+                .transpose()
+                .map_err(anyhow::Error::msg)?;
+            let entry = BlocklistEntry {
+                pubkey,
+                reason,
+                expires_at,
+            };
+            let replaced = blocklist::upsert_blocklist_entry(path, &entry)
+                .await
+                .with_context(|| format!("Failed to update {:?}", path))?;
+            let action = if replaced { "Updated" } else { "Blocked" };
+            report(action, &pubkey, path, control_socket, output).await
+        }
+        ShieldCommand::Remove { pubkey } => {
+            if !blocklist::remove_blocklist_entry(path, &pubkey)
+                .await
+                .with_context(|| format!("Failed to update {:?}", path))?
+            {
+                anyhow::bail!("{} is not in {:?}", pubkey, path);
+            }
+            report("Unblocked", &pubkey, path, control_socket, output).await
+        }
+        ShieldCommand::List => {
+            let entries = blocklist::read_blocklist_file(path)
+                .await
+                .with_context(|| format!("Failed to read {:?}", path))?;
+            let now = unix_now();
+            if output.is_json() {
+                let rows: Vec<EntryRow> = entries
+                    .into_iter()
+                    .map(|entry| EntryRow {
+                        pubkey: entry.pubkey.to_string(),
+                        expired: entry.is_expired(now),
+                        reason: entry.reason,
+                        expires_at: entry.expires_at,
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+                return Ok(());
+            }
+            println!("{} entries in {:?}", entries.len(), path);
+            for entry in &entries {
+                let expiry = match entry.expires_at {
+                    None => "never".to_string(),
+                    Some(at) if entry.is_expired(now) => format!("{} (expired)", at),
+                    Some(at) => format!("{} (in {})", at, format_remaining(at - now)),
+                };
+                println!(
+                    "{:<44}  {:<32}  expires {}",
+                    entry.pubkey,
+                    entry.reason.as_deref().unwrap_or("-"),
+                    expiry
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Print the edit and hot-reload a running instance, if there is one
+async fn report(
+    action: &str,
+    pubkey: &Pubkey,
+    path: &Path,
+    control_socket: &Path,
+    output: Output,
+) -> anyhow::Result<()> {
+    let reloaded = control::request_blocklist_reload(control_socket).await;
+    if output.is_json() {
+        let value = serde_json::json!({
+            "action": action.to_lowercase(),
+            "pubkey": pubkey.to_string(),
+            "file": path,
+            "reloaded": reloaded.as_ref().ok(),
+        });
+        println!("{}", value);
+        return Ok(());
+    }
+    println!("{} {} in {:?}", action, pubkey, path);
+    match reloaded {
+        Ok(blocked) => println!(
+            "Running instance at {:?} reloaded ({} entries)",
+            control_socket, blocked
+        ),
+        Err(e) => println!(
+            "No running instance reloaded ({}); the change applies at its next refresh",
+            e
+        ),
+    }
+    Ok(())
+}
+
+/// `--expires`: `<n>s|m|h|d` from `now`, or a bare Unix timestamp
+fn parse_expiry(spec: &str, now: u64) -> Result<u64, String> {
+    let spec = spec.trim();
+    if let Ok(at) = spec.parse::<u64>() {
+        return if at > now {
+            Ok(at)
+        } else {
+            Err(format!("--expires {} is in the past", spec))
+        };
+    }
+    let (value, unit) = spec.split_at(spec.find(|c: char| !c.is_ascii_digit()).unwrap_or(0));
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format!("Invalid --expires '{}' (e.g. 12h, 7d or a Unix time)", spec))?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        _ => {
+            return Err(format!(
+                "Invalid --expires unit '{}' (use s, m, h or d)",
+                unit
+            ))
+        }
+    };
+    value
+        .checked_mul(unit_secs)
+        .and_then(|secs| now.checked_add(secs))
+        .ok_or_else(|| format!("--expires '{}' is too far in the future", spec))
+}
+
+fn format_remaining(secs: u64) -> String {
+    match secs {
+        s if s >= 86_400 => format!("{}d {}h", s / 86_400, s % 86_400 / 3600),
+        s if s >= 3600 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s => format!("{}m {}s", s / 60, s % 60),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_expiry() {
+        let now = 1_700_000_000;
+        assert_eq!(parse_expiry("90m", now), Ok(now + 5400));
+        assert_eq!(parse_expiry("12h", now), Ok(now + 43_200));
+        assert_eq!(parse_expiry("7d", now), Ok(now + 604_800));
+        assert_eq!(parse_expiry("1767225600", now), Ok(1_767_225_600));
+        assert!(parse_expiry("1600000000", now).is_err());
+        assert!(parse_expiry("soon", now).is_err());
+        assert!(parse_expiry("3w", now).is_err());
+        assert!(parse_expiry(&format!("{}d", u64::MAX / 2), now).is_err());
+        assert!(parse_expiry(&format!("{}s", u64::MAX), now).is_err());
+        assert_eq!(format_remaining(90_061), "1d 1h");
+    }
+}
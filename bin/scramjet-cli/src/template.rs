@@ -0,0 +1,418 @@
+//! `spam --template`: transaction templates for arbitrary program invocations.
+//!
+//! A template lists instructions (program id, accounts, data). It is YAML
+//! when the file ends in `.yaml`/`.yml`, TOML when it ends in `.toml`, and
+//! JSON otherwise. Pubkeys may be `{payer}` or
+//! `{recipient}`. Data is either a string (UTF-8, e.g. a memo) or a list of
+//! parts: `{"utf8": ..}`, `{"base58": ..}`, `{"base64": ..}`, or a
+//! little-endian integer `{"u8" | "u16" | "u32" | "u64": <number or
+//! placeholder>}`. `{counter}` (the spam round) and `{nonce}` (random per
+//! instance) are expanded in text and integer parts, so every round can
+//! produce a distinct transaction.
+//!
+//! ```yaml
+//! compute_unit_limit: 20000
+//! instructions:
+//!   - program_id: MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr
+//!     accounts:
+//!       - { pubkey: "{payer}", is_signer: true }
+//!     data: "scramjet {counter} {nonce}"
+//! ```
+
+use anyhow::Context;
+use base64::Engine;
+use serde::Deserialize;
+use solana_sdk::hash::hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Template file syntax, chosen by extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl Format {
+    fn of(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => Format::Yaml,
+            Some("toml") => Format::Toml,
+            _ => Format::Json,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TemplateFile {
+    /// Overrides `DEFAULT_COMPUTE_UNIT_LIMIT`
+    compute_unit_limit: Option<u32>,
+    instructions: Vec<InstructionFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InstructionFile {
+    program_id: String,
+    #[serde(default)]
+    accounts: Vec<AccountFile>,
+    #[serde(default)]
+    data: DataFile,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountFile {
+    pubkey: String,
+    #[serde(default)]
+    is_signer: bool,
+    #[serde(default)]
+    is_writable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DataFile {
+    Text(String),
+    Parts(Vec<PartFile>),
+}
+
+impl Default for DataFile {
+    fn default() -> Self {
+        DataFile::Parts(Vec::new())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+enum PartFile {
+    Utf8(String),
+    Base58(String),
+    Base64(String),
+    U8(IntFile),
+    U16(IntFile),
+    U32(IntFile),
+    U64(IntFile),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum IntFile {
+    Literal(u64),
+    Placeholder(String),
+}
+
+/// Integer part value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum IntValue {
+    Literal(u64),
+    Counter,
+    Nonce,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Bytes(Vec<u8>),
+    /// UTF-8 text with `{counter}` / `{nonce}` expanded per instance
+    Text(String),
+    /// Little-endian integer of `width` bytes
+    Int {
+        width: usize,
+        value: IntValue,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct InstructionTemplate {
+    program_id: Pubkey,
+    accounts: Vec<AccountMeta>,
+    data: Vec<Segment>,
+}
+
+/// Parsed template with pubkeys resolved; `instructions` builds an instance
+#[derive(Debug, Clone)]
+pub struct TxTemplate {
+    compute_unit_limit: Option<u32>,
+    /// Hash of the file's contents, identifying it across runs
+    fingerprint: String,
+    instructions: Vec<InstructionTemplate>,
+}
+
+impl TxTemplate {
+    /// Load a template file (`.yaml`/`.yml`: YAML, `.toml`: TOML, otherwise JSON)
+    pub fn load(path: &Path, payer: &Pubkey, recipient: &Pubkey) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read template {}", path.display()))?;
+        Self::parse(&contents, Format::of(path), payer, recipient)
+            .with_context(|| format!("Invalid template {}", path.display()))
+    }
+
+    fn parse(
+        contents: &str,
+        format: Format,
+        payer: &Pubkey,
+        recipient: &Pubkey,
+    ) -> anyhow::Result<Self> {
+        let file: TemplateFile = match format {
+            Format::Json => serde_json::from_str(contents)?,
+            Format::Toml => toml::from_str(contents)?,
+            Format::Yaml => serde_yaml::from_str(contents)?,
+        };
+        if file.instructions.is_empty() {
+            anyhow::bail!("template has no instructions");
+        }
+        let resolve = |key: &str| -> anyhow::Result<Pubkey> {
+            match key {
+                "{payer}" => Ok(*payer),
+                "{recipient}" => Ok(*recipient),
+                _ => Pubkey::from_str(key).map_err(|_| anyhow::anyhow!("invalid pubkey '{}'", key)),
+            }
+        };
+
+        let mut instructions = Vec::with_capacity(file.instructions.len());
+        for ix in file.instructions {
+            let mut accounts = Vec::with_capacity(ix.accounts.len());
+            for account in ix.accounts {
+                let pubkey = resolve(&account.pubkey)?;
+                // Spam signs with the identity only
+                if account.is_signer && pubkey != *payer {
+                    anyhow::bail!("signer {} is not the payer", pubkey);
+                }
+                accounts.push(AccountMeta {
+                    pubkey,
+                    is_signer: account.is_signer,
+                    is_writable: account.is_writable,
+                });
+            }
+            let data = match ix.data {
+                DataFile::Text(text) => vec![Segment::Text(text)],
+                DataFile::Parts(parts) => parts
+                    .into_iter()
+                    .map(compile_part)
+                    .collect::<anyhow::Result<_>>()?,
+            };
+            instructions.push(InstructionTemplate {
+                program_id: resolve(&ix.program_id)?,
+                accounts,
+                data,
+            });
+        }
+        Ok(Self {
+            compute_unit_limit: file.compute_unit_limit,
+            fingerprint: hash(contents.as_bytes()).to_string(),
+            instructions,
+        })
+    }
+
+    /// Compute unit limit override, if the template sets one
+    pub fn compute_unit_limit(&self) -> Option<u32> {
+        self.compute_unit_limit
+    }
+
+    /// Identifies the template's contents (unchanged by moving the file)
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    /// Build one instance with `{counter}` and `{nonce}` expanded
+    pub fn instructions(&self, counter: u64, nonce: u64) -> anyhow::Result<Vec<Instruction>> {
+        self.instructions
+            .iter()
+            .map(|ix| {
+                let mut data = Vec::new();
+                for segment in &ix.data {
+                    match segment {
+                        Segment::Bytes(bytes) => data.extend_from_slice(bytes),
+                        Segment::Text(text) => data.extend_from_slice(
+                            text.replace("{counter}", &counter.to_string())
+                                .replace("{nonce}", &nonce.to_string())
+                                .as_bytes(),
+                        ),
+                        Segment::Int { width, value } => {
+                            let bytes = match value {
+                                IntValue::Literal(v) => v.to_le_bytes(),
+                                IntValue::Counter if !fits(counter, *width) => {
+                                    anyhow::bail!(
+                                        "{{counter}} = {} does not fit in u{}",
+                                        counter,
+                                        width * 8
+                                    );
+                                }
+                                IntValue::Counter => counter.to_le_bytes(),
+                                // Random bits: truncation keeps it random
+                                IntValue::Nonce => nonce.to_le_bytes(),
+                            };
+                            data.extend_from_slice(&bytes[..*width]);
+                        }
+                    }
+                }
+                Ok(Instruction {
+                    program_id: ix.program_id,
+                    accounts: ix.accounts.clone(),
+                    data,
+                })
+            })
+            .collect()
+    }
+}
+
+fn compile_part(part: PartFile) -> anyhow::Result<Segment> {
+    let int = |width: usize, value: IntFile| -> anyhow::Result<Segment> {
+        let value = match value {
+            IntFile::Literal(v) if !fits(v, width) => {
+                anyhow::bail!("{} does not fit in u{}", v, width * 8)
+            }
+            IntFile::Literal(v) => IntValue::Literal(v),
+            IntFile::Placeholder(p) if p == "{counter}" => IntValue::Counter,
+            IntFile::Placeholder(p) if p == "{nonce}" => IntValue::Nonce,
+            IntFile::Placeholder(p) => {
+                anyhow::bail!("unknown placeholder '{}' (use {{counter}} or {{nonce}})", p)
+            }
+        };
+        Ok(Segment::Int { width, value })
+    };
+    Ok(match part {
+        PartFile::Utf8(text) => Segment::Text(text),
+        PartFile::Base58(s) => Segment::Bytes(
+            bs58::decode(&s)
+                .into_vec()
+                .map_err(|e| anyhow::anyhow!("invalid base58 data: {}", e))?,
+        ),
+        PartFile::Base64(s) => Segment::Bytes(
+            base64::engine::general_purpose::STANDARD
+                .decode(&s)
+                .map_err(|e| anyhow::anyhow!("invalid base64 data: {}", e))?,
+        ),
+        PartFile::U8(v) => int(1, v)?,
+        PartFile::U16(v) => int(2, v)?,
+        PartFile::U32(v) => int(4, v)?,
+        PartFile::U64(v) => int(8, v)?,
+    })
+}
+
+/// Whether `value` fits in `width` little-endian bytes
+fn fits(value: u64, width: usize) -> bool {
+    width >= 8 || value >> (width * 8) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memo_template_expands_placeholders() {
+        let payer = Pubkey::new_unique();
+        let json = r#"{
+            "instructions": [{
+                "program_id": "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+                "accounts": [{ "pubkey": "{payer}", "is_signer": true }],
+                "data": "round {counter} nonce {nonce}"
+            }]
+        }"#;
+        let template = TxTemplate::parse(json, Format::Json, &payer, &payer).unwrap();
+        assert_eq!(template.compute_unit_limit(), None);
+
+        let ix = &template.instructions(7, 42).unwrap()[0];
+        assert_eq!(ix.data, b"round 7 nonce 42");
+        assert_eq!(ix.accounts, vec![AccountMeta::new_readonly(payer, true)]);
+        assert_ne!(template.instructions(8, 42).unwrap()[0].data, ix.data);
+
+        // Same contents, same fingerprint; any edit changes it
+        let again = TxTemplate::parse(json, Format::Json, &payer, &payer).unwrap();
+        assert_eq!(again.fingerprint(), template.fingerprint());
+        let edited = json.replace("round", "turn");
+        let edited = TxTemplate::parse(&edited, Format::Json, &payer, &payer).unwrap();
+        assert_ne!(edited.fingerprint(), template.fingerprint());
+    }
+
+    #[test]
+    fn test_binary_parts_from_toml() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let toml = r#"
+            compute_unit_limit = 5000
+
+            [[instructions]]
+            program_id = "11111111111111111111111111111111"
+            accounts = [
+                { pubkey = "{payer}", is_signer = true, is_writable = true },
+                { pubkey = "{recipient}", is_writable = true },
+            ]
+            data = [{ u32 = 2 }, { u64 = "{counter}" }, { base58 = "2g" }, { u8 = "{nonce}" }]
+        "#;
+        let template = TxTemplate::parse(toml, Format::Toml, &payer, &recipient).unwrap();
+        assert_eq!(template.compute_unit_limit(), Some(5000));
+
+        let ix = &template.instructions(300, 0x1ff).unwrap()[0];
+        let mut expected = vec![2, 0, 0, 0];
+        expected.extend_from_slice(&300u64.to_le_bytes());
+        expected.extend_from_slice(&[0x61, 0xff]);
+        assert_eq!(ix.data, expected);
+        assert_eq!(ix.accounts[1], AccountMeta::new(recipient, false));
+    }
+
+    #[test]
+    fn test_yaml_template() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let yaml = r#"
+compute_unit_limit: 5000
+instructions:
+  - program_id: "11111111111111111111111111111111"
+    accounts:
+      - { pubkey: "{payer}", is_signer: true, is_writable: true }
+      - { pubkey: "{recipient}", is_writable: true }
+    data:
+      - u32: 2
+      - u64: "{counter}"
+  - program_id: MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr
+    data: "round {counter}"
+"#;
+        let template = TxTemplate::parse(yaml, Format::Yaml, &payer, &recipient).unwrap();
+        assert_eq!(template.compute_unit_limit(), Some(5000));
+
+        let ixs = template.instructions(3, 0).unwrap();
+        let mut expected = vec![2, 0, 0, 0];
+        expected.extend_from_slice(&3u64.to_le_bytes());
+        assert_eq!(ixs[0].data, expected);
+        assert_eq!(ixs[0].accounts[1], AccountMeta::new(recipient, false));
+        assert_eq!(ixs[1].data, b"round 3");
+
+        assert_eq!(Format::of(Path::new("t.yml")), Format::Yaml);
+        assert_eq!(Format::of(Path::new("t.toml")), Format::Toml);
+        assert_eq!(Format::of(Path::new("t.json")), Format::Json);
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        let payer = Pubkey::new_unique();
+        let parse = |json: &str| TxTemplate::parse(json, Format::Json, &payer, &payer);
+        let ix = |body: &str| format!(r#"{{"instructions": [{{{}}}]}}"#, body);
+        let memo = r#""program_id": "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr""#;
+
+        assert!(parse(r#"{"instructions": []}"#).is_err());
+        assert!(parse(&ix(r#""program_id": "nope""#)).is_err());
+        // Only the payer can sign
+        let other = Pubkey::new_unique();
+        assert!(parse(&ix(&format!(
+            r#"{}, "accounts": [{{"pubkey": "{}", "is_signer": true}}]"#,
+            memo, other
+        )))
+        .is_err());
+        assert!(parse(&ix(&format!(r#"{}, "data": [{{"u8": 256}}]"#, memo))).is_err());
+        assert!(parse(&ix(&format!(r#"{}, "data": [{{"u8": "{{slot}}"}}]"#, memo))).is_err());
+
+        // Counter overflow is caught per instance
+        let narrow = parse(&ix(&format!(
+            r#"{}, "data": [{{"u8": "{{counter}}"}}]"#,
+            memo
+        )))
+        .unwrap();
+        assert!(narrow.instructions(255, 0).is_ok());
+        assert!(narrow.instructions(256, 0).is_err());
+    }
+}
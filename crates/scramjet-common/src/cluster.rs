@@ -0,0 +1,109 @@
+use crate::error::ScramjetError;
+
+/// Public Solana cluster presets (`CLUSTER`, `--mainnet` / `--devnet` /
+/// `--testnet`): the default RPC endpoint and priority fee, and the genesis
+/// hash the RPC node must report before anything is sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Testnet,
+}
+
+impl Cluster {
+    /// Public RPC endpoint (rate limited; fine for trying things out)
+    pub fn rpc_url(self) -> &'static str {
+        match self {
+            Cluster::Mainnet => "https://api.mainnet-beta.solana.com",
+            Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Testnet => "https://api.testnet.solana.com",
+        }
+    }
+
+    pub fn genesis_hash(self) -> &'static str {
+        match self {
+            Cluster::Mainnet => "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d",
+            Cluster::Devnet => "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG",
+            Cluster::Testnet => "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY",
+        }
+    }
+
+    /// Priority fee (microlamports per CU) when DEFAULT_PRIORITY_FEE is not
+    /// set: mainnet blocks are contended, the test clusters barely are
+    pub fn default_priority_fee(self) -> u64 {
+        match self {
+            Cluster::Mainnet => 100_000,
+            Cluster::Devnet | Cluster::Testnet => 1_000,
+        }
+    }
+
+    /// The cluster whose genesis hash this is, if it is a known one
+    pub fn from_genesis_hash(genesis_hash: &str) -> Option<Self> {
+        [Cluster::Mainnet, Cluster::Devnet, Cluster::Testnet]
+            .into_iter()
+            .find(|cluster| cluster.genesis_hash() == genesis_hash)
+    }
+
+    /// Fail unless `genesis_hash` (as reported by `rpc_url`) is this cluster's
+    pub fn verify_genesis(self, rpc_url: &str, genesis_hash: &str) -> Result<(), ScramjetError> {
+        if genesis_hash == self.genesis_hash() {
+            return Ok(());
+        }
+        let actual = Cluster::from_genesis_hash(genesis_hash)
+            .map_or_else(|| "an unknown cluster".to_string(), |c| c.to_string());
+        Err(ScramjetError::ClusterMismatch(format!(
+            "expected {}, but {} is {} (genesis hash {})",
+            self, rpc_url, actual, genesis_hash
+        )))
+    }
+}
+
+impl std::fmt::Display for Cluster {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Cluster::Mainnet => "mainnet",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+        })
+    }
+}
+
+impl std::str::FromStr for Cluster {
+    type Err = ScramjetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            other => Err(ScramjetError::ConfigValidationError(format!(
+                "CLUSTER='{}' (expected mainnet, devnet or testnet)",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cluster_genesis_check() {
+        assert_eq!("Mainnet-Beta".parse::<Cluster>().unwrap(), Cluster::Mainnet);
+        assert!("localnet".parse::<Cluster>().is_err());
+
+        let devnet = Cluster::Devnet;
+        assert!(devnet
+            .verify_genesis(devnet.rpc_url(), devnet.genesis_hash())
+            .is_ok());
+        let err = devnet
+            .verify_genesis("http://rpc", Cluster::Mainnet.genesis_hash())
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected devnet, but http://rpc is mainnet"));
+        let err = devnet.verify_genesis("http://rpc", "11111111111111111111111111111111");
+        assert!(err.unwrap_err().to_string().contains("unknown cluster"));
+    }
+}
@@ -1,62 +1,354 @@
+use crate::cluster::Cluster;
 use crate::error::ScramjetError;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Config file picked up from the working directory when `--config` is not given
+pub const DEFAULT_CONFIG_FILE: &str = "scramjet.toml";
+
+/// Fastest accepted send rate (per second): far past what one sender can push
+pub const MAX_SEND_RATE: f64 = 1_000_000.0;
+
+/// Slowest accepted send rate (per second): one send an hour
+pub const MIN_SEND_RATE: f64 = 1.0 / 3600.0;
+
 /// Runtime configuration for Scramjet
-/// Loaded from environment variables with sensible defaults
+/// Loaded from environment variables (optionally layered over a TOML file)
+/// with sensible defaults
 /// Validates all values on construction (fail-fast)
 #[derive(Debug, Clone)]
 pub struct Config {
     // --- Network Endpoints ---
+    /// Preset the RPC node must belong to (checked by genesis hash at startup)
+    pub cluster: Option<Cluster>,
     pub rpc_url: String,
+    pub rpc_fallback_urls: Vec<String>,
     pub geyser_url: Option<String>,
+    pub geyser_redundant_urls: Vec<String>,
+    pub ws_url: Option<String>,
+    pub geyser_blocks_meta: bool,
+    pub geyser_tx_status: bool,
+    pub geyser_slot_commitment: String,
+    pub geyser_interslot_updates: bool,
+    pub geyser_entries: bool,
+    pub control_socket: Option<String>,
+    pub topology_cache_file: Option<String>,
+    /// Compute unit limits learned per `spam --template` (see `compute_profile_path`)
+    pub compute_profile_file: Option<String>,
+    /// Send journal (see `journal_path`)
+    pub journal_file: Option<String>,
+    /// Journal size (MB) past which it is rotated when opened
+    pub journal_max_mb: u64,
+    pub stats_file: Option<String>,
+    /// MaxMind databases (City / Country / ASN) locating validators
+    pub geoip_db_files: Vec<String>,
+    /// Peer scramjet instances (`http://host:port` of `serve --grpc`) that
+    /// received transactions are also forwarded to
+    pub relay_peers: Vec<String>,
 
     // --- Timing (Intervals in ms) ---
     pub rpc_poll_interval_ms: u64,
     pub scout_interval_ms: u64,
     pub scout_lookahead_slots: u64,
+    pub scout_max_warm_connections: usize,
+    pub health_check_interval_ms: u64,
+    pub health_check_lookahead_slots: u64,
+    pub rtt_probe_interval_ms: u64,
+    /// Validators measured per RTT probe pass
+    pub rtt_probe_batch: usize,
     pub monitor_interval_ms: u64,
+    pub cluster_check_interval_ms: u64,
+    pub blockhash_refresh_ms: u64,
+    pub schedule_prefetch_slots: u64,
+
+    // --- RPC Failover ---
+    pub rpc_demote_after_failures: u32,
+    pub rpc_demote_secs: u64,
 
     // --- Geyser Reconnection Backoff ---
     pub geyser_reconnect_delay_ms: u64,
     pub geyser_max_reconnect_delay_ms: u64,
+    pub geyser_ping_interval_ms: u64,
+    pub geyser_stall_timeout_ms: u64,
 
     // --- QUIC Transport (in seconds) ---
     pub quic_keep_alive_secs: u64,
     pub quic_idle_timeout_secs: u64,
+    pub quic_identity_failover_threshold: u32,
+    pub quic_connections_per_leader: usize,
+    pub quic_bind_addr: Option<String>,
+    pub quic_bind_addrs: Vec<String>,
+    pub quic_session_cache: usize,
+    pub quic_max_connections: usize,
+    pub quic_connection_ttl_secs: u64,
+    pub quic_zero_rtt: bool,
+    pub quic_datagrams: bool,
+    pub quic_stream_budget: usize,
+    pub quic_send_timeout_ms: u64,
+    pub quic_send_buffer_bytes: usize,
+    pub quic_recv_buffer_bytes: usize,
+    pub quic_segmentation_offload: bool,
+    pub shutdown_drain_ms: u64,
+
+    // --- Send Retries ---
+    pub send_retry_max_attempts: u32,
+    pub send_retry_backoff_ms: u64,
+    pub send_retry_max_backoff_ms: u64,
+    pub send_retry_on: String,
+
+    // --- Send Rate Limit ---
+    pub send_rate_limit: Option<String>,
+    pub send_rate_burst: u32,
+
+    // --- Send Queue ---
+    pub send_queue_capacity: usize,
+    pub send_queue_overflow: String,
+
+    // --- Duplicate Guard ---
+    /// Refuse a pre-signed transaction sent again within this long (0 = off)
+    pub dedupe_window_ms: u64,
+    /// Most signatures remembered
+    pub dedupe_capacity: usize,
+
+    // --- Send Runtime ---
+    pub send_runtime_threads: usize,
+    pub send_runtime_cores: Vec<String>,
+
+    // --- Auto-Blocklist ---
+    pub auto_block_ttl_secs: u64,
+    pub auto_block_failure_rate: f64,
+    pub auto_block_min_samples: u32,
+    pub auto_block_window_secs: u64,
 
     // --- Transaction Defaults ---
     pub default_compute_unit_limit: u32,
     pub default_priority_fee: u64,
+    pub check_funds: bool,
+
+    // --- Landing SLOs ---
+    pub landing_slos: Option<String>,
+    pub slo_window_secs: u64,
+    pub slo_min_samples: usize,
+    pub alert_webhook_url: Option<String>,
+
+    // --- Jito Block Engine ---
+    pub jito_block_engine_url: Option<String>,
+    pub jito_tip_lamports: u64,
+
+    // --- External Policy Engine ---
+    pub policy_socket: Option<String>,
+    pub policy_timeout_ms: u64,
+    pub policy_cache_ms: u64,
+    pub policy_fail_open: bool,
+
+    // --- Remote Signer ---
+    pub remote_signer_url: Option<String>,
+    pub remote_signer_token: Option<String>,
+    pub remote_signer_timeout_ms: u64,
+
+    /// Settings given explicitly (env or config file), which a cluster preset
+    /// must not replace
+    explicit_rpc_url: bool,
+    explicit_priority_fee: bool,
 }
 
 impl Config {
     /// Load configuration from environment variables
     /// Returns error if validation fails (fail-fast)
     pub fn from_env() -> Result<Self, ScramjetError> {
+        Self::load(&|key| env::var(key).ok())
+    }
+
+    /// Load configuration from a TOML file using its default profile
+    /// (the top-level `profile` key, if any). Env vars override file values.
+    pub fn from_file(path: &Path) -> Result<Self, ScramjetError> {
+        Self::from_file_with_profile(path, None)
+    }
+
+    /// Load configuration from a TOML file with the named profile applied.
+    ///
+    /// Keys are the environment variable names in lowercase. Top-level keys
+    /// apply to every profile; `[profiles.<name>]` tables override them.
+    /// Precedence: env > profile > top level > default.
+    pub fn from_file_with_profile(
+        path: &Path,
+        profile: Option<&str>,
+    ) -> Result<Self, ScramjetError> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| ScramjetError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        let file = file_values(&contents, profile)
+            .map_err(|e| ScramjetError::ConfigError(format!("{}: {}", path.display(), e)))?;
+
+        let used = RefCell::new(HashSet::new());
+        let config = Self::load(&|key| {
+            let file_key = key.to_ascii_lowercase();
+            let value = env::var(key).ok().or_else(|| file.get(&file_key).cloned());
+            used.borrow_mut().insert(file_key);
+            value
+        })?;
+
+        // Typos would otherwise be silently ignored
+        let used = used.into_inner();
+        let mut unknown: Vec<&str> = file
+            .keys()
+            .filter(|key| !used.contains(*key))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            unknown.sort_unstable();
+            return Err(ScramjetError::ConfigError(format!(
+                "{}: unknown key(s): {}",
+                path.display(),
+                unknown.join(", ")
+            )));
+        }
+        Ok(config)
+    }
+
+    fn load(lookup: &dyn Fn(&str) -> Option<String>) -> Result<Self, ScramjetError> {
+        // A cluster preset supplies the defaults of the values below
+        let cluster = lookup("CLUSTER").map(|v| v.parse()).transpose()?;
+        let rpc_url = lookup("SOLANA_RPC_URL");
         let config = Self {
             // Network endpoints
-            rpc_url: env::var("SOLANA_RPC_URL")
-                .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".into()),
-            geyser_url: env::var("GEYSER_URL").ok(),
+            cluster,
+            explicit_rpc_url: rpc_url.is_some(),
+            rpc_url: rpc_url
+                .unwrap_or_else(|| cluster.unwrap_or(Cluster::Mainnet).rpc_url().into()),
+            rpc_fallback_urls: parse_list(lookup, "SOLANA_RPC_FALLBACK_URLS"),
+            geyser_url: lookup("GEYSER_URL"),
+            geyser_redundant_urls: parse_list(lookup, "GEYSER_REDUNDANT_URLS"),
+            ws_url: lookup("SOLANA_WS_URL"),
+            geyser_blocks_meta: parse_value(lookup, "GEYSER_BLOCKS_META", false),
+            geyser_tx_status: parse_value(lookup, "GEYSER_TX_STATUS", false),
+            geyser_slot_commitment: lookup("GEYSER_SLOT_COMMITMENT")
+                .unwrap_or_else(|| "processed".into()),
+            geyser_interslot_updates: parse_value(lookup, "GEYSER_INTERSLOT_UPDATES", false),
+            geyser_entries: parse_value(lookup, "GEYSER_ENTRIES", false),
+            control_socket: lookup("SCRAMJET_CONTROL_SOCKET"),
+            topology_cache_file: lookup("TOPOLOGY_CACHE_FILE"),
+            compute_profile_file: lookup("COMPUTE_PROFILE_FILE"),
+            journal_file: lookup("JOURNAL_FILE"),
+            journal_max_mb: parse_value(lookup, "JOURNAL_MAX_MB", 64),
+            stats_file: lookup("STATS_FILE"),
+            geoip_db_files: parse_list(lookup, "GEOIP_DB_FILES"),
+            relay_peers: parse_list(lookup, "RELAY_PEERS"),
 
             // Intervals
-            rpc_poll_interval_ms: parse_env("RPC_POLL_INTERVAL_MS", 400),
-            scout_interval_ms: parse_env("SCOUT_INTERVAL_MS", 1000),
-            scout_lookahead_slots: parse_env("SCOUT_LOOKAHEAD_SLOTS", 10),
-            monitor_interval_ms: parse_env("MONITOR_INTERVAL_MS", 400),
+            rpc_poll_interval_ms: parse_value(lookup, "RPC_POLL_INTERVAL_MS", 400),
+            scout_interval_ms: parse_value(lookup, "SCOUT_INTERVAL_MS", 1000),
+            scout_lookahead_slots: parse_value(lookup, "SCOUT_LOOKAHEAD_SLOTS", 10),
+            scout_max_warm_connections: parse_value(lookup, "SCOUT_MAX_WARM_CONNECTIONS", 0),
+            health_check_interval_ms: parse_value(lookup, "HEALTH_CHECK_INTERVAL_MS", 500),
+            health_check_lookahead_slots: parse_value(lookup, "HEALTH_CHECK_LOOKAHEAD_SLOTS", 4),
+            rtt_probe_interval_ms: parse_value(lookup, "RTT_PROBE_INTERVAL_MS", 5000),
+            rtt_probe_batch: parse_value(lookup, "RTT_PROBE_BATCH", 32),
+            monitor_interval_ms: parse_value(lookup, "MONITOR_INTERVAL_MS", 400),
+            cluster_check_interval_ms: parse_value(lookup, "CLUSTER_CHECK_INTERVAL_MS", 30_000),
+            blockhash_refresh_ms: parse_value(lookup, "BLOCKHASH_REFRESH_MS", 2_000),
+            schedule_prefetch_slots: parse_value(lookup, "SCHEDULE_PREFETCH_SLOTS", 1_000),
+
+            // RPC failover
+            rpc_demote_after_failures: parse_value(lookup, "RPC_DEMOTE_AFTER_FAILURES", 3),
+            rpc_demote_secs: parse_value(lookup, "RPC_DEMOTE_SECS", 30),
 
             // Backoff
-            geyser_reconnect_delay_ms: parse_env("GEYSER_RECONNECT_DELAY_MS", 1000),
-            geyser_max_reconnect_delay_ms: parse_env("GEYSER_MAX_RECONNECT_DELAY_MS", 10000),
+            geyser_reconnect_delay_ms: parse_value(lookup, "GEYSER_RECONNECT_DELAY_MS", 1000),
+            geyser_max_reconnect_delay_ms: parse_value(
+                lookup,
+                "GEYSER_MAX_RECONNECT_DELAY_MS",
+                10000,
+            ),
+            geyser_ping_interval_ms: parse_value(lookup, "GEYSER_PING_INTERVAL_MS", 10000),
+            geyser_stall_timeout_ms: parse_value(lookup, "GEYSER_STALL_TIMEOUT_MS", 5000),
 
             // QUIC
-            quic_keep_alive_secs: parse_env("QUIC_KEEP_ALIVE_SECS", 5),
-            quic_idle_timeout_secs: parse_env("QUIC_IDLE_TIMEOUT_SECS", 10),
+            quic_keep_alive_secs: parse_value(lookup, "QUIC_KEEP_ALIVE_SECS", 5),
+            quic_idle_timeout_secs: parse_value(lookup, "QUIC_IDLE_TIMEOUT_SECS", 10),
+            quic_identity_failover_threshold: parse_value(
+                lookup,
+                "QUIC_IDENTITY_FAILOVER_THRESHOLD",
+                3,
+            ),
+            quic_connections_per_leader: parse_value(lookup, "QUIC_CONNECTIONS_PER_LEADER", 1),
+            quic_bind_addr: lookup("QUIC_BIND_ADDR"),
+            quic_bind_addrs: parse_list(lookup, "QUIC_BIND_ADDRS"),
+            quic_session_cache: parse_value(lookup, "QUIC_SESSION_CACHE", 1024),
+            quic_max_connections: parse_value(lookup, "QUIC_MAX_CONNECTIONS", 256),
+            quic_connection_ttl_secs: parse_value(lookup, "QUIC_CONNECTION_TTL_SECS", 60),
+            quic_zero_rtt: parse_value(lookup, "QUIC_ZERO_RTT", false),
+            quic_datagrams: parse_value(lookup, "QUIC_DATAGRAMS", false),
+            quic_stream_budget: parse_value(lookup, "QUIC_STREAM_BUDGET", 128),
+            quic_send_timeout_ms: parse_value(lookup, "QUIC_SEND_TIMEOUT_MS", 0),
+            quic_send_buffer_bytes: parse_value(lookup, "QUIC_SEND_BUFFER_BYTES", 0),
+            quic_recv_buffer_bytes: parse_value(lookup, "QUIC_RECV_BUFFER_BYTES", 0),
+            quic_segmentation_offload: parse_value(lookup, "QUIC_SEGMENTATION_OFFLOAD", true),
+            shutdown_drain_ms: parse_value(lookup, "SHUTDOWN_DRAIN_MS", 2_000),
+
+            // Retries
+            send_retry_max_attempts: parse_value(lookup, "SEND_RETRY_MAX_ATTEMPTS", 3),
+            send_retry_backoff_ms: parse_value(lookup, "SEND_RETRY_BACKOFF_MS", 20),
+            send_retry_max_backoff_ms: parse_value(lookup, "SEND_RETRY_MAX_BACKOFF_MS", 200),
+            send_retry_on: lookup("SEND_RETRY_ON").unwrap_or_else(|| "connection,stream".into()),
+
+            // Rate limit
+            send_rate_limit: lookup("SEND_RATE_LIMIT"),
+            send_rate_burst: parse_value(lookup, "SEND_RATE_BURST", 0),
+
+            // Send queue
+            send_queue_capacity: parse_value(lookup, "SEND_QUEUE_CAPACITY", 1_024),
+            send_queue_overflow: lookup("SEND_QUEUE_OVERFLOW").unwrap_or_else(|| "block".into()),
+
+            // Duplicate guard
+            dedupe_window_ms: parse_value(lookup, "DEDUPE_WINDOW_MS", 0),
+            dedupe_capacity: parse_value(lookup, "DEDUPE_CAPACITY", 100_000),
+
+            // Send runtime
+            send_runtime_threads: parse_value(lookup, "SEND_RUNTIME_THREADS", 0),
+            send_runtime_cores: parse_list(lookup, "SEND_RUNTIME_CORES"),
+
+            // Auto-blocklist
+            auto_block_ttl_secs: parse_value(lookup, "AUTO_BLOCK_TTL_SECS", 600),
+            auto_block_failure_rate: parse_value(lookup, "AUTO_BLOCK_FAILURE_RATE", 0.8),
+            auto_block_min_samples: parse_value(lookup, "AUTO_BLOCK_MIN_SAMPLES", 20),
+            auto_block_window_secs: parse_value(lookup, "AUTO_BLOCK_WINDOW_SECS", 60),
 
             // Transaction
-            default_compute_unit_limit: parse_env("DEFAULT_COMPUTE_UNIT_LIMIT", 200_000),
-            default_priority_fee: parse_env("DEFAULT_PRIORITY_FEE", 100_000),
+            default_compute_unit_limit: parse_value(lookup, "DEFAULT_COMPUTE_UNIT_LIMIT", 200_000),
+            default_priority_fee: parse_value(
+                lookup,
+                "DEFAULT_PRIORITY_FEE",
+                cluster.map_or(100_000, Cluster::default_priority_fee),
+            ),
+            // An unparsable value falls back to the default: still the preset's call
+            explicit_priority_fee: lookup("DEFAULT_PRIORITY_FEE")
+                .is_some_and(|fee| fee.parse::<u64>().is_ok()),
+            check_funds: parse_value(lookup, "CHECK_FUNDS", false),
+
+            // SLOs
+            landing_slos: lookup("LANDING_SLOS"),
+            slo_window_secs: parse_value(lookup, "SLO_WINDOW_SECS", 300),
+            slo_min_samples: parse_value(lookup, "SLO_MIN_SAMPLES", 20),
+            alert_webhook_url: lookup("ALERT_WEBHOOK_URL"),
+
+            // Jito
+            jito_block_engine_url: lookup("JITO_BLOCK_ENGINE_URL"),
+            jito_tip_lamports: parse_value(lookup, "JITO_TIP_LAMPORTS", 10_000),
+
+            // Policy
+            policy_socket: lookup("POLICY_SOCKET"),
+            policy_timeout_ms: parse_value(lookup, "POLICY_TIMEOUT_MS", 50),
+            policy_cache_ms: parse_value(lookup, "POLICY_CACHE_MS", 1000),
+            policy_fail_open: parse_value(lookup, "POLICY_FAIL_OPEN", false),
+
+            // Remote signer
+            remote_signer_url: lookup("REMOTE_SIGNER_URL"),
+            remote_signer_token: lookup("REMOTE_SIGNER_TOKEN"),
+            remote_signer_timeout_ms: parse_value(lookup, "REMOTE_SIGNER_TIMEOUT_MS", 2_000),
         };
 
         config.validate()?; // Fail-fast on invalid config
@@ -82,6 +374,20 @@ impl Config {
             )));
         }
 
+        if self.health_check_interval_ms != 0 && self.health_check_interval_ms < MIN_INTERVAL_MS {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "HEALTH_CHECK_INTERVAL_MS={} is too low (min {}ms, 0 = off). CPU will spike.",
+                self.health_check_interval_ms, MIN_INTERVAL_MS
+            )));
+        }
+
+        if self.rtt_probe_interval_ms != 0 && self.rtt_probe_interval_ms < MIN_INTERVAL_MS {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "RTT_PROBE_INTERVAL_MS={} is too low (min {}ms, 0 = off). CPU will spike.",
+                self.rtt_probe_interval_ms, MIN_INTERVAL_MS
+            )));
+        }
+
         if self.monitor_interval_ms < MIN_INTERVAL_MS {
             return Err(ScramjetError::ConfigValidationError(format!(
                 "MONITOR_INTERVAL_MS={} is too low (min {}ms). CPU will spike.",
@@ -89,6 +395,30 @@ impl Config {
             )));
         }
 
+        if self.cluster_check_interval_ms < MIN_INTERVAL_MS {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "CLUSTER_CHECK_INTERVAL_MS={} is too low (min {}ms). CPU will spike.",
+                self.cluster_check_interval_ms, MIN_INTERVAL_MS
+            )));
+        }
+
+        // A new blockhash appears at most once a slot; refreshing faster only
+        // spends RPC requests
+        const MIN_BLOCKHASH_REFRESH_MS: u64 = 400;
+        if self.blockhash_refresh_ms < MIN_BLOCKHASH_REFRESH_MS {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "BLOCKHASH_REFRESH_MS={} is too low (min {}ms, one slot). Each refresh is a getLatestBlockhash RPC call.",
+                self.blockhash_refresh_ms, MIN_BLOCKHASH_REFRESH_MS
+            )));
+        }
+
+        // 0 would demote an endpoint before it ever failed
+        if self.rpc_demote_after_failures == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "RPC_DEMOTE_AFTER_FAILURES=0 would demote every RPC endpoint.".into(),
+            ));
+        }
+
         // Compute unit limit must be > 0
         if self.default_compute_unit_limit == 0 {
             return Err(ScramjetError::ConfigValidationError(
@@ -111,6 +441,137 @@ impl Config {
             )));
         }
 
+        // Validators cap concurrent connections per peer (8 in Agave)
+        const MAX_CONNECTIONS_PER_LEADER: usize = 8;
+        if self.quic_connections_per_leader == 0
+            || self.quic_connections_per_leader > MAX_CONNECTIONS_PER_LEADER
+        {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "QUIC_CONNECTIONS_PER_LEADER={} must be between 1 and {}.",
+                self.quic_connections_per_leader, MAX_CONNECTIONS_PER_LEADER
+            )));
+        }
+
+        // The budget must fit at least one leader's pool
+        if self.quic_max_connections != 0
+            && self.quic_max_connections < self.quic_connections_per_leader
+        {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "QUIC_MAX_CONNECTIONS={} is below QUIC_CONNECTIONS_PER_LEADER={} (0 = unlimited).",
+                self.quic_max_connections, self.quic_connections_per_leader
+            )));
+        }
+
+        if let Some(ref addr) = self.quic_bind_addr {
+            if parse_bind_addr(addr).is_none() {
+                return Err(ScramjetError::ConfigValidationError(format!(
+                    "QUIC_BIND_ADDR='{}' must be an IP address or ip:port.",
+                    addr
+                )));
+            }
+            if !self.quic_bind_addrs.is_empty() {
+                return Err(ScramjetError::ConfigValidationError(
+                    "Set QUIC_BIND_ADDR or QUIC_BIND_ADDRS, not both.".into(),
+                ));
+            }
+        }
+        if let Some(addr) = self
+            .quic_bind_addrs
+            .iter()
+            .find(|addr| parse_bind_addr(addr).is_none())
+        {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "QUIC_BIND_ADDRS entry '{}' must be an IP address or ip:port.",
+                addr
+            )));
+        }
+
+        // 0-RTT needs a session ticket from an earlier connection
+        if self.quic_zero_rtt && self.quic_session_cache == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "QUIC_ZERO_RTT=true needs session resumption (QUIC_SESSION_CACHE > 0).".into(),
+            ));
+        }
+
+        // The first attempt counts: 0 would never send
+        if self.send_retry_max_attempts == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "SEND_RETRY_MAX_ATTEMPTS=0 means transactions are never sent (1 = no retries)."
+                    .into(),
+            ));
+        }
+
+        // A typo would otherwise only surface when the engine starts
+        const RETRY_CLASSES: [&str; 4] = ["connection", "stream", "all", "none"];
+        if let Some(class) = self
+            .send_retry_on
+            .split(',')
+            .map(str::trim)
+            .filter(|class| !class.is_empty())
+            .find(|class| !RETRY_CLASSES.contains(&class.to_ascii_lowercase().as_str()))
+        {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "SEND_RETRY_ON entry '{}' must be connection, stream, all or none.",
+                class
+            )));
+        }
+
+        // Parsed into a `SlotCommitment` only when the Geyser stream starts
+        const SLOT_COMMITMENTS: [&str; 3] = ["processed", "confirmed", "finalized"];
+        let commitment = self.geyser_slot_commitment.trim().to_ascii_lowercase();
+        if !SLOT_COMMITMENTS.contains(&commitment.as_str()) {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "GEYSER_SLOT_COMMITMENT='{}' must be processed, confirmed or finalized.",
+                self.geyser_slot_commitment
+            )));
+        }
+
+        // Parsed into an `OverflowPolicy` only once the sender is being built,
+        // after its background tasks are running
+        const OVERFLOW_POLICIES: [&str; 3] = ["block", "drop-oldest", "reject"];
+        let overflow = self.send_queue_overflow.trim().to_ascii_lowercase();
+        if !OVERFLOW_POLICIES.contains(&overflow.as_str()) {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "SEND_QUEUE_OVERFLOW='{}' must be block, drop-oldest or reject.",
+                self.send_queue_overflow
+            )));
+        }
+
+        // Parsed again into `LandingSlo`s once the sender is being built,
+        // after its background tasks are running
+        if let Some(ref spec) = self.landing_slos {
+            parse_slos(spec).map_err(|e| {
+                ScramjetError::ConfigValidationError(format!("LANDING_SLOS: {}.", e))
+            })?;
+        }
+
+        if let Some(ref spec) = self.send_rate_limit {
+            parse_rate(spec).map_err(|e| {
+                ScramjetError::ConfigValidationError(format!("SEND_RATE_LIMIT: {}.", e))
+            })?;
+        }
+
+        if self.send_retry_max_backoff_ms < self.send_retry_backoff_ms {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "SEND_RETRY_MAX_BACKOFF_MS={} must be >= SEND_RETRY_BACKOFF_MS={}.",
+                self.send_retry_max_backoff_ms, self.send_retry_backoff_ms
+            )));
+        }
+
+        // A rate of 0 would block every validator on its first failure
+        if !(self.auto_block_failure_rate > 0.0 && self.auto_block_failure_rate <= 1.0) {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "AUTO_BLOCK_FAILURE_RATE={} must be in (0, 1].",
+                self.auto_block_failure_rate
+            )));
+        }
+
+        if self.auto_block_min_samples == 0 || self.auto_block_window_secs == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "AUTO_BLOCK_MIN_SAMPLES and AUTO_BLOCK_WINDOW_SECS must be greater than 0.".into(),
+            ));
+        }
+
         // Max backoff must be >= initial backoff
         if self.geyser_max_reconnect_delay_ms < self.geyser_reconnect_delay_ms {
             return Err(ScramjetError::ConfigValidationError(format!(
@@ -119,9 +580,101 @@ impl Config {
             )));
         }
 
+        // Block engines drop bundles tipping less than 1000 lamports
+        if self.jito_tip_lamports < 1_000 {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "JITO_TIP_LAMPORTS={} is below the block engine minimum (1000).",
+                self.jito_tip_lamports
+            )));
+        }
+
+        if self.send_queue_capacity == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "SEND_QUEUE_CAPACITY=0 leaves no room for a single transaction.".into(),
+            ));
+        }
+
+        if self.dedupe_window_ms > 0 && self.dedupe_capacity == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "DEDUPE_CAPACITY=0 cannot remember a single signature.".into(),
+            ));
+        }
+
+        // CPU_SETSIZE: the most cores an affinity mask can hold
+        const MAX_CPU_CORES: usize = 1024;
+        if let Some(core) = self
+            .send_runtime_cores
+            .iter()
+            .find(|core| !matches!(core.parse::<usize>(), Ok(id) if id < MAX_CPU_CORES))
+        {
+            return Err(ScramjetError::ConfigValidationError(format!(
+                "SEND_RUNTIME_CORES entry '{}' must be a CPU core id below {}.",
+                core, MAX_CPU_CORES
+            )));
+        }
+        if !self.send_runtime_cores.is_empty() && self.send_runtime_threads == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "SEND_RUNTIME_CORES pins the send runtime: set SEND_RUNTIME_THREADS > 0.".into(),
+            ));
+        }
+
+        // A zero timeout would turn every policy query into the failure mode
+        if self.policy_timeout_ms == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "POLICY_TIMEOUT_MS=0 means every policy query times out.".into(),
+            ));
+        }
+
+        if let Some(ref url) = self.remote_signer_url {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(ScramjetError::ConfigValidationError(format!(
+                    "REMOTE_SIGNER_URL='{}' must be an http(s) URL.",
+                    url
+                )));
+            }
+        }
+        if self.remote_signer_timeout_ms == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "REMOTE_SIGNER_TIMEOUT_MS=0 means every signing request times out.".into(),
+            ));
+        }
+
+        // SLO window must cover at least one send
+        if self.slo_window_secs == 0 {
+            return Err(ScramjetError::ConfigValidationError(
+                "SLO_WINDOW_SECS=0 means SLOs are evaluated over no sends.".into(),
+            ));
+        }
+
         Ok(())
     }
 
+    /// Geyser endpoints feeding the slot clock: `GEYSER_URL` followed by the
+    /// redundant ones (duplicates removed; empty without `GEYSER_URL`)
+    pub fn geyser_urls(&self) -> Vec<String> {
+        let mut urls: Vec<String> = self.geyser_url.iter().cloned().collect();
+        if urls.is_empty() {
+            return urls;
+        }
+        for url in &self.geyser_redundant_urls {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        urls
+    }
+
+    /// Primary RPC endpoint followed by the fallbacks (duplicates removed)
+    pub fn rpc_urls(&self) -> Vec<String> {
+        let mut urls = vec![self.rpc_url.clone()];
+        for url in &self.rpc_fallback_urls {
+            if !urls.contains(url) {
+                urls.push(url.clone());
+            }
+        }
+        urls
+    }
+
     // --- Convenience methods returning Duration ---
 
     pub fn rpc_poll_interval(&self) -> Duration {
@@ -132,10 +685,33 @@ impl Config {
         Duration::from_millis(self.scout_interval_ms)
     }
 
+    /// Connection health probe interval (None = off)
+    pub fn health_check_interval(&self) -> Option<Duration> {
+        (self.health_check_interval_ms > 0)
+            .then(|| Duration::from_millis(self.health_check_interval_ms))
+    }
+
+    /// Validator RTT probe interval (None = off)
+    pub fn rtt_probe_interval(&self) -> Option<Duration> {
+        (self.rtt_probe_interval_ms > 0).then(|| Duration::from_millis(self.rtt_probe_interval_ms))
+    }
+
     pub fn monitor_interval(&self) -> Duration {
         Duration::from_millis(self.monitor_interval_ms)
     }
 
+    pub fn cluster_check_interval(&self) -> Duration {
+        Duration::from_millis(self.cluster_check_interval_ms)
+    }
+
+    pub fn blockhash_refresh_interval(&self) -> Duration {
+        Duration::from_millis(self.blockhash_refresh_ms)
+    }
+
+    pub fn rpc_demote_duration(&self) -> Duration {
+        Duration::from_secs(self.rpc_demote_secs)
+    }
+
     pub fn geyser_reconnect_delay(&self) -> Duration {
         Duration::from_millis(self.geyser_reconnect_delay_ms)
     }
@@ -144,6 +720,18 @@ impl Config {
         Duration::from_millis(self.geyser_max_reconnect_delay_ms)
     }
 
+    /// Keepalive ping interval on the Geyser stream (None = off)
+    pub fn geyser_ping_interval(&self) -> Option<Duration> {
+        (self.geyser_ping_interval_ms > 0)
+            .then(|| Duration::from_millis(self.geyser_ping_interval_ms))
+    }
+
+    /// Reconnect a Geyser stream with no slot update for this long (None = never)
+    pub fn geyser_stall_timeout(&self) -> Option<Duration> {
+        (self.geyser_stall_timeout_ms > 0)
+            .then(|| Duration::from_millis(self.geyser_stall_timeout_ms))
+    }
+
     pub fn quic_keep_alive(&self) -> Duration {
         Duration::from_secs(self.quic_keep_alive_secs)
     }
@@ -151,13 +739,172 @@ impl Config {
     pub fn quic_idle_timeout(&self) -> Duration {
         Duration::from_secs(self.quic_idle_timeout_secs)
     }
+
+    /// Deadline for opening, writing and finishing one stream (None = none)
+    pub fn quic_send_timeout(&self) -> Option<Duration> {
+        (self.quic_send_timeout_ms > 0).then(|| Duration::from_millis(self.quic_send_timeout_ms))
+    }
+
+    /// Unused connections are closed after this long (None = never)
+    pub fn quic_connection_ttl(&self) -> Option<Duration> {
+        (self.quic_connection_ttl_secs > 0)
+            .then(|| Duration::from_secs(self.quic_connection_ttl_secs))
+    }
+
+    /// Where the topology / leader schedule snapshot is kept: `TOPOLOGY_CACHE_FILE`,
+    /// else `$XDG_CACHE_HOME/scramjet/topology.json` (`~/.cache/...`).
+    /// None when set to `off` or no cache directory is known.
+    pub fn topology_cache_path(&self) -> Option<PathBuf> {
+        cache_file(self.topology_cache_file.as_deref(), "topology.json")
+    }
+
+    /// Where compute unit limits suggested per template are kept:
+    /// `COMPUTE_PROFILE_FILE`, else `$XDG_CACHE_HOME/scramjet/compute.json`.
+    /// None when set to `off` or no cache directory is known.
+    pub fn compute_profile_path(&self) -> Option<PathBuf> {
+        cache_file(self.compute_profile_file.as_deref(), "compute.json")
+    }
+
+    /// Where sent transactions are journaled: `JOURNAL_FILE`, else
+    /// `$XDG_CACHE_HOME/scramjet/journal.jsonl`. None when set to `off` or
+    /// no cache directory is known.
+    pub fn journal_path(&self) -> Option<PathBuf> {
+        cache_file(self.journal_file.as_deref(), "journal.jsonl")
+    }
+
+    pub fn journal_max_bytes(&self) -> u64 {
+        self.journal_max_mb.saturating_mul(1024 * 1024)
+    }
+
+    /// Local address for the QUIC client endpoint (None = dual-stack wildcard)
+    pub fn quic_bind_socket_addr(&self) -> Option<SocketAddr> {
+        self.quic_bind_addr.as_deref().and_then(parse_bind_addr)
+    }
+
+    /// Local addresses to bind client endpoints to: QUIC_BIND_ADDRS, or
+    /// QUIC_BIND_ADDR alone (empty = dual-stack default)
+    pub fn quic_bind_socket_addrs(&self) -> Vec<SocketAddr> {
+        match self.quic_bind_socket_addr() {
+            Some(addr) => vec![addr],
+            None => self
+                .quic_bind_addrs
+                .iter()
+                .filter_map(|addr| parse_bind_addr(addr))
+                .collect(),
+        }
+    }
+
+    /// CPU cores the send runtime's threads are pinned to (empty = unpinned)
+    pub fn send_runtime_core_ids(&self) -> Vec<usize> {
+        self.send_runtime_cores
+            .iter()
+            .filter_map(|core| core.parse().ok())
+            .collect()
+    }
+
+    /// Duplicate guard window (None = off)
+    pub fn dedupe_window(&self) -> Option<Duration> {
+        (self.dedupe_window_ms > 0).then(|| Duration::from_millis(self.dedupe_window_ms))
+    }
+
+    pub fn shutdown_drain_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_drain_ms)
+    }
+
+    pub fn send_retry_backoff(&self) -> Duration {
+        Duration::from_millis(self.send_retry_backoff_ms)
+    }
+
+    pub fn send_retry_max_backoff(&self) -> Duration {
+        Duration::from_millis(self.send_retry_max_backoff_ms)
+    }
+
+    pub fn auto_block_ttl(&self) -> Duration {
+        Duration::from_secs(self.auto_block_ttl_secs)
+    }
+
+    pub fn auto_block_window(&self) -> Duration {
+        Duration::from_secs(self.auto_block_window_secs)
+    }
+
+    pub fn slo_window(&self) -> Duration {
+        Duration::from_secs(self.slo_window_secs)
+    }
+
+    pub fn policy_timeout(&self) -> Duration {
+        Duration::from_millis(self.policy_timeout_ms)
+    }
+
+    pub fn policy_cache_ttl(&self) -> Duration {
+        Duration::from_millis(self.policy_cache_ms)
+    }
+
+    pub fn remote_signer_timeout(&self) -> Duration {
+        Duration::from_millis(self.remote_signer_timeout_ms)
+    }
+
+    /// Switch to a cluster preset (`--mainnet` / `--devnet` / `--testnet`):
+    /// its RPC endpoint and priority fee fill in whichever of the two was not
+    /// set explicitly, and the RPC node's genesis hash is checked against it
+    /// at startup. Fallback, websocket and Geyser endpoints are left as
+    /// configured.
+    pub fn apply_cluster(&mut self, cluster: Cluster) {
+        self.cluster = Some(cluster);
+        if !self.explicit_rpc_url {
+            self.rpc_url = cluster.rpc_url().into();
+        }
+        if !self.explicit_priority_fee {
+            self.default_priority_fee = cluster.default_priority_fee();
+        }
+    }
+
+    /// Copy the settings a running sender can pick up without a restart
+    /// (transaction defaults, rate limit, scout / health / poll intervals)
+    /// from `fresh`. Returns the names of the fields that changed; anything
+    /// else in `fresh` is ignored until the next start.
+    pub fn apply_tunables(&mut self, fresh: &Config) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        macro_rules! tune {
+            ($($field:ident),* $(,)?) => {$(
+                if self.$field != fresh.$field {
+                    self.$field = fresh.$field;
+                    changed.push(stringify!($field));
+                }
+            )*};
+        }
+        tune!(
+            default_priority_fee,
+            default_compute_unit_limit,
+            jito_tip_lamports,
+            send_rate_burst,
+            rpc_poll_interval_ms,
+            scout_interval_ms,
+            scout_lookahead_slots,
+            scout_max_warm_connections,
+            health_check_interval_ms,
+            health_check_lookahead_slots,
+            rtt_probe_interval_ms,
+            rtt_probe_batch,
+            monitor_interval_ms,
+            cluster_check_interval_ms,
+        );
+        if self.send_rate_limit != fresh.send_rate_limit {
+            self.send_rate_limit = fresh.send_rate_limit.clone();
+            changed.push("send_rate_limit");
+        }
+        changed
+    }
 }
 
-/// Helper to parse env var with default fallback.
+/// Helper to parse a config value with default fallback.
 /// Logs a warning if the value exists but fails to parse.
-fn parse_env<T: std::str::FromStr + std::fmt::Display>(key: &str, default: T) -> T {
-    match env::var(key) {
-        Ok(v) => match v.parse() {
+fn parse_value<T: std::str::FromStr + std::fmt::Display>(
+    lookup: &dyn Fn(&str) -> Option<String>,
+    key: &str,
+    default: T,
+) -> T {
+    match lookup(key) {
+        Some(v) => match v.parse() {
             Ok(parsed) => parsed,
             Err(_) => {
                 eprintln!(
@@ -167,8 +914,168 @@ fn parse_env<T: std::str::FromStr + std::fmt::Display>(key: &str, default: T) ->
                 default
             }
         },
-        Err(_) => default,
+        None => default,
+    }
+}
+
+/// Helper to parse a comma-separated list (empty entries are skipped)
+fn parse_list(lookup: &dyn Fn(&str) -> Option<String>, key: &str) -> Vec<String> {
+    lookup(key)
+        .map(|v| {
+            v.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parse a rate spec (`200/s`, `50/100ms`, `1000/m`; bare numbers are per
+/// second) into sends per second, between `MIN_SEND_RATE` and
+/// `MAX_SEND_RATE`
+pub fn parse_rate(spec: &str) -> Result<f64, String> {
+    let invalid = || {
+        format!(
+            "invalid rate '{}' (expected e.g. 200/s, 50/100ms, 1000/m)",
+            spec
+        )
+    };
+    let (count, per) = match spec.trim().split_once('/') {
+        Some((count, per)) => (count.trim(), per.trim()),
+        None => (spec.trim(), "s"),
+    };
+    let count: f64 = count.parse().map_err(|_| invalid())?;
+    let unit_at = per.find(|c: char| !c.is_ascii_digit()).unwrap_or(per.len());
+    let (amount, unit) = per.split_at(unit_at);
+    let amount: u64 = if amount.is_empty() {
+        1
+    } else {
+        amount.parse().map_err(|_| invalid())?
+    };
+    let period = match unit {
+        "ms" => Duration::from_millis(amount),
+        "s" => Duration::from_secs(amount),
+        "m" => Duration::from_secs(amount.checked_mul(60).ok_or_else(invalid)?),
+        _ => return Err(invalid()),
+    };
+    if !(count.is_finite() && count > 0.0) || period.is_zero() {
+        return Err(invalid());
+    }
+    let rate = count / period.as_secs_f64();
+    if !(MIN_SEND_RATE..=MAX_SEND_RATE).contains(&rate) {
+        return Err(format!(
+            "rate '{}' must be between 1/3600s and {}/s",
+            spec, MAX_SEND_RATE
+        ));
+    }
+    Ok(rate)
+}
+
+/// Parse a comma-separated landing SLO list such as `95%@3,99%@10` into
+/// (target fraction in (0, 1], within slots) pairs
+pub fn parse_slos(spec: &str) -> Result<Vec<(f64, u64)>, String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|entry| {
+            let invalid = || format!("Invalid SLO '{}' (expected e.g. 95%@3)", entry);
+            let (pct, slots) = entry.split_once('@').ok_or_else(invalid)?;
+            let pct: f64 = pct
+                .trim()
+                .trim_end_matches('%')
+                .parse()
+                .map_err(|_| invalid())?;
+            let within_slots: u64 = slots.trim().parse().map_err(|_| invalid())?;
+            if !(pct > 0.0 && pct <= 100.0) {
+                return Err(invalid());
+            }
+            Ok((pct / 100.0, within_slots))
+        })
+        .collect()
+}
+
+/// `ip` (ephemeral port) or `ip:port`; IPv6 with a port is `[ip]:port`
+fn parse_bind_addr(value: &str) -> Option<SocketAddr> {
+    let value = value.trim();
+    value.parse::<SocketAddr>().ok().or_else(|| {
+        value
+            .parse::<IpAddr>()
+            .ok()
+            .map(|ip| SocketAddr::new(ip, 0))
+    })
+}
+
+/// A cache file setting: the path given, None for `off`, or `name` in
+/// `$XDG_CACHE_HOME/scramjet` (`~/.cache/...`) when unset
+fn cache_file(setting: Option<&str>, name: &str) -> Option<PathBuf> {
+    match setting {
+        Some("off") | Some("") => None,
+        Some(path) => Some(PathBuf::from(path)),
+        None => env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .map(|dir| dir.join("scramjet").join(name)),
+    }
+}
+
+/// Flatten a config file into lowercase key -> string value, with the
+/// selected profile (explicit, or the file's `profile` key) layered on top
+fn file_values(contents: &str, profile: Option<&str>) -> Result<HashMap<String, String>, String> {
+    let mut root = match contents.parse::<toml::Value>().map_err(|e| e.to_string())? {
+        toml::Value::Table(table) => table,
+        _ => return Err("expected a table".into()),
+    };
+
+    let default_profile = match root.remove("profile") {
+        Some(toml::Value::String(name)) => Some(name),
+        Some(_) => return Err("`profile` must be a string".into()),
+        None => None,
+    };
+    let mut profiles = match root.remove("profiles") {
+        Some(toml::Value::Table(profiles)) => profiles,
+        Some(_) => return Err("`profiles` must be a table".into()),
+        None => toml::value::Table::new(),
+    };
+
+    let mut values = HashMap::new();
+    flatten_into(&mut values, root)?;
+    if let Some(name) = profile.map(str::to_string).or(default_profile) {
+        match profiles.remove(&name) {
+            Some(toml::Value::Table(table)) => flatten_into(&mut values, table)?,
+            Some(_) => return Err(format!("profile `{}` must be a table", name)),
+            None => {
+                let mut known: Vec<&String> = profiles.keys().collect();
+                known.sort_unstable();
+                return Err(format!("unknown profile `{}` (defined: {:?})", name, known));
+            }
+        }
+    }
+    Ok(values)
+}
+
+fn flatten_into(
+    values: &mut HashMap<String, String>,
+    table: toml::value::Table,
+) -> Result<(), String> {
+    for (key, value) in table {
+        let value = match value {
+            toml::Value::String(s) => s,
+            toml::Value::Integer(i) => i.to_string(),
+            toml::Value::Float(f) => f.to_string(),
+            toml::Value::Boolean(b) => b.to_string(),
+            other => {
+                return Err(format!(
+                    "`{}` must be a scalar, got {}",
+                    key,
+                    other.type_str()
+                ))
+            }
+        };
+        values.insert(key.to_ascii_lowercase(), value);
     }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -180,16 +1087,92 @@ mod tests {
     static TEST_LOCK: Mutex<()> = Mutex::new(());
 
     fn clear_env_vars() {
+        env::remove_var("CLUSTER");
         env::remove_var("SOLANA_RPC_URL");
+        env::remove_var("SOLANA_RPC_FALLBACK_URLS");
+        env::remove_var("RPC_DEMOTE_AFTER_FAILURES");
+        env::remove_var("RPC_DEMOTE_SECS");
         env::remove_var("GEYSER_URL");
+        env::remove_var("GEYSER_REDUNDANT_URLS");
+        env::remove_var("SOLANA_WS_URL");
+        env::remove_var("GEYSER_BLOCKS_META");
+        env::remove_var("GEYSER_TX_STATUS");
+        env::remove_var("GEYSER_SLOT_COMMITMENT");
+        env::remove_var("GEYSER_INTERSLOT_UPDATES");
+        env::remove_var("GEYSER_ENTRIES");
+        env::remove_var("SCRAMJET_CONTROL_SOCKET");
         env::remove_var("RPC_POLL_INTERVAL_MS");
         env::remove_var("SCOUT_INTERVAL_MS");
+        env::remove_var("SCOUT_LOOKAHEAD_SLOTS");
+        env::remove_var("SCOUT_MAX_WARM_CONNECTIONS");
+        env::remove_var("HEALTH_CHECK_INTERVAL_MS");
+        env::remove_var("HEALTH_CHECK_LOOKAHEAD_SLOTS");
+        env::remove_var("RTT_PROBE_INTERVAL_MS");
+        env::remove_var("RTT_PROBE_BATCH");
         env::remove_var("MONITOR_INTERVAL_MS");
+        env::remove_var("CLUSTER_CHECK_INTERVAL_MS");
+        env::remove_var("SCHEDULE_PREFETCH_SLOTS");
+        env::remove_var("BLOCKHASH_REFRESH_MS");
         env::remove_var("DEFAULT_COMPUTE_UNIT_LIMIT");
+        env::remove_var("CHECK_FUNDS");
         env::remove_var("QUIC_KEEP_ALIVE_SECS");
         env::remove_var("QUIC_IDLE_TIMEOUT_SECS");
+        env::remove_var("QUIC_CONNECTIONS_PER_LEADER");
+        env::remove_var("QUIC_IDENTITY_FAILOVER_THRESHOLD");
+        env::remove_var("QUIC_BIND_ADDR");
+        env::remove_var("QUIC_BIND_ADDRS");
+        env::remove_var("QUIC_SESSION_CACHE");
+        env::remove_var("QUIC_MAX_CONNECTIONS");
+        env::remove_var("QUIC_CONNECTION_TTL_SECS");
+        env::remove_var("QUIC_SEND_TIMEOUT_MS");
+        env::remove_var("QUIC_ZERO_RTT");
+        env::remove_var("QUIC_DATAGRAMS");
+        env::remove_var("QUIC_STREAM_BUDGET");
+        env::remove_var("QUIC_SEND_BUFFER_BYTES");
+        env::remove_var("QUIC_RECV_BUFFER_BYTES");
+        env::remove_var("QUIC_SEGMENTATION_OFFLOAD");
+        env::remove_var("TOPOLOGY_CACHE_FILE");
+        env::remove_var("COMPUTE_PROFILE_FILE");
+        env::remove_var("JOURNAL_FILE");
+        env::remove_var("JOURNAL_MAX_MB");
+        env::remove_var("STATS_FILE");
+        env::remove_var("GEOIP_DB_FILES");
+        env::remove_var("RELAY_PEERS");
+        env::remove_var("SHUTDOWN_DRAIN_MS");
         env::remove_var("GEYSER_RECONNECT_DELAY_MS");
+        env::remove_var("GEYSER_PING_INTERVAL_MS");
+        env::remove_var("GEYSER_STALL_TIMEOUT_MS");
         env::remove_var("GEYSER_MAX_RECONNECT_DELAY_MS");
+        env::remove_var("LANDING_SLOS");
+        env::remove_var("SEND_RATE_LIMIT");
+        env::remove_var("SEND_RATE_BURST");
+        env::remove_var("SEND_QUEUE_CAPACITY");
+        env::remove_var("SEND_QUEUE_OVERFLOW");
+        env::remove_var("DEDUPE_WINDOW_MS");
+        env::remove_var("DEDUPE_CAPACITY");
+        env::remove_var("SEND_RUNTIME_THREADS");
+        env::remove_var("SEND_RUNTIME_CORES");
+        env::remove_var("DEFAULT_PRIORITY_FEE");
+        env::remove_var("JITO_BLOCK_ENGINE_URL");
+        env::remove_var("JITO_TIP_LAMPORTS");
+        env::remove_var("SEND_RETRY_MAX_ATTEMPTS");
+        env::remove_var("SEND_RETRY_BACKOFF_MS");
+        env::remove_var("SEND_RETRY_MAX_BACKOFF_MS");
+        env::remove_var("SEND_RETRY_ON");
+        env::remove_var("SLO_WINDOW_SECS");
+        env::remove_var("SLO_MIN_SAMPLES");
+        env::remove_var("ALERT_WEBHOOK_URL");
+        env::remove_var("AUTO_BLOCK_TTL_SECS");
+        env::remove_var("AUTO_BLOCK_FAILURE_RATE");
+        env::remove_var("AUTO_BLOCK_MIN_SAMPLES");
+        env::remove_var("AUTO_BLOCK_WINDOW_SECS");
+        env::remove_var("POLICY_SOCKET");
+        env::remove_var("POLICY_TIMEOUT_MS");
+        env::remove_var("POLICY_CACHE_MS");
+        env::remove_var("POLICY_FAIL_OPEN");
+        env::remove_var("REMOTE_SIGNER_URL");
+        env::remove_var("REMOTE_SIGNER_TOKEN");
+        env::remove_var("REMOTE_SIGNER_TIMEOUT_MS");
     }
 
     #[test]
@@ -249,4 +1232,370 @@ mod tests {
         let err = result.unwrap_err().to_string();
         assert!(err.contains("must be less than"));
     }
+
+    #[test]
+    fn test_config_zero_rtt_requires_session_cache() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("QUIC_ZERO_RTT", "true");
+        let config = Config::from_env().unwrap();
+        assert!(config.quic_zero_rtt);
+        assert_eq!(config.quic_session_cache, 1024);
+
+        env::set_var("QUIC_SESSION_CACHE", "0");
+        let result = Config::from_env();
+        env::remove_var("QUIC_ZERO_RTT");
+        env::remove_var("QUIC_SESSION_CACHE");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("QUIC_SESSION_CACHE"));
+    }
+
+    #[test]
+    fn test_config_validation_retry_classes() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("SEND_RETRY_ON", "Connection, stream");
+        assert!(Config::from_env().is_ok());
+        env::set_var("SEND_RETRY_ON", "connection,streams");
+        let result = Config::from_env();
+        env::remove_var("SEND_RETRY_ON");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("SEND_RETRY_ON entry 'streams'"));
+    }
+
+    #[test]
+    fn test_config_validation_blockhash_refresh() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        assert_eq!(
+            Config::from_env().unwrap().blockhash_refresh_interval(),
+            Duration::from_secs(2)
+        );
+        env::set_var("BLOCKHASH_REFRESH_MS", "100");
+        let result = Config::from_env();
+        env::remove_var("BLOCKHASH_REFRESH_MS");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("BLOCKHASH_REFRESH_MS=100"));
+        assert!(err.contains("getLatestBlockhash"));
+    }
+
+    #[test]
+    fn test_config_validation_slot_commitment() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("GEYSER_SLOT_COMMITMENT", "Confirmed");
+        assert!(Config::from_env().is_ok());
+        env::set_var("GEYSER_SLOT_COMMITMENT", "finalised");
+        let result = Config::from_env();
+        env::remove_var("GEYSER_SLOT_COMMITMENT");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("GEYSER_SLOT_COMMITMENT='finalised'"));
+    }
+
+    #[test]
+    fn test_config_validation_send_queue_overflow() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("SEND_QUEUE_OVERFLOW", "Drop-Oldest");
+        assert!(Config::from_env().is_ok());
+        env::set_var("SEND_QUEUE_OVERFLOW", "drop_oldest");
+        let result = Config::from_env();
+        env::remove_var("SEND_QUEUE_OVERFLOW");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("SEND_QUEUE_OVERFLOW='drop_oldest'"));
+    }
+
+    #[test]
+    fn test_config_validation_landing_slos() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("LANDING_SLOS", "95%@3, 99@10");
+        assert!(Config::from_env().is_ok());
+        env::set_var("LANDING_SLOS", "95%@3,99%");
+        let result = Config::from_env();
+        env::remove_var("LANDING_SLOS");
+
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("LANDING_SLOS: Invalid SLO '99%'"));
+    }
+
+    #[test]
+    fn test_config_validation_send_rate_limit() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("SEND_RATE_LIMIT", "50/100ms");
+        assert!(Config::from_env().is_ok());
+        for bad in ["0/s", "-5/s", "fast", "5000000/s", "1/999999999999999999m"] {
+            env::set_var("SEND_RATE_LIMIT", bad);
+            let err = Config::from_env().unwrap_err().to_string();
+            assert!(err.contains("SEND_RATE_LIMIT"), "{}: {}", bad, err);
+        }
+        env::remove_var("SEND_RATE_LIMIT");
+    }
+
+    #[test]
+    fn test_config_rpc_fallback_urls() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("SOLANA_RPC_URL", "https://a.example");
+        env::set_var(
+            "SOLANA_RPC_FALLBACK_URLS",
+            " https://b.example,,https://a.example, https://c.example",
+        );
+        let config = Config::from_env().unwrap();
+        clear_env_vars();
+
+        assert_eq!(
+            config.rpc_urls(),
+            vec![
+                "https://a.example",
+                "https://b.example",
+                "https://c.example"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_geyser_urls() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("GEYSER_REDUNDANT_URLS", "http://b:10000, http://a:10000");
+        assert!(Config::from_env().unwrap().geyser_urls().is_empty());
+        env::set_var("GEYSER_URL", "http://a:10000");
+        let config = Config::from_env().unwrap();
+        clear_env_vars();
+
+        assert_eq!(
+            config.geyser_urls(),
+            vec!["http://a:10000", "http://b:10000"]
+        );
+    }
+
+    #[test]
+    fn test_config_quic_bind_addr() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        assert_eq!(Config::from_env().unwrap().quic_bind_socket_addr(), None);
+        for (value, expected) in [
+            ("10.0.0.5", "10.0.0.5:0"),
+            ("10.0.0.5:9000", "10.0.0.5:9000"),
+            ("::", "[::]:0"),
+            ("[2001:db8::1]:9000", "[2001:db8::1]:9000"),
+        ] {
+            env::set_var("QUIC_BIND_ADDR", value);
+            let config = Config::from_env().unwrap();
+            assert_eq!(
+                config.quic_bind_socket_addr(),
+                Some(expected.parse().unwrap())
+            );
+        }
+
+        env::set_var("QUIC_BIND_ADDR", "eth0");
+        let result = Config::from_env();
+        clear_env_vars();
+        assert!(result.unwrap_err().to_string().contains("QUIC_BIND_ADDR"));
+    }
+
+    #[test]
+    fn test_config_quic_bind_addrs() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        assert!(Config::from_env()
+            .unwrap()
+            .quic_bind_socket_addrs()
+            .is_empty());
+        env::set_var("QUIC_BIND_ADDRS", "10.0.0.5, 10.0.1.5:9000,");
+        assert_eq!(
+            Config::from_env().unwrap().quic_bind_socket_addrs(),
+            vec![
+                "10.0.0.5:0".parse::<SocketAddr>().unwrap(),
+                "10.0.1.5:9000".parse().unwrap()
+            ]
+        );
+
+        env::set_var("QUIC_BIND_ADDRS", "10.0.0.5,eth1");
+        let bad_entry = Config::from_env();
+        env::set_var("QUIC_BIND_ADDRS", "10.0.0.5");
+        env::set_var("QUIC_BIND_ADDR", "10.0.0.6");
+        let both = Config::from_env();
+        clear_env_vars();
+        assert!(bad_entry.unwrap_err().to_string().contains("'eth1'"));
+        assert!(both.unwrap_err().to_string().contains("not both"));
+    }
+
+    #[test]
+    fn test_config_send_runtime() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.send_runtime_threads, 0);
+        assert!(config.send_runtime_core_ids().is_empty());
+
+        env::set_var("SEND_RUNTIME_THREADS", "2");
+        env::set_var("SEND_RUNTIME_CORES", "3,5");
+        assert_eq!(
+            Config::from_env().unwrap().send_runtime_core_ids(),
+            vec![3, 5]
+        );
+
+        env::set_var("SEND_RUNTIME_CORES", "3,cpu5");
+        let bad_core = Config::from_env();
+        env::set_var("SEND_RUNTIME_CORES", "3");
+        env::set_var("SEND_RUNTIME_THREADS", "0");
+        let unpinnable = Config::from_env();
+        clear_env_vars();
+        assert!(bad_core.unwrap_err().to_string().contains("'cpu5'"));
+        assert!(unpinnable
+            .unwrap_err()
+            .to_string()
+            .contains("SEND_RUNTIME_THREADS"));
+    }
+
+    #[test]
+    fn test_config_topology_cache_path() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        env::set_var("TOPOLOGY_CACHE_FILE", "/var/cache/scramjet.json");
+        assert_eq!(
+            Config::from_env().unwrap().topology_cache_path(),
+            Some(PathBuf::from("/var/cache/scramjet.json"))
+        );
+        env::set_var("TOPOLOGY_CACHE_FILE", "off");
+        assert_eq!(Config::from_env().unwrap().topology_cache_path(), None);
+        env::remove_var("TOPOLOGY_CACHE_FILE");
+        if let Some(path) = Config::from_env().unwrap().topology_cache_path() {
+            assert!(path.ends_with("scramjet/topology.json"));
+        }
+
+        env::set_var("COMPUTE_PROFILE_FILE", "off");
+        assert_eq!(Config::from_env().unwrap().compute_profile_path(), None);
+        env::remove_var("COMPUTE_PROFILE_FILE");
+        if let Some(path) = Config::from_env().unwrap().compute_profile_path() {
+            assert!(path.ends_with("scramjet/compute.json"));
+        }
+
+        env::set_var("JOURNAL_FILE", "/var/log/scramjet.jsonl");
+        env::set_var("JOURNAL_MAX_MB", "8");
+        let config = Config::from_env().unwrap();
+        assert_eq!(
+            config.journal_path(),
+            Some(PathBuf::from("/var/log/scramjet.jsonl"))
+        );
+        assert_eq!(config.journal_max_bytes(), 8 * 1024 * 1024);
+        env::set_var("JOURNAL_FILE", "off");
+        assert_eq!(Config::from_env().unwrap().journal_path(), None);
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_config_cluster_preset() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        assert_eq!(Config::from_env().unwrap().cluster, None);
+
+        // The preset fills in defaults; explicit values still win
+        env::set_var("CLUSTER", "devnet");
+        let config = Config::from_env().unwrap();
+        assert_eq!(config.cluster, Some(Cluster::Devnet));
+        assert_eq!(config.rpc_url, "https://api.devnet.solana.com");
+        assert_eq!(config.default_priority_fee, 1_000);
+        env::set_var("SOLANA_RPC_URL", "https://devnet.example");
+        env::set_var("DEFAULT_PRIORITY_FEE", "5000");
+        let mut config = Config::from_env().unwrap();
+        assert_eq!(config.rpc_url, "https://devnet.example");
+        assert_eq!(config.default_priority_fee, 5_000);
+
+        // Switching presets keeps explicit values, and replaces the rest
+        config.apply_cluster(Cluster::Mainnet);
+        assert_eq!(config.cluster, Some(Cluster::Mainnet));
+        assert_eq!(config.rpc_url, "https://devnet.example");
+        assert_eq!(config.default_priority_fee, 5_000);
+        env::remove_var("SOLANA_RPC_URL");
+        let mut config = Config::from_env().unwrap();
+        config.apply_cluster(Cluster::Mainnet);
+        assert_eq!(config.rpc_url, "https://api.mainnet-beta.solana.com");
+        assert_eq!(config.default_priority_fee, 5_000);
+
+        // A value that does not parse leaves the fee to the preset
+        env::set_var("DEFAULT_PRIORITY_FEE", "5k");
+        let mut config = Config::from_env().unwrap();
+        assert_eq!(config.default_priority_fee, 1_000);
+        config.apply_cluster(Cluster::Mainnet);
+        assert_eq!(
+            config.default_priority_fee,
+            Cluster::Mainnet.default_priority_fee()
+        );
+        env::remove_var("DEFAULT_PRIORITY_FEE");
+
+        env::set_var("CLUSTER", "localnet");
+        assert!(Config::from_env().is_err());
+        clear_env_vars();
+    }
+
+    #[test]
+    fn test_config_file_profiles_and_env_override() {
+        let _lock = TEST_LOCK.lock().unwrap();
+        clear_env_vars();
+
+        let path = env::temp_dir().join(format!("scramjet-config-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"
+profile = "mainnet"
+scout_interval_ms = 2000
+
+[profiles.mainnet]
+solana_rpc_url = "https://mainnet.example"
+
+[profiles.devnet]
+solana_rpc_url = "https://devnet.example"
+policy_fail_open = true
+"#,
+        )
+        .unwrap();
+
+        // Default profile from the file, top-level keys shared
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.rpc_url, "https://mainnet.example");
+        assert_eq!(config.scout_interval_ms, 2000);
+        assert!(!config.policy_fail_open);
+
+        let config = Config::from_file_with_profile(&path, Some("devnet")).unwrap();
+        assert_eq!(config.rpc_url, "https://devnet.example");
+        assert!(config.policy_fail_open);
+
+        // Env beats the file
+        env::set_var("SCOUT_INTERVAL_MS", "3000");
+        let config = Config::from_file_with_profile(&path, Some("devnet")).unwrap();
+        env::remove_var("SCOUT_INTERVAL_MS");
+        assert_eq!(config.scout_interval_ms, 3000);
+
+        let err = Config::from_file_with_profile(&path, Some("testnet")).unwrap_err();
+        assert!(err.to_string().contains("unknown profile"));
+
+        std::fs::write(&path, "scout_intervl_ms = 2000\n").unwrap();
+        let err = Config::from_file(&path).unwrap_err();
+        assert!(err.to_string().contains("scout_intervl_ms"));
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
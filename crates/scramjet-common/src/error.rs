@@ -1,3 +1,6 @@
+use crate::packet::OversizedTransaction;
+use std::fmt;
+use std::net::SocketAddr;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -13,6 +16,8 @@ pub enum ScramjetError {
     CertError(String),
     #[error("Keypair error: {0}")]
     KeypairError(String),
+    #[error("Signer error: {0}")]
+    SignerError(String),
     #[error("Home directory not found")]
     HomeDirNotFound,
 
@@ -27,6 +32,14 @@ pub enum ScramjetError {
     InvalidUri(String),
     #[error("Serialization error: {0}")]
     SerializationError(String),
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("Invalid instruction: {0}")]
+    InvalidInstruction(String),
+    /// Over the packet limit (see [`crate::packet`]); boxed, it carries the
+    /// largest instruction
+    #[error("{0}")]
+    TransactionTooLarge(Box<OversizedTransaction>),
 
     // --- QUIC/Transport ---
     #[error("Connection error: {0}")]
@@ -39,6 +52,9 @@ pub enum ScramjetError {
     ClosedStreamError(#[from] quinn::ClosedStream),
     #[error("Stream error: {0}")]
     StreamError(String),
+    /// A send to a leader failed, classified (see [`SendFailureKind`])
+    #[error("{0}")]
+    SendFailed(Box<SendFailure>),
 
     // --- gRPC/Tonic (boxed to reduce Result size) ---
     #[error("gRPC transport error: {0}")]
@@ -52,6 +68,10 @@ pub enum ScramjetError {
     #[error("Geyser stream closed unexpectedly")]
     GeyserStreamClosed,
 
+    // --- Websocket (slotSubscribe) ---
+    #[error("Websocket error: {0}")]
+    WebsocketError(String),
+
     // --- RPC/Solana Client (boxed - 224 bytes otherwise) ---
     #[error("RPC error: {0}")]
     RpcError(String),
@@ -63,15 +83,49 @@ pub enum ScramjetError {
     NoLeaderFound(u64),
     #[error("Leader schedule unavailable")]
     ScheduleUnavailable,
+    #[error("Wrong cluster: {0}")]
+    ClusterMismatch(String),
+
+    // --- Shield ---
+    #[error("Blocklist verification failed: {0}")]
+    BlocklistVerification(String),
+    #[error("Invalid blocklist entry: {0}")]
+    InvalidBlocklistEntry(String),
+
+    // --- Deadlines ---
+    #[error("Transaction expired before sending: {0}")]
+    DeadlineExpired(String),
+
+    // --- Deduplication ---
+    #[error("Duplicate transaction: {0}")]
+    DuplicateTransaction(String),
+
+    // --- Funding ---
+    #[error("Insufficient funds: {0}")]
+    InsufficientFunds(String),
+
+    // --- Durable Nonce ---
+    #[error("Nonce error: {0}")]
+    NonceError(String),
 
     // --- Async/Channel ---
     #[error("Channel error: {0}")]
     ChannelError(String),
+    #[error("Send queue full: {0}")]
+    QueueFull(String),
     #[error("Startup timeout")]
     StartupTimeout,
+    #[error("Shutting down")]
+    ShuttingDown,
 }
 
 // Manual From implementations for boxed types
+impl From<SendFailure> for ScramjetError {
+    fn from(failure: SendFailure) -> Self {
+        ScramjetError::SendFailed(Box::new(failure))
+    }
+}
+
 impl From<tonic::Status> for ScramjetError {
     fn from(err: tonic::Status) -> Self {
         ScramjetError::GrpcStatusError(Box::new(err))
@@ -83,3 +137,154 @@ impl From<solana_client::client_error::ClientError> for ScramjetError {
         ScramjetError::SolanaClientError(Box::new(err))
     }
 }
+
+impl ScramjetError {
+    /// The classified send failure, if this is one
+    pub fn send_failure(&self) -> Option<&SendFailure> {
+        match self {
+            ScramjetError::SendFailed(failure) => Some(failure),
+            _ => None,
+        }
+    }
+
+    /// Record the slot the target was resolved for (send failures only)
+    pub fn with_slot(mut self, slot: u64) -> Self {
+        if let ScramjetError::SendFailed(ref mut failure) = self {
+            failure.slot = Some(slot);
+        }
+        self
+    }
+}
+
+/// How a send to a leader failed. Every kind but `Protocol` is worth
+/// another attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SendFailureKind {
+    /// No stream could be opened on the connection
+    StreamRefused,
+    /// The validator stopped the stream (STOP_SENDING)
+    StreamStopped,
+    /// The stream was not opened, written and finished within
+    /// `QUIC_SEND_TIMEOUT_MS` (a hung stream on a misbehaving leader)
+    StreamTimeout,
+    /// An established connection was closed, reset or went idle
+    ConnectionLost,
+    /// The handshake got no answer in time
+    HandshakeTimeout,
+    /// The TLS handshake failed: the validator refused our certificate (a
+    /// retry may fail over to the secondary identity)
+    CertRejected,
+    /// Version mismatch, protocol violation, unusable address or local
+    /// misuse: another attempt fails the same way
+    Protocol,
+}
+
+impl SendFailureKind {
+    pub fn is_retryable(self) -> bool {
+        self != SendFailureKind::Protocol
+    }
+
+    /// Failed on a stream rather than on the connection under it
+    pub fn is_stream(self) -> bool {
+        matches!(
+            self,
+            SendFailureKind::StreamRefused
+                | SendFailureKind::StreamStopped
+                | SendFailureKind::StreamTimeout
+        )
+    }
+
+    /// Kind of a connection error (`handshake`: before the connection was
+    /// established)
+    pub fn of_connection(err: &quinn::ConnectionError, handshake: bool) -> Self {
+        use quinn::ConnectionError::*;
+        match err {
+            ConnectionClosed(close) if is_crypto(close.error_code) => SendFailureKind::CertRejected,
+            TransportError(e) if is_crypto(e.code) => SendFailureKind::CertRejected,
+            TimedOut if handshake => SendFailureKind::HandshakeTimeout,
+            ConnectionClosed(_) | ApplicationClosed(_) | Reset | TimedOut | LocallyClosed => {
+                SendFailureKind::ConnectionLost
+            }
+            VersionMismatch | TransportError(_) | CidsExhausted => SendFailureKind::Protocol,
+        }
+    }
+
+    /// Kind of a stream write error
+    pub fn of_write(err: &quinn::WriteError) -> Self {
+        match err {
+            quinn::WriteError::Stopped(_) => SendFailureKind::StreamStopped,
+            quinn::WriteError::ConnectionLost(e) => Self::of_connection(e, false),
+            quinn::WriteError::ZeroRttRejected => SendFailureKind::ConnectionLost,
+            quinn::WriteError::ClosedStream => SendFailureKind::Protocol,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SendFailureKind::StreamRefused => "stream-refused",
+            SendFailureKind::StreamStopped => "stream-stopped",
+            SendFailureKind::StreamTimeout => "stream-timeout",
+            SendFailureKind::ConnectionLost => "connection-lost",
+            SendFailureKind::HandshakeTimeout => "handshake-timeout",
+            SendFailureKind::CertRejected => "cert-rejected",
+            SendFailureKind::Protocol => "protocol",
+        }
+    }
+}
+
+impl fmt::Display for SendFailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// TLS alerts travel as QUIC CRYPTO_ERROR codes (0x100-0x1ff)
+fn is_crypto(code: quinn::TransportErrorCode) -> bool {
+    (0x100..0x200).contains(&u64::from(code))
+}
+
+/// A failed send: what happened, to which leader, for which slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendFailure {
+    pub kind: SendFailureKind,
+    /// Leader TPU (QUIC) address
+    pub target: SocketAddr,
+    /// Slot the target was resolved for (None when sent to an address directly)
+    pub slot: Option<u64>,
+    pub message: String,
+}
+
+impl SendFailure {
+    pub fn new(kind: SendFailureKind, target: SocketAddr, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            target,
+            slot: None,
+            message: message.into(),
+        }
+    }
+
+    /// The handshake with `target` failed
+    pub fn handshake(target: SocketAddr, err: &quinn::ConnectionError) -> Self {
+        Self::new(
+            SendFailureKind::of_connection(err, true),
+            target,
+            err.to_string(),
+        )
+    }
+
+    /// Writing to `target` failed
+    pub fn write(target: SocketAddr, err: &quinn::WriteError) -> Self {
+        Self::new(SendFailureKind::of_write(err), target, err.to_string())
+    }
+}
+
+impl fmt::Display for SendFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Send to {} failed ({}", self.target, self.kind)?;
+        if let Some(slot) = self.slot {
+            write!(f, ", slot {}", slot)?;
+        }
+        write!(f, "): {}", self.message)
+    }
+}
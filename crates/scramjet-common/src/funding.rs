@@ -0,0 +1,155 @@
+use crate::error::ScramjetError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+
+/// Base fee charged per transaction signature
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Fee a transaction pays: the base fee per signature plus its priority fee
+/// (`compute_unit_price` microlamports for each of `compute_unit_limit` CUs)
+pub fn transaction_fee(signatures: usize, compute_unit_limit: u32, compute_unit_price: u64) -> u64 {
+    let priority = (compute_unit_limit as u128 * compute_unit_price as u128).div_ceil(1_000_000);
+    (signatures as u64)
+        .saturating_mul(LAMPORTS_PER_SIGNATURE)
+        .saturating_add(u64::try_from(priority).unwrap_or(u64::MAX))
+}
+
+/// Balances a funding check is decided on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FundingSnapshot {
+    pub payer_balance: u64,
+    /// Rent-exempt minimum for the payer's account size
+    pub payer_rent_minimum: u64,
+    /// None when the recipient is the payer
+    pub recipient_balance: Option<u64>,
+    /// Rent-exempt minimum for a new (data-less) account
+    pub new_account_rent_minimum: u64,
+}
+
+/// Whether a transfer of `lamports` to the recipient, paying `fee`, can
+/// succeed: the payer covers both and is left rent-exempt (or empty), and a
+/// recipient that does not exist yet is created rent-exempt
+pub fn check_funding(
+    snapshot: &FundingSnapshot,
+    payer: &Pubkey,
+    recipient: &Pubkey,
+    fee: u64,
+    lamports: u64,
+) -> Result<(), ScramjetError> {
+    let needed = fee.saturating_add(lamports);
+    if snapshot.payer_balance < needed {
+        return Err(ScramjetError::InsufficientFunds(format!(
+            "{} has {} lamports, needs {} (fee {} + transfer {})",
+            payer, snapshot.payer_balance, needed, fee, lamports
+        )));
+    }
+    // A transfer to the payer itself comes straight back
+    let spent = match snapshot.recipient_balance {
+        Some(_) => needed,
+        None => fee,
+    };
+    let left = snapshot.payer_balance - spent;
+    if left > 0 && left < snapshot.payer_rent_minimum {
+        return Err(ScramjetError::InsufficientFunds(format!(
+            "{} would be left with {} lamports, below its rent-exempt minimum of {}",
+            payer, left, snapshot.payer_rent_minimum
+        )));
+    }
+    if snapshot.recipient_balance == Some(0) && lamports < snapshot.new_account_rent_minimum {
+        return Err(ScramjetError::InsufficientFunds(format!(
+            "{} does not exist; a transfer of {} lamports is below the {} needed to create it \
+             rent-exempt",
+            recipient, lamports, snapshot.new_account_rent_minimum
+        )));
+    }
+    Ok(())
+}
+
+/// Fetch the payer's and recipient's accounts (confirmed) and run
+/// [`check_funding`] on them
+pub async fn verify_funding(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    recipient: &Pubkey,
+    fee: u64,
+    lamports: u64,
+) -> Result<(), ScramjetError> {
+    let accounts = rpc
+        .get_multiple_accounts_with_commitment(&[*payer, *recipient], CommitmentConfig::confirmed())
+        .await?
+        .value;
+    let payer_account = accounts.first().cloned().flatten();
+    let recipient_balance = match accounts.get(1).cloned().flatten() {
+        _ if recipient == payer => None,
+        Some(account) => Some(account.lamports),
+        None => Some(0),
+    };
+    let payer_data_len = payer_account
+        .as_ref()
+        .map_or(0, |account| account.data.len());
+    let snapshot = FundingSnapshot {
+        payer_balance: payer_account.map_or(0, |account| account.lamports),
+        payer_rent_minimum: rpc
+            .get_minimum_balance_for_rent_exemption(payer_data_len)
+            .await?,
+        recipient_balance,
+        new_account_rent_minimum: rpc.get_minimum_balance_for_rent_exemption(0).await?,
+    };
+    check_funding(&snapshot, payer, recipient, fee, lamports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_funding() {
+        // 2 signatures + 200k CU at 100k microlamports/CU = 20_000 priority
+        assert_eq!(transaction_fee(2, 200_000, 100_000), 30_000);
+        assert_eq!(transaction_fee(1, 3, 1), 5_001);
+
+        let (payer, recipient) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let funded = FundingSnapshot {
+            payer_balance: 1_000_000_000,
+            payer_rent_minimum: 890_880,
+            recipient_balance: Some(5_000_000),
+            new_account_rent_minimum: 890_880,
+        };
+        assert!(check_funding(&funded, &payer, &recipient, 10_000, 1).is_ok());
+
+        let broke = FundingSnapshot {
+            payer_balance: 5_000,
+            ..funded
+        };
+        let err = check_funding(&broke, &payer, &recipient, 10_000, 1).unwrap_err();
+        assert!(err.to_string().contains("needs 10001"));
+
+        // Draining to exactly zero is fine; leaving dust is not
+        let exact = FundingSnapshot {
+            payer_balance: 10_001,
+            ..funded
+        };
+        assert!(check_funding(&exact, &payer, &recipient, 10_000, 1).is_ok());
+        let dust = FundingSnapshot {
+            payer_balance: 20_000,
+            ..funded
+        };
+        let err = check_funding(&dust, &payer, &recipient, 10_000, 1).unwrap_err();
+        assert!(err.to_string().contains("rent-exempt minimum"));
+
+        // A new recipient must be created rent-exempt
+        let new_recipient = FundingSnapshot {
+            recipient_balance: Some(0),
+            ..funded
+        };
+        let err = check_funding(&new_recipient, &payer, &recipient, 10_000, 1).unwrap_err();
+        assert!(err.to_string().contains("does not exist"));
+        assert!(check_funding(&new_recipient, &payer, &recipient, 10_000, 890_880).is_ok());
+        let to_self = FundingSnapshot {
+            recipient_balance: None,
+            ..funded
+        };
+        assert!(check_funding(&to_self, &payer, &payer, 10_000, 1).is_ok());
+    }
+}
@@ -2,6 +2,7 @@ use crate::config::Config;
 use crate::error::ScramjetError;
 use quinn::crypto::rustls::QuicClientConfig;
 use rcgen::CertificateParams;
+use rustls::client::Resumption;
 use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
 use solana_sdk::signature::Keypair;
 use std::sync::Arc;
@@ -38,7 +39,16 @@ pub fn create_quic_config(
     // CRITICAL: Set ALPN to "solana-tpu" for Solana protocol
     client_crypto.alpn_protocols = vec![b"solana-tpu".to_vec()];
 
-    // STEP 4: Configure Quinn QUIC transport (keep-alive + timeout + FIFO scheduling)
+    // STEP 4: Session resumption. Tickets are cached per server name and the
+    // engine names connections after the leader's IP, so a reconnect to a
+    // recently seen leader resumes (in 0-RTT with QUIC_ZERO_RTT)
+    client_crypto.resumption = match config.quic_session_cache {
+        0 => Resumption::disabled(),
+        sessions => Resumption::in_memory_sessions(sessions),
+    };
+    client_crypto.enable_early_data = config.quic_zero_rtt;
+
+    // STEP 5: Configure Quinn QUIC transport (keep-alive + timeout + FIFO scheduling + no inbound)
     let quic_crypto = QuicClientConfig::try_from(client_crypto)
         .map_err(|e| ScramjetError::ConfigError(format!("QUIC crypto config error: {}", e)))?;
     let mut client_config = quinn::ClientConfig::new(Arc::new(quic_crypto));
@@ -53,6 +63,16 @@ pub fn create_quic_config(
     // This ensures each transaction completes as an atomic UDP packet before the next starts
     transport_config.send_fairness(false);
 
+    // PROTOCOL POSTURE: TPU traffic is strictly client -> validator on uni streams.
+    // Grant validators zero stream credit and no datagram buffer so inbound data is refused.
+    transport_config.max_concurrent_bidi_streams(0u32.into());
+    transport_config.max_concurrent_uni_streams(0u32.into());
+    // quinn only sends datagrams when it can receive them, so datagram mode
+    // advertises a 1-byte limit: validators still cannot push anything useful
+    transport_config.datagram_receive_buffer_size(config.quic_datagrams.then_some(1));
+    // GSO batches packets into one syscall; some NICs/drivers mangle them
+    transport_config.enable_segmentation_offload(config.quic_segmentation_offload);
+
     client_config.transport_config(Arc::new(transport_config));
 
     Ok(client_config)
@@ -0,0 +1,167 @@
+//! Encrypted keypair files, so sender boxes don't keep a plaintext id.json.
+//!
+//! The file is an [age](https://age-encryption.org) file sealed to a
+//! passphrase (scrypt), holding the id.json contents. `age -p` produces the
+//! same format, so either tool can create or open it:
+//!
+//! ```bash
+//! age -p -o sender-key.age ~/.config/solana/id.json
+//! ```
+
+use crate::error::ScramjetError;
+use age::secrecy::SecretString;
+use age::{DecryptError, Decryptor, Encryptor};
+use solana_sdk::signature::{read_keypair, write_keypair, Keypair};
+use std::io::{Read, Write};
+use std::iter;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// Environment variable holding the passphrase (otherwise it is prompted for)
+pub const PASSPHRASE_ENV: &str = "KEYPAIR_PASSPHRASE";
+
+/// scrypt work factor (log2 N) for newly encrypted files: 256 MiB, ~1s
+pub const DEFAULT_WORK_FACTOR: u8 = 18;
+
+/// Highest scrypt work factor accepted when decrypting (1 GiB): a file can't
+/// make the sender spend unbounded time and memory before it starts
+pub const MAX_WORK_FACTOR: u8 = 20;
+
+/// Encrypt `keypair` under `passphrase` with scrypt work factor `log_n`
+pub fn seal(keypair: &Keypair, passphrase: &str, log_n: u8) -> Result<Vec<u8>, ScramjetError> {
+    check_passphrase(passphrase)?;
+    let mut recipient = age::scrypt::Recipient::new(SecretString::from(passphrase.to_owned()));
+    recipient.set_work_factor(log_n);
+    let encryptor = Encryptor::with_recipients(iter::once(&recipient as _))
+        .map_err(|e| ScramjetError::KeypairError(format!("Encryption failed: {}", e)))?;
+
+    let mut plaintext = Zeroizing::new(Vec::new());
+    write_keypair(keypair, &mut *plaintext)
+        .map_err(|e| ScramjetError::KeypairError(e.to_string()))?;
+    let mut sealed = Vec::new();
+    let mut writer = encryptor.wrap_output(&mut sealed)?;
+    writer.write_all(&plaintext)?;
+    writer.finish()?;
+    Ok(sealed)
+}
+
+/// Decrypt an age file with `passphrase` (a wrong passphrase fails)
+pub fn open(sealed: &[u8], passphrase: &str) -> Result<Keypair, ScramjetError> {
+    check_passphrase(passphrase)?;
+    let decryptor = Decryptor::new(sealed).map_err(decrypt_error)?;
+    if !decryptor.is_scrypt() {
+        return Err(ScramjetError::KeypairError(
+            "Not a passphrase-encrypted age file".into(),
+        ));
+    }
+    let mut identity = age::scrypt::Identity::new(SecretString::from(passphrase.to_owned()));
+    identity.set_max_work_factor(MAX_WORK_FACTOR);
+    let mut reader = decryptor
+        .decrypt(iter::once(&identity as _))
+        .map_err(decrypt_error)?;
+    let mut plaintext = Zeroizing::new(Vec::new());
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|_| ScramjetError::KeypairError("Corrupted file".into()))?;
+    read_keypair(&mut &plaintext[..]).map_err(|e| ScramjetError::KeypairError(e.to_string()))
+}
+
+/// Whether `bytes` look like a passphrase-encrypted age file (header only;
+/// nothing is decrypted)
+pub fn is_encrypted(bytes: &[u8]) -> bool {
+    Decryptor::new(bytes).is_ok_and(|decryptor| decryptor.is_scrypt())
+}
+
+/// Load and decrypt an encrypted keypair file
+pub fn read_encrypted_keypair_file(
+    path: &Path,
+    passphrase: &str,
+) -> Result<Keypair, ScramjetError> {
+    let bytes = std::fs::read(path)?;
+    open(&bytes, passphrase)
+        .map_err(|e| ScramjetError::KeypairError(format!("{}: {}", path.display(), e)))
+}
+
+/// Encrypt `keypair` to `path` with `DEFAULT_WORK_FACTOR`, readable by the
+/// owner only
+pub fn write_encrypted_keypair_file(
+    keypair: &Keypair,
+    passphrase: &str,
+    path: &Path,
+) -> Result<(), ScramjetError> {
+    let sealed = seal(keypair, passphrase, DEFAULT_WORK_FACTOR)?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(&sealed)?;
+    Ok(())
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), ScramjetError> {
+    if passphrase.is_empty() {
+        return Err(ScramjetError::KeypairError(
+            "Passphrase must not be empty".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn decrypt_error(e: DecryptError) -> ScramjetError {
+    ScramjetError::KeypairError(match e {
+        DecryptError::ExcessiveWork { required, .. } => format!(
+            "scrypt work factor {} exceeds the maximum of {}",
+            required, MAX_WORK_FACTOR
+        ),
+        DecryptError::DecryptionFailed | DecryptError::NoMatchingKeys => {
+            "Wrong passphrase or corrupted file".into()
+        }
+        e => e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Signer;
+
+    #[test]
+    fn test_seal_and_open() {
+        let keypair = Keypair::new();
+        let sealed = seal(&keypair, "correct horse", 10).unwrap();
+        assert!(is_encrypted(&sealed));
+        assert_eq!(
+            open(&sealed, "correct horse").unwrap().pubkey(),
+            keypair.pubkey()
+        );
+        assert!(open(&sealed, "wrong").is_err());
+        assert!(open(&sealed, "").is_err());
+        assert!(seal(&keypair, "", 10).is_err());
+
+        // Tampered ciphertext fails authentication
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(open(&tampered, "correct horse").is_err());
+
+        // Files demanding more work than the cap are refused up front (the
+        // header's "-> scrypt <salt> <log_n>" stanza raised past it)
+        let header = String::from_utf8_lossy(&sealed[..sealed.len().min(200)]).into_owned();
+        let stanza = header
+            .lines()
+            .find(|line| line.starts_with("-> scrypt"))
+            .unwrap();
+        let raised = stanza.replace(" 10", &format!(" {}", MAX_WORK_FACTOR + 1));
+        let mut costly = sealed.clone();
+        let at = header.find(stanza).unwrap();
+        costly.splice(at..at + stanza.len(), raised.bytes());
+        let err = open(&costly, "correct horse").unwrap_err().to_string();
+        assert!(err.contains("work factor"), "{}", err);
+
+        let path = std::env::temp_dir().join(format!("scramjet-key-{}.age", std::process::id()));
+        std::fs::write(&path, &sealed).unwrap();
+        let loaded = read_encrypted_keypair_file(&path, "correct horse").unwrap();
+        assert_eq!(loaded.pubkey(), keypair.pubkey());
+        std::fs::remove_file(&path).unwrap();
+        assert!(!is_encrypted(b"[1, 2, 3]"));
+    }
+}
@@ -1,10 +1,21 @@
+pub mod cluster;
 pub mod config;
 pub mod error;
+pub mod funding;
 pub mod identity;
+pub mod keystore;
+pub mod nonce;
+pub mod packet;
+pub mod signer;
+pub mod tx_builder;
 
+pub use cluster::Cluster;
 pub use config::Config;
-pub use error::ScramjetError;
+pub use error::{ScramjetError, SendFailure, SendFailureKind};
 pub use identity::create_quic_config;
+pub use nonce::NonceManager;
+pub use signer::{LocalSigner, RemoteSigner, TxSigner};
+pub use tx_builder::TxBuilder;
 
 // --- UNIT TEST ---
 #[cfg(test)]
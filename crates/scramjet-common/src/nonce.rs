@@ -0,0 +1,113 @@
+use crate::error::ScramjetError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::nonce_utils::nonblocking::{data_from_account, get_account_with_commitment};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+#[allow(deprecated)]
+use solana_sdk::system_instruction;
+use std::sync::{Arc, RwLock};
+
+/// Durable nonce source for transactions that must outlive blockhash rotation.
+///
+/// A nonce transaction uses the stored nonce as its `recent_blockhash` and
+/// starts with an `AdvanceNonceAccount` instruction, so it stays valid until
+/// it lands (which advances the nonce) instead of expiring after ~150 slots.
+pub struct NonceManager {
+    rpc: Arc<RpcClient>,
+    nonce_account: Pubkey,
+    authority: Pubkey,
+    /// Last nonce fetched from chain
+    current: RwLock<Option<Hash>>,
+}
+
+impl NonceManager {
+    pub fn new(rpc: Arc<RpcClient>, nonce_account: Pubkey, authority: Pubkey) -> Self {
+        Self {
+            rpc,
+            nonce_account,
+            authority,
+            current: RwLock::new(None),
+        }
+    }
+
+    pub fn nonce_account(&self) -> Pubkey {
+        self.nonce_account
+    }
+
+    pub fn authority(&self) -> Pubkey {
+        self.authority
+    }
+
+    /// Fetch the current nonce from chain (confirmed commitment: a finalized
+    /// read can trail a nonce that was just advanced)
+    pub async fn fetch(&self) -> Result<Hash, ScramjetError> {
+        let account = get_account_with_commitment(
+            &self.rpc,
+            &self.nonce_account,
+            CommitmentConfig::confirmed(),
+        )
+        .await
+        .map_err(|e| ScramjetError::NonceError(format!("{}: {}", self.nonce_account, e)))?;
+        let data = data_from_account(&account)
+            .map_err(|e| ScramjetError::NonceError(format!("{}: {}", self.nonce_account, e)))?;
+
+        if data.authority != self.authority {
+            return Err(ScramjetError::NonceError(format!(
+                "{} is controlled by {}, not {}",
+                self.nonce_account, data.authority, self.authority
+            )));
+        }
+
+        let nonce = data.blockhash();
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Some(nonce);
+        Ok(nonce)
+    }
+
+    /// Last fetched nonce (None before the first `fetch`)
+    pub fn current(&self) -> Option<Hash> {
+        *self.current.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// `AdvanceNonceAccount` instruction (must be the first instruction)
+    pub fn advance_instruction(&self) -> Instruction {
+        #[allow(deprecated)]
+        system_instruction::advance_nonce_account(&self.nonce_account, &self.authority)
+    }
+
+    /// Prepend the advance instruction to a transaction's instructions
+    pub fn with_advance(&self, instructions: Vec<Instruction>) -> Vec<Instruction> {
+        let mut all = Vec::with_capacity(instructions.len() + 1);
+        all.push(self.advance_instruction());
+        all.extend(instructions);
+        all
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use solana_sdk::system_program;
+
+    #[test]
+    fn test_advance_instruction_prepended() {
+        let rpc = Arc::new(RpcClient::new("http://mock-rpc".into()));
+        let nonce_account = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let manager = NonceManager::new(rpc, nonce_account, authority);
+        assert!(manager.current().is_none());
+
+        let memo = Instruction::new_with_bytes(Pubkey::new_unique(), b"hi", vec![]);
+        let instructions = manager.with_advance(vec![memo.clone()]);
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[0].program_id, system_program::id());
+        assert_eq!(instructions[0].accounts[0].pubkey, nonce_account);
+        assert!(instructions[0]
+            .accounts
+            .iter()
+            .any(|meta| meta.pubkey == authority && meta.is_signer));
+        assert_eq!(instructions[1], memo);
+    }
+}
@@ -0,0 +1,199 @@
+//! Packet budget: a transaction has to fit one `PACKET_DATA_SIZE` (1232
+//! byte) packet. Validators drop larger ones without telling anyone, so
+//! serialized transactions are checked before sending, and an oversized one
+//! is reported with how far over it is and which instruction to trim.
+
+use crate::error::ScramjetError;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use std::fmt;
+
+pub use solana_sdk::packet::PACKET_DATA_SIZE;
+
+/// Bytes taken by a signature, and by an account key, on the wire
+const SIGNATURE_BYTES: usize = 64;
+const PUBKEY_BYTES: usize = 32;
+
+/// An instruction's share of a transaction's wire size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InstructionSize {
+    /// Position in the message (compute budget and nonce advance included)
+    pub index: usize,
+    pub program: Pubkey,
+    /// The compiled instruction, plus the account keys (and signatures)
+    /// no other instruction shares
+    pub bytes: usize,
+}
+
+/// A serialized transaction over the packet limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OversizedTransaction {
+    pub size: usize,
+    /// Largest contributor (None when the bytes do not deserialize)
+    pub largest: Option<InstructionSize>,
+}
+
+impl OversizedTransaction {
+    /// Bytes to trim to fit a packet
+    pub fn over(&self) -> usize {
+        self.size.saturating_sub(PACKET_DATA_SIZE)
+    }
+}
+
+impl fmt::Display for OversizedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Transaction is {} bytes, {} over the {} byte packet limit",
+            self.size,
+            self.over(),
+            PACKET_DATA_SIZE
+        )?;
+        if let Some(largest) = self.largest {
+            write!(
+                f,
+                " (largest: instruction {}, program {}, {} bytes)",
+                largest.index, largest.program, largest.bytes
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Length prefix of a short_vec (compact-u16) of `len` items
+fn compact_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
+
+/// Account key indices `instruction` references (program included), once each
+fn referenced(instruction: &CompiledInstruction) -> Vec<usize> {
+    let mut indices: Vec<usize> = instruction
+        .accounts
+        .iter()
+        .chain([&instruction.program_id_index])
+        .map(|&index| usize::from(index))
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// Wire bytes each instruction of `message` accounts for, in order. Keys
+/// referenced by several instructions (and the fee payer) are not charged
+/// to any of them.
+pub fn instruction_sizes(message: &VersionedMessage) -> Vec<InstructionSize> {
+    let keys = message.static_account_keys();
+    let signers = usize::from(message.header().num_required_signatures);
+    let instructions = message.instructions();
+    // Lookup table indices (v0) are past the static keys: one byte each,
+    // already counted in the compiled instruction
+    let mut references = vec![0usize; keys.len()];
+    for instruction in instructions {
+        for index in referenced(instruction) {
+            if let Some(count) = references.get_mut(index) {
+                *count += 1;
+            }
+        }
+    }
+
+    instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| {
+            let compiled = 1
+                + compact_len(instruction.accounts.len())
+                + instruction.accounts.len()
+                + compact_len(instruction.data.len())
+                + instruction.data.len();
+            let owned: usize = referenced(instruction)
+                .into_iter()
+                .filter(|&key| key > 0 && references.get(key) == Some(&1))
+                .map(|key| PUBKEY_BYTES + if key < signers { SIGNATURE_BYTES } else { 0 })
+                .sum();
+            InstructionSize {
+                index,
+                program: keys
+                    .get(usize::from(instruction.program_id_index))
+                    .copied()
+                    .unwrap_or_default(),
+                bytes: compiled + owned,
+            }
+        })
+        .collect()
+}
+
+/// Check that serialized transaction `bytes` fit one packet
+pub fn check_packet_size(bytes: &[u8]) -> Result<(), ScramjetError> {
+    if bytes.len() <= PACKET_DATA_SIZE {
+        return Ok(());
+    }
+    let largest = bincode::deserialize::<VersionedTransaction>(bytes)
+        .ok()
+        .and_then(|tx| {
+            instruction_sizes(&tx.message)
+                .into_iter()
+                .max_by_key(|size| size.bytes)
+        });
+    Err(ScramjetError::TransactionTooLarge(Box::new(
+        OversizedTransaction {
+            size: bytes.len(),
+            largest,
+        },
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx_builder::{TxBuilder, MEMO_PROGRAM_ID};
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::{Keypair, Signer};
+    use solana_sdk::transaction::Transaction;
+
+    fn serialize(builder: TxBuilder, payer: &Keypair) -> Vec<u8> {
+        let message = builder.legacy_message(Hash::default());
+        let tx = Transaction::new(&[payer], message, Hash::default());
+        bincode::serialize(&tx).unwrap()
+    }
+
+    #[test]
+    fn test_check_packet_size() {
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let fits = TxBuilder::new(payer.pubkey())
+            .compute_unit_price(1)
+            .transfer(&recipient, 1);
+        assert!(check_packet_size(&serialize(fits.clone(), &payer)).is_ok());
+
+        // The memo is the one to trim, not the transfer or compute budget
+        let memo = "x".repeat(1200);
+        let bytes = serialize(fits.memo(&memo), &payer);
+        let err = check_packet_size(&bytes).unwrap_err();
+        let ScramjetError::TransactionTooLarge(ref oversized) = err else {
+            panic!("unexpected error: {}", err);
+        };
+        assert_eq!(oversized.size, bytes.len());
+        assert_eq!(oversized.over(), bytes.len() - PACKET_DATA_SIZE);
+        let largest = oversized.largest.unwrap();
+        assert_eq!(largest.index, 2);
+        assert_eq!(largest.program, MEMO_PROGRAM_ID);
+        // Compiled memo (1 + 1 + 0 + 2 + 1200) plus the memo program's key
+        assert_eq!(largest.bytes, 1204 + PUBKEY_BYTES);
+        assert!(err.to_string().contains(&format!(
+            "{} over the 1232 byte packet limit",
+            oversized.over()
+        )));
+
+        // Garbage still gets the size report
+        let err = check_packet_size(&[0u8; 1300]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Transaction is 1300 bytes, 68 over"));
+    }
+}
@@ -0,0 +1,289 @@
+//! Transaction signing: a local keypair file, or a remote signing service so
+//! the sending box holds no hot key.
+//!
+//! Remote protocol (HTTP, JSON; `REMOTE_SIGNER_TOKEN` sent as a bearer token):
+//! - `GET {url}/pubkey` -> `{"pubkey": "<base58>"}`
+//! - `POST {url}/sign` `{"message": "<base64>"}` -> `{"signature": "<base58>"}`
+//!
+//! Returned signatures are verified against the pubkey before use.
+
+use crate::error::ScramjetError;
+use base64::Engine;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub type SignFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Signature, ScramjetError>> + Send + 'a>>;
+
+/// Signs serialized transaction messages for one pubkey
+pub trait TxSigner: Send + Sync {
+    fn pubkey(&self) -> Pubkey;
+
+    /// Sign a serialized transaction message
+    fn sign_message<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a>;
+}
+
+/// Keypair held in memory
+pub struct LocalSigner {
+    keypair: Keypair,
+}
+
+impl LocalSigner {
+    pub fn new(keypair: Keypair) -> Self {
+        Self { keypair }
+    }
+
+    /// Load a Solana CLI keypair file (JSON byte array)
+    pub fn from_file(path: &Path) -> Result<Self, ScramjetError> {
+        read_keypair_file(path).map(Self::new).map_err(|e| {
+            ScramjetError::KeypairError(format!("Failed to read {}: {}", path.display(), e))
+        })
+    }
+
+    pub fn keypair(&self) -> &Keypair {
+        &self.keypair
+    }
+}
+
+impl TxSigner for LocalSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    fn sign_message<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a> {
+        let signature = self
+            .keypair
+            .try_sign_message(message)
+            .map_err(|e| ScramjetError::SignerError(e.to_string()));
+        Box::pin(std::future::ready(signature))
+    }
+}
+
+#[derive(Deserialize)]
+struct PubkeyResponse {
+    pubkey: String,
+}
+
+#[derive(Deserialize)]
+struct SignResponse {
+    signature: String,
+}
+
+/// Signing service reached over HTTP
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+    pubkey: Pubkey,
+}
+
+impl RemoteSigner {
+    /// Ask the service at `url` which pubkey it signs for
+    pub async fn connect(
+        url: &str,
+        token: Option<String>,
+        timeout: Duration,
+    ) -> Result<Self, ScramjetError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| ScramjetError::SignerError(e.to_string()))?;
+        let mut signer = Self {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            token,
+            pubkey: Pubkey::default(),
+        };
+        let response: PubkeyResponse = signer
+            .request(signer.client.get(signer.endpoint("pubkey")))
+            .await?;
+        signer.pubkey = Pubkey::from_str(&response.pubkey)
+            .map_err(|_| ScramjetError::InvalidPubkey(response.pubkey))?;
+        Ok(signer)
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.url, path)
+    }
+
+    async fn request<T: for<'de> Deserialize<'de>>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, ScramjetError> {
+        let request = match self.token {
+            Some(ref token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| ScramjetError::SignerError(format!("{}: {}", self.url, e)))?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|e| ScramjetError::SignerError(format!("{}: {}", self.url, e)))?;
+        serde_json::from_slice(&body).map_err(|e| {
+            ScramjetError::SignerError(format!("{}: malformed response: {}", self.url, e))
+        })
+    }
+
+    async fn sign(&self, message: &[u8]) -> Result<Signature, ScramjetError> {
+        let body = serde_json::json!({
+            "message": base64::engine::general_purpose::STANDARD.encode(message),
+        });
+        let request = self
+            .client
+            .post(self.endpoint("sign"))
+            .header("content-type", "application/json")
+            .body(body.to_string());
+        let response: SignResponse = self.request(request).await?;
+        let signature = Signature::from_str(&response.signature).map_err(|_| {
+            ScramjetError::SignerError(format!("Invalid signature '{}'", response.signature))
+        })?;
+        // Never send a transaction the service signed with some other key
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(ScramjetError::SignerError(format!(
+                "Signature does not verify against {}",
+                self.pubkey
+            )));
+        }
+        Ok(signature)
+    }
+}
+
+impl TxSigner for RemoteSigner {
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn sign_message<'a>(&'a self, message: &'a [u8]) -> SignFuture<'a> {
+        Box::pin(self.sign(message))
+    }
+}
+
+/// Fill in `signer`'s signature on `tx` (message and blockhash already set;
+/// other signatures are left as they are)
+pub async fn sign_transaction(
+    signer: &dyn TxSigner,
+    tx: &mut Transaction,
+) -> Result<(), ScramjetError> {
+    let pubkey = signer.pubkey();
+    let required = tx.message.header.num_required_signatures as usize;
+    let index = tx.message.account_keys[..required]
+        .iter()
+        .position(|key| *key == pubkey)
+        .ok_or_else(|| {
+            ScramjetError::SignerError(format!("{} is not a signer of this transaction", pubkey))
+        })?;
+    let signature = signer.sign_message(&tx.message_data()).await?;
+    tx.signatures.resize(required, Signature::default());
+    tx.signatures[index] = signature;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::hash::Hash;
+    #[allow(deprecated)]
+    use solana_sdk::system_instruction;
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Minimal signing service: answers `/pubkey` and `/sign` with `keypair`
+    async fn serve(keypair: Arc<Keypair>, token: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let keypair = keypair.clone();
+                tokio::spawn(async move {
+                    let mut buf = vec![0u8; 8192];
+                    let mut len = 0;
+                    let (head, body) = loop {
+                        len += socket.read(&mut buf[len..]).await.unwrap();
+                        let text = String::from_utf8_lossy(&buf[..len]).to_string();
+                        if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                            let content_length = head
+                                .lines()
+                                .find_map(|line| {
+                                    line.to_ascii_lowercase()
+                                        .strip_prefix("content-length:")
+                                        .map(|n| n.trim().parse::<usize>().unwrap())
+                                })
+                                .unwrap_or(0);
+                            if body.len() >= content_length {
+                                break (head.to_string(), body.to_string());
+                            }
+                        }
+                    };
+                    let (status, reply) = if !head.contains(&format!("Bearer {}", token)) {
+                        ("401 Unauthorized", String::new())
+                    } else if head.starts_with("GET /pubkey") {
+                        let reply = serde_json::json!({ "pubkey": keypair.pubkey().to_string() });
+                        ("200 OK", reply.to_string())
+                    } else {
+                        let request: serde_json::Value = serde_json::from_str(&body).unwrap();
+                        let message = base64::engine::general_purpose::STANDARD
+                            .decode(request["message"].as_str().unwrap())
+                            .unwrap();
+                        let signature = keypair.sign_message(&message);
+                        let reply = serde_json::json!({ "signature": signature.to_string() });
+                        ("200 OK", reply.to_string())
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        status,
+                        reply.len(),
+                        reply
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                });
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_remote_signer_signs_transaction() {
+        let keypair = Arc::new(Keypair::new());
+        let url = serve(keypair.clone(), "secret").await;
+        let timeout = Duration::from_secs(2);
+
+        let signer = RemoteSigner::connect(&url, Some("secret".into()), timeout)
+            .await
+            .unwrap();
+        assert_eq!(signer.pubkey(), keypair.pubkey());
+
+        let payer = signer.pubkey();
+        let ix = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let mut tx = Transaction::new_with_payer(&[ix], Some(&payer));
+        tx.message.recent_blockhash = Hash::new_unique();
+        sign_transaction(&signer, &mut tx).await.unwrap();
+        tx.verify().unwrap();
+
+        // Same bytes as signing locally
+        let local = LocalSigner::new(keypair.insecure_clone());
+        let mut expected = tx.clone();
+        sign_transaction(&local, &mut expected).await.unwrap();
+        assert_eq!(tx.signatures, expected.signatures);
+
+        // Not a signer of this transaction
+        let other = LocalSigner::new(Keypair::new());
+        assert!(sign_transaction(&other, &mut tx).await.is_err());
+
+        assert!(matches!(
+            RemoteSigner::connect(&url, Some("wrong".into()), timeout).await,
+            Err(ScramjetError::SignerError(_))
+        ));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::error::ScramjetError;
+#[allow(deprecated)]
+use solana_sdk::system_instruction;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, AddressLookupTableAccount, Message, VersionedMessage},
+    pubkey::Pubkey,
+};
+
+/// Memo program (v2)
+pub const MEMO_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Composes a transaction's instructions for one fee payer: the durable
+/// nonce advance and compute budget (when set) first, then everything added,
+/// in order. With address lookup tables the message is v0, otherwise legacy.
+///
+/// ```
+/// # fn build(
+/// #     payer: solana_sdk::pubkey::Pubkey,
+/// #     recipient: solana_sdk::pubkey::Pubkey,
+/// #     blockhash: solana_sdk::hash::Hash,
+/// # ) -> Result<(), scramjet_common::ScramjetError> {
+/// use scramjet_common::TxBuilder;
+///
+/// let message = TxBuilder::new(payer)
+///     .compute_unit_limit(20_000)
+///     .compute_unit_price(100_000)
+///     .transfer(&recipient, 1)
+///     .memo("run-42")
+///     .message(blockhash)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TxBuilder {
+    payer: Pubkey,
+    nonce_advance: Option<Instruction>,
+    compute_unit_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
+    instructions: Vec<Instruction>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+}
+
+impl TxBuilder {
+    pub fn new(payer: Pubkey) -> Self {
+        Self {
+            payer,
+            nonce_advance: None,
+            compute_unit_limit: None,
+            compute_unit_price: None,
+            instructions: Vec::new(),
+            lookup_tables: Vec::new(),
+        }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer
+    }
+
+    /// Durable nonce `AdvanceNonceAccount` (see
+    /// [`NonceManager::advance_instruction`](crate::NonceManager::advance_instruction));
+    /// it has to be the first instruction, so it always goes first
+    pub fn nonce_advance(mut self, advance: Instruction) -> Self {
+        self.nonce_advance = Some(advance);
+        self
+    }
+
+    /// `SetComputeUnitLimit`
+    pub fn compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units);
+        self
+    }
+
+    /// `SetComputeUnitPrice` (priority fee, microlamports per CU)
+    pub fn compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// System transfer from the payer
+    pub fn transfer(self, to: &Pubkey, lamports: u64) -> Self {
+        #[allow(deprecated)]
+        let transfer = system_instruction::transfer(&self.payer, to, lamports);
+        self.instruction(transfer)
+    }
+
+    /// Memo (UTF-8 text, no signers)
+    pub fn memo(self, memo: &str) -> Self {
+        self.instruction(Instruction::new_with_bytes(
+            MEMO_PROGRAM_ID,
+            memo.as_bytes(),
+            Vec::new(),
+        ))
+    }
+
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    pub fn instructions(mut self, instructions: impl IntoIterator<Item = Instruction>) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// Instruction for any program, its data given as hex (`0x` optional)
+    pub fn raw_instruction(
+        self,
+        program_id: Pubkey,
+        accounts: Vec<AccountMeta>,
+        hex_data: &str,
+    ) -> Result<Self, ScramjetError> {
+        let data = decode_hex(hex_data)?;
+        Ok(self.instruction(Instruction::new_with_bytes(program_id, &data, accounts)))
+    }
+
+    /// Resolve accounts through this lookup table (makes the message v0)
+    pub fn lookup_table(mut self, table: AddressLookupTableAccount) -> Self {
+        self.lookup_tables.push(table);
+        self
+    }
+
+    /// Everything in order: nonce advance, compute budget, then the added
+    /// instructions
+    pub fn build_instructions(&self) -> Vec<Instruction> {
+        let prefix = [
+            self.nonce_advance.clone(),
+            self.compute_unit_limit
+                .map(ComputeBudgetInstruction::set_compute_unit_limit),
+            self.compute_unit_price
+                .map(ComputeBudgetInstruction::set_compute_unit_price),
+        ];
+        prefix
+            .into_iter()
+            .flatten()
+            .chain(self.instructions.iter().cloned())
+            .collect()
+    }
+
+    /// Legacy message (lookup tables are not used)
+    pub fn legacy_message(&self, blockhash: Hash) -> Message {
+        Message::new_with_blockhash(&self.build_instructions(), Some(&self.payer), &blockhash)
+    }
+
+    /// v0 message when lookup tables were added, legacy otherwise
+    pub fn message(&self, blockhash: Hash) -> Result<VersionedMessage, ScramjetError> {
+        if self.lookup_tables.is_empty() {
+            return Ok(VersionedMessage::Legacy(self.legacy_message(blockhash)));
+        }
+        let message = v0::Message::try_compile(
+            &self.payer,
+            &self.build_instructions(),
+            &self.lookup_tables,
+            blockhash,
+        )
+        .map_err(|e| ScramjetError::InvalidTransaction(format!("v0 message: {}", e)))?;
+        Ok(VersionedMessage::V0(message))
+    }
+}
+
+/// Hex string (`0x` prefix and surrounding whitespace allowed) to bytes
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>, ScramjetError> {
+    let hex = hex.trim();
+    let digits = hex.strip_prefix("0x").unwrap_or(hex).as_bytes();
+    if !digits.len().is_multiple_of(2) {
+        return Err(ScramjetError::InvalidInstruction(format!(
+            "hex data '{}' has an odd number of digits",
+            hex
+        )));
+    }
+    digits
+        .chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| {
+                    ScramjetError::InvalidInstruction(format!("'{}' is not valid hex", hex))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use solana_sdk::compute_budget;
+
+    #[test]
+    fn test_instruction_order_and_raw_data() {
+        let (payer, recipient, program) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let builder = TxBuilder::new(payer)
+            .transfer(&recipient, 7)
+            .compute_unit_price(5)
+            .memo("run-42")
+            .raw_instruction(
+                program,
+                vec![AccountMeta::new(recipient, false)],
+                "0xDEad01",
+            )
+            .unwrap()
+            .compute_unit_limit(1_000);
+        let instructions = builder.build_instructions();
+        let programs: Vec<Pubkey> = instructions.iter().map(|ix| ix.program_id).collect();
+        assert_eq!(
+            programs,
+            vec![
+                compute_budget::id(),
+                compute_budget::id(),
+                solana_sdk::system_program::id(),
+                MEMO_PROGRAM_ID,
+                program,
+            ]
+        );
+        assert_eq!(instructions[3].data, b"run-42");
+        assert_eq!(instructions[4].data, vec![0xde, 0xad, 0x01]);
+
+        // Nothing but the added instructions without a compute budget; a
+        // nonce advance goes before everything
+        assert_eq!(
+            TxBuilder::new(payer).memo("x").build_instructions().len(),
+            1
+        );
+        let advance = Instruction::new_with_bytes(program, &[4], Vec::new());
+        let with_nonce = builder.nonce_advance(advance.clone()).build_instructions();
+        assert_eq!((with_nonce.len(), &with_nonce[0]), (6, &advance));
+
+        assert!(decode_hex("abc").is_err());
+        assert!(decode_hex("zz").is_err());
+        assert_eq!(decode_hex(" ").unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_lookup_tables_make_v0() {
+        let (payer, recipient) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let blockhash = Hash::new_unique();
+        let builder = TxBuilder::new(payer).transfer(&recipient, 1);
+        let legacy = builder.message(blockhash).unwrap();
+        assert!(matches!(legacy, VersionedMessage::Legacy(_)));
+        assert_eq!(legacy.static_account_keys().len(), 3);
+
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![recipient],
+        };
+        let v0 = builder.lookup_table(table).message(blockhash).unwrap();
+        let VersionedMessage::V0(message) = v0 else {
+            panic!("expected a v0 message");
+        };
+        // The recipient is loaded from the table instead of listed
+        assert!(!message.account_keys.contains(&recipient));
+        assert_eq!(message.address_table_lookups.len(), 1);
+        assert_eq!(message.recent_blockhash, blockhash);
+    }
+}
@@ -0,0 +1,271 @@
+//! Hot path micro-benchmarks.
+//!
+//! Run with `cargo bench -p scramjet-net`. These give optimization PRs a
+//! measurable baseline for the primitives touched on every send.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use scramjet_common::Config;
+use scramjet_net::{
+    blocklist::blocklist_handle, cartographer::Cartographer, engine::QuicEngine,
+    schedule::LeaderWindows,
+};
+#[allow(deprecated)]
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::Transaction,
+};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::sync::RwLock;
+
+/// Slots in a mainnet epoch (schedule size the hot path actually sees)
+const EPOCH_SLOTS: u64 = 432_000;
+const START_SLOT: u64 = 300_000_000;
+
+fn runtime() -> Runtime {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+}
+
+/// Cartographer populated with a full epoch schedule (4-slot leader windows)
+fn populated_cartographer(rt: &Runtime) -> Arc<Cartographer> {
+    let blocklist = blocklist_handle(HashSet::new());
+    let c = Arc::new(Cartographer::new("http://mock-rpc".to_string(), blocklist));
+
+    let leaders: Vec<Pubkey> = (0..2_000).map(|_| Pubkey::new_unique()).collect();
+    let mut nodes = HashMap::new();
+    for (i, pk) in leaders.iter().enumerate() {
+        let addr: SocketAddr = format!("10.0.{}.{}:8009", i / 250, i % 250).parse().unwrap();
+        nodes.insert(*pk, addr);
+    }
+    let schedule: HashMap<u64, Pubkey> = (0..EPOCH_SLOTS)
+        .map(|i| (START_SLOT + i, leaders[(i / 4) as usize % leaders.len()]))
+        .collect();
+
+    rt.block_on(async {
+        c.install_topology(nodes).await;
+        c.install_schedule(schedule).await;
+    });
+    c.update_slot(START_SLOT);
+    c
+}
+
+fn sample_transaction() -> Transaction {
+    let payer = Keypair::new();
+    let instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(200_000),
+        ComputeBudgetInstruction::set_compute_unit_price(100_000),
+        system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1),
+    ];
+    Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[&payer],
+        Hash::new_unique(),
+    )
+}
+
+fn bench_target_resolution(c: &mut Criterion) {
+    let rt = runtime();
+    let cart = populated_cartographer(&rt);
+
+    c.bench_function("get_target/uncontended", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(cart.get_target(START_SLOT + 1_000).await) })
+    });
+
+    // Background readers hammer the same locks while we measure
+    let stop = Arc::new(AtomicBool::new(false));
+    for _ in 0..4 {
+        let cart = cart.clone();
+        let stop = stop.clone();
+        rt.spawn(async move {
+            let mut slot = START_SLOT;
+            while !stop.load(Ordering::Relaxed) {
+                black_box(cart.get_target(slot).await);
+                slot = START_SLOT + (slot + 1 - START_SLOT) % EPOCH_SLOTS;
+                tokio::task::yield_now().await;
+            }
+        });
+    }
+    c.bench_function("get_target/contended_4_readers", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(cart.get_target(START_SLOT + 1_000).await) })
+    });
+    stop.store(true, Ordering::Relaxed);
+}
+
+/// The RwLock layout `get_target` read before topology snapshots
+#[derive(Default)]
+struct LockedTopology {
+    windows: RwLock<LeaderWindows>,
+    nodes: RwLock<HashMap<Pubkey, SocketAddr>>,
+    blocklist: RwLock<HashSet<Pubkey>>,
+    allowlist: RwLock<Option<HashSet<Pubkey>>>,
+}
+
+impl LockedTopology {
+    async fn get_target(&self, slot: u64) -> Option<SocketAddr> {
+        let leader = self.windows.read().await.window_at(slot)?.leader;
+        {
+            let blocklist = self.blocklist.read().await;
+            let allowlist = self.allowlist.read().await;
+            if blocklist.contains(&leader)
+                || allowlist
+                    .as_ref()
+                    .is_some_and(|allowed| !allowed.contains(&leader))
+            {
+                return None;
+            }
+        }
+        self.nodes.read().await.get(&leader).copied()
+    }
+}
+
+/// Snapshot lookups against the RwLock reads they replaced, idle and while
+/// the schedule is rebuilt over and over (an epoch refresh under send load)
+fn bench_topology_snapshot(c: &mut Criterion) {
+    let rt = runtime();
+    let cart = populated_cartographer(&rt);
+    let topology = cart.topology();
+    let schedule: Arc<HashMap<u64, Pubkey>> = Arc::new(
+        topology
+            .windows
+            .iter()
+            .flat_map(|w| (w.start_slot..=w.end_slot).map(move |slot| (slot, w.leader)))
+            .collect(),
+    );
+    let locked = Arc::new(LockedTopology {
+        windows: RwLock::new(LeaderWindows::clone(&topology.windows)),
+        nodes: RwLock::new(HashMap::clone(&topology.nodes)),
+        ..Default::default()
+    });
+    let slot = START_SLOT + 1_000;
+
+    c.bench_function("topology/snapshot", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(cart.get_target(slot).await) })
+    });
+    c.bench_function("topology/rwlock", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(locked.get_target(slot).await) })
+    });
+
+    // Snapshots are rebuilt off to the side; the old layout rebuilt the windows
+    // under the write lock
+    let stop = Arc::new(AtomicBool::new(false));
+    {
+        let (cart, schedule, stop) = (cart.clone(), schedule.clone(), stop.clone());
+        rt.spawn(async move {
+            while !stop.load(Ordering::Relaxed) {
+                cart.install_schedule(HashMap::clone(&schedule)).await;
+                tokio::task::yield_now().await;
+            }
+        });
+    }
+    {
+        let (locked, schedule, stop) = (locked.clone(), schedule.clone(), stop.clone());
+        rt.spawn(async move {
+            while !stop.load(Ordering::Relaxed) {
+                let mut windows = locked.windows.write().await;
+                *windows = LeaderWindows::from_schedule(&schedule);
+                drop(windows);
+                tokio::task::yield_now().await;
+            }
+        });
+    }
+    c.bench_function("topology/snapshot_during_refresh", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(cart.get_target(slot).await) })
+    });
+    c.bench_function("topology/rwlock_during_refresh", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(locked.get_target(slot).await) })
+    });
+    stop.store(true, Ordering::Relaxed);
+}
+
+fn bench_upcoming_leaders(c: &mut Criterion) {
+    let rt = runtime();
+    let cart = populated_cartographer(&rt);
+
+    for lookahead in [10u64, 50, 100] {
+        c.bench_function(
+            &format!("get_upcoming_leaders/lookahead_{}", lookahead),
+            |b| {
+                b.to_async(&rt).iter(|| async {
+                    black_box(
+                        cart.get_upcoming_leaders(START_SLOT + 1_000, lookahead)
+                            .await,
+                    )
+                })
+            },
+        );
+    }
+}
+
+fn bench_connection_cache(c: &mut Criterion) {
+    let rt = runtime();
+    let (server_addr, engine) = rt.block_on(async {
+        let server = mock_server();
+        let addr = server.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Some(incoming) = server.accept().await {
+                tokio::spawn(async move {
+                    if let Ok(conn) = incoming.await {
+                        // Hold the connection open until the client closes it
+                        let _ = conn.closed().await;
+                    }
+                });
+            }
+        });
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        engine
+            .get_connection_handle(addr)
+            .await
+            .expect("Handshake with mock server failed");
+        (addr, engine)
+    });
+
+    c.bench_function("connection_cache/hit", |b| {
+        b.to_async(&rt)
+            .iter(|| async { black_box(engine.get_connection_handle(server_addr).await.unwrap()) })
+    });
+}
+
+fn bench_transaction_bytes(c: &mut Criterion) {
+    let tx = sample_transaction();
+    let tx_bytes = bincode::serialize(&tx).unwrap();
+
+    c.bench_function("prepared_tx/clone", |b| b.iter(|| black_box(tx_bytes.clone())));
+    c.bench_function("prepared_tx/serialize", |b| {
+        b.iter(|| black_box(bincode::serialize(&tx).unwrap()))
+    });
+}
+
+/// Minimal solana-tpu QUIC server (the testkit's mock validator config)
+fn mock_server() -> quinn::Endpoint {
+    let server_config = scramjet_testkit::server_config();
+    quinn::Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap()
+}
+
+criterion_group!(
+    benches,
+    bench_target_resolution,
+    bench_topology_snapshot,
+    bench_upcoming_leaders,
+    bench_connection_cache,
+    bench_transaction_bytes
+);
+criterion_main!(benches);
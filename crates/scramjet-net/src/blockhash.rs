@@ -0,0 +1,135 @@
+//! Background blockhash refresher.
+//!
+//! Signing against `getLatestBlockhash` costs an RPC round trip on the hot
+//! path, and long runs that sign once go stale after ~150 slots. The cache
+//! refreshes on an interval and serves the latest blockhash with a single
+//! lock-free pointer load.
+
+use arc_swap::ArcSwapOption;
+use log::{debug, warn};
+use scramjet_common::ScramjetError;
+use solana_sdk::clock::MAX_PROCESSING_AGE;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::rpc_pool::RpcPool;
+
+/// A fetched blockhash and how long it stays usable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CachedBlockhash {
+    pub blockhash: Hash,
+    /// Last block height at which transactions using it are accepted
+    pub last_valid_block_height: u64,
+    pub fetched_at: Instant,
+}
+
+pub struct BlockhashCache {
+    rpc: Arc<RpcPool>,
+    latest: ArcSwapOption<CachedBlockhash>,
+}
+
+impl BlockhashCache {
+    pub fn new(rpc: Arc<RpcPool>) -> Self {
+        Self {
+            rpc,
+            latest: ArcSwapOption::empty(),
+        }
+    }
+
+    /// Fetch the latest blockhash (confirmed: finalized trails by ~32 slots,
+    /// which is a fifth of the validity window)
+    pub async fn refresh(&self) -> Result<Hash, ScramjetError> {
+        let (blockhash, last_valid_block_height) = self
+            .rpc
+            .call(|rpc| async move {
+                rpc.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())
+                    .await
+            })
+            .await
+            .map_err(|e| ScramjetError::RpcError(format!("Failed to get blockhash: {}", e)))?;
+        self.store(blockhash, last_valid_block_height);
+        Ok(blockhash)
+    }
+
+    pub fn store(&self, blockhash: Hash, last_valid_block_height: u64) {
+        self.latest.store(Some(Arc::new(CachedBlockhash {
+            blockhash,
+            last_valid_block_height,
+            fetched_at: Instant::now(),
+        })));
+    }
+
+    /// Latest cached blockhash (lock-free; None before the first refresh)
+    pub fn latest(&self) -> Option<CachedBlockhash> {
+        self.latest.load().as_deref().copied()
+    }
+
+    /// Block height when the latest blockhash was fetched (a lower bound on
+    /// the current one; None before the first refresh)
+    pub fn block_height(&self) -> Option<u64> {
+        self.latest().map(|cached| {
+            cached
+                .last_valid_block_height
+                .saturating_sub(MAX_PROCESSING_AGE as u64)
+        })
+    }
+
+    /// Latest blockhash if it was fetched within `max_age`
+    pub fn fresh(&self, max_age: Duration) -> Option<Hash> {
+        self.latest()
+            .filter(|cached| cached.fetched_at.elapsed() <= max_age)
+            .map(|cached| cached.blockhash)
+    }
+
+    /// Refresh every `interval` until the task is aborted. Failures keep the
+    /// previous blockhash (still valid for up to ~60s) and are retried next tick.
+    pub fn spawn_refresher(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            let mut failures: u32 = 0;
+            loop {
+                ticker.tick().await;
+                match self.refresh().await {
+                    Ok(blockhash) => {
+                        if failures > 0 {
+                            debug!("Blockhash: Refresh recovered after {} failures", failures);
+                        }
+                        failures = 0;
+                        debug!("Blockhash: {}", blockhash);
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        // Log once per streak, not every tick
+                        if failures == 1 {
+                            warn!("Blockhash: Refresh failed: {}", e);
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fresh_respects_max_age() {
+        let cache = BlockhashCache::new(Arc::new(RpcPool::single("http://mock-rpc".into())));
+        assert!(cache.latest().is_none());
+        assert!(cache.fresh(Duration::from_secs(60)).is_none());
+
+        let hash = Hash::new_unique();
+        cache.store(hash, 1_000);
+        assert_eq!(cache.latest().unwrap().last_valid_block_height, 1_000);
+        assert_eq!(cache.block_height(), Some(850));
+        assert_eq!(cache.fresh(Duration::from_secs(60)), Some(hash));
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.fresh(Duration::from_millis(1)).is_none());
+    }
+}
@@ -7,41 +7,157 @@
 //! - Primary: Local file (`blocklist.txt`) - user maintains their own list
 //! - Optional: Remote URL sync if configured via `SCRAMJET_BLOCKLIST_URL`
 //! - Fail-safe: never overwrites good data with empty responses
+//!
+//! **Entry format**: `pubkey[,reason[,expires_at]]` per line, `expires_at`
+//! in Unix seconds. Expired entries are ignored on load and pruned from the
+//! live list when they lapse; the reason is logged when a leader is skipped.
+//!
+//! **Signed remote lists** (`SCRAMJET_BLOCKLIST_PUBKEY`): remote updates must
+//! be a [`SignedBlocklist`] signed by the configured publisher; anything else
+//! is rejected and the current list stays in force, so a compromised URL
+//! cannot unblock or poison the Shield. Signed lists are verified even
+//! without a configured publisher. Each carries a signed, increasing
+//! `version`; the last one accepted is kept beside the local file
+//! (`<file>.version`) and older lists are refused, so an old signed list
+//! cannot be replayed to lift later blocks.
+//!
+//! **Allowlist mode** (`SCRAMJET_ALLOWLIST_FILE`): only listed validators are
+//! targeted. Same file format; the blocklist still applies on top. Fails
+//! closed: if the file can't be read, nothing is targeted until it can.
+//!
+//! **Temporary blocks** (auto-blocklist): validators blocked with a TTL by
+//! `ValidatorScore`. They survive reloads and are lifted on expiry unless the
+//! loaded list blocks them too.
 
-use log::{debug, info, warn};
+use arc_swap::ArcSwap;
+use log::{debug, error, info, warn};
+use scramjet_common::ScramjetError;
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
-use std::collections::HashSet;
+use solana_sdk::signature::{Signature, Signer};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::RwLock;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio_util::sync::CancellationToken;
 
 /// Default refresh interval for file watching (5 minutes)
 const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
 
-/// Handle type for sharing blocklist across components
-pub type BlocklistHandle = Arc<RwLock<HashSet<Pubkey>>>;
+/// Handle type for sharing the Shield lists across components. Updates swap
+/// in new lists, so readers (the send hot path) never wait on a lock.
+pub type BlocklistHandle = Arc<ArcSwap<ShieldLists>>;
+
+/// Handle type for block reasons (blocked validators with a known reason)
+pub type BlockReasonsHandle = Arc<ArcSwap<HashMap<Pubkey, String>>>;
+
+/// Blocked and allowed validators, published as one snapshot so a reader
+/// never pairs a new blocklist with a stale allowlist
+#[derive(Debug, Clone, Default)]
+pub struct ShieldLists {
+    /// Blocked validators
+    pub blocked: HashSet<Pubkey>,
+    /// Validators allowed as targets (None = allowlist mode off)
+    pub allowed: Option<HashSet<Pubkey>>,
+}
+
+impl ShieldLists {
+    /// Lists blocking `blocked`, allowlist mode off
+    pub fn blocking(blocked: HashSet<Pubkey>) -> Self {
+        Self {
+            blocked,
+            allowed: None,
+        }
+    }
+
+    /// Shield verdict for a leader: not blocked and, in allowlist mode, allowlisted
+    pub fn permits(&self, leader: &Pubkey) -> bool {
+        !self.blocked.contains(leader)
+            && self
+                .allowed
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(leader))
+    }
+}
+
+/// Blocklist handle starting with `blocked` (allowlist mode off)
+pub fn blocklist_handle(blocked: HashSet<Pubkey>) -> BlocklistHandle {
+    Arc::new(ArcSwap::from_pointee(ShieldLists::blocking(blocked)))
+}
+
+/// Reason recorded for auto-blocklist blocks
+const AUTO_BLOCK_REASON: &str = "auto-blocked (send failures)";
+
+/// One blocklist line: `pubkey[,reason[,expires_at]]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlocklistEntry {
+    pub pubkey: Pubkey,
+    /// Why it is blocked (logged when its slots are skipped)
+    pub reason: Option<String>,
+    /// Unix seconds after which the block lapses (None = permanent)
+    pub expires_at: Option<u64>,
+}
+
+impl BlocklistEntry {
+    pub fn is_expired(&self, now_unix: u64) -> bool {
+        self.expires_at.is_some_and(|expiry| expiry <= now_unix)
+    }
+
+    /// The line as written to a blocklist file
+    pub fn to_line(&self) -> String {
+        match (&self.reason, self.expires_at) {
+            (None, None) => self.pubkey.to_string(),
+            (reason, None) => format!("{},{}", self.pubkey, reason.as_deref().unwrap_or("")),
+            (reason, Some(expiry)) => format!(
+                "{},{},{}",
+                self.pubkey,
+                reason.as_deref().unwrap_or(""),
+                expiry
+            ),
+        }
+    }
+}
+
+impl From<Pubkey> for BlocklistEntry {
+    fn from(pubkey: Pubkey) -> Self {
+        Self {
+            pubkey,
+            reason: None,
+            expires_at: None,
+        }
+    }
+}
 
 /// BlocklistManager handles loading, persisting, and refreshing the blocklist.
 ///
 /// Architecture:
-/// - Hot path (reads): O(1) HashSet lookup via shared RwLock (non-blocking for readers)
-/// - Cold path (writes): Background task acquires write lock periodically
+/// - Hot path (reads): O(1) HashSet lookup on a lock-free snapshot
+/// - Cold path (writes): Background task swaps in an updated set periodically
 ///
 /// **Usage:**
 /// 1. Create a `blocklist.txt` file with one validator pubkey per line
 /// 2. The Shield loads it on startup and periodically checks for changes
 /// 3. Optionally set `SCRAMJET_BLOCKLIST_URL` for remote sync
 pub struct BlocklistManager {
-    /// The shared blocklist data structure
+    /// The shared Shield lists (blocklist and allowlist)
     blocklist: BlocklistHandle,
     /// Local file path for the blocklist
     local_path: PathBuf,
     /// Optional remote URL for updates (None = local-only mode)
     remote_url: Option<String>,
+    /// Publisher whose signature remote updates must carry (None = unsigned accepted)
+    publisher: Option<Pubkey>,
+    /// Reasons for blocked validators (shared with Cartographer for logging)
+    reasons: BlockReasonsHandle,
     /// Refresh interval (for file watching or remote sync)
     refresh_interval: Duration,
+    /// Allowlist file (None = allowlist mode off)
+    allowlist_path: Option<PathBuf>,
+    /// Last loaded (file or remote) blocklist, without temporary blocks
+    loaded: Mutex<HashMap<Pubkey, BlocklistEntry>>,
+    /// Temporarily blocked validators -> expiry
+    temporary: Mutex<HashMap<Pubkey, Instant>>,
 }
 
 impl BlocklistManager {
@@ -62,26 +178,65 @@ impl BlocklistManager {
         refresh_interval: Duration,
     ) -> Self {
         Self {
-            blocklist: Arc::new(RwLock::new(HashSet::new())),
+            blocklist: blocklist_handle(HashSet::new()),
             local_path,
             remote_url,
+            publisher: None,
+            reasons: Arc::new(ArcSwap::from_pointee(HashMap::new())),
             refresh_interval,
+            allowlist_path: None,
+            loaded: Mutex::new(HashMap::new()),
+            temporary: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Enable allowlist mode: only validators listed in `path` are targeted.
+    /// Nothing is allowed until the file is loaded (fail closed).
+    pub fn with_allowlist(mut self, path: PathBuf) -> Self {
+        self.blocklist.rcu(|lists| ShieldLists {
+            allowed: Some(HashSet::new()),
+            ..ShieldLists::clone(lists)
+        });
+        self.allowlist_path = Some(path);
+        self
+    }
+
+    /// Only accept remote updates signed by `publisher`.
+    pub fn with_publisher(mut self, publisher: Pubkey) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
     /// Create from environment variables with fallback to defaults.
     ///
     /// Environment variables:
     /// - `SCRAMJET_BLOCKLIST_FILE`: Local file path (default: `./blocklist.txt`)
     /// - `SCRAMJET_BLOCKLIST_URL`: Optional remote URL (default: none, local-only)
     /// - `SCRAMJET_BLOCKLIST_REFRESH_SECS`: Refresh interval in seconds (default: 300)
+    /// - `SCRAMJET_BLOCKLIST_PUBKEY`: Optional publisher remote updates must be signed by
+    /// - `SCRAMJET_ALLOWLIST_FILE`: Optional allowlist file (default: none, allowlist mode off)
     pub fn from_env() -> Self {
         let local_path = std::env::var("SCRAMJET_BLOCKLIST_FILE")
             .map(PathBuf::from)
             .unwrap_or_else(|_| PathBuf::from("./blocklist.txt"));
 
         // Remote URL is OPTIONAL - only set if explicitly configured
-        let remote_url = std::env::var("SCRAMJET_BLOCKLIST_URL").ok();
+        let mut remote_url = std::env::var("SCRAMJET_BLOCKLIST_URL").ok();
+        let publisher = match std::env::var("SCRAMJET_BLOCKLIST_PUBKEY") {
+            Ok(key) => match Pubkey::from_str(key.trim()) {
+                Ok(pubkey) => Some(pubkey),
+                Err(_) => {
+                    // Fail safe: without a usable publisher, never trust the URL
+                    error!(
+                        "Shield: Invalid SCRAMJET_BLOCKLIST_PUBKEY {:?}. Remote sync disabled.",
+                        key
+                    );
+                    remote_url = None;
+                    None
+                }
+            },
+            Err(_) => None,
+        };
 
         let refresh_interval = std::env::var("SCRAMJET_BLOCKLIST_REFRESH_SECS")
             .ok()
@@ -89,26 +244,80 @@ impl BlocklistManager {
             .map(Duration::from_secs)
             .unwrap_or(DEFAULT_REFRESH_INTERVAL);
 
-        Self::with_config(local_path, remote_url, refresh_interval)
+        let mut manager = Self::with_config(local_path, remote_url, refresh_interval);
+        manager.publisher = publisher;
+        match std::env::var("SCRAMJET_ALLOWLIST_FILE") {
+            Ok(path) => manager.with_allowlist(PathBuf::from(path)),
+            Err(_) => manager,
+        }
+    }
+
+    /// Local blocklist file (edited by `scramjet shield`)
+    pub fn local_path(&self) -> &Path {
+        &self.local_path
+    }
+
+    /// Remote list synced from (None: local-only)
+    pub fn remote_url(&self) -> Option<&str> {
+        self.remote_url.as_deref()
     }
 
-    /// Get a handle to the blocklist for injection into Cartographer.
+    /// Get a handle to the Shield lists for injection into Cartographer.
     ///
     /// This handle can be cloned and shared across threads safely.
     pub fn get_handle(&self) -> BlocklistHandle {
         self.blocklist.clone()
     }
 
+    /// Get a handle to the block reasons for injection into Cartographer.
+    pub fn get_reasons_handle(&self) -> BlockReasonsHandle {
+        self.reasons.clone()
+    }
+
+    /// Load the allowlist file (allowlist mode only).
+    ///
+    /// Returns the number of allowed validators, or None if allowlist mode is
+    /// off. A read failure keeps the previously loaded allowlist.
+    pub async fn load_allowlist(&self) -> Option<usize> {
+        let path = self.allowlist_path.as_ref()?;
+        match self.load_from_file(path).await {
+            Ok(keys) => {
+                let count = keys.len();
+                if count == 0 {
+                    warn!(
+                        "Shield: Allowlist {:?} is empty. No validators will be targeted.",
+                        path
+                    );
+                } else {
+                    debug!(
+                        "Shield: Loaded {} allowed validators from {:?}",
+                        count, path
+                    );
+                }
+                let allowed: HashSet<Pubkey> = keys.into_keys().collect();
+                self.blocklist.rcu(|lists| ShieldLists {
+                    allowed: Some(allowed.clone()),
+                    ..ShieldLists::clone(lists)
+                });
+                Some(count)
+            }
+            Err(e) => {
+                warn!("Shield: Failed to load allowlist {:?}: {}", path, e);
+                self.blocklist.load().allowed.as_ref().map(HashSet::len)
+            }
+        }
+    }
+
     /// Load blocklist from local file (for fast boot).
     ///
-    /// Returns the number of valid pubkeys loaded.
+    /// Returns the number of valid, unexpired entries loaded. An empty file
+    /// is installed too, so removing the last entry unblocks it.
     pub async fn load_local(&self) -> usize {
         match self.load_from_file(&self.local_path).await {
             Ok(keys) => {
-                let count = keys.len();
+                self.install(keys).await;
+                let count = self.loaded.lock().unwrap().len();
                 if count > 0 {
-                    let mut guard = self.blocklist.write().await;
-                    *guard = keys;
                     info!(
                         "Shield: Loaded {} blocked validators from {:?}",
                         count, self.local_path
@@ -141,6 +350,7 @@ impl BlocklistManager {
     /// Fetch blocklist from remote URL and update if valid.
     ///
     /// Safety checks:
+    /// - Rejects lists failing signature verification (see [`verify_signed_blocklist`])
     /// - Rejects empty responses (protects against accidental deletion)
     /// - Rejects HTTP errors (404, 500, etc.)
     /// - On success, persists to local file for next boot
@@ -167,7 +377,15 @@ impl BlocklistManager {
             .await
             .map_err(|e| format!("Failed to read response body: {}", e))?;
 
-        let keys = self.parse_blocklist(&body);
+        let (payload, version) =
+            verify_signed_blocklist(&body, self.publisher.as_ref()).map_err(|e| e.to_string())?;
+        if let Some(version) = version {
+            if !self.is_newer(version).await? {
+                debug!("Shield: Remote blocklist unchanged (version {})", version);
+                return Ok(self.loaded.lock().unwrap().len());
+            }
+        }
+        let keys = self.parse_blocklist(&payload);
 
         // SAFETY CHECK: Reject empty responses to prevent accidental unblock-all
         if keys.is_empty() {
@@ -183,9 +401,11 @@ impl BlocklistManager {
         }
 
         // Hot-swap the blocklist (write lock, brief)
-        {
-            let mut guard = self.blocklist.write().await;
-            *guard = keys;
+        self.install(keys).await;
+        if let Some(version) = version {
+            if let Err(e) = write_atomic(&self.version_path(), &format!("{}\n", version)).await {
+                warn!("Shield: Failed to persist blocklist version: {}", e);
+            }
         }
 
         info!(
@@ -195,6 +415,145 @@ impl BlocklistManager {
         Ok(count)
     }
 
+    /// Swap in a loaded blocklist, keeping active temporary blocks.
+    /// Entries that already expired are dropped.
+    async fn install(&self, mut entries: HashMap<Pubkey, BlocklistEntry>) {
+        let now_unix = unix_now();
+        entries.retain(|_, entry| !entry.is_expired(now_unix));
+
+        let mut merged: HashSet<Pubkey> = entries.keys().copied().collect();
+        let mut reasons: HashMap<Pubkey, String> = entries
+            .values()
+            .filter_map(|entry| Some((entry.pubkey, entry.reason.clone()?)))
+            .collect();
+        {
+            let now = Instant::now();
+            let temporary = self.temporary.lock().unwrap();
+            for (pubkey, _) in temporary.iter().filter(|(_, expiry)| **expiry > now) {
+                merged.insert(*pubkey);
+                reasons
+                    .entry(*pubkey)
+                    .or_insert_with(|| AUTO_BLOCK_REASON.to_string());
+            }
+        }
+        *self.loaded.lock().unwrap() = entries;
+        self.reasons.store(Arc::new(reasons));
+        self.blocklist.rcu(|lists| ShieldLists {
+            blocked: merged.clone(),
+            ..ShieldLists::clone(lists)
+        });
+    }
+
+    /// Drop loaded entries whose `expires_at` has passed. Returns the
+    /// validators unblocked (a still-active temporary block is kept).
+    pub async fn prune_expired(&self) -> Vec<Pubkey> {
+        let now_unix = unix_now();
+        let expired: Vec<Pubkey> = {
+            let mut loaded = self.loaded.lock().unwrap();
+            let expired: Vec<Pubkey> = loaded
+                .values()
+                .filter(|entry| entry.is_expired(now_unix))
+                .map(|entry| entry.pubkey)
+                .collect();
+            for pubkey in &expired {
+                loaded.remove(pubkey);
+            }
+            let temporary = self.temporary.lock().unwrap();
+            expired
+                .into_iter()
+                .filter(|pubkey| !temporary.contains_key(pubkey))
+                .collect()
+        };
+        if !expired.is_empty() {
+            self.unblock(&expired);
+        }
+        expired
+    }
+
+    /// When the next loaded entry expires (None = no expiring entries, or
+    /// one too far off to schedule: `expires_at` comes from untrusted lists)
+    fn next_expiry(&self) -> Option<Instant> {
+        let now_unix = unix_now();
+        let loaded = self.loaded.lock().unwrap();
+        let soonest = loaded.values().filter_map(|entry| entry.expires_at).min()?;
+        Instant::now().checked_add(Duration::from_secs(soonest.saturating_sub(now_unix)))
+    }
+
+    /// Block a validator for `ttl` (auto-blocklist).
+    ///
+    /// Returns false if it was already blocked (by the loaded list, or
+    /// temporarily; a temporary block's expiry is extended).
+    pub async fn block_temporarily(&self, pubkey: Pubkey, ttl: Duration) -> bool {
+        if self.loaded.lock().unwrap().contains_key(&pubkey) {
+            return false;
+        }
+        let newly_blocked = self
+            .temporary
+            .lock()
+            .unwrap()
+            .insert(pubkey, Instant::now() + ttl)
+            .is_none();
+        self.blocklist.rcu(|lists| {
+            let mut lists = ShieldLists::clone(lists);
+            lists.blocked.insert(pubkey);
+            lists
+        });
+        self.reasons.rcu(|reasons| {
+            let mut reasons = HashMap::clone(reasons);
+            reasons.insert(pubkey, AUTO_BLOCK_REASON.to_string());
+            reasons
+        });
+        newly_blocked
+    }
+
+    /// Remove validators (and their reasons) from the live blocklist
+    fn unblock(&self, pubkeys: &[Pubkey]) {
+        self.blocklist.rcu(|lists| {
+            let mut lists = ShieldLists::clone(lists);
+            for pubkey in pubkeys {
+                lists.blocked.remove(pubkey);
+            }
+            lists
+        });
+        self.reasons.rcu(|reasons| {
+            let mut reasons = HashMap::clone(reasons);
+            for pubkey in pubkeys {
+                reasons.remove(pubkey);
+            }
+            reasons
+        });
+    }
+
+    /// Lift expired temporary blocks. Returns the validators unblocked.
+    pub async fn expire_temporary(&self) -> Vec<Pubkey> {
+        let expired: Vec<Pubkey> = {
+            let now = Instant::now();
+            let mut temporary = self.temporary.lock().unwrap();
+            let expired: Vec<Pubkey> = temporary
+                .iter()
+                .filter(|(_, expiry)| **expiry <= now)
+                .map(|(pubkey, _)| *pubkey)
+                .collect();
+            for pubkey in &expired {
+                temporary.remove(pubkey);
+            }
+            let loaded = self.loaded.lock().unwrap();
+            expired
+                .into_iter()
+                .filter(|pubkey| !loaded.contains_key(pubkey))
+                .collect()
+        };
+        if !expired.is_empty() {
+            self.unblock(&expired);
+        }
+        expired
+    }
+
+    /// Number of active temporary blocks (for monitoring).
+    pub fn temporarily_blocked(&self) -> usize {
+        self.temporary.lock().unwrap().len()
+    }
+
     /// Reload blocklist from local file.
     pub async fn reload_local(&self) -> usize {
         self.load_local().await
@@ -205,7 +564,13 @@ impl BlocklistManager {
     /// Behavior depends on configuration:
     /// - If remote URL configured: Fetches from remote periodically
     /// - If local-only: Watches local file for changes
-    pub fn spawn_updater(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+    ///
+    /// Entries with an `expires_at` are pruned as they lapse, between refreshes.
+    /// The task exits when `shutdown` is cancelled.
+    pub fn spawn_updater(
+        self: Arc<Self>,
+        shutdown: CancellationToken,
+    ) -> tokio::task::JoinHandle<()> {
         let manager = self.clone();
         tokio::spawn(async move {
             // Initial remote fetch if configured
@@ -215,8 +580,23 @@ impl BlocklistManager {
                 }
             }
 
+            let mut next_refresh = Instant::now() + manager.refresh_interval;
             loop {
-                tokio::time::sleep(manager.refresh_interval).await;
+                let wake = manager
+                    .next_expiry()
+                    .map_or(next_refresh, |expiry| expiry.min(next_refresh));
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep_until(wake.into()) => {}
+                }
+
+                for pubkey in manager.prune_expired().await {
+                    info!("Shield: Block of {} expired", pubkey);
+                }
+                if Instant::now() < next_refresh {
+                    continue;
+                }
+                next_refresh = Instant::now() + manager.refresh_interval;
 
                 if manager.remote_url.is_some() {
                     if let Err(e) = manager.fetch_remote().await {
@@ -227,22 +607,30 @@ impl BlocklistManager {
                     // Local-only: periodically reload file
                     manager.reload_local().await;
                 }
+                manager.load_allowlist().await;
             }
         })
     }
 
     /// Check if a pubkey is blocked.
     ///
-    /// This is the hot path - O(1) lookup with shared read lock.
+    /// This is the hot path - O(1) lookup, lock-free.
     pub async fn is_blocked(&self, pubkey: &Pubkey) -> bool {
-        let guard = self.blocklist.read().await;
-        guard.contains(pubkey)
+        self.blocklist.load().blocked.contains(pubkey)
+    }
+
+    /// Check if a pubkey may be targeted under allowlist mode (always true when off).
+    pub async fn is_allowed(&self, pubkey: &Pubkey) -> bool {
+        self.blocklist
+            .load()
+            .allowed
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(pubkey))
     }
 
     /// Get current blocklist size (for monitoring).
     pub async fn len(&self) -> usize {
-        let guard = self.blocklist.read().await;
-        guard.len()
+        self.blocklist.load().blocked.len()
     }
 
     /// Check if blocklist is empty.
@@ -250,47 +638,291 @@ impl BlocklistManager {
         self.len().await == 0
     }
 
-    /// Parse blocklist text into HashSet of Pubkeys.
+    /// Parse blocklist text into entries by pubkey.
     ///
-    /// Format: One base58 pubkey per line. Empty lines and invalid keys are skipped.
-    fn parse_blocklist(&self, content: &str) -> HashSet<Pubkey> {
+    /// Format: One `pubkey[,reason[,expires_at]]` per line. Empty lines and
+    /// invalid entries are skipped; a repeated pubkey keeps its last entry.
+    fn parse_blocklist(&self, content: &str) -> HashMap<Pubkey, BlocklistEntry> {
         content
             .lines()
-            .filter_map(|line| {
-                let trimmed = line.trim();
-                if trimmed.is_empty() || trimmed.starts_with('#') {
-                    return None;
-                }
-                match Pubkey::from_str(trimmed) {
-                    Ok(pk) => Some(pk),
-                    Err(_) => {
-                        debug!("Shield: Skipping invalid pubkey: {}", trimmed);
-                        None
-                    }
+            .filter_map(|line| match parse_blocklist_line(line) {
+                Ok(entry) => entry.map(|entry| (entry.pubkey, entry)),
+                Err(e) => {
+                    debug!("Shield: Skipping {}", e);
+                    None
                 }
             })
             .collect()
     }
 
     /// Load blocklist from a file.
-    async fn load_from_file(&self, path: &Path) -> Result<HashSet<Pubkey>, std::io::Error> {
+    async fn load_from_file(
+        &self,
+        path: &Path,
+    ) -> Result<HashMap<Pubkey, BlocklistEntry>, std::io::Error> {
         let content = tokio::fs::read_to_string(path).await?;
         Ok(self.parse_blocklist(&content))
     }
 
+    /// Where the version of the last accepted signed list is kept
+    fn version_path(&self) -> PathBuf {
+        let mut path = self.local_path.as_os_str().to_owned();
+        path.push(".version");
+        PathBuf::from(path)
+    }
+
+    /// Version of the last signed list accepted (None: none yet)
+    async fn accepted_version(&self) -> Option<u64> {
+        let content = tokio::fs::read_to_string(self.version_path()).await.ok()?;
+        content.trim().parse().ok()
+    }
+
+    /// Whether signed `version` should replace the accepted list: false when
+    /// it is the same one, an error when it is older
+    async fn is_newer(&self, version: u64) -> Result<bool, String> {
+        match self.accepted_version().await {
+            Some(accepted) if version < accepted => Err(format!(
+                "Blocklist version {} is older than the accepted {} (replayed?)",
+                version, accepted
+            )),
+            Some(accepted) => Ok(version > accepted),
+            None => Ok(true),
+        }
+    }
+
     /// Persist blocklist to local file.
-    async fn persist_to_file(&self, keys: &HashSet<Pubkey>) -> Result<(), std::io::Error> {
-        let content: String = keys.iter().map(|pk| format!("{}\n", pk)).collect();
-        tokio::fs::write(&self.local_path, content).await?;
+    async fn persist_to_file(
+        &self,
+        entries: &HashMap<Pubkey, BlocklistEntry>,
+    ) -> Result<(), std::io::Error> {
+        let content: String = entries
+            .values()
+            .map(|entry| format!("{}\n", entry.to_line()))
+            .collect();
+        write_atomic(&self.local_path, &content).await?;
         debug!(
             "Shield: Persisted {} keys to {:?}",
-            keys.len(),
+            entries.len(),
             self.local_path
         );
         Ok(())
     }
 }
 
+/// Entries of a blocklist file in file order (missing file = empty)
+pub async fn read_blocklist_file(path: &Path) -> Result<Vec<BlocklistEntry>, ScramjetError> {
+    let content = match tokio::fs::read_to_string(path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .filter_map(|line| parse_blocklist_line(line).transpose())
+        .collect()
+}
+
+/// Add `entry` to a blocklist file, replacing any line for the same pubkey
+/// (comments and other lines are kept). Returns true if it replaced one.
+pub async fn upsert_blocklist_entry(
+    path: &Path,
+    entry: &BlocklistEntry,
+) -> Result<bool, ScramjetError> {
+    let mut replaced = false;
+    let mut lines = Vec::new();
+    for line in read_lines(path).await? {
+        if entry_pubkey(&line) == Some(entry.pubkey) {
+            if !replaced {
+                lines.push(entry.to_line());
+            }
+            replaced = true;
+        } else {
+            lines.push(line);
+        }
+    }
+    if !replaced {
+        lines.push(entry.to_line());
+    }
+    write_atomic(path, &join_lines(&lines)).await?;
+    Ok(replaced)
+}
+
+/// Remove every line for `pubkey` from a blocklist file. Returns false if it
+/// was not listed (the file is left untouched).
+pub async fn remove_blocklist_entry(path: &Path, pubkey: &Pubkey) -> Result<bool, ScramjetError> {
+    let lines = read_lines(path).await?;
+    let kept: Vec<String> = lines
+        .iter()
+        .filter(|line| entry_pubkey(line) != Some(*pubkey))
+        .cloned()
+        .collect();
+    if kept.len() == lines.len() {
+        return Ok(false);
+    }
+    write_atomic(path, &join_lines(&kept)).await?;
+    Ok(true)
+}
+
+async fn read_lines(path: &Path) -> Result<Vec<String>, ScramjetError> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => Ok(content.lines().map(str::to_string).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn entry_pubkey(line: &str) -> Option<Pubkey> {
+    parse_blocklist_line(line)
+        .ok()
+        .flatten()
+        .map(|entry| entry.pubkey)
+}
+
+fn join_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Replace `path` with `content` via a sibling temp file and rename, so a
+/// reader (or a crash) never sees a half-written list
+async fn write_atomic(path: &Path, content: &str) -> Result<(), std::io::Error> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+    tokio::fs::write(&tmp, content).await?;
+    if let Err(e) = tokio::fs::rename(&tmp, path).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Parse a single blocklist line: `pubkey[,reason[,expires_at]]`.
+///
+/// Returns `Ok(None)` for blank lines and comments, `Err` for malformed
+/// pubkeys or expiries. The reason may not contain commas; empty fields are
+/// allowed (`pubkey,,1767225600`). Must never panic: this is fed untrusted
+/// remote content.
+pub fn parse_blocklist_line(line: &str) -> Result<Option<BlocklistEntry>, ScramjetError> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return Ok(None);
+    }
+    let mut fields = trimmed.splitn(3, ',').map(str::trim);
+    let key = fields.next().unwrap_or_default();
+    let pubkey =
+        Pubkey::from_str(key).map_err(|_| ScramjetError::InvalidPubkey(key.to_string()))?;
+    let reason = fields
+        .next()
+        .filter(|reason| !reason.is_empty())
+        .map(str::to_string);
+    let expires_at = match fields.next().filter(|expiry| !expiry.is_empty()) {
+        Some(expiry) => Some(expiry.parse::<u64>().map_err(|_| {
+            ScramjetError::InvalidBlocklistEntry(format!(
+                "{}: expires_at must be Unix seconds",
+                trimmed
+            ))
+        })?),
+        None => None,
+    };
+    Ok(Some(BlocklistEntry {
+        pubkey,
+        reason,
+        expires_at,
+    }))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Signed remote blocklist: the usual list text and its version, plus the
+/// publisher's Ed25519 signature over both (see [`SignedBlocklist::message`]).
+/// Served as JSON: `{"version": 7, "payload": "...", "signature":
+/// "<base58>", "publisher": "<base58>"}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedBlocklist {
+    /// Increases with every list the publisher signs (e.g. a Unix timestamp)
+    pub version: u64,
+    pub payload: String,
+    pub signature: String,
+    pub publisher: String,
+}
+
+impl SignedBlocklist {
+    /// Sign `payload` as `version` of `publisher`'s list (for list maintainers)
+    pub fn sign(payload: String, version: u64, publisher: &dyn Signer) -> Self {
+        Self {
+            signature: publisher
+                .sign_message(&Self::message(version, &payload))
+                .to_string(),
+            publisher: publisher.pubkey().to_string(),
+            version,
+            payload,
+        }
+    }
+
+    /// Signed bytes: `version` (u64, little-endian) then the payload's UTF-8
+    pub fn message(version: u64, payload: &str) -> Vec<u8> {
+        let mut message = version.to_le_bytes().to_vec();
+        message.extend_from_slice(payload.as_bytes());
+        message
+    }
+
+    /// Check the signature; returns the signing publisher
+    pub fn verify(&self) -> Result<Pubkey, ScramjetError> {
+        let publisher = Pubkey::from_str(&self.publisher)
+            .map_err(|_| ScramjetError::InvalidPubkey(self.publisher.clone()))?;
+        let signature = Signature::from_str(&self.signature)
+            .map_err(|_| ScramjetError::BlocklistVerification("Malformed signature".into()))?;
+        if !signature.verify(
+            publisher.as_ref(),
+            &Self::message(self.version, &self.payload),
+        ) {
+            return Err(ScramjetError::BlocklistVerification(format!(
+                "Bad signature for publisher {}",
+                publisher
+            )));
+        }
+        Ok(publisher)
+    }
+}
+
+/// Blocklist text from a remote response body, with its signed version
+/// (None for plain text).
+///
+/// A [`SignedBlocklist`] body must verify (and come from `publisher` when
+/// set). A plain-text body is only accepted when no publisher is required.
+pub fn verify_signed_blocklist(
+    body: &str,
+    publisher: Option<&Pubkey>,
+) -> Result<(String, Option<u64>), ScramjetError> {
+    let signed = match serde_json::from_str::<SignedBlocklist>(body) {
+        Ok(signed) => signed,
+        Err(_) if publisher.is_none() => return Ok((body.to_string(), None)),
+        Err(_) => {
+            return Err(ScramjetError::BlocklistVerification(
+                "Unsigned blocklist rejected (SCRAMJET_BLOCKLIST_PUBKEY is set)".into(),
+            ))
+        }
+    };
+    let signer = signed.verify()?;
+    if let Some(expected) = publisher {
+        if signer != *expected {
+            return Err(ScramjetError::BlocklistVerification(format!(
+                "Signed by {}, expected {}",
+                signer, expected
+            )));
+        }
+    }
+    debug!(
+        "Shield: Blocklist version {} signature from {} verified",
+        signed.version, signer
+    );
+    Ok((signed.payload, Some(signed.version)))
+}
+
 impl Default for BlocklistManager {
     fn default() -> Self {
         Self::new()
@@ -318,6 +950,87 @@ mod tests {
         assert_eq!(keys.len(), 3);
     }
 
+    #[test]
+    fn test_parse_blocklist_line_malformed() {
+        assert!(matches!(parse_blocklist_line("   "), Ok(None)));
+        assert!(matches!(parse_blocklist_line("# note"), Ok(None)));
+        assert!(matches!(
+            parse_blocklist_line("not-base58-0OIl"),
+            Err(ScramjetError::InvalidPubkey(_))
+        ));
+        // Oversized and non-ASCII garbage must error, not panic
+        assert!(parse_blocklist_line(&"z".repeat(10_000)).is_err());
+        assert!(parse_blocklist_line("\u{0}\u{feff}ключ").is_err());
+    }
+
+    #[test]
+    fn test_parse_blocklist_entry_fields() {
+        let pk = Pubkey::new_unique();
+        let entry = parse_blocklist_line(&format!("{}, sandwiching , 1767225600", pk))
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.reason.as_deref(), Some("sandwiching"));
+        assert_eq!(entry.expires_at, Some(1_767_225_600));
+        assert!(!entry.is_expired(1_767_225_599));
+        assert!(entry.is_expired(1_767_225_600));
+        assert_eq!(entry.to_line(), format!("{},sandwiching,1767225600", pk));
+
+        let entry = parse_blocklist_line(&format!("{},,42", pk))
+            .unwrap()
+            .unwrap();
+        assert_eq!((entry.reason, entry.expires_at), (None, Some(42)));
+        let entry = parse_blocklist_line(&format!("{},spam", pk))
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.expires_at, None);
+        assert_eq!(entry.to_line(), format!("{},spam", pk));
+
+        assert!(matches!(
+            parse_blocklist_line(&format!("{},spam,tomorrow", pk)),
+            Err(ScramjetError::InvalidBlocklistEntry(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_edit_blocklist_file() {
+        let path =
+            std::env::temp_dir().join(format!("scramjet-blocklist-{}.txt", std::process::id()));
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        std::fs::write(&path, format!("# partners-only\n{}\n", a)).unwrap();
+
+        let entry = BlocklistEntry {
+            pubkey: b,
+            reason: Some("sandwiching".into()),
+            expires_at: Some(1_767_225_600),
+        };
+        assert!(!upsert_blocklist_entry(&path, &entry).await.unwrap());
+        let updated = BlocklistEntry {
+            reason: Some("confirmed".into()),
+            expires_at: None,
+            ..entry
+        };
+        assert!(upsert_blocklist_entry(&path, &updated).await.unwrap());
+        assert_eq!(
+            read_blocklist_file(&path).await.unwrap(),
+            vec![BlocklistEntry::from(a), updated]
+        );
+
+        assert!(remove_blocklist_entry(&path, &a).await.unwrap());
+        assert!(!remove_blocklist_entry(&path, &a).await.unwrap());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            format!("# partners-only\n{},confirmed\n", b)
+        );
+
+        // Emptying the file unblocks on the next reload
+        let manager = BlocklistManager::with_config(path.clone(), None, DEFAULT_REFRESH_INTERVAL);
+        assert_eq!(manager.load_local().await, 1);
+        assert!(remove_blocklist_entry(&path, &b).await.unwrap());
+        assert_eq!(manager.reload_local().await, 0);
+        assert!(!manager.is_blocked(&b).await);
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_parse_empty_blocklist() {
         let manager = BlocklistManager::new();
@@ -334,26 +1047,202 @@ mod tests {
         assert!(!manager.is_blocked(&pk).await);
 
         // Add to blocklist
-        {
-            let mut guard = manager.blocklist.write().await;
-            guard.insert(pk);
-        }
+        manager
+            .blocklist
+            .store(Arc::new(ShieldLists::blocking(HashSet::from([pk]))));
 
         // Now blocked
         assert!(manager.is_blocked(&pk).await);
     }
 
+    #[tokio::test]
+    async fn test_temporary_block_expires_and_survives_reload() {
+        let manager = BlocklistManager::new();
+        let pk = Pubkey::new_unique();
+        let listed = Pubkey::new_unique();
+        let loaded = HashMap::from([(listed, BlocklistEntry::from(listed))]);
+        manager.install(loaded.clone()).await;
+
+        // Already on the loaded list: never temporary
+        assert!(!manager.block_temporarily(listed, Duration::ZERO).await);
+        assert!(manager.block_temporarily(pk, Duration::from_secs(60)).await);
+        assert!(!manager.block_temporarily(pk, Duration::from_secs(60)).await);
+        assert_eq!(manager.temporarily_blocked(), 1);
+
+        // A reload keeps the temporary block
+        manager.install(loaded).await;
+        assert!(manager.is_blocked(&pk).await);
+        assert!(manager.expire_temporary().await.is_empty());
+
+        // Expired: lifted, while the loaded list stays
+        manager.temporary.lock().unwrap().insert(pk, Instant::now());
+        assert_eq!(manager.expire_temporary().await, vec![pk]);
+        assert!(!manager.is_blocked(&pk).await);
+        assert!(manager.is_blocked(&listed).await);
+        assert_eq!(manager.temporarily_blocked(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_expiring_entries_are_pruned() {
+        let manager = BlocklistManager::new();
+        let (lapsed, expiring, permanent) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let now = unix_now();
+        let content = format!(
+            "{},old,{}\n{},investigation,{}\n{},sandwiching\n",
+            lapsed,
+            now - 1,
+            expiring,
+            now + 3600,
+            permanent
+        );
+        manager.install(manager.parse_blocklist(&content)).await;
+
+        // Already expired on load: never blocked
+        assert!(!manager.is_blocked(&lapsed).await);
+        assert!(manager.is_blocked(&expiring).await);
+        let reasons = manager.get_reasons_handle();
+        assert_eq!(reasons.load()[&permanent], "sandwiching");
+        assert!(manager.next_expiry().is_some());
+        assert!(manager.prune_expired().await.is_empty());
+
+        // Lapsed: pruned with its reason, the permanent entry stays
+        manager
+            .loaded
+            .lock()
+            .unwrap()
+            .get_mut(&expiring)
+            .unwrap()
+            .expires_at = Some(now);
+        assert_eq!(manager.prune_expired().await, vec![expiring]);
+        assert!(!manager.is_blocked(&expiring).await);
+        assert!(!reasons.load().contains_key(&expiring));
+        assert!(manager.is_blocked(&permanent).await);
+        assert!(manager.next_expiry().is_none());
+
+        // An expiry past what an Instant can hold is never scheduled
+        let distant = Pubkey::new_unique();
+        let content = format!("{},forever,{}\n", distant, u64::MAX);
+        manager.install(manager.parse_blocklist(&content)).await;
+        assert!(manager.is_blocked(&distant).await);
+        assert!(manager.next_expiry().is_none());
+    }
+
     #[test]
     fn test_from_env_defaults() {
         // Clear env vars to test defaults
         std::env::remove_var("SCRAMJET_BLOCKLIST_FILE");
         std::env::remove_var("SCRAMJET_BLOCKLIST_URL");
         std::env::remove_var("SCRAMJET_BLOCKLIST_REFRESH_SECS");
+        std::env::remove_var("SCRAMJET_ALLOWLIST_FILE");
+        std::env::remove_var("SCRAMJET_BLOCKLIST_PUBKEY");
 
         let manager = BlocklistManager::from_env();
         assert_eq!(manager.local_path, PathBuf::from("./blocklist.txt"));
         assert!(manager.remote_url.is_none()); // Local-only by default!
         assert_eq!(manager.refresh_interval, DEFAULT_REFRESH_INTERVAL);
+        assert!(manager.allowlist_path.is_none()); // Allowlist mode off
+        assert!(manager.publisher.is_none()); // Unsigned lists accepted
+    }
+
+    #[test]
+    fn test_signed_blocklist_verification() {
+        use solana_sdk::signature::Keypair;
+
+        let publisher = Keypair::new();
+        let trusted = publisher.pubkey();
+        let payload = format!("{}\n", Pubkey::new_unique());
+        let signed = SignedBlocklist::sign(payload.clone(), 7, &publisher);
+        let body = serde_json::to_string(&signed).unwrap();
+
+        let verified = (payload.clone(), Some(7));
+        assert_eq!(
+            verify_signed_blocklist(&body, Some(&trusted)).unwrap(),
+            verified
+        );
+        assert_eq!(verify_signed_blocklist(&body, None).unwrap(), verified);
+
+        // Unsigned lists only pass without a configured publisher
+        assert_eq!(
+            verify_signed_blocklist(&payload, None).unwrap(),
+            (payload.clone(), None)
+        );
+        assert!(matches!(
+            verify_signed_blocklist(&payload, Some(&trusted)),
+            Err(ScramjetError::BlocklistVerification(_))
+        ));
+
+        // Another publisher's valid signature is still rejected
+        let other = SignedBlocklist::sign(payload.clone(), 7, &Keypair::new());
+        let other = serde_json::to_string(&other).unwrap();
+        assert!(verify_signed_blocklist(&other, None).is_ok());
+        assert!(verify_signed_blocklist(&other, Some(&trusted)).is_err());
+
+        // Tampered payloads fail even when no publisher is configured
+        let mut tampered = signed.clone();
+        tampered.payload = String::new();
+        let tampered = serde_json::to_string(&tampered).unwrap();
+        assert!(verify_signed_blocklist(&tampered, None).is_err());
+        assert!(verify_signed_blocklist(&tampered, Some(&trusted)).is_err());
+
+        // So does a version changed after signing
+        let mut bumped = signed.clone();
+        bumped.version = 8;
+        let bumped = serde_json::to_string(&bumped).unwrap();
+        assert!(verify_signed_blocklist(&bumped, Some(&trusted)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signed_versions_only_move_forward() {
+        let dir = std::env::temp_dir().join(format!("scramjet-version-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manager = BlocklistManager::with_config(
+            dir.join("blocklist.txt"),
+            None,
+            DEFAULT_REFRESH_INTERVAL,
+        );
+        assert_eq!(manager.is_newer(5).await, Ok(true));
+        std::fs::write(manager.version_path(), "5\n").unwrap();
+        assert_eq!(manager.is_newer(5).await, Ok(false));
+        assert_eq!(manager.is_newer(6).await, Ok(true));
+        // An older signed list is a replay
+        assert!(manager.is_newer(4).await.is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_allowlist_mode() {
+        let manager = BlocklistManager::new();
+        let pk = Pubkey::new_unique();
+        assert!(manager.is_allowed(&pk).await);
+        assert_eq!(manager.load_allowlist().await, None);
+
+        // Fails closed until the file is readable
+        let path =
+            std::env::temp_dir().join(format!("scramjet-allowlist-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let manager = BlocklistManager::new().with_allowlist(path.clone());
+        assert_eq!(manager.load_allowlist().await, Some(0));
+        assert!(!manager.is_allowed(&pk).await);
+
+        std::fs::write(&path, format!("# partners\n{}\n", pk)).unwrap();
+        assert_eq!(manager.load_allowlist().await, Some(1));
+        assert!(manager.is_allowed(&pk).await);
+        assert!(!manager.is_allowed(&Pubkey::new_unique()).await);
+
+        // Blocklist updates keep the allowlist in the same snapshot
+        manager.block_temporarily(pk, Duration::from_secs(60)).await;
+        let lists = manager.get_handle().load_full();
+        assert!(lists.blocked.contains(&pk));
+        assert_eq!(lists.allowed.as_ref().map(HashSet::len), Some(1));
+        assert!(!lists.permits(&pk));
+
+        // A vanished file keeps the last good allowlist
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(manager.load_allowlist().await, Some(1));
     }
 
     #[test]
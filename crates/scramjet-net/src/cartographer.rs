@@ -1,36 +1,389 @@
-use log::{debug, info};
+use arc_swap::ArcSwap;
+use dashmap::DashMap;
+use log::{debug, info, warn};
 use scramjet_common::ScramjetError;
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::response::{RpcContactInfo, RpcVoteAccountStatus};
+use solana_sdk::hash::Hash;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
 use solana_sdk::pubkey::Pubkey;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::str::FromStr;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::time::{Duration, Instant};
+use tokio::sync::{watch, RwLock};
+use tokio::task::JoinHandle;
 
-use crate::blocklist::BlocklistHandle;
+use crate::blocklist::{BlockReasonsHandle, BlocklistHandle};
+use crate::policy::{PolicyClient, PolicyPurpose};
+use crate::proximity::RttEstimate;
+use crate::rpc_pool::RpcPool;
+use crate::schedule::{FiringPoint, LeaderWindow, LeaderWindows};
+use crate::scout::UpcomingLeader;
+use crate::slot_timing::SlotTimeModel;
+use crate::topology_cache::{self, TopologyCache, TopologySnapshot};
+use crate::validator_info::{self, GeoIp, ValidatorInfo};
+
+/// Slot regression (in slots) treated as a cluster restart rather than a stale update
+const RESTART_REGRESSION_SLOTS: u64 = 1_000;
+
+/// Slots to wait before retrying a failed schedule refresh
+const SCHEDULE_RETRY_SLOTS: u64 = 25;
+
+/// What the schedule refresher should do at a given slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleAction {
+    None,
+    /// Within the prefetch lead of the boundary: merge the next epoch's schedule
+    PrefetchNext,
+    /// Past the end of the installed epoch: re-fetch schedule and topology
+    Rollover,
+}
+
+/// Consecutive slots a leader is scheduled for (leader schedule granularity)
+const NUM_CONSECUTIVE_LEADER_SLOTS: u64 = 4;
+
+/// Most a priority fee is scaled up for a leader with little (or unknown) stake
+pub const MAX_STAKE_FEE_MULTIPLIER: f64 = 2.0;
+
+/// How an observed block producer compares to the leader schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderObservation {
+    /// Schedule agrees
+    Confirmed,
+    /// Schedule names a different leader
+    Mismatch { scheduled: Pubkey },
+    /// Slot was unscheduled; the producer's whole group was filled in
+    Derived { slots: u64 },
+}
+
+/// A slot seen starting production (its first shred or entry on Geyser),
+/// ahead of any slot status update for it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProductionStart {
+    pub slot: u64,
+    /// Scheduled leader (None off the known schedule)
+    pub leader: Option<Pubkey>,
+    /// First slot of the leader's window: a new leader just took over
+    pub window_start: bool,
+    pub seen_at: Instant,
+}
+
+/// One upcoming slot as the Cartographer sees it (`scramjet leaders`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduledSlot {
+    pub slot: u64,
+    pub leader: Pubkey,
+    /// Leader's TPU QUIC socket (None when it advertises none)
+    pub tpu: Option<SocketAddr>,
+    /// Shield would skip this leader (blocklist or allowlist)
+    pub blocked: bool,
+}
+
+/// Immutable topology snapshot: leader schedule + QUIC sockets, with stake
+/// and validator metadata. Updates
+/// publish a new snapshot, so the hot path resolves a target from one
+/// lock-free load instead of waiting on writers.
+#[derive(Debug, Default, Clone)]
+pub struct Topology {
+    pub windows: Arc<LeaderWindows>,
+    pub nodes: Arc<HashMap<Pubkey, SocketAddr>>,
+    /// Validator identity -> activated stake (lamports, all its vote accounts)
+    pub stakes: Arc<HashMap<Pubkey, u64>>,
+    pub total_stake: u64,
+    /// Validator identity -> name, version and location
+    pub validators: Arc<HashMap<Pubkey, ValidatorInfo>>,
+    /// Epoch the schedule was fetched for, swapped with it
+    pub epoch: EpochSpan,
+}
+
+/// An epoch and the slots it covers (all zero until bootstrapped)
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EpochSpan {
+    pub epoch: u64,
+    /// First slot of the epoch
+    pub start_slot: u64,
+    /// Length of the epoch in slots
+    pub slots: u64,
+}
+
+impl EpochSpan {
+    /// First slot of the next epoch
+    pub fn next_start(&self) -> u64 {
+        self.start_slot + self.slots
+    }
+}
+
+impl Topology {
+    /// Activated stake of `leader` (0 when unknown)
+    pub fn stake(&self, leader: &Pubkey) -> u64 {
+        self.stakes.get(leader).copied().unwrap_or(0)
+    }
+
+    /// What is known about `validator` (None when nothing is)
+    pub fn validator(&self, validator: &Pubkey) -> Option<&ValidatorInfo> {
+        self.validators.get(validator)
+    }
+}
+
+/// A resolved target with its leader's stake
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StakedTarget {
+    pub addr: SocketAddr,
+    pub leader: Pubkey,
+    /// Activated stake in lamports (0 when unknown)
+    pub stake: u64,
+    /// Share of the cluster's activated stake (0.0 when unknown)
+    pub stake_share: f64,
+    /// Stake relative to the average staked validator (1.0 = average)
+    pub relative_stake: f64,
+}
+
+impl StakedTarget {
+    /// `base` priority fee scaled for this leader. More stake means a leader
+    /// accepts more of what it is sent, so at or above the average stake the
+    /// fee stays at `base`; below it, it rises inversely with stake, up to
+    /// `MAX_STAKE_FEE_MULTIPLIER` (also applied when the stake is unknown).
+    pub fn priority_fee(&self, base: u64) -> u64 {
+        let multiplier = if self.relative_stake > 0.0 {
+            (1.0 / self.relative_stake).clamp(1.0, MAX_STAKE_FEE_MULTIPLIER)
+        } else {
+            MAX_STAKE_FEE_MULTIPLIER
+        };
+        (base as f64 * multiplier).round() as u64
+    }
+}
 
 /// Cartographer maintains cluster topology and leader schedule
 pub struct Cartographer {
-    rpc: Arc<RpcClient>,
-    node_map: Arc<RwLock<HashMap<Pubkey, SocketAddr>>>, // Validator pubkey -> QUIC socket
-    schedule: Arc<RwLock<HashMap<u64, Pubkey>>>,        // Slot -> Leader pubkey
+    rpc: Arc<RpcPool>,                                  // RPC endpoints with failover
+    topology: Arc<ArcSwap<Topology>>,                   // Schedule + QUIC sockets (lock-free reads)
     current_slot: Arc<AtomicU64>,                       // Atomic slot tracker (lock-free)
-    current_epoch: Arc<AtomicU64>,
-    blocklist: BlocklistHandle,                          // Shield: blocked validators
+    slot_tx: Arc<watch::Sender<u64>>,                   // Slot advance notifications
+    production: Arc<watch::Sender<Option<ProductionStart>>>,
+    prefetched_epoch: Arc<AtomicU64>,                   // Next epoch already merged (0 = none)
+    genesis_hash: Arc<RwLock<Option<Hash>>>,            // Cluster identity (hard fork check)
+    restart_suspected: Arc<AtomicBool>,                 // Set on large slot regression
+    slot_regressions: Arc<AtomicU64>,                   // Stale slot updates rejected
+    slot_time: Arc<SlotTimeModel>,                      // Calibrated slot duration
+    blocklist: BlocklistHandle,                          // Shield: blocked / allowed validators
+    block_reasons: BlockReasonsHandle,                   // Shield: why a validator is blocked
+    policy: Option<Arc<PolicyClient>>,                   // External targeting policy (optional)
+    leader_mismatches: Arc<AtomicU64>,                  // Blocks produced by an unscheduled leader
+    cache: Option<TopologyCache>,                       // Warm-start snapshot (optional)
+    geoip: Option<Arc<GeoIp>>,                          // Validator location lookups (optional)
+    rtts: Arc<DashMap<Pubkey, RttEstimate>>,            // Measured round-trip time per validator
 }
 
 impl Cartographer {
     pub fn new(rpc_url: String, blocklist: BlocklistHandle) -> Self {
-        let rpc = Arc::new(RpcClient::new(rpc_url));
+        Self::with_rpc_pool(Arc::new(RpcPool::single(rpc_url)), blocklist)
+    }
+
+    /// Route topology / schedule / slot calls through a multi-endpoint pool
+    pub fn with_rpc_pool(rpc: Arc<RpcPool>, blocklist: BlocklistHandle) -> Self {
         Self {
             rpc,
-            node_map: Arc::new(RwLock::new(HashMap::new())),
-            schedule: Arc::new(RwLock::new(HashMap::new())),
+            topology: Arc::new(ArcSwap::from_pointee(Topology::default())),
             current_slot: Arc::new(AtomicU64::new(0)),
-            current_epoch: Arc::new(AtomicU64::new(0)),
+            slot_tx: Arc::new(watch::channel(0).0),
+            production: Arc::new(watch::channel(None).0),
+            prefetched_epoch: Arc::new(AtomicU64::new(0)),
+            genesis_hash: Arc::new(RwLock::new(None)),
+            restart_suspected: Arc::new(AtomicBool::new(false)),
+            slot_regressions: Arc::new(AtomicU64::new(0)),
+            slot_time: Arc::new(SlotTimeModel::new()),
             blocklist,
+            block_reasons: Arc::new(ArcSwap::from_pointee(HashMap::new())),
+            policy: None,
+            leader_mismatches: Arc::new(AtomicU64::new(0)),
+            cache: None,
+            geoip: None,
+            rtts: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Log the Shield's reason when a blocked leader is skipped
+    pub fn with_block_reasons(mut self, reasons: BlockReasonsHandle) -> Self {
+        self.block_reasons = reasons;
+        self
+    }
+
+    /// Consult an external policy engine before targeting or pre-warming a leader
+    pub fn with_policy(mut self, policy: Arc<PolicyClient>) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Persist topology + schedule after every refresh and allow `warm_start`
+    pub fn with_topology_cache(mut self, cache: TopologyCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Locate validators' TPU addresses (country, ASN) on every topology refresh
+    pub fn with_geoip(mut self, geoip: GeoIp) -> Self {
+        self.geoip = Some(Arc::new(geoip));
+        self
+    }
+
+    /// Full bootstrap: topology + leader schedule + genesis hash snapshot
+    pub async fn bootstrap(&self) -> Result<(), ScramjetError> {
+        self.refresh_topology().await?;
+        self.refresh_metadata_or_warn().await;
+        self.update_schedule().await?;
+        let genesis = self
+            .rpc
+            .call(|rpc| async move { rpc.get_genesis_hash().await })
+            .await
+            .map_err(|e| ScramjetError::RpcError(format!("Failed to get genesis hash: {}", e)))?;
+        *self.genesis_hash.write().await = Some(genesis);
+        self.persist().await;
+        Ok(())
+    }
+
+    /// Install the cached snapshot, if any. Returns false (nothing installed)
+    /// when there is no cache or it can't be used; `bootstrap` is then required.
+    pub async fn warm_start(&self) -> bool {
+        let Some(ref cache) = self.cache else {
+            return false;
+        };
+        let snapshot = match cache.load().await {
+            Ok(Some(snapshot)) => snapshot,
+            Ok(None) => return false,
+            Err(e) => {
+                warn!("Topology cache unreadable, ignoring: {}", e);
+                return false;
+            }
+        };
+        let age = Duration::from_millis(snapshot.age_ms());
+        match self.restore(&snapshot).await {
+            Ok(()) => {
+                info!(
+                    "Warm start from {} (epoch {}, {} validators, {:.0?} old)",
+                    cache.path().display(),
+                    snapshot.epoch,
+                    snapshot.nodes.len(),
+                    age
+                );
+                true
+            }
+            Err(e) => {
+                warn!("Topology cache unusable, ignoring: {}", e);
+                false
+            }
+        }
+    }
+
+    /// After a warm start: bootstrap over RPC, discarding the cached world view
+    /// if the cluster restarted since it was saved
+    pub async fn revalidate(&self) -> Result<(), ScramjetError> {
+        if self.detect_cluster_restart().await? {
+            warn!("Cached topology is from before a cluster restart. Discarding it.");
+            self.rebootstrap().await
+        } else {
+            self.bootstrap().await
+        }
+    }
+
+    /// Current topology + schedule (None until bootstrapped)
+    pub async fn snapshot(&self) -> Option<TopologySnapshot> {
+        let genesis_hash = (*self.genesis_hash.read().await)?;
+        let topology = self.topology();
+        let span = topology.epoch;
+        if span.slots == 0 {
+            return None;
+        }
+        let epoch_start_slot = span.start_slot;
+        let nodes = topology
+            .nodes
+            .iter()
+            .map(|(pubkey, addr)| (pubkey.to_string(), *addr))
+            .collect();
+        let mut by_leader: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+        for window in topology.windows.iter() {
+            if window.end_slot < epoch_start_slot {
+                continue;
+            }
+            let offsets = by_leader.entry(window.leader).or_default();
+            for slot in window.start_slot.max(epoch_start_slot)..=window.end_slot {
+                offsets.push((slot - epoch_start_slot) as usize);
+            }
+        }
+        let schedule = by_leader
+            .into_iter()
+            .map(|(leader, mut offsets)| {
+                offsets.sort_unstable();
+                (leader.to_string(), offsets)
+            })
+            .collect();
+        Some(TopologySnapshot {
+            version: topology_cache::SNAPSHOT_VERSION,
+            saved_at_ms: topology_cache::unix_time_ms(),
+            genesis_hash: genesis_hash.to_string(),
+            epoch: span.epoch,
+            epoch_start_slot,
+            slots_in_epoch: span.slots,
+            nodes,
+            schedule,
+        })
+    }
+
+    /// Install a snapshot as the current topology + schedule. The slot clock
+    /// is left alone: Geyser / RPC slot updates are cheap compared to the
+    /// schedule and cluster node fetches.
+    pub async fn restore(&self, snapshot: &TopologySnapshot) -> Result<(), ScramjetError> {
+        let genesis = Hash::from_str(&snapshot.genesis_hash).map_err(|_| {
+            ScramjetError::SerializationError(format!(
+                "Invalid genesis hash '{}'",
+                snapshot.genesis_hash
+            ))
+        })?;
+        if snapshot.slots_in_epoch == 0 {
+            return Err(ScramjetError::ScheduleUnavailable);
+        }
+        let mut nodes = HashMap::with_capacity(snapshot.nodes.len());
+        for (pubkey, addr) in &snapshot.nodes {
+            match Pubkey::from_str(pubkey) {
+                Ok(pubkey) => {
+                    nodes.insert(pubkey, *addr);
+                }
+                Err(_) => debug!("Skipping cached node: {}", pubkey),
+            }
+        }
+        self.install_topology(nodes).await;
+        let span = EpochSpan {
+            epoch: snapshot.epoch,
+            start_slot: snapshot.epoch_start_slot,
+            slots: snapshot.slots_in_epoch,
+        };
+        self.install_epoch_schedule(
+            span,
+            parse_leader_schedule(&snapshot.schedule, span.start_slot),
+        )
+        .await;
+        self.prefetched_epoch.store(0, Ordering::Relaxed);
+        *self.genesis_hash.write().await = Some(genesis);
+        Ok(())
+    }
+
+    /// Write the current snapshot to the cache (no-op without one)
+    async fn persist(&self) {
+        let Some(ref cache) = self.cache else {
+            return;
+        };
+        let Some(snapshot) = self.snapshot().await else {
+            return;
+        };
+        match cache.save(&snapshot).await {
+            Ok(()) => debug!("Topology cache written to {}", cache.path().display()),
+            Err(e) => warn!(
+                "Failed to write topology cache {}: {}",
+                cache.path().display(),
+                e
+            ),
         }
     }
 
@@ -39,99 +392,626 @@ impl Cartographer {
         self.current_slot.load(Ordering::Relaxed)
     }
 
-    /// Update slot tracker (atomic write)
+    /// Update slot tracker. The clock only moves forward (atomic max): an
+    /// older slot, e.g. an RPC response arriving after a Geyser update, is
+    /// rejected and counted in `slot_regressions`
     pub fn update_slot(&self, slot: u64) {
-        let old = self.current_slot.swap(slot, Ordering::Relaxed);
+        let old = self.current_slot.fetch_max(slot, Ordering::Relaxed);
         if slot > old {
             debug!("Slot advanced: {} -> {}", old, slot);
+            self.slot_time.observe(slot);
+            self.slot_tx.send_replace(slot);
+            return;
+        }
+        if slot < old {
+            self.slot_regressions.fetch_add(1, Ordering::Relaxed);
+            debug!("Rejected stale slot {} (clock at {})", slot, old);
+        }
+        if slot + RESTART_REGRESSION_SLOTS < old {
+            // Regression at this scale is not a stale update: the cluster likely restarted
+            warn!("Slot regressed {} -> {}. Possible cluster restart.", old, slot);
+            self.restart_suspected.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Watch the slot clock (notified on every advance; latest slot only)
+    pub fn subscribe_slots(&self) -> watch::Receiver<u64> {
+        self.slot_tx.subscribe()
+    }
+
+    /// Record the first sign of `slot` being produced: advances the clock and
+    /// notifies production watchers. Returns false when a slot at or after
+    /// it was already seen starting.
+    pub async fn observe_production(&self, slot: u64) -> bool {
+        let newer = |last: &Option<ProductionStart>| last.is_none_or(|last| slot > last.slot);
+        if !newer(&self.production.borrow()) {
+            return false;
         }
+        self.update_slot(slot);
+        let window = self.get_leader_window(slot).await;
+        let start = ProductionStart {
+            slot,
+            leader: window.map(|window| window.leader),
+            window_start: window.is_some_and(|window| window.start_slot == slot),
+            seen_at: Instant::now(),
+        };
+        self.production.send_if_modified(|last| {
+            let newer = newer(last);
+            if newer {
+                *last = Some(start);
+            }
+            newer
+        })
+    }
+
+    /// Watch slots starting production (needs a Geyser clock with entries or
+    /// interslot updates; latest start only)
+    pub fn subscribe_production(&self) -> watch::Receiver<Option<ProductionStart>> {
+        self.production.subscribe()
+    }
+
+    /// Calibrated slot duration from observed slot intervals (nominal 400ms until warmed up)
+    pub fn slot_duration(&self) -> Duration {
+        self.slot_time.slot_duration()
+    }
+
+    /// Slot-time model shared with timing-sensitive callers
+    pub fn slot_time_model(&self) -> Arc<SlotTimeModel> {
+        self.slot_time.clone()
+    }
+
+    /// Current topology snapshot (stays valid while newer ones are published)
+    pub fn topology(&self) -> Arc<Topology> {
+        self.topology.load_full()
+    }
+
+    /// Next point `offset` into a leader window, timed from when the current
+    /// slot was first seen on the slot clock (None before the first slot
+    /// update, or off the known schedule)
+    pub async fn firing_point(&self, offset: Duration) -> Option<FiringPoint> {
+        let slot_start = self.slot_time.last_slot_start()?;
+        self.topology.load().windows.firing_point(
+            self.get_known_slot(),
+            slot_start,
+            self.slot_duration(),
+            offset,
+            Instant::now(),
+        )
+    }
+
+    /// Estimated time left in the current leader window (None before the
+    /// first slot update, or off the known schedule)
+    pub async fn remaining_in_window(&self) -> Option<Duration> {
+        let slot_start = self.slot_time.last_slot_start()?;
+        self.topology.load().windows.remaining_in_window(
+            self.get_known_slot(),
+            slot_start,
+            self.slot_duration(),
+            Instant::now(),
+        )
+    }
+
+    /// Check for signs of a cluster restart / hard fork.
+    /// Local signals (slot regression, schedule invalidation) are checked first,
+    /// then the RPC genesis hash is compared against the bootstrap snapshot.
+    pub async fn detect_cluster_restart(&self) -> Result<bool, ScramjetError> {
+        if self.restart_suspected.swap(false, Ordering::Relaxed) {
+            return Ok(true);
+        }
+
+        // Schedule invalidation: clock is behind the epoch the schedule was built for
+        let slot = self.get_known_slot();
+        let epoch_start = self.topology.load().epoch.start_slot;
+        if slot > 0 && slot < epoch_start {
+            warn!(
+                "Slot {} precedes schedule epoch start {}. Schedule invalidated.",
+                slot, epoch_start
+            );
+            return Ok(true);
+        }
+
+        let known = *self.genesis_hash.read().await;
+        if let Some(known) = known {
+            let current = self
+                .rpc
+                .call(|rpc| async move { rpc.get_genesis_hash().await })
+                .await
+                .map_err(|e| {
+                    ScramjetError::RpcError(format!("Failed to get genesis hash: {}", e))
+                })?;
+            if current != known {
+                warn!("Genesis hash changed: {} -> {}", known, current);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Discard the current world view and re-run the full bootstrap
+    pub async fn rebootstrap(&self) -> Result<(), ScramjetError> {
+        info!("Cluster restart: re-running topology + schedule bootstrap...");
+        self.topology.rcu(|topology| Topology {
+            epoch: EpochSpan::default(),
+            ..Topology::clone(topology)
+        });
+        self.prefetched_epoch.store(0, Ordering::Relaxed);
+        self.current_slot.store(0, Ordering::Relaxed);
+        self.restart_suspected.store(false, Ordering::Relaxed);
+        self.bootstrap().await
+    }
+
+    /// Leader pubkey scheduled for a slot (no Shield filtering)
+    pub async fn get_leader(&self, slot: u64) -> Option<Pubkey> {
+        self.topology
+            .load()
+            .windows
+            .window_at(slot)
+            .map(|w| w.leader)
+    }
+
+    /// Leader window containing `slot`: its leader and first / last slot
+    /// (None off the known schedule)
+    pub async fn get_leader_window(&self, slot: u64) -> Option<LeaderWindow> {
+        self.topology.load().windows.window_at(slot).copied()
+    }
+
+    /// Slots of `slot`'s leader window from `slot` on, itself included
+    /// (None off the known schedule)
+    pub async fn slots_remaining_in_window(&self, slot: u64) -> Option<u64> {
+        let window = self.get_leader_window(slot).await?;
+        Some(window.end_slot - slot + 1)
+    }
+
+    /// Whether the Shield lets `leader` be targeted (blocklist / allowlist
+    /// only, without the policy round trip of `get_target`)
+    pub fn shield_allows(&self, leader: &Pubkey) -> bool {
+        self.blocklist.load().permits(leader)
     }
 
     /// Resolve leader IP for given slot (pubkey lookup + socket resolution)
-    /// Returns None if leader is blocked by Shield
+    /// Returns None if leader is blocked (or not allowlisted) by Shield or denied by policy
     pub async fn get_target(&self, slot: u64) -> Option<SocketAddr> {
-        // Step 1: Lookup leader pubkey for this slot
-        let leader_pubkey = {
-            let schedule = self.schedule.read().await;
-            schedule.get(&slot).cloned()?
-        };
-        
-        // Step 2: Shield check - skip blocked validators
+        // Step 1: Lookup leader pubkey for this slot (one snapshot load)
+        let topology = self.topology.load();
+        let leader_pubkey = topology.windows.window_at(slot)?.leader;
+
+        // Step 2: Shield check - skip blocked (or non-allowlisted) validators
         {
-            let blocklist = self.blocklist.read().await;
-            if blocklist.contains(&leader_pubkey) {
-                debug!("Shield: Blocked {} for slot {}", leader_pubkey, slot);
+            let lists = self.blocklist.load();
+            if !lists.permits(&leader_pubkey) {
+                let reasons = self.block_reasons.load();
+                let reason = if lists.blocked.contains(&leader_pubkey) {
+                    reasons
+                        .get(&leader_pubkey)
+                        .map_or("blocklisted", String::as_str)
+                } else {
+                    "not allowlisted"
+                };
+                debug!(
+                    "Shield: Blocked {} for slot {} ({})",
+                    leader_pubkey, slot, reason
+                );
                 return None;
             }
         }
         
         // Step 3: Resolve pubkey to QUIC socket address
-        let node_map = self.node_map.read().await;
-        node_map.get(&leader_pubkey).cloned()
+        let addr = topology.nodes.get(&leader_pubkey).copied()?;
+        drop(topology); // Not held across the policy round trip
+
+        // Step 4: External policy check (optional)
+        if let Some(ref policy) = self.policy {
+            let decision = policy
+                .evaluate(PolicyPurpose::Send, slot, leader_pubkey, addr)
+                .await;
+            if !decision.is_allowed() {
+                debug!("Policy: Denied {} for slot {}", leader_pubkey, slot);
+                return None;
+            }
+        }
+        Some(addr)
+    }
+
+    /// `get_target` plus the leader's stake (for logging and fee heuristics)
+    pub async fn get_target_with_stake(&self, slot: u64) -> Option<StakedTarget> {
+        let addr = self.get_target(slot).await?;
+        let topology = self.topology.load();
+        let leader = topology.windows.window_at(slot)?.leader;
+        let stake = topology.stake(&leader);
+        let (stake_share, relative_stake) = match topology.total_stake {
+            0 => (0.0, 0.0),
+            total => {
+                let share = stake as f64 / total as f64;
+                (share, share * topology.stakes.len() as f64)
+            }
+        };
+        Some(StakedTarget {
+            addr,
+            leader,
+            stake,
+            stake_share,
+            relative_stake,
+        })
+    }
+
+    /// Target for the leader window after the one containing `slot` (the
+    /// "double tap" target), with the same filtering as `get_target`
+    pub async fn get_next_window_target(&self, slot: u64) -> Option<SocketAddr> {
+        let next_slot = match self.get_leader_window(slot).await {
+            Some(window) => window.end_slot + 1,
+            None => slot + NUM_CONSECUTIVE_LEADER_SLOTS,
+        };
+        self.get_target(next_slot).await
+    }
+
+    /// Validator identity advertising `addr` as its TPU QUIC socket (reverse topology lookup)
+    pub async fn identity_for(&self, addr: SocketAddr) -> Option<Pubkey> {
+        self.topology
+            .load()
+            .nodes
+            .iter()
+            .find(|(_, socket)| **socket == addr)
+            .map(|(pubkey, _)| *pubkey)
+    }
+
+    /// Fold an RTT probe result for `validator` into its estimate (None = the
+    /// probe failed)
+    pub fn record_rtt(&self, validator: Pubkey, sample: Option<Duration>) {
+        let previous = self.rtts.get(&validator).map(|estimate| *estimate);
+        self.rtts
+            .insert(validator, RttEstimate::update(previous, sample));
+    }
+
+    /// Smoothed round-trip time to `validator` (None until measured)
+    pub fn rtt(&self, validator: &Pubkey) -> Option<Duration> {
+        self.rtts.get(validator).and_then(|estimate| estimate.rtt)
+    }
+
+    /// When `validator` was last probed (None = never)
+    pub fn rtt_probed_at(&self, validator: &Pubkey) -> Option<Instant> {
+        self.rtts.get(validator).map(|estimate| estimate.probed_at)
+    }
+
+    /// Every measured validator with its RTT, nearest first
+    pub fn proximity_map(&self) -> Vec<(Pubkey, Duration)> {
+        let mut map: Vec<(Pubkey, Duration)> = self
+            .rtts
+            .iter()
+            .filter_map(|entry| Some((*entry.key(), entry.value().rtt?)))
+            .collect();
+        map.sort_by_key(|(_, rtt)| *rtt);
+        map
+    }
+
+    /// The scheduled slots among the `count` after `current_slot`, with their
+    /// leader, TPU socket and Shield verdict (the policy engine is not asked)
+    pub async fn upcoming_schedule(&self, current_slot: u64, count: u64) -> Vec<ScheduledSlot> {
+        let topology = self.topology.load();
+        let lists = self.blocklist.load();
+        let first = current_slot.saturating_add(1);
+        let last = current_slot.saturating_add(count);
+        topology
+            .windows
+            .upcoming(current_slot, count)
+            .iter()
+            .flat_map(|window| {
+                let tpu = topology.nodes.get(&window.leader).copied();
+                let blocked = !lists.permits(&window.leader);
+                (window.start_slot.max(first)..=window.end_slot.min(last)).map(move |slot| {
+                    ScheduledSlot {
+                        slot,
+                        leader: window.leader,
+                        tpu,
+                        blocked,
+                    }
+                })
+            })
+            .collect()
     }
 
     /// Returns deduplicated upcoming leader sockets (for Scout pre-warming)
     /// Filters out blocked validators to save resources
     pub async fn get_upcoming_leaders(&self, current_slot: u64, lookahead: u64) -> Vec<SocketAddr> {
-        let mut unique_targets = Vec::new();
-        let schedule = self.schedule.read().await;
-        let node_map = self.node_map.read().await;
-        let blocklist = self.blocklist.read().await;
-
-        // Collect unique addresses for upcoming slots (excluding blocked validators)
-        for i in 1..=lookahead {
-            let target_slot = current_slot + i;
-            if let Some(pubkey) = schedule.get(&target_slot) {
+        // Distinct upcoming leaders in schedule order: one binary search over
+        // leader windows instead of a hash lookup per slot
+        let topology = self.topology();
+        let mut leaders = Vec::new();
+        let mut seen = HashSet::new();
+        for window in topology.windows.upcoming(current_slot, lookahead) {
+            if seen.insert(window.leader) {
+                leaders.push(window.leader);
+            }
+        }
+
+        let mut candidates = Vec::with_capacity(leaders.len());
+        {
+            let lists = self.blocklist.load();
+            for pubkey in leaders {
                 // Shield: Skip blocked validators
-                if blocklist.contains(pubkey) {
+                if !lists.permits(&pubkey) {
                     debug!("Shield: Skipping blocked leader {} for scout", pubkey);
                     continue;
                 }
-                if let Some(addr) = node_map.get(pubkey) {
-                    if !unique_targets.contains(addr) {
-                        unique_targets.push(*addr);
-                    }
+                if let Some(addr) = topology.nodes.get(&pubkey) {
+                    candidates.push((pubkey, *addr));
+                }
+            }
+        }
+
+        let mut unique_targets = Vec::with_capacity(candidates.len());
+        for (pubkey, addr) in candidates {
+            if unique_targets.contains(&addr) {
+                continue;
+            }
+            if let Some(ref policy) = self.policy {
+                let decision = policy
+                    .evaluate(PolicyPurpose::Scout, current_slot, pubkey, addr)
+                    .await;
+                if !decision.is_allowed() {
+                    continue;
                 }
             }
+            unique_targets.push(addr);
         }
         unique_targets
     }
 
+    /// Like `get_upcoming_leaders`, but with each leader's slot share of the
+    /// lookahead window (input for Scout budget allocation)
+    pub async fn get_upcoming_leader_slots(
+        &self,
+        current_slot: u64,
+        lookahead: u64,
+    ) -> Vec<UpcomingLeader> {
+        let first = current_slot.saturating_add(1);
+        let last = current_slot.saturating_add(lookahead);
+
+        let mut upcoming: Vec<UpcomingLeader> = Vec::new();
+        {
+            let topology = self.topology.load();
+            let lists = self.blocklist.load();
+            for window in topology.windows.upcoming(current_slot, lookahead) {
+                // Shield: Skip blocked validators
+                if !lists.permits(&window.leader) {
+                    continue;
+                }
+                let Some(addr) = topology.nodes.get(&window.leader) else {
+                    continue;
+                };
+                let start = window.start_slot.max(first);
+                let slots = window.end_slot.min(last) - start + 1;
+                match upcoming.iter_mut().find(|u| u.addr == *addr) {
+                    Some(existing) => existing.slots += slots,
+                    None => upcoming.push(UpcomingLeader {
+                        leader: window.leader,
+                        addr: *addr,
+                        first_slot: start,
+                        slots,
+                        stake: topology.stake(&window.leader),
+                        rtt: self.rtt(&window.leader),
+                        priority: 0,
+                    }),
+                }
+            }
+        }
+
+        if let Some(ref policy) = self.policy {
+            let mut allowed = Vec::with_capacity(upcoming.len());
+            for mut candidate in upcoming {
+                let decision = policy
+                    .evaluate(
+                        PolicyPurpose::Scout,
+                        candidate.first_slot,
+                        candidate.leader,
+                        candidate.addr,
+                    )
+                    .await;
+                if decision.is_allowed() {
+                    candidate.priority = decision.priority;
+                    allowed.push(candidate);
+                }
+            }
+            upcoming = allowed;
+        }
+        upcoming
+    }
+
     /// Fetch cluster topology (validator pubkey -> QUIC socket mapping)
     pub async fn refresh_topology(&self) -> Result<(), ScramjetError> {
         info!("Refreshing cluster topology via RPC...");
         let nodes = self
             .rpc
-            .get_cluster_nodes()
+            .call(|rpc| async move { rpc.get_cluster_nodes().await })
             .await
             .map_err(|e| ScramjetError::RpcError(format!("Failed to fetch nodes: {}", e)))?;
         let mut new_map = HashMap::new();
+        let mut versions = HashMap::new();
 
         for node in nodes {
-            if let Some(tpu_quic) = node.tpu_quic {
-                if let Ok(pubkey) = Pubkey::from_str(&node.pubkey) {
+            match parse_cluster_node(&node) {
+                Ok(Some((pubkey, tpu_quic))) => {
                     new_map.insert(pubkey, tpu_quic);
+                    if let Some(version) = node.version {
+                        versions.insert(pubkey, version);
+                    }
                 }
+                Ok(None) => {}
+                Err(e) => debug!("Skipping cluster node: {}", e),
+            }
+        }
+        let count = new_map.len();
+        let validators = self.describe_nodes(&new_map, versions);
+        self.install_topology(new_map).await;
+        self.install_validators(validators).await;
+        info!("Topology updated. Known QUIC Validators: {}", count);
+        Ok(())
+    }
+
+    /// Metadata for a fresh node map: the gossip versions, a GeoIP lookup of
+    /// each TPU address, and the names already known
+    fn describe_nodes(
+        &self,
+        nodes: &HashMap<Pubkey, SocketAddr>,
+        mut versions: HashMap<Pubkey, String>,
+    ) -> HashMap<Pubkey, ValidatorInfo> {
+        let mut validators: HashMap<Pubkey, ValidatorInfo> = self
+            .topology
+            .load()
+            .validators
+            .iter()
+            .filter(|(_, info)| info.name.is_some())
+            .map(|(pubkey, info)| {
+                let named = ValidatorInfo {
+                    name: info.name.clone(),
+                    ..ValidatorInfo::default()
+                };
+                (*pubkey, named)
+            })
+            .collect();
+        for (pubkey, addr) in nodes {
+            let version = versions.remove(pubkey);
+            let location = self
+                .geoip
+                .as_ref()
+                .map(|geoip| geoip.lookup(addr.ip()))
+                .unwrap_or_default();
+            if version.is_none() && location.is_empty() {
+                continue;
             }
+            let info = validators.entry(*pubkey).or_default();
+            info.version = version;
+            info.location = location;
         }
-        let mut map_guard = self.node_map.write().await;
-        *map_guard = new_map;
+        validators
+    }
+
+    /// Replace the validator metadata map (identity -> name, version, location)
+    pub async fn install_validators(&self, validators: HashMap<Pubkey, ValidatorInfo>) {
+        let validators = Arc::new(validators);
+        self.topology.rcu(|topology| Topology {
+            validators: validators.clone(),
+            ..Topology::clone(topology)
+        });
+    }
+
+    /// Fetch validator names from the on-chain Validator Info records
+    pub async fn refresh_validator_names(&self) -> Result<(), ScramjetError> {
+        let names = validator_info::fetch_onchain_names(&self.rpc).await?;
+        let count = names.len();
+        self.install_validator_names(names).await;
+        info!("Validator names updated. Named validators: {}", count);
+        Ok(())
+    }
+
+    /// Replace every validator's name, keeping versions and locations
+    pub async fn install_validator_names(&self, names: HashMap<Pubkey, String>) {
+        self.topology.rcu(|topology| {
+            let mut validators: HashMap<Pubkey, ValidatorInfo> = topology
+                .validators
+                .iter()
+                .map(|(pubkey, info)| {
+                    let unnamed = ValidatorInfo {
+                        name: None,
+                        ..info.clone()
+                    };
+                    (*pubkey, unnamed)
+                })
+                .collect();
+            for (pubkey, name) in &names {
+                validators.entry(*pubkey).or_default().name = Some(name.clone());
+            }
+            Topology {
+                validators: Arc::new(validators),
+                ..Topology::clone(topology)
+            }
+        });
+    }
+
+    /// Replace the topology map (validator pubkey -> QUIC socket)
+    pub async fn install_topology(&self, nodes: HashMap<Pubkey, SocketAddr>) {
+        let nodes = Arc::new(nodes);
+        self.topology.rcu(|topology| Topology {
+            nodes: nodes.clone(),
+            ..Topology::clone(topology)
+        });
+    }
+
+    /// Fetch activated stake per validator (vote accounts, delinquent included)
+    pub async fn refresh_stakes(&self) -> Result<(), ScramjetError> {
+        let accounts = self
+            .rpc
+            .call(|rpc| async move { rpc.get_vote_accounts().await })
+            .await
+            .map_err(|e| {
+                ScramjetError::RpcError(format!("Failed to fetch vote accounts: {}", e))
+            })?;
+        let stakes = parse_vote_accounts(&accounts);
+        let count = stakes.len();
+        self.install_stakes(stakes).await;
         info!(
-            "Topology updated. Known QUIC Validators: {}",
-            map_guard.len()
+            "Stakes updated. Staked validators: {} ({:.0} SOL active)",
+            count,
+            self.topology.load().total_stake as f64 / LAMPORTS_PER_SOL as f64
         );
         Ok(())
     }
 
+    /// Stakes only weight logging, fees and the Scout, and names are for
+    /// display: keep going without them
+    async fn refresh_metadata_or_warn(&self) {
+        if let Err(e) = self.refresh_stakes().await {
+            warn!("Stake refresh failed: {}. Keeping previous stakes.", e);
+        }
+        if let Err(e) = self.refresh_validator_names().await {
+            warn!(
+                "Validator name refresh failed: {}. Keeping previous names.",
+                e
+            );
+        }
+    }
+
+    /// Replace the stake map (validator identity -> activated stake)
+    pub async fn install_stakes(&self, stakes: HashMap<Pubkey, u64>) {
+        let total_stake = stakes.values().sum();
+        let stakes = Arc::new(stakes);
+        self.topology.rcu(|topology| Topology {
+            stakes: stakes.clone(),
+            total_stake,
+            ..Topology::clone(topology)
+        });
+    }
+
+    /// Replace the leader schedule (absolute slot -> leader pubkey). The
+    /// windows are built off to the side and published in one swap, so
+    /// readers never wait for an epoch refresh.
+    pub async fn install_schedule(&self, schedule: HashMap<u64, Pubkey>) {
+        let windows = Arc::new(LeaderWindows::from_schedule(&schedule));
+        self.topology.rcu(|topology| Topology {
+            windows: windows.clone(),
+            ..Topology::clone(topology)
+        });
+    }
+
+    /// Replace the leader schedule and the epoch it covers in the same swap,
+    /// so no reader pairs the new schedule with the old epoch bounds
+    pub async fn install_epoch_schedule(&self, epoch: EpochSpan, schedule: HashMap<u64, Pubkey>) {
+        let windows = Arc::new(LeaderWindows::from_schedule(&schedule));
+        self.topology.rcu(|topology| Topology {
+            windows: windows.clone(),
+            epoch,
+            ..Topology::clone(topology)
+        });
+    }
+
     /// Update leader schedule for current epoch (refresh on epoch change)
     pub async fn update_schedule(&self) -> Result<(), ScramjetError> {
         let epoch_info = self
             .rpc
-            .get_epoch_info()
+            .call(|rpc| async move { rpc.get_epoch_info().await })
             .await
             .map_err(|e| ScramjetError::RpcError(format!("Failed to get epoch info: {}", e)))?;
         let current_epoch = epoch_info.epoch;
-        let stored_epoch = self.current_epoch.load(Ordering::Relaxed);
+        let stored_epoch = self.topology.load().epoch.epoch;
 
         // Only refresh if epoch changed or first run
         if current_epoch > stored_epoch || stored_epoch == 0 {
@@ -141,61 +1021,308 @@ impl Cartographer {
             );
             let schedule_data = self
                 .rpc
-                .get_leader_schedule(None)
+                .call(|rpc| async move { rpc.get_leader_schedule(None).await })
                 .await
                 .map_err(|e| ScramjetError::RpcError(format!("Failed to get leader schedule: {}", e)))?
                 .ok_or(ScramjetError::ScheduleUnavailable)?;
 
-            let mut new_schedule = HashMap::new();
-            let start_slot = epoch_info.absolute_slot - epoch_info.slot_index;
+            let start_slot = epoch_info
+                .absolute_slot
+                .checked_sub(epoch_info.slot_index)
+                .ok_or(ScramjetError::ScheduleUnavailable)?;
+            let new_schedule = parse_leader_schedule(&schedule_data, start_slot);
+            let span = EpochSpan {
+                epoch: current_epoch,
+                start_slot,
+                slots: epoch_info.slots_in_epoch,
+            };
 
-            // Convert relative slot offsets to absolute slot numbers
-            for (pubkey_str, relative_slots) in schedule_data {
-                if let Ok(pubkey) = Pubkey::from_str(&pubkey_str) {
-                    for rel_slot in relative_slots {
-                        let abs_slot = start_slot + rel_slot as u64;
-                        new_schedule.insert(abs_slot, pubkey);
+            self.install_epoch_schedule(span, new_schedule).await;
+            self.update_slot(epoch_info.absolute_slot);
+        }
+        Ok(())
+    }
+
+    /// Merge slots into the leader schedule (existing entries are kept)
+    pub async fn merge_schedule(&self, additional: HashMap<u64, Pubkey>) {
+        let additional = LeaderWindows::from_schedule(&additional);
+        self.topology.rcu(|topology| {
+            let mut windows = LeaderWindows::clone(&topology.windows);
+            windows.merge(&additional);
+            Topology {
+                windows: Arc::new(windows),
+                ..Topology::clone(topology)
+            }
+        });
+    }
+
+    /// Fetch the next epoch's leader schedule ahead of the boundary and merge
+    /// it, so targeting does not stall while the rollover refresh runs.
+    /// Returns false if the cluster has not published it yet.
+    pub async fn prefetch_next_epoch(&self) -> Result<bool, ScramjetError> {
+        let span = self.topology.load().epoch;
+        let next_epoch = span.epoch + 1;
+        if self.prefetched_epoch.load(Ordering::Relaxed) == next_epoch {
+            return Ok(true);
+        }
+        let next_start = span.next_start();
+        let Some(schedule_data) = self
+            .rpc
+            .call(|rpc| async move { rpc.get_leader_schedule(Some(next_start)).await })
+            .await
+            .map_err(|e| {
+                ScramjetError::RpcError(format!("Failed to get leader schedule: {}", e))
+            })?
+        else {
+            return Ok(false);
+        };
+
+        self.merge_schedule(parse_leader_schedule(&schedule_data, next_start))
+            .await;
+        self.prefetched_epoch.store(next_epoch, Ordering::Relaxed);
+        info!(
+            "Prefetched Leader Schedule for epoch {} (starts at slot {})",
+            next_epoch, next_start
+        );
+        Ok(true)
+    }
+
+    /// Decide whether `slot` calls for a prefetch or a rollover refresh
+    pub fn schedule_action(&self, slot: u64, prefetch_lead: u64) -> ScheduleAction {
+        let span = self.topology.load().epoch;
+        if span.slots == 0 {
+            return ScheduleAction::None; // Not bootstrapped
+        }
+        let next_start = span.next_start();
+        let next_epoch = span.epoch + 1;
+        if slot >= next_start {
+            ScheduleAction::Rollover
+        } else if prefetch_lead > 0
+            && slot + prefetch_lead >= next_start
+            && self.prefetched_epoch.load(Ordering::Relaxed) != next_epoch
+        {
+            ScheduleAction::PrefetchNext
+        } else {
+            ScheduleAction::None
+        }
+    }
+
+    /// Watch the slot clock and keep the schedule current across epochs:
+    /// prefetch the next epoch `prefetch_lead` slots before the boundary,
+    /// then re-fetch schedule and topology once the boundary is crossed.
+    pub fn spawn_schedule_refresher(self: Arc<Self>, prefetch_lead: u64) -> JoinHandle<()> {
+        let mut slots = self.subscribe_slots();
+        tokio::spawn(async move {
+            let mut retry_at = 0;
+            while slots.changed().await.is_ok() {
+                let slot = *slots.borrow_and_update();
+                if slot < retry_at {
+                    continue;
+                }
+                let result = match self.schedule_action(slot, prefetch_lead) {
+                    ScheduleAction::None => continue,
+                    ScheduleAction::PrefetchNext => self.prefetch_next_epoch().await.map(|_| ()),
+                    ScheduleAction::Rollover => {
+                        info!("Epoch boundary crossed at slot {}. Refreshing...", slot);
+                        match self.update_schedule().await {
+                            Ok(()) => {
+                                let refreshed = self.refresh_topology().await;
+                                self.refresh_metadata_or_warn().await;
+                                refreshed
+                            }
+                            Err(e) => Err(e),
+                        }
+                    }
+                };
+                match result {
+                    // Not published yet / RPC still on the old epoch: check again later
+                    Ok(()) if self.schedule_action(slot, prefetch_lead) != ScheduleAction::None => {
+                        retry_at = slot + SCHEDULE_RETRY_SLOTS;
+                    }
+                    Ok(()) => self.persist().await,
+                    Err(e) => {
+                        warn!("Schedule refresh failed at slot {}: {}", slot, e);
+                        retry_at = slot + SCHEDULE_RETRY_SLOTS;
                     }
                 }
             }
+        })
+    }
 
-            let mut schedule_guard = self.schedule.write().await;
-            *schedule_guard = new_schedule;
-            self.current_epoch.store(current_epoch, Ordering::Relaxed);
-            self.update_slot(epoch_info.absolute_slot);
+    /// Cross-check a produced block's leader (from Geyser block metadata)
+    /// against the schedule. Unscheduled slots (RPC schedule missing or
+    /// degraded) are filled in for the whole leader group the slot belongs
+    /// to, so targeting keeps working until the next schedule fetch.
+    pub async fn observe_block_leader(&self, slot: u64, leader: Pubkey) -> LeaderObservation {
+        let scheduled = self.get_leader(slot).await;
+        match scheduled {
+            Some(scheduled) if scheduled == leader => LeaderObservation::Confirmed,
+            Some(scheduled) => {
+                self.leader_mismatches.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    "Schedule mismatch at slot {}: expected {}, block produced by {}",
+                    slot, scheduled, leader
+                );
+                LeaderObservation::Mismatch { scheduled }
+            }
+            None => {
+                let group_start = slot - slot % NUM_CONSECUTIVE_LEADER_SLOTS;
+                let group_end = group_start + NUM_CONSECUTIVE_LEADER_SLOTS - 1;
+                let derived = LeaderWindow {
+                    leader,
+                    start_slot: group_start,
+                    end_slot: group_end,
+                };
+                let mut inserted = false;
+                self.topology.rcu(|topology| {
+                    let mut windows = LeaderWindows::clone(&topology.windows);
+                    inserted = windows.insert(derived);
+                    Topology {
+                        windows: Arc::new(windows),
+                        ..Topology::clone(topology)
+                    }
+                });
+                if !inserted {
+                    // Part of the group is scheduled: leave it to the schedule
+                    return LeaderObservation::Derived { slots: 0 };
+                }
+                let slots = group_end - group_start + 1;
+                debug!(
+                    "Derived leader {} for unscheduled slots {}..={}",
+                    leader, group_start, group_end
+                );
+                LeaderObservation::Derived { slots }
+            }
         }
-        Ok(())
+    }
+
+    /// Slot updates rejected for being older than the clock
+    pub fn slot_regressions(&self) -> u64 {
+        self.slot_regressions.load(Ordering::Relaxed)
+    }
+
+    /// Blocks observed from a leader other than the scheduled one
+    pub fn leader_mismatches(&self) -> u64 {
+        self.leader_mismatches.load(Ordering::Relaxed)
     }
 
     /// Fetch current slot from RPC and update tracker (legacy polling mode)
     pub async fn fetch_rpc_slot(&self) -> Result<u64, ScramjetError> {
         let slot = self
             .rpc
-            .get_slot()
+            .call(|rpc| async move { rpc.get_slot().await })
             .await
             .map_err(|e| ScramjetError::RpcError(format!("Failed to get slot: {}", e)))?;
         self.update_slot(slot);
         Ok(slot)
     }
 
+    /// Healthiest RPC endpoint's client (for calls outside the failover pool)
     pub fn rpc_client(&self) -> Arc<RpcClient> {
+        self.rpc.client()
+    }
+
+    pub fn rpc_pool(&self) -> Arc<RpcPool> {
         self.rpc.clone()
     }
 }
 
+/// Parse a cluster node entry into (identity, TPU QUIC socket).
+/// Returns `Ok(None)` for nodes that don't advertise a QUIC TPU port.
+pub fn parse_cluster_node(
+    node: &RpcContactInfo,
+) -> Result<Option<(Pubkey, SocketAddr)>, ScramjetError> {
+    let Some(tpu_quic) = node.tpu_quic else {
+        return Ok(None);
+    };
+    let pubkey = Pubkey::from_str(&node.pubkey)
+        .map_err(|_| ScramjetError::InvalidPubkey(node.pubkey.clone()))?;
+    Ok(Some((pubkey, tpu_quic)))
+}
+
+/// Activated stake per validator identity, summed over its vote accounts.
+/// Entries with an invalid node pubkey are skipped.
+pub fn parse_vote_accounts(accounts: &RpcVoteAccountStatus) -> HashMap<Pubkey, u64> {
+    let mut stakes = HashMap::new();
+    for account in accounts.current.iter().chain(&accounts.delinquent) {
+        let Ok(node) = Pubkey::from_str(&account.node_pubkey) else {
+            debug!(
+                "Skipping invalid vote account node: {}",
+                account.node_pubkey
+            );
+            continue;
+        };
+        *stakes.entry(node).or_insert(0) += account.activated_stake;
+    }
+    stakes
+}
+
+/// Convert an RPC leader schedule (relative slot offsets) to absolute slots.
+/// Invalid pubkeys and offsets that would overflow are skipped.
+pub fn parse_leader_schedule(
+    schedule: &HashMap<String, Vec<usize>>,
+    start_slot: u64,
+) -> HashMap<u64, Pubkey> {
+    let mut absolute = HashMap::new();
+    for (pubkey_str, relative_slots) in schedule {
+        let Ok(pubkey) = Pubkey::from_str(pubkey_str) else {
+            debug!("Skipping invalid schedule pubkey: {}", pubkey_str);
+            continue;
+        };
+        for rel_slot in relative_slots {
+            if let Some(abs_slot) = start_slot.checked_add(*rel_slot as u64) {
+                absolute.insert(abs_slot, pubkey);
+            }
+        }
+    }
+    absolute
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashSet;
+    use crate::blocklist::{blocklist_handle, ShieldLists};
+    use solana_rpc_client_api::response::RpcVoteAccountInfo;
 
     fn create_empty_blocklist() -> BlocklistHandle {
-        Arc::new(RwLock::new(HashSet::new()))
+        blocklist_handle(HashSet::new())
     }
 
     fn create_empty_cartographer() -> Cartographer {
         Cartographer::new("http://mock-rpc".to_string(), create_empty_blocklist())
     }
 
+    /// Shield lists in allowlist mode, allowing only `partner`
+    fn allowlist_shield(partner: Pubkey) -> BlocklistHandle {
+        Arc::new(ArcSwap::from_pointee(ShieldLists {
+            blocked: HashSet::new(),
+            allowed: Some(HashSet::from([partner])),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_observe_production() {
+        let c = create_empty_cartographer();
+        let leader = Pubkey::new_unique();
+        c.install_schedule((100..104).map(|slot| (slot, leader)).collect())
+            .await;
+        let mut starts = c.subscribe_production();
+
+        assert!(c.observe_production(100).await);
+        assert_eq!(c.get_known_slot(), 100);
+        let start = starts.borrow_and_update().unwrap();
+        assert_eq!((start.slot, start.leader), (100, Some(leader)));
+        assert!(start.window_start);
+
+        // Later entries of the same slot, or older slots, are not new starts
+        assert!(!c.observe_production(100).await);
+        assert!(!c.observe_production(99).await);
+        assert!(!starts.has_changed().unwrap());
+
+        assert!(c.observe_production(101).await);
+        assert!(!starts.borrow_and_update().unwrap().window_start);
+    }
+
     #[test]
     fn test_atomic_clock_basics() {
         let c = create_empty_cartographer();
@@ -206,6 +1333,199 @@ mod tests {
         assert_eq!(c.get_known_slot(), 101);
     }
 
+    #[tokio::test]
+    async fn test_observe_block_leader() {
+        let c = create_empty_cartographer();
+        let scheduled = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        c.install_schedule((100..104).map(|slot| (slot, scheduled)).collect())
+            .await;
+        c.install_topology([(other, addr)].into_iter().collect())
+            .await;
+
+        assert_eq!(
+            c.observe_block_leader(100, scheduled).await,
+            LeaderObservation::Confirmed
+        );
+        assert_eq!(
+            c.observe_block_leader(101, other).await,
+            LeaderObservation::Mismatch { scheduled }
+        );
+        assert_eq!(c.leader_mismatches(), 1);
+
+        // Unscheduled slot 105: producer gets its whole group (104..=107)
+        assert_eq!(
+            c.observe_block_leader(105, other).await,
+            LeaderObservation::Derived { slots: 4 }
+        );
+        assert_eq!(c.get_leader(104).await, Some(other));
+        assert_eq!(c.get_leader(107).await, Some(other));
+        assert_eq!(c.get_target(106).await, Some(addr));
+        assert_eq!(c.get_upcoming_leaders(104, 3).await, vec![addr]);
+        assert!(c.get_leader(108).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_action_around_epoch_boundary() {
+        let c = create_empty_cartographer();
+        assert_eq!(c.schedule_action(500, 100), ScheduleAction::None);
+
+        let span = EpochSpan {
+            epoch: 7,
+            start_slot: 1_000,
+            slots: 1_000,
+        };
+        c.install_epoch_schedule(span, HashMap::new()).await;
+
+        assert_eq!(c.schedule_action(1_500, 100), ScheduleAction::None);
+        assert_eq!(c.schedule_action(1_900, 100), ScheduleAction::PrefetchNext);
+        assert_eq!(c.schedule_action(1_900, 0), ScheduleAction::None);
+        c.prefetched_epoch.store(8, Ordering::Relaxed);
+        assert_eq!(c.schedule_action(1_950, 100), ScheduleAction::None);
+        assert_eq!(c.schedule_action(2_000, 100), ScheduleAction::Rollover);
+
+        // Merged next-epoch slots extend the schedule without clobbering it
+        let current = Pubkey::new_unique();
+        let next = Pubkey::new_unique();
+        c.install_schedule([(1_999, current)].into_iter().collect())
+            .await;
+        c.merge_schedule([(1_999, next), (2_000, next)].into_iter().collect())
+            .await;
+        assert_eq!(c.get_leader(1_999).await, Some(current));
+        assert_eq!(c.get_leader(2_000).await, Some(next));
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_slots_sees_advances_only() {
+        let c = create_empty_cartographer();
+        let mut slots = c.subscribe_slots();
+        c.update_slot(100);
+        slots.changed().await.unwrap();
+        assert_eq!(*slots.borrow_and_update(), 100);
+
+        // Stale update does not notify (nor move the clock back)
+        c.update_slot(99);
+        assert!(!slots.has_changed().unwrap());
+        assert_eq!(c.get_known_slot(), 100);
+        // A repeat is not a regression
+        c.update_slot(100);
+        assert_eq!(c.slot_regressions(), 1);
+        c.update_slot(102);
+        assert_eq!(*slots.borrow_and_update(), 102);
+    }
+
+    #[tokio::test]
+    async fn test_slot_regression_flags_restart() {
+        let c = create_empty_cartographer();
+        c.update_slot(250_000);
+        // Small regression (stale update) is not a restart
+        c.update_slot(249_990);
+        assert!(!c.restart_suspected.load(Ordering::Relaxed));
+
+        c.update_slot(250_000);
+        c.update_slot(10);
+        assert!(c.detect_cluster_restart().await.unwrap());
+        // Signal is consumed once reported
+        assert!(!c.restart_suspected.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn test_schedule_invalidation_flags_restart() {
+        let c = create_empty_cartographer();
+        let span = EpochSpan {
+            start_slot: 1_000,
+            ..EpochSpan::default()
+        };
+        c.install_epoch_schedule(span, HashMap::new()).await;
+        c.current_slot.store(999, Ordering::Relaxed);
+        assert!(c.detect_cluster_restart().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_target_with_stake() {
+        let c = create_empty_cartographer();
+        let (whale, minnow) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let addr: SocketAddr = "10.0.0.1:8009".parse().unwrap();
+        let vote = |node: &Pubkey, activated_stake| RpcVoteAccountInfo {
+            vote_pubkey: Pubkey::new_unique().to_string(),
+            node_pubkey: node.to_string(),
+            activated_stake,
+            commission: 0,
+            epoch_vote_account: true,
+            epoch_credits: Vec::new(),
+            last_vote: 0,
+            root_slot: 0,
+        };
+        // Two vote accounts for one identity add up; invalid nodes are skipped
+        let accounts = RpcVoteAccountStatus {
+            current: vec![vote(&whale, 600), vote(&minnow, 100)],
+            delinquent: vec![
+                vote(&whale, 300),
+                RpcVoteAccountInfo {
+                    node_pubkey: "not-a-pubkey".into(),
+                    ..vote(&minnow, 50)
+                },
+            ],
+        };
+        let stakes = parse_vote_accounts(&accounts);
+        assert_eq!(stakes.len(), 2);
+        assert_eq!(stakes[&whale], 900);
+        c.install_stakes(stakes).await;
+        c.install_schedule(HashMap::from([(100, whale), (101, minnow)]))
+            .await;
+        c.install_topology(HashMap::from([(whale, addr), (minnow, addr)]))
+            .await;
+
+        let target = c.get_target_with_stake(100).await.unwrap();
+        assert_eq!(
+            (target.addr, target.leader, target.stake),
+            (addr, whale, 900)
+        );
+        assert_eq!(target.stake_share, 0.9);
+        assert_eq!(target.priority_fee(1_000), 1_000);
+
+        // A tenth of the stake, a fifth of the average: fee capped at 2x
+        let target = c.get_target_with_stake(101).await.unwrap();
+        assert_eq!(target.relative_stake, 0.2);
+        assert_eq!(target.priority_fee(1_000), 2_000);
+        assert!(c.get_target_with_stake(102).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_validator_metadata() {
+        let c = create_empty_cartographer();
+        let (alpha, beta) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let addr: SocketAddr = "10.0.0.1:8009".parse().unwrap();
+        c.install_validator_names(HashMap::from([(alpha, "Alpha".to_string())]))
+            .await;
+
+        // A node refresh keeps names and replaces versions
+        let nodes = HashMap::from([(alpha, addr), (beta, addr)]);
+        let validators = c.describe_nodes(&nodes, HashMap::from([(beta, "2.1.5".to_string())]));
+        c.install_validators(validators).await;
+        let topology = c.topology();
+        assert_eq!(
+            topology.validator(&alpha).unwrap().name.as_deref(),
+            Some("Alpha")
+        );
+        assert_eq!(topology.validator(&alpha).unwrap().version, None);
+        assert_eq!(
+            topology.validator(&beta).unwrap().version.as_deref(),
+            Some("2.1.5")
+        );
+
+        // A name refresh keeps versions and drops names no longer published
+        c.install_validator_names(HashMap::from([(beta, "Beta".to_string())]))
+            .await;
+        let topology = c.topology();
+        assert_eq!(topology.validator(&alpha).unwrap().name, None);
+        assert_eq!(
+            topology.validator(&beta).unwrap().to_string(),
+            "Beta (v2.1.5)"
+        );
+    }
+
     #[tokio::test]
     async fn test_topology_resolution() {
         let c = create_empty_cartographer();
@@ -213,14 +1533,8 @@ mod tests {
         let addr: SocketAddr = "127.0.0.1:8000".parse().unwrap();
 
         // Simulate Schedule and Topology update
-        {
-            let mut sched = c.schedule.write().await;
-            sched.insert(500, pk);
-        }
-        {
-            let mut nodes = c.node_map.write().await;
-            nodes.insert(pk, addr);
-        }
+        c.install_schedule(HashMap::from([(500, pk)])).await;
+        c.install_topology(HashMap::from([(pk, addr)])).await;
 
         // Test Hit
         let result = c.get_target(500).await;
@@ -231,6 +1545,46 @@ mod tests {
         assert_eq!(miss, None);
     }
 
+    #[tokio::test]
+    async fn test_warm_start_from_topology_cache() {
+        let path = std::env::temp_dir().join(format!(
+            "scramjet-cartographer-cache-{}.json",
+            std::process::id()
+        ));
+        let leader = Pubkey::new_unique();
+        let addr: SocketAddr = "10.0.0.7:8009".parse().unwrap();
+
+        let c = create_empty_cartographer().with_topology_cache(TopologyCache::new(&path));
+        assert!(!c.warm_start().await);
+        // Not bootstrapped: nothing to persist
+        assert!(c.snapshot().await.is_none());
+
+        c.install_topology(HashMap::from([(leader, addr)])).await;
+        let span = EpochSpan {
+            epoch: 3,
+            start_slot: 1_000,
+            slots: 500,
+        };
+        c.install_epoch_schedule(span, HashMap::from([(1_004, leader), (1_005, leader)]))
+            .await;
+        *c.genesis_hash.write().await = Some(Hash::new_unique());
+        c.persist().await;
+
+        let restarted = create_empty_cartographer().with_topology_cache(TopologyCache::new(&path));
+        assert!(restarted.warm_start().await);
+        assert_eq!(restarted.get_leader(1_005).await, Some(leader));
+        assert_eq!(restarted.get_target(1_004).await, Some(addr));
+        assert_eq!(
+            restarted.schedule_action(1_500, 0),
+            ScheduleAction::Rollover
+        );
+        assert_eq!(
+            *restarted.genesis_hash.read().await,
+            *c.genesis_hash.read().await
+        );
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[tokio::test]
     async fn test_shield_blocks_malicious_validator() {
         let blocklist = create_empty_blocklist();
@@ -240,24 +1594,17 @@ mod tests {
         let addr2: SocketAddr = "2.2.2.2:80".parse().unwrap();
 
         // Add malicious validator to blocklist
-        {
-            let mut guard = blocklist.write().await;
-            guard.insert(malicious_pk);
-        }
+        blocklist.store(Arc::new(ShieldLists::blocking(HashSet::from([
+            malicious_pk,
+        ]))));
 
         let c = Cartographer::new("http://mock-rpc".to_string(), blocklist);
 
         // Setup schedule and topology
-        {
-            let mut sched = c.schedule.write().await;
-            sched.insert(100, malicious_pk);
-            sched.insert(101, good_pk);
-        }
-        {
-            let mut nodes = c.node_map.write().await;
-            nodes.insert(malicious_pk, addr1);
-            nodes.insert(good_pk, addr2);
-        }
+        c.install_schedule(HashMap::from([(100, malicious_pk), (101, good_pk)]))
+            .await;
+        c.install_topology(HashMap::from([(malicious_pk, addr1), (good_pk, addr2)]))
+            .await;
 
         // Blocked validator should return None
         assert_eq!(c.get_target(100).await, None);
@@ -265,6 +1612,50 @@ mod tests {
         assert_eq!(c.get_target(101).await, Some(addr2));
     }
 
+    #[tokio::test]
+    async fn test_shield_allowlist_mode() {
+        let partner = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let addr1: SocketAddr = "1.1.1.1:80".parse().unwrap();
+        let addr2: SocketAddr = "2.2.2.2:80".parse().unwrap();
+        let shield = allowlist_shield(partner);
+        let c = Cartographer::new("http://mock-rpc".to_string(), shield.clone());
+        c.install_schedule(HashMap::from([(100, partner), (101, other)]))
+            .await;
+        c.install_topology(HashMap::from([(partner, addr1), (other, addr2)]))
+            .await;
+
+        assert_eq!(c.get_target(100).await, Some(addr1));
+        assert_eq!(c.get_target(101).await, None);
+        assert_eq!(c.get_upcoming_leaders(99, 4).await, vec![addr1]);
+
+        // Allowlist mode off: everyone is a target again
+        shield.store(Arc::new(ShieldLists::default()));
+        assert_eq!(c.get_target(101).await, Some(addr2));
+    }
+
+    #[tokio::test]
+    async fn test_upcoming_schedule_view() {
+        let partner = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let addr: SocketAddr = "1.1.1.1:80".parse().unwrap();
+        let c = Cartographer::new("http://mock-rpc".to_string(), allowlist_shield(partner));
+        let schedule = HashMap::from([(100, partner), (101, other), (103, partner)]);
+        c.install_schedule(schedule).await;
+        c.install_topology(HashMap::from([(partner, addr)])).await;
+
+        // Unscheduled slots are skipped; blocked leaders are still listed
+        let view = c.upcoming_schedule(99, 5).await;
+        let slots: Vec<u64> = view.iter().map(|s| s.slot).collect();
+        assert_eq!(slots, vec![100, 101, 103]);
+        assert_eq!(view[0].tpu, Some(addr));
+        assert!(!view[0].blocked);
+        assert_eq!(view[1].leader, other);
+        assert_eq!(view[1].tpu, None);
+        assert!(view[1].blocked);
+        assert!(c.upcoming_schedule(103, 10).await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_scout_lookahead() {
         let c = create_empty_cartographer();
@@ -274,17 +1665,10 @@ mod tests {
         let addr2: SocketAddr = "2.2.2.2:80".parse().unwrap();
 
         // Schedule: Slot 101->A, 102->A, 103->B
-        {
-            let mut sched = c.schedule.write().await;
-            sched.insert(101, pk1);
-            sched.insert(102, pk1);
-            sched.insert(103, pk2);
-        }
-        {
-            let mut nodes = c.node_map.write().await;
-            nodes.insert(pk1, addr1);
-            nodes.insert(pk2, addr2);
-        }
+        c.install_schedule(HashMap::from([(101, pk1), (102, pk1), (103, pk2)]))
+            .await;
+        c.install_topology(HashMap::from([(pk1, addr1), (pk2, addr2)]))
+            .await;
 
         // Scout looking ahead 5 slots from 100
         let targets = c.get_upcoming_leaders(100, 5).await;
@@ -293,6 +1677,54 @@ mod tests {
         assert_eq!(targets.len(), 2);
         assert!(targets.contains(&addr1));
         assert!(targets.contains(&addr2));
+
+        // Slot shares are clipped to the window: (101, 102] leaves one slot each
+        let shares = c.get_upcoming_leader_slots(101, 2).await;
+        assert_eq!(shares.len(), 2);
+        assert_eq!(
+            (shares[0].addr, shares[0].first_slot, shares[0].slots),
+            (addr1, 102, 1)
+        );
+        assert_eq!((shares[1].addr, shares[1].slots), (addr2, 1));
+    }
+
+    #[tokio::test]
+    async fn test_next_window_target() {
+        let c = create_empty_cartographer();
+        let pk1 = Pubkey::new_unique();
+        let pk2 = Pubkey::new_unique();
+        let addr1: SocketAddr = "1.1.1.1:80".parse().unwrap();
+        let addr2: SocketAddr = "2.2.2.2:80".parse().unwrap();
+        let schedule = (100..104)
+            .map(|slot| (slot, pk1))
+            .chain((104..108).map(|slot| (slot, pk2)));
+        c.install_schedule(schedule.collect()).await;
+        c.install_topology(HashMap::from([(pk1, addr1), (pk2, addr2)]))
+            .await;
+
+        // From anywhere in A's window, the next window is B's
+        assert_eq!(c.get_next_window_target(100).await, Some(addr2));
+        assert_eq!(c.get_next_window_target(103).await, Some(addr2));
+        // Off the schedule past B's window
+        assert_eq!(c.get_next_window_target(105).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_leader_window() {
+        let c = create_empty_cartographer();
+        let leader = Pubkey::new_unique();
+        c.install_schedule((100..104).map(|slot| (slot, leader)).collect())
+            .await;
+
+        let window = c.get_leader_window(102).await.unwrap();
+        assert_eq!(
+            (window.leader, window.start_slot, window.end_slot),
+            (leader, 100, 103)
+        );
+        assert_eq!(c.slots_remaining_in_window(100).await, Some(4));
+        assert_eq!(c.slots_remaining_in_window(103).await, Some(1));
+        assert_eq!(c.get_leader_window(104).await, None);
+        assert_eq!(c.slots_remaining_in_window(99).await, None);
     }
 
     #[tokio::test]
@@ -304,24 +1736,18 @@ mod tests {
         let good_addr: SocketAddr = "2.2.2.2:80".parse().unwrap();
 
         // Block one validator
-        {
-            let mut guard = blocklist.write().await;
-            guard.insert(blocked_pk);
-        }
+        blocklist.store(Arc::new(ShieldLists::blocking(HashSet::from([blocked_pk]))));
 
         let c = Cartographer::new("http://mock-rpc".to_string(), blocklist);
 
         // Schedule: Slot 101->blocked, 102->good
-        {
-            let mut sched = c.schedule.write().await;
-            sched.insert(101, blocked_pk);
-            sched.insert(102, good_pk);
-        }
-        {
-            let mut nodes = c.node_map.write().await;
-            nodes.insert(blocked_pk, blocked_addr);
-            nodes.insert(good_pk, good_addr);
-        }
+        c.install_schedule(HashMap::from([(101, blocked_pk), (102, good_pk)]))
+            .await;
+        c.install_topology(HashMap::from([
+            (blocked_pk, blocked_addr),
+            (good_pk, good_addr),
+        ]))
+        .await;
 
         // Scout should only return the good validator
         let targets = c.get_upcoming_leaders(100, 5).await;
@@ -329,4 +1755,41 @@ mod tests {
         assert!(targets.contains(&good_addr));
         assert!(!targets.contains(&blocked_addr));
     }
+
+    #[test]
+    fn test_parse_cluster_node_malformed() {
+        let addr: SocketAddr = "1.1.1.1:8009".parse().unwrap();
+        let mut node: RpcContactInfo = serde_json::from_str(
+            r#"{"pubkey":"not-a-pubkey","gossip":null,"tvu":null,"tpu":null,"tpuQuic":null,
+                "tpuForwards":null,"tpuForwardsQuic":null,"tpuVote":null,"serveRepair":null,
+                "rpc":null,"pubsub":null,"version":null,"featureSet":null,"shredVersion":null}"#,
+        )
+        .unwrap();
+
+        // No QUIC port: skipped regardless of pubkey
+        assert!(matches!(parse_cluster_node(&node), Ok(None)));
+
+        node.tpu_quic = Some(addr);
+        assert!(matches!(
+            parse_cluster_node(&node),
+            Err(ScramjetError::InvalidPubkey(_))
+        ));
+
+        let pk = Pubkey::new_unique();
+        node.pubkey = pk.to_string();
+        assert_eq!(parse_cluster_node(&node).unwrap(), Some((pk, addr)));
+    }
+
+    #[test]
+    fn test_parse_leader_schedule_overflow() {
+        let pk = Pubkey::new_unique();
+        let mut schedule = HashMap::new();
+        schedule.insert(pk.to_string(), vec![0, 1, usize::MAX]);
+        schedule.insert("garbage".to_string(), vec![2]);
+
+        let parsed = parse_leader_schedule(&schedule, u64::MAX - 1);
+        // Offsets past u64::MAX are dropped instead of panicking
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed.get(&(u64::MAX - 1)), Some(&pk));
+    }
 }
@@ -0,0 +1,434 @@
+//! Control socket for long-running scramjet processes.
+//!
+//! A Unix domain socket speaking one JSON line per request/response. Operators
+//! (and `scramjet status`) connect, write a command such as `status`, and read
+//! back a single JSON document.
+//!
+//! Admin commands manage a live sender without a restart:
+//! - `reload-blocklist`: re-read the Shield's local blocklist file (`scramjet
+//!   shield` sends it after editing the file)
+//! - `reload-config`: re-read the configuration and apply what can change live
+//! - `flush-connections`: close every warm QUIC connection (re-handshake on
+//!   the next send)
+//! - `set-rate <spec|off> [burst]`: replace the send rate limit
+
+use crate::blocklist::BlocklistManager;
+use crate::cartographer::Cartographer;
+use crate::dedupe::Dedupe;
+use crate::engine::QuicEngine;
+use crate::geyser::GeyserHealth;
+use crate::queue::SendQueue;
+use crate::rate_limit::{RateLimitHandle, RateLimiter};
+use crate::rpc_pool::RpcEndpointHealth;
+use crate::slo::SloMonitor;
+use crate::stats::StatsReport;
+use log::{debug, info, warn};
+use scramjet_common::ScramjetError;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+/// Socket path used when none is configured
+pub const DEFAULT_CONTROL_SOCKET: &str = "/tmp/scramjet.sock";
+
+/// Live status snapshot of a running process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusReport {
+    /// Slot clock source ("geyser", "websocket" or "rpc-poll")
+    pub clock: String,
+    pub slot: u64,
+    pub slot_time_ms: u64,
+    /// Stale slot updates rejected by the clock
+    #[serde(default)]
+    pub slot_regressions: u64,
+    /// Milliseconds since the latest Geyser slot update (None = no Geyser clock or none yet)
+    #[serde(default)]
+    pub geyser_last_slot_ms: Option<u64>,
+    /// Geyser streams reconnected after going quiet for `GEYSER_STALL_TIMEOUT_MS`
+    #[serde(default)]
+    pub geyser_stalls: u64,
+    pub leader: Option<String>,
+    pub leader_addr: Option<SocketAddr>,
+    pub warm_connections: usize,
+    /// None when the process has no send queue
+    pub queue_depth: Option<usize>,
+    /// Submissions the send queue dropped or rejected because it was full
+    #[serde(default)]
+    pub queue_overflows: u64,
+    /// Transactions dropped unsent because their deadline passed
+    #[serde(default)]
+    pub expired_before_send: u64,
+    /// Transactions refused as sent within `DEDUPE_WINDOW_MS`
+    #[serde(default)]
+    pub duplicates_refused: u64,
+    /// Fraction of sends landed over the last 5 minutes (None without confirmation tracking)
+    pub landing_rate_5m: Option<f64>,
+    /// RPC endpoints in configured order (failover health)
+    #[serde(default)]
+    pub rpc: Vec<RpcEndpointHealth>,
+    /// Send rate limit in sends per second (None = unlimited)
+    #[serde(default)]
+    pub send_rate: Option<f64>,
+    pub uptime_secs: u64,
+}
+
+/// Re-reads the configuration and applies what can change live; returns the
+/// names of the settings applied
+pub type ConfigReloader = Arc<dyn Fn() -> Result<Vec<String>, ScramjetError> + Send + Sync>;
+
+/// State the control socket reports on
+pub struct ControlContext {
+    pub cartographer: Arc<Cartographer>,
+    pub engine: Arc<QuicEngine>,
+    pub clock: String,
+    pub started: Instant,
+    /// Send outcome tracker (None when no landing SLOs are configured)
+    pub landing: Option<Arc<SloMonitor>>,
+    /// Shield, for `reload-blocklist` (None = not reloadable)
+    pub shield: Option<Arc<BlocklistManager>>,
+    /// Send rate limit, for `set-rate` (None = not adjustable)
+    pub rate_limit: Option<RateLimitHandle>,
+    /// Send queue, for its depth (None = no queue)
+    pub queue: Option<Arc<SendQueue>>,
+    /// Duplicate guard, for its refusals (None = off)
+    pub dedupe: Option<Arc<Dedupe>>,
+    /// Config source, for `reload-config` (None = not reloadable)
+    pub reload_config: Option<ConfigReloader>,
+    /// Geyser stream liveness (None = not on the Geyser clock)
+    pub geyser: Option<Arc<GeyserHealth>>,
+}
+
+impl ControlContext {
+    pub async fn status(&self) -> StatusReport {
+        let slot = self.cartographer.get_known_slot();
+        StatusReport {
+            clock: self.clock.clone(),
+            slot,
+            slot_time_ms: self.cartographer.slot_duration().as_millis() as u64,
+            slot_regressions: self.cartographer.slot_regressions(),
+            geyser_last_slot_ms: self
+                .geyser
+                .as_ref()
+                .and_then(|health| health.since_last_slot())
+                .map(|elapsed| elapsed.as_millis() as u64),
+            geyser_stalls: self.geyser.as_ref().map_or(0, |health| health.stalls()),
+            leader: self
+                .cartographer
+                .get_leader(slot)
+                .await
+                .map(|pk| pk.to_string()),
+            leader_addr: self.cartographer.get_target(slot).await,
+            warm_connections: self.engine.warm_connections(),
+            queue_depth: self.queue.as_ref().map(|queue| queue.len()),
+            queue_overflows: self.queue.as_ref().map_or(0, |queue| queue.overflows()),
+            expired_before_send: self.engine.expired_before_send(),
+            duplicates_refused: self.dedupe.as_ref().map_or(0, |dedupe| dedupe.refused()),
+            landing_rate_5m: self.landing.as_ref().and_then(|m| m.landing_rate()),
+            rpc: self.cartographer.rpc_pool().health(),
+            send_rate: self
+                .rate_limit
+                .as_ref()
+                .and_then(|handle| handle.load().as_ref().map(|limiter| limiter.rate())),
+            uptime_secs: self.started.elapsed().as_secs(),
+        }
+    }
+
+    async fn handle(&self, command: &str) -> String {
+        let mut args = command.split_whitespace();
+        let verb = args.next().unwrap_or_default();
+        let args: Vec<&str> = args.collect();
+        match verb {
+            "status" => serde_json::to_string(&self.status().await)
+                .unwrap_or_else(|e| error_json(&e.to_string())),
+            "stats" => {
+                self.engine.sample_paths();
+                let report = self.engine.stats().report(&self.cartographer).await;
+                serde_json::to_string(&report).unwrap_or_else(|e| error_json(&e.to_string()))
+            }
+            "reload-blocklist" => match self.shield {
+                Some(ref shield) => {
+                    let blocked = shield.reload_local().await;
+                    info!("Control: Blocklist reloaded ({} entries)", blocked);
+                    serde_json::json!({ "blocked": blocked }).to_string()
+                }
+                None => error_json("no blocklist to reload"),
+            },
+            "reload-config" => match self.reload_config {
+                Some(ref reload) => match reload() {
+                    Ok(applied) => {
+                        info!("Control: Config reloaded (applied: {:?})", applied);
+                        serde_json::json!({ "applied": applied }).to_string()
+                    }
+                    Err(e) => error_json(&e.to_string()),
+                },
+                None => error_json("no config source to reload"),
+            },
+            "flush-connections" => {
+                let flushed = self.engine.quarantine_connections();
+                info!("Control: Flushed {} connections", flushed);
+                serde_json::json!({ "flushed": flushed }).to_string()
+            }
+            "set-rate" => self.set_rate(&args),
+            other => error_json(&format!("unknown command: {}", other)),
+        }
+    }
+
+    /// `set-rate <spec|off> [burst]`
+    fn set_rate(&self, args: &[&str]) -> String {
+        let Some(ref handle) = self.rate_limit else {
+            return error_json("send rate is not adjustable");
+        };
+        let limiter = match args {
+            ["off"] => Ok(None),
+            [spec] => RateLimiter::from_spec(spec, 0).map(Some),
+            [spec, burst] => match burst.parse() {
+                Ok(burst) => RateLimiter::from_spec(spec, burst).map(Some),
+                Err(_) => return error_json(&format!("invalid burst: {}", burst)),
+            },
+            _ => return error_json("usage: set-rate <spec|off> [burst]"),
+        };
+        match limiter {
+            Ok(Some(limiter)) => {
+                let (rate, burst) = (limiter.rate(), limiter.burst());
+                handle.store(Some(Arc::new(limiter)));
+                info!(
+                    "Control: Rate limit set to {:.0} sends/s (burst {})",
+                    rate, burst
+                );
+                serde_json::json!({ "rate": rate, "burst": burst }).to_string()
+            }
+            Ok(None) => {
+                handle.store(None);
+                info!("Control: Rate limit removed");
+                serde_json::json!({ "rate": null }).to_string()
+            }
+            Err(e) => error_json(&e.to_string()),
+        }
+    }
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Bind a Unix socket at `path` that only its owner may connect to (anyone
+/// who can reach it can drive the process). A socket left behind by a process
+/// that is gone is replaced; a live socket, or anything that is not a socket,
+/// is an error.
+pub fn bind_private_socket(path: &Path) -> Result<UnixListener, ScramjetError> {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_socket() => {
+            if std::os::unix::net::UnixStream::connect(path).is_ok() {
+                return Err(ScramjetError::ConfigError(format!(
+                    "{:?} is in use by another running process",
+                    path
+                )));
+            }
+            std::fs::remove_file(path)?;
+        }
+        Ok(_) => {
+            return Err(ScramjetError::ConfigError(format!(
+                "{:?} exists and is not a socket",
+                path
+            )))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Bind the control socket (see `bind_private_socket`) and serve requests in
+/// the background
+pub fn spawn_control_server(
+    path: PathBuf,
+    context: Arc<ControlContext>,
+) -> Result<tokio::task::JoinHandle<()>, ScramjetError> {
+    let listener = bind_private_socket(&path)?;
+    info!("Control socket listening on {:?}", path);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let context = context.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = serve_connection(stream, context).await {
+                            debug!("Control connection error: {}", e);
+                        }
+                    });
+                }
+                Err(e) => warn!("Control socket accept failed: {}", e),
+            }
+        }
+    }))
+}
+
+async fn serve_connection(
+    stream: UnixStream,
+    context: Arc<ControlContext>,
+) -> Result<(), ScramjetError> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let mut response = context.handle(&line).await;
+        response.push('\n');
+        writer.write_all(response.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Send one command to a control socket and return the raw JSON response
+pub async fn send_command(path: &Path, command: &str) -> Result<String, ScramjetError> {
+    let stream = UnixStream::connect(path)
+        .await
+        .map_err(|e| ScramjetError::ConnectionError(format!("Control socket {:?}: {}", path, e)))?;
+    let (reader, mut writer) = stream.into_split();
+    writer
+        .write_all(format!("{}\n", command).as_bytes())
+        .await?;
+
+    let mut lines = BufReader::new(reader).lines();
+    let response = lines.next_line().await?.ok_or_else(|| {
+        ScramjetError::ConnectionError("Control socket closed without a response".into())
+    })?;
+
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_str(&response) {
+        if let Some(error) = map.get("error").and_then(|e| e.as_str()) {
+            return Err(ScramjetError::ConnectionError(error.to_string()));
+        }
+    }
+    Ok(response)
+}
+
+/// Fetch a status snapshot from a running process
+pub async fn request_status(path: &Path) -> Result<StatusReport, ScramjetError> {
+    let response = send_command(path, "status").await?;
+    serde_json::from_str(&response).map_err(|e| ScramjetError::SerializationError(e.to_string()))
+}
+
+/// Fetch per-leader statistics from a running process
+pub async fn request_stats(path: &Path) -> Result<StatsReport, ScramjetError> {
+    let response = send_command(path, "stats").await?;
+    serde_json::from_str(&response).map_err(|e| ScramjetError::SerializationError(e.to_string()))
+}
+
+/// Ask a running process to reload its blocklist file; returns the number
+/// of entries now loaded
+pub async fn request_blocklist_reload(path: &Path) -> Result<usize, ScramjetError> {
+    let response = send_command(path, "reload-blocklist").await?;
+    serde_json::from_str::<serde_json::Value>(&response)
+        .ok()
+        .and_then(|value| value.get("blocked")?.as_u64())
+        .map(|blocked| blocked as usize)
+        .ok_or_else(|| ScramjetError::SerializationError(format!("Unexpected reply: {}", response)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocklist::blocklist_handle;
+    use scramjet_common::Config;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::signature::Keypair;
+    use std::collections::{HashMap, HashSet};
+
+    #[tokio::test]
+    async fn test_status_roundtrip() {
+        let blocklist = blocklist_handle(HashSet::new());
+        let cartographer = Arc::new(Cartographer::new("http://mock-rpc".into(), blocklist));
+        let leader = Pubkey::new_unique();
+        let addr: SocketAddr = "1.1.1.1:8009".parse().unwrap();
+        cartographer
+            .install_schedule(HashMap::from([(500, leader)]))
+            .await;
+        cartographer
+            .install_topology(HashMap::from([(leader, addr)]))
+            .await;
+        cartographer.update_slot(500);
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = Arc::new(QuicEngine::new(&Keypair::new(), &config).unwrap());
+        let context = Arc::new(ControlContext {
+            cartographer,
+            engine,
+            clock: "rpc-poll".into(),
+            started: Instant::now(),
+            landing: None,
+            shield: None,
+            rate_limit: Some(crate::rate_limit::rate_limit_handle(None)),
+            queue: Some(Arc::new(SendQueue::from_config(&config).unwrap())),
+            dedupe: None,
+            reload_config: Some(Arc::new(|| Ok(vec!["send_rate_limit".to_string()]))),
+            geyser: None,
+        });
+
+        let path = std::env::temp_dir().join(format!("scramjet-test-{}.sock", std::process::id()));
+        let server = spawn_control_server(path.clone(), context).unwrap();
+
+        let status = request_status(&path).await.unwrap();
+        assert_eq!(status.slot, 500);
+        assert_eq!(status.slot_regressions, 0);
+        assert_eq!(status.leader, Some(leader.to_string()));
+        assert_eq!(status.leader_addr, Some(addr));
+        assert_eq!(status.warm_connections, 0);
+        assert_eq!(status.queue_depth, Some(0));
+        assert_eq!(status.rpc.len(), 1);
+        assert_eq!(status.rpc[0].url, "http://mock-rpc");
+
+        let stats = request_stats(&path).await.unwrap();
+        assert!(stats.leaders.is_empty());
+
+        assert!(send_command(&path, "bogus").await.is_err());
+        assert!(request_blocklist_reload(&path).await.is_err());
+
+        // Admin commands
+        assert_eq!(status.send_rate, None);
+        let reply = send_command(&path, "set-rate 50/100ms 5").await.unwrap();
+        assert_eq!(reply, r#"{"burst":5,"rate":500.0}"#);
+        assert_eq!(request_status(&path).await.unwrap().send_rate, Some(500.0));
+        assert!(send_command(&path, "set-rate fast").await.is_err());
+        send_command(&path, "set-rate off").await.unwrap();
+        assert_eq!(request_status(&path).await.unwrap().send_rate, None);
+        assert_eq!(
+            send_command(&path, "flush-connections").await.unwrap(),
+            r#"{"flushed":0}"#
+        );
+        assert_eq!(
+            send_command(&path, "reload-config").await.unwrap(),
+            r#"{"applied":["send_rate_limit"]}"#
+        );
+
+        server.abort();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_bind_private_socket() {
+        let path = std::env::temp_dir().join(format!("scramjet-bind-{}.sock", std::process::id()));
+        let listener = bind_private_socket(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+
+        // A live socket is not taken over
+        assert!(bind_private_socket(&path).is_err());
+        // A stale one is
+        drop(listener);
+        let listener = bind_private_socket(&path).unwrap();
+        drop(listener);
+
+        // Anything else at the path is left alone
+        std::fs::remove_file(&path).unwrap();
+        std::fs::write(&path, b"not a socket").unwrap();
+        assert!(bind_private_socket(&path).is_err());
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a socket");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,92 @@
+//! Per-transaction deadlines.
+//!
+//! A transaction queued behind a busy sender (or a rate limit) can outlive its
+//! usefulness: its blockhash expires, or the opportunity it was built for is
+//! gone. A [`Deadline`] travels with the transaction and is checked right
+//! before it is written to the leader; expired transactions are dropped and
+//! counted instead of being transmitted.
+
+use scramjet_common::ScramjetError;
+
+/// Last point at which a transaction is still worth sending
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deadline {
+    /// Send while the current slot is at most this one
+    Slot(u64),
+    /// Send while the block height is at most this one (a blockhash's
+    /// `lastValidBlockHeight`)
+    BlockHeight(u64),
+}
+
+impl Deadline {
+    /// Whether the deadline has passed at `slot` and `block_height`. An
+    /// unknown block height never expires a `BlockHeight` deadline.
+    pub fn is_expired(&self, slot: u64, block_height: Option<u64>) -> bool {
+        match *self {
+            Deadline::Slot(last) => slot > last,
+            Deadline::BlockHeight(last) => block_height.is_some_and(|height| height > last),
+        }
+    }
+
+    /// Error for a transaction dropped at `slot`
+    pub fn expired_error(&self, slot: u64) -> ScramjetError {
+        ScramjetError::DeadlineExpired(format!("{} passed at slot {}", self, slot))
+    }
+}
+
+impl std::fmt::Display for Deadline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Deadline::Slot(slot) => write!(f, "slot {}", slot),
+            Deadline::BlockHeight(height) => write!(f, "block height {}", height),
+        }
+    }
+}
+
+impl std::str::FromStr for Deadline {
+    type Err = ScramjetError;
+
+    /// `slot:<n>` or `height:<n>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            ScramjetError::ConfigValidationError(format!(
+                "Invalid deadline '{}' (expected slot:<n> or height:<n>)",
+                s
+            ))
+        };
+        let (kind, value) = s.trim().split_once(':').ok_or_else(invalid)?;
+        let value: u64 = value.trim().parse().map_err(|_| invalid())?;
+        match kind.trim() {
+            "slot" => Ok(Deadline::Slot(value)),
+            "height" => Ok(Deadline::BlockHeight(value)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_expiry() {
+        let slot = Deadline::Slot(100);
+        assert!(!slot.is_expired(100, None));
+        assert!(slot.is_expired(101, Some(0)));
+
+        let height = Deadline::BlockHeight(50);
+        assert!(!height.is_expired(1_000, Some(50)));
+        assert!(height.is_expired(0, Some(51)));
+        // Unknown height: keep sending
+        assert!(!height.is_expired(u64::MAX, None));
+
+        assert_eq!("slot:100".parse::<Deadline>().unwrap(), slot);
+        assert_eq!(" height: 50".parse::<Deadline>().unwrap(), height);
+        assert!("epoch:3".parse::<Deadline>().is_err());
+        assert!("slot".parse::<Deadline>().is_err());
+        assert!(slot
+            .expired_error(101)
+            .to_string()
+            .contains("slot 100 passed at slot 101"));
+    }
+}
@@ -0,0 +1,174 @@
+//! Duplicate submission guard.
+//!
+//! With `DEDUPE_WINDOW_MS` set, pre-signed transactions (`send_raw`: `serve`
+//! and `send-raw`) claim their signature before they are sent. The same
+//! signature submitted again within the window is refused with
+//! [`ScramjetError::DuplicateTransaction`] instead of going out twice, e.g.
+//! when a client pipes the same transaction into the daemon twice. A send
+//! that fails gives its claim back, so the transaction can be submitted
+//! again right away.
+//!
+//! Signatures are kept in arrival order (a ring of at most `DEDUPE_CAPACITY`)
+//! plus a map for lookups; the oldest are forgotten once they leave the
+//! window or the ring is full.
+
+use scramjet_common::{Config, ScramjetError};
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Default)]
+struct Recent {
+    /// Live claims, oldest first
+    order: VecDeque<(Signature, Instant)>,
+    /// Live claims and when they were made
+    claimed: HashMap<Signature, Instant>,
+}
+
+/// Recently sent signatures
+#[derive(Debug)]
+pub struct Dedupe {
+    window: Duration,
+    capacity: usize,
+    recent: Mutex<Recent>,
+    /// Submissions refused as duplicates
+    refused: AtomicU64,
+}
+
+impl Dedupe {
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity: capacity.max(1),
+            recent: Mutex::new(Recent::default()),
+            refused: AtomicU64::new(0),
+        }
+    }
+
+    /// The guard `DEDUPE_WINDOW_MS` / `DEDUPE_CAPACITY` ask for (None = off)
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .dedupe_window()
+            .map(|window| Self::new(window, config.dedupe_capacity))
+    }
+
+    /// Claim `signature` for the window; fails if it is already claimed
+    pub fn claim(&self, signature: Signature) -> Result<(), ScramjetError> {
+        self.claim_at(signature, Instant::now())
+    }
+
+    /// Give a claim back (its send failed)
+    pub fn release(&self, signature: &Signature) {
+        let mut recent = self.recent.lock().unwrap();
+        let Recent { order, claimed } = &mut *recent;
+        if let Some(at) = claimed.remove(signature) {
+            // Failed sends are recent: search from the newest end
+            if let Some(index) = order.iter().rposition(|entry| *entry == (*signature, at)) {
+                order.remove(index);
+            }
+        }
+    }
+
+    /// Submissions refused as duplicates
+    pub fn refused(&self) -> u64 {
+        self.refused.load(Ordering::Relaxed)
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    fn claim_at(&self, signature: Signature, now: Instant) -> Result<(), ScramjetError> {
+        let mut recent = self.recent.lock().unwrap();
+        let Recent { order, claimed } = &mut *recent;
+        while order
+            .front()
+            .is_some_and(|&(_, at)| now.duration_since(at) >= self.window)
+        {
+            forget_oldest(order, claimed);
+        }
+        if claimed.contains_key(&signature) {
+            self.refused.fetch_add(1, Ordering::Relaxed);
+            return Err(ScramjetError::DuplicateTransaction(format!(
+                "{} was already submitted within the last {:?}",
+                signature, self.window
+            )));
+        }
+        claimed.insert(signature, now);
+        order.push_back((signature, now));
+        while order.len() > self.capacity {
+            forget_oldest(order, claimed);
+        }
+        Ok(())
+    }
+}
+
+fn forget_oldest(
+    order: &mut VecDeque<(Signature, Instant)>,
+    claimed: &mut HashMap<Signature, Instant>,
+) {
+    if let Some((oldest, _)) = order.pop_front() {
+        claimed.remove(&oldest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedupe_window() {
+        let dedupe = Dedupe::new(Duration::from_secs(10), 2);
+        let (a, b, c) = (
+            Signature::new_unique(),
+            Signature::new_unique(),
+            Signature::new_unique(),
+        );
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        dedupe.claim_at(a, at(0)).unwrap();
+        assert!(matches!(
+            dedupe.claim_at(a, at(5)),
+            Err(ScramjetError::DuplicateTransaction(_))
+        ));
+        assert_eq!(dedupe.refused(), 1);
+
+        // Out of the window: sent again
+        dedupe.claim_at(a, at(10)).unwrap();
+
+        // A failed send gives its claim back
+        dedupe.claim_at(b, at(11)).unwrap();
+        dedupe.release(&b);
+        dedupe.claim_at(b, at(12)).unwrap();
+
+        // Full: the oldest claim is forgotten early
+        dedupe.claim_at(c, at(13)).unwrap();
+        dedupe.claim_at(a, at(14)).unwrap();
+        assert!(dedupe.claim_at(c, at(15)).is_err());
+        assert_eq!(dedupe.refused(), 2);
+    }
+
+    #[test]
+    fn test_release_frees_capacity() {
+        let dedupe = Dedupe::new(Duration::from_secs(10), 2);
+        let (a, b) = (Signature::new_unique(), Signature::new_unique());
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        dedupe.claim_at(a, at(0)).unwrap();
+        // Retried after failed sends: one slot, however often it is released
+        for secs in 1..4 {
+            dedupe.claim_at(b, at(secs)).unwrap();
+            dedupe.release(&b);
+        }
+        dedupe.claim_at(b, at(4)).unwrap();
+
+        // At capacity, but nothing live was evicted
+        assert!(dedupe.claim_at(a, at(5)).is_err());
+        assert!(dedupe.claim_at(b, at(5)).is_err());
+        assert_eq!(dedupe.recent.lock().unwrap().order.len(), 2);
+    }
+}
@@ -1,60 +1,582 @@
-use dashmap::DashMap;
-use log::{debug, info};
-use quinn::{Connection, Endpoint};
-use scramjet_common::{create_quic_config, Config, ScramjetError};
+use arc_swap::ArcSwapOption;
+use bytes::Bytes;
+use dashmap::{DashMap, DashSet};
+use futures::FutureExt;
+use log::{debug, info, warn};
+use quinn::{Connection, Endpoint, SendStream};
+use scramjet_common::{create_quic_config, Config, ScramjetError, SendFailure, SendFailureKind};
+use socket2::{Domain, Protocol, Socket, Type};
+use solana_sdk::packet::PACKET_DATA_SIZE;
 use solana_sdk::signature::Keypair;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::journal::Journal;
+use crate::leader_tracker::ActiveConnection;
+use crate::priority::{Priority, StreamBudget, StreamPermit};
+use crate::retry::RetryPolicy;
+use crate::runtime::SendRuntime;
+use crate::score::ValidatorScore;
+use crate::stats::StatsRegistry;
+
+/// Application close code sent to validators on graceful shutdown
+pub const SHUTDOWN_CLOSE_CODE: u32 = 0;
+
+/// Application code for streams reset by the send timeout
+/// (`QUIC_SEND_TIMEOUT_MS`), so a half-written transaction is discarded
+pub const SEND_TIMEOUT_RESET_CODE: u32 = 1;
+
+/// Poll interval while waiting for in-flight sends to finish on shutdown
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How `QuicEngine::send_batch` maps transactions to streams
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchMode {
+    /// One stream per transaction, as validator TPUs expect
+    #[default]
+    StreamPerTx,
+    /// Consecutive transactions back to back in one stream, up to
+    /// `PACKET_DATA_SIZE` bytes each. Transactions are self-delimiting, so a
+    /// receiver can deserialize them in turn; stock Agave and Firedancer TPUs
+    /// read a stream as one transaction, so this is only for receivers
+    /// (relays, custom ingest) that unpack streams.
+    Packed,
+}
+
+/// Received-datagram count of a pooled connection when last probed
+#[derive(Debug, Clone, Copy)]
+struct Liveness {
+    rx_datagrams: u64,
+    /// When the count last grew (or the connection was made)
+    since: Instant,
+}
+
+/// Result of one `QuicEngine::probe_connections` pass
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProbeReport {
+    /// Open connections still hearing from their leader
+    pub healthy: usize,
+    /// Leaders with an open connection whose keep-alives go unanswered
+    pub stale: Vec<SocketAddr>,
+    /// Closed connections dropped from the cache
+    pub dead: usize,
+}
+
+/// Which client identity a validator accepted a handshake from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdentityKind {
+    /// The configured (usually staked) identity
+    Primary,
+    /// Secondary identity (explicit keypair or unstaked ephemeral key)
+    Fallback,
+}
+
+/// Stream limits a validator grants our connection (stake-weighted QoS).
+/// quinn does not expose the peer's transport parameters, so the grant is
+/// observed: the stream count at which `open_uni` first has to wait for credit
+/// (before any MAX_STREAMS update) is the peer's `initial_max_streams_uni`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerLimits {
+    /// Concurrent uni streams granted (None until the grant has been saturated once)
+    pub max_concurrent_uni_streams: Option<u64>,
+    /// Uni streams opened on the current connection
+    pub streams_opened: u64,
+    /// Times opening a stream had to wait for stream credit
+    pub blocked_opens: u64,
+}
 
 /// The Engine manages QUIC connections to validator TPU ports
 pub struct QuicEngine {
-    endpoint: Endpoint,
-    /// Cache: Target IP -> Active QUIC Connection (lock-free via DashMap)
-    connection_cache: Arc<DashMap<SocketAddr, Connection>>,
+    /// Client endpoints with their local address: IPv4 and (when the host
+    /// supports it) IPv6, or the `QUIC_BIND_ADDR(S)` endpoints
+    endpoints: Vec<(SocketAddr, Endpoint)>,
+    /// Client config for the secondary identity (used per-target after repeated rejections)
+    fallback_config: quinn::ClientConfig,
+    /// Consecutive primary handshake failures before failing over (0 = disabled)
+    failover_threshold: u32,
+    /// Session tickets are cached (connections are named per leader IP)
+    resumption: bool,
+    /// Send in 0-RTT when a ticket for the leader is cached
+    zero_rtt: bool,
+    /// Send transactions as QUIC DATAGRAM frames when the leader supports them
+    datagrams: bool,
+    /// Transactions sent as datagrams
+    datagram_sends: AtomicU64,
+    /// Datagram-mode sends that went over a stream (unsupported or too large)
+    datagram_fallbacks: AtomicU64,
+    /// Transactions dropped because their deadline passed before sending
+    expired_before_send: AtomicU64,
+    /// Cache: (Target IP, pool index) -> Active QUIC Connection (lock-free via DashMap)
+    connection_cache: Arc<DashMap<(SocketAddr, usize), Connection>>,
+    /// Parallel connections kept per leader (streams round-robin across them)
+    connections_per_leader: usize,
+    /// Cached connection budget; least recently used leaders go first (0 = unlimited)
+    max_connections: usize,
+    /// Leaders unused for this long are evicted by `evict_idle` (None = never)
+    connection_ttl: Option<Duration>,
+    /// Target IP -> when a connection to it was last handed out
+    last_used: Arc<DashMap<SocketAddr, Instant>>,
+    /// Silence after which an open connection counts as stale: past the
+    /// keep-alive ack, about halfway to the idle timeout
+    stale_after: Duration,
+    /// Pool slot -> liveness at the last probe
+    liveness: Arc<DashMap<(SocketAddr, usize), Liveness>>,
+    /// Round-robin cursor over pool indexes
+    next_connection: AtomicUsize,
+    /// Current leader's pool, promoted by the `LeaderTracker` (None = look
+    /// connections up in the cache)
+    active: ArcSwapOption<ActiveConnection>,
+    /// Target IP -> consecutive handshake failures with the primary identity
+    handshake_failures: Arc<DashMap<SocketAddr, u32>>,
+    /// Target IP -> identity the validator last accepted
+    accepted_identity: Arc<DashMap<SocketAddr, IdentityKind>>,
+    /// Inbound streams/datagrams that slipped past the zero-credit transport posture
+    inbound_rejections: Arc<AtomicU64>,
+    /// Target IP -> observed stream limits on the current connection
+    peer_limits: Arc<DashMap<SocketAddr, PeerLimits>>,
+    /// Concurrent streams per leader shared out by priority (0 = no budget)
+    stream_budget: usize,
+    /// Deadline for opening, writing and finishing one stream (None = none)
+    send_timeout: Option<Duration>,
+    /// Target IP -> its stream budget
+    stream_budgets: Arc<DashMap<SocketAddr, Arc<StreamBudget>>>,
+    /// Retries for transient `send_transaction` failures
+    retry: RetryPolicy,
+    /// Target IP -> duration of the last successful handshake
+    handshake_times: Arc<DashMap<SocketAddr, Duration>>,
+    /// Connections (by stable id) still sending 0-RTT data the leader has
+    /// not accepted yet
+    early_data: Arc<DashSet<usize>>,
+    /// Per-validator failure scoring (auto-blocklist; None = disabled)
+    score: Option<Arc<ValidatorScore>>,
+    /// Per-leader send / handshake counters (`scramjet stats`)
+    stats: Arc<StatsRegistry>,
+    /// Journal of sent transactions, written by the callers that know their
+    /// signatures (None = off)
+    journal: Option<Arc<Journal>>,
+    /// Set by `shutdown`: no new connections or streams
+    closing: AtomicBool,
+    /// `send_transaction` attempts currently writing
+    in_flight: AtomicUsize,
+    /// Runtime the endpoints are driven on (None = the caller's runtime)
+    send_runtime: Option<Arc<SendRuntime>>,
+}
+
+/// Decrements the in-flight send count when an attempt ends
+struct InFlight<'a>(&'a AtomicUsize);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
 impl QuicEngine {
     pub fn new(identity: &Keypair, config: &Config) -> Result<Self, ScramjetError> {
+        Self::with_fallback_identity(identity, None, config)
+    }
+
+    /// Create an engine with an explicit secondary identity for failover.
+    /// If `fallback` is None, an unstaked ephemeral keypair is generated.
+    pub fn with_fallback_identity(
+        identity: &Keypair,
+        fallback: Option<&Keypair>,
+        config: &Config,
+    ) -> Result<Self, ScramjetError> {
         // Create QUIC client config with Solana identity certificate
         let client_config = create_quic_config(identity, config)?;
+        let fallback_config = match fallback {
+            Some(keypair) => create_quic_config(keypair, config)?,
+            None => create_quic_config(&Keypair::new(), config)?,
+        };
+
+        let send_runtime = SendRuntime::from_config(config)?.map(Arc::new);
+        let runtime = match send_runtime {
+            Some(ref send_runtime) => send_runtime.quinn_runtime(),
+            None => quinn::default_runtime()
+                .ok_or_else(|| ScramjetError::ConfigError("No async runtime for QUIC".into()))?,
+        };
 
-        // Bind to any available port (IPv4)
-        let mut endpoint = Endpoint::client(SocketAddr::from(([0, 0, 0, 0], 0)))?;
-        endpoint.set_default_client_config(client_config);
+        // Bind QUIC_BIND_ADDR(S), or dual-stack: any port on IPv4, plus IPv6
+        // when available (some validators advertise IPv6 TPU addresses)
+        let mut endpoints = Vec::new();
+        let bind_addrs = config.quic_bind_socket_addrs();
+        for addr in &bind_addrs {
+            endpoints.push(bind_client(*addr, &client_config, config, &runtime)?);
+        }
+        if bind_addrs.is_empty() {
+            endpoints.push(bind_client(
+                SocketAddr::from(([0, 0, 0, 0], 0)),
+                &client_config,
+                config,
+                &runtime,
+            )?);
+            match bind_client(
+                SocketAddr::from(([0u16; 8], 0)),
+                &client_config,
+                config,
+                &runtime,
+            ) {
+                Ok(endpoint) => endpoints.push(endpoint),
+                Err(e) => debug!("IPv6 unavailable, sending over IPv4 only: {}", e),
+            }
+        }
 
+        let idle_timeout = config.quic_idle_timeout().max(config.quic_keep_alive());
         Ok(Self {
-            endpoint,
+            endpoints,
+            fallback_config,
+            failover_threshold: config.quic_identity_failover_threshold,
+            resumption: config.quic_session_cache > 0,
+            zero_rtt: config.quic_zero_rtt,
+            datagrams: config.quic_datagrams,
+            datagram_sends: AtomicU64::new(0),
+            datagram_fallbacks: AtomicU64::new(0),
+            expired_before_send: AtomicU64::new(0),
             connection_cache: Arc::new(DashMap::new()),
+            connections_per_leader: config.quic_connections_per_leader.max(1),
+            max_connections: config.quic_max_connections,
+            connection_ttl: config.quic_connection_ttl(),
+            last_used: Arc::new(DashMap::new()),
+            stale_after: config.quic_keep_alive() + (idle_timeout - config.quic_keep_alive()) / 2,
+            liveness: Arc::new(DashMap::new()),
+            next_connection: AtomicUsize::new(0),
+            active: ArcSwapOption::empty(),
+            handshake_failures: Arc::new(DashMap::new()),
+            accepted_identity: Arc::new(DashMap::new()),
+            inbound_rejections: Arc::new(AtomicU64::new(0)),
+            peer_limits: Arc::new(DashMap::new()),
+            stream_budget: config.quic_stream_budget,
+            send_timeout: config.quic_send_timeout(),
+            stream_budgets: Arc::new(DashMap::new()),
+            retry: RetryPolicy::from_config(config)?,
+            handshake_times: Arc::new(DashMap::new()),
+            early_data: Arc::new(DashSet::new()),
+            score: None,
+            stats: Arc::new(StatsRegistry::new()),
+            journal: None,
+            closing: AtomicBool::new(false),
+            in_flight: AtomicUsize::new(0),
+            send_runtime,
         })
     }
 
-    /// Standard single-shot send (Thread-safe via DashMap)
+    /// Override the retry policy (default: `SEND_RETRY_*` config)
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Feed every send attempt into a failure scorer (auto-blocklist)
+    pub fn with_validator_score(mut self, score: Arc<ValidatorScore>) -> Self {
+        self.score = Some(score);
+        self
+    }
+
+    /// Journal sends to `journal` (`scramjet history`)
+    pub fn with_journal(mut self, journal: Arc<Journal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Send journal (`JOURNAL_FILE`), if enabled
+    pub fn journal(&self) -> Option<&Arc<Journal>> {
+        self.journal.as_ref()
+    }
+
+    /// Dedicated send runtime (SEND_RUNTIME_THREADS), if configured
+    pub fn send_runtime(&self) -> Option<&Arc<SendRuntime>> {
+        self.send_runtime.as_ref()
+    }
+
+    /// Per-leader delivery statistics for every send and handshake
+    pub fn stats(&self) -> &Arc<StatsRegistry> {
+        &self.stats
+    }
+
+    /// Report a send attempt made outside `send_transaction` (direct
+    /// streams) to the statistics and the failure scorer, if any
+    pub fn record_send_result(&self, target: SocketAddr, error: Option<&ScramjetError>) {
+        self.stats.record_send(target, error);
+        if let Some(ref score) = self.score {
+            score.record(target, error);
+        }
+    }
+
+    /// Standard single-shot send (Thread-safe via DashMap).
+    /// Transient failures are retried per the engine's `RetryPolicy`.
+    /// `tx_bytes` is shared, not copied, by retries and the stream write.
     pub async fn send_transaction(
         &self,
         target: SocketAddr,
-        tx_bytes: Vec<u8>,
+        tx_bytes: Bytes,
+    ) -> Result<(), ScramjetError> {
+        self.send_transaction_with(target, tx_bytes, Priority::Normal)
+            .await
+    }
+
+    /// [`send_transaction`](Self::send_transaction) in a priority class (see
+    /// [`stream_permit`](Self::stream_permit))
+    pub async fn send_transaction_with(
+        &self,
+        target: SocketAddr,
+        tx_bytes: Bytes,
+        priority: Priority,
+    ) -> Result<(), ScramjetError> {
+        let mut attempt = 1;
+        loop {
+            let result = self.send_once(target, &tx_bytes, priority).await;
+            self.record_send_result(target, result.as_ref().err());
+            let err = match result {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+            let Some(backoff) = self.retry.backoff(attempt, &err) else {
+                return Err(err);
+            };
+            debug!(
+                "Send to {} failed (attempt {}/{}): {}. Retrying in {:?}",
+                target, attempt, self.retry.max_attempts, err, backoff
+            );
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    /// Send many transactions to `target` over one pool lookup, rotating
+    /// streams across its connections. Buffers are handed to quinn as-is (no
+    /// copies) and packed streams use vectored writes. Fails as a whole only
+    /// when no connection is available; otherwise returns one result per
+    /// transaction, in order. Nothing is retried. Every stream takes a
+    /// `priority` permit from the leader's stream budget.
+    pub async fn send_batch(
+        &self,
+        target: SocketAddr,
+        txs: Vec<Bytes>,
+        mode: BatchMode,
+        priority: Priority,
+    ) -> Result<Vec<Result<(), ScramjetError>>, ScramjetError> {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let _in_flight = InFlight(&self.in_flight);
+        let pool = self.get_connection_pool(target).await?;
+
+        // Datagram mode: every transaction that fits goes as a datagram
+        if self.datagrams && mode == BatchMode::StreamPerTx {
+            let mut results = Vec::with_capacity(txs.len());
+            for (i, tx) in txs.iter().enumerate() {
+                let connection = &pool[i % pool.len()];
+                let result = self
+                    .write_transaction(target, connection, tx, priority)
+                    .await;
+                self.record_send_result(target, result.as_ref().err());
+                results.push(result);
+            }
+            return Ok(results);
+        }
+
+        let streams = match mode {
+            BatchMode::StreamPerTx => vec![1; txs.len()],
+            BatchMode::Packed => pack_streams(txs.iter().map(Bytes::len), PACKET_DATA_SIZE),
+        };
+        let mut results = Vec::with_capacity(txs.len());
+        let mut txs = txs.into_iter();
+        let mut chunks = Vec::new();
+        for (i, count) in streams.into_iter().enumerate() {
+            chunks.clear();
+            chunks.extend(txs.by_ref().take(count));
+            let connection = &pool[i % pool.len()];
+            let result = self
+                .write_stream(target, connection, &mut chunks, priority)
+                .await;
+            for _ in 0..count {
+                self.record_send_result(target, result.as_ref().err());
+            }
+            match result {
+                Ok(()) => results.extend((0..count).map(|_| Ok(()))),
+                Err(e) => {
+                    // Every transaction packed into a failed stream failed with it
+                    let failure = e.send_failure().cloned();
+                    let message = e.to_string();
+                    results.push(Err(e));
+                    results.extend((1..count).map(|_| {
+                        Err(match failure {
+                            Some(ref failure) => failure.clone().into(),
+                            None => ScramjetError::StreamError(message.clone()),
+                        })
+                    }));
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// One send attempt: connection (cached or new) + one uni stream
+    async fn send_once(
+        &self,
+        target: SocketAddr,
+        tx_bytes: &Bytes,
+        priority: Priority,
     ) -> Result<(), ScramjetError> {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        let _in_flight = InFlight(&self.in_flight);
+
         // Get or create connection from cache
         let connection = self.get_connection(target).await?;
 
-        // Open unidirectional stream for this transaction
-        let mut send_stream = connection
-            .open_uni()
+        self.write_transaction(target, &connection, tx_bytes, priority)
             .await
-            .map_err(|e| ScramjetError::StreamError(format!("Failed to open stream: {}", e)))?;
+    }
 
-        // Write transaction bytes to stream
-        send_stream.write_all(&tx_bytes).await?;
+    /// Write one transaction on `connection`: a uni stream, or a DATAGRAM
+    /// frame in datagram mode (`QUIC_DATAGRAMS`) when the leader supports
+    /// datagrams and the transaction fits in one. Datagrams are unreliable:
+    /// a lost one is not retransmitted. A stream waits for a `priority`
+    /// permit from the leader's stream budget.
+    pub async fn write_transaction(
+        &self,
+        target: SocketAddr,
+        connection: &Connection,
+        tx_bytes: &Bytes,
+        priority: Priority,
+    ) -> Result<(), ScramjetError> {
+        if self.datagrams {
+            if self.is_closing() {
+                return Err(ScramjetError::ShuttingDown);
+            }
+            if self.send_datagram(target, connection, tx_bytes) {
+                return Ok(());
+            }
+        }
 
+        // One unidirectional stream for this transaction, sharing the buffer
+        // with quinn (write_all would copy it)
+        self.write_stream(target, connection, &mut [tx_bytes.clone()], priority)
+            .await
+    }
+
+    /// Take a `priority` permit from `target`'s stream budget, open a uni
+    /// stream on `connection`, write `chunks` to it and finish it, all within
+    /// `QUIC_SEND_TIMEOUT_MS` when set: a full budget, or a leader that stops
+    /// granting streams or reading data, fails the send with
+    /// [`SendFailureKind::StreamTimeout`] instead of stalling it. A stream
+    /// that times out after opening is reset, so the partial write is
+    /// discarded. A stream sent as 0-RTT data waits for the leader to accept
+    /// it, and is sent again on the completed connection if it does not.
+    pub async fn write_stream(
+        &self,
+        target: SocketAddr,
+        connection: &Connection,
+        chunks: &mut [Bytes],
+        priority: Priority,
+    ) -> Result<(), ScramjetError> {
+        let deadline = self
+            .send_timeout
+            .map(|timeout| (tokio::time::Instant::now() + timeout, timeout));
+        let _permit = within(deadline, target, async {
+            Ok(self.stream_permit(target, priority).await)
+        })
+        .await?;
+        if !self.early_data.contains(&connection.stable_id()) {
+            self.write_once(target, connection, chunks, deadline)
+                .await?;
+            return Ok(());
+        }
+        let mut resend = chunks.to_vec();
+        let stream = self
+            .write_once(target, connection, chunks, deadline)
+            .await?;
+        // Acknowledged, or discarded with the rest of the early data
+        let verdict = within(deadline, target, async { Ok(stream.stopped().await) }).await?;
+        match verdict {
+            Err(quinn::StoppedError::ZeroRttRejected) => {
+                debug!("0-RTT stream to {} rejected, resending", target);
+                self.write_once(target, connection, &mut resend, deadline)
+                    .await?;
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// One attempt of [`write_stream`](Self::write_stream): the finished stream
+    async fn write_once(
+        &self,
+        target: SocketAddr,
+        connection: &Connection,
+        chunks: &mut [Bytes],
+        deadline: Option<(tokio::time::Instant, Duration)>,
+    ) -> Result<SendStream, ScramjetError> {
+        let mut stream = within(deadline, target, self.open_stream(target, connection)).await?;
+        let written = within(deadline, target, async {
+            stream
+                .write_all_chunks(chunks)
+                .await
+                .map_err(|e| SendFailure::write(target, &e).into())
+        })
+        .await;
+        if let Err(ref e) = written {
+            if e.send_failure().map(|f| f.kind) == Some(SendFailureKind::StreamTimeout) {
+                let _ = stream.reset(SEND_TIMEOUT_RESET_CODE.into());
+            }
+        }
+        written?;
         // Close stream to signal completion (no longer async in quinn 0.11)
-        send_stream.finish()?;
+        finish_stream(target, &mut stream)?;
+        Ok(stream)
+    }
 
-        Ok(())
+    /// Try `tx_bytes` as one datagram; false means send it over a stream
+    fn send_datagram(&self, target: SocketAddr, connection: &Connection, tx_bytes: &Bytes) -> bool {
+        let sent = match connection.max_datagram_size() {
+            Some(max) if tx_bytes.len() <= max => connection
+                .send_datagram(tx_bytes.clone())
+                .map_err(|e| debug!("Datagram to {} failed, using a stream: {}", target, e))
+                .is_ok(),
+            _ => false,
+        };
+        let counter = if sent {
+            &self.datagram_sends
+        } else {
+            &self.datagram_fallbacks
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        sent
+    }
+
+    /// Datagram-mode sends: (sent as datagrams, fell back to streams)
+    pub fn datagram_counts(&self) -> (u64, u64) {
+        (
+            self.datagram_sends.load(Ordering::Relaxed),
+            self.datagram_fallbacks.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Count a transaction dropped because its deadline passed (see
+    /// [`crate::deadline`])
+    pub fn record_expired(&self) {
+        self.expired_before_send.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Transactions dropped unsent because their deadline passed
+    pub fn expired_before_send(&self) -> u64 {
+        self.expired_before_send.load(Ordering::Relaxed)
+    }
+
+    pub fn datagrams_enabled(&self) -> bool {
+        self.datagrams
     }
 
     /// MACHINE GUN OPTIMIZATION:
     /// Returns direct handle for high-frequency sending.
     /// Caller can open multiple streams on same connection (multiplexing).
+    /// With a connection pool, successive calls round-robin across it.
     pub async fn get_connection_handle(
         &self,
         target: SocketAddr,
@@ -62,72 +584,799 @@ impl QuicEngine {
         self.get_connection(target).await
     }
 
-    /// Internal: Manage connection cache with lock-free reads
+    /// Every pooled connection to `target` (`QUIC_CONNECTIONS_PER_LEADER`),
+    /// handshaking any that are missing. Callers rotate streams across them,
+    /// since one connection's stream grant caps throughput.
+    pub async fn get_connection_pool(
+        &self,
+        target: SocketAddr,
+    ) -> Result<Vec<Connection>, ScramjetError> {
+        let mut pool = Vec::with_capacity(self.connections_per_leader);
+        for index in 0..self.connections_per_leader {
+            pool.push(self.get_pooled_connection(target, index).await?);
+        }
+        Ok(pool)
+    }
+
+    /// The current leader's connection pool, when a
+    /// [`LeaderTracker`](crate::leader_tracker::LeaderTracker) has promoted one
+    pub fn active_connection(&self) -> Option<Arc<ActiveConnection>> {
+        self.active.load_full()
+    }
+
+    /// Promote `active` (None demotes the current one)
+    pub fn set_active_connection(&self, active: Option<Arc<ActiveConnection>>) {
+        self.active.store(active);
+    }
+
+    /// Open a uni stream on a connection to `target`, recording when the
+    /// validator's stream grant is exhausted (see `peer_limits`)
+    pub async fn open_stream(
+        &self,
+        target: SocketAddr,
+        connection: &Connection,
+    ) -> Result<SendStream, ScramjetError> {
+        if self.is_closing() {
+            return Err(ScramjetError::ShuttingDown);
+        }
+        let mut open = std::pin::pin!(connection.open_uni());
+        let stream = match open.as_mut().now_or_never() {
+            Some(result) => result,
+            None => {
+                self.record_blocked_open(target, connection);
+                open.await
+            }
+        }
+        .map_err(|e| {
+            let message = format!("Failed to open stream: {}", e);
+            SendFailure::new(SendFailureKind::StreamRefused, target, message)
+        })?;
+
+        let mut limits = self.peer_limits.entry(target).or_default();
+        limits.streams_opened = limits.streams_opened.max(stream.id().index() + 1);
+        Ok(stream)
+    }
+
+    /// Wait until `target`'s stream budget (`QUIC_STREAM_BUDGET`) has room
+    /// for a `priority` stream; None when there is no budget. Hold the permit
+    /// until the stream is written.
+    pub async fn stream_permit(
+        &self,
+        target: SocketAddr,
+        priority: Priority,
+    ) -> Option<StreamPermit> {
+        if self.stream_budget == 0 {
+            return None;
+        }
+        let budget = self
+            .stream_budgets
+            .entry(target)
+            .or_insert_with(|| Arc::new(StreamBudget::new(self.stream_budget)))
+            .clone();
+        Some(budget.acquire(priority).await)
+    }
+
+    /// Stream limits observed for the current connection to `addr`
+    pub fn peer_limits(&self, addr: &SocketAddr) -> Option<PeerLimits> {
+        self.peer_limits.get(addr).map(|limits| *limits)
+    }
+
+    /// Number of cached connections that are still open
+    pub fn warm_connections(&self) -> usize {
+        self.connection_cache
+            .iter()
+            .filter(|entry| entry.value().close_reason().is_none())
+            .count()
+    }
+
+    /// How long the last successful handshake with `addr` took
+    pub fn handshake_time(&self, addr: &SocketAddr) -> Option<Duration> {
+        self.handshake_times.get(addr).map(|elapsed| *elapsed)
+    }
+
+    /// Smoothed round-trip time of an open pooled connection to `target`
+    pub fn pooled_rtt(&self, target: &SocketAddr) -> Option<Duration> {
+        (0..self.connections_per_leader).find_map(|index| {
+            let connection = self.connection_cache.get(&(*target, index))?;
+            connection
+                .close_reason()
+                .is_none()
+                .then(|| connection.rtt())
+        })
+    }
+
+    /// Round-trip time to `target`: from an open pooled connection, else a
+    /// throwaway handshake under the secondary identity (a probe never takes
+    /// one of the validator's connection slots for the staked identity).
+    /// Nothing is cached and no handshake statistics are recorded.
+    pub async fn measure_rtt(&self, target: SocketAddr) -> Result<Duration, ScramjetError> {
+        if let Some(rtt) = self.pooled_rtt(&target) {
+            return Ok(rtt);
+        }
+        if self.is_closing() {
+            return Err(ScramjetError::ShuttingDown);
+        }
+        let (endpoint, remote) = self.endpoint_for(target, 0)?;
+        let connection = endpoint
+            .connect_with(
+                self.fallback_config.clone(),
+                remote,
+                &self.server_name(&target),
+            )
+            .map_err(|e| connect_error(target, e))?
+            .await
+            .map_err(|e| SendFailure::handshake(target, &e))?;
+        let rtt = connection.rtt();
+        connection.close(SHUTDOWN_CLOSE_CODE.into(), b"rtt probe");
+        Ok(rtt)
+    }
+
+    /// Identity the target last accepted (None if never connected)
+    pub fn accepted_identity(&self, addr: &SocketAddr) -> Option<IdentityKind> {
+        self.accepted_identity.get(addr).map(|kind| *kind)
+    }
+
+    /// Number of inbound streams/datagrams refused from validators (should stay 0)
+    pub fn inbound_rejections(&self) -> u64 {
+        self.inbound_rejections.load(Ordering::Relaxed)
+    }
+
+    /// Hand the QUIC path statistics of every open cached connection to the
+    /// stats registry
+    pub fn sample_paths(&self) {
+        let connections = self
+            .connection_cache
+            .iter()
+            .filter(|entry| entry.value().close_reason().is_none())
+            .map(|entry| {
+                let connection = entry.value();
+                (entry.key().0, connection.stable_id(), connection.stats())
+            })
+            .collect();
+        self.stats.record_paths(connections);
+    }
+
+    /// Check every cached connection: closed ones are dropped, and open ones
+    /// that received nothing (not even keep-alive acks) for `stale_after`
+    /// are reported stale. quinn has no explicit ping, but keep-alives are
+    /// ack-eliciting, so a live peer always sends something back.
+    pub fn probe_connections(&self) -> ProbeReport {
+        let now = Instant::now();
+        let mut report = ProbeReport::default();
+        let mut dead = Vec::new();
+        for entry in self.connection_cache.iter() {
+            let (key, connection) = (*entry.key(), entry.value());
+            if connection.close_reason().is_some() {
+                dead.push(key);
+                continue;
+            }
+            let rx_datagrams = connection.stats().udp_rx.datagrams;
+            let mut liveness = self.liveness.entry(key).or_insert(Liveness {
+                rx_datagrams,
+                since: now,
+            });
+            if liveness.rx_datagrams != rx_datagrams {
+                *liveness = Liveness {
+                    rx_datagrams,
+                    since: now,
+                };
+            }
+            if now.duration_since(liveness.since) < self.stale_after {
+                report.healthy += 1;
+            } else if !report.stale.contains(&key.0) {
+                report.stale.push(key.0);
+            }
+        }
+        for key in dead {
+            // A concurrent send may already have reconnected this slot
+            if self
+                .connection_cache
+                .remove_if(&key, |_, connection| connection.close_reason().is_some())
+                .is_some()
+            {
+                report.dead += 1;
+            }
+        }
+        self.liveness
+            .retain(|key, _| self.connection_cache.contains_key(key));
+        report
+    }
+
+    /// Re-handshake `target`'s pool slots that are missing, closed or stale
+    /// (as of the last `probe_connections`). A stale connection stays in use
+    /// until its replacement is up, so sends never wait on these handshakes.
+    /// Returns the number of new connections.
+    pub async fn refresh_pool(&self, target: SocketAddr) -> Result<usize, ScramjetError> {
+        if self.is_closing() {
+            return Err(ScramjetError::ShuttingDown);
+        }
+        let mut connected = 0;
+        for index in 0..self.connections_per_leader {
+            let key = (target, index);
+            let open = self
+                .connection_cache
+                .get(&key)
+                .is_some_and(|connection| connection.close_reason().is_none());
+            let stale = self.is_stale(&key);
+            if open && !stale {
+                continue;
+            }
+            if stale {
+                debug!(
+                    "Health: Replacing stale connection to {} (pool slot {})",
+                    target, index
+                );
+            }
+            self.connect(target, index).await?;
+            connected += 1;
+        }
+        Ok(connected)
+    }
+
+    /// Leaders whose pools fit in `QUIC_MAX_CONNECTIONS` (None = unlimited)
+    pub fn leader_capacity(&self) -> Option<usize> {
+        (self.max_connections > 0).then(|| self.max_connections / self.connections_per_leader)
+    }
+
+    /// Close connections to leaders unused for `QUIC_CONNECTION_TTL_SECS`.
+    /// Returns the number of leaders evicted.
+    pub fn evict_idle(&self) -> usize {
+        let Some(ttl) = self.connection_ttl else {
+            return 0;
+        };
+        let idle: Vec<SocketAddr> = self
+            .last_used
+            .iter()
+            .filter(|entry| entry.value().elapsed() >= ttl)
+            .map(|entry| *entry.key())
+            .collect();
+        for addr in &idle {
+            self.evict_leader(*addr, "idle");
+        }
+        idle.len()
+    }
+
+    /// Evict least recently used leaders (never `keep`) until the cache fits
+    /// `QUIC_MAX_CONNECTIONS`
+    fn enforce_budget(&self, keep: SocketAddr) {
+        if self.max_connections == 0 {
+            return;
+        }
+        while self.connection_cache.len() > self.max_connections {
+            let lru = self
+                .last_used
+                .iter()
+                .filter(|entry| *entry.key() != keep)
+                .min_by_key(|entry| *entry.value())
+                .map(|entry| *entry.key());
+            match lru {
+                Some(addr) => self.evict_leader(addr, "over budget"),
+                None => break,
+            }
+        }
+    }
+
+    /// Close and drop every pooled connection to `addr`
+    fn evict_leader(&self, addr: SocketAddr, reason: &str) {
+        self.last_used.remove(&addr);
+        self.peer_limits.remove(&addr);
+        self.stream_budgets.remove(&addr);
+        for index in 0..self.connections_per_leader {
+            if let Some((_, connection)) = self.connection_cache.remove(&(addr, index)) {
+                connection.close(0u32.into(), b"evicted");
+            }
+        }
+        debug!("Evicted connections to {} ({})", addr, reason);
+    }
+
+    /// Close and drop every cached connection (e.g. after a cluster restart).
+    /// Returns the number of connections quarantined.
+    pub fn quarantine_connections(&self) -> usize {
+        let count = self.connection_cache.len();
+        for entry in self.connection_cache.iter() {
+            entry.value().close(0u32.into(), b"quarantined");
+        }
+        self.connection_cache.clear();
+        self.peer_limits.clear();
+        self.last_used.clear();
+        if count > 0 {
+            info!("Quarantined {} warm connections", count);
+        }
+        count
+    }
+
+    /// True once `shutdown` has started
+    pub fn is_closing(&self) -> bool {
+        self.closing.load(Ordering::Acquire)
+    }
+
+    /// Graceful shutdown: refuse new sends, wait for in-flight
+    /// `send_transaction` attempts, give finished streams a couple of RTTs
+    /// to be delivered, then close every connection with
+    /// `SHUTDOWN_CLOSE_CODE`. Everything is bounded by `drain_timeout`.
+    /// Returns the number of connections closed.
+    pub async fn shutdown(&self, drain_timeout: Duration) -> usize {
+        self.closing.store(true, Ordering::Release);
+        self.active.store(None);
+        let deadline = Instant::now() + drain_timeout;
+
+        while self.in_flight.load(Ordering::Acquire) > 0 && Instant::now() < deadline {
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+        let abandoned = self.in_flight.load(Ordering::Acquire);
+        if abandoned > 0 {
+            warn!(
+                "Shutdown: {} send(s) still in flight after {:?}",
+                abandoned, drain_timeout
+            );
+        }
+
+        // Stream data written before finish() may still be unacknowledged
+        let flush = self
+            .connection_cache
+            .iter()
+            .filter(|entry| entry.value().close_reason().is_none())
+            .map(|entry| entry.value().rtt() * 2)
+            .max()
+            .unwrap_or_default()
+            .min(deadline.saturating_duration_since(Instant::now()));
+        tokio::time::sleep(flush).await;
+
+        let mut closed = 0;
+        for entry in self.connection_cache.iter() {
+            if entry.value().close_reason().is_none() {
+                entry.value().close(SHUTDOWN_CLOSE_CODE.into(), b"shutdown");
+                closed += 1;
+            }
+        }
+        self.connection_cache.clear();
+        self.peer_limits.clear();
+        for (_, endpoint) in &self.endpoints {
+            endpoint.close(SHUTDOWN_CLOSE_CODE.into(), b"shutdown");
+        }
+
+        // Let the CONNECTION_CLOSE frames go out
+        for (local, endpoint) in &self.endpoints {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if tokio::time::timeout(remaining, endpoint.wait_idle())
+                .await
+                .is_err()
+            {
+                debug!(
+                    "Shutdown: Endpoint {} not idle after {:?}",
+                    local, drain_timeout
+                );
+            }
+        }
+        // Sends the drain above finished may still be queued for the journal
+        if let Some(journal) = self.journal.clone() {
+            let _ = tokio::task::spawn_blocking(move || journal.flush()).await;
+        }
+        info!("Shutdown: Closed {} connection(s)", closed);
+        closed
+    }
+
+    /// Local addresses of the client endpoints
+    pub fn local_addrs(&self) -> Vec<SocketAddr> {
+        self.endpoints.iter().map(|(local, _)| *local).collect()
+    }
+
+    /// Internal: Endpoint of the same address family as `addr` (v4-mapped
+    /// IPv6 targets go out over IPv4). An IPv4 target can also use an IPv6
+    /// endpoint, which quinn reaches through a v4-mapped address.
+    fn endpoint_for(
+        &self,
+        addr: SocketAddr,
+        index: usize,
+    ) -> Result<(&Endpoint, SocketAddr), ScramjetError> {
+        let remote = SocketAddr::new(addr.ip().to_canonical(), addr.port());
+        let family = |ipv6: bool| -> Vec<&Endpoint> {
+            self.endpoints
+                .iter()
+                .filter(|(local, _)| local.is_ipv6() == ipv6)
+                .map(|(_, endpoint)| endpoint)
+                .collect()
+        };
+        let mut candidates = family(remote.is_ipv6());
+        if candidates.is_empty() && remote.is_ipv4() {
+            candidates = family(true);
+        }
+        // Several local addresses (QUIC_BIND_ADDRS): spread leaders and their
+        // pool slots across them, the same one for a slot on every reconnect
+        let spread = |count: usize| {
+            let mut hasher = DefaultHasher::new();
+            remote.hash(&mut hasher);
+            (hasher.finish() as usize).wrapping_add(index) % count
+        };
+        candidates
+            .get(spread(candidates.len().max(1)))
+            .map(|endpoint| (*endpoint, remote))
+            .ok_or_else(|| {
+                let family = if remote.is_ipv6() { "IPv6" } else { "IPv4" };
+                ScramjetError::ConnectionError(format!("No {} endpoint for {}", family, addr))
+            })
+    }
+
+    /// Internal: Next pooled connection to `addr` (round-robin)
     async fn get_connection(&self, addr: SocketAddr) -> Result<Connection, ScramjetError> {
+        // The current leader's pool was handed over at rotation: no lookup
+        if let Some(active) = self.active.load().as_deref() {
+            if active.target == addr {
+                if let Some(connection) = active.connection() {
+                    return Ok(connection);
+                }
+            }
+        }
+        let index = if self.connections_per_leader > 1 {
+            self.next_connection.fetch_add(1, Ordering::Relaxed) % self.connections_per_leader
+        } else {
+            0
+        };
+        self.get_pooled_connection(addr, index).await
+    }
+
+    /// Internal: Manage connection cache with lock-free reads
+    async fn get_pooled_connection(
+        &self,
+        addr: SocketAddr,
+        index: usize,
+    ) -> Result<Connection, ScramjetError> {
+        if self.is_closing() {
+            return Err(ScramjetError::ShuttingDown);
+        }
+
         // Fast path: check cache without blocking
-        if let Some(conn) = self.connection_cache.get(&addr) {
+        if let Some(conn) = self.connection_cache.get(&(addr, index)) {
             if conn.close_reason().is_none() {
+                self.last_used.insert(addr, Instant::now());
                 return Ok(conn.clone());
             }
         }
 
         // Remove stale connection if exists
-        self.connection_cache.remove(&addr);
+        self.connection_cache.remove(&(addr, index));
+        self.connect(addr, index).await
+    }
 
+    /// Internal: Whether the pool slot heard nothing for `stale_after` (as of
+    /// the last `probe_connections`)
+    fn is_stale(&self, key: &(SocketAddr, usize)) -> bool {
+        self.liveness
+            .get(key)
+            .is_some_and(|liveness| liveness.since.elapsed() >= self.stale_after)
+    }
+
+    /// Internal: Handshake a new connection for pool slot `index` of `addr`
+    /// and cache it, replacing whatever was there. A replaced stale
+    /// connection is closed; any other closes once in-flight sends drop
+    /// their handles (it may be a concurrent handshake still in use).
+    async fn connect(&self, addr: SocketAddr, index: usize) -> Result<Connection, ScramjetError> {
         // Handshake OUTSIDE of any lock (avoids blocking other lookups)
-        info!("Handshake: Connecting to leader at {}...", addr);
-        let connecting = self
-            .endpoint
-            .connect(addr, "solana")
-            .map_err(|e| ScramjetError::ConnectionError(format!("Connect failed: {}", e)))?;
-        let connection = connecting.await?;
+        let identity = self.select_identity(&addr);
+        let started = Instant::now();
+        info!(
+            "Handshake: Connecting to leader at {} ({:?} identity, pool slot {})...",
+            addr, identity, index
+        );
+        let (endpoint, remote) = self.endpoint_for(addr, index)?;
+        let server_name = self.server_name(&addr);
+        let connecting = match identity {
+            IdentityKind::Primary => endpoint.connect(remote, &server_name),
+            IdentityKind::Fallback => {
+                endpoint.connect_with(self.fallback_config.clone(), remote, &server_name)
+            }
+        }
+        .map_err(|e| connect_error(addr, e))?;
+
+        // 0-RTT: usable at once when a ticket for this leader is cached,
+        // otherwise wait for the full handshake
+        let attempt = if self.zero_rtt {
+            connecting.into_0rtt()
+        } else {
+            Err(connecting)
+        };
+        let (result, resumed) = match attempt {
+            Ok((connection, accepted)) => {
+                // Streams written until the leader's verdict wait for it, and
+                // are resent if it rejects the early data
+                let id = connection.stable_id();
+                self.early_data.insert(id);
+                let early_data = self.early_data.clone();
+                let handshake_times = self.handshake_times.clone();
+                let stats = self.stats.clone();
+                tokio::spawn(async move {
+                    if !accepted.await {
+                        debug!("Handshake: {} rejected 0-RTT data (sends resent)", addr);
+                    }
+                    early_data.remove(&id);
+                    // The full handshake has completed either way
+                    let elapsed = started.elapsed();
+                    handshake_times.insert(addr, elapsed);
+                    stats.record_handshake(addr, elapsed);
+                });
+                (Ok(connection), true)
+            }
+            Err(connecting) => (connecting.await, false),
+        };
+        let connection = match result {
+            Ok(connection) if resumed => {
+                debug!("Handshake: 0-RTT resumption with {}", addr);
+                self.record_handshake(addr, identity, None);
+                connection
+            }
+            Ok(connection) => {
+                let elapsed = started.elapsed();
+                self.record_handshake(addr, identity, None);
+                self.handshake_times.insert(addr, elapsed);
+                self.stats.record_handshake(addr, elapsed);
+                connection
+            }
+            Err(e) => {
+                let failure = SendFailure::handshake(addr, &e);
+                self.record_handshake(addr, identity, Some(failure.kind));
+                return Err(failure.into());
+            }
+        };
+        self.spawn_inbound_guard(connection.clone());
+        // Limits are per connection: a new handshake may be granted a new tier
+        self.peer_limits.insert(addr, PeerLimits::default());
 
         // Insert with minimal contention
-        self.connection_cache.insert(addr, connection.clone());
-        debug!("Connection cached for {}", addr);
+        let stale = self.is_stale(&(addr, index));
+        self.liveness.insert(
+            (addr, index),
+            Liveness {
+                rx_datagrams: connection.stats().udp_rx.datagrams,
+                since: Instant::now(),
+            },
+        );
+        let replaced = self
+            .connection_cache
+            .insert((addr, index), connection.clone());
+        if let Some(replaced) = replaced.filter(|_| stale) {
+            replaced.close(0u32.into(), b"stale");
+        }
+        self.last_used.insert(addr, Instant::now());
+        debug!("Connection cached for {} (pool slot {})", addr, index);
+        self.enforce_budget(addr);
 
         Ok(connection)
     }
+
+    /// TLS server name for `addr`. Session tickets are cached per name, so
+    /// with resumption on each leader gets its own (an IP name also means no
+    /// SNI is sent; validators don't check it)
+    fn server_name(&self, addr: &SocketAddr) -> String {
+        if self.resumption {
+            addr.ip().to_string()
+        } else {
+            "solana".to_string()
+        }
+    }
+
+    /// Pick the identity for a target: fall back once the primary has been
+    /// rejected `failover_threshold` times in a row, or if the target already
+    /// accepted the fallback.
+    fn select_identity(&self, addr: &SocketAddr) -> IdentityKind {
+        if self.accepted_identity(addr) == Some(IdentityKind::Fallback) {
+            return IdentityKind::Fallback;
+        }
+        let failures = self.handshake_failures.get(addr).map_or(0, |f| *f);
+        if self.failover_threshold > 0 && failures >= self.failover_threshold {
+            IdentityKind::Fallback
+        } else {
+            IdentityKind::Primary
+        }
+    }
+
+    /// Track handshake outcomes per target for identity failover (`failure`:
+    /// None on success). Only a refused certificate counts against an
+    /// identity; timeouts and unreachable leaders say nothing about it.
+    fn record_handshake(
+        &self,
+        addr: SocketAddr,
+        identity: IdentityKind,
+        failure: Option<SendFailureKind>,
+    ) {
+        match (identity, failure) {
+            (_, None) => {
+                self.handshake_failures.remove(&addr);
+                self.accepted_identity.insert(addr, identity);
+            }
+            (_, Some(kind)) if kind != SendFailureKind::CertRejected => {}
+            (IdentityKind::Primary, Some(_)) => {
+                let mut failures = self.handshake_failures.entry(addr).or_insert(0);
+                *failures += 1;
+                if self.failover_threshold > 0 && *failures == self.failover_threshold {
+                    warn!(
+                        "Primary identity rejected {} times by {}. Failing over to secondary identity.",
+                        *failures, addr
+                    );
+                }
+            }
+            (IdentityKind::Fallback, Some(_)) => {
+                // Secondary rejected too: start over with the primary identity
+                self.handshake_failures.remove(&addr);
+                self.accepted_identity.remove(&addr);
+            }
+        }
+    }
+
+    /// `open_uni` had to wait: the validator's stream grant is saturated
+    fn record_blocked_open(&self, target: SocketAddr, connection: &Connection) {
+        let credit_updates = connection.stats().frame_rx.max_streams_uni;
+        let mut limits = self.peer_limits.entry(target).or_default();
+        limits.blocked_opens += 1;
+        // Before any MAX_STREAMS update, streams opened == initial grant
+        if limits.max_concurrent_uni_streams.is_none() && credit_updates == 0 {
+            limits.max_concurrent_uni_streams = Some(limits.streams_opened);
+            info!(
+                "QoS: {} grants {} concurrent uni streams",
+                target, limits.streams_opened
+            );
+        } else {
+            debug!(
+                "QoS: Waiting for stream credit from {} ({} streams opened)",
+                target, limits.streams_opened
+            );
+        }
+    }
+
+    /// Refuse anything a validator pushes toward us. The transport grants zero
+    /// stream credit, so this only fires on peers that ignore flow control.
+    fn spawn_inbound_guard(&self, connection: Connection) {
+        let rejections = self.inbound_rejections.clone();
+        tokio::spawn(async move {
+            let peer = connection.remote_address();
+            loop {
+                tokio::select! {
+                    uni = connection.accept_uni() => match uni {
+                        Ok(mut recv) => {
+                            let _ = recv.stop(0u32.into());
+                            rejections.fetch_add(1, Ordering::Relaxed);
+                            warn!("Refused inbound uni stream from {}", peer);
+                        }
+                        Err(_) => break,
+                    },
+                    bi = connection.accept_bi() => match bi {
+                        Ok((mut send, mut recv)) => {
+                            let _ = send.reset(0u32.into());
+                            let _ = recv.stop(0u32.into());
+                            rejections.fetch_add(1, Ordering::Relaxed);
+                            warn!("Refused inbound bi stream from {}", peer);
+                        }
+                        Err(_) => break,
+                    },
+                    datagram = connection.read_datagram() => match datagram {
+                        Ok(_) => {
+                            rejections.fetch_add(1, Ordering::Relaxed);
+                            warn!("Dropped inbound datagram from {}", peer);
+                        }
+                        Err(_) => break,
+                    },
+                }
+            }
+        });
+    }
+}
+
+/// Finish a written stream (fails only if it was already finished or reset)
+pub fn finish_stream(target: SocketAddr, stream: &mut SendStream) -> Result<(), ScramjetError> {
+    stream
+        .finish()
+        .map_err(|e| SendFailure::new(SendFailureKind::Protocol, target, e.to_string()).into())
+}
+
+/// Run one step of a stream send against `deadline` (instant, and the
+/// configured timeout for the error message)
+async fn within<T>(
+    deadline: Option<(tokio::time::Instant, Duration)>,
+    target: SocketAddr,
+    step: impl std::future::Future<Output = Result<T, ScramjetError>>,
+) -> Result<T, ScramjetError> {
+    let Some((at, timeout)) = deadline else {
+        return step.await;
+    };
+    match tokio::time::timeout_at(at, step).await {
+        Ok(result) => result,
+        Err(_) => {
+            let message = format!("Stream not written within {:?}", timeout);
+            Err(SendFailure::new(SendFailureKind::StreamTimeout, target, message).into())
+        }
+    }
+}
+
+/// `Endpoint::connect` refused to start a handshake (bad address, endpoint
+/// stopping, ...)
+fn connect_error(target: SocketAddr, err: quinn::ConnectError) -> SendFailure {
+    let message = format!("Connect failed: {}", err);
+    SendFailure::new(SendFailureKind::Protocol, target, message)
+}
+
+/// Group consecutive transaction lengths into streams of at most `limit`
+/// bytes (a larger transaction gets a stream of its own). Returns the number
+/// of transactions in each stream.
+fn pack_streams(lens: impl IntoIterator<Item = usize>, limit: usize) -> Vec<usize> {
+    let mut streams = Vec::new();
+    let (mut count, mut bytes) = (0, 0);
+    for len in lens {
+        if count > 0 && bytes + len > limit {
+            streams.push(count);
+            (count, bytes) = (0, 0);
+        }
+        count += 1;
+        bytes += len;
+    }
+    if count > 0 {
+        streams.push(count);
+    }
+    streams
+}
+
+/// Bind a client endpoint using `client_config` for outgoing connections,
+/// with the socket sized by QUIC_SEND_BUFFER_BYTES / QUIC_RECV_BUFFER_BYTES
+/// and driven by `runtime`
+fn bind_client(
+    addr: SocketAddr,
+    client_config: &quinn::ClientConfig,
+    config: &Config,
+    runtime: &Arc<dyn quinn::Runtime>,
+) -> Result<(SocketAddr, Endpoint), ScramjetError> {
+    let socket = Socket::new(Domain::for_address(addr), Type::DGRAM, Some(Protocol::UDP))?;
+    if addr.is_ipv6() {
+        // Same as Endpoint::client: dual-stack where the OS allows it
+        if let Err(e) = socket.set_only_v6(false) {
+            debug!("Unable to make {} dual-stack: {}", addr, e);
+        }
+    }
+    if config.quic_send_buffer_bytes > 0 {
+        socket.set_send_buffer_size(config.quic_send_buffer_bytes)?;
+        let actual = socket.send_buffer_size()?;
+        check_buffer_size("send", config.quic_send_buffer_bytes, actual, "wmem_max");
+    }
+    if config.quic_recv_buffer_bytes > 0 {
+        socket.set_recv_buffer_size(config.quic_recv_buffer_bytes)?;
+        let actual = socket.recv_buffer_size()?;
+        check_buffer_size("receive", config.quic_recv_buffer_bytes, actual, "rmem_max");
+    }
+    socket.bind(&addr.into())?;
+
+    let mut endpoint = Endpoint::new(
+        quinn::EndpointConfig::default(),
+        None,
+        socket.into(),
+        runtime.clone(),
+    )?;
+    endpoint.set_default_client_config(client_config.clone());
+    Ok((endpoint.local_addr()?, endpoint))
+}
+
+/// The kernel silently caps buffer sizes (Linux: net.core.wmem_max /
+/// rmem_max, then doubles the value for bookkeeping), so report what stuck
+fn check_buffer_size(kind: &str, requested: usize, actual: usize, limit: &str) {
+    if actual < requested {
+        warn!(
+            "QUIC UDP {} buffer is {} bytes, below the {} requested (raise net.core.{})",
+            kind, actual, requested, limit
+        );
+    } else {
+        debug!("QUIC UDP {} buffer: {} bytes", kind, actual);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use scramjet_testkit::{server_config, MockValidator};
     use solana_sdk::signature::Keypair;
     use std::sync::Arc;
     use tokio::sync::mpsc;
 
-    fn make_server_config() -> (quinn::ServerConfig, Vec<u8>) {
-        use quinn::crypto::rustls::QuicServerConfig;
-        use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
-        
-        let certified_key = rcgen::generate_simple_self_signed(vec!["solana".into()]).unwrap();
-        let cert_der = certified_key.cert.der().to_vec();
-        let key_der = certified_key.key_pair.serialize_der();
-        
-        let key = PrivatePkcs8KeyDer::from(key_der).into();
-        let cert_chain = vec![CertificateDer::from(cert_der.clone())];
-
-        let mut server_crypto = rustls::ServerConfig::builder()
-            .with_no_client_auth()
-            .with_single_cert(cert_chain, key)
-            .unwrap();
-
-        server_crypto.alpn_protocols = vec![b"solana-tpu".to_vec()];
-
-        // Wrap with QuicServerConfig for quinn 0.11
-        let quic_server_config = QuicServerConfig::try_from(server_crypto).unwrap();
-
-        (
-            quinn::ServerConfig::with_crypto(Arc::new(quic_server_config)),
-            cert_der,
-        )
-    }
-
     #[tokio::test]
+    #[allow(clippy::while_let_loop, clippy::redundant_pattern_matching)]
     async fn test_connection_reuse_multiplexing() {
         // 1. SETUP: Server (IPv4 to match client)
-        let (server_config, _) = make_server_config();
+        let server_config = server_config();
         let server_endpoint =
             Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
         let server_addr = server_endpoint.local_addr().unwrap();
@@ -192,4 +1441,583 @@ mod tests {
 
         assert_eq!(received_count, 10, "Multiplexing failed");
     }
+
+    #[tokio::test]
+    async fn test_mock_validator_single_handshake_and_path_stats() {
+        let validator = MockValidator::start().unwrap();
+        let server_addr = validator.addr();
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+
+        let connection_handle = engine
+            .get_connection_handle(server_addr)
+            .await
+            .expect("Failed to get connection handle");
+        for i in 0..10u8 {
+            let mut stream = connection_handle.open_uni().await.expect("open stream");
+            stream.write_all(&[i]).await.expect("Write failed");
+            stream.finish().expect("Finish failed");
+        }
+        assert!(
+            validator
+                .wait_for_transactions(10, Duration::from_secs(5))
+                .await
+        );
+        assert_eq!(validator.handshakes(), 1);
+
+        // The pooled connection's QUIC path shows up in the stats registry
+        engine.sample_paths();
+        let path = engine.stats().path(&server_addr).expect("path sampled");
+        assert!(path.rtt_ms > 0.0);
+        assert!(path.cwnd > 0);
+        assert!(path.datagrams_sent > 0);
+    }
+
+    #[tokio::test]
+    async fn test_inbound_streams_refused() {
+        let server_config = server_config();
+        let server_endpoint =
+            Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let server_addr = server_endpoint.local_addr().unwrap();
+
+        // Server (validator) tries to push a stream back to the client
+        let (tx, mut rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            if let Some(conn) = server_endpoint.accept().await {
+                let connection = conn.await.expect("Handshake failed");
+                let opened = tokio::time::timeout(
+                    std::time::Duration::from_millis(300),
+                    connection.open_uni(),
+                )
+                .await;
+                tx.send(opened.is_ok()).await.unwrap();
+            }
+        });
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        let _conn = engine
+            .get_connection_handle(server_addr)
+            .await
+            .expect("Failed to connect");
+
+        // Zero stream credit: the validator can never open a stream to us
+        assert_eq!(rx.recv().await, Some(false));
+        assert_eq!(engine.inbound_rejections(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_peer_stream_limit_detected() {
+        // Validator granting 4 concurrent uni streams that never reads them
+        let mut server_config = server_config();
+        let mut transport = quinn::TransportConfig::default();
+        transport.max_concurrent_uni_streams(4u32.into());
+        server_config.transport_config(Arc::new(transport));
+        let server_endpoint =
+            Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let server_addr = server_endpoint.local_addr().unwrap();
+        tokio::spawn(async move {
+            if let Some(conn) = server_endpoint.accept().await {
+                let connection = conn.await.expect("Handshake failed");
+                let _ = connection.closed().await;
+            }
+        });
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        let conn = engine
+            .get_connection_handle(server_addr)
+            .await
+            .expect("Failed to connect");
+
+        for _ in 0..4 {
+            let mut stream = engine.open_stream(server_addr, &conn).await.unwrap();
+            stream.write_all(b"tx").await.unwrap();
+            stream.finish().unwrap();
+        }
+        let limits = engine.peer_limits(&server_addr).unwrap();
+        assert_eq!(limits.max_concurrent_uni_streams, None);
+        assert_eq!(limits.streams_opened, 4);
+
+        // The fifth stream has to wait for credit the validator never grants
+        let fifth = tokio::time::timeout(
+            std::time::Duration::from_millis(200),
+            engine.open_stream(server_addr, &conn),
+        )
+        .await;
+        assert!(fifth.is_err());
+
+        let limits = engine.peer_limits(&server_addr).unwrap();
+        assert_eq!(limits.max_concurrent_uni_streams, Some(4));
+        assert_eq!(limits.blocked_opens, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_timeout() {
+        // No stream credit: every open waits forever
+        let validator = MockValidator::builder()
+            .max_concurrent_uni_streams(0)
+            .start()
+            .unwrap();
+        let mut config = Config::from_env().expect("Failed to load config");
+        config.quic_send_timeout_ms = 100;
+        config.send_retry_max_attempts = 1;
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+
+        let send = engine.send_transaction(validator.addr(), Bytes::from_static(b"tx"));
+        let err = tokio::time::timeout(std::time::Duration::from_secs(5), send)
+            .await
+            .expect("send stalled past the timeout")
+            .unwrap_err();
+        let failure = err.send_failure().expect("unclassified");
+        assert_eq!(failure.kind, SendFailureKind::StreamTimeout);
+        assert!(failure.kind.is_retryable() && failure.kind.is_stream());
+        assert_eq!(validator.transaction_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_send_timeout_covers_stream_budget() {
+        let validator = MockValidator::start().unwrap();
+        let target = validator.addr();
+        let mut config = Config::from_env().expect("Failed to load config");
+        config.quic_send_timeout_ms = 100;
+        config.quic_stream_budget = 1;
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        let connection = engine.get_connection_handle(target).await.unwrap();
+
+        // The only stream of the budget is taken: waiting for it times out
+        let _held = engine.stream_permit(target, Priority::High).await;
+        let mut chunks = [Bytes::from_static(b"tx")];
+        let write = engine.write_stream(target, &connection, &mut chunks, Priority::High);
+        let err = tokio::time::timeout(std::time::Duration::from_secs(5), write)
+            .await
+            .expect("write stalled past the timeout")
+            .unwrap_err();
+        let failure = err.send_failure().expect("unclassified");
+        assert_eq!(failure.kind, SendFailureKind::StreamTimeout);
+        assert_eq!(validator.transaction_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_connection_pool_round_robin() {
+        let server_config = server_config();
+        let server_endpoint =
+            Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let server_addr = server_endpoint.local_addr().unwrap();
+        let (tx, mut rx) = mpsc::channel(8);
+        tokio::spawn(async move {
+            while let Some(conn) = server_endpoint.accept().await {
+                let connection = conn.await.expect("Handshake failed");
+                tx.send(()).await.unwrap();
+                tokio::spawn(async move {
+                    let _ = connection.closed().await;
+                });
+            }
+        });
+
+        let mut config = Config::from_env().expect("Failed to load config");
+        config.quic_connections_per_leader = 3;
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+
+        let pool = engine.get_connection_pool(server_addr).await.unwrap();
+        assert_eq!(pool.len(), 3);
+        for _ in 0..3 {
+            rx.recv().await.unwrap();
+        }
+        assert_eq!(engine.warm_connections(), 3);
+
+        // Handles rotate across the pool without new handshakes
+        let ids: std::collections::HashSet<usize> =
+            futures::future::join_all((0..6).map(|_| engine.get_connection_handle(server_addr)))
+                .await
+                .into_iter()
+                .map(|conn| conn.unwrap().stable_id())
+                .collect();
+        assert_eq!(ids.len(), 3);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(engine.quarantine_connections(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_connection_budget_and_idle_eviction() {
+        let mut servers = Vec::new();
+        for _ in 0..3 {
+            let server_config = server_config();
+            let endpoint = Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+            servers.push(endpoint.local_addr().unwrap());
+            tokio::spawn(async move {
+                while let Some(conn) = endpoint.accept().await {
+                    if let Ok(connection) = conn.await {
+                        tokio::spawn(async move {
+                            let _ = connection.closed().await;
+                        });
+                    }
+                }
+            });
+        }
+
+        let mut config = Config::from_env().expect("Failed to load config");
+        config.quic_max_connections = 2;
+        config.quic_connection_ttl_secs = 1;
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        assert_eq!(engine.leader_capacity(), Some(2));
+
+        let first = engine.get_connection_handle(servers[0]).await.unwrap();
+        engine.get_connection_handle(servers[1]).await.unwrap();
+        // Touch the first leader: the second is now least recently used
+        engine.get_connection_handle(servers[0]).await.unwrap();
+        engine.get_connection_handle(servers[2]).await.unwrap();
+        assert_eq!(engine.warm_connections(), 2);
+        assert!(engine.connection_cache.contains_key(&(servers[0], 0)));
+        assert!(!engine.connection_cache.contains_key(&(servers[1], 0)));
+        assert!(first.close_reason().is_none());
+
+        tokio::time::sleep(Duration::from_millis(1_100)).await;
+        engine.get_connection_handle(servers[2]).await.unwrap();
+        assert_eq!(engine.evict_idle(), 1);
+        assert!(first.close_reason().is_some());
+        assert_eq!(engine.warm_connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_probe_replaces_stale_connections() {
+        let server_config = server_config();
+        let endpoint = Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let server_addr = endpoint.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Some(conn) = endpoint.accept().await {
+                if let Ok(connection) = conn.await {
+                    tokio::spawn(async move {
+                        let _ = connection.closed().await;
+                    });
+                }
+            }
+        });
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        let first = engine.get_connection_handle(server_addr).await.unwrap();
+        let report = engine.probe_connections();
+        assert_eq!(report.healthy, 1);
+        assert!(report.stale.is_empty());
+        assert_eq!(engine.refresh_pool(server_addr).await.unwrap(), 0);
+
+        // Nothing heard since long ago: stale, and replaced by a new handshake
+        engine.liveness.insert(
+            (server_addr, 0),
+            Liveness {
+                rx_datagrams: first.stats().udp_rx.datagrams,
+                since: Instant::now() - engine.stale_after,
+            },
+        );
+        assert_eq!(engine.probe_connections().stale, vec![server_addr]);
+        assert_eq!(engine.refresh_pool(server_addr).await.unwrap(), 1);
+        let second = engine.get_connection_handle(server_addr).await.unwrap();
+        assert_ne!(first.stable_id(), second.stable_id());
+        assert!(first.close_reason().is_some());
+        assert_eq!(engine.probe_connections().healthy, 1);
+
+        // Closed connections are dropped from the cache
+        second.close(0u32.into(), b"test");
+        let report = engine.probe_connections();
+        assert_eq!(report.dead, 1);
+        assert_eq!(engine.warm_connections(), 0);
+    }
+
+    #[test]
+    fn test_pack_streams() {
+        assert_eq!(pack_streams([400, 400, 400, 400], 1232), vec![3, 1]);
+        assert_eq!(pack_streams([1232, 1, 2000, 5], 1232), vec![1, 1, 1, 1]);
+        assert_eq!(pack_streams([], 1232), Vec::<usize>::new());
+    }
+
+    #[tokio::test]
+    async fn test_send_batch_modes() {
+        let validator = MockValidator::start().unwrap();
+        let server_addr = validator.addr();
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        let txs: Vec<Bytes> = (1..=3u8).map(|i| Bytes::from(vec![i; 300])).collect();
+
+        let results = engine
+            .send_batch(
+                server_addr,
+                txs.clone(),
+                BatchMode::StreamPerTx,
+                Priority::Normal,
+            )
+            .await
+            .unwrap();
+        assert!(results.iter().all(Result::is_ok));
+        assert!(
+            validator
+                .wait_for_transactions(3, Duration::from_secs(5))
+                .await
+        );
+        let mut received: Vec<Vec<u8>> = validator
+            .transactions()
+            .into_iter()
+            .map(|received| received.data)
+            .collect();
+        received.sort();
+        assert_eq!(received, txs);
+
+        // Packed: all three back to back in a single stream, in order
+        let results = engine
+            .send_batch(
+                server_addr,
+                txs.clone(),
+                BatchMode::Packed,
+                Priority::Normal,
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(
+            validator
+                .wait_for_transactions(4, Duration::from_secs(5))
+                .await
+        );
+        assert_eq!(validator.transactions()[3].data, txs.concat());
+        assert_eq!(validator.transaction_count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_datagram_mode_with_stream_fallback() {
+        let validator = MockValidator::start().unwrap();
+        let mut config = Config::from_env().expect("Failed to load config");
+        config.quic_datagrams = true;
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+
+        // Fits in a datagram: sent streamless
+        engine
+            .send_transaction(validator.addr(), Bytes::from(vec![1; 200]))
+            .await
+            .unwrap();
+        // Larger than the path allows: falls back to a stream
+        engine
+            .send_transaction(validator.addr(), Bytes::from(vec![2; 1500]))
+            .await
+            .unwrap();
+        assert!(
+            validator
+                .wait_for_transactions(2, Duration::from_secs(5))
+                .await
+        );
+        let mut received = validator.transactions();
+        received.sort_by_key(|received| received.data[0]);
+        assert!(received[0].datagram);
+        assert!(!received[1].datagram);
+        assert_eq!(received[1].data, vec![2; 1500]);
+        assert_eq!(engine.datagram_counts(), (1, 1));
+
+        // A leader without datagram support gets streams only
+        let no_datagrams = MockValidator::builder().datagrams(false).start().unwrap();
+        engine
+            .send_transaction(no_datagrams.addr(), Bytes::from(vec![3; 200]))
+            .await
+            .unwrap();
+        assert!(
+            no_datagrams
+                .wait_for_transactions(1, Duration::from_secs(5))
+                .await
+        );
+        assert!(!no_datagrams.transactions()[0].datagram);
+        assert_eq!(engine.datagram_counts(), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_and_closes_with_code() {
+        let server_config = server_config();
+        let server_endpoint =
+            Endpoint::server(server_config, "127.0.0.1:0".parse().unwrap()).unwrap();
+        let server_addr = server_endpoint.local_addr().unwrap();
+        let (tx, mut rx) = mpsc::channel(2);
+        tokio::spawn(async move {
+            if let Some(conn) = server_endpoint.accept().await {
+                let connection = conn.await.expect("Handshake failed");
+                let mut stream = connection.accept_uni().await.unwrap();
+                let data = stream.read_to_end(1024).await.unwrap();
+                tx.send(format!("{:?}", data)).await.unwrap();
+                let reason = connection.closed().await;
+                tx.send(reason.to_string()).await.unwrap();
+            }
+        });
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        engine
+            .send_transaction(server_addr, Bytes::from_static(b"tx"))
+            .await
+            .unwrap();
+
+        assert_eq!(engine.shutdown(Duration::from_secs(2)).await, 1);
+        assert!(engine.is_closing());
+        assert_eq!(rx.recv().await.unwrap(), format!("{:?}", b"tx".to_vec()));
+        let reason = rx.recv().await.unwrap();
+        assert!(reason.contains("shutdown (code 0)"), "{}", reason);
+
+        // No new sends once closing
+        assert!(matches!(
+            engine
+                .send_transaction(server_addr, Bytes::from_static(b"late"))
+                .await,
+            Err(ScramjetError::ShuttingDown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dual_stack_endpoint_selection() {
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        let v4: SocketAddr = "127.0.0.1:8009".parse().unwrap();
+        let (_, remote) = engine.endpoint_for(v4, 0).unwrap();
+        assert_eq!(remote, v4);
+        // v4-mapped targets are sent to the plain IPv4 address
+        let mapped: SocketAddr = "[::ffff:127.0.0.1]:8009".parse().unwrap();
+        assert_eq!(engine.endpoint_for(mapped, 0).unwrap().1, v4);
+
+        // IPv6 leaders are reachable when the host has IPv6
+        let server_config = server_config();
+        let Ok(server_endpoint) = Endpoint::server(server_config, "[::1]:0".parse().unwrap())
+        else {
+            return;
+        };
+        if !engine.local_addrs().iter().any(|local| local.is_ipv6()) {
+            return;
+        }
+        let server_addr = server_endpoint.local_addr().unwrap();
+        let (tx, mut rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            if let Some(conn) = server_endpoint.accept().await {
+                let connection = conn.await.expect("Handshake failed");
+                let mut stream = connection.accept_uni().await.unwrap();
+                let data = stream.read_to_end(1024).await.unwrap();
+                tx.send(data).await.unwrap();
+            }
+        });
+        engine
+            .send_transaction(server_addr, Bytes::from_static(b"tx6"))
+            .await
+            .unwrap();
+        assert_eq!(rx.recv().await.unwrap(), b"tx6".to_vec());
+    }
+
+    #[tokio::test]
+    async fn test_measure_rtt() {
+        let validator = MockValidator::start().unwrap();
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+
+        // Throwaway handshake: nothing cached or counted
+        let rtt = engine.measure_rtt(validator.addr()).await.unwrap();
+        assert!(rtt < Duration::from_secs(1));
+        assert_eq!(engine.warm_connections(), 0);
+        assert!(engine.handshake_time(&validator.addr()).is_none());
+
+        // With a pooled connection its RTT is used
+        assert!(engine.pooled_rtt(&validator.addr()).is_none());
+        engine
+            .get_connection_handle(validator.addr())
+            .await
+            .unwrap();
+        assert!(engine.pooled_rtt(&validator.addr()).is_some());
+        engine.measure_rtt(validator.addr()).await.unwrap();
+        assert_eq!(engine.warm_connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_tuned_socket_buffers() {
+        let validator = MockValidator::start().unwrap();
+        let mut config = Config::from_env().expect("Failed to load config");
+        config.quic_send_buffer_bytes = 1 << 20;
+        config.quic_recv_buffer_bytes = 1 << 20;
+        config.quic_segmentation_offload = false;
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+
+        engine
+            .send_transaction(validator.addr(), Bytes::from_static(b"tuned"))
+            .await
+            .unwrap();
+        assert!(
+            validator
+                .wait_for_transactions(1, Duration::from_secs(5))
+                .await
+        );
+        assert_eq!(validator.transactions()[0].data, b"tuned".to_vec());
+    }
+
+    // Only Linux routes all of 127.0.0.0/8 to loopback without setup
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_multiple_bind_addrs() {
+        let validator = MockValidator::start().unwrap();
+        let mut config = Config::from_env().expect("Failed to load config");
+        config.quic_bind_addrs = vec!["127.0.0.1".into(), "127.0.0.2".into()];
+        config.quic_connections_per_leader = 2;
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        assert_eq!(engine.local_addrs().len(), 2);
+
+        // The two pool slots of a leader go out through different addresses
+        let local = |index| {
+            let (endpoint, _) = engine.endpoint_for(validator.addr(), index).unwrap();
+            endpoint.local_addr().unwrap().ip()
+        };
+        assert_ne!(local(0), local(1));
+        assert_eq!(local(0), local(2));
+
+        let pool = engine.get_connection_pool(validator.addr()).await.unwrap();
+        assert_eq!(pool.len(), 2);
+        engine
+            .send_transaction(validator.addr(), Bytes::from_static(b"multi"))
+            .await
+            .unwrap();
+        assert!(
+            validator
+                .wait_for_transactions(1, Duration::from_secs(5))
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_identity_failover_selection() {
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        let addr: SocketAddr = "1.1.1.1:8009".parse().unwrap();
+        let threshold = config.quic_identity_failover_threshold;
+
+        let rejected = Some(SendFailureKind::CertRejected);
+
+        // A leader that is down is not rejecting the identity
+        for _ in 0..threshold {
+            engine.record_handshake(
+                addr,
+                IdentityKind::Primary,
+                Some(SendFailureKind::HandshakeTimeout),
+            );
+        }
+        assert_eq!(engine.select_identity(&addr), IdentityKind::Primary);
+
+        // Primary until the rejection threshold is reached
+        for _ in 0..threshold {
+            assert_eq!(engine.select_identity(&addr), IdentityKind::Primary);
+            engine.record_handshake(addr, IdentityKind::Primary, rejected);
+        }
+        assert_eq!(engine.select_identity(&addr), IdentityKind::Fallback);
+
+        // Fallback accepted: sticky for this target only
+        engine.record_handshake(addr, IdentityKind::Fallback, None);
+        assert_eq!(
+            engine.accepted_identity(&addr),
+            Some(IdentityKind::Fallback)
+        );
+        assert_eq!(engine.select_identity(&addr), IdentityKind::Fallback);
+        let other: SocketAddr = "2.2.2.2:8009".parse().unwrap();
+        assert_eq!(engine.select_identity(&other), IdentityKind::Primary);
+
+        // Fallback rejected: reset to primary
+        engine.record_handshake(addr, IdentityKind::Fallback, rejected);
+        assert_eq!(engine.select_identity(&addr), IdentityKind::Primary);
+    }
 }
@@ -0,0 +1,54 @@
+//! Fuzz harness entry points for external-input parsers.
+//!
+//! Enabled with the `fuzzing` feature and driven by the cargo-fuzz targets in
+//! `fuzz/` (`cargo +nightly fuzz run blocklist_line`). Every harness must
+//! return normally for any input: parsers report malformed data as typed
+//! errors, never panics.
+
+use crate::blocklist::parse_blocklist_line;
+use crate::cartographer::{parse_cluster_node, parse_leader_schedule};
+use crate::geyser::parse_geyser_endpoint;
+use crate::raw::{parse_raw_transaction, RawEncoding};
+use solana_rpc_client_api::response::RpcContactInfo;
+use std::collections::HashMap;
+
+/// Blocklist file / remote body, line by line
+pub fn blocklist(data: &[u8]) {
+    let text = String::from_utf8_lossy(data);
+    for line in text.lines() {
+        let _ = parse_blocklist_line(line);
+    }
+}
+
+/// Geyser URL (with optional auth token path)
+pub fn geyser_url(data: &[u8]) {
+    let _ = parse_geyser_endpoint(&String::from_utf8_lossy(data));
+}
+
+/// `getClusterNodes` entry as returned by RPC
+pub fn cluster_node(data: &[u8]) {
+    if let Ok(node) = serde_json::from_slice::<RpcContactInfo>(data) {
+        let _ = parse_cluster_node(&node);
+    }
+}
+
+/// `getLeaderSchedule` body; the first 8 bytes pick the epoch start slot
+pub fn leader_schedule(data: &[u8]) {
+    if data.len() < 8 {
+        return;
+    }
+    let (slot_bytes, body) = data.split_at(8);
+    let mut start = [0u8; 8];
+    start.copy_from_slice(slot_bytes);
+    if let Ok(schedule) = serde_json::from_slice::<HashMap<String, Vec<usize>>>(body) {
+        let _ = parse_leader_schedule(&schedule, u64::from_le_bytes(start));
+    }
+}
+
+/// `send-raw` input line, in every encoding mode
+pub fn raw_transaction(data: &[u8]) {
+    let line = String::from_utf8_lossy(data);
+    for encoding in [RawEncoding::Auto, RawEncoding::Base64, RawEncoding::Base58] {
+        let _ = parse_raw_transaction(&line, encoding);
+    }
+}
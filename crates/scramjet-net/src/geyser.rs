@@ -1,23 +1,193 @@
 use crate::cartographer::Cartographer;
 use http::Uri;
-use log::{error, info};
+use log::{debug, error, info, warn};
 use scramjet_common::ScramjetError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot};
 use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
 use tonic::transport::{Channel, Endpoint};
 use tonic::{service::Interceptor, Request, Status};
 use yellowstone_grpc_proto::geyser::SubscribeRequest;
 use yellowstone_grpc_proto::geyser::{
-    geyser_client::GeyserClient, subscribe_update::UpdateOneof, SubscribeRequestFilterSlots,
+    geyser_client::GeyserClient, subscribe_update::UpdateOneof, CommitmentLevel, GetSlotRequest,
+    SlotStatus, SubscribeRequestFilterBlocksMeta, SubscribeRequestFilterEntry,
+    SubscribeRequestFilterSlots, SubscribeRequestFilterTransactions, SubscribeRequestPing,
+    SubscribeUpdateBlockMeta, SubscribeUpdateSlot, SubscribeUpdateTransactionStatus,
 };
+use yellowstone_grpc_proto::prelude::RewardType;
 
 /// Geyser listener for real-time slot updates via Yellowstone gRPC
 pub struct GeyserListener {
-    client: GeyserClient<tonic::service::interceptor::InterceptedService<Channel, AuthInterceptor>>,
+    client: Client,
     cartographer: Arc<Cartographer>,
+    blocks_meta: bool,
+    /// Signer whose transaction statuses are forwarded to the channel
+    tx_status: Option<(Pubkey, mpsc::Sender<LandedSignature>)>,
+    keepalive: Keepalive,
+    slot_filter: SlotFilter,
+}
+
+/// Slot status the clock follows (`GEYSER_SLOT_COMMITMENT`): `processed` is
+/// the earliest signal, `confirmed` and `finalized` trail it but never
+/// report a slot that is later abandoned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SlotCommitment {
+    #[default]
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl FromStr for SlotCommitment {
+    type Err = ScramjetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "processed" => Ok(Self::Processed),
+            "confirmed" => Ok(Self::Confirmed),
+            "finalized" => Ok(Self::Finalized),
+            other => Err(ScramjetError::ConfigValidationError(format!(
+                "GEYSER_SLOT_COMMITMENT='{}' (expected processed, confirmed or finalized)",
+                other
+            ))),
+        }
+    }
+}
+
+/// What the slot subscription delivers and which updates drive the clock
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SlotFilter {
+    pub commitment: SlotCommitment,
+    /// Also receive the statuses a slot goes through before it is processed
+    /// (first shred received, bank created, completed): an earlier clock
+    /// under `processed`
+    pub interslot_updates: bool,
+    /// Also receive entries: the first one of a slot shows its leader
+    /// producing, before any status update for it (under `processed`)
+    pub entries: bool,
+}
+
+impl SlotFilter {
+    /// `GEYSER_SLOT_COMMITMENT` / `GEYSER_INTERSLOT_UPDATES`
+    pub fn from_config(config: &scramjet_common::Config) -> Result<Self, ScramjetError> {
+        Ok(Self {
+            commitment: config.geyser_slot_commitment.parse()?,
+            interslot_updates: config.geyser_interslot_updates,
+            entries: config.geyser_entries,
+        })
+    }
+
+    /// Slot whose production `update` shows starting (first shred received)
+    pub fn production_slot(&self, update: &SubscribeUpdateSlot) -> Option<u64> {
+        let first_shred = update.status == SlotStatus::SlotFirstShredReceived as i32;
+        (first_shred && self.commitment == SlotCommitment::Processed).then_some(update.slot)
+    }
+
+    /// Slot to advance the clock to on `update` (None = not a clock signal)
+    pub fn clock_slot(&self, update: &SubscribeUpdateSlot) -> Option<u64> {
+        let status = SlotStatus::try_from(update.status).ok()?;
+        let signal = match self.commitment {
+            SlotCommitment::Processed => matches!(
+                status,
+                SlotStatus::SlotProcessed
+                    | SlotStatus::SlotFirstShredReceived
+                    | SlotStatus::SlotCreatedBank
+                    | SlotStatus::SlotCompleted
+            ),
+            SlotCommitment::Confirmed => status == SlotStatus::SlotConfirmed,
+            SlotCommitment::Finalized => status == SlotStatus::SlotFinalized,
+        };
+        signal.then_some(update.slot)
+    }
+}
+
+/// Keepalive pings and the stale-stream watchdog. A stream can stall without
+/// erroring (e.g. a proxy silently dropping it), which would freeze the slot
+/// clock: pings keep idle intermediaries from closing it, and the watchdog
+/// reconnects when no slot update arrives in time.
+#[derive(Debug, Clone, Default)]
+pub struct Keepalive {
+    /// Ping request interval (None = no pings)
+    pub ping_interval: Option<Duration>,
+    /// Reconnect after this long without a slot update (None = never)
+    pub stall_timeout: Option<Duration>,
+    pub health: Arc<GeyserHealth>,
+}
+
+impl Keepalive {
+    /// `GEYSER_PING_INTERVAL_MS` / `GEYSER_STALL_TIMEOUT_MS`, reporting to `health`
+    pub fn from_config(config: &scramjet_common::Config, health: Arc<GeyserHealth>) -> Self {
+        Self {
+            ping_interval: config.geyser_ping_interval(),
+            stall_timeout: config.geyser_stall_timeout(),
+            health,
+        }
+    }
+}
+
+/// Liveness of the Geyser streams of a process (shared by redundant
+/// endpoints, so it tracks the freshest one)
+#[derive(Debug)]
+pub struct GeyserHealth {
+    started: Instant,
+    /// Milliseconds from `started` to the latest slot update (0 = none yet)
+    last_slot_ms: AtomicU64,
+    /// Streams reconnected by the watchdog
+    stalls: AtomicU64,
+}
+
+impl Default for GeyserHealth {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            last_slot_ms: AtomicU64::new(0),
+            stalls: AtomicU64::new(0),
+        }
+    }
+}
+
+impl GeyserHealth {
+    fn slot_seen(&self) {
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        self.last_slot_ms
+            .fetch_max(elapsed.max(1), Ordering::Relaxed);
+    }
+
+    fn record_stall(&self) {
+        self.stalls.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Time since the latest slot update on any stream (None = none yet)
+    pub fn since_last_slot(&self) -> Option<Duration> {
+        match self.last_slot_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(
+                self.started
+                    .elapsed()
+                    .saturating_sub(Duration::from_millis(ms)),
+            ),
+        }
+    }
+
+    /// Streams the watchdog reconnected for going quiet
+    pub fn stalls(&self) -> u64 {
+        self.stalls.load(Ordering::Relaxed)
+    }
+}
+
+/// A transaction seen landing on the Geyser transaction status stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LandedSignature {
+    pub signature: Signature,
+    pub slot: u64,
+    /// Landed with an error (fees paid, no effect)
+    pub failed: bool,
 }
 
 #[derive(Clone)]
@@ -36,84 +206,207 @@ impl Interceptor for AuthInterceptor {
     }
 }
 
-impl GeyserListener {
-    pub async fn connect(
-        mut endpoint: String,
-        cartographer: Arc<Cartographer>,
-    ) -> Result<Self, ScramjetError> {
-        info!("Geyser: Parsing endpoint...");
-
-        // Extract auth token from URL path if present (e.g., https://host/token123)
-        let mut x_token = None;
-        if let Ok(uri) = endpoint.parse::<Uri>() {
-            if let Some(path) = uri.path_and_query() {
-                let path_str = path.as_str();
-                if path_str.len() > 10 {
-                    info!("Geyser: Extracting Auth Token from URL path.");
-                    x_token = Some(path_str.trim_start_matches('/').to_string());
-
-                    // Reconstruct clean endpoint without path
-                    let scheme = uri.scheme_str().unwrap_or("https");
-                    let authority = uri
-                        .authority()
-                        .ok_or_else(|| {
-                            ScramjetError::InvalidUri(format!(
-                                "Geyser URL missing authority: {}",
-                                endpoint
-                            ))
-                        })?
-                        .as_str();
-                    endpoint = format!("{}://{}", scheme, authority);
-                }
-            }
+/// Geyser endpoint with the auth token split out of the URL path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeyserEndpoint {
+    pub url: String,
+    pub x_token: Option<String>,
+}
+
+/// Parse a Geyser URL, extracting an auth token from the path if present
+/// (e.g., `https://host/token123`). Never panics on malformed input.
+pub fn parse_geyser_endpoint(endpoint: &str) -> Result<GeyserEndpoint, ScramjetError> {
+    let uri = endpoint
+        .parse::<Uri>()
+        .map_err(|e| ScramjetError::InvalidUri(format!("Invalid Geyser URL: {}", e)))?;
+
+    if let Some(path) = uri.path_and_query() {
+        let path_str = path.as_str();
+        if path_str.len() > 10 {
+            let token = path_str.trim_start_matches('/').to_string();
+            // Reject tokens that can't be sent as gRPC metadata up front
+            tonic::metadata::MetadataValue::<tonic::metadata::Ascii>::from_str(&token).map_err(
+                |_| ScramjetError::InvalidUri("Geyser auth token has invalid characters".into()),
+            )?;
+
+            // Reconstruct clean endpoint without path
+            let scheme = uri.scheme_str().unwrap_or("https");
+            let authority = uri
+                .authority()
+                .ok_or_else(|| {
+                    ScramjetError::InvalidUri(format!("Geyser URL missing authority: {}", endpoint))
+                })?
+                .as_str();
+            return Ok(GeyserEndpoint {
+                url: format!("{}://{}", scheme, authority),
+                x_token: Some(token),
+            });
         }
+    }
 
-        info!("Geyser: Connecting to {}", endpoint);
+    Ok(GeyserEndpoint {
+        url: endpoint.to_string(),
+        x_token: None,
+    })
+}
+
+type Client =
+    GeyserClient<tonic::service::interceptor::InterceptedService<Channel, AuthInterceptor>>;
+
+/// Open an authenticated gRPC client to `endpoint`
+async fn connect_client(endpoint: &str) -> Result<Client, ScramjetError> {
+    info!("Geyser: Parsing endpoint...");
+    let GeyserEndpoint {
+        url: endpoint,
+        x_token,
+    } = parse_geyser_endpoint(endpoint)?;
+    if x_token.is_some() {
+        info!("Geyser: Extracting Auth Token from URL path.");
+    }
+
+    info!("Geyser: Connecting to {}", endpoint);
 
-        // Create gRPC channel with TLS
-        let channel = Endpoint::from_shared(endpoint.clone())
-            .map_err(|e| ScramjetError::InvalidUri(format!("Invalid endpoint: {}", e)))?
-            .tls_config(tonic::transport::ClientTlsConfig::new())?
-            .connect()
-            .await?;
+    // Create gRPC channel with TLS
+    let channel = Endpoint::from_shared(endpoint.clone())
+        .map_err(|e| ScramjetError::InvalidUri(format!("Invalid endpoint: {}", e)))?
+        .tls_config(tonic::transport::ClientTlsConfig::new())?
+        .connect()
+        .await?;
 
-        let interceptor = AuthInterceptor { token: x_token };
-        let client = GeyserClient::with_interceptor(channel, interceptor);
+    let interceptor = AuthInterceptor { token: x_token };
+    let client = GeyserClient::with_interceptor(channel, interceptor);
+
+    info!("Geyser: Connected.");
+    Ok(client)
+}
+
+/// Connect to `endpoint` and ask for its processed slot: a one-shot check
+/// that needs no subscription (`scramjet doctor`)
+pub async fn probe_slot(endpoint: &str) -> Result<u64, ScramjetError> {
+    let mut client = connect_client(endpoint).await?;
+    let response = client
+        .get_slot(GetSlotRequest {
+            commitment: Some(CommitmentLevel::Processed as i32),
+        })
+        .await?;
+    Ok(response.into_inner().slot)
+}
 
-        info!("Geyser: Connected.");
+impl GeyserListener {
+    pub async fn connect(
+        endpoint: String,
+        cartographer: Arc<Cartographer>,
+    ) -> Result<Self, ScramjetError> {
+        let client = connect_client(&endpoint).await?;
         Ok(Self {
             client,
             cartographer,
+            blocks_meta: false,
+            tx_status: None,
+            keepalive: Keepalive::default(),
+            slot_filter: SlotFilter::default(),
         })
     }
 
+    /// Slot commitment and interslot updates (default: processed, none)
+    pub fn with_slot_filter(mut self, slot_filter: SlotFilter) -> Self {
+        self.slot_filter = slot_filter;
+        self
+    }
+
+    /// Ping the stream and reconnect it when slot updates stop (default: neither)
+    pub fn with_keepalive(mut self, keepalive: Keepalive) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
+    /// Also subscribe to block metadata and cross-check/fill the leader
+    /// schedule from block producers (keeps targeting alive while RPC is degraded)
+    pub fn with_blocks_meta(mut self, enabled: bool) -> Self {
+        self.blocks_meta = enabled;
+        self
+    }
+
+    /// Also subscribe to the status of transactions that require `signer`
+    /// and forward each landed signature to `landed` (confirmation without
+    /// RPC polling). Dropped when the channel is full.
+    pub fn with_transaction_status(
+        mut self,
+        signer: Pubkey,
+        landed: mpsc::Sender<LandedSignature>,
+    ) -> Self {
+        self.tx_status = Some((signer, landed));
+        self
+    }
+
     pub async fn start_tracking(&mut self) -> Result<(), ScramjetError> {
-        info!("Geyser: Subscribing to Slot Updates.");
+        let SlotFilter {
+            commitment,
+            interslot_updates,
+            entries,
+        } = self.slot_filter;
+        info!(
+            "Geyser: Subscribing to Slot Updates ({:?}{}).",
+            commitment,
+            if interslot_updates { ", interslot" } else { "" }
+        );
 
-        // Subscribe to slot updates only (minimal data)
+        let mut entry = std::collections::HashMap::new();
+        if entries {
+            info!("Geyser: Subscribing to Entries (production start).");
+            entry.insert("client".to_string(), SubscribeRequestFilterEntry {});
+        }
+
+        // Subscribe to slot updates only (minimal data). Under `processed`
+        // every status comes through; a stricter commitment is filtered on
+        // the server, and then applies to the whole subscription
+        let filtered = commitment != SlotCommitment::Processed;
         let mut slots = std::collections::HashMap::new();
         slots.insert(
             "client".to_string(),
             SubscribeRequestFilterSlots {
-                filter_by_commitment: None,
-                interslot_updates: None,
+                filter_by_commitment: Some(filtered),
+                interslot_updates: Some(interslot_updates),
             },
         );
 
+        let mut blocks_meta = std::collections::HashMap::new();
+        if self.blocks_meta {
+            info!("Geyser: Subscribing to Block Metadata (leader validation).");
+            blocks_meta.insert("client".to_string(), SubscribeRequestFilterBlocksMeta {});
+        }
+
+        let mut transactions_status = std::collections::HashMap::new();
+        if let Some((signer, _)) = self.tx_status {
+            info!("Geyser: Subscribing to Transaction Status for {}.", signer);
+            transactions_status.insert(
+                "client".to_string(),
+                SubscribeRequestFilterTransactions {
+                    vote: Some(false),
+                    failed: None,
+                    signature: None,
+                    account_include: vec![],
+                    account_exclude: vec![],
+                    account_required: vec![signer.to_string()],
+                },
+            );
+        }
+
         let request = SubscribeRequest {
             slots,
             accounts: std::collections::HashMap::new(),
             transactions: std::collections::HashMap::new(),
-            transactions_status: std::collections::HashMap::new(),
+            transactions_status,
             blocks: std::collections::HashMap::new(),
-            blocks_meta: std::collections::HashMap::new(),
-            entry: std::collections::HashMap::new(),
-            commitment: None,
+            blocks_meta,
+            entry,
+            commitment: filtered.then(|| commitment_level(commitment) as i32),
             accounts_data_slice: vec![],
             ping: None,
             from_slot: None,
         };
 
+        // The request channel stays open for keepalive pings
         let (tx, rx) = mpsc::channel(32);
         tx.send(request)
             .await
@@ -125,14 +418,106 @@ impl GeyserListener {
 
         info!("Geyser: Stream Active.");
 
+        let Keepalive {
+            ping_interval,
+            stall_timeout,
+            ref health,
+        } = self.keepalive;
+        let mut pings = ping_interval.map(|every| {
+            let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            interval
+        });
+        let mut ping_id = 0;
+        let mut last_slot = tokio::time::Instant::now();
+
         // Process slot updates as they arrive (real-time)
-        while let Some(message) = stream.message().await? {
-            if let Some(UpdateOneof::Slot(slot_update)) = message.update_oneof {
-                if slot_update.status == 0 {
-                    // Processed slot
-                    let slot = slot_update.slot;
-                    self.cartographer.update_slot(slot);
+        loop {
+            let ping_due = async {
+                match pings {
+                    Some(ref mut interval) => interval.tick().await,
+                    None => std::future::pending().await,
+                }
+            };
+            let stalled = async {
+                match stall_timeout {
+                    Some(timeout) => tokio::time::sleep_until(last_slot + timeout).await,
+                    None => std::future::pending().await,
+                }
+            };
+            let message = tokio::select! {
+                message = stream.message() => message?,
+                _ = ping_due => {
+                    ping_id += 1;
+                    tx.send(ping_request(ping_id)).await.map_err(|e| {
+                        ScramjetError::ChannelError(format!("Failed to send ping: {}", e))
+                    })?;
+                    continue;
+                }
+                _ = stalled => {
+                    health.record_stall();
+                    return Err(ScramjetError::GeyserError(format!(
+                        "No slot update for {:?}, stream stalled",
+                        last_slot.elapsed()
+                    )));
+                }
+            };
+            let Some(message) = message else {
+                break;
+            };
+            match message.update_oneof {
+                Some(UpdateOneof::Slot(slot_update)) => {
+                    last_slot = tokio::time::Instant::now();
+                    health.slot_seen();
+                    if slot_update.status == SlotStatus::SlotDead as i32 {
+                        warn!(
+                            "Geyser: Slot {} is dead: {}",
+                            slot_update.slot,
+                            slot_update
+                                .dead_error
+                                .as_deref()
+                                .unwrap_or("no reason given")
+                        );
+                    } else if let Some(slot) = self.slot_filter.production_slot(&slot_update) {
+                        self.cartographer.observe_production(slot).await;
+                    } else if let Some(slot) = self.slot_filter.clock_slot(&slot_update) {
+                        self.cartographer.update_slot(slot);
+                    }
                 }
+                Some(UpdateOneof::Entry(entry)) => {
+                    // A stricter commitment keeps the clock off unconfirmed slots
+                    let started = commitment == SlotCommitment::Processed
+                        && self.cartographer.observe_production(entry.slot).await;
+                    if started {
+                        debug!(
+                            "Geyser: Slot {} producing (entry {})",
+                            entry.slot, entry.index
+                        );
+                    }
+                }
+                Some(UpdateOneof::Pong(pong)) => debug!("Geyser: Pong {}", pong.id),
+                Some(UpdateOneof::BlockMeta(meta)) => match block_leader(&meta) {
+                    Some(leader) => {
+                        self.cartographer
+                            .observe_block_leader(meta.slot, leader)
+                            .await;
+                    }
+                    None => debug!("Geyser: No fee reward in block {}", meta.slot),
+                },
+                Some(UpdateOneof::TransactionStatus(status)) => {
+                    let Some((_, ref landed)) = self.tx_status else {
+                        continue;
+                    };
+                    match landed_signature(&status) {
+                        Some(update) => {
+                            if landed.try_send(update).is_err() {
+                                warn!("Geyser: Landing channel full, dropped {}", update.signature);
+                            }
+                        }
+                        None => debug!("Geyser: Malformed signature in slot {}", status.slot),
+                    }
+                }
+                _ => {}
             }
         }
 
@@ -140,24 +525,78 @@ impl GeyserListener {
     }
 }
 
-/// Spawn Geyser monitor with exponential backoff reconnection.
-/// Returns a oneshot receiver that signals when the first connection attempt completes.
+fn commitment_level(commitment: SlotCommitment) -> CommitmentLevel {
+    match commitment {
+        SlotCommitment::Processed => CommitmentLevel::Processed,
+        SlotCommitment::Confirmed => CommitmentLevel::Confirmed,
+        SlotCommitment::Finalized => CommitmentLevel::Finalized,
+    }
+}
+
+/// Keepalive request: the subscription filters are left as they are
+fn ping_request(id: i32) -> SubscribeRequest {
+    SubscribeRequest {
+        ping: Some(SubscribeRequestPing { id }),
+        ..Default::default()
+    }
+}
+
+/// Producer of a block: the recipient of its fee reward
+pub fn block_leader(meta: &SubscribeUpdateBlockMeta) -> Option<Pubkey> {
+    meta.rewards
+        .as_ref()?
+        .rewards
+        .iter()
+        .find(|reward| reward.reward_type == RewardType::Fee as i32)
+        .and_then(|reward| Pubkey::from_str(&reward.pubkey).ok())
+}
+
+/// Landed signature from a transaction status update
+pub fn landed_signature(status: &SubscribeUpdateTransactionStatus) -> Option<LandedSignature> {
+    let signature = Signature::try_from(status.signature.as_slice()).ok()?;
+    Some(LandedSignature {
+        signature,
+        slot: status.slot,
+        failed: status.err.is_some(),
+    })
+}
+
+/// Spawn Geyser monitor with exponential backoff reconnection (also after
+/// the `keepalive` watchdog gives up on a stalled stream).
+/// Returns the monitor task and a oneshot receiver that signals when the first
+/// connection attempt completes. The task exits when `shutdown` is cancelled.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_geyser_monitor(
     endpoint: String,
     cartographer: Arc<Cartographer>,
+    blocks_meta: bool,
+    tx_status: Option<(Pubkey, mpsc::Sender<LandedSignature>)>,
+    slot_filter: SlotFilter,
+    keepalive: Keepalive,
     initial_delay: Duration,
     max_delay: Duration,
-) -> oneshot::Receiver<Result<(), ScramjetError>> {
+    shutdown: CancellationToken,
+) -> (
+    tokio::task::JoinHandle<()>,
+    oneshot::Receiver<Result<(), ScramjetError>>,
+) {
     let (startup_tx, startup_rx) = oneshot::channel();
 
-    tokio::spawn(async move {
+    let task = tokio::spawn(async move {
         let mut retry_delay = initial_delay;
         let mut startup_tx = Some(startup_tx);
 
         // Reconnect loop with exponential backoff
         loop {
             match GeyserListener::connect(endpoint.clone(), cartographer.clone()).await {
-                Ok(mut listener) => {
+                Ok(listener) => {
+                    let mut listener = listener
+                        .with_blocks_meta(blocks_meta)
+                        .with_slot_filter(slot_filter)
+                        .with_keepalive(keepalive.clone());
+                    if let Some((signer, ref landed)) = tx_status {
+                        listener = listener.with_transaction_status(signer, landed.clone());
+                    }
                     // Reset backoff on successful connection
                     retry_delay = initial_delay;
 
@@ -166,11 +605,16 @@ pub fn spawn_geyser_monitor(
                         let _ = tx.send(Ok(()));
                     }
 
-                    if let Err(e) = listener.start_tracking().await {
-                        error!(
-                            "Geyser Stream Error: {}. Reconnecting in {:?}...",
-                            e, retry_delay
-                        );
+                    tokio::select! {
+                        _ = shutdown.cancelled() => break,
+                        result = listener.start_tracking() => {
+                            if let Err(e) = result {
+                                error!(
+                                    "Geyser Stream Error: {}. Reconnecting in {:?}...",
+                                    e, retry_delay
+                                );
+                            }
+                        }
                     }
                 }
                 Err(e) => {
@@ -185,12 +629,154 @@ pub fn spawn_geyser_monitor(
                 }
             }
 
-            tokio::time::sleep(retry_delay).await;
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = tokio::time::sleep(retry_delay) => {}
+            }
 
             // Exponential backoff: double delay, capped at max
-            retry_delay = std::cmp::min(retry_delay * 2, max_delay);
+            retry_delay = std::cmp::min(retry_delay.saturating_mul(2), max_delay);
         }
+        debug!("Geyser monitor stopped");
     });
 
-    startup_rx
+    (task, startup_rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_geyser_endpoint_token() {
+        let parsed = parse_geyser_endpoint("https://geyser.example.com/abcdef0123456789").unwrap();
+        assert_eq!(parsed.url, "https://geyser.example.com");
+        assert_eq!(parsed.x_token.as_deref(), Some("abcdef0123456789"));
+
+        let plain = parse_geyser_endpoint("http://127.0.0.1:10000").unwrap();
+        assert_eq!(plain.url, "http://127.0.0.1:10000");
+        assert!(plain.x_token.is_none());
+    }
+
+    #[test]
+    fn test_parse_geyser_endpoint_malformed() {
+        for input in [
+            "",
+            "::::",
+            "http://",
+            "/only/a/long/path/here",
+            "http://[::1",
+        ] {
+            // Typed error or a usable endpoint, never a panic
+            let _ = parse_geyser_endpoint(input);
+        }
+        assert!(matches!(
+            parse_geyser_endpoint("not a url at all"),
+            Err(ScramjetError::InvalidUri(_))
+        ));
+        assert!(parse_geyser_endpoint("/relative-path-with-token").is_err());
+    }
+
+    #[test]
+    fn test_geyser_health() {
+        let health = GeyserHealth::default();
+        assert_eq!(health.since_last_slot(), None);
+
+        health.slot_seen();
+        health.record_stall();
+        assert!(health.since_last_slot().unwrap() < Duration::from_secs(1));
+        assert_eq!(health.stalls(), 1);
+
+        let ping = ping_request(7);
+        assert_eq!(ping.ping.unwrap().id, 7);
+        assert!(ping.slots.is_empty());
+    }
+
+    #[test]
+    fn test_slot_filter_clock_signals() {
+        let update = |status: SlotStatus| SubscribeUpdateSlot {
+            slot: 9,
+            status: status as i32,
+            ..Default::default()
+        };
+        let processed = SlotFilter::default();
+        assert_eq!(
+            processed.clock_slot(&update(SlotStatus::SlotProcessed)),
+            Some(9)
+        );
+        assert_eq!(
+            processed.clock_slot(&update(SlotStatus::SlotFirstShredReceived)),
+            Some(9)
+        );
+        assert_eq!(processed.clock_slot(&update(SlotStatus::SlotDead)), None);
+        assert_eq!(
+            processed.clock_slot(&update(SlotStatus::SlotConfirmed)),
+            None
+        );
+
+        let confirmed = SlotFilter {
+            commitment: "Confirmed".parse().unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(
+            confirmed.clock_slot(&update(SlotStatus::SlotProcessed)),
+            None
+        );
+        assert_eq!(
+            confirmed.clock_slot(&update(SlotStatus::SlotConfirmed)),
+            Some(9)
+        );
+        assert!("rooted".parse::<SlotCommitment>().is_err());
+    }
+
+    #[test]
+    fn test_block_leader_from_fee_reward() {
+        use yellowstone_grpc_proto::prelude::{Reward, Rewards};
+
+        let leader = Pubkey::new_unique();
+        let reward = |pubkey: Pubkey, reward_type: RewardType| Reward {
+            pubkey: pubkey.to_string(),
+            lamports: 5_000,
+            post_balance: 0,
+            reward_type: reward_type as i32,
+            commission: String::new(),
+        };
+        let mut meta = SubscribeUpdateBlockMeta {
+            slot: 42,
+            ..Default::default()
+        };
+        assert!(block_leader(&meta).is_none());
+
+        meta.rewards = Some(Rewards {
+            rewards: vec![
+                reward(Pubkey::new_unique(), RewardType::Voting),
+                reward(leader, RewardType::Fee),
+            ],
+            num_partitions: None,
+        });
+        assert_eq!(block_leader(&meta), Some(leader));
+    }
+
+    #[test]
+    fn test_landed_signature_from_status() {
+        let signature = Signature::new_unique();
+        let mut status = SubscribeUpdateTransactionStatus {
+            slot: 77,
+            signature: signature.as_ref().to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(
+            landed_signature(&status),
+            Some(LandedSignature {
+                signature,
+                slot: 77,
+                failed: false,
+            })
+        );
+
+        status.err = Some(Default::default());
+        assert!(landed_signature(&status).unwrap().failed);
+        status.signature.truncate(10);
+        assert_eq!(landed_signature(&status), None);
+    }
 }
@@ -0,0 +1,138 @@
+//! Value histograms for benchmarking (exact percentiles + log2 buckets).
+//!
+//! Samples are kept verbatim: bench runs record at most a few hundred
+//! thousand values, and exact percentiles beat bucket interpolation there.
+
+use serde::Serialize;
+use std::time::Duration;
+
+/// Summary of a histogram (in the unit the samples were recorded in)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Percentiles {
+    pub count: usize,
+    pub min: u64,
+    pub p50: u64,
+    pub p95: u64,
+    pub p99: u64,
+    pub max: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    samples: Vec<u64>,
+    sorted: bool,
+}
+
+impl Histogram {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, value: u64) {
+        self.samples.push(value);
+        self.sorted = false;
+    }
+
+    /// Record a latency in microseconds
+    pub fn record_duration(&mut self, elapsed: Duration) {
+        self.record(elapsed.as_micros().try_into().unwrap_or(u64::MAX));
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    fn sort(&mut self) {
+        if !self.sorted {
+            self.samples.sort_unstable();
+            self.sorted = true;
+        }
+    }
+
+    /// Nearest-rank percentile, `pct` in [0, 100] (None when empty)
+    pub fn percentile(&mut self, pct: f64) -> Option<u64> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        self.sort();
+        let rank = (pct / 100.0 * self.samples.len() as f64).ceil() as usize;
+        Some(self.samples[rank.clamp(1, self.samples.len()) - 1])
+    }
+
+    pub fn percentiles(&mut self) -> Option<Percentiles> {
+        Some(Percentiles {
+            count: self.samples.len(),
+            min: self.percentile(0.0)?,
+            p50: self.percentile(50.0)?,
+            p95: self.percentile(95.0)?,
+            p99: self.percentile(99.0)?,
+            max: self.percentile(100.0)?,
+        })
+    }
+
+    /// Power-of-two buckets `(lower, upper, count)`, covering every sample;
+    /// empty buckets between the first and last populated one are included
+    pub fn buckets(&self) -> Vec<(u64, u64, usize)> {
+        let bucket = |value: u64| (u64::BITS - value.leading_zeros()) as usize;
+        let mut counts = [0usize; u64::BITS as usize + 1];
+        for &value in &self.samples {
+            counts[bucket(value)] += 1;
+        }
+        let Some(first) = counts.iter().position(|&c| c > 0) else {
+            return Vec::new();
+        };
+        let last = counts.iter().rposition(|&c| c > 0).unwrap_or(first);
+        (first..=last)
+            .map(|b| {
+                let lower = if b == 0 { 0 } else { 1u64 << (b - 1) };
+                let upper = if b == 0 {
+                    0
+                } else {
+                    (1u64 << (b - 1)).saturating_mul(2) - 1
+                };
+                (lower, upper, counts[b])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentiles_nearest_rank() {
+        let mut h = Histogram::new();
+        assert!(h.percentiles().is_none());
+        for v in 1..=100 {
+            h.record(v);
+        }
+        let p = h.percentiles().unwrap();
+        assert_eq!(
+            (p.count, p.min, p.p50, p.p95, p.p99, p.max),
+            (100, 1, 50, 95, 99, 100)
+        );
+
+        h.record_duration(Duration::from_millis(2));
+        assert_eq!(h.percentile(100.0), Some(2_000));
+    }
+
+    #[test]
+    fn test_log2_buckets() {
+        let mut h = Histogram::new();
+        for v in [0, 3, 5, 6, 40] {
+            h.record(v);
+        }
+        let buckets = h.buckets();
+        assert_eq!(buckets.first(), Some(&(0, 0, 1)));
+        assert!(buckets.contains(&(2, 3, 1)));
+        assert!(buckets.contains(&(4, 7, 2)));
+        assert!(buckets.contains(&(8, 15, 0)));
+        assert_eq!(buckets.last(), Some(&(32, 63, 1)));
+        assert_eq!(buckets.iter().map(|b| b.2).sum::<usize>(), 5);
+    }
+}
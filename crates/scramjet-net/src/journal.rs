@@ -0,0 +1,472 @@
+//! Send journal: an append-only JSON-lines record of every transaction
+//! written to a leader (or that failed to be), plus the landing outcomes
+//! confirmation later saw, so whether a transaction was actually transmitted
+//! can be answered from disk after the fact (`scramjet history`).
+//!
+//! Entries are handed to a writer thread, so recording never blocks a send on
+//! disk I/O. Each is a single `write` to a file opened for appending: entries
+//! never interleave, and a crash loses at most the entries not yet written (a
+//! torn last line is skipped when reading). Once the file grows past its size
+//! cap (`JOURNAL_MAX_MB`) it is rotated to `<file>.1`, replacing the previous
+//! one.
+//!
+//! The first send entry of each signature also carries the wire transaction,
+//! so `scramjet replay` can resubmit what never landed after a crash.
+
+use crate::topology_cache::unix_time_ms;
+use base64::Engine;
+use log::warn;
+use scramjet_common::ScramjetError;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+use std::collections::{HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Signatures remembered as already journaled with their transaction, so a
+/// transaction resent many times is stored once
+const STORED_MEMORY: usize = 4096;
+
+/// What happened to a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalResult {
+    /// Written to the leader
+    Sent,
+    /// Not written: connection, stream or deadline failure
+    SendFailed,
+    /// Included in a block without error
+    Landed,
+    /// Included in a block with a transaction error
+    Failed,
+    /// Blockhash expired without the signature showing up
+    Expired,
+    /// Still unseen when confirmation gave up
+    Unconfirmed,
+    /// Never landed; re-signed and resent as `replaced_by` (`scramjet replay`)
+    Replaced,
+}
+
+impl JournalResult {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalResult::Sent => "sent",
+            JournalResult::SendFailed => "send_failed",
+            JournalResult::Landed => "landed",
+            JournalResult::Failed => "failed",
+            JournalResult::Expired => "expired",
+            JournalResult::Unconfirmed => "unconfirmed",
+            JournalResult::Replaced => "replaced",
+        }
+    }
+}
+
+/// One journal line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Unix time (ms) the entry was written
+    pub at_ms: u64,
+    pub signature: String,
+    /// Slot the leader was resolved for (sends), or landed in (outcomes)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u64>,
+    /// Leader TPU address written to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<SocketAddr>,
+    /// What sent it: a CLI command, or `send` for the library
+    pub source: String,
+    pub result: JournalResult,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Wire transaction (base64), on the signature's first send entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx: Option<String>,
+    /// Signature of the re-signed transaction (`Replaced` entries)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
+}
+
+/// Request to the writer thread
+enum Queued {
+    Line(Vec<u8>),
+    /// Acknowledged once every line queued before it is written
+    Flush(mpsc::Sender<()>),
+}
+
+/// Append-only journal file (`JOURNAL_FILE`)
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    /// Queue to the writer thread (None once dropped)
+    writes: Mutex<Option<mpsc::Sender<Queued>>>,
+    writer: Mutex<Option<JoinHandle<()>>>,
+    /// Signatures whose transaction is already in the journal
+    stored: Mutex<StoredSignatures>,
+    /// Write failures are logged once, not per send
+    warned: Arc<AtomicBool>,
+}
+
+impl Journal {
+    /// Open `path` for appending (created with its directory if missing),
+    /// first rotating it when it holds more than `max_bytes`
+    pub fn open(path: impl Into<PathBuf>, max_bytes: u64) -> Result<Self, ScramjetError> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = JournalFile::open(path.clone(), max_bytes)?;
+        file.rotate_if_full()?;
+
+        let warned = Arc::new(AtomicBool::new(false));
+        let (writes, queue) = mpsc::channel();
+        let writer = {
+            let warned = warned.clone();
+            std::thread::Builder::new()
+                .name("scramjet-journal".into())
+                .spawn(move || file.run(queue, &warned))?
+        };
+        Ok(Self {
+            path,
+            writes: Mutex::new(Some(writes)),
+            writer: Mutex::new(Some(writer)),
+            stored: Mutex::new(StoredSignatures::default()),
+            warned,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Queue `entry` for appending. A failed write is logged, never
+    /// returned: the journal must not fail the send it describes.
+    pub fn record(&self, entry: &JournalEntry) {
+        let mut line = match serde_json::to_vec(entry) {
+            Ok(line) => line,
+            Err(e) => return warn_once(&self.warned, &self.path, &e.to_string()),
+        };
+        line.push(b'\n');
+        self.send(Queued::Line(line));
+    }
+
+    /// Wait until every entry recorded so far is written (blocking)
+    pub fn flush(&self) {
+        let (done, written) = mpsc::channel();
+        self.send(Queued::Flush(done));
+        let _ = written.recv();
+    }
+
+    fn send(&self, queued: Queued) {
+        let writes = self
+            .writes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(writes) = writes.as_ref() {
+            // The writer only exits once the queue is closed
+            let _ = writes.send(queued);
+        }
+    }
+
+    /// A send attempt of wire transaction `tx` to `target`: written, or the
+    /// error it hit
+    pub fn record_send(
+        &self,
+        source: &str,
+        signature: &Signature,
+        tx: &[u8],
+        slot: u64,
+        target: SocketAddr,
+        error: Option<&ScramjetError>,
+    ) {
+        let first = self
+            .stored
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(*signature);
+        self.record(&JournalEntry {
+            at_ms: unix_time_ms(),
+            signature: signature.to_string(),
+            slot: Some(slot),
+            target: Some(target),
+            source: source.to_string(),
+            result: match error {
+                None => JournalResult::Sent,
+                Some(_) => JournalResult::SendFailed,
+            },
+            error: error.map(ToString::to_string),
+            tx: first.then(|| base64::engine::general_purpose::STANDARD.encode(tx)),
+            replaced_by: None,
+        });
+    }
+
+    /// What confirmation saw of a sent signature (`slot`: where it landed)
+    pub fn record_outcome(
+        &self,
+        source: &str,
+        signature: &Signature,
+        slot: Option<u64>,
+        target: SocketAddr,
+        result: JournalResult,
+        error: Option<String>,
+    ) {
+        self.record(&JournalEntry {
+            at_ms: unix_time_ms(),
+            signature: signature.to_string(),
+            slot,
+            target: Some(target),
+            source: source.to_string(),
+            result,
+            error,
+            tx: None,
+            replaced_by: None,
+        });
+    }
+
+    /// `original` never landed and is resent re-signed as `replacement`.
+    /// Recorded before the replacement is sent, so a later replay never
+    /// re-signs `original` a second time.
+    pub fn record_replaced(&self, source: &str, original: &Signature, replacement: &Signature) {
+        self.record(&JournalEntry {
+            at_ms: unix_time_ms(),
+            signature: original.to_string(),
+            slot: None,
+            target: None,
+            source: source.to_string(),
+            result: JournalResult::Replaced,
+            error: None,
+            tx: None,
+            replaced_by: Some(replacement.to_string()),
+        });
+    }
+}
+
+impl Drop for Journal {
+    /// Write out whatever is still queued
+    fn drop(&mut self) {
+        let writes = self.writes.get_mut().unwrap_or_else(|e| e.into_inner());
+        drop(writes.take());
+        let writer = self.writer.get_mut().unwrap_or_else(|e| e.into_inner());
+        if let Some(writer) = writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+/// The journal file as its writer thread sees it
+struct JournalFile {
+    path: PathBuf,
+    file: File,
+    /// Bytes in `file`
+    len: u64,
+    max_bytes: u64,
+}
+
+impl JournalFile {
+    fn open(path: PathBuf, max_bytes: u64) -> std::io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        // End a line torn by a crash, so the next entry starts clean
+        if ends_torn(&mut file)? {
+            file.write_all(b"\n")?;
+        }
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            len,
+            max_bytes,
+        })
+    }
+
+    /// Move the file to `<file>.1` and start a new one, once past the cap
+    /// (checked before every append)
+    fn rotate_if_full(&mut self) -> std::io::Result<()> {
+        if self.len <= self.max_bytes {
+            return Ok(());
+        }
+        std::fs::rename(&self.path, rotated_path(&self.path))?;
+        *self = Self::open(self.path.clone(), self.max_bytes)?;
+        Ok(())
+    }
+
+    fn append(&mut self, line: &[u8]) -> std::io::Result<()> {
+        self.rotate_if_full()?;
+        self.file.write_all(line)?;
+        self.len += line.len() as u64;
+        Ok(())
+    }
+
+    /// Writer thread: append queued lines until the journal is dropped
+    fn run(mut self, queue: mpsc::Receiver<Queued>, warned: &AtomicBool) {
+        for queued in queue {
+            match queued {
+                Queued::Line(line) => {
+                    if let Err(e) = self.append(&line) {
+                        warn_once(warned, &self.path, &e.to_string());
+                    }
+                }
+                Queued::Flush(done) => {
+                    let _ = done.send(());
+                }
+            }
+        }
+    }
+}
+
+fn warn_once(warned: &AtomicBool, path: &Path, error: &str) {
+    if !warned.swap(true, Ordering::Relaxed) {
+        warn!(
+            "Journal: Write to {} failed: {}. Sends continue unrecorded.",
+            path.display(),
+            error
+        );
+    }
+}
+
+/// Recently journaled signatures, oldest evicted first
+#[derive(Debug, Default)]
+struct StoredSignatures {
+    set: HashSet<Signature>,
+    order: VecDeque<Signature>,
+}
+
+impl StoredSignatures {
+    /// Remember `signature`; false when it already was
+    fn insert(&mut self, signature: Signature) -> bool {
+        if !self.set.insert(signature) {
+            return false;
+        }
+        self.order.push_back(signature);
+        if self.order.len() > STORED_MEMORY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+/// Whether `file` is non-empty and does not end in a newline
+fn ends_torn(file: &mut File) -> std::io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(false);
+    }
+    let mut last = [0u8];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] != b'\n')
+}
+
+/// Where `path` is rotated to
+pub fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+/// Every entry of the journal at `path`, oldest first (its rotated file
+/// included). Lines that do not parse, such as one torn by a crash, are
+/// skipped.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>, ScramjetError> {
+    let mut entries = Vec::new();
+    for file in [rotated_path(path), path.to_path_buf()] {
+        let reader = match File::open(&file) {
+            Ok(reader) => BufReader::new(reader),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for line in reader.lines() {
+            if let Ok(entry) = serde_json::from_str(&line?) {
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_appends_and_rotates() {
+        let dir = std::env::temp_dir().join(format!("scramjet-journal-{}", std::process::id()));
+        let path = dir.join("journal.jsonl");
+        let signature = Signature::from([7u8; 64]);
+        let target: SocketAddr = "10.0.0.1:8009".parse().unwrap();
+
+        let journal = Journal::open(&path, u64::MAX).unwrap();
+        journal.record_send("spam", &signature, b"wire", 100, target, None);
+        let error = ScramjetError::NoLeaderFound(101);
+        journal.record_send("spam", &signature, b"wire", 101, target, Some(&error));
+        journal.record_outcome(
+            "spam",
+            &signature,
+            Some(102),
+            target,
+            JournalResult::Landed,
+            None,
+        );
+        drop(journal);
+        // A crash mid-write leaves a torn line behind
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"at_ms\":1,\"sig")
+            .unwrap();
+        let journal = Journal::open(&path, u64::MAX).unwrap();
+        journal.record_send("fire", &signature, b"wire", 103, target, None);
+        journal.flush();
+
+        let entries = read_journal(&path).unwrap();
+        let results: Vec<JournalResult> = entries.iter().map(|entry| entry.result).collect();
+        assert_eq!(
+            results,
+            vec![
+                JournalResult::Sent,
+                JournalResult::SendFailed,
+                JournalResult::Landed,
+                JournalResult::Sent
+            ]
+        );
+        assert_eq!(entries[0].signature, signature.to_string());
+        assert_eq!(entries[0].target, Some(target));
+        assert_eq!(
+            entries[1].error.as_deref(),
+            Some("No leader found for slot 101")
+        );
+        assert_eq!(entries[2].slot, Some(102));
+        // The transaction is stored once per signature (per open journal)
+        assert_eq!(entries[0].tx.as_deref(), Some("d2lyZQ=="));
+        assert_eq!(entries[1].tx, None);
+        assert_eq!(entries[3].tx.as_deref(), Some("d2lyZQ=="));
+
+        // Over the cap: the old entries move to journal.jsonl.1, still read
+        drop(journal);
+        let journal = Journal::open(&path, 16).unwrap();
+        assert!(rotated_path(&path).exists());
+        journal.record_send("fire", &signature, b"wire", 200, target, None);
+        journal.flush();
+        let entries = read_journal(&path).unwrap();
+        assert_eq!(entries.len(), 5);
+        assert_eq!(entries[4].slot, Some(200));
+
+        // A running journal rotates too, once past the cap (replacing the
+        // previous journal.jsonl.1)
+        journal.record_send("fire", &signature, b"wire", 201, target, None);
+        journal.flush();
+        let entries = read_journal(&path).unwrap();
+        let slots: Vec<_> = entries.iter().map(|entry| entry.slot).collect();
+        assert_eq!(slots, vec![Some(200), Some(201)]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
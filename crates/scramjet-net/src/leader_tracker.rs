@@ -0,0 +1,183 @@
+//! Warm connection handoff at leader rotation.
+//!
+//! The [`LeaderTracker`] follows the slot clock and keeps the engine's
+//! *active connection* on the current leader: the leader's connection pool,
+//! resolved (Shield and policy applied) once per window instead of on every
+//! send. In the last slot of a window it stages the next leader's pool
+//! (normally pre-warmed by the Scout), and when the window turns over it
+//! promotes that pool and demotes the previous one. Sends to the active
+//! target then take their connection straight from it, with no topology or
+//! connection-cache lookup.
+
+use crate::cartographer::Cartographer;
+use crate::engine::QuicEngine;
+use crate::schedule::LeaderWindow;
+use log::debug;
+use quinn::Connection;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// The current leader's connections, promoted for its window
+#[derive(Debug)]
+pub struct ActiveConnection {
+    pub target: SocketAddr,
+    pub window: LeaderWindow,
+    pool: Vec<Connection>,
+    next: AtomicUsize,
+}
+
+impl ActiveConnection {
+    pub fn new(target: SocketAddr, window: LeaderWindow, pool: Vec<Connection>) -> Self {
+        Self {
+            target,
+            window,
+            pool,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// `slot` falls in this leader's window
+    pub fn covers(&self, slot: u64) -> bool {
+        self.window.contains(slot)
+    }
+
+    /// Next open connection of the pool (round-robin); None once the
+    /// validator has closed them all
+    pub fn connection(&self) -> Option<Connection> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..self.pool.len())
+            .map(|offset| &self.pool[(start + offset) % self.pool.len()])
+            .find(|connection| connection.close_reason().is_none())
+            .cloned()
+    }
+
+    fn is_open(&self) -> bool {
+        self.pool
+            .iter()
+            .any(|connection| connection.close_reason().is_none())
+    }
+}
+
+/// Promotes each leader's pool to the engine's active connection as its
+/// window starts
+pub struct LeaderTracker {
+    cartographer: Arc<Cartographer>,
+    engine: Arc<QuicEngine>,
+    /// The next window's pool, staged in the current window's last slot
+    staged: Mutex<Option<Arc<ActiveConnection>>>,
+    /// Leaders promoted so far
+    handoffs: AtomicU64,
+}
+
+impl LeaderTracker {
+    pub fn new(cartographer: Arc<Cartographer>, engine: Arc<QuicEngine>) -> Self {
+        Self {
+            cartographer,
+            engine,
+            staged: Mutex::new(None),
+            handoffs: AtomicU64::new(0),
+        }
+    }
+
+    /// Follow the cartographer's slot clock until `stop` is cancelled
+    pub fn spawn(self: Arc<Self>, stop: CancellationToken) -> JoinHandle<()> {
+        let mut slots = self.cartographer.subscribe_slots();
+        tokio::spawn(async move {
+            loop {
+                let slot = *slots.borrow_and_update();
+                if slot > 0 {
+                    self.on_slot(slot).await;
+                }
+                tokio::select! {
+                    _ = stop.cancelled() => break,
+                    changed = slots.changed() => if changed.is_err() { break },
+                }
+            }
+            self.engine.set_active_connection(None);
+        })
+    }
+
+    /// Bring the active connection in line with `slot`: keep it while its
+    /// window lasts and its leader is still allowed, otherwise promote the
+    /// slot's leader (demoting the previous one, or leaving none when the
+    /// leader is unknown, blocked or unreachable)
+    pub async fn on_slot(&self, slot: u64) {
+        let Some(window) = self.cartographer.get_leader_window(slot).await else {
+            self.engine.set_active_connection(None);
+            return;
+        };
+        // The target (and its policy check) is resolved once per leader; the
+        // Shield is re-checked every slot, so a block applies within one
+        let current = self.engine.active_connection();
+        let keep = current.as_ref().is_some_and(|active| {
+            active.window == window
+                && active.is_open()
+                && self.cartographer.shield_allows(&window.leader)
+        });
+        if !keep {
+            let target = self.cartographer.get_target(slot).await;
+            let staged = self.staged.lock().unwrap().take().filter(|staged| {
+                staged.window == window && Some(staged.target) == target && staged.is_open()
+            });
+            let promoted = match (staged, target) {
+                (Some(staged), _) => Some(staged),
+                (None, Some(target)) => self.prepare(target, window).await,
+                (None, None) => None,
+            };
+            match promoted {
+                Some(ref active) => {
+                    self.handoffs.fetch_add(1, Ordering::Relaxed);
+                    debug!(
+                        "Leader handoff: {} ({}) active for slots {}-{}{}",
+                        window.leader,
+                        active.target,
+                        window.start_slot,
+                        window.end_slot,
+                        current
+                            .as_ref()
+                            .map(|previous| format!(", {} demoted", previous.target))
+                            .unwrap_or_default()
+                    );
+                }
+                None => debug!("Leader handoff: No usable target for slot {}", slot),
+            }
+            self.engine.set_active_connection(promoted);
+        }
+
+        // Last slot of the window: get the next leader's pool ready
+        if slot == window.end_slot {
+            let next_slot = window.end_slot + 1;
+            let next = match self.cartographer.get_leader_window(next_slot).await {
+                Some(next_window) => match self.cartographer.get_target(next_slot).await {
+                    Some(next_target) => self.prepare(next_target, next_window).await,
+                    None => None,
+                },
+                None => None,
+            };
+            *self.staged.lock().unwrap() = next;
+        }
+    }
+
+    /// Leaders promoted so far
+    pub fn handoffs(&self) -> u64 {
+        self.handoffs.load(Ordering::Relaxed)
+    }
+
+    /// `target`'s pool (cached or handshaken now) as an active connection
+    async fn prepare(
+        &self,
+        target: SocketAddr,
+        window: LeaderWindow,
+    ) -> Option<Arc<ActiveConnection>> {
+        match self.engine.get_connection_pool(target).await {
+            Ok(pool) => Some(Arc::new(ActiveConnection::new(target, window, pool))),
+            Err(e) => {
+                debug!("Leader handoff: No connection to {}: {}", target, e);
+                None
+            }
+        }
+    }
+}
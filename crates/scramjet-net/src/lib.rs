@@ -1,4 +1,35 @@
+pub mod blockhash;
 pub mod blocklist;
 pub mod cartographer;
+pub mod control;
+pub mod deadline;
+pub mod dedupe;
 pub mod engine;
+#[cfg(feature = "fuzzing")]
+pub mod fuzz;
 pub mod geyser;
+pub mod histogram;
+pub mod jito;
+pub mod journal;
+pub mod leader_tracker;
+pub mod policy;
+pub mod priority;
+pub mod proximity;
+pub mod queue;
+pub mod rate_limit;
+pub mod raw;
+pub mod relay;
+pub mod retry;
+pub mod rpc_pool;
+pub mod runtime;
+pub mod schedule;
+pub mod score;
+pub mod scout;
+pub mod server;
+pub mod slo;
+pub mod slot_timing;
+pub mod stats;
+pub mod topology_cache;
+pub mod uds;
+pub mod validator_info;
+pub mod websocket;
@@ -0,0 +1,270 @@
+//! External policy hook for targeting decisions.
+//!
+//! Before a leader is targeted (send) or pre-warmed (scout), the candidate is
+//! sent to an external policy engine over a Unix domain socket, one JSON line
+//! per request/response (same framing as the control socket):
+//!
+//! ```text
+//! -> {"purpose":"send","slot":123,"leader":"<pubkey>","addr":"1.2.3.4:8009"}
+//! <- {"verdict":"allow","priority":5}
+//! ```
+//!
+//! Decisions are cached per leader for a short TTL so the send path does not
+//! pay a round trip on every call. When the policy engine is unreachable or
+//! too slow, the configured failure mode (fail open / fail closed) applies.
+
+use dashmap::DashMap;
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+
+use scramjet_common::ScramjetError;
+
+/// Why a leader is being considered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyPurpose {
+    Send,
+    Scout,
+}
+
+/// Candidate leader sent to the policy engine
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyRequest {
+    pub purpose: PolicyPurpose,
+    pub slot: u64,
+    pub leader: String,
+    pub addr: SocketAddr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Allow,
+    Deny,
+}
+
+/// Policy engine answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    pub verdict: Verdict,
+    /// Higher is more important (used to order Scout pre-warming)
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl PolicyDecision {
+    pub const ALLOW: PolicyDecision = PolicyDecision {
+        verdict: Verdict::Allow,
+        priority: 0,
+    };
+    pub const DENY: PolicyDecision = PolicyDecision {
+        verdict: Verdict::Deny,
+        priority: 0,
+    };
+
+    pub fn is_allowed(&self) -> bool {
+        self.verdict == Verdict::Allow
+    }
+}
+
+/// Idle connections kept for reuse
+const MAX_IDLE_CONNECTIONS: usize = 8;
+
+type PolicyConnection = (Lines<BufReader<OwnedReadHalf>>, OwnedWriteHalf);
+
+/// Client for an external policy engine listening on a Unix socket
+pub struct PolicyClient {
+    path: PathBuf,
+    timeout: Duration,
+    cache_ttl: Duration,
+    fail_open: bool,
+    /// Connections between queries. Each query takes one (or connects), so
+    /// concurrent queries never wait on each other
+    idle: Mutex<Vec<PolicyConnection>>,
+    cache: DashMap<(Pubkey, PolicyPurpose), (Instant, PolicyDecision)>,
+}
+
+impl PolicyClient {
+    pub fn new(path: PathBuf, timeout: Duration, cache_ttl: Duration, fail_open: bool) -> Self {
+        Self {
+            path,
+            timeout,
+            cache_ttl,
+            fail_open,
+            idle: Mutex::new(Vec::new()),
+            cache: DashMap::new(),
+        }
+    }
+
+    /// Decision for `leader` (cached, never fails: errors map to the failure mode)
+    pub async fn evaluate(
+        &self,
+        purpose: PolicyPurpose,
+        slot: u64,
+        leader: Pubkey,
+        addr: SocketAddr,
+    ) -> PolicyDecision {
+        if let Some(entry) = self.cache.get(&(leader, purpose)) {
+            let (at, decision) = *entry;
+            if at.elapsed() < self.cache_ttl {
+                return decision;
+            }
+        }
+
+        let request = PolicyRequest {
+            purpose,
+            slot,
+            leader: leader.to_string(),
+            addr,
+        };
+        let decision = match tokio::time::timeout(self.timeout, self.query(&request)).await {
+            Ok(Ok(decision)) => decision,
+            Ok(Err(e)) => {
+                warn!("Policy: Query for {} failed: {}", leader, e);
+                self.failure_decision()
+            }
+            Err(_) => {
+                // The query's connection is dropped with it: a half-read
+                // response would desync the stream
+                warn!(
+                    "Policy: Query for {} timed out after {:?}",
+                    leader, self.timeout
+                );
+                self.failure_decision()
+            }
+        };
+        debug!("Policy: {:?} {} -> {:?}", purpose, leader, decision);
+        self.cache
+            .insert((leader, purpose), (Instant::now(), decision));
+        decision
+    }
+
+    fn failure_decision(&self) -> PolicyDecision {
+        if self.fail_open {
+            PolicyDecision::ALLOW
+        } else {
+            PolicyDecision::DENY
+        }
+    }
+
+    async fn query(&self, request: &PolicyRequest) -> Result<PolicyDecision, ScramjetError> {
+        let mut line = serde_json::to_string(request)
+            .map_err(|e| ScramjetError::SerializationError(e.to_string()))?;
+        line.push('\n');
+
+        let idle = self.idle.lock().unwrap().pop();
+        let (mut lines, mut writer) = match idle {
+            Some(connection) => connection,
+            None => {
+                let stream = UnixStream::connect(&self.path).await.map_err(|e| {
+                    ScramjetError::ConnectionError(format!("Policy socket {:?}: {}", self.path, e))
+                })?;
+                let (reader, writer) = stream.into_split();
+                (BufReader::new(reader).lines(), writer)
+            }
+        };
+
+        let result = async {
+            writer.write_all(line.as_bytes()).await?;
+            let response = lines.next_line().await?.ok_or_else(|| {
+                ScramjetError::ConnectionError("Policy socket closed without a response".into())
+            })?;
+            serde_json::from_str::<PolicyDecision>(&response)
+                .map_err(|e| ScramjetError::SerializationError(e.to_string()))
+        }
+        .await;
+
+        // A failed connection is dropped; the next query connects afresh
+        if result.is_ok() {
+            let mut idle = self.idle.lock().unwrap();
+            if idle.len() < MAX_IDLE_CONNECTIONS {
+                idle.push((lines, writer));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::UnixListener;
+
+    /// Policy engine that denies one leader and prioritizes everyone else
+    fn spawn_mock_policy(path: PathBuf, denied: Pubkey) {
+        let listener = UnixListener::bind(&path).unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let request: serde_json::Value = serde_json::from_str(&line).unwrap();
+                        let response = if request["leader"] == denied.to_string() {
+                            r#"{"verdict":"deny"}"#
+                        } else {
+                            r#"{"verdict":"allow","priority":7}"#
+                        };
+                        let _ = writer.write_all(format!("{}\n", response).as_bytes()).await;
+                    }
+                });
+            }
+        });
+    }
+
+    #[tokio::test]
+    async fn test_policy_verdicts_and_failure_modes() {
+        let path =
+            std::env::temp_dir().join(format!("scramjet-policy-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let denied = Pubkey::new_unique();
+        spawn_mock_policy(path.clone(), denied);
+
+        let addr: SocketAddr = "1.1.1.1:8009".parse().unwrap();
+        let timeout = Duration::from_secs(1);
+        let client = PolicyClient::new(path.clone(), timeout, Duration::from_secs(60), false);
+
+        let allowed = client
+            .evaluate(PolicyPurpose::Send, 1, Pubkey::new_unique(), addr)
+            .await;
+        assert_eq!(allowed.verdict, Verdict::Allow);
+        assert_eq!(allowed.priority, 7);
+        let deny = client.evaluate(PolicyPurpose::Send, 1, denied, addr).await;
+        assert!(!deny.is_allowed());
+
+        // Concurrent queries each get an answer, on connections kept for reuse
+        let leaders: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let decisions = futures::future::join_all(
+            leaders
+                .iter()
+                .map(|leader| client.evaluate(PolicyPurpose::Scout, 1, *leader, addr)),
+        )
+        .await;
+        assert!(decisions.iter().all(PolicyDecision::is_allowed));
+        assert!(!client.idle.lock().unwrap().is_empty());
+
+        // Unreachable engine: fail closed denies, fail open allows
+        let missing = std::env::temp_dir().join("scramjet-policy-missing.sock");
+        let closed = PolicyClient::new(missing.clone(), timeout, Duration::ZERO, false);
+        let open = PolicyClient::new(missing, timeout, Duration::ZERO, true);
+        let leader = Pubkey::new_unique();
+        assert!(!closed
+            .evaluate(PolicyPurpose::Scout, 1, leader, addr)
+            .await
+            .is_allowed());
+        assert!(open
+            .evaluate(PolicyPurpose::Scout, 1, leader, addr)
+            .await
+            .is_allowed());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
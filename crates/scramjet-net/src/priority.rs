@@ -0,0 +1,235 @@
+//! Priority classes for transactions.
+//!
+//! Every send is `high`, `normal` (the default) or `bulk`. The class decides
+//! three things:
+//!
+//! - the [`SendQueue`](crate::queue::SendQueue) lane a submission waits in
+//!   (highest first)
+//! - the send rate limiter: `high` sends go ahead of sends already waiting
+//!   for a token
+//! - each leader's [`StreamBudget`] (`QUIC_STREAM_BUDGET` concurrent streams):
+//!   a `bulk` send opens a stream only while less than half of the budget is
+//!   in use and a `normal` one while an eighth is left, so the rest is kept
+//!   for `high`; a waiting send is served before any lower class
+//!
+//! So spam run as `bulk` cannot starve the latency-sensitive sends sharing
+//! its connections.
+
+use scramjet_common::ScramjetError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// Priority class of a transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Background traffic (spam, backfills)
+    Bulk,
+    #[default]
+    Normal,
+    /// Latency-sensitive sends
+    High,
+}
+
+impl Priority {
+    /// Classes from highest to lowest
+    pub const BY_PRIORITY: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Bulk];
+
+    /// Class for a signed priority (the gRPC `priority` field): above zero is
+    /// high, below zero is bulk
+    pub fn from_level(level: i32) -> Self {
+        match level {
+            l if l > 0 => Priority::High,
+            l if l < 0 => Priority::Bulk,
+            _ => Priority::Normal,
+        }
+    }
+
+    /// Signed priority for this class (inverse of `from_level`)
+    pub fn level(self) -> i32 {
+        match self {
+            Priority::High => 1,
+            Priority::Normal => 0,
+            Priority::Bulk => -1,
+        }
+    }
+
+    /// Position in per-class arrays (bulk = 0)
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Priority::Bulk => "bulk",
+            Priority::Normal => "normal",
+            Priority::High => "high",
+        })
+    }
+}
+
+impl std::str::FromStr for Priority {
+    type Err = ScramjetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "high" => Ok(Priority::High),
+            "normal" => Ok(Priority::Normal),
+            "bulk" => Ok(Priority::Bulk),
+            other => Err(ScramjetError::ConfigValidationError(format!(
+                "Unknown priority '{}' (expected high, normal or bulk)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Concurrent streams to one leader, shared out by priority
+#[derive(Debug)]
+pub struct StreamBudget {
+    capacity: usize,
+    in_use: Mutex<usize>,
+    /// Senders waiting, per class (indexed by [`Priority::index`])
+    waiting: [AtomicUsize; 3],
+    /// Signalled whenever a stream is released
+    released: Notify,
+}
+
+impl StreamBudget {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            in_use: Mutex::new(0),
+            waiting: Default::default(),
+            released: Notify::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Streams currently held
+    pub fn in_use(&self) -> usize {
+        *self.in_use.lock().unwrap()
+    }
+
+    /// Streams in use (by every class) below which a class may open another
+    pub fn limit(&self, priority: Priority) -> usize {
+        match priority {
+            Priority::High => self.capacity,
+            Priority::Normal => (self.capacity - self.capacity / 8).max(1),
+            Priority::Bulk => (self.capacity / 2).max(1),
+        }
+    }
+
+    /// Wait until a `priority` stream fits: under its class limit, with no
+    /// higher class waiting. The stream is released when the permit drops.
+    pub async fn acquire(self: &Arc<Self>, priority: Priority) -> StreamPermit {
+        let mut waiting = None;
+        loop {
+            let released = self.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+            if self.try_take(priority) {
+                return StreamPermit(self.clone());
+            }
+            // Counted from the first miss, so higher classes jump the line
+            waiting.get_or_insert_with(|| Waiting::new(&self.waiting[priority.index()]));
+            released.await;
+        }
+    }
+
+    fn try_take(&self, priority: Priority) -> bool {
+        let mut in_use = self.in_use.lock().unwrap();
+        let outranked = Priority::BY_PRIORITY
+            .iter()
+            .take_while(|class| **class > priority)
+            .any(|class| self.waiting[class.index()].load(Ordering::Acquire) > 0);
+        if outranked || *in_use >= self.limit(priority) {
+            return false;
+        }
+        *in_use += 1;
+        true
+    }
+}
+
+/// One stream of a [`StreamBudget`], held until the stream is written
+#[derive(Debug)]
+pub struct StreamPermit(Arc<StreamBudget>);
+
+impl Drop for StreamPermit {
+    fn drop(&mut self) {
+        *self.0.in_use.lock().unwrap() -= 1;
+        self.0.released.notify_waiters();
+    }
+}
+
+/// Counts a waiting sender until it gets its stream (or gives up)
+struct Waiting<'a>(&'a AtomicUsize);
+
+impl<'a> Waiting<'a> {
+    fn new(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::AcqRel);
+        Self(count)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_stream_budget_favors_high() {
+        assert_eq!("HIGH".parse::<Priority>().unwrap(), Priority::High);
+        assert_eq!(" bulk".parse::<Priority>().unwrap(), Priority::Bulk);
+        assert!("urgent".parse::<Priority>().is_err());
+        assert_eq!(Priority::from_level(-3), Priority::Bulk);
+        assert_eq!(Priority::default().to_string(), "normal");
+
+        let budget = Arc::new(StreamBudget::new(8));
+        assert_eq!(
+            [Priority::Bulk, Priority::Normal, Priority::High].map(|p| budget.limit(p)),
+            [4, 7, 8]
+        );
+
+        // Bulk stops once half the budget is in use; high still gets in
+        let mut bulk = Vec::new();
+        for _ in 0..4 {
+            bulk.push(budget.acquire(Priority::Bulk).await);
+        }
+        let blocked = {
+            let budget = budget.clone();
+            tokio::spawn(async move { budget.acquire(Priority::Bulk).await })
+        };
+        let mut high = Vec::new();
+        for _ in 0..4 {
+            high.push(budget.acquire(Priority::High).await);
+        }
+        assert_eq!(budget.in_use(), 8);
+
+        // A waiting high send is served before the waiting bulk one
+        let queued_high = {
+            let budget = budget.clone();
+            tokio::spawn(async move { budget.acquire(Priority::High).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(bulk.pop());
+        let queued_permit = queued_high.await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!blocked.is_finished());
+
+        drop((high, queued_permit));
+        let _unblocked = blocked.await.unwrap();
+        assert_eq!(budget.in_use(), 4);
+    }
+}
@@ -0,0 +1,112 @@
+//! Leader proximity map: round-trip time to every validator.
+//!
+//! Each probe pass measures the `RTT_PROBE_BATCH` validators probed longest
+//! ago (never-probed ones first): from the pooled connection when there is
+//! one, otherwise with a throwaway handshake. The Cartographer keeps a
+//! smoothed estimate per validator; the Scout starts handshakes with distant
+//! leaders early enough to finish before their slot, and `monitor` / `stats`
+//! show it.
+
+use crate::cartographer::Cartographer;
+use crate::engine::QuicEngine;
+use futures::stream::{self, StreamExt};
+use log::debug;
+use solana_sdk::pubkey::Pubkey;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// A probe handshake that takes longer counts as failed
+pub const RTT_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Probe handshakes in flight at once
+const RTT_PROBE_CONCURRENCY: usize = 8;
+
+/// Weight of a new sample in the smoothed RTT (as in TCP's SRTT)
+const RTT_SAMPLE_WEIGHT: u32 = 4;
+
+/// One validator's round-trip time estimate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RttEstimate {
+    /// Smoothed RTT (None until a probe succeeds)
+    pub rtt: Option<Duration>,
+    /// Last probe, successful or not
+    pub probed_at: Instant,
+}
+
+impl RttEstimate {
+    /// Fold in a probe result (None = the probe failed; the estimate stays)
+    pub fn update(previous: Option<Self>, sample: Option<Duration>) -> Self {
+        let rtt = match (previous.and_then(|p| p.rtt), sample) {
+            (Some(rtt), Some(sample)) => {
+                Some((rtt * (RTT_SAMPLE_WEIGHT - 1) + sample) / RTT_SAMPLE_WEIGHT)
+            }
+            (rtt, sample) => sample.or(rtt),
+        };
+        Self {
+            rtt,
+            probed_at: Instant::now(),
+        }
+    }
+}
+
+/// Probe up to `batch` validators, least recently probed first. Returns the
+/// number measured.
+pub async fn probe_pass(cartographer: &Cartographer, engine: &QuicEngine, batch: usize) -> usize {
+    let topology = cartographer.topology();
+    let mut due: Vec<(Option<Instant>, Pubkey, SocketAddr)> = topology
+        .nodes
+        .iter()
+        .map(|(validator, addr)| (cartographer.rtt_probed_at(validator), *validator, *addr))
+        .collect();
+    // None (never probed) sorts first
+    due.sort_unstable_by_key(|(probed_at, ..)| *probed_at);
+    due.truncate(batch);
+
+    let results: Vec<(Pubkey, Option<Duration>)> = stream::iter(due)
+        .map(|(_, validator, addr)| async move {
+            let rtt = match tokio::time::timeout(RTT_PROBE_TIMEOUT, engine.measure_rtt(addr)).await
+            {
+                Ok(Ok(rtt)) => Some(rtt),
+                Ok(Err(e)) => {
+                    debug!("RTT: Probe to {} ({}) failed: {}", validator, addr, e);
+                    None
+                }
+                Err(_) => {
+                    debug!("RTT: Probe to {} ({}) timed out", validator, addr);
+                    None
+                }
+            };
+            (validator, rtt)
+        })
+        .buffer_unordered(RTT_PROBE_CONCURRENCY)
+        .collect()
+        .await;
+
+    let measured = results.iter().filter(|(_, rtt)| rtt.is_some()).count();
+    for (validator, rtt) in results {
+        cartographer.record_rtt(validator, rtt);
+    }
+    measured
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtt_estimate_smoothing() {
+        let ms = Duration::from_millis;
+        let first = RttEstimate::update(None, Some(ms(100)));
+        assert_eq!(first.rtt, Some(ms(100)));
+
+        // A new sample moves the estimate a quarter of the way
+        let second = RttEstimate::update(Some(first), Some(ms(20)));
+        assert_eq!(second.rtt, Some(ms(80)));
+
+        // A failed probe keeps the estimate but counts as a probe
+        let failed = RttEstimate::update(Some(second), None);
+        assert_eq!(failed.rtt, Some(ms(80)));
+        assert!(failed.probed_at >= second.probed_at);
+        assert_eq!(RttEstimate::update(None, None).rtt, None);
+    }
+}
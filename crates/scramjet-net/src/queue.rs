@@ -0,0 +1,296 @@
+//! Bounded send queue between ingestion (JSON-RPC, gRPC, Unix socket) and
+//! the sender.
+//!
+//! Submissions wait in one lane per [`Priority`] (the transaction's) and are
+//! taken highest lane first, oldest first within a lane. The queue holds at most
+//! `SEND_QUEUE_CAPACITY` submissions; what happens to one more is the
+//! [`OverflowPolicy`] (`SEND_QUEUE_OVERFLOW`):
+//!
+//! - `block`: the producer waits for room (gRPC and Unix socket clients stop
+//!   being read, so backpressure reaches them)
+//! - `drop-oldest`: the oldest submission in the lowest lane not above the
+//!   new one's is answered with an error and replaced
+//! - `reject`: the new submission is answered with an error right away
+//!
+//! Dropped and rejected submissions are counted; the depth and that count
+//! are reported by `scramjet status`.
+
+use crate::priority::Priority;
+use crate::server::Submission;
+use scramjet_common::{Config, ScramjetError};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// What a full queue does with one more submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    Block,
+    DropOldest,
+    Reject,
+}
+
+impl std::str::FromStr for OverflowPolicy {
+    type Err = ScramjetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "block" => Ok(Self::Block),
+            "drop-oldest" => Ok(Self::DropOldest),
+            "reject" => Ok(Self::Reject),
+            other => Err(ScramjetError::ConfigValidationError(format!(
+                "SEND_QUEUE_OVERFLOW='{}' (expected block, drop-oldest or reject)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Bounded, prioritized queue of submissions waiting to be sent. Meant for
+/// one consumer; any number of producers.
+pub struct SendQueue {
+    /// Indexed by [`Priority::index`]
+    lanes: Mutex<[VecDeque<Submission>; 3]>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    /// Signalled on every push (and on close)
+    pushed: Notify,
+    /// Signalled on every pop (and on close)
+    popped: Notify,
+    closed: AtomicBool,
+    /// Submissions dropped or rejected because the queue was full
+    overflows: AtomicU64,
+}
+
+impl SendQueue {
+    pub fn new(capacity: usize, policy: OverflowPolicy) -> Self {
+        Self {
+            lanes: Mutex::new(Default::default()),
+            capacity: capacity.max(1),
+            policy,
+            pushed: Notify::new(),
+            popped: Notify::new(),
+            closed: AtomicBool::new(false),
+            overflows: AtomicU64::new(0),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ScramjetError> {
+        Ok(Self::new(
+            config.send_queue_capacity,
+            config.send_queue_overflow.parse()?,
+        ))
+    }
+
+    /// Queue `submission` in its transaction's priority lane. Fails (after answering the submission
+    /// with the same error) when the queue is full under `reject` or
+    /// `drop-oldest` with nothing lower to drop, or once the queue is closed.
+    pub async fn push(&self, submission: Submission) -> Result<(), ScramjetError> {
+        let lane = submission.transaction.priority;
+        let mut submission = Some(submission);
+        loop {
+            // Registered before checking, so a pop in between is not missed
+            let room = self.popped.notified();
+            tokio::pin!(room);
+            room.as_mut().enable();
+            if self.try_push(&mut submission, lane)? {
+                self.pushed.notify_one();
+                return Ok(());
+            }
+            room.await;
+        }
+    }
+
+    /// Next submission, highest lane first; None once the queue is closed
+    /// and empty
+    pub async fn pop(&self) -> Option<Submission> {
+        loop {
+            let pushed = self.pushed.notified();
+            tokio::pin!(pushed);
+            pushed.as_mut().enable();
+            {
+                let mut lanes = self.lanes.lock().unwrap();
+                let next = Priority::BY_PRIORITY
+                    .iter()
+                    .find_map(|lane| lanes[lane.index()].pop_front());
+                if let Some(submission) = next {
+                    drop(lanes);
+                    self.popped.notify_one();
+                    return Some(submission);
+                }
+                if self.closed.load(Ordering::Acquire) {
+                    return None;
+                }
+            }
+            pushed.await;
+        }
+    }
+
+    /// Refuse new submissions; queued ones can still be popped
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.pushed.notify_waiters();
+        self.popped.notify_waiters();
+    }
+
+    /// Submissions waiting
+    pub fn len(&self) -> usize {
+        self.lanes.lock().unwrap().iter().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> OverflowPolicy {
+        self.policy
+    }
+
+    /// Submissions dropped or rejected because the queue was full
+    pub fn overflows(&self) -> u64 {
+        self.overflows.load(Ordering::Relaxed)
+    }
+
+    /// Queue the submission if there is room (or room can be made); false
+    /// when the producer should wait. A refused submission is answered here.
+    fn try_push(
+        &self,
+        submission: &mut Option<Submission>,
+        lane: Priority,
+    ) -> Result<bool, ScramjetError> {
+        let mut lanes = self.lanes.lock().unwrap();
+        if self.closed.load(Ordering::Acquire) {
+            refuse(submission, || ScramjetError::ShuttingDown);
+            return Err(ScramjetError::ShuttingDown);
+        }
+        if lanes.iter().map(VecDeque::len).sum::<usize>() >= self.capacity {
+            let victim = match self.policy {
+                OverflowPolicy::Block => return Ok(false),
+                OverflowPolicy::DropOldest => evict(&mut lanes, lane),
+                OverflowPolicy::Reject => None,
+            };
+            self.overflows.fetch_add(1, Ordering::Relaxed);
+            match victim {
+                Some(victim) => victim.respond(Err(ScramjetError::QueueFull(
+                    "dropped for a newer submission".into(),
+                ))),
+                None => {
+                    let full = || {
+                        ScramjetError::QueueFull(format!("{} submissions waiting", self.capacity))
+                    };
+                    refuse(submission, full);
+                    return Err(full());
+                }
+            }
+        }
+        lanes[lane.index()].push_back(submission.take().expect("queued once"));
+        Ok(true)
+    }
+}
+
+fn refuse(submission: &mut Option<Submission>, error: impl Fn() -> ScramjetError) {
+    if let Some(submission) = submission.take() {
+        submission.respond(Err(error()));
+    }
+}
+
+/// Oldest submission in the lowest non-empty lane not above `lane`
+fn evict(lanes: &mut [VecDeque<Submission>; 3], lane: Priority) -> Option<Submission> {
+    Priority::BY_PRIORITY
+        .iter()
+        .rev()
+        .filter(|candidate| **candidate <= lane)
+        .find_map(|candidate| lanes[candidate.index()].pop_front())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::RawTransaction;
+    use crate::server;
+    use solana_sdk::signature::Signature;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn submission(
+        tag: u8,
+        priority: Priority,
+    ) -> (
+        Submission,
+        tokio::sync::oneshot::Receiver<Result<(), String>>,
+    ) {
+        Submission::new(RawTransaction {
+            bytes: vec![tag].into(),
+            signature: Signature::default(),
+            deadline: None,
+            priority,
+            relayed: false,
+        })
+    }
+
+    async fn pop_tag(queue: &SendQueue) -> u8 {
+        queue.pop().await.unwrap().transaction.bytes[0]
+    }
+
+    #[tokio::test]
+    async fn test_lanes_and_overflow_policies() {
+        // Highest lane first, FIFO within a lane
+        let queue = SendQueue::new(4, OverflowPolicy::Reject);
+        for (tag, priority) in [
+            (1, Priority::Normal),
+            (2, Priority::Bulk),
+            (3, Priority::High),
+            (4, Priority::Normal),
+        ] {
+            queue.push(submission(tag, priority).0).await.unwrap();
+        }
+        assert_eq!(queue.len(), 4);
+        let (extra, rejected) = submission(5, Priority::High);
+        let err = queue.push(extra).await.unwrap_err();
+        assert!(matches!(err, ScramjetError::QueueFull(_)));
+        assert!(server::outcome(rejected).await.is_err());
+        let mut order = Vec::new();
+        for _ in 0..4 {
+            order.push(pop_tag(&queue).await);
+        }
+        assert_eq!(order, vec![3, 1, 4, 2]);
+        assert_eq!(queue.overflows(), 1);
+
+        // Drop-oldest evicts from the lowest lane, never a higher one
+        let queue = SendQueue::new(2, OverflowPolicy::DropOldest);
+        let (bulk, evicted) = submission(1, Priority::Bulk);
+        queue.push(bulk).await.unwrap();
+        queue.push(submission(2, Priority::High).0).await.unwrap();
+        queue.push(submission(3, Priority::Normal).0).await.unwrap();
+        assert!(server::outcome(evicted)
+            .await
+            .unwrap_err()
+            .contains("dropped"));
+        assert!(queue.push(submission(4, Priority::Bulk).0).await.is_err());
+        assert_eq!((pop_tag(&queue).await, pop_tag(&queue).await), (2, 3));
+
+        // Block waits for room; close drains what is queued, then ends
+        let queue = Arc::new(SendQueue::new(1, OverflowPolicy::Block));
+        queue.push(submission(1, Priority::Normal).0).await.unwrap();
+        let producer = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.push(submission(2, Priority::Normal).0).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!producer.is_finished());
+        assert_eq!(pop_tag(&queue).await, 1);
+        producer.await.unwrap().unwrap();
+        queue.close();
+        assert!(matches!(
+            queue.push(submission(3, Priority::High).0).await,
+            Err(ScramjetError::ShuttingDown)
+        ));
+        assert_eq!(pop_tag(&queue).await, 2);
+        assert!(queue.pop().await.is_none());
+    }
+}
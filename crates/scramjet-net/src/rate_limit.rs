@@ -0,0 +1,196 @@
+//! Token bucket send rate limiter.
+//!
+//! The bucket refills at `rate` tokens per second up to `burst`; every send
+//! takes one token. Callers that find the bucket empty reserve the next
+//! token and sleep until it is due, so concurrent senders are served in
+//! arrival order and the long-run rate never exceeds `rate`. Keeping
+//! `burst` small stops a backlog from hitting a leader all at once and
+//! tripping its stream throttling / QoS drops.
+//!
+//! `high` priority callers (see `priority`) are charged a token like anyone
+//! else but do not queue behind the sleepers: they wait at most one token
+//! interval, and the reservation they add pushes back later arrivals.
+
+use crate::priority::Priority;
+use arc_swap::ArcSwapOption;
+use scramjet_common::{config, ScramjetError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default burst as a fraction of one second's worth of tokens
+const DEFAULT_BURST_FRACTION: f64 = 0.1;
+
+/// Swappable limiter shared by senders and the control socket's `set-rate`
+/// (None = unlimited)
+pub type RateLimitHandle = Arc<ArcSwapOption<RateLimiter>>;
+
+/// Handle starting with `limiter`
+pub fn rate_limit_handle(limiter: Option<Arc<RateLimiter>>) -> RateLimitHandle {
+    Arc::new(ArcSwapOption::new(limiter))
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative when sleepers have reserved tokens ahead of the refill
+    tokens: f64,
+    refilled: Instant,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Tokens per second
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+    /// `rate` sends per second, at most `burst` back-to-back (0 = a tenth of
+    /// a second's worth, at least 1). The bucket starts full.
+    pub fn new(rate: f64, burst: u32) -> Result<Self, ScramjetError> {
+        if !(rate.is_finite() && rate > 0.0) {
+            return Err(ScramjetError::ConfigError(format!(
+                "Send rate must be positive, got {}",
+                rate
+            )));
+        }
+        let burst = if burst == 0 {
+            (rate * DEFAULT_BURST_FRACTION).floor().max(1.0)
+        } else {
+            burst as f64
+        };
+        Ok(Self {
+            rate,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled: Instant::now(),
+            }),
+        })
+    }
+
+    /// Parse a rate spec (`200/s`, `50/100ms`, `1000/m`; bare numbers are per
+    /// second) into sends per second (see `config::parse_rate`)
+    pub fn parse_rate(spec: &str) -> Result<f64, ScramjetError> {
+        config::parse_rate(spec).map_err(ScramjetError::ConfigError)
+    }
+
+    /// Limiter from a rate spec (see `parse_rate`)
+    pub fn from_spec(spec: &str, burst: u32) -> Result<Self, ScramjetError> {
+        Self::new(Self::parse_rate(spec)?, burst)
+    }
+
+    /// Sends per second
+    pub fn rate(&self) -> f64 {
+        self.rate
+    }
+
+    pub fn burst(&self) -> u32 {
+        self.burst as u32
+    }
+
+    fn refill(&self, bucket: &mut Bucket, now: Instant) {
+        let elapsed = now.saturating_duration_since(bucket.refilled).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.rate).min(self.burst);
+        bucket.refilled = now;
+    }
+
+    /// Take a token, or reserve the next one and return how long to wait for it
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket, now);
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        }
+    }
+
+    /// [`reserve`](Self::reserve), jumping the queue for `high` priority
+    fn reserve_for(&self, priority: Priority, now: Instant) -> Duration {
+        let wait = self.reserve(now);
+        match priority {
+            Priority::High => wait.min(Duration::from_secs_f64(1.0 / self.rate)),
+            Priority::Normal | Priority::Bulk => wait,
+        }
+    }
+
+    /// Take a token without waiting (false when the bucket is empty)
+    pub fn try_acquire(&self) -> bool {
+        let mut bucket = self.bucket.lock().unwrap();
+        self.refill(&mut bucket, Instant::now());
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Wait for a token
+    pub async fn acquire(&self) {
+        self.acquire_with(Priority::Normal).await
+    }
+
+    /// Wait for a token at `priority`
+    pub async fn acquire_with(&self, priority: Priority) {
+        let wait = self.reserve_for(priority, Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(RateLimiter::parse_rate("200/s").unwrap(), 200.0);
+        assert_eq!(RateLimiter::parse_rate("200").unwrap(), 200.0);
+        assert_eq!(RateLimiter::parse_rate("50/100ms").unwrap(), 500.0);
+        assert_eq!(RateLimiter::parse_rate("120/m").unwrap(), 2.0);
+        assert_eq!(RateLimiter::parse_rate(" 10 / 2s ").unwrap(), 5.0);
+        for bad in ["", "fast", "0/s", "10/h", "10/0ms", "-5/s", "10/xs"] {
+            assert!(RateLimiter::parse_rate(bad).is_err(), "{}", bad);
+        }
+        // Out of bounds, and a minute count that overflows
+        for absurd in ["1e9/s", "1/7200s", "1/999999999999999999m"] {
+            assert!(RateLimiter::parse_rate(absurd).is_err(), "{}", absurd);
+        }
+    }
+
+    #[test]
+    fn test_bucket_bursts_then_paces() {
+        let limiter = RateLimiter::new(100.0, 0).unwrap();
+        assert_eq!(limiter.burst(), 10);
+        let start = Instant::now();
+
+        // Full bucket: one burst goes out immediately
+        for _ in 0..10 {
+            assert_eq!(limiter.reserve(start), Duration::ZERO);
+        }
+        // Then one token every 10ms, reserved in arrival order
+        let first = limiter.reserve(start);
+        let second = limiter.reserve(start);
+        assert!((first.as_secs_f64() - 0.01).abs() < 1e-9);
+        assert!((second.as_secs_f64() - 0.02).abs() < 1e-9);
+        // High priority goes ahead of both sleepers, yet is charged
+        let high = limiter.reserve_for(Priority::High, start);
+        assert!((high.as_secs_f64() - 0.01).abs() < 1e-9);
+        assert!((limiter.reserve(start).as_secs_f64() - 0.04).abs() < 1e-9);
+
+        // Reserved tokens are paid back before the bucket refills
+        assert_eq!(
+            limiter.reserve(start + Duration::from_millis(50)),
+            Duration::ZERO
+        );
+        assert!(RateLimiter::new(0.0, 1).is_err());
+
+        let single = RateLimiter::new(1.0, 1).unwrap();
+        assert!(single.try_acquire());
+        assert!(!single.try_acquire());
+    }
+}
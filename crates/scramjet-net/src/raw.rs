@@ -0,0 +1,193 @@
+//! Decoding of externally built, pre-signed transactions (`scramjet send-raw`).
+//!
+//! Input is one encoded transaction per line, base64 or base58 (the encodings
+//! `solana` tooling and RPC `sendTransaction` accept). Decoded bytes are
+//! forwarded to the leader untouched; they are only deserialized to check
+//! that they are a signed, wire-sized transaction and to report the signature.
+
+use crate::deadline::Deadline;
+use crate::priority::Priority;
+use base64::Engine as _;
+use bytes::Bytes;
+use scramjet_common::packet::check_packet_size;
+use scramjet_common::ScramjetError;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+
+/// Wire encoding of a raw transaction line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawEncoding {
+    /// Base58 when the line decodes as base58, base64 otherwise
+    Auto,
+    Base64,
+    Base58,
+}
+
+impl std::str::FromStr for RawEncoding {
+    type Err = ScramjetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "base64" => Ok(Self::Base64),
+            "base58" => Ok(Self::Base58),
+            other => Err(ScramjetError::ConfigValidationError(format!(
+                "Unknown encoding '{}' (expected auto, base64 or base58)",
+                other
+            ))),
+        }
+    }
+}
+
+/// A decoded, signed transaction ready to send
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawTransaction {
+    /// Wire bytes exactly as decoded (shared, not copied, when sent)
+    pub bytes: Bytes,
+    /// First (fee payer) signature
+    pub signature: Signature,
+    /// Dropped instead of sent once this passes (None = no deadline)
+    pub deadline: Option<Deadline>,
+    /// Priority class (queue lane, rate limit and stream budget)
+    pub priority: Priority,
+    /// Forwarded by a peer's relay: sent from here, never relayed again
+    pub relayed: bool,
+}
+
+impl RawTransaction {
+    pub fn with_deadline(mut self, deadline: Deadline) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// Decode one encoded transaction (surrounding whitespace ignored)
+pub fn parse_raw_transaction(
+    line: &str,
+    encoding: RawEncoding,
+) -> Result<RawTransaction, ScramjetError> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Err(ScramjetError::InvalidTransaction("empty input".into()));
+    }
+
+    let bytes = match encoding {
+        RawEncoding::Base64 => decode_base64(line)?,
+        RawEncoding::Base58 => decode_base58(line)?,
+        // The base58 alphabet is a subset of base64's, so try the stricter
+        // one first and only accept it if the result is a transaction
+        RawEncoding::Auto => match decode_base58(line) {
+            Ok(bytes) if validate(&bytes).is_ok() => bytes,
+            _ => decode_base64(line)?,
+        },
+    };
+    let signature = validate(&bytes)?;
+    Ok(RawTransaction {
+        bytes: bytes.into(),
+        signature,
+        deadline: None,
+        priority: Priority::Normal,
+        relayed: false,
+    })
+}
+
+/// Check already-decoded wire bytes (e.g. from the gRPC ingestion service)
+pub fn parse_wire_transaction(bytes: Vec<u8>) -> Result<RawTransaction, ScramjetError> {
+    let signature = validate(&bytes)?;
+    Ok(RawTransaction {
+        bytes: bytes.into(),
+        signature,
+        deadline: None,
+        priority: Priority::Normal,
+        relayed: false,
+    })
+}
+
+fn decode_base64(line: &str) -> Result<Vec<u8>, ScramjetError> {
+    base64::engine::general_purpose::STANDARD
+        .decode(line)
+        .map_err(|e| ScramjetError::InvalidTransaction(format!("base64: {}", e)))
+}
+
+fn decode_base58(line: &str) -> Result<Vec<u8>, ScramjetError> {
+    bs58::decode(line)
+        .into_vec()
+        .map_err(|e| ScramjetError::InvalidTransaction(format!("base58: {}", e)))
+}
+
+/// Check size and shape, returning the fee payer signature
+fn validate(bytes: &[u8]) -> Result<Signature, ScramjetError> {
+    check_packet_size(bytes)?;
+    let tx: VersionedTransaction = bincode::deserialize(bytes)
+        .map_err(|e| ScramjetError::InvalidTransaction(e.to_string()))?;
+    let required = usize::from(tx.message.header().num_required_signatures);
+    if tx.signatures.len() != required || required == 0 {
+        return Err(ScramjetError::InvalidTransaction(format!(
+            "{} signature(s) for {} required signer(s)",
+            tx.signatures.len(),
+            required
+        )));
+    }
+    if tx.signatures.iter().any(|sig| *sig == Signature::default()) {
+        return Err(ScramjetError::InvalidTransaction("not fully signed".into()));
+    }
+    Ok(tx.signatures[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[allow(deprecated)]
+    use solana_sdk::system_instruction;
+    use solana_sdk::{
+        hash::Hash,
+        signature::{Keypair, Signer},
+        transaction::Transaction,
+    };
+
+    fn signed_transfer() -> Transaction {
+        let payer = Keypair::new();
+        #[allow(deprecated)]
+        let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+        Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], Hash::default())
+    }
+
+    #[test]
+    fn test_parse_raw_transaction_encodings() {
+        let tx = signed_transfer();
+        let bytes = bincode::serialize(&tx).unwrap();
+        let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+        let b58 = bs58::encode(&bytes).into_string();
+
+        for (line, encoding) in [
+            (b64.as_str(), RawEncoding::Base64),
+            (b58.as_str(), RawEncoding::Base58),
+            (b64.as_str(), RawEncoding::Auto),
+            (b58.as_str(), RawEncoding::Auto),
+        ] {
+            let raw = parse_raw_transaction(&format!("  {}\n", line), encoding).unwrap();
+            assert_eq!(raw.bytes, bytes);
+            assert_eq!(raw.signature, tx.signatures[0]);
+        }
+
+        // Wrong explicit encoding is rejected, not misrouted
+        assert!(parse_raw_transaction(&b58, RawEncoding::Base64).is_err());
+        assert!(parse_raw_transaction("", RawEncoding::Auto).is_err());
+        assert!(parse_raw_transaction("aGVsbG8=", RawEncoding::Auto).is_err());
+    }
+
+    #[test]
+    fn test_parse_raw_transaction_rejects_unsigned() {
+        let mut tx = signed_transfer();
+        tx.signatures[0] = Signature::default();
+        let b64 =
+            base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&tx).unwrap());
+        let err = parse_raw_transaction(&b64, RawEncoding::Auto).unwrap_err();
+        assert!(err.to_string().contains("not fully signed"));
+    }
+}
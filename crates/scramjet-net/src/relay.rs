@@ -0,0 +1,286 @@
+//! Multi-region relaying: forward received transactions to peer scramjet
+//! instances (`RELAY_PEERS`), each of which sends them to the leader from its
+//! own vantage point. A leader far from this region may be next door to a
+//! peer.
+//!
+//! Peers are reached over their `scramjet.Ingest` gRPC service (`serve
+//! --grpc`), one long-lived stream each. Forwarding never waits on a peer:
+//! each has a bounded buffer, and a transaction that does not fit (the peer
+//! is slow or unreachable) is dropped for that peer and counted. Relayed
+//! messages are flagged, so a peer sends them but never forwards them again
+//! and a mesh of relaying instances cannot loop.
+
+use crate::deadline::Deadline;
+use crate::raw::RawTransaction;
+use crate::server::{SubmitTransactionsRequest, SubmitTransactionsResponse, SUBMIT_TRANSACTIONS};
+use futures::stream;
+use http::uri::PathAndQuery;
+use log::{debug, info, warn};
+use scramjet_common::ScramjetError;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
+use tonic::client::Grpc;
+use tonic::transport::Endpoint;
+use tonic::Request;
+use tonic_prost::ProstCodec;
+
+/// Transactions buffered per peer while its stream is busy or reconnecting
+pub const RELAY_BUFFER: usize = 1_024;
+
+/// Pause before reconnecting to a peer whose stream failed
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// What was forwarded to one peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayCounts {
+    pub peer: String,
+    /// Accepted into the peer's stream
+    pub forwarded: u64,
+    /// Dropped: the peer's buffer was full
+    pub dropped: u64,
+}
+
+struct Peer {
+    url: String,
+    sender: mpsc::Sender<SubmitTransactionsRequest>,
+    forwarded: AtomicU64,
+    dropped: AtomicU64,
+}
+
+/// Forwarding streams to every relay peer
+pub struct Relay {
+    peers: Vec<Arc<Peer>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl Relay {
+    /// Open a forwarding stream to each peer URL (`http://host:port`); a
+    /// stream that fails is reopened until the relay is closed
+    pub fn spawn(urls: &[String]) -> Result<Self, ScramjetError> {
+        let mut peers = Vec::with_capacity(urls.len());
+        let mut tasks = Vec::with_capacity(urls.len());
+        for url in urls {
+            let endpoint = Endpoint::from_shared(url.clone()).map_err(|e| {
+                ScramjetError::ConfigError(format!("Invalid relay peer '{}': {}", url, e))
+            })?;
+            let (sender, receiver) = mpsc::channel(RELAY_BUFFER);
+            tasks.push(tokio::spawn(stream_to_peer(
+                url.clone(),
+                endpoint,
+                receiver,
+            )));
+            peers.push(Arc::new(Peer {
+                url: url.clone(),
+                sender,
+                forwarded: AtomicU64::new(0),
+                dropped: AtomicU64::new(0),
+            }));
+        }
+        info!("Relay: Forwarding to {} peer(s)", peers.len());
+        Ok(Self { peers, tasks })
+    }
+
+    /// Hand `tx` to every peer without waiting. Transactions that were
+    /// themselves relayed are not forwarded. Returns the peers that took it.
+    pub fn forward(&self, tx: &RawTransaction) -> usize {
+        if tx.relayed {
+            return 0;
+        }
+        let request = relay_request(tx);
+        let mut accepted = 0;
+        for peer in &self.peers {
+            match peer.sender.try_send(request.clone()) {
+                Ok(()) => {
+                    peer.forwarded.fetch_add(1, Ordering::Relaxed);
+                    accepted += 1;
+                }
+                Err(_) => {
+                    peer.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        accepted
+    }
+
+    pub fn counts(&self) -> Vec<RelayCounts> {
+        self.peers
+            .iter()
+            .map(|peer| RelayCounts {
+                peer: peer.url.clone(),
+                forwarded: peer.forwarded.load(Ordering::Relaxed),
+                dropped: peer.dropped.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Stop accepting transactions and give the streams up to `drain` to
+    /// deliver what is buffered
+    pub async fn close(self, drain: Duration) -> Vec<RelayCounts> {
+        let counts = self.counts();
+        drop(self.peers);
+        for task in self.tasks {
+            let abort = task.abort_handle();
+            if tokio::time::timeout(drain, task).await.is_err() {
+                warn!("Relay: Peer stream still busy, closing");
+                abort.abort();
+            }
+        }
+        counts
+    }
+}
+
+/// The gRPC message for a forwarded transaction
+fn relay_request(tx: &RawTransaction) -> SubmitTransactionsRequest {
+    let (deadline_slot, last_valid_block_height) = match tx.deadline {
+        Some(Deadline::Slot(slot)) => (slot, 0),
+        Some(Deadline::BlockHeight(height)) => (0, height),
+        None => (0, 0),
+    };
+    SubmitTransactionsRequest {
+        transaction: tx.bytes.to_vec(),
+        deadline_slot,
+        last_valid_block_height,
+        priority: tx.priority.level(),
+        relayed: true,
+    }
+}
+
+/// Keep a `SubmitTransactions` stream open to one peer, fed from `receiver`,
+/// until every sender is gone and the buffer is delivered
+async fn stream_to_peer(
+    url: String,
+    endpoint: Endpoint,
+    receiver: mpsc::Receiver<SubmitTransactionsRequest>,
+) {
+    // Shared so a failed stream hands the buffer back for the next one
+    let receiver = Arc::new(Mutex::new(receiver));
+    let finished = |receiver: &mpsc::Receiver<_>| receiver.is_closed() && receiver.is_empty();
+    loop {
+        if finished(&*receiver.lock().await) {
+            return;
+        }
+        let result = async {
+            let channel = endpoint.connect().await?;
+            let mut grpc = Grpc::new(channel);
+            grpc.ready().await?;
+            debug!("Relay: Stream open to {}", url);
+            let requests = stream::unfold(receiver.clone(), |receiver| async move {
+                let request = receiver.lock().await.recv().await?;
+                Some((request, receiver))
+            });
+            let codec =
+                ProstCodec::<SubmitTransactionsRequest, SubmitTransactionsResponse>::default();
+            let response = grpc
+                .client_streaming(
+                    Request::new(requests),
+                    PathAndQuery::from_static(SUBMIT_TRANSACTIONS),
+                    codec,
+                )
+                .await?;
+            Ok::<_, Box<dyn std::error::Error + Send + Sync>>(response.into_inner())
+        }
+        .await;
+        match result {
+            Ok(summary) => debug!(
+                "Relay: Stream to {} closed (received {}, sent {}, failed {})",
+                url, summary.received, summary.sent, summary.failed
+            ),
+            Err(e) => {
+                warn!(
+                    "Relay: Stream to {} failed: {}. Reconnecting in {:?}.",
+                    url, e, RECONNECT_DELAY
+                );
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::priority::Priority;
+    use crate::queue::{OverflowPolicy, SendQueue};
+    use crate::raw;
+    use crate::server::spawn_ingest_server;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::{Keypair, Signer};
+    #[allow(deprecated)]
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::Transaction;
+    use tokio_util::sync::CancellationToken;
+
+    fn transfer(lamports: u64) -> RawTransaction {
+        let payer = Keypair::new();
+        let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), lamports);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+        raw::parse_wire_transaction(bincode::serialize(&tx).unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_relay_to_peer() {
+        let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = probe.local_addr().unwrap();
+        drop(probe);
+        let stop = CancellationToken::new();
+        let queue = Arc::new(SendQueue::new(8, OverflowPolicy::Block));
+        let server = spawn_ingest_server(addr, queue.clone(), stop.clone())
+            .await
+            .unwrap();
+        let peer = queue.clone();
+        let received = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(submission) = peer.pop().await {
+                received.push(submission.transaction.clone());
+                submission.respond(Ok(()));
+            }
+            received
+        });
+
+        let relay = Relay::spawn(&[format!("http://{}", addr)]).unwrap();
+        let urgent = RawTransaction {
+            priority: Priority::High,
+            ..transfer(1)
+        }
+        .with_deadline(Deadline::BlockHeight(900));
+        let echoed = RawTransaction {
+            relayed: true,
+            ..transfer(2)
+        };
+        assert_eq!(relay.forward(&urgent), 1);
+        assert_eq!(relay.forward(&echoed), 0);
+        assert_eq!(relay.forward(&transfer(3)), 1);
+
+        let counts = relay.close(Duration::from_secs(5)).await;
+        assert_eq!(counts[0].forwarded, 2);
+        assert_eq!(counts[0].dropped, 0);
+        queue.close();
+        let received = received.await.unwrap();
+
+        // Flagged as relayed, with deadline and priority intact
+        assert_eq!(received.len(), 2);
+        assert_eq!(
+            received[0],
+            RawTransaction {
+                relayed: true,
+                ..urgent
+            }
+        );
+        assert!(received[1].relayed);
+        stop.cancel();
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn test_invalid_peer_url() {
+        assert!(Relay::spawn(&["not a url".to_string()]).is_err());
+    }
+}
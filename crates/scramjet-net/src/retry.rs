@@ -0,0 +1,243 @@
+//! Retry policy for single-shot sends.
+//!
+//! The engine reports send failures as [`SendFailureKind`]s. Only transient
+//! ones are retried: connection loss (reset, idle timeout, validator closing
+//! the connection), handshake timeouts and certificate rejections (the retry
+//! may fail over to the secondary identity), and stream refusals (stream
+//! stopped, failing to open, or not written within `QUIC_SEND_TIMEOUT_MS`).
+//! Protocol errors, bad addresses and local misuse fail immediately.
+
+use scramjet_common::{Config, ScramjetError, SendFailureKind};
+use std::time::Duration;
+
+/// Failure classes a send can be retried on (`SEND_RETRY_ON`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryClass {
+    /// Handshake failed or an established connection was lost
+    Connection,
+    /// Stream could not be opened, was stopped by the validator or timed out
+    Stream,
+}
+
+/// Which failure classes are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryOn {
+    pub connection: bool,
+    pub stream: bool,
+}
+
+impl RetryOn {
+    pub const ALL: RetryOn = RetryOn {
+        connection: true,
+        stream: true,
+    };
+    pub const NONE: RetryOn = RetryOn {
+        connection: false,
+        stream: false,
+    };
+
+    pub fn contains(&self, class: RetryClass) -> bool {
+        match class {
+            RetryClass::Connection => self.connection,
+            RetryClass::Stream => self.stream,
+        }
+    }
+}
+
+/// Parse a comma-separated class list: `connection`, `stream`, `all`, `none`
+pub fn parse_retry_on(spec: &str) -> Result<RetryOn, ScramjetError> {
+    let mut retry_on = RetryOn::NONE;
+    for class in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match class.to_ascii_lowercase().as_str() {
+            "connection" => retry_on.connection = true,
+            "stream" => retry_on.stream = true,
+            "all" => retry_on = RetryOn::ALL,
+            "none" => {}
+            _ => {
+                return Err(ScramjetError::ConfigValidationError(format!(
+                    "Invalid retry class '{}' (expected connection, stream, all or none)",
+                    class
+                )))
+            }
+        }
+    }
+    Ok(retry_on)
+}
+
+/// Retry class of a send error (None = not retriable)
+pub fn classify(err: &ScramjetError) -> Option<RetryClass> {
+    match err {
+        ScramjetError::SendFailed(failure) => class_of(failure.kind),
+        ScramjetError::TransportError(e) => classify_connection(e),
+        ScramjetError::WriteError(quinn::WriteError::Stopped(_)) => Some(RetryClass::Stream),
+        ScramjetError::WriteError(quinn::WriteError::ConnectionLost(e)) => classify_connection(e),
+        ScramjetError::WriteError(quinn::WriteError::ZeroRttRejected) => {
+            Some(RetryClass::Connection)
+        }
+        ScramjetError::StreamError(_) => Some(RetryClass::Stream),
+        _ => None,
+    }
+}
+
+/// Retry class of a classified send failure
+pub fn class_of(kind: SendFailureKind) -> Option<RetryClass> {
+    if !kind.is_retryable() {
+        None
+    } else if kind.is_stream() {
+        Some(RetryClass::Stream)
+    } else {
+        Some(RetryClass::Connection)
+    }
+}
+
+fn classify_connection(err: &quinn::ConnectionError) -> Option<RetryClass> {
+    use quinn::ConnectionError::*;
+    match err {
+        ConnectionClosed(_) | ApplicationClosed(_) | Reset | TimedOut | LocallyClosed => {
+            Some(RetryClass::Connection)
+        }
+        VersionMismatch | TransportError(_) | CidsExhausted => None,
+    }
+}
+
+/// Attempts and exponential backoff for `QuicEngine::send_transaction`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first (1 = no retries)
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub retry_on: RetryOn,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(20),
+            max_backoff: Duration::from_millis(200),
+            retry_on: RetryOn::ALL,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Single attempt, no retries
+    pub const NEVER: RetryPolicy = RetryPolicy {
+        max_attempts: 1,
+        initial_backoff: Duration::ZERO,
+        max_backoff: Duration::ZERO,
+        retry_on: RetryOn::NONE,
+    };
+
+    pub fn from_config(config: &Config) -> Result<Self, ScramjetError> {
+        Ok(Self {
+            max_attempts: config.send_retry_max_attempts,
+            initial_backoff: config.send_retry_backoff(),
+            max_backoff: config.send_retry_max_backoff(),
+            retry_on: parse_retry_on(&config.send_retry_on)?,
+        })
+    }
+
+    /// Backoff before the next attempt if `err` on attempt `attempt`
+    /// (1-based) should be retried, else None
+    pub fn backoff(&self, attempt: u32, err: &ScramjetError) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let class = classify(err)?;
+        if !self.retry_on.contains(class) {
+            return None;
+        }
+        let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+        Some(
+            self.initial_backoff
+                .saturating_mul(factor)
+                .min(self.max_backoff),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_send_errors() {
+        let reset = ScramjetError::TransportError(quinn::ConnectionError::Reset);
+        assert_eq!(classify(&reset), Some(RetryClass::Connection));
+        let stopped = ScramjetError::WriteError(quinn::WriteError::Stopped(0u32.into()));
+        assert_eq!(classify(&stopped), Some(RetryClass::Stream));
+        let lost = ScramjetError::WriteError(quinn::WriteError::ConnectionLost(
+            quinn::ConnectionError::TimedOut,
+        ));
+        assert_eq!(classify(&lost), Some(RetryClass::Connection));
+        let version = ScramjetError::TransportError(quinn::ConnectionError::VersionMismatch);
+        assert_eq!(classify(&version), None);
+        assert_eq!(
+            classify(&ScramjetError::ConnectionError("bad addr".into())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_classify_send_failures() {
+        use scramjet_common::SendFailure;
+        let target = "1.1.1.1:8009".parse().unwrap();
+        let kind = |err: &quinn::ConnectionError, handshake| {
+            SendFailureKind::of_connection(err, handshake)
+        };
+
+        // A timeout is the handshake's until the connection is up
+        let timed_out = quinn::ConnectionError::TimedOut;
+        assert_eq!(kind(&timed_out, true), SendFailureKind::HandshakeTimeout);
+        assert_eq!(kind(&timed_out, false), SendFailureKind::ConnectionLost);
+        // A TLS alert (CRYPTO_ERROR) rejects the certificate
+        let alert = quinn::ConnectionError::ConnectionClosed(quinn::ConnectionClose {
+            error_code: quinn::TransportErrorCode::crypto(42),
+            frame_type: None,
+            reason: bytes::Bytes::new(),
+        });
+        assert_eq!(kind(&alert, true), SendFailureKind::CertRejected);
+
+        let stopped = SendFailure::write(target, &quinn::WriteError::Stopped(0u32.into()));
+        assert_eq!(stopped.kind, SendFailureKind::StreamStopped);
+        let stopped = ScramjetError::from(stopped).with_slot(500);
+        assert_eq!(classify(&stopped), Some(RetryClass::Stream));
+        assert_eq!(stopped.send_failure().unwrap().slot, Some(500));
+        assert!(stopped.to_string().contains("1.1.1.1:8009"));
+
+        let rejected = ScramjetError::from(SendFailure::handshake(target, &alert));
+        assert_eq!(classify(&rejected), Some(RetryClass::Connection));
+        let version = SendFailure::handshake(target, &quinn::ConnectionError::VersionMismatch);
+        assert_eq!(classify(&version.into()), None);
+    }
+
+    #[test]
+    fn test_backoff_doubles_until_exhausted() {
+        let policy = RetryPolicy {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(20),
+            max_backoff: Duration::from_millis(50),
+            retry_on: parse_retry_on("connection").unwrap(),
+        };
+        let reset = ScramjetError::TransportError(quinn::ConnectionError::Reset);
+        assert_eq!(policy.backoff(1, &reset), Some(Duration::from_millis(20)));
+        assert_eq!(policy.backoff(2, &reset), Some(Duration::from_millis(40)));
+        assert_eq!(policy.backoff(3, &reset), Some(Duration::from_millis(50)));
+        assert_eq!(policy.backoff(4, &reset), None);
+
+        // Stream refusals are not in this policy's classes
+        let stopped = ScramjetError::WriteError(quinn::WriteError::Stopped(0u32.into()));
+        assert_eq!(policy.backoff(1, &stopped), None);
+        assert_eq!(RetryPolicy::NEVER.backoff(1, &reset), None);
+    }
+
+    #[test]
+    fn test_parse_retry_on() {
+        assert_eq!(parse_retry_on("all").unwrap(), RetryOn::ALL);
+        assert_eq!(parse_retry_on("none").unwrap(), RetryOn::NONE);
+        assert!(parse_retry_on(" Stream ").unwrap().stream);
+        assert!(parse_retry_on("timeouts").is_err());
+    }
+}
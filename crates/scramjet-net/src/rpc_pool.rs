@@ -0,0 +1,306 @@
+//! RPC endpoint pool with health-based failover.
+//!
+//! Topology, schedule, slot and blockhash calls go through the pool instead of
+//! a single `RpcClient`. Each call tries endpoints best-first and falls over to
+//! the next one on error. Per-endpoint latency and error rate are tracked as
+//! moving averages; an endpoint that fails `demote_after` calls in a row is
+//! demoted (tried last) for `demote_for`, then gets one call on probation.
+
+use log::{debug, warn};
+use scramjet_common::{Config, ScramjetError};
+use serde::{Deserialize, Serialize};
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Weight of the newest sample in the latency / error rate moving averages
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Latency penalty per unit of error rate when ranking endpoints (1.0 = 5x)
+const ERROR_RATE_PENALTY: f64 = 4.0;
+
+/// Latency assumed for an endpoint without a successful call yet: ranked
+/// behind every measured one, so it never preempts a healthy primary
+const UNMEASURED_LATENCY_MS: f64 = 10_000.0;
+
+#[derive(Debug, Default)]
+struct EndpointStats {
+    latency_ms: Option<f64>,
+    error_rate: f64,
+    consecutive_failures: u32,
+    demoted_until: Option<Instant>,
+    requests: u64,
+    errors: u64,
+}
+
+impl EndpointStats {
+    fn is_demoted(&self, now: Instant) -> bool {
+        self.demoted_until.is_some_and(|until| now < until)
+    }
+
+    /// Ranking score: lower is better (unmeasured endpoints rank last, those
+    /// that only ever failed behind those never tried)
+    fn score(&self) -> f64 {
+        self.latency_ms.unwrap_or(UNMEASURED_LATENCY_MS)
+            * (1.0 + ERROR_RATE_PENALTY * self.error_rate)
+    }
+}
+
+struct RpcEndpoint {
+    url: String,
+    client: Arc<RpcClient>,
+    stats: Mutex<EndpointStats>,
+}
+
+/// Health snapshot of one endpoint (status / control socket)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcEndpointHealth {
+    pub url: String,
+    /// Moving-average call latency (None until the first success)
+    pub latency_ms: Option<u64>,
+    /// Moving-average error rate in [0, 1]
+    pub error_rate: f64,
+    pub demoted: bool,
+    pub requests: u64,
+    pub errors: u64,
+}
+
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    demote_after: u32,
+    demote_for: Duration,
+}
+
+impl RpcPool {
+    /// Pool over `urls` in preference order (the first is the primary)
+    pub fn new(
+        urls: Vec<String>,
+        demote_after: u32,
+        demote_for: Duration,
+    ) -> Result<Self, ScramjetError> {
+        if urls.is_empty() {
+            return Err(ScramjetError::ConfigError(
+                "RPC pool needs at least one endpoint".into(),
+            ));
+        }
+        let endpoints = urls
+            .into_iter()
+            .map(|url| RpcEndpoint {
+                client: Arc::new(RpcClient::new(url.clone())),
+                url,
+                stats: Mutex::new(EndpointStats::default()),
+            })
+            .collect();
+        Ok(Self {
+            endpoints,
+            demote_after: demote_after.max(1),
+            demote_for,
+        })
+    }
+
+    /// Single endpoint (failover disabled; health is still tracked)
+    pub fn single(url: String) -> Self {
+        Self {
+            endpoints: vec![RpcEndpoint {
+                client: Arc::new(RpcClient::new(url.clone())),
+                url,
+                stats: Mutex::new(EndpointStats::default()),
+            }],
+            demote_after: u32::MAX,
+            demote_for: Duration::ZERO,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ScramjetError> {
+        Self::new(
+            config.rpc_urls(),
+            config.rpc_demote_after_failures,
+            config.rpc_demote_duration(),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// Endpoint indices in the order calls try them: healthy endpoints by
+    /// score (ties keep configured order), then demoted ones
+    fn ranked(&self, now: Instant) -> Vec<usize> {
+        let mut ranked: Vec<(bool, f64, usize)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, endpoint)| {
+                let stats = endpoint.stats.lock().unwrap();
+                (stats.is_demoted(now), stats.score(), i)
+            })
+            .collect();
+        ranked.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        ranked.into_iter().map(|(_, _, i)| i).collect()
+    }
+
+    /// Best endpoint's client, for one-off calls outside the pool (status
+    /// polling, nonce accounts)
+    pub fn client(&self) -> Arc<RpcClient> {
+        let best = self.ranked(Instant::now())[0];
+        self.endpoints[best].client.clone()
+    }
+
+    /// Run `call` against endpoints best-first until one succeeds.
+    /// Returns the last endpoint's error if all of them fail.
+    pub async fn call<T, F, Fut>(&self, call: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut last_err = None;
+        for i in self.ranked(Instant::now()) {
+            let endpoint = &self.endpoints[i];
+            let started = Instant::now();
+            match call(endpoint.client.clone()).await {
+                Ok(value) => {
+                    self.record(i, Ok(started.elapsed()));
+                    return Ok(value);
+                }
+                Err(e) => {
+                    self.record(i, Err(()));
+                    if self.endpoints.len() > 1 {
+                        debug!("RPC {} failed, trying next endpoint: {}", endpoint.url, e);
+                    }
+                    last_err = Some(e);
+                }
+            }
+        }
+        Err(last_err.expect("RPC pool is never empty"))
+    }
+
+    fn record(&self, index: usize, result: Result<Duration, ()>) {
+        let endpoint = &self.endpoints[index];
+        let mut stats = endpoint.stats.lock().unwrap();
+        stats.requests += 1;
+        match result {
+            Ok(latency) => {
+                let ms = latency.as_secs_f64() * 1000.0;
+                stats.latency_ms = Some(match stats.latency_ms {
+                    Some(avg) => avg + EWMA_ALPHA * (ms - avg),
+                    None => ms,
+                });
+                stats.error_rate *= 1.0 - EWMA_ALPHA;
+                stats.consecutive_failures = 0;
+                if stats.demoted_until.take().is_some() {
+                    debug!("RPC {} recovered", endpoint.url);
+                }
+            }
+            Err(()) => {
+                stats.errors += 1;
+                stats.error_rate += EWMA_ALPHA * (1.0 - stats.error_rate);
+                stats.consecutive_failures = stats.consecutive_failures.saturating_add(1);
+                // Failing on probation (or crossing the threshold) demotes again
+                if stats.consecutive_failures >= self.demote_after {
+                    let now = Instant::now();
+                    if !stats.is_demoted(now) {
+                        warn!(
+                            "RPC {} demoted for {:?} after {} consecutive failures",
+                            endpoint.url, self.demote_for, stats.consecutive_failures
+                        );
+                        stats.demoted_until = Some(now + self.demote_for);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Per-endpoint health in configured order
+    pub fn health(&self) -> Vec<RpcEndpointHealth> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let stats = endpoint.stats.lock().unwrap();
+                RpcEndpointHealth {
+                    url: endpoint.url.clone(),
+                    latency_ms: stats.latency_ms.map(|ms| ms.round() as u64),
+                    error_rate: stats.error_rate,
+                    demoted: stats.is_demoted(now),
+                    requests: stats.requests,
+                    errors: stats.errors,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool() -> RpcPool {
+        RpcPool::new(
+            vec!["http://primary".into(), "http://fallback".into()],
+            2,
+            Duration::from_secs(30),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_ranking_prefers_fast_healthy_endpoints() {
+        let pool = pool();
+        let now = Instant::now();
+        assert_eq!(pool.ranked(now), vec![0, 1]);
+
+        pool.record(0, Ok(Duration::from_millis(30)));
+        pool.record(1, Ok(Duration::from_millis(20)));
+        assert_eq!(pool.ranked(now), vec![1, 0]);
+
+        // One error makes the faster endpoint score worse than the slower one
+        pool.record(1, Err(()));
+        let health = pool.health();
+        assert!(!health[1].demoted);
+        assert_eq!((health[1].requests, health[1].errors), (2, 1));
+        assert_eq!(pool.ranked(now), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_unmeasured_endpoints_rank_last() {
+        let pool = RpcPool::new(
+            vec![
+                "http://primary".into(),
+                "http://untried".into(),
+                "http://failing".into(),
+            ],
+            100,
+            Duration::from_secs(30),
+        )
+        .unwrap();
+        let now = Instant::now();
+        pool.record(2, Err(()));
+        assert_eq!(pool.ranked(now), vec![0, 1, 2]);
+
+        // A healthy primary keeps its place ahead of endpoints never measured
+        pool.record(0, Ok(Duration::from_millis(900)));
+        assert_eq!(pool.ranked(now), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_demotion_and_recovery() {
+        let pool = pool();
+        pool.record(0, Err(()));
+        assert!(!pool.health()[0].demoted);
+        pool.record(0, Err(()));
+        assert!(pool.health()[0].demoted);
+        assert_eq!(pool.ranked(Instant::now()), vec![1, 0]);
+        assert_eq!(pool.client().url(), "http://fallback");
+
+        // A success (e.g. the last-resort attempt) lifts the demotion
+        pool.record(0, Ok(Duration::from_millis(5)));
+        assert!(!pool.health()[0].demoted);
+        assert!(RpcPool::new(vec![], 3, Duration::ZERO).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Dedicated runtime for the send path.
+//!
+//! With `SEND_RUNTIME_THREADS` > 0 the QUIC endpoints run on their own
+//! multi-thread tokio runtime: quinn spawns every endpoint and connection
+//! driver (packetization, pacing, UDP sends, timers) there, and the library's
+//! sends are executed there too. RPC polling, Geyser decoding and the other
+//! background loops stay on the caller's runtime, so they cannot hold up a
+//! latency-critical write. `SEND_RUNTIME_CORES` additionally pins the
+//! runtime's threads to CPU cores (Linux only).
+
+use log::{debug, warn};
+use quinn::{AsyncTimer, AsyncUdpSocket, Runtime as QuicRuntime, TokioRuntime};
+use scramjet_common::{Config, ScramjetError};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::runtime::{Handle, Runtime};
+
+/// Tokio runtime owned by the send path (shut down in the background when
+/// dropped, so dropping it from async code does not block)
+pub struct SendRuntime {
+    runtime: Option<Runtime>,
+    handle: Handle,
+}
+
+impl SendRuntime {
+    /// The runtime `SEND_RUNTIME_THREADS` / `SEND_RUNTIME_CORES` ask for
+    /// (None = share the caller's runtime)
+    pub fn from_config(config: &Config) -> Result<Option<Self>, ScramjetError> {
+        if config.send_runtime_threads == 0 {
+            return Ok(None);
+        }
+        Self::new(config.send_runtime_threads, config.send_runtime_core_ids()).map(Some)
+    }
+
+    /// `threads` workers, pinned round-robin to `cores` (empty = unpinned)
+    pub fn new(threads: usize, cores: Vec<usize>) -> Result<Self, ScramjetError> {
+        let next_core = AtomicUsize::new(0);
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(threads.max(1))
+            .thread_name("scramjet-send")
+            .enable_all()
+            .on_thread_start(move || {
+                if cores.is_empty() {
+                    return;
+                }
+                let core = cores[next_core.fetch_add(1, Ordering::Relaxed) % cores.len()];
+                match pin_to_core(core) {
+                    Ok(()) => debug!("Send runtime: Thread pinned to core {}", core),
+                    Err(e) => warn!("Send runtime: Failed to pin to core {}: {}", core, e),
+                }
+            })
+            .build()
+            .map_err(|e| ScramjetError::ConfigError(format!("Send runtime: {}", e)))?;
+        let handle = runtime.handle().clone();
+        Ok(Self {
+            runtime: Some(runtime),
+            handle,
+        })
+    }
+
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// quinn runtime that spawns drivers, arms timers and registers sockets
+    /// on this runtime, whichever runtime the endpoint is used from
+    pub fn quinn_runtime(&self) -> Arc<dyn QuicRuntime> {
+        Arc::new(QuinnRuntime {
+            handle: self.handle.clone(),
+        })
+    }
+
+    /// Run `future` on this runtime and wait for its output
+    pub async fn run<F>(&self, future: F) -> Result<F::Output, ScramjetError>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        match self.handle.spawn(future).await {
+            Ok(output) => Ok(output),
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(_) => Err(ScramjetError::ShuttingDown),
+        }
+    }
+}
+
+impl Drop for SendRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.runtime.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+/// `TokioRuntime` bound to one runtime's handle
+#[derive(Debug)]
+struct QuinnRuntime {
+    handle: Handle,
+}
+
+impl QuicRuntime for QuinnRuntime {
+    fn new_timer(&self, at: Instant) -> Pin<Box<dyn AsyncTimer>> {
+        let _guard = self.handle.enter();
+        Box::pin(tokio::time::sleep_until(at.into()))
+    }
+
+    fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        self.handle.spawn(future);
+    }
+
+    fn wrap_udp_socket(
+        &self,
+        socket: std::net::UdpSocket,
+    ) -> std::io::Result<Arc<dyn AsyncUdpSocket>> {
+        let _guard = self.handle.enter();
+        TokioRuntime.wrap_udp_socket(socket)
+    }
+
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn pin_to_core(core: usize) -> Result<(), String> {
+    use nix::sched::{sched_setaffinity, CpuSet};
+    use nix::unistd::Pid;
+
+    let mut set = CpuSet::new();
+    set.set(core).map_err(|e| e.to_string())?;
+    sched_setaffinity(Pid::from_raw(0), &set).map_err(|e| e.to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_to_core(_core: usize) -> Result<(), String> {
+    Err("core pinning is only supported on Linux".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_on_dedicated_threads() {
+        let runtime = SendRuntime::new(1, Vec::new()).unwrap();
+        let name = runtime
+            .run(async { std::thread::current().name().map(String::from) })
+            .await
+            .unwrap();
+        assert_eq!(name.as_deref(), Some("scramjet-send"));
+
+        // Dropped from async code without blocking it
+        drop(runtime);
+    }
+}
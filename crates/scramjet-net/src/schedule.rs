@@ -0,0 +1,495 @@
+//! Leader schedule stored as sorted leader windows.
+//!
+//! Leaders produce in runs of consecutive slots (4 on mainnet), so the
+//! per-slot schedule collapses into ~4x fewer sorted windows, and an epoch
+//! (~432k slots) fits in ~108k compact entries instead of a hash map entry
+//! per slot. Point lookups are a binary search; range queries ("who leads
+//! the next N slots?") are a binary search plus a short scan.
+//!
+//! Landing odds also depend on *when* in a window a transaction arrives, so
+//! the index can place a send at a fixed offset into the current or next
+//! window (`firing_point`), or tell how much of the current window is left
+//! to spread sends over (`remaining_in_window`).
+//!
+//! The schedule's windows are shared between copies of the index; windows
+//! added one at a time (`insert`) are kept apart, so adding one to a copy
+//! does not copy the epoch.
+
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Contiguous run of slots led by one validator (inclusive bounds)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeaderWindow {
+    pub leader: Pubkey,
+    pub start_slot: u64,
+    pub end_slot: u64,
+}
+
+impl LeaderWindow {
+    pub fn contains(&self, slot: u64) -> bool {
+        self.start_slot <= slot && slot <= self.end_slot
+    }
+}
+
+/// A send timed to a fixed offset into a leader window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FiringPoint {
+    pub leader: Pubkey,
+    /// Window slot the offset falls in
+    pub slot: u64,
+    /// Estimated local time of the offset
+    pub at: Instant,
+}
+
+/// Schedule collapsed into sorted, non-overlapping leader windows
+#[derive(Debug, Default, Clone)]
+pub struct LeaderWindows {
+    windows: Arc<Vec<LeaderWindow>>,
+    /// Added by `insert` (sorted, overlapping nothing in `windows`)
+    inserted: Vec<LeaderWindow>,
+}
+
+/// Windows of a [`LeaderWindows`] range, in slot order
+#[derive(Debug, Clone, Copy)]
+pub struct Windows<'a> {
+    scheduled: &'a [LeaderWindow],
+    inserted: &'a [LeaderWindow],
+}
+
+impl<'a> Windows<'a> {
+    pub fn iter(&self) -> WindowsIter<'a> {
+        WindowsIter(*self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.scheduled.len() + self.inserted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a> IntoIterator for Windows<'a> {
+    type Item = &'a LeaderWindow;
+    type IntoIter = WindowsIter<'a>;
+
+    fn into_iter(self) -> WindowsIter<'a> {
+        self.iter()
+    }
+}
+
+/// Iterator over [`Windows`]: merges the scheduled and inserted runs
+#[derive(Debug, Clone)]
+pub struct WindowsIter<'a>(Windows<'a>);
+
+impl<'a> Iterator for WindowsIter<'a> {
+    type Item = &'a LeaderWindow;
+
+    fn next(&mut self) -> Option<&'a LeaderWindow> {
+        let Windows {
+            scheduled,
+            inserted,
+        } = &mut self.0;
+        let run = match (scheduled.first(), inserted.first()) {
+            (Some(next), Some(added)) if added.start_slot < next.start_slot => inserted,
+            (None, _) => inserted,
+            _ => scheduled,
+        };
+        let (first, rest) = run.split_first()?;
+        *run = rest;
+        Some(first)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.0.len(), Some(self.0.len()))
+    }
+}
+
+impl ExactSizeIterator for WindowsIter<'_> {}
+
+impl LeaderWindows {
+    /// Build from a slot -> leader map. Gaps in the slot range split windows.
+    pub fn from_schedule(schedule: &HashMap<u64, Pubkey>) -> Self {
+        let mut slots: Vec<(u64, Pubkey)> = schedule.iter().map(|(s, pk)| (*s, *pk)).collect();
+        slots.sort_unstable_by_key(|(slot, _)| *slot);
+
+        let mut windows: Vec<LeaderWindow> = Vec::with_capacity(slots.len() / 4 + 1);
+        for (slot, leader) in slots {
+            match windows.last_mut() {
+                Some(last) if last.leader == leader && last.end_slot + 1 == slot => {
+                    last.end_slot = slot;
+                }
+                _ => windows.push(LeaderWindow {
+                    leader,
+                    start_slot: slot,
+                    end_slot: slot,
+                }),
+            }
+        }
+        Self {
+            windows: Arc::new(windows),
+            inserted: Vec::new(),
+        }
+    }
+
+    /// Window containing `slot`, if scheduled
+    pub fn window_at(&self, slot: u64) -> Option<&LeaderWindow> {
+        window_in(&self.windows, slot).or_else(|| window_in(&self.inserted, slot))
+    }
+
+    /// Windows overlapping the slot range `(current_slot, current_slot + lookahead]`
+    pub fn upcoming(&self, current_slot: u64, lookahead: u64) -> Windows<'_> {
+        let first = current_slot.saturating_add(1);
+        let last = current_slot.saturating_add(lookahead);
+        if lookahead == 0 {
+            return Windows {
+                scheduled: &[],
+                inserted: &[],
+            };
+        }
+        Windows {
+            scheduled: range_in(&self.windows, first, last),
+            inserted: range_in(&self.inserted, first, last),
+        }
+    }
+
+    /// Add a window for slots not covered by any existing window.
+    /// Returns false (and changes nothing) if it would overlap one.
+    pub fn insert(&mut self, window: LeaderWindow) -> bool {
+        let overlaps = |windows: &[LeaderWindow]| {
+            !range_in(windows, window.start_slot, window.end_slot).is_empty()
+        };
+        if overlaps(&self.windows) || overlaps(&self.inserted) {
+            return false;
+        }
+        let idx = self
+            .inserted
+            .partition_point(|w| w.end_slot < window.start_slot);
+        self.inserted.insert(idx, window);
+        true
+    }
+
+    /// Merge in `other`'s windows. Slots already covered keep their leader;
+    /// only the uncovered parts of `other`'s windows are added.
+    pub fn merge(&mut self, other: &LeaderWindows) {
+        let windows = Arc::make_mut(&mut self.windows);
+        // Inserted windows count as covered, and join the rest from here on
+        if !self.inserted.is_empty() {
+            windows.append(&mut self.inserted);
+            windows.sort_unstable_by_key(|w| w.start_slot);
+        }
+        let mut added = Vec::new();
+        for incoming in other.iter() {
+            let mut next_free = incoming.start_slot;
+            let first = windows.partition_point(|w| w.end_slot < incoming.start_slot);
+            for existing in windows[first..]
+                .iter()
+                .take_while(|w| w.start_slot <= incoming.end_slot)
+            {
+                if existing.start_slot > next_free {
+                    added.push(LeaderWindow {
+                        end_slot: existing.start_slot - 1,
+                        start_slot: next_free,
+                        ..*incoming
+                    });
+                }
+                next_free = existing.end_slot.saturating_add(1);
+            }
+            if next_free <= incoming.end_slot {
+                added.push(LeaderWindow {
+                    start_slot: next_free,
+                    ..*incoming
+                });
+            }
+        }
+        if added.is_empty() {
+            return;
+        }
+        windows.extend(added);
+        windows.sort_unstable_by_key(|w| w.start_slot);
+        // Re-join windows split only by the merge boundary
+        windows.dedup_by(|next, prev| {
+            let joins = prev.leader == next.leader && prev.end_slot + 1 == next.start_slot;
+            if joins {
+                prev.end_slot = next.end_slot;
+            }
+            joins
+        });
+    }
+
+    /// All windows in slot order
+    pub fn iter(&self) -> WindowsIter<'_> {
+        Windows {
+            scheduled: &self.windows,
+            inserted: &self.inserted,
+        }
+        .iter()
+    }
+
+    /// Point `offset` into the window of `current_slot`, or into the next
+    /// window when that point is already behind `now`. Window starts are
+    /// extrapolated from `slot_start` (when `current_slot` was first seen) at
+    /// `slot_duration` per slot; offsets past a window's last slot are clamped
+    /// to the start of that slot.
+    pub fn firing_point(
+        &self,
+        current_slot: u64,
+        slot_start: Instant,
+        slot_duration: Duration,
+        offset: Duration,
+        now: Instant,
+    ) -> Option<FiringPoint> {
+        let window = self.window_at(current_slot)?;
+        let window_start =
+            slot_start.checked_sub(slots(slot_duration, current_slot - window.start_slot)?)?;
+        let point = point_in(window, window_start, slot_duration, offset)?;
+        if point.at >= now {
+            return Some(point);
+        }
+        let next = self.window_at(window.end_slot + 1)?;
+        let next_start = slot_start + slots(slot_duration, next.start_slot - current_slot)?;
+        point_in(next, next_start, slot_duration, offset)
+    }
+
+    /// Time left until the end of `current_slot`'s window at `now`, with the
+    /// window's end extrapolated like `firing_point` (zero once it has passed)
+    pub fn remaining_in_window(
+        &self,
+        current_slot: u64,
+        slot_start: Instant,
+        slot_duration: Duration,
+        now: Instant,
+    ) -> Option<Duration> {
+        let window = self.window_at(current_slot)?;
+        let end = slot_start + slots(slot_duration, window.end_slot - current_slot + 1)?;
+        Some(end.saturating_duration_since(now))
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len() + self.inserted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Window of sorted `windows` containing `slot`
+fn window_in(windows: &[LeaderWindow], slot: u64) -> Option<&LeaderWindow> {
+    let idx = windows.partition_point(|w| w.end_slot < slot);
+    windows.get(idx).filter(|w| w.contains(slot))
+}
+
+/// Windows of sorted `windows` overlapping `first..=last`
+fn range_in(windows: &[LeaderWindow], first: u64, last: u64) -> &[LeaderWindow] {
+    let start = windows.partition_point(|w| w.end_slot < first);
+    let end = windows.partition_point(|w| w.start_slot <= last);
+    &windows[start..end.max(start)]
+}
+
+fn slots(slot_duration: Duration, count: u64) -> Option<Duration> {
+    slot_duration.checked_mul(u32::try_from(count).ok()?)
+}
+
+fn point_in(
+    window: &LeaderWindow,
+    start: Instant,
+    slot_duration: Duration,
+    offset: Duration,
+) -> Option<FiringPoint> {
+    let offset = offset.min(slots(slot_duration, window.end_slot - window.start_slot)?);
+    let index = offset.as_micros() / slot_duration.as_micros().max(1);
+    Some(FiringPoint {
+        leader: window.leader,
+        slot: window.start_slot + index as u64,
+        at: start + offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(entries: &[(u64, Pubkey)]) -> HashMap<u64, Pubkey> {
+        entries.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_windows_collapse_consecutive_slots() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let windows = LeaderWindows::from_schedule(&schedule(&[
+            (100, a),
+            (101, a),
+            (102, b),
+            (103, b),
+            (105, b), // gap at 104 splits the window
+        ]));
+
+        assert_eq!(windows.len(), 3);
+        assert_eq!(windows.window_at(101).unwrap().leader, a);
+        assert_eq!(windows.window_at(103).unwrap().start_slot, 102);
+        assert!(windows.window_at(104).is_none());
+        assert!(windows.window_at(99).is_none());
+    }
+
+    #[test]
+    fn test_upcoming_range() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let c = Pubkey::new_unique();
+        let windows = LeaderWindows::from_schedule(&schedule(&[
+            (100, a),
+            (101, a),
+            (102, b),
+            (103, b),
+            (104, c),
+        ]));
+
+        // (100, 102] overlaps windows a (partially) and b
+        let upcoming: Vec<Pubkey> = windows.upcoming(100, 2).iter().map(|w| w.leader).collect();
+        assert_eq!(upcoming, vec![a, b]);
+        assert!(windows.upcoming(100, 0).is_empty());
+        assert!(windows.upcoming(104, 10).is_empty());
+        assert_eq!(windows.upcoming(0, u64::MAX).len(), 3);
+    }
+
+    #[test]
+    fn test_insert_rejects_overlap() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let mut windows = LeaderWindows::from_schedule(&schedule(&[(100, a), (101, a), (108, a)]));
+
+        let derived = LeaderWindow {
+            leader: b,
+            start_slot: 104,
+            end_slot: 107,
+        };
+        let scheduled = windows.clone();
+        assert!(windows.insert(derived));
+        assert_eq!(windows.window_at(105).unwrap().leader, b);
+        assert_eq!(windows.upcoming(101, 10).len(), 2);
+        // The insert went beside the scheduled windows, still shared
+        assert!(Arc::ptr_eq(&windows.windows, &scheduled.windows));
+        let starts: Vec<u64> = windows.iter().map(|w| w.start_slot).collect();
+        assert_eq!(starts, vec![100, 104, 108]);
+
+        let overlapping = LeaderWindow {
+            leader: b,
+            start_slot: 101,
+            end_slot: 103,
+        };
+        assert!(!windows.insert(overlapping));
+        assert_eq!(windows.window_at(101).unwrap().leader, a);
+    }
+
+    #[test]
+    fn test_merge_keeps_covered_slots() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut windows = LeaderWindows::from_schedule(&schedule(&[(100, a), (101, a), (104, a)]));
+        windows.merge(&LeaderWindows::from_schedule(&schedule(&[
+            (101, b),
+            (102, b),
+            (103, b),
+            (104, b),
+            (105, b),
+            (106, a),
+        ])));
+
+        let merged: Vec<(Pubkey, u64, u64)> = windows
+            .iter()
+            .map(|w| (w.leader, w.start_slot, w.end_slot))
+            .collect();
+        assert_eq!(
+            merged,
+            vec![
+                (a, 100, 101),
+                (b, 102, 103),
+                (a, 104, 104),
+                (b, 105, 105),
+                (a, 106, 106)
+            ]
+        );
+
+        // Contiguous same-leader windows are re-joined across the boundary
+        windows.merge(&LeaderWindows::from_schedule(&schedule(&[
+            (107, a),
+            (108, a),
+        ])));
+        assert_eq!(windows.window_at(107).map(|w| w.start_slot), Some(106));
+        assert_eq!(windows.window_at(108).map(|w| w.end_slot), Some(108));
+    }
+
+    #[test]
+    fn test_firing_point_phase() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let windows = LeaderWindows::from_schedule(&schedule(&[
+            (100, a),
+            (101, a),
+            (102, a),
+            (103, a),
+            (104, b),
+            (105, b),
+            (106, b),
+            (107, b),
+        ]));
+        let slot = Duration::from_millis(400);
+        let t0 = Instant::now();
+        // Slot 101 seen at t0: a's window started 400ms earlier
+        let seen = t0 + Duration::from_millis(400);
+
+        // 500ms in is still ahead: slot 101, 100ms after it was seen
+        let point = windows
+            .firing_point(101, seen, slot, Duration::from_millis(500), seen)
+            .unwrap();
+        assert_eq!((point.leader, point.slot), (a, 101));
+        assert_eq!(point.at, t0 + Duration::from_millis(500));
+
+        // 100ms in has passed: wait for b's window instead
+        let point = windows
+            .firing_point(101, seen, slot, Duration::from_millis(100), seen)
+            .unwrap();
+        assert_eq!((point.leader, point.slot), (b, 104));
+        assert_eq!(point.at, t0 + Duration::from_millis(1_700));
+
+        // Past the window: clamped to the start of its last slot
+        let point = windows
+            .firing_point(100, t0, slot, Duration::from_secs(5), t0)
+            .unwrap();
+        assert_eq!(point.slot, 103);
+        assert_eq!(point.at, t0 + Duration::from_millis(1_200));
+
+        // No window after b's is scheduled
+        assert!(windows
+            .firing_point(105, t0, slot, Duration::ZERO, t0)
+            .is_none());
+    }
+
+    #[test]
+    fn test_remaining_in_window() {
+        let a = Pubkey::new_unique();
+        let windows =
+            LeaderWindows::from_schedule(&schedule(&[(100, a), (101, a), (102, a), (103, a)]));
+        let slot = Duration::from_millis(400);
+        let t0 = Instant::now();
+
+        // Slot 101 seen at t0, 100ms ago: slots 101-103 end 1.2s after t0
+        let now = t0 + Duration::from_millis(100);
+        assert_eq!(
+            windows.remaining_in_window(101, t0, slot, now),
+            Some(Duration::from_millis(1_100))
+        );
+        // Overran the estimate (slow slots): nothing left
+        let late = t0 + Duration::from_secs(2);
+        assert_eq!(
+            windows.remaining_in_window(103, t0, slot, late),
+            Some(Duration::ZERO)
+        );
+        assert!(windows.remaining_in_window(104, t0, slot, t0).is_none());
+    }
+}
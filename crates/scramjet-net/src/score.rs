@@ -0,0 +1,225 @@
+//! Per-validator send failure scoring (auto-blocklist).
+//!
+//! Every send attempt reported by the engine lands in a fixed window per
+//! target. Only transient transport failures count against a validator
+//! (stream refused, connection reset or closed, timeouts: the same classes
+//! `RetryPolicy` retries); local errors are ignored. When a target's failure
+//! rate over a window with enough samples crosses the threshold, it trips:
+//! the enforcer resolves its identity and puts it on the Shield blocklist
+//! for `block_ttl`, after which it is unblocked and scored afresh.
+
+use dashmap::DashMap;
+use log::{debug, info, warn};
+use scramjet_common::{Config, ScramjetError};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+use crate::blocklist::BlocklistManager;
+use crate::cartographer::Cartographer;
+use crate::retry;
+
+/// How often the enforcer applies trips and expires temporary blocks
+const ENFORCE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Auto-blocklist thresholds (`AUTO_BLOCK_*`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoreConfig {
+    /// Scoring window per validator
+    pub window: Duration,
+    /// Attempts needed in a window before its failure rate counts
+    pub min_samples: u32,
+    /// Failure rate in (0, 1] that trips the auto-block
+    pub failure_rate: f64,
+    /// How long a tripped validator stays blocked
+    pub block_ttl: Duration,
+}
+
+impl ScoreConfig {
+    /// None when auto-blocking is disabled (`AUTO_BLOCK_TTL_SECS=0`)
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.auto_block_ttl_secs == 0 {
+            return None;
+        }
+        Some(Self {
+            window: config.auto_block_window(),
+            min_samples: config.auto_block_min_samples,
+            failure_rate: config.auto_block_failure_rate,
+            block_ttl: config.auto_block_ttl(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started: Instant,
+    attempts: u32,
+    failures: u32,
+}
+
+pub struct ValidatorScore {
+    config: ScoreConfig,
+    windows: DashMap<SocketAddr, Window>,
+    tripped: Mutex<Vec<SocketAddr>>,
+}
+
+impl ValidatorScore {
+    pub fn new(config: ScoreConfig) -> Self {
+        Self {
+            config,
+            windows: DashMap::new(),
+            tripped: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn config(&self) -> &ScoreConfig {
+        &self.config
+    }
+
+    /// Feed one send attempt (`error` = None on success).
+    /// Returns true if this attempt tripped the auto-block for `target`.
+    pub fn record(&self, target: SocketAddr, error: Option<&ScramjetError>) -> bool {
+        let failed = match error {
+            None => false,
+            Some(e) if retry::classify(e).is_some() => true,
+            Some(_) => return false, // Not the validator's fault
+        };
+
+        let now = Instant::now();
+        let mut window = self.windows.entry(target).or_insert(Window {
+            started: now,
+            attempts: 0,
+            failures: 0,
+        });
+        if now.duration_since(window.started) > self.config.window {
+            *window = Window {
+                started: now,
+                attempts: 0,
+                failures: 0,
+            };
+        }
+        window.attempts += 1;
+        window.failures += u32::from(failed);
+
+        let rate = window.failures as f64 / window.attempts as f64;
+        if !failed || window.attempts < self.config.min_samples || rate < self.config.failure_rate {
+            return false;
+        }
+        debug!(
+            "Score: {} failed {}/{} sends ({:.0}%)",
+            target,
+            window.failures,
+            window.attempts,
+            rate * 100.0
+        );
+        // Start over: the next verdict needs a fresh window
+        *window = Window {
+            started: now,
+            attempts: 0,
+            failures: 0,
+        };
+        drop(window);
+        self.tripped.lock().unwrap().push(target);
+        true
+    }
+
+    /// Failure rate in the target's current window (None without attempts)
+    pub fn failure_rate(&self, target: &SocketAddr) -> Option<f64> {
+        let window = self.windows.get(target)?;
+        (window.attempts > 0).then(|| window.failures as f64 / window.attempts as f64)
+    }
+
+    /// Targets that tripped since the last call
+    pub fn take_tripped(&self) -> Vec<SocketAddr> {
+        std::mem::take(&mut *self.tripped.lock().unwrap())
+    }
+
+    /// Apply trips (resolve identity, block for `block_ttl`) and expire
+    /// temporary blocks until the task is aborted
+    pub fn spawn_enforcer(
+        self: Arc<Self>,
+        cartographer: Arc<Cartographer>,
+        shield: Arc<BlocklistManager>,
+    ) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(ENFORCE_INTERVAL);
+            loop {
+                ticker.tick().await;
+                for target in self.take_tripped() {
+                    let Some(identity) = cartographer.identity_for(target).await else {
+                        debug!("Score: {} tripped but is not in the topology", target);
+                        continue;
+                    };
+                    if shield
+                        .block_temporarily(identity, self.config.block_ttl)
+                        .await
+                    {
+                        warn!(
+                            "Shield: Auto-blocked {} ({}) for {:?}: send failure rate >= {:.0}%",
+                            identity,
+                            target,
+                            self.config.block_ttl,
+                            self.config.failure_rate * 100.0
+                        );
+                    }
+                }
+                for identity in shield.expire_temporary().await {
+                    info!("Shield: Auto-block of {} expired", identity);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score() -> ValidatorScore {
+        ValidatorScore::new(ScoreConfig {
+            window: Duration::from_secs(60),
+            min_samples: 4,
+            failure_rate: 0.5,
+            block_ttl: Duration::from_secs(600),
+        })
+    }
+
+    #[test]
+    fn test_trips_on_failure_rate_after_min_samples() {
+        let score = score();
+        let target: SocketAddr = "1.1.1.1:8009".parse().unwrap();
+        let reset = ScramjetError::TransportError(quinn::ConnectionError::Reset);
+
+        assert!(!score.record(target, Some(&reset)));
+        assert!(!score.record(target, None));
+        assert!(!score.record(target, Some(&reset)));
+        assert_eq!(score.failure_rate(&target), Some(2.0 / 3.0));
+        // Fourth sample reaches min_samples at 75% failures
+        assert!(score.record(target, Some(&reset)));
+        assert_eq!(score.take_tripped(), vec![target]);
+        assert!(score.take_tripped().is_empty());
+
+        // Window starts over after a trip
+        assert_eq!(score.failure_rate(&target), None);
+    }
+
+    #[test]
+    fn test_local_errors_do_not_count() {
+        let score = score();
+        let target: SocketAddr = "2.2.2.2:8009".parse().unwrap();
+        let local = ScramjetError::ConnectionError("bad addr".into());
+        for _ in 0..10 {
+            assert!(!score.record(target, Some(&local)));
+        }
+        assert_eq!(score.failure_rate(&target), None);
+
+        // Successes keep a mostly-healthy validator below the threshold
+        let stopped = ScramjetError::WriteError(quinn::WriteError::Stopped(0u32.into()));
+        for _ in 0..3 {
+            score.record(target, None);
+        }
+        assert!(!score.record(target, Some(&stopped)));
+        assert_eq!(score.failure_rate(&target), Some(0.25));
+    }
+}
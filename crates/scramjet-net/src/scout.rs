@@ -0,0 +1,247 @@
+//! Scout warm-connection budgeting.
+//!
+//! When the lookahead window holds more distinct leaders than we are willing
+//! to keep warm, the budget goes to the leaders that matter most: the ones
+//! owning the most upcoming slots, discounted by how well our sends to them
+//! have historically landed. Between equally weighted leaders (the usual
+//! case: one 4-slot window each) the one with more stake is warmed first.
+//!
+//! The chosen leaders are warmed in order of when their handshake has to
+//! start: a distant leader (high measured RTT) goes ahead of a nearby one
+//! whose slot comes slightly earlier, so both are connected in time.
+
+use crate::cartographer::Cartographer;
+use crate::engine::QuicEngine;
+use dashmap::DashMap;
+use log::debug;
+use solana_sdk::pubkey::Pubkey;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Sends to a leader required before its landing rate affects allocation
+const MIN_HISTORY_SAMPLES: u64 = 10;
+
+/// RTT assumed for a leader that has not been measured
+const UNKNOWN_RTT: Duration = Duration::from_millis(100);
+
+/// Round trips budgeted for a handshake (QUIC + TLS, with room for a loss)
+const HANDSHAKE_RTTS: u32 = 2;
+
+/// Upcoming leader with its share of the lookahead window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpcomingLeader {
+    pub leader: Pubkey,
+    pub addr: SocketAddr,
+    /// First slot this leader owns inside the window
+    pub first_slot: u64,
+    /// Slots this leader owns inside the window
+    pub slots: u64,
+    /// Activated stake in lamports (0 when unknown)
+    pub stake: u64,
+    /// Measured round-trip time (None until probed)
+    pub rtt: Option<Duration>,
+    /// Policy engine priority (0 without a policy; higher is warmed first)
+    pub priority: i32,
+}
+
+impl UpcomingLeader {
+    /// Expected handshake time with this leader
+    pub fn handshake_estimate(&self) -> Duration {
+        self.rtt.unwrap_or(UNKNOWN_RTT) * HANDSHAKE_RTTS
+    }
+
+    /// Latest a handshake can start (µs from slot 0) and still finish by
+    /// this leader's first slot
+    fn warm_deadline(&self, slot_duration: Duration) -> i128 {
+        self.first_slot as i128 * slot_duration.as_micros() as i128
+            - self.handshake_estimate().as_micros() as i128
+    }
+}
+
+/// Per-leader landed/sent counters
+#[derive(Debug, Default)]
+pub struct LandingHistory {
+    counts: DashMap<SocketAddr, (u64, u64)>,
+}
+
+impl LandingHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, addr: SocketAddr, landed: bool) {
+        let mut entry = self.counts.entry(addr).or_insert((0, 0));
+        entry.1 += 1;
+        if landed {
+            entry.0 += 1;
+        }
+    }
+
+    /// Fraction of sends to `addr` that landed (None until enough history)
+    pub fn landing_rate(&self, addr: &SocketAddr) -> Option<f64> {
+        self.counts.get(addr).and_then(|entry| {
+            let (landed, sent) = *entry;
+            (sent >= MIN_HISTORY_SAMPLES).then(|| landed as f64 / sent as f64)
+        })
+    }
+}
+
+/// Pick at most `budget` leaders to keep warm (0 = no budget, keep all).
+///
+/// Leaders are ranked by policy priority, then by `slots * landing_rate` (rate
+/// defaults to 1.0 without history), then by stake; remaining ties go to the
+/// leader that comes up first. The result is returned in warm-up order: by the
+/// latest moment each handshake can start (first slot at `slot_duration` per
+/// slot, less `handshake_estimate`), which is schedule order until RTTs differ.
+pub fn allocate_warm_budget(
+    candidates: &[UpcomingLeader],
+    budget: usize,
+    history: Option<&LandingHistory>,
+    slot_duration: Duration,
+) -> Vec<SocketAddr> {
+    let mut ranked: Vec<&UpcomingLeader> = candidates.iter().collect();
+    if budget > 0 && candidates.len() > budget {
+        let weight = |c: &UpcomingLeader| {
+            let rate = history.and_then(|h| h.landing_rate(&c.addr)).unwrap_or(1.0);
+            c.slots as f64 * rate
+        };
+        ranked.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(weight(b).total_cmp(&weight(a)))
+                .then(b.stake.cmp(&a.stake))
+                .then(a.first_slot.cmp(&b.first_slot))
+        });
+        ranked.truncate(budget);
+    }
+    ranked.sort_by_key(|c| (c.warm_deadline(slot_duration), c.first_slot));
+    ranked.into_iter().map(|c| c.addr).collect()
+}
+
+/// How many leaders one Scout pass may warm
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WarmBudget {
+    /// Slots past the current one to look at
+    pub lookahead: u64,
+    /// Leaders to keep warm (0 = all in the lookahead)
+    pub max_warm: usize,
+    /// Pools the engine can hold beside the current leader's (None = no limit)
+    pub capacity: Option<usize>,
+}
+
+/// One Scout pass at `current_slot`: pick the upcoming leaders the Shield and
+/// policy allow, budget them and warm a connection to each. Returns the
+/// targets now warm, in warm-up order.
+pub async fn warm_pass(
+    cartographer: &Cartographer,
+    engine: &QuicEngine,
+    current_slot: u64,
+    budget: WarmBudget,
+    history: Option<&LandingHistory>,
+) -> Vec<SocketAddr> {
+    let candidates = cartographer
+        .get_upcoming_leader_slots(current_slot, budget.lookahead)
+        .await;
+    let mut upcoming = allocate_warm_budget(
+        &candidates,
+        budget.max_warm,
+        history,
+        cartographer.slot_duration(),
+    );
+    // Over the engine's connection budget: nearest leaders first (warming
+    // the rest would only evict them again)
+    if let Some(capacity) = budget.capacity {
+        upcoming.truncate(capacity);
+    }
+    let mut warmed = Vec::with_capacity(upcoming.len());
+    for target in upcoming {
+        debug!("Scout: Warming up connection to {}", target);
+        // Best-effort: failures logged but not fatal
+        match engine.get_connection_pool(target).await {
+            Ok(_) => warmed.push(target),
+            Err(e) => debug!("Scout: Failed to warm connection to {}: {}", target, e),
+        }
+    }
+    warmed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leader(port: u16, first_slot: u64, slots: u64) -> UpcomingLeader {
+        UpcomingLeader {
+            leader: Pubkey::new_unique(),
+            addr: SocketAddr::from(([10, 0, 0, 1], port)),
+            first_slot,
+            slots,
+            stake: 0,
+            rtt: None,
+            priority: 0,
+        }
+    }
+
+    const SLOT: Duration = Duration::from_millis(400);
+
+    #[test]
+    fn test_budget_favors_slot_share_over_order() {
+        let candidates = [leader(1, 101, 1), leader(2, 102, 4), leader(3, 106, 8)];
+
+        let warm = allocate_warm_budget(&candidates, 2, None, SLOT);
+        // Leader 1 comes first but owns a single slot
+        assert_eq!(warm, vec![candidates[1].addr, candidates[2].addr]);
+
+        // Policy priority outranks slot share
+        let mut prioritized = candidates;
+        prioritized[0].priority = 1;
+        let warm = allocate_warm_budget(&prioritized, 2, None, SLOT);
+        assert_eq!(warm, vec![candidates[0].addr, candidates[2].addr]);
+
+        // No budget (or budget >= candidates) keeps schedule order untouched
+        assert_eq!(allocate_warm_budget(&candidates, 0, None, SLOT).len(), 3);
+        assert_eq!(allocate_warm_budget(&candidates, 5, None, SLOT).len(), 3);
+    }
+
+    #[test]
+    fn test_budget_breaks_ties_by_stake() {
+        let mut candidates = [leader(1, 101, 4), leader(2, 105, 4), leader(3, 109, 4)];
+        candidates[2].stake = 1_000;
+
+        // Same slot share: the staked leader outranks the earlier one
+        let warm = allocate_warm_budget(&candidates, 2, None, SLOT);
+        assert_eq!(warm, vec![candidates[0].addr, candidates[2].addr]);
+    }
+
+    #[test]
+    fn test_distant_leaders_warm_first() {
+        let mut candidates = [leader(1, 101, 4), leader(2, 102, 4), leader(3, 110, 4)];
+        let ms = Duration::from_millis;
+        candidates[0].rtt = Some(ms(10));
+        // Handshake (2 x 400ms) has to start before leader 1's (2 x 10ms)
+        candidates[1].rtt = Some(ms(400));
+
+        let warm = allocate_warm_budget(&candidates, 0, None, SLOT);
+        assert_eq!(
+            warm,
+            vec![candidates[1].addr, candidates[0].addr, candidates[2].addr]
+        );
+
+        // Slots far enough apart keep schedule order
+        candidates[2].rtt = Some(ms(1_000));
+        let warm = allocate_warm_budget(&candidates, 0, None, SLOT);
+        assert_eq!(warm[2], candidates[2].addr);
+    }
+
+    #[test]
+    fn test_budget_discounts_poor_landing_history() {
+        let candidates = [leader(1, 101, 4), leader(2, 105, 4), leader(3, 109, 2)];
+        let history = LandingHistory::new();
+        for i in 0..MIN_HISTORY_SAMPLES {
+            // Leader 2 lands 10% of the time: weight 0.4 < leader 3's 2.0
+            history.record(candidates[1].addr, i == 0);
+        }
+
+        let warm = allocate_warm_budget(&candidates, 2, Some(&history), SLOT);
+        assert_eq!(warm, vec![candidates[0].addr, candidates[2].addr]);
+    }
+}
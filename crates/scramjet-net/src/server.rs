@@ -0,0 +1,358 @@
+//! gRPC ingestion service for long-running processes.
+//!
+//! Upstream systems open one `SubmitTransactions` client stream and pipe
+//! signed transactions (wire bytes) into it instead of spawning the CLI per
+//! transaction. Each message is validated and pushed to the [`SendQueue`]
+//! with the priority class its `priority` asks for; when the queue is full (and set to
+//! block), the stream stops being read and HTTP/2 flow control pushes back
+//! on the client. When the client closes its side, it gets one summary of
+//! what was sent.
+//!
+//! ```proto
+//! package scramjet;
+//! service Ingest {
+//!   rpc SubmitTransactions(stream SubmitTransactionsRequest) returns (SubmitTransactionsResponse);
+//! }
+//! ```
+
+use crate::deadline::Deadline;
+use crate::priority::Priority;
+use crate::queue::SendQueue;
+use crate::raw::{self, RawTransaction};
+use futures::stream::{FuturesUnordered, StreamExt};
+use log::{debug, info, warn};
+use scramjet_common::ScramjetError;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio_stream::wrappers::TcpListenerStream;
+use tokio_util::sync::CancellationToken;
+use tonic::body::Body;
+use tonic::codegen::{BoxFuture, Service};
+use tonic::server::NamedService;
+use tonic::{Request, Response, Status, Streaming};
+use tonic_prost::ProstCodec;
+
+/// Per-transaction errors reported in a summary (the rest are only counted)
+pub const MAX_REPORTED_ERRORS: usize = 1_000;
+
+// --- scramjet.proto ---
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubmitTransactionsRequest {
+    /// Signed transaction, bincode wire format
+    #[prost(bytes = "vec", tag = "1")]
+    pub transaction: Vec<u8>,
+    /// Drop instead of sending after this slot (0 = no deadline)
+    #[prost(uint64, tag = "2")]
+    pub deadline_slot: u64,
+    /// Drop instead of sending above this block height, e.g. the blockhash's
+    /// `lastValidBlockHeight` (0 = no deadline; ignored with `deadline_slot`)
+    #[prost(uint64, tag = "3")]
+    pub last_valid_block_height: u64,
+    /// Priority class: above zero is high, below zero is bulk
+    #[prost(int32, tag = "4")]
+    pub priority: i32,
+    /// Forwarded by another instance's relay (not forwarded again)
+    #[prost(bool, tag = "5")]
+    pub relayed: bool,
+}
+
+impl SubmitTransactionsRequest {
+    pub fn deadline(&self) -> Option<Deadline> {
+        match (self.deadline_slot, self.last_valid_block_height) {
+            (0, 0) => None,
+            (0, height) => Some(Deadline::BlockHeight(height)),
+            (slot, _) => Some(Deadline::Slot(slot)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct TransactionError {
+    /// Position in the stream (0-based)
+    #[prost(uint64, tag = "1")]
+    pub index: u64,
+    /// Empty when the transaction could not be decoded
+    #[prost(string, tag = "2")]
+    pub signature: String,
+    #[prost(string, tag = "3")]
+    pub error: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SubmitTransactionsResponse {
+    #[prost(uint64, tag = "1")]
+    pub received: u64,
+    #[prost(uint64, tag = "2")]
+    pub sent: u64,
+    #[prost(uint64, tag = "3")]
+    pub failed: u64,
+    #[prost(message, repeated, tag = "4")]
+    pub errors: Vec<TransactionError>,
+}
+
+impl SubmitTransactionsResponse {
+    fn record(&mut self, index: u64, signature: String, result: Result<(), String>) {
+        match result {
+            Ok(()) => self.sent += 1,
+            Err(error) => {
+                self.failed += 1;
+                if self.errors.len() < MAX_REPORTED_ERRORS {
+                    self.errors.push(TransactionError {
+                        index,
+                        signature,
+                        error,
+                    });
+                }
+            }
+        }
+    }
+}
+
+pub const SUBMIT_TRANSACTIONS: &str = "/scramjet.Ingest/SubmitTransactions";
+
+/// A validated transaction waiting to be sent; answer it with [`Submission::respond`]
+#[derive(Debug)]
+pub struct Submission {
+    pub transaction: RawTransaction,
+    reply: oneshot::Sender<Result<(), String>>,
+}
+
+impl Submission {
+    pub fn new(transaction: RawTransaction) -> (Self, oneshot::Receiver<Result<(), String>>) {
+        let (reply, outcome) = oneshot::channel();
+        (Self { transaction, reply }, outcome)
+    }
+
+    pub fn respond(self, result: Result<(), ScramjetError>) {
+        // The client may have gone away; nothing left to tell it
+        let _ = self.reply.send(result.map_err(|e| e.to_string()));
+    }
+}
+
+/// What the sender made of a submission
+pub async fn outcome(receiver: oneshot::Receiver<Result<(), String>>) -> Result<(), String> {
+    receiver
+        .await
+        .unwrap_or_else(|_| Err("Dropped before sending".into()))
+}
+
+/// The `scramjet.Ingest` service: forwards submissions to the send queue
+#[derive(Clone)]
+pub struct IngestService {
+    queue: Arc<SendQueue>,
+}
+
+impl IngestService {
+    async fn submit_transactions(
+        self,
+        request: Request<Streaming<SubmitTransactionsRequest>>,
+    ) -> Result<Response<SubmitTransactionsResponse>, Status> {
+        let mut stream = request.into_inner();
+        let mut summary = SubmitTransactionsResponse::default();
+        let mut pending = FuturesUnordered::new();
+        loop {
+            tokio::select! {
+                Some((index, signature, result)) = pending.next() => {
+                    summary.record(index, signature, result);
+                }
+                message = stream.message() => {
+                    let Some(message) = message? else { break };
+                    let index = summary.received;
+                    summary.received += 1;
+                    let (deadline, priority) = (message.deadline(), Priority::from_level(message.priority));
+                    let relayed = message.relayed;
+                    let transaction = match raw::parse_wire_transaction(message.transaction) {
+                        Ok(transaction) => RawTransaction { deadline, priority, relayed, ..transaction },
+                        Err(e) => {
+                            summary.record(index, String::new(), Err(e.to_string()));
+                            continue;
+                        }
+                    };
+                    let signature = transaction.signature.to_string();
+                    let (submission, receiver) = Submission::new(transaction);
+                    // Waits while the queue is full (block): this is the backpressure
+                    match self.queue.push(submission).await {
+                        // A refused submission was answered with the reason
+                        Ok(()) | Err(ScramjetError::QueueFull(_)) => {}
+                        Err(_) => return Err(Status::unavailable("Sender is shutting down")),
+                    }
+                    pending.push(async move { (index, signature, outcome(receiver).await) });
+                }
+            }
+        }
+        while let Some((index, signature, result)) = pending.next().await {
+            summary.record(index, signature, result);
+        }
+        debug!(
+            "Ingest: Stream done (received {}, sent {}, failed {})",
+            summary.received, summary.sent, summary.failed
+        );
+        Ok(Response::new(summary))
+    }
+}
+
+impl NamedService for IngestService {
+    const NAME: &'static str = "scramjet.Ingest";
+}
+
+impl Service<Request<Streaming<SubmitTransactionsRequest>>> for IngestService {
+    type Response = Response<SubmitTransactionsResponse>;
+    type Error = Status;
+    type Future = BoxFuture<Self::Response, Status>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Streaming<SubmitTransactionsRequest>>) -> Self::Future {
+        Box::pin(self.clone().submit_transactions(request))
+    }
+}
+
+impl Service<http::Request<Body>> for IngestService {
+    type Response = http::Response<Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Infallible>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            if request.uri().path() != SUBMIT_TRANSACTIONS {
+                return Ok(Status::unimplemented(request.uri().path()).into_http());
+            }
+            let codec =
+                ProstCodec::<SubmitTransactionsResponse, SubmitTransactionsRequest>::default();
+            let mut grpc = tonic::server::Grpc::new(codec);
+            Ok(grpc.client_streaming(service, request).await)
+        })
+    }
+}
+
+/// Bind `addr` and serve `scramjet.Ingest` until `stop` is cancelled,
+/// forwarding submissions to `queue` (its capacity sets how far clients may
+/// run ahead of the sender)
+pub async fn spawn_ingest_server(
+    addr: SocketAddr,
+    queue: Arc<SendQueue>,
+    stop: CancellationToken,
+) -> Result<tokio::task::JoinHandle<()>, ScramjetError> {
+    let listener = TcpListener::bind(addr).await?;
+    info!("Ingest: gRPC listening on {}", listener.local_addr()?);
+    let service = IngestService { queue };
+
+    let task = tokio::spawn(async move {
+        let result = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), stop.cancelled())
+            .await;
+        if let Err(e) = result {
+            warn!("Ingest: gRPC server stopped: {}", e);
+        }
+    });
+    Ok(task)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::OverflowPolicy;
+    use http::uri::PathAndQuery;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::{Keypair, Signer};
+    #[allow(deprecated)]
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::Transaction;
+    use tonic::client::Grpc;
+    use tonic::transport::Endpoint;
+
+    fn signed_transfer(lamports: u64) -> Vec<u8> {
+        let payer = Keypair::new();
+        let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), lamports);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+        bincode::serialize(&tx).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_submit_transactions_stream() {
+        let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = probe.local_addr().unwrap();
+        drop(probe);
+        let stop = CancellationToken::new();
+        let queue = Arc::new(SendQueue::new(1, OverflowPolicy::Block));
+        let server = spawn_ingest_server(addr, queue.clone(), stop.clone())
+            .await
+            .unwrap();
+
+        // Sender side: even lamports succeed, odd ones fail
+        tokio::spawn(async move {
+            while let Some(submission) = queue.pop().await {
+                let tx: Transaction = bincode::deserialize(&submission.transaction.bytes).unwrap();
+                let lamports = tx.message.instructions[0].data[4];
+                // Only the first transaction was given a deadline
+                let deadline = (lamports == 0).then_some(Deadline::Slot(500));
+                assert_eq!(submission.transaction.deadline, deadline);
+                let result = match lamports % 2 {
+                    0 => Ok(()),
+                    _ => Err(ScramjetError::NoLeaderFound(0)),
+                };
+                submission.respond(result);
+            }
+        });
+
+        let mut messages: Vec<_> = (0..6).map(signed_transfer).collect();
+        messages.insert(3, b"not a transaction".to_vec());
+        let requests =
+            futures::stream::iter(messages.into_iter().enumerate().map(|(i, transaction)| {
+                SubmitTransactionsRequest {
+                    transaction,
+                    deadline_slot: if i == 0 { 500 } else { 0 },
+                    last_valid_block_height: 0,
+                    priority: 0,
+                    relayed: false,
+                }
+            }));
+
+        let channel = Endpoint::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut grpc = Grpc::new(channel);
+        grpc.ready().await.unwrap();
+        let response = grpc
+            .client_streaming(
+                Request::new(requests),
+                PathAndQuery::from_static(SUBMIT_TRANSACTIONS),
+                ProstCodec::<SubmitTransactionsRequest, SubmitTransactionsResponse>::default(),
+            )
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(response.received, 7);
+        assert_eq!(response.sent, 3);
+        assert_eq!(response.failed, 4);
+        let mut indices: Vec<u64> = response.errors.iter().map(|e| e.index).collect();
+        indices.sort_unstable();
+        assert_eq!(indices, vec![1, 3, 4, 6]);
+        let invalid = response.errors.iter().find(|e| e.index == 3).unwrap();
+        assert!(invalid.signature.is_empty());
+
+        stop.cancel();
+        server.await.unwrap();
+    }
+}
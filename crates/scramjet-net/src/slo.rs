@@ -0,0 +1,802 @@
+//! Landing-latency SLOs evaluated over a rolling window of send outcomes.
+//!
+//! An SLO reads as "95% of sends land within 3 slots" (`95%@3`). Every send
+//! records an outcome; `SloMonitor::check` evaluates each SLO over the window
+//! and fires the alert notifier when one starts failing, naming the failure
+//! stage that accounts for most misses.
+//!
+//! Outcomes come from RPC signature polling, or from a `LandingFeed` fed by
+//! the Geyser transaction status stream when it is enabled.
+
+use crate::cartographer::Cartographer;
+use crate::geyser::LandedSignature;
+use crate::scout::LandingHistory;
+use crate::stats::StatsRegistry;
+use log::{error, info, warn};
+use scramjet_common::{config, ScramjetError};
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Blockhashes expire after 150 slots; a send not seen by then never lands
+pub const BLOCKHASH_EXPIRY_SLOTS: u64 = 150;
+
+/// Signatures per `getSignatureStatuses` call (the RPC's limit)
+const STATUS_BATCH: usize = 256;
+
+/// Where a send fell short of its SLO
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureStage {
+    /// No leader (or no TPU address) for the current slot
+    NoLeader,
+    /// QUIC handshake / connection failure
+    Connect,
+    /// Stream open / write / finish failure
+    Stream,
+    /// Landed, but later than the SLO allows
+    Late,
+    /// Sent, but never observed on-chain before blockhash expiry
+    Expired,
+}
+
+impl FailureStage {
+    /// Map a send error onto the stage that produced it
+    pub fn from_send_error(err: &ScramjetError) -> Self {
+        match err {
+            ScramjetError::SendFailed(failure) if failure.kind.is_stream() => FailureStage::Stream,
+            ScramjetError::SendFailed(_) => FailureStage::Connect,
+            ScramjetError::WriteError(_)
+            | ScramjetError::ClosedStreamError(_)
+            | ScramjetError::StreamError(_) => FailureStage::Stream,
+            _ => FailureStage::Connect,
+        }
+    }
+
+    const ALL: [FailureStage; 5] = [
+        FailureStage::NoLeader,
+        FailureStage::Connect,
+        FailureStage::Stream,
+        FailureStage::Late,
+        FailureStage::Expired,
+    ];
+}
+
+impl fmt::Display for FailureStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            FailureStage::NoLeader => "no-leader",
+            FailureStage::Connect => "connect",
+            FailureStage::Stream => "stream",
+            FailureStage::Late => "late",
+            FailureStage::Expired => "expired",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Result of one send
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendOutcome {
+    /// Landed this many slots after it was sent
+    Landed {
+        slots: u64,
+    },
+    Failed(FailureStage),
+}
+
+/// "`target` of sends land within `within_slots` slots"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LandingSlo {
+    /// Required fraction in (0, 1]
+    pub target: f64,
+    pub within_slots: u64,
+}
+
+impl LandingSlo {
+    /// Whether `outcome` counts toward this SLO (None if it does, else the miss stage)
+    fn miss(&self, outcome: &SendOutcome) -> Option<FailureStage> {
+        match *outcome {
+            SendOutcome::Landed { slots } if slots <= self.within_slots => None,
+            SendOutcome::Landed { .. } => Some(FailureStage::Late),
+            SendOutcome::Failed(stage) => Some(stage),
+        }
+    }
+}
+
+impl fmt::Display for LandingSlo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}% within {} slots",
+            self.target * 100.0,
+            self.within_slots
+        )
+    }
+}
+
+/// Parse a comma-separated SLO list such as `95%@3,99%@10` (grammar in
+/// `scramjet_common::config::parse_slos`)
+pub fn parse_slos(spec: &str) -> Result<Vec<LandingSlo>, ScramjetError> {
+    let slos = config::parse_slos(spec).map_err(ScramjetError::ConfigValidationError)?;
+    Ok(slos
+        .into_iter()
+        .map(|(target, within_slots)| LandingSlo {
+            target,
+            within_slots,
+        })
+        .collect())
+}
+
+/// Evaluation of one SLO over the current window
+#[derive(Debug, Clone)]
+pub struct SloReport {
+    pub slo: LandingSlo,
+    pub samples: usize,
+    /// Fraction of samples meeting the SLO
+    pub achieved: f64,
+    /// Misses per stage, most frequent first
+    pub breakdown: Vec<(FailureStage, usize)>,
+}
+
+impl SloReport {
+    pub fn is_met(&self) -> bool {
+        self.achieved >= self.slo.target
+    }
+
+    /// Stage responsible for the most misses
+    pub fn dominant_failure(&self) -> Option<FailureStage> {
+        self.breakdown.first().map(|(stage, _)| *stage)
+    }
+}
+
+impl fmt::Display for SloReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SLO {}: achieved {:.1}% over {} sends",
+            self.slo,
+            self.achieved * 100.0,
+            self.samples
+        )?;
+        if !self.breakdown.is_empty() {
+            let misses: usize = self.breakdown.iter().map(|(_, n)| n).sum();
+            let stages: Vec<String> = self
+                .breakdown
+                .iter()
+                .map(|(stage, n)| format!("{}={}", stage, n))
+                .collect();
+            write!(f, " | misses: {} ({})", misses, stages.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Receives SLO violations
+pub trait AlertNotifier: Send + Sync {
+    fn notify(&self, report: &SloReport);
+}
+
+/// Logs violations at error level
+pub struct LogNotifier;
+
+impl AlertNotifier for LogNotifier {
+    fn notify(&self, report: &SloReport) {
+        error!(
+            "SLO VIOLATED: {} | dominant failure: {}",
+            report,
+            report
+                .dominant_failure()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| "none".into())
+        );
+    }
+}
+
+/// Logs violations and POSTs them as JSON to a webhook
+pub struct WebhookNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+impl AlertNotifier for WebhookNotifier {
+    fn notify(&self, report: &SloReport) {
+        LogNotifier.notify(report);
+
+        let body = serde_json::json!({
+            "slo": report.slo.to_string(),
+            "target": report.slo.target,
+            "within_slots": report.slo.within_slots,
+            "achieved": report.achieved,
+            "samples": report.samples,
+            "dominant_failure": report.dominant_failure().map(|s| s.to_string()),
+            "breakdown": report
+                .breakdown
+                .iter()
+                .map(|(stage, n)| (stage.to_string(), *n))
+                .collect::<std::collections::HashMap<_, _>>(),
+        });
+        let request = self
+            .client
+            .post(&self.url)
+            .header("content-type", "application/json")
+            .body(body.to_string());
+        // Best-effort: alert delivery must never block the send path
+        tokio::spawn(async move {
+            if let Err(e) = request.send().await {
+                warn!("SLO webhook delivery failed: {}", e);
+            }
+        });
+    }
+}
+
+/// Rolling-window SLO evaluator
+pub struct SloMonitor {
+    slos: Vec<LandingSlo>,
+    window: Duration,
+    min_samples: usize,
+    samples: Mutex<VecDeque<(Instant, SendOutcome)>>,
+    /// Per-SLO violation state (alerts fire on the transition into violation)
+    violated: Mutex<Vec<bool>>,
+    notifier: Arc<dyn AlertNotifier>,
+    /// Per-leader landing counters (feeds Scout budget allocation)
+    history: LandingHistory,
+    /// Lifetime per-leader statistics that also get landing outcomes
+    stats: Option<Arc<StatsRegistry>>,
+}
+
+impl SloMonitor {
+    pub fn new(
+        slos: Vec<LandingSlo>,
+        window: Duration,
+        min_samples: usize,
+        notifier: Arc<dyn AlertNotifier>,
+    ) -> Self {
+        let violated = Mutex::new(vec![false; slos.len()]);
+        Self {
+            slos,
+            window,
+            min_samples,
+            samples: Mutex::new(VecDeque::new()),
+            violated,
+            notifier,
+            history: LandingHistory::new(),
+            stats: None,
+        }
+    }
+
+    /// Also feed per-leader outcomes into `stats`
+    pub fn with_stats(mut self, stats: Arc<StatsRegistry>) -> Self {
+        self.stats = Some(stats);
+        self
+    }
+
+    pub fn record(&self, outcome: SendOutcome) {
+        self.record_at(outcome, Instant::now());
+    }
+
+    /// Record an outcome for a send to a known leader
+    pub fn record_to(&self, target: SocketAddr, outcome: SendOutcome) {
+        self.history
+            .record(target, matches!(outcome, SendOutcome::Landed { .. }));
+        if let Some(ref stats) = self.stats {
+            stats.record_landing(target, outcome);
+        }
+        self.record(outcome);
+    }
+
+    pub fn history(&self) -> &LandingHistory {
+        &self.history
+    }
+
+    pub fn record_at(&self, outcome: SendOutcome, at: Instant) {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.push_back((at, outcome));
+        Self::prune(&mut samples, self.window, at);
+    }
+
+    fn prune(samples: &mut VecDeque<(Instant, SendOutcome)>, window: Duration, now: Instant) {
+        while let Some((at, _)) = samples.front() {
+            if now.saturating_duration_since(*at) <= window {
+                break;
+            }
+            samples.pop_front();
+        }
+    }
+
+    /// Outcomes inside the window as of `now`
+    fn window_at(&self, now: Instant) -> Vec<SendOutcome> {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        Self::prune(&mut samples, self.window, now);
+        samples.iter().map(|(_, outcome)| *outcome).collect()
+    }
+
+    /// Fraction of windowed sends that landed at all (None with no samples)
+    pub fn landing_rate(&self) -> Option<f64> {
+        let outcomes = self.window_at(Instant::now());
+        if outcomes.is_empty() {
+            return None;
+        }
+        let landed = outcomes
+            .iter()
+            .filter(|o| matches!(o, SendOutcome::Landed { .. }))
+            .count();
+        Some(landed as f64 / outcomes.len() as f64)
+    }
+
+    /// Evaluate every SLO over the window as of `now`
+    pub fn evaluate_at(&self, now: Instant) -> Vec<SloReport> {
+        let outcomes = self.window_at(now);
+        self.slos
+            .iter()
+            .map(|slo| {
+                let mut counts = [0usize; FailureStage::ALL.len()];
+                let mut met = 0usize;
+                for outcome in &outcomes {
+                    match slo.miss(outcome) {
+                        None => met += 1,
+                        Some(stage) => {
+                            let idx = FailureStage::ALL.iter().position(|s| *s == stage);
+                            if let Some(idx) = idx {
+                                counts[idx] += 1;
+                            }
+                        }
+                    }
+                }
+                let mut breakdown: Vec<(FailureStage, usize)> = FailureStage::ALL
+                    .iter()
+                    .zip(counts)
+                    .filter(|(_, n)| *n > 0)
+                    .map(|(stage, n)| (*stage, n))
+                    .collect();
+                breakdown.sort_by_key(|(_, n)| std::cmp::Reverse(*n));
+
+                SloReport {
+                    slo: *slo,
+                    samples: outcomes.len(),
+                    achieved: if outcomes.is_empty() {
+                        1.0
+                    } else {
+                        met as f64 / outcomes.len() as f64
+                    },
+                    breakdown,
+                }
+            })
+            .collect()
+    }
+
+    /// Evaluate and notify on SLOs that just started failing.
+    /// Returns the reports for every currently violated SLO.
+    pub fn check(&self) -> Vec<SloReport> {
+        self.check_at(Instant::now())
+    }
+
+    pub fn check_at(&self, now: Instant) -> Vec<SloReport> {
+        let reports = self.evaluate_at(now);
+        let mut violated = self.violated.lock().unwrap_or_else(|e| e.into_inner());
+        let mut failing = Vec::new();
+
+        for (i, report) in reports.into_iter().enumerate() {
+            // Too few sends to judge: keep the previous state
+            if report.samples < self.min_samples {
+                continue;
+            }
+            if report.is_met() {
+                if violated[i] {
+                    info!("SLO recovered: {}", report);
+                    violated[i] = false;
+                }
+                continue;
+            }
+            if !violated[i] {
+                self.notifier.notify(&report);
+                violated[i] = true;
+            }
+            failing.push(report);
+        }
+        failing
+    }
+
+    /// Re-evaluate every `interval` in the background
+    pub fn spawn_evaluator(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.check();
+            }
+        })
+    }
+}
+
+/// Signatures seen landing on the Geyser transaction status stream, kept
+/// until their blockhash would have expired
+#[derive(Debug, Default)]
+pub struct LandingFeed {
+    landed: Mutex<HashMap<Signature, u64>>,
+    newest_slot: AtomicU64,
+    notify: tokio::sync::Notify,
+}
+
+impl LandingFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a landing and wake `await_landing` callers
+    pub fn record(&self, landed: LandedSignature) {
+        let newest = self.newest_slot.fetch_max(landed.slot, Ordering::Relaxed);
+        {
+            let mut map = self.landed.lock().unwrap_or_else(|e| e.into_inner());
+            map.insert(landed.signature, landed.slot);
+            // Anything older than two expiry windows has been awaited already
+            if landed.slot > newest {
+                let horizon = landed.slot.saturating_sub(2 * BLOCKHASH_EXPIRY_SLOTS);
+                map.retain(|_, slot| *slot >= horizon);
+            }
+        }
+        self.notify.notify_waiters();
+    }
+
+    /// Slot `signature` landed in, if seen
+    pub fn landed_slot(&self, signature: &Signature) -> Option<u64> {
+        self.landed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(signature)
+            .copied()
+    }
+
+    /// Drain the Geyser landing channel into the feed (exits when it closes)
+    pub fn spawn_consumer(
+        self: Arc<Self>,
+        mut landed: tokio::sync::mpsc::Receiver<LandedSignature>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(update) = landed.recv().await {
+                self.record(update);
+            }
+        })
+    }
+}
+
+/// Per-transaction results of a run that writes each transaction on one or
+/// more streams, so the SLO window gets one outcome per transaction: its
+/// landing (or expiry) once any write got it out, otherwise the stage its
+/// last write failed at
+#[derive(Debug, Default)]
+pub struct TxOutcomes {
+    /// Send order
+    order: Vec<Signature>,
+    txs: HashMap<Signature, TxSends>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TxSends {
+    target: SocketAddr,
+    /// Slot of the first successful write
+    sent_slot: Option<u64>,
+    failure: FailureStage,
+}
+
+impl TxOutcomes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A write of `signature` to `target` at `slot` (Err = the stage it
+    /// failed at)
+    pub fn record(
+        &mut self,
+        signature: Signature,
+        target: SocketAddr,
+        slot: u64,
+        result: Result<(), FailureStage>,
+    ) {
+        let stage = result.err().unwrap_or(FailureStage::Stream);
+        let sends = self.txs.entry(signature).or_insert_with(|| {
+            self.order.push(signature);
+            TxSends {
+                target,
+                sent_slot: None,
+                failure: stage,
+            }
+        });
+        match result {
+            Ok(()) if sends.sent_slot.is_none() => {
+                sends.target = target;
+                sends.sent_slot = Some(slot);
+            }
+            Err(stage) if sends.sent_slot.is_none() => {
+                sends.target = target;
+                sends.failure = stage;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Wait for the transactions that got out to land, record every
+    /// transaction's outcome to `monitor` and return them in send order
+    pub async fn finish(
+        self,
+        cartographer: &Cartographer,
+        feed: Option<&LandingFeed>,
+        monitor: &SloMonitor,
+    ) -> Vec<(Signature, SocketAddr, SendOutcome)> {
+        let sent: Vec<(Signature, u64)> = self
+            .order
+            .iter()
+            .filter_map(|sig| Some((*sig, self.txs[sig].sent_slot?)))
+            .collect();
+        let mut landings = await_landings(cartographer, feed, &sent).await.into_iter();
+        self.order
+            .iter()
+            .map(|sig| {
+                let sends = self.txs[sig];
+                let outcome = match sends.sent_slot {
+                    Some(_) => landings
+                        .next()
+                        .unwrap_or(SendOutcome::Failed(FailureStage::Expired)),
+                    None => SendOutcome::Failed(sends.failure),
+                };
+                monitor.record_to(sends.target, outcome);
+                (*sig, sends.target, outcome)
+            })
+            .collect()
+    }
+}
+
+/// Wait until `signature` shows up on-chain (or its blockhash expires) and
+/// convert the landing slot into an outcome relative to `sent_slot`. Watches
+/// `feed` when given (no RPC calls), otherwise polls signature statuses.
+pub async fn await_landing(
+    cartographer: &Cartographer,
+    feed: Option<&LandingFeed>,
+    signature: &Signature,
+    sent_slot: u64,
+) -> SendOutcome {
+    await_landings(cartographer, feed, &[(*signature, sent_slot)]).await[0]
+}
+
+/// `await_landing` for each `(signature, sent_slot)`, polling statuses in
+/// batches rather than once per signature
+pub async fn await_landings(
+    cartographer: &Cartographer,
+    feed: Option<&LandingFeed>,
+    sent: &[(Signature, u64)],
+) -> Vec<SendOutcome> {
+    if let Some(feed) = feed {
+        return futures::future::join_all(sent.iter().map(|(signature, sent_slot)| {
+            await_feed_landing(cartographer, feed, signature, *sent_slot)
+        }))
+        .await;
+    }
+    let rpc = cartographer.rpc_client();
+    let mut outcomes: Vec<Option<SendOutcome>> = vec![None; sent.len()];
+    loop {
+        let pending: Vec<usize> = (0..sent.len()).filter(|&i| outcomes[i].is_none()).collect();
+        if pending.is_empty() {
+            return outcomes.into_iter().flatten().collect();
+        }
+        for batch in pending.chunks(STATUS_BATCH) {
+            let signatures: Vec<Signature> = batch.iter().map(|&i| sent[i].0).collect();
+            match rpc.get_signature_statuses(&signatures).await {
+                Ok(response) => {
+                    for (&i, status) in batch.iter().zip(response.value) {
+                        if let Some(status) = status {
+                            outcomes[i] = Some(SendOutcome::Landed {
+                                slots: status.slot.saturating_sub(sent[i].1),
+                            });
+                        }
+                    }
+                }
+                Err(e) => warn!("Signature status lookup failed: {}", e),
+            }
+        }
+        let now = cartographer.get_known_slot();
+        let mut waiting = false;
+        for &i in &pending {
+            if outcomes[i].is_some() {
+                continue;
+            }
+            if now > sent[i].1.saturating_add(BLOCKHASH_EXPIRY_SLOTS) {
+                outcomes[i] = Some(SendOutcome::Failed(FailureStage::Expired));
+            } else {
+                waiting = true;
+            }
+        }
+        if waiting {
+            tokio::time::sleep(cartographer.slot_duration()).await;
+        }
+    }
+}
+
+async fn await_feed_landing(
+    cartographer: &Cartographer,
+    feed: &LandingFeed,
+    signature: &Signature,
+    sent_slot: u64,
+) -> SendOutcome {
+    loop {
+        // Register before checking so a landing in between still wakes us
+        let notified = feed.notify.notified();
+        if let Some(slot) = feed.landed_slot(signature) {
+            return SendOutcome::Landed {
+                slots: slot.saturating_sub(sent_slot),
+            };
+        }
+        if cartographer.get_known_slot() > sent_slot.saturating_add(BLOCKHASH_EXPIRY_SLOTS) {
+            return SendOutcome::Failed(FailureStage::Expired);
+        }
+        tokio::select! {
+            _ = notified => {}
+            _ = tokio::time::sleep(cartographer.slot_duration()) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingNotifier(AtomicUsize);
+
+    impl AlertNotifier for CountingNotifier {
+        fn notify(&self, _report: &SloReport) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_tx_outcomes_one_per_transaction() {
+        let (landed, lost) = (Signature::new_unique(), Signature::new_unique());
+        let first: SocketAddr = "127.0.0.1:8001".parse().unwrap();
+        let second: SocketAddr = "127.0.0.1:8002".parse().unwrap();
+        let mut outcomes = TxOutcomes::new();
+
+        // Failed streams before and after a successful write don't count
+        outcomes.record(landed, first, 10, Err(FailureStage::Connect));
+        outcomes.record(landed, second, 11, Ok(()));
+        outcomes.record(landed, first, 12, Err(FailureStage::Stream));
+        // Never got out: one failure, at the last stage it reached
+        outcomes.record(lost, first, 12, Err(FailureStage::Connect));
+        outcomes.record(lost, second, 13, Err(FailureStage::Stream));
+
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes.order, vec![landed, lost]);
+        let sends = outcomes.txs[&landed];
+        assert_eq!((sends.target, sends.sent_slot), (second, Some(11)));
+        let sends = outcomes.txs[&lost];
+        assert_eq!((sends.target, sends.sent_slot), (second, None));
+        assert_eq!(sends.failure, FailureStage::Stream);
+    }
+
+    #[test]
+    fn test_parse_slos() {
+        let slos = parse_slos("95%@3, 99@10").unwrap();
+        assert_eq!(slos.len(), 2);
+        assert_eq!(slos[0].within_slots, 3);
+        assert!((slos[0].target - 0.95).abs() < 1e-9);
+        assert!((slos[1].target - 0.99).abs() < 1e-9);
+
+        assert!(parse_slos("95%").is_err());
+        assert!(parse_slos("0%@3").is_err());
+        assert!(parse_slos("150%@3").is_err());
+        assert!(parse_slos("95%@soon").is_err());
+    }
+
+    #[test]
+    fn test_violation_alerts_once_with_dominant_stage() {
+        let notifier = Arc::new(CountingNotifier(AtomicUsize::new(0)));
+        let slo = LandingSlo {
+            target: 0.9,
+            within_slots: 3,
+        };
+        let monitor = SloMonitor::new(vec![slo], Duration::from_secs(60), 10, notifier.clone());
+        let t0 = Instant::now();
+
+        for _ in 0..6 {
+            monitor.record_at(SendOutcome::Landed { slots: 2 }, t0);
+        }
+        monitor.record_at(SendOutcome::Landed { slots: 7 }, t0);
+        for _ in 0..3 {
+            monitor.record_at(SendOutcome::Failed(FailureStage::Connect), t0);
+        }
+
+        let failing = monitor.check_at(t0);
+        assert_eq!(failing.len(), 1);
+        assert!((failing[0].achieved - 0.6).abs() < 1e-9);
+        assert_eq!(failing[0].dominant_failure(), Some(FailureStage::Connect));
+        assert_eq!(failing[0].breakdown[1], (FailureStage::Late, 1));
+
+        // Still violated: no repeat alert
+        monitor.check_at(t0);
+        assert_eq!(notifier.0.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_window_expiry_and_min_samples() {
+        let notifier = Arc::new(CountingNotifier(AtomicUsize::new(0)));
+        let slo = LandingSlo {
+            target: 0.95,
+            within_slots: 3,
+        };
+        let monitor = SloMonitor::new(vec![slo], Duration::from_secs(60), 5, notifier.clone());
+        let t0 = Instant::now();
+
+        for _ in 0..5 {
+            monitor.record_at(SendOutcome::Failed(FailureStage::Expired), t0);
+        }
+        // Old failures roll out of the window; too few fresh samples to judge
+        let later = t0 + Duration::from_secs(120);
+        monitor.record_at(SendOutcome::Landed { slots: 1 }, later);
+        assert!(monitor.check_at(later).is_empty());
+        assert_eq!(notifier.0.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_await_landing_from_feed() {
+        use crate::blocklist::blocklist_handle;
+        use std::collections::HashSet;
+
+        let blocklist = blocklist_handle(HashSet::new());
+        let cartographer = Cartographer::new("http://mock-rpc".to_string(), blocklist);
+        cartographer.update_slot(1_000);
+        let feed = Arc::new(LandingFeed::new());
+        let signature = Signature::new_unique();
+
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        let consumer = feed.clone().spawn_consumer(rx);
+        let sender = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            tx.send(LandedSignature {
+                signature,
+                slot: 1_002,
+                failed: false,
+            })
+            .await
+            .unwrap();
+        });
+        let outcome = await_landing(&cartographer, Some(&feed), &signature, 1_000).await;
+        assert_eq!(outcome, SendOutcome::Landed { slots: 2 });
+        sender.await.unwrap();
+        consumer.await.unwrap();
+
+        // Never seen: expires once the clock passes the blockhash window
+        cartographer.update_slot(1_000 + BLOCKHASH_EXPIRY_SLOTS + 1);
+        let missing = Signature::new_unique();
+        assert_eq!(
+            await_landing(&cartographer, Some(&feed), &missing, 1_000).await,
+            SendOutcome::Failed(FailureStage::Expired)
+        );
+
+        // Old landings are pruned as the stream moves on
+        feed.record(LandedSignature {
+            signature: missing,
+            slot: 1_002 + 2 * BLOCKHASH_EXPIRY_SLOTS + 1,
+            failed: false,
+        });
+        assert_eq!(feed.landed_slot(&signature), None);
+    }
+}
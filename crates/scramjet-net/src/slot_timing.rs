@@ -0,0 +1,139 @@
+//! Slot-time model: rolling estimate of the actual slot duration.
+//!
+//! Mainnet slots drift away from the nominal 400ms, so every timing feature
+//! reads the calibrated estimate here instead of hardcoding the constant.
+//! Updates are lock-free (atomics only) since they ride on the slot clock.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Nominal Solana slot duration (used until enough observations arrive)
+pub const NOMINAL_SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// EWMA weight of a new observation = 1 / EWMA_DIVISOR
+const EWMA_DIVISOR: u64 = 16;
+
+/// Per-slot intervals above this are gaps (reconnects, stalls), not slot time
+const MAX_PLAUSIBLE_SLOT_US: u64 = 2_000_000;
+
+/// Rolling slot duration estimate fed by observed slot advances
+#[derive(Debug)]
+pub struct SlotTimeModel {
+    origin: Instant,
+    last_slot: AtomicU64,
+    /// Micros since `origin` when `last_slot` was observed (0 = never)
+    last_advance_us: AtomicU64,
+    /// Current estimate in micros
+    estimate_us: AtomicU64,
+    samples: AtomicU64,
+}
+
+impl SlotTimeModel {
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now(),
+            last_slot: AtomicU64::new(0),
+            last_advance_us: AtomicU64::new(0),
+            estimate_us: AtomicU64::new(NOMINAL_SLOT_DURATION.as_micros() as u64),
+            samples: AtomicU64::new(0),
+        }
+    }
+
+    /// Record that `slot` was first observed now
+    pub fn observe(&self, slot: u64) {
+        self.observe_at(slot, Instant::now());
+    }
+
+    /// Record that `slot` was first observed at `at`
+    pub fn observe_at(&self, slot: u64, at: Instant) {
+        // +1 so a slot observed exactly at origin is distinguishable from "never"
+        let now_us = at.saturating_duration_since(self.origin).as_micros() as u64 + 1;
+        let prev_slot = self.last_slot.swap(slot, Ordering::Relaxed);
+        let prev_us = self.last_advance_us.swap(now_us, Ordering::Relaxed);
+
+        if prev_us == 0 || slot <= prev_slot || now_us <= prev_us {
+            return;
+        }
+
+        let per_slot_us = (now_us - prev_us) / (slot - prev_slot);
+        if per_slot_us == 0 || per_slot_us > MAX_PLAUSIBLE_SLOT_US {
+            return;
+        }
+
+        let samples = self.samples.fetch_add(1, Ordering::Relaxed);
+        let estimate = if samples == 0 {
+            per_slot_us
+        } else {
+            let old = self.estimate_us.load(Ordering::Relaxed);
+            (old * (EWMA_DIVISOR - 1) + per_slot_us) / EWMA_DIVISOR
+        };
+        self.estimate_us.store(estimate, Ordering::Relaxed);
+    }
+
+    /// Calibrated slot duration (nominal 400ms until the first observation)
+    pub fn slot_duration(&self) -> Duration {
+        Duration::from_micros(self.estimate_us.load(Ordering::Relaxed))
+    }
+
+    /// Number of intervals folded into the estimate
+    pub fn samples(&self) -> u64 {
+        self.samples.load(Ordering::Relaxed)
+    }
+
+    /// When the latest slot was first observed (None before the first update)
+    pub fn last_slot_start(&self) -> Option<Instant> {
+        match self.last_advance_us.load(Ordering::Relaxed) {
+            0 => None,
+            us => Some(self.origin + Duration::from_micros(us - 1)),
+        }
+    }
+
+    /// Time elapsed since the latest slot was observed
+    pub fn elapsed_in_slot(&self) -> Option<Duration> {
+        self.last_slot_start().map(|start| start.elapsed())
+    }
+}
+
+impl Default for SlotTimeModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nominal_until_observed() {
+        let model = SlotTimeModel::new();
+        assert_eq!(model.slot_duration(), NOMINAL_SLOT_DURATION);
+        assert!(model.last_slot_start().is_none());
+    }
+
+    #[test]
+    fn test_calibrates_to_observed_intervals() {
+        let model = SlotTimeModel::new();
+        let t0 = Instant::now();
+        for i in 0..64u64 {
+            model.observe_at(100 + i, t0 + Duration::from_millis(450 * i));
+        }
+        let estimate = model.slot_duration().as_millis();
+        assert!((440..=460).contains(&estimate), "estimate {}ms", estimate);
+    }
+
+    #[test]
+    fn test_multi_slot_jump_and_gaps() {
+        let model = SlotTimeModel::new();
+        let t0 = Instant::now();
+        model.observe_at(100, t0);
+        // Skipped updates: 4 slots in 1.6s is still 400ms per slot
+        model.observe_at(104, t0 + Duration::from_millis(1_600));
+        assert_eq!(model.slot_duration().as_millis(), 400);
+
+        // A 30s stall (reconnect) must not poison the estimate
+        model.observe_at(105, t0 + Duration::from_secs(32));
+        assert_eq!(model.slot_duration().as_millis(), 400);
+        assert_eq!(model.samples(), 1);
+    }
+}
@@ -0,0 +1,462 @@
+//! Per-leader delivery statistics over the process lifetime.
+//!
+//! The engine counts every send attempt and handshake per leader TPU address;
+//! when landing confirmation is on, the SLO monitor adds landed / expired
+//! outcomes. `scramjet stats` ranks leaders by delivery quality, either live
+//! over the control socket or from the file written to `STATS_FILE`.
+//!
+//! QUIC path statistics (RTT, congestion window, loss) are sampled from the
+//! engine's open connections, so a leader that lands little can be told apart
+//! as a lossy path or a validator that drops what it receives.
+
+use crate::cartographer::Cartographer;
+use crate::slo::{FailureStage, SendOutcome};
+use crate::topology_cache::unix_time_ms;
+use crate::validator_info::ValidatorInfo;
+use dashmap::DashMap;
+use log::debug;
+use quinn::ConnectionStats;
+use scramjet_common::{ScramjetError, SendFailureKind};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// How often `STATS_FILE` is rewritten (and once more on shutdown)
+pub const STATS_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Counters {
+    sent: u64,
+    stream_errors: u64,
+    timeouts: u64,
+    connect_errors: u64,
+    handshakes: u64,
+    handshake_us: u64,
+    max_handshake_us: u64,
+    landed: u64,
+    expired: u64,
+}
+
+/// Live per-leader counters (cheap to update from the send path)
+#[derive(Debug)]
+pub struct StatsRegistry {
+    leaders: DashMap<SocketAddr, Counters>,
+    paths: DashMap<SocketAddr, PathStats>,
+    /// quinn statistics of each open connection (by `stable_id`) as of the
+    /// last `record_paths` pass
+    connections: Mutex<HashMap<usize, ConnectionStats>>,
+    started: Instant,
+    started_at_ms: u64,
+}
+
+impl Default for StatsRegistry {
+    fn default() -> Self {
+        Self {
+            leaders: DashMap::new(),
+            paths: DashMap::new(),
+            connections: Mutex::new(HashMap::new()),
+            started: Instant::now(),
+            started_at_ms: unix_time_ms(),
+        }
+    }
+}
+
+impl StatsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One send attempt to `target` (stream written, or the error it hit).
+    /// Stream timeouts are counted apart from other stream errors.
+    pub fn record_send(&self, target: SocketAddr, error: Option<&ScramjetError>) {
+        let mut counters = self.leaders.entry(target).or_default();
+        let timed_out = error
+            .and_then(ScramjetError::send_failure)
+            .is_some_and(|failure| failure.kind == SendFailureKind::StreamTimeout);
+        match error.map(FailureStage::from_send_error) {
+            None => counters.sent += 1,
+            Some(_) if timed_out => counters.timeouts += 1,
+            Some(FailureStage::Stream) => counters.stream_errors += 1,
+            Some(_) => counters.connect_errors += 1,
+        }
+    }
+
+    /// A successful handshake with `target`
+    pub fn record_handshake(&self, target: SocketAddr, elapsed: Duration) {
+        let micros = elapsed.as_micros() as u64;
+        let mut counters = self.leaders.entry(target).or_default();
+        counters.handshakes += 1;
+        counters.handshake_us += micros;
+        counters.max_handshake_us = counters.max_handshake_us.max(micros);
+    }
+
+    /// Landing outcome of a send that reached `target` (send failures are
+    /// already counted by `record_send`)
+    pub fn record_landing(&self, target: SocketAddr, outcome: SendOutcome) {
+        let landed = match outcome {
+            SendOutcome::Landed { .. } | SendOutcome::Failed(FailureStage::Late) => true,
+            SendOutcome::Failed(FailureStage::Expired) => false,
+            SendOutcome::Failed(_) => return,
+        };
+        let mut counters = self.leaders.entry(target).or_default();
+        if landed {
+            counters.landed += 1;
+        } else {
+            counters.expired += 1;
+        }
+    }
+
+    /// One pass over the engine's open connections: `(leader, stable_id,
+    /// stats)`. Packets sent and lost since a connection's previous pass are
+    /// added to its leader's totals; RTT and congestion window are replaced.
+    pub fn record_paths(&self, connections: Vec<(SocketAddr, usize, ConnectionStats)>) {
+        // Held throughout, so concurrent passes never count a delta twice
+        let mut previous = self
+            .connections
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut current: HashMap<SocketAddr, (Duration, u64, u32)> = HashMap::new();
+        let mut seen = HashMap::with_capacity(connections.len());
+        for (target, id, stats) in connections {
+            let before = previous.get(&id).copied().unwrap_or_default();
+            let mut path = self.paths.entry(target).or_default();
+            path.packets_sent += stats
+                .path
+                .sent_packets
+                .saturating_sub(before.path.sent_packets);
+            path.lost_packets += stats
+                .path
+                .lost_packets
+                .saturating_sub(before.path.lost_packets);
+            path.datagrams_sent += stats
+                .udp_tx
+                .datagrams
+                .saturating_sub(before.udp_tx.datagrams);
+            let (rtt, cwnd, open) = current.entry(target).or_default();
+            *rtt += stats.path.rtt;
+            *cwnd += stats.path.cwnd;
+            *open += 1;
+            seen.insert(id, stats);
+        }
+        *previous = seen;
+        for (target, (rtt, cwnd, open)) in current {
+            let mut path = self.paths.entry(target).or_default();
+            path.rtt_ms = (rtt / open).as_secs_f64() * 1_000.0;
+            path.cwnd = cwnd;
+        }
+    }
+
+    /// QUIC path statistics of `target`, once a connection to it was sampled
+    pub fn path(&self, target: &SocketAddr) -> Option<PathStats> {
+        self.paths.get(target).map(|path| *path)
+    }
+
+    /// Ranked snapshot; identities are resolved through `cartographer`
+    pub async fn report(&self, cartographer: &Cartographer) -> StatsReport {
+        let counters: Vec<(SocketAddr, Counters)> = self
+            .leaders
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect();
+        let topology = cartographer.topology();
+        let mut leaders = Vec::with_capacity(counters.len());
+        for (addr, counters) in counters {
+            let identity = cartographer.identity_for(addr).await;
+            let info = identity.and_then(|pk| topology.validator(&pk));
+            let rtt = identity.and_then(|pk| cartographer.rtt(&pk));
+            leaders.push(LeaderStats::new(
+                addr,
+                identity.map(|pk| pk.to_string()),
+                info,
+                rtt,
+                counters,
+                self.path(&addr),
+            ));
+        }
+        StatsReport::ranked(self.started_at_ms, self.started.elapsed(), leaders)
+    }
+
+    /// Rewrite `path` with the current report every `STATS_SAVE_INTERVAL`
+    /// until `stop` is cancelled, then once more
+    pub fn spawn_persister(
+        self: Arc<Self>,
+        path: PathBuf,
+        cartographer: Arc<Cartographer>,
+        stop: CancellationToken,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                let stopping = tokio::select! {
+                    _ = stop.cancelled() => true,
+                    _ = tokio::time::sleep(STATS_SAVE_INTERVAL) => false,
+                };
+                let report = self.report(&cartographer).await;
+                if let Err(e) = report.save(&path).await {
+                    debug!("Stats: Failed to write {:?}: {}", path, e);
+                }
+                if stopping {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+/// QUIC path of a leader's connections, from `quinn::Connection::stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PathStats {
+    /// Mean smoothed RTT of its open connections, as last sampled
+    pub rtt_ms: f64,
+    /// Congestion window (bytes) summed over its open connections, as last
+    /// sampled
+    pub cwnd: u64,
+    pub packets_sent: u64,
+    pub lost_packets: u64,
+    /// UDP datagrams sent (packets coalesced into one datagram count once)
+    pub datagrams_sent: u64,
+}
+
+impl PathStats {
+    /// Fraction of packets sent that were lost
+    pub fn loss_rate(&self) -> Option<f64> {
+        (self.packets_sent > 0).then(|| self.lost_packets as f64 / self.packets_sent as f64)
+    }
+}
+
+/// One leader's delivery record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaderStats {
+    pub addr: SocketAddr,
+    pub identity: Option<String>,
+    /// Validator Info name (reports from older versions have none)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Software version advertised in gossip
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Hosting network and country, e.g. `AS24940 Hetzner Online GmbH, DE`
+    #[serde(default)]
+    pub datacenter: Option<String>,
+    /// Smoothed round-trip time from the RTT prober
+    #[serde(default)]
+    pub rtt_ms: Option<f64>,
+    pub sent: u64,
+    pub stream_errors: u64,
+    /// Streams not written within `QUIC_SEND_TIMEOUT_MS`
+    #[serde(default)]
+    pub timeouts: u64,
+    pub connect_errors: u64,
+    pub handshakes: u64,
+    pub avg_handshake_ms: Option<f64>,
+    pub max_handshake_ms: Option<f64>,
+    pub landed: u64,
+    pub expired: u64,
+    /// QUIC path statistics, once a connection to the leader was sampled
+    #[serde(default)]
+    pub path: Option<PathStats>,
+}
+
+impl LeaderStats {
+    fn new(
+        addr: SocketAddr,
+        identity: Option<String>,
+        info: Option<&ValidatorInfo>,
+        rtt: Option<Duration>,
+        counters: Counters,
+        path: Option<PathStats>,
+    ) -> Self {
+        let millis = |micros: u64| micros as f64 / 1_000.0;
+        let handshakes = counters.handshakes;
+        Self {
+            addr,
+            identity,
+            name: info.and_then(|info| info.name.clone()),
+            version: info.and_then(|info| info.version.clone()),
+            datacenter: info.and_then(|info| info.location.datacenter()),
+            rtt_ms: rtt.map(|rtt| millis(rtt.as_micros() as u64)),
+            sent: counters.sent,
+            stream_errors: counters.stream_errors,
+            timeouts: counters.timeouts,
+            connect_errors: counters.connect_errors,
+            handshakes,
+            avg_handshake_ms: (handshakes > 0).then(|| millis(counters.handshake_us / handshakes)),
+            max_handshake_ms: (handshakes > 0).then(|| millis(counters.max_handshake_us)),
+            landed: counters.landed,
+            expired: counters.expired,
+            path,
+        }
+    }
+
+    pub fn attempts(&self) -> u64 {
+        self.sent + self.stream_errors + self.timeouts + self.connect_errors
+    }
+
+    /// Fraction of attempts that reached the leader
+    pub fn delivery_rate(&self) -> Option<f64> {
+        let attempts = self.attempts();
+        (attempts > 0).then(|| self.sent as f64 / attempts as f64)
+    }
+
+    /// Fraction of confirmed sends that landed (None without confirmation)
+    pub fn landing_rate(&self) -> Option<f64> {
+        let confirmed = self.landed + self.expired;
+        (confirmed > 0).then(|| self.landed as f64 / confirmed as f64)
+    }
+
+    /// Delivery rate, discounted by the landing rate when it is known
+    pub fn quality(&self) -> f64 {
+        self.delivery_rate().unwrap_or(0.0) * self.landing_rate().unwrap_or(1.0)
+    }
+}
+
+/// Leaders ranked best first
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsReport {
+    /// Unix time (ms) the reporting process started
+    pub started_at_ms: u64,
+    pub uptime_secs: u64,
+    pub leaders: Vec<LeaderStats>,
+}
+
+impl StatsReport {
+    /// Best quality first; ties go to more attempts, then faster handshakes
+    pub fn ranked(started_at_ms: u64, uptime: Duration, mut leaders: Vec<LeaderStats>) -> Self {
+        leaders.sort_by(|a, b| {
+            b.quality()
+                .total_cmp(&a.quality())
+                .then(b.attempts().cmp(&a.attempts()))
+                .then(
+                    a.avg_handshake_ms
+                        .unwrap_or(f64::MAX)
+                        .total_cmp(&b.avg_handshake_ms.unwrap_or(f64::MAX)),
+                )
+        });
+        Self {
+            started_at_ms,
+            uptime_secs: uptime.as_secs(),
+            leaders,
+        }
+    }
+
+    pub async fn load(path: &Path) -> Result<Self, ScramjetError> {
+        let bytes = tokio::fs::read(path).await?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| ScramjetError::SerializationError(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Write via a temp file, so readers never see a partial report
+    pub async fn save(&self, path: &Path) -> Result<(), ScramjetError> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| ScramjetError::SerializationError(e.to_string()))?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(&tmp, path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocklist::blocklist_handle;
+    use scramjet_common::SendFailure;
+    use solana_sdk::pubkey::Pubkey;
+    use std::collections::{HashMap, HashSet};
+
+    #[tokio::test]
+    async fn test_stats_ranking_and_persistence() {
+        let good: SocketAddr = "10.0.0.1:8009".parse().unwrap();
+        let flaky: SocketAddr = "10.0.0.2:8009".parse().unwrap();
+        let dropping: SocketAddr = "10.0.0.3:8009".parse().unwrap();
+        let stats = StatsRegistry::new();
+        for _ in 0..10 {
+            stats.record_send(good, None);
+            stats.record_send(dropping, None);
+        }
+        for _ in 0..5 {
+            stats.record_send(flaky, None);
+        }
+        let refused = ScramjetError::StreamError("refused".into());
+        stats.record_send(flaky, Some(&refused));
+        let timeout = ScramjetError::ConnectionError("timeout".into());
+        stats.record_send(flaky, Some(&timeout));
+        let hung = SendFailure::new(SendFailureKind::StreamTimeout, flaky, "hung");
+        stats.record_send(flaky, Some(&hung.into()));
+        stats.record_handshake(good, Duration::from_millis(4));
+        stats.record_handshake(good, Duration::from_millis(8));
+        stats.record_landing(good, SendOutcome::Landed { slots: 1 });
+        stats.record_landing(dropping, SendOutcome::Landed { slots: 2 });
+        stats.record_landing(dropping, SendOutcome::Failed(FailureStage::Expired));
+        // Send failures are counted by record_send, not as landings
+        stats.record_landing(dropping, SendOutcome::Failed(FailureStage::Stream));
+
+        let blocklist = blocklist_handle(HashSet::new());
+        let cartographer = Cartographer::new("http://mock-rpc".into(), blocklist);
+        let identity = Pubkey::new_unique();
+        cartographer
+            .install_topology(HashMap::from([(identity, good)]))
+            .await;
+        cartographer
+            .install_validator_names(HashMap::from([(identity, "Alpha".to_string())]))
+            .await;
+        cartographer.record_rtt(identity, Some(Duration::from_millis(30)));
+
+        // Two pooled connections to `good`: RTTs average, windows add up
+        let sample = |rtt_ms: u64, cwnd: u64, sent: u64, lost: u64| {
+            let mut stats = ConnectionStats::default();
+            stats.path.rtt = Duration::from_millis(rtt_ms);
+            stats.path.cwnd = cwnd;
+            stats.path.sent_packets = sent;
+            stats.path.lost_packets = lost;
+            stats.udp_tx.datagrams = sent;
+            stats
+        };
+        stats.record_paths(vec![
+            (good, 1, sample(20, 12_000, 100, 1)),
+            (good, 2, sample(40, 14_000, 50, 0)),
+        ]);
+        // Connection 2 closed; connection 3 replaced it from scratch
+        stats.record_paths(vec![
+            (good, 1, sample(24, 16_000, 150, 3)),
+            (good, 3, sample(30, 10_000, 50, 1)),
+        ]);
+        let report = stats.report(&cartographer).await;
+        let order: Vec<SocketAddr> = report.leaders.iter().map(|l| l.addr).collect();
+        assert_eq!(order, vec![good, flaky, dropping]);
+
+        let [good_stats, flaky_stats, dropping_stats] = &report.leaders[..] else {
+            panic!("expected three leaders");
+        };
+        assert_eq!(good_stats.identity, Some(identity.to_string()));
+        assert_eq!(good_stats.name.as_deref(), Some("Alpha"));
+        assert_eq!(flaky_stats.name, None);
+        assert_eq!(good_stats.rtt_ms, Some(30.0));
+        assert_eq!(good_stats.avg_handshake_ms, Some(6.0));
+        assert_eq!(good_stats.max_handshake_ms, Some(8.0));
+        assert_eq!(flaky_stats.stream_errors, 1);
+        assert_eq!(flaky_stats.timeouts, 1);
+        assert_eq!(flaky_stats.connect_errors, 1);
+        assert_eq!(flaky_stats.landing_rate(), None);
+        assert_eq!(dropping_stats.landing_rate(), Some(0.5));
+        let path = good_stats.path.unwrap();
+        assert_eq!(path.rtt_ms, 27.0);
+        assert_eq!(path.cwnd, 26_000);
+        assert_eq!(path.packets_sent, 250);
+        assert_eq!(path.lost_packets, 4);
+        assert_eq!(path.datagrams_sent, 250);
+        assert_eq!(path.loss_rate(), Some(4.0 / 250.0));
+        assert_eq!(flaky_stats.path, None);
+
+        let path = std::env::temp_dir().join(format!("scramjet-stats-{}.json", std::process::id()));
+        report.save(&path).await.unwrap();
+        assert_eq!(StatsReport::load(&path).await.unwrap(), report);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
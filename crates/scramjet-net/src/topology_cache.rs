@@ -0,0 +1,122 @@
+//! On-disk snapshot of the cluster map and leader schedule.
+//!
+//! Written after every successful refresh and read on startup, so a restart
+//! can target leaders within milliseconds instead of waiting on
+//! `getClusterNodes` / `getLeaderSchedule`. The snapshot is only a warm
+//! start: RPC is still queried in the background and replaces it.
+
+use scramjet_common::ScramjetError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bumped when the snapshot layout changes; other versions are ignored
+pub(crate) const SNAPSHOT_VERSION: u32 = 1;
+
+/// Cluster state as of `saved_at_ms`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopologySnapshot {
+    pub version: u32,
+    /// Unix time in milliseconds
+    pub saved_at_ms: u64,
+    pub genesis_hash: String,
+    pub epoch: u64,
+    pub epoch_start_slot: u64,
+    pub slots_in_epoch: u64,
+    /// Validator pubkey -> TPU QUIC socket
+    pub nodes: HashMap<String, SocketAddr>,
+    /// Leader pubkey -> slot offsets from `epoch_start_slot` (RPC layout)
+    pub schedule: HashMap<String, Vec<usize>>,
+}
+
+impl TopologySnapshot {
+    /// Milliseconds since the snapshot was taken (0 if the clock went backwards)
+    pub fn age_ms(&self) -> u64 {
+        unix_time_ms().saturating_sub(self.saved_at_ms)
+    }
+}
+
+pub(crate) fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// JSON snapshot file
+#[derive(Debug, Clone)]
+pub struct TopologyCache {
+    path: PathBuf,
+}
+
+impl TopologyCache {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the snapshot. `Ok(None)` if there is none yet or it was written
+    /// by an incompatible version.
+    pub async fn load(&self) -> Result<Option<TopologySnapshot>, ScramjetError> {
+        let bytes = match tokio::fs::read(&self.path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let snapshot: TopologySnapshot = serde_json::from_slice(&bytes).map_err(|e| {
+            ScramjetError::SerializationError(format!("{}: {}", self.path.display(), e))
+        })?;
+        Ok((snapshot.version == SNAPSHOT_VERSION).then_some(snapshot))
+    }
+
+    /// Write the snapshot (via a temp file, so readers never see a partial one)
+    pub async fn save(&self, snapshot: &TopologySnapshot) -> Result<(), ScramjetError> {
+        let json = serde_json::to_vec(snapshot)
+            .map_err(|e| ScramjetError::SerializationError(e.to_string()))?;
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, json).await?;
+        tokio::fs::rename(&tmp, &self.path).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cache_roundtrip_and_versioning() {
+        let dir = std::env::temp_dir().join(format!("scramjet-topology-{}", std::process::id()));
+        let cache = TopologyCache::new(dir.join("nested").join("topology.json"));
+        assert_eq!(cache.load().await.unwrap(), None);
+
+        let mut snapshot = TopologySnapshot {
+            version: SNAPSHOT_VERSION,
+            saved_at_ms: unix_time_ms(),
+            genesis_hash: "genesis".into(),
+            epoch: 700,
+            epoch_start_slot: 302_400_000,
+            slots_in_epoch: 432_000,
+            nodes: HashMap::from([("node".into(), "10.0.0.1:8009".parse().unwrap())]),
+            schedule: HashMap::from([("leader".into(), vec![0, 1, 2, 3])]),
+        };
+        cache.save(&snapshot).await.unwrap();
+        assert_eq!(cache.load().await.unwrap(), Some(snapshot.clone()));
+
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        cache.save(&snapshot).await.unwrap();
+        assert_eq!(cache.load().await.unwrap(), None);
+
+        std::fs::write(cache.path(), b"{not json").unwrap();
+        assert!(cache.load().await.is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,200 @@
+//! Unix domain socket ingestion for bots on the same host.
+//!
+//! Cheaper than HTTP or gRPC: each frame is a big-endian `u32` length
+//! followed by a signed transaction in wire format. Every frame is answered,
+//! in order, with a frame of the same shape holding an empty payload when the
+//! transaction was sent or a UTF-8 error otherwise. Clients may pipeline;
+//! when the send queue is full (and set to block), the socket stops being
+//! read.
+
+use crate::control;
+use crate::priority::Priority;
+use crate::queue::SendQueue;
+use crate::raw;
+use crate::server::{self, Submission};
+use log::{debug, info, warn};
+use scramjet_common::ScramjetError;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::UnixStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_util::sync::CancellationToken;
+
+/// Replies a connection may owe before it stops reading frames
+const MAX_PIPELINED: usize = 1024;
+
+/// Bind the socket at `path` (owner-only, see `control::bind_private_socket`)
+/// and forward frames to `queue`, as `priority` sends, until `stop` is
+/// cancelled
+pub fn spawn_uds_server(
+    path: PathBuf,
+    queue: Arc<SendQueue>,
+    priority: Priority,
+    stop: CancellationToken,
+) -> Result<tokio::task::JoinHandle<()>, ScramjetError> {
+    let listener = control::bind_private_socket(&path)?;
+    info!("Ingest: Unix socket listening on {:?}", path);
+
+    Ok(tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = stop.cancelled() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let queue = queue.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = serve_connection(stream, queue, priority).await {
+                                debug!("Ingest: Unix socket connection error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => warn!("Ingest: Unix socket accept failed: {}", e),
+                },
+            }
+        }
+        let _ = std::fs::remove_file(&path);
+    }))
+}
+
+type Reply = Result<oneshot::Receiver<Result<(), String>>, String>;
+
+async fn serve_connection(
+    stream: UnixStream,
+    queue: Arc<SendQueue>,
+    priority: Priority,
+) -> Result<(), ScramjetError> {
+    let (mut reader, writer) = stream.into_split();
+    // Replies are written by their own task so reading never waits on a send
+    let (replies, pending) = mpsc::channel(MAX_PIPELINED);
+    let writer = tokio::spawn(write_replies(writer, pending));
+
+    while let Some(frame) = read_frame(&mut reader).await? {
+        let reply = match raw::parse_wire_transaction(frame) {
+            Ok(transaction) => {
+                let (submission, receiver) = Submission::new(transaction.with_priority(priority));
+                match queue.push(submission).await {
+                    // A refused submission was answered with the reason
+                    Ok(()) | Err(ScramjetError::QueueFull(_)) => Ok(receiver),
+                    Err(_) => break,
+                }
+            }
+            Err(e) => Err(e.to_string()),
+        };
+        if replies.send(reply).await.is_err() {
+            break;
+        }
+    }
+    drop(replies);
+    writer
+        .await
+        .map_err(|e| ScramjetError::ConnectionError(e.to_string()))?
+}
+
+async fn write_replies(
+    mut writer: OwnedWriteHalf,
+    mut pending: mpsc::Receiver<Reply>,
+) -> Result<(), ScramjetError> {
+    while let Some(reply) = pending.recv().await {
+        let result = match reply {
+            Ok(receiver) => server::outcome(receiver).await,
+            Err(e) => Err(e),
+        };
+        let message = result.err().unwrap_or_default();
+        writer.write_u32(message.len() as u32).await?;
+        writer.write_all(message.as_bytes()).await?;
+    }
+    Ok(())
+}
+
+/// Next frame, or `None` when the client closed the connection between frames
+async fn read_frame(reader: &mut OwnedReadHalf) -> Result<Option<Vec<u8>>, ScramjetError> {
+    let len = match reader.read_u32().await {
+        Ok(len) => len as usize,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    // A bad length leaves no way to find the next frame, so the connection ends
+    if len > PACKET_DATA_SIZE {
+        return Err(ScramjetError::InvalidTransaction(format!(
+            "{} byte frame exceeds the {} byte packet limit",
+            len, PACKET_DATA_SIZE
+        )));
+    }
+    let mut frame = vec![0u8; len];
+    reader.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::OverflowPolicy;
+    use solana_sdk::hash::Hash;
+    use solana_sdk::signature::{Keypair, Signer};
+    #[allow(deprecated)]
+    use solana_sdk::system_instruction;
+    use solana_sdk::transaction::Transaction;
+
+    fn signed_transfer(lamports: u64) -> Vec<u8> {
+        let payer = Keypair::new();
+        let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), lamports);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+        bincode::serialize(&tx).unwrap()
+    }
+
+    async fn read_reply(stream: &mut UnixStream) -> String {
+        let len = stream.read_u32().await.unwrap() as usize;
+        let mut message = vec![0u8; len];
+        stream.read_exact(&mut message).await.unwrap();
+        String::from_utf8(message).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_uds_frames_answered_in_order() {
+        let path = std::env::temp_dir().join(format!("scramjet-uds-{}.sock", std::process::id()));
+        let stop = CancellationToken::new();
+        let queue = Arc::new(SendQueue::new(4, OverflowPolicy::Block));
+        let server =
+            spawn_uds_server(path.clone(), queue.clone(), Priority::High, stop.clone()).unwrap();
+
+        // Sender side: odd lamports fail
+        tokio::spawn(async move {
+            while let Some(submission) = queue.pop().await {
+                assert_eq!(submission.transaction.priority, Priority::High);
+                let tx: Transaction = bincode::deserialize(&submission.transaction.bytes).unwrap();
+                let result = match tx.message.instructions[0].data[4] % 2 {
+                    0 => Ok(()),
+                    _ => Err(ScramjetError::NoLeaderFound(0)),
+                };
+                submission.respond(result);
+            }
+        });
+
+        // Pipeline every frame before reading any reply
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        let frames = [signed_transfer(2), signed_transfer(1), b"junk".to_vec()];
+        for frame in &frames {
+            stream.write_u32(frame.len() as u32).await.unwrap();
+            stream.write_all(frame).await.unwrap();
+        }
+        assert_eq!(read_reply(&mut stream).await, "");
+        assert!(read_reply(&mut stream).await.contains("No leader"));
+        assert!(!read_reply(&mut stream).await.is_empty());
+
+        // Oversized frame closes the connection
+        stream.write_u32(PACKET_DATA_SIZE as u32 + 1).await.unwrap();
+        assert_eq!(stream.read(&mut [0u8; 1]).await.unwrap(), 0);
+
+        stop.cancel();
+        server.await.unwrap();
+        assert!(!path.exists());
+    }
+}
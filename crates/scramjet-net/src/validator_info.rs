@@ -0,0 +1,354 @@
+//! Validator metadata: who a leader is and where it runs.
+//!
+//! Names come from the on-chain Validator Info records (Config program
+//! accounts published with `solana validator-info publish`), or from a
+//! validators.app-style JSON registry: either `{"<identity>": "<name>"}` or
+//! an array of objects with an `account` (or `identity`) and a `name`.
+//! Software versions come from gossip (`getClusterNodes`), and country / ASN
+//! from MaxMind databases (`GEOIP_DB_FILES`, GeoLite2 City / Country / ASN)
+//! when configured.
+
+use crate::rpc_pool::RpcPool;
+use log::debug;
+use maxminddb::Reader;
+use scramjet_common::ScramjetError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_rpc_client_api::config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_rpc_client_api::filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+
+/// Owner of every Validator Info account
+pub const CONFIG_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("Config1111111111111111111111111111111111111");
+
+/// First config key of a Validator Info account (marks the record type)
+pub const VALIDATOR_INFO_ID: Pubkey =
+    solana_sdk::pubkey!("Va1idator1nfo111111111111111111111111111111");
+
+/// Identity -> display name
+pub type ValidatorNames = HashMap<Pubkey, String>;
+
+/// What is known about one validator (every field optional)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidatorInfo {
+    /// Validator Info display name
+    pub name: Option<String>,
+    /// Software version advertised in gossip
+    pub version: Option<String>,
+    #[serde(flatten)]
+    pub location: Location,
+}
+
+impl fmt::Display for ValidatorInfo {
+    /// `Alpha (v2.1.5, AS24940 Hetzner Online GmbH, DE)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name.as_deref().unwrap_or("unnamed"))?;
+        let details: Vec<String> = self
+            .version
+            .iter()
+            .map(|version| format!("v{}", version))
+            .chain(self.location.datacenter())
+            .collect();
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Where a validator's TPU address is hosted, per the GeoIP databases
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    /// ISO 3166 country code
+    pub country: Option<String>,
+    pub city: Option<String>,
+    /// Autonomous system number
+    pub asn: Option<u32>,
+    /// Autonomous system organization (usually the hosting provider)
+    pub as_org: Option<String>,
+}
+
+impl Location {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Provider and country, e.g. `AS24940 Hetzner Online GmbH, DE`
+    pub fn datacenter(&self) -> Option<String> {
+        let network = match (self.asn, self.as_org.as_deref()) {
+            (Some(asn), Some(org)) => Some(format!("AS{} {}", asn, org)),
+            (Some(asn), None) => Some(format!("AS{}", asn)),
+            (None, org) => org.map(String::from),
+        };
+        let parts: Vec<String> = network.into_iter().chain(self.country.clone()).collect();
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+
+    /// Fill the fields still unknown from `other`
+    fn merge(&mut self, other: Location) {
+        self.country = self.country.take().or(other.country);
+        self.city = self.city.take().or(other.city);
+        self.asn = self.asn.or(other.asn);
+        self.as_org = self.as_org.take().or(other.as_org);
+    }
+}
+
+/// MaxMind databases (any mix of City, Country and ASN), consulted in order
+pub struct GeoIp {
+    readers: Vec<Reader<Vec<u8>>>,
+}
+
+/// The fields `Location` needs from City, Country and ASN records alike
+#[derive(Deserialize)]
+struct GeoRecord<'a> {
+    #[serde(borrow)]
+    country: Option<NamedPlace<'a>>,
+    #[serde(borrow)]
+    city: Option<NamedPlace<'a>>,
+    autonomous_system_number: Option<u32>,
+    autonomous_system_organization: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct NamedPlace<'a> {
+    iso_code: Option<&'a str>,
+    #[serde(borrow)]
+    names: Option<BTreeMap<&'a str, &'a str>>,
+}
+
+impl GeoIp {
+    pub fn open<P: AsRef<Path>>(paths: &[P]) -> Result<Self, ScramjetError> {
+        let readers = paths
+            .iter()
+            .map(|path| {
+                Reader::open_readfile(path).map_err(|e| {
+                    ScramjetError::ConfigError(format!(
+                        "GeoIP database {}: {}",
+                        path.as_ref().display(),
+                        e
+                    ))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { readers })
+    }
+
+    /// Everything the databases know about `ip` (empty when it is not listed)
+    pub fn lookup(&self, ip: IpAddr) -> Location {
+        let mut location = Location::default();
+        for reader in &self.readers {
+            let Ok(record) = reader.lookup::<GeoRecord>(ip) else {
+                continue;
+            };
+            location.merge(Location {
+                country: record
+                    .country
+                    .and_then(|country| country.iso_code)
+                    .map(String::from),
+                city: record
+                    .city
+                    .and_then(|city| city.names?.get("en").map(|name| name.to_string())),
+                asn: record.autonomous_system_number,
+                as_org: record.autonomous_system_organization.map(String::from),
+            });
+        }
+        location
+    }
+}
+
+impl fmt::Debug for GeoIp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GeoIp")
+            .field("databases", &self.readers.len())
+            .finish()
+    }
+}
+
+/// Every published Validator Info name
+pub async fn fetch_onchain_names(rpc: &RpcPool) -> Result<ValidatorNames, ScramjetError> {
+    let accounts = rpc
+        .call(|rpc| async move {
+            // Config keys are a short_vec: one length byte, then the record type
+            let config = RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    1,
+                    VALIDATOR_INFO_ID.to_bytes().to_vec(),
+                ))]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            };
+            rpc.get_program_accounts_with_config(&CONFIG_PROGRAM_ID, config)
+                .await
+        })
+        .await
+        .map_err(|e| ScramjetError::RpcError(format!("Failed to fetch validator info: {}", e)))?;
+    let names: ValidatorNames = accounts
+        .iter()
+        .filter_map(|(_, account)| parse_validator_info(&account.data))
+        .collect();
+    debug!("Validator info: {} names on chain", names.len());
+    Ok(names)
+}
+
+/// Identity and name from a Validator Info account's data: config keys
+/// `[(VALIDATOR_INFO_ID, false), (identity, true)]`, then the info JSON as a
+/// bincode string (u64 length + UTF-8)
+pub fn parse_validator_info(data: &[u8]) -> Option<(Pubkey, String)> {
+    const KEY_LEN: usize = 32 + 1;
+    let (&keys, rest) = data.split_first()?;
+    if keys != 2 || rest.len() < 2 * KEY_LEN + 8 {
+        return None;
+    }
+    if rest[..32] != VALIDATOR_INFO_ID.to_bytes() {
+        return None;
+    }
+    let identity = Pubkey::try_from(&rest[KEY_LEN..KEY_LEN + 32]).ok()?;
+
+    let rest = &rest[2 * KEY_LEN..];
+    let len = u64::from_le_bytes(rest[..8].try_into().ok()?) as usize;
+    let info: Value = serde_json::from_slice(rest[8..].get(..len)?).ok()?;
+    let name = info.get("name")?.as_str()?.trim();
+    (!name.is_empty()).then(|| (identity, name.to_string()))
+}
+
+/// Names from a registry file or http(s) URL
+pub async fn load_registry(source: &str) -> Result<ValidatorNames, ScramjetError> {
+    let body = if source.starts_with("http://") || source.starts_with("https://") {
+        let response = reqwest::get(source)
+            .await
+            .map_err(|e| ScramjetError::ConnectionError(format!("{}: {}", source, e)))?;
+        if !response.status().is_success() {
+            return Err(ScramjetError::ConnectionError(format!(
+                "{}: HTTP {}",
+                source,
+                response.status()
+            )));
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| ScramjetError::ConnectionError(format!("{}: {}", source, e)))?
+    } else {
+        tokio::fs::read_to_string(source).await?
+    };
+    parse_registry(&body)
+}
+
+/// Parse a registry body (entries without a name or a valid identity are skipped)
+pub fn parse_registry(body: &str) -> Result<ValidatorNames, ScramjetError> {
+    let value: Value = serde_json::from_str(body)
+        .map_err(|e| ScramjetError::SerializationError(format!("Validator registry: {}", e)))?;
+    let entries: Vec<(&str, &Value)> = match &value {
+        Value::Object(map) => map.iter().map(|(key, name)| (key.as_str(), name)).collect(),
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| {
+                let identity = item.get("account").or_else(|| item.get("identity"))?;
+                Some((identity.as_str()?, item.get("name")?))
+            })
+            .collect(),
+        _ => {
+            return Err(ScramjetError::SerializationError(
+                "Validator registry must be a JSON object or array".into(),
+            ))
+        }
+    };
+    Ok(entries
+        .into_iter()
+        .filter_map(|(identity, name)| {
+            let name = name.as_str()?.trim();
+            (!name.is_empty()).then_some((identity.parse().ok()?, name.to_string()))
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator_info_data(identity: &Pubkey, info: &str) -> Vec<u8> {
+        let mut data = vec![2];
+        data.extend_from_slice(&VALIDATOR_INFO_ID.to_bytes());
+        data.push(0);
+        data.extend_from_slice(&identity.to_bytes());
+        data.push(1);
+        data.extend_from_slice(&(info.len() as u64).to_le_bytes());
+        data.extend_from_slice(info.as_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_validator_info() {
+        let identity = Pubkey::new_unique();
+        let data = validator_info_data(&identity, r#"{"name":"Alpha ","website":"a.io"}"#);
+        assert_eq!(
+            parse_validator_info(&data),
+            Some((identity, "Alpha".to_string()))
+        );
+
+        // Unnamed, truncated and other config records are ignored
+        assert!(parse_validator_info(&validator_info_data(&identity, "{}")).is_none());
+        assert!(parse_validator_info(&data[..data.len() - 1]).is_none());
+        let mut other = data.clone();
+        other[1] ^= 1;
+        assert!(parse_validator_info(&other).is_none());
+    }
+
+    #[test]
+    fn test_validator_info_display() {
+        let mut info = ValidatorInfo {
+            name: Some("Alpha".into()),
+            version: Some("2.1.5".into()),
+            location: Location {
+                country: Some("DE".into()),
+                city: None,
+                asn: Some(24940),
+                as_org: Some("Hetzner Online GmbH".into()),
+            },
+        };
+        assert_eq!(
+            info.to_string(),
+            "Alpha (v2.1.5, AS24940 Hetzner Online GmbH, DE)"
+        );
+
+        info.version = None;
+        info.location.as_org = None;
+        info.location.country = None;
+        assert_eq!(info.to_string(), "Alpha (AS24940)");
+        assert_eq!(ValidatorInfo::default().to_string(), "unnamed");
+        assert!(ValidatorInfo::default().location.datacenter().is_none());
+
+        // Flattened location in JSON
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["asn"], 24940);
+        assert!(json["country"].is_null());
+    }
+
+    #[test]
+    fn test_parse_registry() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let map = parse_registry(&format!(r#"{{"{}": "Alpha", "nope": "Bad"}}"#, a)).unwrap();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map[&a], "Alpha");
+
+        // validators.app shape: unnamed entries are skipped
+        let list = parse_registry(&format!(
+            r#"[{{"account": "{}", "name": "Alpha"}}, {{"identity": "{}", "name": null}}]"#,
+            a, b
+        ))
+        .unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[&a], "Alpha");
+
+        assert!(parse_registry("42").is_err());
+    }
+}
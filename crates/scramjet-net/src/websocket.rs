@@ -0,0 +1,169 @@
+//! Websocket slot clock: standard `slotSubscribe` over an RPC node's pubsub
+//! endpoint.
+//!
+//! Sits between Geyser and RPC polling: most RPC providers serve it for free
+//! and a notification arrives as soon as the node processes a slot, instead of
+//! once per poll interval. Reconnects with the same exponential backoff as the
+//! Geyser monitor.
+
+use crate::cartographer::Cartographer;
+use futures::StreamExt;
+use log::{debug, error};
+use scramjet_common::ScramjetError;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+/// Spawn the websocket slot subscriber with exponential backoff reconnection.
+/// Returns the task and a oneshot receiver that signals when the first
+/// subscription attempt completes. The task exits when `shutdown` is cancelled.
+pub fn spawn_slot_subscriber(
+    url: String,
+    cartographer: Arc<Cartographer>,
+    initial_delay: Duration,
+    max_delay: Duration,
+    shutdown: CancellationToken,
+) -> (
+    tokio::task::JoinHandle<()>,
+    oneshot::Receiver<Result<(), ScramjetError>>,
+) {
+    let (startup_tx, startup_rx) = oneshot::channel();
+
+    let task = tokio::spawn(async move {
+        let mut retry_delay = initial_delay;
+        let mut startup_tx = Some(startup_tx);
+
+        loop {
+            let mut subscribed = false;
+            let result = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                result = stream_slots(&url, &cartographer, || {
+                    subscribed = true;
+                    if let Some(tx) = startup_tx.take() {
+                        let _ = tx.send(Ok(()));
+                    }
+                }) => result,
+            };
+            if subscribed {
+                // Reset backoff after a successful subscription
+                retry_delay = initial_delay;
+            }
+            match result {
+                Ok(()) => error!(
+                    "Websocket slot stream closed. Reconnecting in {:?}...",
+                    retry_delay
+                ),
+                Err(e) => {
+                    error!(
+                        "Websocket slot stream error: {}. Reconnecting in {:?}...",
+                        e, retry_delay
+                    );
+                    // Signal startup failure (once)
+                    if let Some(tx) = startup_tx.take() {
+                        let _ = tx.send(Err(e));
+                    }
+                }
+            }
+
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = tokio::time::sleep(retry_delay) => {}
+            }
+
+            // Exponential backoff: double delay, capped at max
+            retry_delay = std::cmp::min(retry_delay.saturating_mul(2), max_delay);
+        }
+        debug!("Websocket slot subscriber stopped");
+    });
+
+    (task, startup_rx)
+}
+
+/// Subscribe and feed slots into the clock until the stream ends.
+/// `on_subscribed` runs once the subscription is confirmed.
+async fn stream_slots(
+    url: &str,
+    cartographer: &Cartographer,
+    on_subscribed: impl FnOnce(),
+) -> Result<(), ScramjetError> {
+    let client = PubsubClient::new(url)
+        .await
+        .map_err(|e| ScramjetError::WebsocketError(format!("Connect to {}: {}", url, e)))?;
+    let (mut slots, unsubscribe) = client
+        .slot_subscribe()
+        .await
+        .map_err(|e| ScramjetError::WebsocketError(format!("slotSubscribe: {}", e)))?;
+    on_subscribed();
+
+    while let Some(info) = slots.next().await {
+        cartographer.update_slot(info.slot);
+    }
+    unsubscribe().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocklist::blocklist_handle;
+    use futures::SinkExt;
+    use serde_json::{json, Value};
+    use std::collections::HashSet;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    #[tokio::test]
+    async fn test_websocket_slots_drive_clock() {
+        // Minimal pubsub node: confirm the subscription, then notify two slots
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let Some(Ok(Message::Text(request))) = ws.next().await else {
+                panic!("expected a subscribe request");
+            };
+            let request: Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["method"], "slotSubscribe");
+            let reply = json!({ "jsonrpc": "2.0", "result": 7, "id": request["id"] });
+            ws.send(Message::Text(reply.to_string())).await.unwrap();
+            for slot in [41u64, 42] {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "slotNotification",
+                    "params": {
+                        "result": { "slot": slot, "parent": slot - 1, "root": slot - 32 },
+                        "subscription": 7,
+                    },
+                });
+                ws.send(Message::Text(notification.to_string()))
+                    .await
+                    .unwrap();
+            }
+            // Hold the connection open until the client goes away
+            while ws.next().await.is_some() {}
+        });
+
+        let blocklist = blocklist_handle(HashSet::new());
+        let cartographer = Arc::new(Cartographer::new("http://mock-rpc".into(), blocklist));
+        let mut slots = cartographer.subscribe_slots();
+        let shutdown = CancellationToken::new();
+        let (task, startup) = spawn_slot_subscriber(
+            url,
+            cartographer.clone(),
+            Duration::from_millis(10),
+            Duration::from_millis(100),
+            shutdown.clone(),
+        );
+        startup.await.unwrap().unwrap();
+        tokio::time::timeout(Duration::from_secs(5), slots.wait_for(|slot| *slot == 42))
+            .await
+            .expect("slot notifications did not reach the clock")
+            .unwrap();
+
+        shutdown.cancel();
+        task.await.unwrap();
+    }
+}
@@ -0,0 +1,130 @@
+//! Engine retry and Scout failover against mock validators in chaos mode.
+//! Seeds are fixed, so a failure here replays the same faults locally.
+
+use bytes::Bytes;
+use scramjet_common::{Config, SendFailureKind};
+use scramjet_net::blocklist::blocklist_handle;
+use scramjet_net::cartographer::Cartographer;
+use scramjet_net::engine::QuicEngine;
+use scramjet_net::scout::{self, WarmBudget};
+use scramjet_testkit::{Chaos, MockCluster, MockValidator, SLOTS_PER_LEADER};
+use solana_sdk::signature::Keypair;
+use std::collections::HashSet;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Defaults, but a silent port fails its handshake within a couple of seconds
+fn config() -> Config {
+    let mut config = Config::from_env().expect("Failed to load config");
+    config.quic_keep_alive_secs = 1;
+    config.quic_idle_timeout_secs = 2;
+    config
+}
+
+#[tokio::test]
+async fn test_sends_survive_chaos() {
+    let validator = MockValidator::builder()
+        .chaos(
+            Chaos::seeded(7)
+                .refuse_streams(0.2)
+                .drop_connections(0.2)
+                .delay_handshakes(0.5, Duration::from_millis(50)),
+        )
+        .start()
+        .unwrap();
+    let engine = QuicEngine::new(&Keypair::new(), &config()).expect("Failed to init engine");
+
+    // Streams the validator has dealt with, one way or another
+    let settled = |validator: &MockValidator| {
+        validator.transaction_count() + validator.refused_streams() + validator.resets()
+    };
+    let mut failed = 0;
+    for i in 0..40u8 {
+        let before = settled(&validator);
+        let send = engine.send_transaction(validator.addr(), Bytes::from(vec![i; 64]));
+        match tokio::time::timeout(TIMEOUT, send)
+            .await
+            .expect("send hung")
+        {
+            Ok(()) => {}
+            Err(e) => {
+                // Whatever chaos does is worth retrying; only attempts run out
+                let failure = e.send_failure().expect("unclassified send error");
+                assert!(failure.kind.is_retryable(), "{}", failure);
+                failed += 1;
+            }
+        }
+        // One stream at a time keeps the draws in seed order (a stream
+        // written into a dying connection never arrives)
+        let _ = tokio::time::timeout(Duration::from_millis(500), async {
+            while settled(&validator) == before {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+    }
+
+    assert!(validator.resets() > 0);
+    assert!(validator.refused_streams() > 0);
+    assert!(validator.delayed_handshakes() > 0);
+    // Dropped connections were replaced and sends kept landing
+    assert!(validator.handshakes() > 1);
+    assert!(validator.transaction_count() > 0);
+    assert!(failed < 40);
+}
+
+#[tokio::test]
+async fn test_scout_warms_around_wrong_ports() {
+    let cluster = MockCluster::with_chaos(4, Chaos::seeded(9).wrong_ports(0.5)).unwrap();
+    // Which validators get a silent port is up to the seed's draws: check
+    // against what the topology advertises, not fixed indices
+    let (wrong, right): (Vec<usize>, Vec<usize>) = (0..cluster.size())
+        .partition(|&index| cluster.advertised(index) != cluster.validator(index).addr());
+    assert!(!wrong.is_empty(), "no validator misadvertised");
+    assert!(!right.is_empty(), "every validator misadvertised");
+
+    let cartographer =
+        Cartographer::new("http://mock-rpc".into(), blocklist_handle(HashSet::new()));
+    cartographer
+        .install_schedule(cluster.leader_schedule(0..64))
+        .await;
+    cartographer.install_topology(cluster.topology()).await;
+    cartographer.update_slot(0);
+    let mut config = config();
+    config.send_retry_max_attempts = 1;
+    let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+
+    // Every validator leads within the lookahead: the misadvertised ones
+    // fail, the rest warm
+    let budget = WarmBudget {
+        lookahead: SLOTS_PER_LEADER * cluster.size() as u64,
+        max_warm: 0,
+        capacity: None,
+    };
+    let warmed: HashSet<_> = scout::warm_pass(&cartographer, &engine, 0, budget, None)
+        .await
+        .into_iter()
+        .collect();
+    let expected: HashSet<_> = right
+        .iter()
+        .map(|&index| cluster.validator(index).addr())
+        .collect();
+    assert_eq!(warmed, expected);
+    for &index in &wrong {
+        assert_eq!(cluster.validator(index).handshakes(), 0);
+    }
+
+    // Sending in its window times out on the handshake: a connection failure
+    let slot = SLOTS_PER_LEADER * wrong[0] as u64;
+    let target = cartographer.get_target(slot).await.unwrap();
+    assert_eq!(target, cluster.advertised(wrong[0]));
+    let err = engine
+        .send_transaction(target, Bytes::from_static(b"lost"))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        err.send_failure().map(|failure| failure.kind),
+        Some(SendFailureKind::HandshakeTimeout)
+    );
+}
@@ -0,0 +1,204 @@
+//! End-to-end: cartographer, Scout and engine against a mock cluster of
+//! leaders, with the slot clock scripted by the test.
+
+use bytes::Bytes;
+use scramjet_common::Config;
+use scramjet_net::blocklist::{blocklist_handle, BlocklistHandle, ShieldLists};
+use scramjet_net::cartographer::Cartographer;
+use scramjet_net::engine::QuicEngine;
+use scramjet_net::leader_tracker::LeaderTracker;
+use scramjet_net::scout::{self, WarmBudget};
+use scramjet_testkit::{MockCluster, ScriptedClock, SLOTS_PER_LEADER};
+use solana_sdk::signature::Keypair;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Two leader windows ahead, no budget
+const LOOKAHEAD: WarmBudget = WarmBudget {
+    lookahead: 8,
+    max_warm: 0,
+    capacity: None,
+};
+
+struct Harness {
+    cluster: MockCluster,
+    cartographer: Arc<Cartographer>,
+    engine: Arc<QuicEngine>,
+    clock: ScriptedClock,
+    blocklist: BlocklistHandle,
+}
+
+impl Harness {
+    /// `leaders` mock validators, their schedule and topology installed, and
+    /// the cartographer following a clock stopped at `slot`
+    async fn start(leaders: usize, slot: u64) -> Self {
+        let cluster = MockCluster::start(leaders).unwrap();
+        let blocklist = blocklist_handle(HashSet::new());
+        let cartographer = Arc::new(Cartographer::new(
+            "http://mock-rpc".into(),
+            blocklist.clone(),
+        ));
+        cartographer
+            .install_schedule(cluster.leader_schedule(0..256))
+            .await;
+        cartographer.install_topology(cluster.topology()).await;
+
+        let clock = ScriptedClock::new(slot);
+        let tracker = cartographer.clone();
+        clock.on_slot(move |slot| tracker.update_slot(slot));
+
+        let config = Config::from_env().expect("Failed to load config");
+        let engine = QuicEngine::new(&Keypair::new(), &config).expect("Failed to init engine");
+        Self {
+            cluster,
+            cartographer,
+            engine: Arc::new(engine),
+            clock,
+            blocklist,
+        }
+    }
+
+    /// Send to the current slot's leader, as `fire` does; false if the
+    /// Shield leaves no target
+    async fn fire(&self, payload: &'static [u8]) -> bool {
+        let slot = self.cartographer.get_known_slot();
+        let Some(target) = self.cartographer.get_target(slot).await else {
+            return false;
+        };
+        self.engine
+            .send_transaction(target, Bytes::from_static(payload))
+            .await
+            .unwrap();
+        true
+    }
+
+    async fn scout(&self) -> Vec<std::net::SocketAddr> {
+        let slot = self.cartographer.get_known_slot();
+        scout::warm_pass(&self.cartographer, &self.engine, slot, LOOKAHEAD, None).await
+    }
+
+    /// Handshakes per validator, once every warmed one has seen its own
+    async fn handshakes(&self, warmed: &[usize]) -> Vec<usize> {
+        for &i in warmed {
+            let validator = self.cluster.validator(i);
+            assert!(validator.wait_for_handshakes(1, TIMEOUT).await);
+        }
+        (0..self.cluster.size())
+            .map(|i| self.cluster.validator(i).handshakes())
+            .collect()
+    }
+}
+
+#[tokio::test]
+async fn test_scout_prewarms_upcoming_leaders() {
+    // Last slot of leader 0: the next two windows belong to leaders 1 and 2
+    let harness = Harness::start(4, 3).await;
+    let cluster = &harness.cluster;
+
+    let warmed = harness.scout().await;
+    assert_eq!(
+        warmed,
+        vec![cluster.validator(1).addr(), cluster.validator(2).addr()]
+    );
+    assert_eq!(harness.handshakes(&[1, 2]).await, vec![0, 1, 1, 0]);
+
+    // Leader 1 takes over: the send rides the pre-warmed connection
+    harness.clock.advance(1);
+    assert!(harness.fire(b"warm").await);
+    assert!(cluster.validator(1).wait_for_transactions(1, TIMEOUT).await);
+    assert_eq!(cluster.validator(1).transactions()[0].data, b"warm");
+    assert_eq!(harness.handshakes(&[1, 2]).await, vec![0, 1, 1, 0]);
+}
+
+#[tokio::test]
+async fn test_fire_follows_leader_rotation() {
+    let harness = Harness::start(3, 0).await;
+    let cluster = &harness.cluster;
+
+    for (window, leader) in [0, 1, 2, 0].into_iter().enumerate() {
+        assert_eq!(cluster.leader_of(harness.clock.slot()), leader);
+        assert!(harness.fire(b"tx").await);
+        // Window 3 wraps around to leader 0, which now has its second
+        let expected = if window == 3 { 2 } else { 1 };
+        assert!(
+            cluster
+                .validator(leader)
+                .wait_for_transactions(expected, TIMEOUT)
+                .await,
+            "leader {} missed the send for window {}",
+            leader,
+            window
+        );
+        harness.clock.advance(SLOTS_PER_LEADER);
+    }
+    let counts: Vec<usize> = (0..3)
+        .map(|i| cluster.validator(i).transaction_count())
+        .collect();
+    assert_eq!(counts, vec![2, 1, 1]);
+}
+
+#[tokio::test]
+async fn test_shield_filters_blocked_leader() {
+    let harness = Harness::start(4, 3).await;
+    let cluster = &harness.cluster;
+    harness
+        .blocklist
+        .store(Arc::new(ShieldLists::blocking(HashSet::from([
+            cluster.identity(1)
+        ]))));
+
+    // Scout skips the blocked leader
+    let warmed = harness.scout().await;
+    assert_eq!(warmed, vec![cluster.validator(2).addr()]);
+    assert_eq!(harness.handshakes(&[2]).await, vec![0, 0, 1, 0]);
+
+    // ... and so does fire during its window
+    harness.clock.advance(1);
+    assert!(!harness.fire(b"blocked").await);
+
+    // Next window: an allowed leader again
+    harness.clock.advance(SLOTS_PER_LEADER);
+    assert!(harness.fire(b"allowed").await);
+    assert!(cluster.validator(2).wait_for_transactions(1, TIMEOUT).await);
+    assert_eq!(cluster.validator(1).handshakes(), 0);
+    assert_eq!(cluster.validator(1).transaction_count(), 0);
+}
+
+#[tokio::test]
+async fn test_tracker_hands_off_at_rotation() {
+    let harness = Harness::start(3, 2).await;
+    let cluster = &harness.cluster;
+    let tracker = LeaderTracker::new(harness.cartographer.clone(), harness.engine.clone());
+
+    tracker.on_slot(harness.clock.slot()).await;
+    let active = harness.engine.active_connection().unwrap();
+    assert_eq!(active.target, cluster.validator(0).addr());
+    assert!(active.covers(3) && !active.covers(4));
+
+    // Last slot of the window: the next leader's pool is staged, not promoted
+    tracker.on_slot(harness.clock.advance(1)).await;
+    assert_eq!(harness.handshakes(&[0, 1]).await, vec![1, 1, 0]);
+    let active = harness.engine.active_connection().unwrap();
+    assert_eq!(active.target, cluster.validator(0).addr());
+
+    // Rotation: the staged pool takes over without another handshake
+    tracker.on_slot(harness.clock.advance(1)).await;
+    let active = harness.engine.active_connection().unwrap();
+    assert_eq!(active.target, cluster.validator(1).addr());
+    assert_eq!(tracker.handoffs(), 2);
+    assert!(harness.fire(b"handoff").await);
+    assert!(cluster.validator(1).wait_for_transactions(1, TIMEOUT).await);
+    assert_eq!(harness.handshakes(&[0, 1]).await, vec![1, 1, 0]);
+
+    // A blocked leader is demoted mid-window: no active connection
+    harness
+        .blocklist
+        .store(Arc::new(ShieldLists::blocking(HashSet::from([
+            cluster.identity(1)
+        ]))));
+    tracker.on_slot(harness.clock.advance(1)).await;
+    assert!(harness.engine.active_connection().is_none());
+}
@@ -0,0 +1,143 @@
+//! Chaos mode: seeded random faults for resilience tests.
+
+use crate::validator::Fault;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Random misbehavior for a [`MockValidator`](crate::MockValidator) or a
+/// [`MockCluster`](crate::MockCluster). Every draw comes from an RNG seeded
+/// with `seed`: streams delivered in the same order meet the same faults, so
+/// a run that failed in CI replays locally from its seed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chaos {
+    seed: u64,
+    refuse_streams: f64,
+    drop_connections: f64,
+    delay_handshakes: f64,
+    handshake_delay: Duration,
+    wrong_ports: f64,
+}
+
+impl Chaos {
+    /// No faults until they are switched on below
+    pub fn seeded(seed: u64) -> Self {
+        Self {
+            seed,
+            refuse_streams: 0.0,
+            drop_connections: 0.0,
+            delay_handshakes: 0.0,
+            handshake_delay: Duration::ZERO,
+            wrong_ports: 0.0,
+        }
+    }
+
+    /// Stop this share of streams unread (and drop this share of datagrams),
+    /// as [`Fault::RefuseStreams`] does
+    pub fn refuse_streams(mut self, probability: f64) -> Self {
+        self.refuse_streams = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Close the connection on this share of streams and datagrams, as
+    /// [`Fault::ResetConnection`] does
+    pub fn drop_connections(mut self, probability: f64) -> Self {
+        self.drop_connections = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Hold this share of handshakes back by `delay`
+    pub fn delay_handshakes(mut self, probability: f64, delay: Duration) -> Self {
+        self.delay_handshakes = probability.clamp(0.0, 1.0);
+        self.handshake_delay = delay;
+        self
+    }
+
+    /// Advertise a port nobody answers on for this share of a cluster's
+    /// validators (in [`MockCluster::topology`](crate::MockCluster::topology));
+    /// a lone validator has nothing to advertise and ignores it
+    pub fn wrong_ports(mut self, probability: f64) -> Self {
+        self.wrong_ports = probability.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The same faults under another seed (one per cluster validator)
+    pub(crate) fn reseeded(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+
+    pub(crate) fn wrong_port_probability(&self) -> f64 {
+        self.wrong_ports
+    }
+}
+
+/// A validator's chaos settings and the RNG its draws come from
+#[derive(Debug)]
+pub(crate) struct ChaosRng {
+    chaos: Chaos,
+    rng: Mutex<StdRng>,
+}
+
+impl ChaosRng {
+    pub(crate) fn new(chaos: Chaos) -> Self {
+        Self {
+            chaos,
+            rng: Mutex::new(StdRng::seed_from_u64(chaos.seed)),
+        }
+    }
+
+    /// Fault for the next stream or datagram, if any
+    pub(crate) fn draw_fault(&self) -> Option<Fault> {
+        let mut rng = self.rng.lock().unwrap();
+        if rng.gen_bool(self.chaos.drop_connections) {
+            Some(Fault::ResetConnection)
+        } else if rng.gen_bool(self.chaos.refuse_streams) {
+            Some(Fault::RefuseStreams)
+        } else {
+            None
+        }
+    }
+
+    /// Delay for the next handshake, if any
+    pub(crate) fn draw_handshake_delay(&self) -> Option<Duration> {
+        let mut rng = self.rng.lock().unwrap();
+        rng.gen_bool(self.chaos.delay_handshakes)
+            .then_some(self.chaos.handshake_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_draws_replay_from_seed() {
+        let chaos = Chaos::seeded(42)
+            .refuse_streams(0.3)
+            .drop_connections(0.1)
+            .delay_handshakes(0.5, Duration::from_millis(10));
+        let draws = |chaos| {
+            let rng = ChaosRng::new(chaos);
+            (0..64)
+                .map(|_| (rng.draw_fault(), rng.draw_handshake_delay()))
+                .collect::<Vec<_>>()
+        };
+        let first = draws(chaos);
+        assert_eq!(first, draws(chaos));
+        assert_ne!(first, draws(chaos.reseeded(43)));
+        for expected in [Fault::RefuseStreams, Fault::ResetConnection] {
+            assert!(first.iter().any(|(fault, _)| *fault == Some(expected)));
+        }
+        assert!(first.iter().any(|(_, delay)| delay.is_some()));
+
+        // Off by default
+        let calm = ChaosRng::new(Chaos::seeded(42));
+        assert!((0..64).all(|_| calm.draw_fault().is_none()));
+        assert_eq!(Chaos::seeded(1).refuse_streams(7.0).refuse_streams, 1.0);
+    }
+}
@@ -0,0 +1,233 @@
+//! Mock cluster: several mock validators under a round-robin leader
+//! schedule, and a slot clock advanced by the test instead of the network.
+
+use crate::chaos::Chaos;
+use crate::validator::MockValidator;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::net::{SocketAddr, UdpSocket};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+/// Consecutive slots each leader holds (as on mainnet)
+pub const SLOTS_PER_LEADER: u64 = 4;
+
+#[derive(Debug)]
+struct Leader {
+    identity: Pubkey,
+    validator: MockValidator,
+    /// Address in the topology (the validator's own unless chaos lies)
+    advertised: SocketAddr,
+}
+
+/// Mock validators taking turns as leader: validator `i` leads slots
+/// `4i..4i+3`, then the rotation starts over.
+/// Every validator stops accepting when dropped.
+#[derive(Debug)]
+pub struct MockCluster {
+    leaders: Vec<Leader>,
+    /// Held open so wrong advertised ports stay silent
+    _decoys: Vec<UdpSocket>,
+}
+
+impl MockCluster {
+    /// Start `leaders` validators, each with a fresh identity
+    pub fn start(leaders: usize) -> std::io::Result<Self> {
+        Self::start_with(leaders, None)
+    }
+
+    /// Start `leaders` validators misbehaving per `chaos` (each validator
+    /// draws from its own seed, derived from the cluster's)
+    pub fn with_chaos(leaders: usize, chaos: Chaos) -> std::io::Result<Self> {
+        Self::start_with(leaders, Some(chaos))
+    }
+
+    fn start_with(leaders: usize, chaos: Option<Chaos>) -> std::io::Result<Self> {
+        let mut ports = chaos.map(|chaos| StdRng::seed_from_u64(chaos.seed()));
+        let mut started = Vec::with_capacity(leaders);
+        let mut decoys = Vec::new();
+        for index in 0..leaders {
+            let mut builder = MockValidator::builder();
+            if let Some(chaos) = chaos {
+                builder =
+                    builder.chaos(chaos.reseeded(chaos.seed().wrapping_add(index as u64 + 1)));
+            }
+            let validator = builder.start()?;
+            let mut advertised = validator.addr();
+            if let (Some(rng), Some(chaos)) = (ports.as_mut(), chaos) {
+                if rng.gen_bool(chaos.wrong_port_probability()) {
+                    let decoy = UdpSocket::bind(SocketAddr::from(([127, 0, 0, 1], 0)))?;
+                    advertised = decoy.local_addr()?;
+                    decoys.push(decoy);
+                }
+            }
+            started.push(Leader {
+                identity: Pubkey::new_unique(),
+                validator,
+                advertised,
+            });
+        }
+        Ok(Self {
+            leaders: started,
+            _decoys: decoys,
+        })
+    }
+
+    /// Number of validators in the rotation
+    pub fn size(&self) -> usize {
+        self.leaders.len()
+    }
+
+    pub fn identity(&self, index: usize) -> Pubkey {
+        self.leaders[index].identity
+    }
+
+    pub fn validator(&self, index: usize) -> &MockValidator {
+        &self.leaders[index].validator
+    }
+
+    /// Address the topology reports for validator `index`: its own, or a
+    /// silent port under [`Chaos::wrong_ports`]
+    pub fn advertised(&self, index: usize) -> SocketAddr {
+        self.leaders[index].advertised
+    }
+
+    /// Index of the validator leading `slot`
+    pub fn leader_of(&self, slot: u64) -> usize {
+        ((slot / SLOTS_PER_LEADER) % self.leaders.len() as u64) as usize
+    }
+
+    /// Leader schedule for `slots` (slot -> identity), as an RPC would return it
+    pub fn leader_schedule(&self, slots: Range<u64>) -> HashMap<u64, Pubkey> {
+        slots
+            .map(|slot| (slot, self.identity(self.leader_of(slot))))
+            .collect()
+    }
+
+    /// Advertised TPU QUIC address of every validator (identity ->
+    /// address), as gossip would report it
+    pub fn topology(&self) -> HashMap<Pubkey, SocketAddr> {
+        self.leaders
+            .iter()
+            .map(|leader| (leader.identity, leader.advertised))
+            .collect()
+    }
+}
+
+type SlotListener = Box<dyn Fn(u64) + Send + Sync>;
+
+struct Clock {
+    slot: watch::Sender<u64>,
+    listeners: Mutex<Vec<SlotListener>>,
+}
+
+/// Slot clock driven by the test. Listeners run synchronously on every
+/// change, so the new slot is visible to them as soon as `set` returns.
+#[derive(Clone)]
+pub struct ScriptedClock {
+    inner: Arc<Clock>,
+}
+
+impl ScriptedClock {
+    pub fn new(slot: u64) -> Self {
+        Self {
+            inner: Arc::new(Clock {
+                slot: watch::Sender::new(slot),
+                listeners: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    pub fn slot(&self) -> u64 {
+        *self.inner.slot.borrow()
+    }
+
+    /// Call `listener` with the current slot now and with every later one
+    /// (e.g. to feed a sender's slot tracker)
+    pub fn on_slot(&self, listener: impl Fn(u64) + Send + Sync + 'static) {
+        listener(self.slot());
+        self.inner
+            .listeners
+            .lock()
+            .unwrap()
+            .push(Box::new(listener));
+    }
+
+    /// Slot changes, for tasks that wait on the clock
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.inner.slot.subscribe()
+    }
+
+    /// Jump to `slot` (backwards too, to script a regression)
+    pub fn set(&self, slot: u64) {
+        self.inner.slot.send_replace(slot);
+        for listener in self.inner.listeners.lock().unwrap().iter() {
+            listener(slot);
+        }
+    }
+
+    /// Move `slots` forward; returns the new slot
+    pub fn advance(&self, slots: u64) -> u64 {
+        let slot = self.slot() + slots;
+        self.set(slot);
+        slot
+    }
+
+    /// Advance one slot every `slot_time`, `slots` times, in the background
+    pub fn play(&self, slot_time: Duration, slots: u64) -> JoinHandle<()> {
+        let clock = self.clone();
+        tokio::spawn(async move {
+            for _ in 0..slots {
+                tokio::time::sleep(slot_time).await;
+                clock.advance(1);
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for ScriptedClock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScriptedClock")
+            .field("slot", &self.slot())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[tokio::test]
+    async fn test_schedule_and_clock() {
+        let cluster = MockCluster::start(3).unwrap();
+        let schedule = cluster.leader_schedule(0..16);
+        assert_eq!(schedule.len(), 16);
+        assert_eq!(schedule[&3], cluster.identity(0));
+        assert_eq!(schedule[&4], cluster.identity(1));
+        // Rotation wraps after the last validator
+        assert_eq!(schedule[&12], cluster.identity(0));
+        assert_eq!(
+            cluster.topology()[&cluster.identity(2)],
+            cluster.validator(2).addr()
+        );
+
+        let clock = ScriptedClock::new(10);
+        let seen = Arc::new(AtomicU64::new(0));
+        let sink = seen.clone();
+        clock.on_slot(move |slot| sink.store(slot, Ordering::Relaxed));
+        assert_eq!(seen.load(Ordering::Relaxed), 10);
+        assert_eq!(clock.advance(2), 12);
+        assert_eq!(seen.load(Ordering::Relaxed), 12);
+
+        let mut slots = clock.subscribe();
+        clock.play(Duration::from_millis(1), 3).await.unwrap();
+        assert_eq!(*slots.borrow_and_update(), 15);
+        assert_eq!(seen.load(Ordering::Relaxed), 15);
+    }
+}
@@ -0,0 +1,42 @@
+//! Test harness for Solana TPU senders.
+//!
+//! [`MockValidator`] is a local QUIC server speaking the `solana-tpu` ALPN,
+//! the same server the engine's own tests run against. It records every
+//! stream it receives and can be told to refuse streams or reset
+//! connections mid-run, so senders built on Scramjet (or anything else) can
+//! be integration-tested without a cluster.
+//!
+//! [`MockCluster`] runs several of them as a round-robin leader rotation
+//! (schedule and topology maps ready to install in a cartographer), and
+//! [`ScriptedClock`] stands in for the cluster's slot clock, so leader
+//! changes happen exactly when the test says.
+//!
+//! In chaos mode ([`Chaos`], on a validator builder or a whole cluster) the
+//! faults come at random instead: refused streams, dropped connections,
+//! delayed handshakes and wrongly advertised ports, drawn from a fixed seed
+//! so retry and failover paths can be exercised reproducibly in CI.
+//!
+//! ```no_run
+//! # async fn run() {
+//! use scramjet_testkit::{Fault, MockValidator};
+//! use std::time::Duration;
+//!
+//! let validator = MockValidator::start().unwrap();
+//! // ... point the sender at validator.addr() and send two transactions ...
+//! assert!(validator.wait_for_transactions(2, Duration::from_secs(5)).await);
+//!
+//! // From now on every stream is stopped unread
+//! validator.set_fault(Some(Fault::RefuseStreams));
+//! # }
+//! ```
+
+mod chaos;
+mod cluster;
+mod validator;
+
+pub use chaos::Chaos;
+pub use cluster::{MockCluster, ScriptedClock, SLOTS_PER_LEADER};
+pub use validator::{
+    server_config, Fault, MockValidator, MockValidatorBuilder, Received, CONNECTION_RESET,
+    MAX_STREAM_BYTES, STREAM_REFUSED,
+};
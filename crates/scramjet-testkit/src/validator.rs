@@ -0,0 +1,414 @@
+//! Mock TPU validator: accepts `solana-tpu` connections and records the
+//! payload of every uni stream, with optional fault injection.
+
+use crate::chaos::{Chaos, ChaosRng};
+use log::debug;
+use quinn::crypto::rustls::QuicServerConfig;
+use quinn::{Connection, Endpoint};
+use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Application code for streams stopped by [`Fault::RefuseStreams`] (the
+/// sender only sees it when the stream had not been fully acknowledged yet)
+pub const STREAM_REFUSED: u32 = 1;
+
+/// Application code for connections closed by [`Fault::ResetConnection`]
+pub const CONNECTION_RESET: u32 = 2;
+
+/// Largest stream the mock reads; longer ones are dropped (validators read
+/// one packet per stream, but packed batches span several)
+pub const MAX_STREAM_BYTES: usize = 64 * 1024;
+
+/// Misbehavior applied to incoming streams (and datagrams) while set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fault {
+    /// Stop every stream unread, like a validator over its stream quota
+    /// (datagrams are dropped)
+    RefuseStreams,
+    /// Close the connection when a stream or datagram arrives; the sender
+    /// has to re-handshake
+    ResetConnection,
+}
+
+/// One stream's payload (a transaction, or several when packed), or one
+/// datagram
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Received {
+    /// Sender's address
+    pub from: SocketAddr,
+    pub data: Vec<u8>,
+    /// Arrived as a QUIC DATAGRAM frame rather than a stream
+    pub datagram: bool,
+}
+
+/// Self-signed `solana-tpu` server config (the validator side of the
+/// handshake the engine performs)
+pub fn server_config() -> quinn::ServerConfig {
+    let certified_key =
+        rcgen::generate_simple_self_signed(vec!["solana".into()]).expect("self-signed certificate");
+    let key = PrivatePkcs8KeyDer::from(certified_key.key_pair.serialize_der()).into();
+    let cert_chain = vec![CertificateDer::from(certified_key.cert.der().to_vec())];
+
+    let mut server_crypto = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(cert_chain, key)
+        .expect("server certificate");
+    server_crypto.alpn_protocols = vec![b"solana-tpu".to_vec()];
+
+    // Wrap with QuicServerConfig for quinn 0.11
+    let quic_server_config =
+        QuicServerConfig::try_from(server_crypto).expect("QUIC server crypto config");
+    quinn::ServerConfig::with_crypto(Arc::new(quic_server_config))
+}
+
+#[derive(Debug)]
+struct State {
+    received: Mutex<Vec<Received>>,
+    count: watch::Sender<usize>,
+    handshakes: watch::Sender<usize>,
+    refused: AtomicUsize,
+    resets: AtomicUsize,
+    delayed: AtomicUsize,
+    fault: Mutex<Option<Fault>>,
+    chaos: Option<ChaosRng>,
+}
+
+impl State {
+    /// The set fault, else whatever chaos draws
+    fn fault(&self) -> Option<Fault> {
+        let fault = *self.fault.lock().unwrap();
+        fault.or_else(|| self.chaos.as_ref().and_then(ChaosRng::draw_fault))
+    }
+
+    fn record(&self, received: Received) {
+        let mut all = self.received.lock().unwrap();
+        all.push(received);
+        self.count.send_replace(all.len());
+    }
+}
+
+/// Options for [`MockValidator`]
+#[derive(Debug, Clone)]
+pub struct MockValidatorBuilder {
+    bind: SocketAddr,
+    max_concurrent_uni_streams: Option<u32>,
+    datagrams: bool,
+    fault: Option<Fault>,
+    chaos: Option<Chaos>,
+}
+
+impl Default for MockValidatorBuilder {
+    fn default() -> Self {
+        Self {
+            bind: SocketAddr::from(([127, 0, 0, 1], 0)),
+            max_concurrent_uni_streams: None,
+            datagrams: true,
+            fault: None,
+            chaos: None,
+        }
+    }
+}
+
+impl MockValidatorBuilder {
+    /// Listen address (default: an ephemeral port on 127.0.0.1)
+    pub fn bind(mut self, addr: SocketAddr) -> Self {
+        self.bind = addr;
+        self
+    }
+
+    /// Concurrent uni streams granted per connection (default: quinn's)
+    pub fn max_concurrent_uni_streams(mut self, streams: u32) -> Self {
+        self.max_concurrent_uni_streams = Some(streams);
+        self
+    }
+
+    /// Advertise QUIC datagram support (default: true, as quinn does)
+    pub fn datagrams(mut self, enabled: bool) -> Self {
+        self.datagrams = enabled;
+        self
+    }
+
+    /// Fault active from the first connection
+    pub fn fault(mut self, fault: Fault) -> Self {
+        self.fault = Some(fault);
+        self
+    }
+
+    /// Random faults from the first connection (a set fault takes precedence)
+    pub fn chaos(mut self, chaos: Chaos) -> Self {
+        self.chaos = Some(chaos);
+        self
+    }
+
+    /// Bind and start accepting connections (within a Tokio runtime)
+    pub fn start(self) -> std::io::Result<MockValidator> {
+        let mut config = server_config();
+        let mut transport = quinn::TransportConfig::default();
+        if let Some(streams) = self.max_concurrent_uni_streams {
+            transport.max_concurrent_uni_streams(streams.into());
+        }
+        if !self.datagrams {
+            transport.datagram_receive_buffer_size(None);
+        }
+        config.transport_config(Arc::new(transport));
+        let endpoint = Endpoint::server(config, self.bind)?;
+        let addr = endpoint.local_addr()?;
+        let state = Arc::new(State {
+            received: Mutex::new(Vec::new()),
+            count: watch::Sender::new(0),
+            handshakes: watch::Sender::new(0),
+            refused: AtomicUsize::new(0),
+            resets: AtomicUsize::new(0),
+            delayed: AtomicUsize::new(0),
+            fault: Mutex::new(self.fault),
+            chaos: self.chaos.map(ChaosRng::new),
+        });
+        let task = tokio::spawn(serve(endpoint.clone(), state.clone()));
+        Ok(MockValidator {
+            addr,
+            endpoint,
+            state,
+            task,
+        })
+    }
+}
+
+/// Local `solana-tpu` QUIC server recording what senders deliver.
+/// Stops accepting when dropped.
+#[derive(Debug)]
+pub struct MockValidator {
+    addr: SocketAddr,
+    endpoint: Endpoint,
+    state: Arc<State>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MockValidator {
+    pub fn builder() -> MockValidatorBuilder {
+        MockValidatorBuilder::default()
+    }
+
+    /// Start on an ephemeral 127.0.0.1 port with no faults
+    pub fn start() -> std::io::Result<Self> {
+        Self::builder().start()
+    }
+
+    /// TPU QUIC address to send to
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Streams received in full (and datagrams) so far
+    pub fn transaction_count(&self) -> usize {
+        *self.state.count.borrow()
+    }
+
+    /// Every stream received in full and every datagram, in arrival order
+    pub fn transactions(&self) -> Vec<Received> {
+        self.state.received.lock().unwrap().clone()
+    }
+
+    /// Wait until at least `count` transactions have arrived; false on timeout
+    pub async fn wait_for_transactions(&self, count: usize, timeout: Duration) -> bool {
+        let mut received = self.state.count.subscribe();
+        let reached = tokio::time::timeout(timeout, received.wait_for(|n| *n >= count)).await;
+        reached.is_ok()
+    }
+
+    /// Completed handshakes
+    pub fn handshakes(&self) -> usize {
+        *self.state.handshakes.borrow()
+    }
+
+    /// Wait until at least `count` handshakes have completed on this side
+    /// (the sender sees its handshake finish first); false on timeout
+    pub async fn wait_for_handshakes(&self, count: usize, timeout: Duration) -> bool {
+        let mut handshakes = self.state.handshakes.subscribe();
+        let reached = tokio::time::timeout(timeout, handshakes.wait_for(|n| *n >= count)).await;
+        reached.is_ok()
+    }
+
+    /// Streams stopped by [`Fault::RefuseStreams`] (set, or drawn by chaos)
+    pub fn refused_streams(&self) -> usize {
+        self.state.refused.load(Ordering::Relaxed)
+    }
+
+    /// Connections closed by [`Fault::ResetConnection`] (set, or drawn by chaos)
+    pub fn resets(&self) -> usize {
+        self.state.resets.load(Ordering::Relaxed)
+    }
+
+    /// Handshakes held back by [`Chaos::delay_handshakes`]
+    pub fn delayed_handshakes(&self) -> usize {
+        self.state.delayed.load(Ordering::Relaxed)
+    }
+
+    /// Apply `fault` to streams arriving from now on (None = behave)
+    pub fn set_fault(&self, fault: Option<Fault>) {
+        *self.state.fault.lock().unwrap() = fault;
+    }
+}
+
+impl Drop for MockValidator {
+    fn drop(&mut self) {
+        self.endpoint.close(0u32.into(), b"mock validator shutdown");
+        self.task.abort();
+    }
+}
+
+async fn serve(endpoint: Endpoint, state: Arc<State>) {
+    while let Some(incoming) = endpoint.accept().await {
+        // Drawn in arrival order, so the same seed delays the same handshakes
+        let delay = state
+            .chaos
+            .as_ref()
+            .and_then(ChaosRng::draw_handshake_delay);
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Some(delay) = delay {
+                state.delayed.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(delay).await;
+            }
+            match incoming.await {
+                Ok(connection) => {
+                    state.handshakes.send_modify(|n| *n += 1);
+                    accept_streams(connection, state).await;
+                }
+                Err(e) => debug!("Mock validator: Handshake failed: {}", e),
+            }
+        });
+    }
+}
+
+async fn accept_streams(connection: Connection, state: Arc<State>) {
+    let from = connection.remote_address();
+    let datagrams = tokio::spawn(accept_datagrams(connection.clone(), state.clone()));
+    while let Ok(mut stream) = connection.accept_uni().await {
+        match state.fault() {
+            Some(Fault::RefuseStreams) => {
+                state.refused.fetch_add(1, Ordering::Relaxed);
+                let _ = stream.stop(STREAM_REFUSED.into());
+                continue;
+            }
+            Some(Fault::ResetConnection) => {
+                state.resets.fetch_add(1, Ordering::Relaxed);
+                connection.close(CONNECTION_RESET.into(), b"reset");
+                break;
+            }
+            None => {}
+        }
+        let state = state.clone();
+        tokio::spawn(async move {
+            match stream.read_to_end(MAX_STREAM_BYTES).await {
+                Ok(data) => state.record(Received {
+                    from,
+                    data,
+                    datagram: false,
+                }),
+                Err(e) => debug!("Mock validator: Stream from {} dropped: {}", from, e),
+            }
+        });
+    }
+    datagrams.abort();
+}
+
+async fn accept_datagrams(connection: Connection, state: Arc<State>) {
+    let from = connection.remote_address();
+    while let Ok(data) = connection.read_datagram().await {
+        match state.fault() {
+            Some(Fault::RefuseStreams) => {
+                state.refused.fetch_add(1, Ordering::Relaxed);
+            }
+            Some(Fault::ResetConnection) => {
+                state.resets.fetch_add(1, Ordering::Relaxed);
+                connection.close(CONNECTION_RESET.into(), b"reset");
+                break;
+            }
+            None => state.record(Received {
+                from,
+                data: data.to_vec(),
+                datagram: true,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_common::{create_quic_config, Config};
+    use solana_sdk::signature::Keypair;
+
+    async fn connect(addr: SocketAddr) -> Connection {
+        let config = Config::from_env().expect("Failed to load config");
+        let client_config = create_quic_config(&Keypair::new(), &config).unwrap();
+        let mut endpoint = Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        endpoint.set_default_client_config(client_config);
+        endpoint.connect(addr, "solana").unwrap().await.unwrap()
+    }
+
+    async fn send(connection: &Connection, payload: &[u8]) {
+        let mut stream = connection.open_uni().await.unwrap();
+        stream.write_all(payload).await.unwrap();
+        stream.finish().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_records_and_injects_faults() {
+        let validator = MockValidator::start().unwrap();
+        let connection = connect(validator.addr()).await;
+        for i in 1..=3u8 {
+            send(&connection, &[i; 100]).await;
+        }
+        assert!(
+            validator
+                .wait_for_transactions(3, Duration::from_secs(5))
+                .await
+        );
+        let mut payloads: Vec<Vec<u8>> = validator
+            .transactions()
+            .into_iter()
+            .map(|received| received.data)
+            .collect();
+        payloads.sort();
+        assert_eq!(payloads, vec![vec![1; 100], vec![2; 100], vec![3; 100]]);
+        assert_eq!(validator.handshakes(), 1);
+
+        // Refused streams are stopped unread and not recorded
+        validator.set_fault(Some(Fault::RefuseStreams));
+        send(&connection, b"refused").await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while validator.refused_streams() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("stream was not refused");
+        assert_eq!(validator.transaction_count(), 3);
+
+        // A reset closes the connection; a new handshake works once healed
+        validator.set_fault(Some(Fault::ResetConnection));
+        send(&connection, b"reset").await;
+        match connection.closed().await {
+            quinn::ConnectionError::ApplicationClosed(close) => {
+                assert_eq!(close.error_code, CONNECTION_RESET.into())
+            }
+            other => panic!("unexpected close: {}", other),
+        }
+        assert_eq!(validator.resets(), 1);
+
+        validator.set_fault(None);
+        let connection = connect(validator.addr()).await;
+        send(&connection, b"healed").await;
+        assert!(
+            validator
+                .wait_for_transactions(4, Duration::from_secs(5))
+                .await
+        );
+        assert_eq!(validator.handshakes(), 2);
+    }
+}
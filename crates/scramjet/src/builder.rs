@@ -0,0 +1,720 @@
+use arc_swap::ArcSwap;
+use log::{debug, error, info, warn};
+use scramjet_common::{Config, LocalSigner, RemoteSigner, ScramjetError, TxSigner};
+use scramjet_net::{
+    blockhash::BlockhashCache,
+    blocklist::BlocklistManager,
+    cartographer::Cartographer,
+    control::{self, ControlContext},
+    dedupe::Dedupe,
+    engine::QuicEngine,
+    geyser::{spawn_geyser_monitor, GeyserHealth, Keepalive, SlotFilter},
+    journal::Journal,
+    leader_tracker::LeaderTracker,
+    policy::PolicyClient,
+    proximity,
+    queue::SendQueue,
+    rate_limit::{self, RateLimiter},
+    rpc_pool::RpcPool,
+    score::{ScoreConfig, ValidatorScore},
+    scout,
+    slo::{self, AlertNotifier, LandingFeed, LogNotifier, SloMonitor, WebhookNotifier},
+    topology_cache::TopologyCache,
+    validator_info::GeoIp,
+    websocket::spawn_slot_subscriber,
+};
+use solana_sdk::signature::{Keypair, Signer};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+use crate::reload::{self, ConfigLoader, SharedConfig};
+use crate::sender::Scramjet;
+
+/// How long `build` waits for the first Geyser connection before continuing
+/// with background retries
+const GEYSER_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Geyser landed-signature backlog before updates are dropped
+const LANDING_CHANNEL_CAPACITY: usize = 4_096;
+
+/// How often configured landing SLOs are re-evaluated
+const SLO_EVAL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Builder for [`Scramjet`]. Only the identity is required; everything else
+/// defaults to what the CLI does (config from env, blocklist from env,
+/// SLO alerts to the webhook or the log).
+#[derive(Default)]
+pub struct ScramjetBuilder {
+    config: Option<Config>,
+    config_loader: Option<ConfigLoader>,
+    config_file: Option<PathBuf>,
+    identity: Option<Keypair>,
+    fallback_identity: Option<Keypair>,
+    signer: Option<Arc<dyn TxSigner>>,
+    blocklist: Option<Arc<BlocklistManager>>,
+    notifier: Option<Arc<dyn AlertNotifier>>,
+}
+
+impl ScramjetBuilder {
+    /// Runtime configuration (default: `Config::from_env()`)
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Where `reload-config` re-reads the configuration from (default:
+    /// `Config::from_env()` when no `config` was given, else not reloadable)
+    pub fn config_loader(
+        mut self,
+        loader: impl Fn() -> Result<Config, ScramjetError> + Send + Sync + 'static,
+    ) -> Self {
+        self.config_loader = Some(Arc::new(loader));
+        self
+    }
+
+    /// Reload the tunable settings whenever this file changes (through the
+    /// `config_loader`, else `Config::from_file`)
+    pub fn watch_config_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_file = Some(path.into());
+        self
+    }
+
+    /// Identity for QUIC client certificates (staked identities get more streams)
+    pub fn identity(mut self, identity: Keypair) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Secondary identity for validators that reject the primary (default: ephemeral)
+    pub fn fallback_identity(mut self, identity: Keypair) -> Self {
+        self.fallback_identity = Some(identity);
+        self
+    }
+
+    /// Transaction signer (default: `REMOTE_SIGNER_URL` when set, else the identity)
+    pub fn signer(mut self, signer: Arc<dyn TxSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Pre-configured Shield (default: `BlocklistManager::from_env()`)
+    pub fn blocklist(mut self, blocklist: Arc<BlocklistManager>) -> Self {
+        self.blocklist = Some(blocklist);
+        self
+    }
+
+    /// Where SLO violations go (default: `ALERT_WEBHOOK_URL`, else the log)
+    pub fn alert_notifier(mut self, notifier: Arc<dyn AlertNotifier>) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// Bootstrap the cluster map, start the slot clock and background tasks,
+    /// and return a ready sender
+    pub async fn build(self) -> Result<Scramjet, ScramjetError> {
+        let identity = self
+            .identity
+            .ok_or_else(|| ScramjetError::ConfigError("Identity keypair is required".into()))?;
+        let (config, config_loader) = match (self.config, self.config_loader) {
+            (Some(config), loader) => (config, loader),
+            (None, Some(loader)) => (loader()?, Some(loader)),
+            (None, None) => (
+                Config::from_env()?,
+                Some(Arc::new(Config::from_env) as ConfigLoader),
+            ),
+        };
+        let config_loader = match (config_loader, &self.config_file) {
+            (None, Some(path)) => {
+                let path = path.clone();
+                Some(Arc::new(move || Config::from_file(&path)) as ConfigLoader)
+            }
+            (loader, _) => loader,
+        };
+        // Tunables are read from here on every iteration (see `reload`)
+        let live_config: SharedConfig = Arc::new(ArcSwap::from_pointee(config.clone()));
+        // Loops that stop on `shutdown` (drained by `Scramjet::shutdown`);
+        // the rest are aborted
+        let shutdown = CancellationToken::new();
+        let mut cooperative_tasks = Vec::new();
+        let mut tasks = Vec::new();
+        info!("Identity: {}", identity.pubkey());
+        let signer: Arc<dyn TxSigner> = match (self.signer, config.remote_signer_url.as_deref()) {
+            (Some(signer), _) => signer,
+            (None, Some(url)) => {
+                info!("Remote signer: {}", url);
+                Arc::new(
+                    RemoteSigner::connect(
+                        url,
+                        config.remote_signer_token.clone(),
+                        config.remote_signer_timeout(),
+                    )
+                    .await?,
+                )
+            }
+            (None, None) => Arc::new(LocalSigner::new(identity.insecure_clone())),
+        };
+        if signer.pubkey() != identity.pubkey() {
+            info!("Signer: {}", signer.pubkey());
+        }
+
+        // Shield (blocklist protection)
+        info!("Initializing Shield (blocklist protection)...");
+        let shield = self
+            .blocklist
+            .unwrap_or_else(|| Arc::new(BlocklistManager::from_env()));
+
+        // Load local blocklist synchronously (fast boot with protection)
+        let loaded_count = shield.load_local().await;
+        if loaded_count > 0 {
+            info!("Shield: Active with {} blocked validators", loaded_count);
+        } else {
+            warn!("Shield: No local blocklist found. Will fetch from remote.");
+        }
+
+        if let Some(allowed) = shield.load_allowlist().await {
+            info!("Shield: Allowlist mode with {} allowed validators", allowed);
+        }
+
+        // Spawn background updater (hourly refresh from remote)
+        cooperative_tasks.push(shield.clone().spawn_updater(shutdown.clone()));
+
+        // Cartographer (cluster map + leader schedule)
+        info!("Initializing Cartographer with RPC: {}", config.rpc_url);
+        if !config.rpc_fallback_urls.is_empty() {
+            info!("   RPC fallbacks: {}", config.rpc_fallback_urls.join(", "));
+        }
+        let rpc = Arc::new(RpcPool::from_config(&config)?);
+        if let Some(cluster) = config.cluster {
+            // Before anything is sent: a devnet config pointed at mainnet
+            // (or the reverse) stops here
+            let genesis = rpc
+                .call(|rpc| async move { rpc.get_genesis_hash().await })
+                .await
+                .map_err(|e| {
+                    ScramjetError::RpcError(format!("Failed to get genesis hash: {}", e))
+                })?;
+            cluster.verify_genesis(&config.rpc_url, &genesis.to_string())?;
+            info!("Cluster: {} (genesis hash verified)", cluster);
+        }
+        let mut cartographer = Cartographer::with_rpc_pool(rpc.clone(), shield.get_handle())
+            .with_block_reasons(shield.get_reasons_handle());
+        if let Some(ref path) = config.policy_socket {
+            let mode = if config.policy_fail_open {
+                "open"
+            } else {
+                "closed"
+            };
+            info!(
+                "Policy: Consulting external engine at {} (fail {})",
+                path, mode
+            );
+            cartographer = cartographer.with_policy(Arc::new(PolicyClient::new(
+                PathBuf::from(path),
+                config.policy_timeout(),
+                config.policy_cache_ttl(),
+                config.policy_fail_open,
+            )));
+        }
+        if let Some(path) = config.topology_cache_path() {
+            cartographer = cartographer.with_topology_cache(TopologyCache::new(path));
+        }
+        if !config.geoip_db_files.is_empty() {
+            match GeoIp::open(&config.geoip_db_files) {
+                Ok(geoip) => cartographer = cartographer.with_geoip(geoip),
+                Err(e) => warn!("{}. Validators will not be located.", e),
+            }
+        }
+        let cartographer = Arc::new(cartographer);
+        if cartographer.warm_start().await {
+            // Serve from the cached snapshot; RPC replaces it in the background
+            let cart_clone = cartographer.clone();
+            let retry_interval = config.cluster_check_interval();
+            let stop = shutdown.clone();
+            cooperative_tasks.push(tokio::spawn(async move {
+                loop {
+                    match cart_clone.revalidate().await {
+                        Ok(()) => {
+                            info!("Topology refreshed over RPC after warm start.");
+                            break;
+                        }
+                        Err(e) => warn!(
+                            "RPC bootstrap after warm start failed: {}. Retrying in {:?}.",
+                            e, retry_interval
+                        ),
+                    }
+                    tokio::select! {
+                        _ = stop.cancelled() => break,
+                        _ = tokio::time::sleep(retry_interval) => {}
+                    }
+                }
+            }));
+        } else {
+            cartographer.bootstrap().await?; // Topology + leader schedule + genesis hash snapshot
+        }
+
+        // Clock (Geyser hybrid, websocket slotSubscribe, or RPC polling mode)
+        let mut landing_feed = None;
+        let mut geyser_health = None;
+        let geyser_urls = config.geyser_urls();
+        let clock = if let Some((url, redundant)) = geyser_urls.split_first() {
+            info!("MODE: HYBRID (RPC Map + Geyser Clock)");
+            info!("   Geyser Endpoint: {}", url);
+            for url in redundant {
+                info!("   Redundant Geyser Endpoint (slot clock): {}", url);
+            }
+            if config.geyser_blocks_meta {
+                info!("   Leader validation: Geyser block metadata");
+            }
+            let tx_status = if config.geyser_tx_status {
+                info!("   Landing confirmation: Geyser transaction status");
+                let (landed_tx, landed_rx) = tokio::sync::mpsc::channel(LANDING_CHANNEL_CAPACITY);
+                let feed = Arc::new(LandingFeed::new());
+                tasks.push(feed.clone().spawn_consumer(landed_rx));
+                landing_feed = Some(feed);
+                Some((identity.pubkey(), landed_tx))
+            } else {
+                None
+            };
+            let health = Arc::new(GeyserHealth::default());
+            let keepalive = Keepalive::from_config(&config, health.clone());
+            let slot_filter = SlotFilter::from_config(&config)?;
+            geyser_health = Some(health);
+
+            // Use Yellowstone Geyser for real-time slot updates (lowest latency)
+            let (task, startup_rx) = spawn_geyser_monitor(
+                url.clone(),
+                cartographer.clone(),
+                config.geyser_blocks_meta,
+                tx_status,
+                slot_filter,
+                keepalive.clone(),
+                config.geyser_reconnect_delay(),
+                config.geyser_max_reconnect_delay(),
+                shutdown.clone(),
+            );
+            cooperative_tasks.push(task);
+            let mut startups = vec![startup_rx];
+
+            // Redundant endpoints only drive the slot clock: whichever stream
+            // reports a slot first advances it (`update_slot` never goes back)
+            for url in redundant {
+                let (task, startup_rx) = spawn_geyser_monitor(
+                    url.clone(),
+                    cartographer.clone(),
+                    false,
+                    None,
+                    slot_filter,
+                    keepalive.clone(),
+                    config.geyser_reconnect_delay(),
+                    config.geyser_max_reconnect_delay(),
+                    shutdown.clone(),
+                );
+                cooperative_tasks.push(task);
+                startups.push(startup_rx);
+            }
+
+            // Wait for the first endpoint to connect, then continue regardless
+            let first_connected = futures::future::select_ok(startups.into_iter().map(|rx| {
+                Box::pin(async move {
+                    match rx.await {
+                        Ok(result) => result.map_err(|e| e.to_string()),
+                        Err(_) => Err("startup signal lost".to_string()),
+                    }
+                })
+            }));
+            match tokio::time::timeout(GEYSER_STARTUP_TIMEOUT, first_connected).await {
+                Ok(Ok(_)) => {
+                    info!("Geyser: Initial connection established.");
+                }
+                Ok(Err(e)) => {
+                    warn!(
+                        "Geyser: Initial connection failed: {}. Continuing with background retries.",
+                        e
+                    );
+                }
+                Err(_) => {
+                    warn!(
+                        "Geyser: Connection timed out after {:?}. Continuing with background retries.",
+                        GEYSER_STARTUP_TIMEOUT
+                    );
+                }
+            }
+            "geyser"
+        } else if let Some(ref url) = config.ws_url {
+            info!("MODE: WEBSOCKET (RPC Map + slotSubscribe Clock)");
+            info!("   Websocket Endpoint: {}", url);
+            let (task, startup_rx) = spawn_slot_subscriber(
+                url.clone(),
+                cartographer.clone(),
+                config.geyser_reconnect_delay(),
+                config.geyser_max_reconnect_delay(),
+                shutdown.clone(),
+            );
+            cooperative_tasks.push(task);
+            match tokio::time::timeout(GEYSER_STARTUP_TIMEOUT, startup_rx).await {
+                Ok(Ok(Ok(()))) => info!("Websocket: Subscribed to slot updates."),
+                Ok(Ok(Err(e))) => warn!(
+                    "Websocket: Subscription failed: {}. Continuing with background retries.",
+                    e
+                ),
+                Ok(Err(_)) => {
+                    warn!("Websocket: Startup signal lost. Continuing with background retries.")
+                }
+                Err(_) => warn!(
+                    "Websocket: Subscription timed out after {:?}. Continuing with background retries.",
+                    GEYSER_STARTUP_TIMEOUT
+                ),
+            }
+            "websocket"
+        } else {
+            info!("MODE: LEGACY (RPC Polling)");
+            info!("   (Geyser URL not configured. Using fallback.)");
+            // Fall back to RPC polling for slot updates
+            let cart_clone = cartographer.clone();
+            let live = live_config.clone();
+            let stop = shutdown.clone();
+            cooperative_tasks.push(tokio::spawn(async move {
+                loop {
+                    if let Err(e) = cart_clone.fetch_rpc_slot().await {
+                        debug!("RPC slot fetch failed: {}", e);
+                    }
+                    tokio::select! {
+                        _ = stop.cancelled() => break,
+                        _ = tokio::time::sleep(live.load().rpc_poll_interval()) => {}
+                    }
+                }
+            }));
+            "rpc-poll"
+        };
+
+        // Blockhash refresher (signing reads the cache, not RPC)
+        let blockhashes = Arc::new(BlockhashCache::new(rpc));
+        if let Err(e) = blockhashes.refresh().await {
+            warn!(
+                "Blockhash: Initial fetch failed: {}. Retrying in background.",
+                e
+            );
+        }
+        tasks.push(
+            blockhashes
+                .clone()
+                .spawn_refresher(config.blockhash_refresh_interval()),
+        );
+
+        // QUIC Engine with client certificate
+        info!("Initializing Engine...");
+        let mut engine = QuicEngine::with_fallback_identity(
+            &identity,
+            self.fallback_identity.as_ref(),
+            &config,
+        )?;
+        if engine.send_runtime().is_some() {
+            info!(
+                "Engine: Sending on a dedicated runtime ({} threads, cores {:?})",
+                config.send_runtime_threads,
+                config.send_runtime_core_ids()
+            );
+        }
+
+        // Auto-blocklist (block validators that keep failing sends, with a TTL)
+        if let Some(score_config) = ScoreConfig::from_config(&config) {
+            info!(
+                "Shield: Auto-blocking validators failing >= {:.0}% of sends for {:?}",
+                score_config.failure_rate * 100.0,
+                score_config.block_ttl
+            );
+            let score = Arc::new(ValidatorScore::new(score_config));
+            tasks.push(
+                score
+                    .clone()
+                    .spawn_enforcer(cartographer.clone(), shield.clone()),
+            );
+            engine = engine.with_validator_score(score);
+        }
+
+        // Send journal (`scramjet history`); a journal that cannot be opened
+        // does not stop sending
+        if let Some(path) = config.journal_path() {
+            match Journal::open(&path, config.journal_max_bytes()) {
+                Ok(journal) => {
+                    debug!("Journal: Recording sends to {:?}", path);
+                    engine = engine.with_journal(Arc::new(journal));
+                }
+                Err(e) => warn!(
+                    "Journal: Cannot open {:?}: {}. Sends are not journaled.",
+                    path, e
+                ),
+            }
+        }
+        let engine = Arc::new(engine);
+
+        // Send rate limit (opt-in via SEND_RATE_LIMIT)
+        let rate_limiter = match config.send_rate_limit {
+            Some(ref spec) => {
+                let limiter = RateLimiter::from_spec(spec, config.send_rate_burst)?;
+                info!(
+                    "Rate limit: {:.0} sends/s (burst {})",
+                    limiter.rate(),
+                    limiter.burst()
+                );
+                Some(Arc::new(limiter))
+            }
+            None => None,
+        };
+        let rate_limiter = rate_limit::rate_limit_handle(rate_limiter);
+
+        // Landing SLO monitor (opt-in via LANDING_SLOS)
+        let slo_monitor = match config.landing_slos {
+            Some(ref spec) => {
+                let slos = slo::parse_slos(spec)?;
+                let notifier: Arc<dyn AlertNotifier> =
+                    match (self.notifier, &config.alert_webhook_url) {
+                        (Some(notifier), _) => notifier,
+                        (None, Some(url)) => Arc::new(WebhookNotifier::new(url.clone())),
+                        (None, None) => Arc::new(LogNotifier),
+                    };
+                info!(
+                    "SLO: Tracking {} landing SLO(s) over {}s",
+                    slos.len(),
+                    config.slo_window_secs
+                );
+                let monitor = Arc::new(
+                    SloMonitor::new(slos, config.slo_window(), config.slo_min_samples, notifier)
+                        .with_stats(engine.stats().clone()),
+                );
+                tasks.push(monitor.clone().spawn_evaluator(SLO_EVAL_INTERVAL));
+                Some(monitor)
+            }
+            None => None,
+        };
+
+        // Scout (pre-warm connections to upcoming leaders, sample QUIC paths)
+        let cart_clone = cartographer.clone();
+        let engine_clone = engine.clone();
+        let live = live_config.clone();
+        // Room for the current leader's pool beside the pre-warmed ones
+        let warm_capacity = engine
+            .leader_capacity()
+            .map(|leaders| leaders.saturating_sub(1).max(1));
+        let slo_clone = slo_monitor.clone();
+        let stop = shutdown.clone();
+        cooperative_tasks.push(tokio::spawn(async move {
+            loop {
+                let config = live.load_full();
+                let current_slot = cart_clone.get_known_slot();
+                if current_slot > 0 {
+                    let budget = scout::WarmBudget {
+                        lookahead: config.scout_lookahead_slots,
+                        max_warm: config.scout_max_warm_connections,
+                        capacity: warm_capacity,
+                    };
+                    let history = slo_clone.as_deref().map(|m| m.history());
+                    scout::warm_pass(&cart_clone, &engine_clone, current_slot, budget, history)
+                        .await;
+                }
+                engine_clone.sample_paths();
+                let evicted = engine_clone.evict_idle();
+                if evicted > 0 {
+                    debug!("Scout: Evicted idle connections to {} leader(s)", evicted);
+                }
+                tokio::select! {
+                    _ = stop.cancelled() => break,
+                    _ = tokio::time::sleep(config.scout_interval()) => {}
+                }
+            }
+        }));
+
+        // Leader tracker (hand the pre-warmed pool over as each window starts)
+        let tracker = Arc::new(LeaderTracker::new(cartographer.clone(), engine.clone()));
+        cooperative_tasks.push(tracker.spawn(shutdown.clone()));
+
+        // Health checker (re-handshake stale connections before their leader's
+        // slot). Runs while HEALTH_CHECK_INTERVAL_MS is non-zero; a reload can
+        // switch it on or off.
+        let cart_clone = cartographer.clone();
+        let engine_clone = engine.clone();
+        let live = live_config.clone();
+        let stop = shutdown.clone();
+        cooperative_tasks.push(tokio::spawn(async move {
+            loop {
+                let config = live.load_full();
+                // Off: look again on the Scout's cadence
+                let Some(health_interval) = config.health_check_interval() else {
+                    tokio::select! {
+                        _ = stop.cancelled() => break,
+                        _ = tokio::time::sleep(config.scout_interval()) => {}
+                    }
+                    continue;
+                };
+                let report = engine_clone.probe_connections();
+                if report.dead > 0 || !report.stale.is_empty() {
+                    debug!(
+                        "Health: {} healthy, {} stale leader(s), {} closed connection(s) dropped",
+                        report.healthy,
+                        report.stale.len(),
+                        report.dead
+                    );
+                }
+                let current_slot = cart_clone.get_known_slot();
+                if current_slot > 0 {
+                    let upcoming = cart_clone
+                        .get_upcoming_leaders(current_slot, config.health_check_lookahead_slots)
+                        .await;
+                    for target in upcoming {
+                        match engine_clone.refresh_pool(target).await {
+                            Ok(0) => {}
+                            Ok(connected) => debug!(
+                                "Health: {} new connection(s) to {} ahead of its slot",
+                                connected, target
+                            ),
+                            Err(e) => debug!("Health: Failed to refresh {}: {}", target, e),
+                        }
+                    }
+                }
+                tokio::select! {
+                    _ = stop.cancelled() => break,
+                    _ = tokio::time::sleep(health_interval) => {}
+                }
+            }
+        }));
+
+        // RTT prober (leader proximity map for the Scout, monitor and stats).
+        // Runs while RTT_PROBE_INTERVAL_MS is non-zero; a reload can switch
+        // it on or off.
+        let cart_clone = cartographer.clone();
+        let engine_clone = engine.clone();
+        let live = live_config.clone();
+        let stop = shutdown.clone();
+        cooperative_tasks.push(tokio::spawn(async move {
+            loop {
+                let config = live.load_full();
+                let interval = match config.rtt_probe_interval() {
+                    Some(interval) => {
+                        let measured = proximity::probe_pass(
+                            &cart_clone,
+                            &engine_clone,
+                            config.rtt_probe_batch,
+                        )
+                        .await;
+                        if measured > 0 {
+                            debug!("RTT: Measured {} validator(s)", measured);
+                        }
+                        interval
+                    }
+                    // Off: look again on the Scout's cadence
+                    None => config.scout_interval(),
+                };
+                tokio::select! {
+                    _ = stop.cancelled() => break,
+                    _ = tokio::time::sleep(interval) => {}
+                }
+            }
+        }));
+
+        // Epoch rollover (prefetch next schedule, refresh at the boundary)
+        tasks.push(
+            cartographer
+                .clone()
+                .spawn_schedule_refresher(config.schedule_prefetch_slots),
+        );
+
+        // Cluster restart watchdog (re-bootstrap on hard fork / restart)
+        let cart_clone = cartographer.clone();
+        let engine_clone = engine.clone();
+        let live = live_config.clone();
+        tasks.push(tokio::spawn(async move {
+            loop {
+                let check_interval = live.load().cluster_check_interval();
+                tokio::time::sleep(check_interval).await;
+                match cart_clone.detect_cluster_restart().await {
+                    Ok(true) => {
+                        warn!("Cluster restart detected. Quarantining warm connections.");
+                        engine_clone.quarantine_connections();
+                        if let Err(e) = cart_clone.rebootstrap().await {
+                            error!("Re-bootstrap failed: {}. Retrying next check.", e);
+                        }
+                    }
+                    Ok(false) => {}
+                    Err(e) => debug!("Cluster check failed: {}", e),
+                }
+            }
+        }));
+
+        // Per-leader statistics file (opt-in, for `scramjet stats --file`)
+        if let Some(ref path) = config.stats_file {
+            cooperative_tasks.push(engine.stats().clone().spawn_persister(
+                PathBuf::from(path),
+                cartographer.clone(),
+                shutdown.clone(),
+            ));
+        }
+
+        // Send queue (fed by `serve` ingestion; depth shown by `scramjet status`)
+        let send_queue = Arc::new(SendQueue::from_config(&config)?);
+        let dedupe = Dedupe::from_config(&config).map(Arc::new);
+
+        // Live reload of the tunables (`reload-config`, and the watched file)
+        let reload_config = config_loader.map(|loader| {
+            reload::config_reloader(loader, live_config.clone(), rate_limiter.clone())
+        });
+        if let (Some(path), Some(reload)) = (self.config_file, &reload_config) {
+            info!("Config: Watching {:?} for tunable changes", path);
+            cooperative_tasks.push(reload::spawn_config_watcher(
+                path,
+                reload.clone(),
+                shutdown.clone(),
+            ));
+        }
+
+        // Control socket (opt-in, for `scramjet status` and operators)
+        if let Some(ref path) = config.control_socket {
+            let context = Arc::new(ControlContext {
+                cartographer: cartographer.clone(),
+                engine: engine.clone(),
+                clock: clock.into(),
+                started: Instant::now(),
+                landing: slo_monitor.clone(),
+                shield: Some(shield.clone()),
+                rate_limit: Some(rate_limiter.clone()),
+                queue: Some(send_queue.clone()),
+                dedupe: dedupe.clone(),
+                reload_config,
+                geyser: geyser_health,
+            });
+            tasks.push(control::spawn_control_server(PathBuf::from(path), context)?);
+        }
+
+        Ok(Scramjet {
+            config: live_config,
+            identity,
+            signer,
+            cartographer,
+            engine,
+            shield,
+            blockhashes,
+            slo_monitor,
+            rate_limiter,
+            send_queue,
+            dedupe,
+            landing_feed,
+            clock,
+            shutdown,
+            cooperative_tasks,
+            tasks,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_build_requires_identity() {
+        let err = Scramjet::builder().build().await.err().unwrap();
+        assert!(err.to_string().contains("Identity keypair is required"));
+    }
+}
+
@@ -0,0 +1,39 @@
+//! Embeddable Scramjet sender.
+//!
+//! [`Scramjet`] runs the same pipeline as the CLI (Shield blocklist,
+//! Cartographer, Geyser or RPC-polling slot clock, Scout pre-warming, cluster
+//! restart watchdog and the QUIC engine) behind a builder, so bots can send
+//! straight to the current leader without re-implementing the orchestration.
+//!
+//! ```no_run
+//! # async fn run(
+//! #     identity: solana_sdk::signature::Keypair,
+//! #     tx: solana_sdk::transaction::Transaction,
+//! # ) -> Result<(), scramjet::ScramjetError> {
+//! let scramjet = scramjet::Scramjet::builder()
+//!     .config(scramjet::Config::from_env()?)
+//!     .identity(identity)
+//!     .build()
+//!     .await?;
+//!
+//! let receipt = scramjet.send(&tx).await?;
+//! println!("{} -> {} (slot {})", receipt.signature, receipt.leader, receipt.slot);
+//!
+//! let mut slots = scramjet.subscribe_slots();
+//! while slots.changed().await.is_ok() {
+//!     println!("slot {}", *slots.borrow_and_update());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod builder;
+mod reload;
+mod sender;
+
+pub use builder::ScramjetBuilder;
+pub use reload::{SharedConfig, CONFIG_WATCH_INTERVAL};
+pub use sender::{Scramjet, SendReceipt};
+
+pub use scramjet_common::{Config, ScramjetError};
+pub use scramjet_net as net;
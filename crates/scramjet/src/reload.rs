@@ -0,0 +1,140 @@
+//! Live configuration: the control socket's `reload-config` and the config
+//! file watcher both load the configuration again and publish its tunables
+//! (see [`Config::apply_tunables`]) to the running tasks through a shared
+//! [`ArcSwap`]. Connections, the slot clock and everything else keep running.
+
+use arc_swap::ArcSwap;
+use log::{info, warn};
+use scramjet_common::{Config, ScramjetError};
+use scramjet_net::{
+    control::ConfigReloader,
+    rate_limit::{RateLimitHandle, RateLimiter},
+};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How often the watched config file's modification time is checked
+pub const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Configuration the background tasks read on every iteration
+pub type SharedConfig = Arc<ArcSwap<Config>>;
+
+/// Loads a fresh [`Config`] (the file or env the sender started from)
+pub(crate) type ConfigLoader = Arc<dyn Fn() -> Result<Config, ScramjetError> + Send + Sync>;
+
+/// Load the configuration again and publish the tunables that changed; the
+/// send rate limiter is rebuilt when its settings are among them
+pub(crate) fn config_reloader(
+    loader: ConfigLoader,
+    config: SharedConfig,
+    rate_limit: RateLimitHandle,
+) -> ConfigReloader {
+    // The watcher and the control socket may reload at the same time
+    let reloading = Mutex::new(());
+    Arc::new(move || {
+        let fresh = loader()?;
+        let _reloading = reloading.lock().unwrap();
+        let mut next = Config::clone(&config.load());
+        let applied = next.apply_tunables(&fresh);
+        if applied.contains(&"send_rate_limit") || applied.contains(&"send_rate_burst") {
+            let limiter = match next.send_rate_limit {
+                Some(ref spec) => Some(Arc::new(RateLimiter::from_spec(
+                    spec,
+                    next.send_rate_burst,
+                )?)),
+                None => None,
+            };
+            rate_limit.store(limiter);
+        }
+        config.store(Arc::new(next));
+        Ok(applied.into_iter().map(String::from).collect())
+    })
+}
+
+/// Reload whenever `path` is modified, until `shutdown`. A file that fails to
+/// load or validate is logged and the running configuration kept.
+pub(crate) fn spawn_config_watcher(
+    path: PathBuf,
+    reload: ConfigReloader,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last_modified = modified(&path).await;
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = tokio::time::sleep(CONFIG_WATCH_INTERVAL) => {}
+            }
+            let current = modified(&path).await;
+            if current.is_none() || current == last_modified {
+                continue;
+            }
+            last_modified = current;
+            match reload() {
+                Ok(applied) if applied.is_empty() => {
+                    info!("Config: {:?} changed; no tunable settings differ", path)
+                }
+                Ok(applied) => info!(
+                    "Config: Reloaded {:?}; applied {}",
+                    path,
+                    applied.join(", ")
+                ),
+                Err(e) => warn!(
+                    "Config: Ignoring change to {:?} ({}); keeping the running configuration",
+                    path, e
+                ),
+            }
+        }
+    })
+}
+
+async fn modified(path: &PathBuf) -> Option<SystemTime> {
+    tokio::fs::metadata(path).await.ok()?.modified().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scramjet_net::rate_limit;
+
+    #[test]
+    fn test_reloader_publishes_tunables() {
+        let config = Config::from_env().unwrap();
+        let shared: SharedConfig = Arc::new(ArcSwap::from_pointee(config.clone()));
+        let rate_limit = rate_limit::rate_limit_handle(None);
+
+        let mut edited = config.clone();
+        edited.scout_lookahead_slots += 4;
+        edited.send_rate_limit = Some("250/s".into());
+        edited.default_priority_fee += 1;
+        edited.rpc_url = "https://elsewhere.example".into();
+        let loaded = Mutex::new(edited);
+        let reload = config_reloader(
+            Arc::new(move || Ok(loaded.lock().unwrap().clone())),
+            shared.clone(),
+            rate_limit.clone(),
+        );
+
+        let applied = reload().unwrap();
+        assert_eq!(
+            applied,
+            vec![
+                "default_priority_fee",
+                "scout_lookahead_slots",
+                "send_rate_limit"
+            ]
+        );
+        let live = shared.load();
+        assert_eq!(live.default_priority_fee, config.default_priority_fee + 1);
+        assert_eq!(live.scout_lookahead_slots, config.scout_lookahead_slots + 4);
+        // Not a tunable: needs a restart
+        assert_eq!(live.rpc_url, config.rpc_url);
+        assert_eq!(rate_limit.load_full().unwrap().rate(), 250.0);
+
+        // Unchanged file: nothing to apply
+        assert!(reload().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,444 @@
+use bytes::Bytes;
+use log::debug;
+use scramjet_common::packet::check_packet_size;
+use scramjet_common::{Config, ScramjetError, TxSigner};
+use scramjet_net::{
+    blockhash::BlockhashCache,
+    blocklist::BlocklistManager,
+    cartographer::{Cartographer, ProductionStart},
+    deadline::Deadline,
+    dedupe::Dedupe,
+    engine::{BatchMode, QuicEngine},
+    priority::Priority,
+    queue::SendQueue,
+    rate_limit::{RateLimitHandle, RateLimiter},
+    raw::RawTransaction,
+    slo::{LandingFeed, SloMonitor},
+};
+use solana_client::rpc_client::SerializableTransaction;
+use solana_sdk::signature::{Keypair, Signature};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::builder::ScramjetBuilder;
+use crate::reload::SharedConfig;
+
+/// How long `shutdown` waits for background loops to notice cancellation
+/// before aborting them
+const TASK_STOP_GRACE: Duration = Duration::from_secs(1);
+
+/// Where and when a transaction was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SendReceipt {
+    pub signature: Signature,
+    /// Leader TPU (QUIC) address the transaction was written to
+    pub leader: SocketAddr,
+    /// Slot the leader was resolved for
+    pub slot: u64,
+}
+
+/// Running sender: owns the background tasks (aborted on drop; use
+/// [`Scramjet::shutdown`] to stop them and drain in-flight sends first)
+pub struct Scramjet {
+    pub(crate) config: SharedConfig,
+    pub(crate) identity: Keypair,
+    pub(crate) signer: Arc<dyn TxSigner>,
+    pub(crate) cartographer: Arc<Cartographer>,
+    pub(crate) engine: Arc<QuicEngine>,
+    pub(crate) shield: Arc<BlocklistManager>,
+    pub(crate) blockhashes: Arc<BlockhashCache>,
+    pub(crate) slo_monitor: Option<Arc<SloMonitor>>,
+    pub(crate) rate_limiter: RateLimitHandle,
+    pub(crate) send_queue: Arc<SendQueue>,
+    pub(crate) dedupe: Option<Arc<Dedupe>>,
+    pub(crate) landing_feed: Option<Arc<LandingFeed>>,
+    pub(crate) clock: &'static str,
+    pub(crate) shutdown: CancellationToken,
+    /// Loops that exit on `shutdown` (Shield updater, slot clock, Scout)
+    pub(crate) cooperative_tasks: Vec<JoinHandle<()>>,
+    pub(crate) tasks: Vec<JoinHandle<()>>,
+}
+
+impl Scramjet {
+    pub fn builder() -> ScramjetBuilder {
+        ScramjetBuilder::default()
+    }
+
+    /// Current configuration (tunables reflect the latest reload)
+    pub fn config(&self) -> Arc<Config> {
+        self.config.load_full()
+    }
+
+    /// Live configuration, swapped on every reload
+    pub fn config_handle(&self) -> &SharedConfig {
+        &self.config
+    }
+
+    /// Identity used for QUIC client certificates
+    pub fn identity(&self) -> &Keypair {
+        &self.identity
+    }
+
+    /// Signs transactions built on behalf of the caller (the identity, or a
+    /// remote signer)
+    pub fn signer(&self) -> &Arc<dyn TxSigner> {
+        &self.signer
+    }
+
+    pub fn cartographer(&self) -> &Arc<Cartographer> {
+        &self.cartographer
+    }
+
+    pub fn engine(&self) -> &Arc<QuicEngine> {
+        &self.engine
+    }
+
+    pub fn blocklist(&self) -> &Arc<BlocklistManager> {
+        &self.shield
+    }
+
+    /// Background-refreshed latest blockhash (`BLOCKHASH_REFRESH_MS`)
+    pub fn blockhash_cache(&self) -> &Arc<BlockhashCache> {
+        &self.blockhashes
+    }
+
+    /// Landing SLO monitor (only when `LANDING_SLOS` is configured)
+    pub fn slo_monitor(&self) -> Option<&Arc<SloMonitor>> {
+        self.slo_monitor.as_ref()
+    }
+
+    /// Current send rate limiter (`SEND_RATE_LIMIT`, or the control
+    /// socket's `set-rate`; None = unlimited)
+    pub fn rate_limiter(&self) -> Option<Arc<RateLimiter>> {
+        self.rate_limiter.load_full()
+    }
+
+    /// Bounded, prioritized queue for ingestion (`SEND_QUEUE_CAPACITY`,
+    /// `SEND_QUEUE_OVERFLOW`). Producers push submissions; one consumer pops
+    /// them and sends each with [`send_raw`](Self::send_raw), as `serve` does.
+    pub fn send_queue(&self) -> &Arc<SendQueue> {
+        &self.send_queue
+    }
+
+    /// Duplicate guard of [`send_raw`](Self::send_raw) (only when
+    /// `DEDUPE_WINDOW_MS` is set)
+    pub fn dedupe(&self) -> Option<&Arc<Dedupe>> {
+        self.dedupe.as_ref()
+    }
+
+    /// Landings from the Geyser transaction status stream (only when
+    /// `GEYSER_TX_STATUS` is enabled in hybrid mode)
+    pub fn landing_feed(&self) -> Option<&Arc<LandingFeed>> {
+        self.landing_feed.as_ref()
+    }
+
+    /// Slot clock source: "geyser", "websocket" or "rpc-poll"
+    pub fn clock_mode(&self) -> &'static str {
+        self.clock
+    }
+
+    pub fn current_slot(&self) -> u64 {
+        self.cartographer.get_known_slot()
+    }
+
+    /// Watch the slot clock (latest slot; notified on every advance)
+    pub fn subscribe_slots(&self) -> watch::Receiver<u64> {
+        self.cartographer.subscribe_slots()
+    }
+
+    /// Watch slots starting production: the earliest sign of a new leader
+    /// (Geyser clock with `GEYSER_ENTRIES` or `GEYSER_INTERSLOT_UPDATES`)
+    pub fn subscribe_production(&self) -> watch::Receiver<Option<ProductionStart>> {
+        self.cartographer.subscribe_production()
+    }
+
+    /// Send a signed transaction to the current leader
+    pub async fn send<T: SerializableTransaction>(
+        &self,
+        tx: &T,
+    ) -> Result<SendReceipt, ScramjetError> {
+        let bytes = serialize(tx)?;
+        self.send_wire(*tx.get_signature(), bytes, None, Priority::Normal)
+            .await
+    }
+
+    /// [`send`](Self::send) in a priority class: `high` goes ahead of sends
+    /// waiting on the rate limiter and of lower classes in the leader's
+    /// stream budget (see `scramjet_net::priority`)
+    pub async fn send_with_priority<T: SerializableTransaction>(
+        &self,
+        tx: &T,
+        priority: Priority,
+    ) -> Result<SendReceipt, ScramjetError> {
+        let bytes = serialize(tx)?;
+        self.send_wire(*tx.get_signature(), bytes, None, priority)
+            .await
+    }
+
+    /// [`send`](Self::send), unless `deadline` has passed by the time a rate
+    /// limit token and a leader are available: then the transaction is
+    /// dropped with [`ScramjetError::DeadlineExpired`] and counted in the
+    /// engine's `expired_before_send`
+    pub async fn send_with_deadline<T: SerializableTransaction>(
+        &self,
+        tx: &T,
+        deadline: Deadline,
+    ) -> Result<SendReceipt, ScramjetError> {
+        let bytes = serialize(tx)?;
+        self.send_wire(*tx.get_signature(), bytes, Some(deadline), Priority::Normal)
+            .await
+    }
+
+    /// Send a pre-encoded transaction (see `scramjet_net::raw`) as-is,
+    /// honoring its deadline and priority. With `DEDUPE_WINDOW_MS` set, a
+    /// signature already sent within the window is refused with
+    /// [`ScramjetError::DuplicateTransaction`] (see `scramjet_net::dedupe`).
+    pub async fn send_raw(&self, tx: RawTransaction) -> Result<SendReceipt, ScramjetError> {
+        if let Some(ref dedupe) = self.dedupe {
+            dedupe.claim(tx.signature)?;
+        }
+        self.send_raw_claimed(tx).await
+    }
+
+    /// [`send_raw`](Self::send_raw) for a transaction whose signature the
+    /// caller already claimed from [`dedupe`](Self::dedupe) (e.g. to relay
+    /// it only once the claim holds). The claim is given back if the send fails.
+    pub async fn send_raw_claimed(&self, tx: RawTransaction) -> Result<SendReceipt, ScramjetError> {
+        let signature = tx.signature;
+        let result = self
+            .send_wire(signature, tx.bytes, tx.deadline, tx.priority)
+            .await;
+        if let (Err(_), Some(dedupe)) = (&result, &self.dedupe) {
+            dedupe.release(&signature);
+        }
+        result
+    }
+
+    /// Send signed transactions to the current leader, one stream each,
+    /// round-robin across its connection pool. Fails as a whole only when no
+    /// leader or connection is available; otherwise returns one result per
+    /// transaction, in order. Each transaction takes a rate limit token.
+    pub async fn send_batch<T: SerializableTransaction>(
+        &self,
+        txs: &[T],
+    ) -> Result<Vec<Result<SendReceipt, ScramjetError>>, ScramjetError> {
+        self.send_batch_with(txs, BatchMode::StreamPerTx).await
+    }
+
+    /// [`send_batch`](Self::send_batch) with a choice of stream mapping (see
+    /// [`BatchMode`]). When rate limited, the batch goes out in runs of at
+    /// most the limiter's burst; a run failing as a whole after earlier runs
+    /// went out fails its own transactions, so the results (and journal
+    /// entries) of what was already sent are never lost.
+    pub async fn send_batch_with<T: SerializableTransaction>(
+        &self,
+        txs: &[T],
+        mode: BatchMode,
+    ) -> Result<Vec<Result<SendReceipt, ScramjetError>>, ScramjetError> {
+        let (slot, leader) = self.current_target().await?;
+        let run_len = self
+            .rate_limiter()
+            .map_or(txs.len(), |limiter| limiter.burst() as usize)
+            .max(1);
+
+        let mut results = Vec::with_capacity(txs.len());
+        for run in txs.chunks(run_len) {
+            let mut wire = Vec::with_capacity(run.len());
+            let mut encoded = Vec::with_capacity(run.len());
+            for tx in run {
+                match serialize(tx) {
+                    Ok(bytes) => {
+                        wire.push(bytes);
+                        encoded.push(Ok(()));
+                    }
+                    Err(e) => encoded.push(Err(e)),
+                }
+            }
+            for _ in 0..wire.len() {
+                self.throttle(Priority::Normal).await;
+            }
+            let mut journaled = wire.clone().into_iter();
+            let wire_len = wire.len();
+            let engine = self.engine.clone();
+            let send = async move {
+                engine
+                    .send_batch(leader, wire, mode, Priority::Normal)
+                    .await
+            };
+            let sent = match self.engine.send_runtime() {
+                Some(runtime) => runtime.run(send).await.and_then(|sent| sent),
+                None => send.await,
+            };
+            let mut sent = match sent {
+                Ok(sent) => sent.into_iter(),
+                Err(e) if results.is_empty() => return Err(e),
+                Err(e) => {
+                    let failure = e.send_failure().cloned();
+                    let message = e.to_string();
+                    let failed: Vec<Result<(), ScramjetError>> = (0..wire_len)
+                        .map(|_| {
+                            Err(match failure {
+                                Some(ref failure) => failure.clone().into(),
+                                None => ScramjetError::StreamError(message.clone()),
+                            })
+                        })
+                        .collect();
+                    failed.into_iter()
+                }
+            };
+            for (tx, encoded) in run.iter().zip(encoded) {
+                // Transactions that failed to serialize have no wire bytes
+                let bytes = encoded
+                    .as_ref()
+                    .ok()
+                    .and_then(|()| journaled.next())
+                    .unwrap_or_default();
+                let result = encoded
+                    .and_then(|()| sent.next().expect("one result per serialized transaction"))
+                    .map_err(|e| e.with_slot(slot));
+                self.journal_send(
+                    tx.get_signature(),
+                    &bytes,
+                    slot,
+                    leader,
+                    result.as_ref().err(),
+                );
+                results.push(result.map(|()| SendReceipt {
+                    signature: *tx.get_signature(),
+                    leader,
+                    slot,
+                }));
+            }
+        }
+        Ok(results)
+    }
+
+    /// Cancelled when shutdown begins. Hand it to send loops (and signal
+    /// handlers: cancelling it stops the background loops too).
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Stop background loops, wait up to `SHUTDOWN_DRAIN_MS` for in-flight
+    /// sends, then close every connection with
+    /// [`SHUTDOWN_CLOSE_CODE`](scramjet_net::engine::SHUTDOWN_CLOSE_CODE).
+    /// Returns the number of connections closed.
+    pub async fn shutdown(mut self) -> usize {
+        self.shutdown.cancel();
+        let deadline = tokio::time::Instant::now() + TASK_STOP_GRACE;
+        for mut task in std::mem::take(&mut self.cooperative_tasks) {
+            if tokio::time::timeout_at(deadline, &mut task).await.is_err() {
+                debug!("Shutdown: Background task did not stop in time, aborting");
+                task.abort();
+            }
+        }
+        for task in self.tasks.drain(..) {
+            task.abort();
+        }
+        self.engine
+            .shutdown(self.config.load().shutdown_drain_timeout())
+            .await
+    }
+
+    async fn send_wire(
+        &self,
+        signature: Signature,
+        bytes: Bytes,
+        deadline: Option<Deadline>,
+        priority: Priority,
+    ) -> Result<SendReceipt, ScramjetError> {
+        self.throttle(priority).await;
+        let (slot, leader) = self.current_target().await?;
+        if let Some(deadline) = deadline {
+            self.check_deadline(deadline, slot)?;
+        }
+        let engine = self.engine.clone();
+        let wire = bytes.clone();
+        let send = async move { engine.send_transaction_with(leader, wire, priority).await };
+        let sent = match self.engine.send_runtime() {
+            Some(runtime) => runtime.run(send).await?,
+            None => send.await,
+        };
+        let sent = sent.map_err(|e| e.with_slot(slot));
+        self.journal_send(&signature, &bytes, slot, leader, sent.as_ref().err());
+        sent?;
+        Ok(SendReceipt {
+            signature,
+            leader,
+            slot,
+        })
+    }
+
+    /// Journal a send attempt, if the journal is on
+    fn journal_send(
+        &self,
+        signature: &Signature,
+        tx: &[u8],
+        slot: u64,
+        leader: SocketAddr,
+        error: Option<&ScramjetError>,
+    ) {
+        if let Some(journal) = self.engine.journal() {
+            journal.record_send("send", signature, tx, slot, leader, error);
+        }
+    }
+
+    /// Refuse (and count) a transaction whose deadline has already passed at
+    /// the known slot. Sends check again once they reach the front of the
+    /// rate limiter.
+    pub fn check_expiry(&self, deadline: Deadline) -> Result<(), ScramjetError> {
+        self.check_deadline(deadline, self.cartographer.get_known_slot())
+    }
+
+    /// Drop (and count) a transaction whose deadline passed while it waited
+    fn check_deadline(&self, deadline: Deadline, slot: u64) -> Result<(), ScramjetError> {
+        if deadline.is_expired(slot, self.blockhashes.block_height()) {
+            self.engine.record_expired();
+            return Err(deadline.expired_error(slot));
+        }
+        Ok(())
+    }
+
+    /// Wait for a send token when rate limited
+    async fn throttle(&self, priority: Priority) {
+        if let Some(limiter) = self.rate_limiter() {
+            limiter.acquire_with(priority).await;
+        }
+    }
+
+    async fn current_target(&self) -> Result<(u64, SocketAddr), ScramjetError> {
+        let slot = self.cartographer.get_known_slot();
+        // The leader tracker already resolved (and Shield-checked) this window
+        if let Some(active) = self.engine.active_connection() {
+            if active.covers(slot) {
+                return Ok((slot, active.target));
+            }
+        }
+        match self.cartographer.get_target(slot).await {
+            Some(leader) => Ok((slot, leader)),
+            None => Err(ScramjetError::NoLeaderFound(slot)),
+        }
+    }
+}
+
+impl Drop for Scramjet {
+    fn drop(&mut self) {
+        self.shutdown.cancel();
+        for task in self.cooperative_tasks.iter().chain(&self.tasks) {
+            task.abort();
+        }
+    }
+}
+
+/// Wire bytes of `tx`, refused when over the packet limit
+fn serialize<T: SerializableTransaction>(tx: &T) -> Result<Bytes, ScramjetError> {
+    let bytes = bincode::serialize(tx)
+        .map(Bytes::from)
+        .map_err(|e| ScramjetError::SerializationError(e.to_string()))?;
+    check_packet_size(&bytes)?;
+    Ok(bytes)
+}